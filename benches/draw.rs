@@ -0,0 +1,162 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use {
+    criterion::{Criterion, criterion_group, criterion_main},
+    device_monitor_core::{
+        app::{
+            App, AppConfig,
+            screens::{ConfigurationScreen, ElogScreen},
+        },
+        mqtt_ctrl::{MqttCtrl, with_local_mqtt_ctrl},
+    },
+    ratatui::{Terminal, backend::TestBackend},
+};
+
+const WIDTH: u16 = 160;
+const HEIGHT: u16 = 48;
+
+/// Elogs received before the frame is drawn, as after a long session
+const ELOGS: usize = 500;
+
+const DEVICE_INFO: &str = r#"{
+    "device_manifest": "",
+    "chips": [
+        {
+            "name": "main_chip", "id": "100A50500A2010072364012000000000",
+            "hardware_version": "1.0", "temperature": 42,
+            "loader_version": "020301", "loader_hash": "", "update_date_loader": "",
+            "firmware_version": "D52408", "firmware_hash": "", "update_date_firmware": "",
+            "ai_models": []
+        },
+        {
+            "name": "sensor_chip", "id": "100A50500A2010072364012000000000",
+            "hardware_version": "IMX500", "temperature": 38,
+            "loader_version": "020301", "loader_hash": "", "update_date_loader": "",
+            "firmware_version": "010707", "firmware_hash": "", "update_date_firmware": "",
+            "ai_models": [
+                { "version": "0308000000000100", "hash": "", "update_date": "2025-01-01T00:00:00.000Z" }
+            ]
+        }
+    ]
+}"#;
+
+const DEVICE_STATES: &str = r#"{
+    "power_states": { "source": [{ "type": 0, "level": 100 }], "in_use": 0, "is_battery_low": false },
+    "process_state": "Idle",
+    "hours_meter": 120,
+    "bootup_reason": 0,
+    "last_bootup_time": ""
+}"#;
+
+const SYSTEM_SETTINGS: &str = r#"{
+    "req_info": { "req_id": "0" },
+    "led_enabled": true,
+    "temperature_update_interval": 10,
+    "log_settings": [
+        { "filter": "all", "level": 3, "destination": 0, "storage_name": "", "path": "" }
+    ],
+    "res_info": { "res_id": "0", "code": 0, "detail_msg": "ok" }
+}"#;
+
+const NETWORK_SETTINGS: &str = r#"{
+    "req_info": { "req_id": "0" },
+    "ip_method": 1,
+    "ntp_url": "pool.ntp.org",
+    "static_settings_ipv4": {
+        "ip_address": "192.168.28.39", "subnet_mask": "255.255.255.0",
+        "gateway_address": "192.168.28.1", "dns_address": "8.8.8.8"
+    },
+    "res_info": { "res_id": "0", "code": 0, "detail_msg": "ok" }
+}"#;
+
+const WIRELESS_SETTINGS: &str = r#"{
+    "req_info": { "req_id": "0" },
+    "sta_mode_setting": { "ssid": "lab", "password": "secret", "encryption": 2 },
+    "rssi": -48,
+    "connection_state": "connected",
+    "res_info": { "res_id": "0", "code": 0, "detail_msg": "ok" }
+}"#;
+
+/// Device which reported its state and a history of elogs
+fn populated_mqtt_ctrl() -> MqttCtrl {
+    // Nothing listens on the port, the MqttCtrl is never updated
+    let mut mqtt_ctrl = MqttCtrl::new("127.0.0.1", 1).unwrap();
+
+    let state = serde_json::json!({
+        "state/$system/device_info": DEVICE_INFO,
+        "state/$system/device_states": DEVICE_STATES,
+        "state/$system/system_settings": SYSTEM_SETTINGS,
+        "state/$system/network_settings": NETWORK_SETTINGS,
+        "state/$system/wireless_setting": WIRELESS_SETTINGS,
+        "state/$agent/report-status-interval-min": 3,
+        "state/$agent/report-status-interval-max": 180,
+    });
+    mqtt_ctrl
+        .on_message("v1/devices/me/attributes", &state.to_string())
+        .unwrap();
+
+    for i in 0..ELOGS {
+        let elog = serde_json::json!({"$system/event_log": {
+            "serial": "SN001", "level": 1 + i % 4,
+            "timestamp": format!("2025-01-01T{:02}:{:02}:{:02}Z", i / 3600, i / 60 % 60, i % 60),
+            "component_id": 100, "component_name": "Main", "event_id": 4096 + i % 16,
+            "event_description": "Critical error"
+        }});
+        mqtt_ctrl
+            .on_message("v1/devices/me/telemetry", &elog.to_string())
+            .unwrap();
+    }
+    mqtt_ctrl
+}
+
+fn bench_draw(c: &mut Criterion) {
+    // Moves from the main screen to the drawn one
+    let screens: [(&str, fn(&mut App)); 3] = [
+        ("main", |_| {}),
+        ("elog", |app| app.dm_screen_move_to(ElogScreen::default())),
+        ("configuration", |app| {
+            app.dm_screen_move_to(ConfigurationScreen::default())
+        }),
+    ];
+
+    let mut mqtt_ctrl = populated_mqtt_ctrl();
+    let mut group = c.benchmark_group("draw");
+    for (name, move_to) in screens {
+        let mut app = App::new(AppConfig {
+            broker: "localhost",
+            ..Default::default()
+        })
+        .unwrap();
+        move_to(&mut app);
+
+        let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        (mqtt_ctrl, ()) = with_local_mqtt_ctrl(mqtt_ctrl, || {
+            group.bench_function(name, |b| {
+                b.iter(|| {
+                    terminal
+                        .draw(|frame| frame.render_widget(&app, frame.area()))
+                        .unwrap();
+                })
+            });
+        });
+    }
+    group.finish();
+    mqtt_ctrl.exit();
+}
+
+criterion_group!(benches, bench_draw);
+criterion_main!(benches);
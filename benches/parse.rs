@@ -0,0 +1,113 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use {
+    criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main},
+    device_monitor_core::mqtt_ctrl::evp::EvpMsg,
+};
+
+const DEVICE_INFO: &str = r#"{
+    "device_manifest": "",
+    "chips": [
+        {
+            "name": "main_chip", "id": "100A50500A2010072364012000000000",
+            "hardware_version": "1.0", "temperature": 42,
+            "loader_version": "020301", "loader_hash": "", "update_date_loader": "",
+            "firmware_version": "D52408", "firmware_hash": "", "update_date_firmware": "",
+            "ai_models": []
+        },
+        {
+            "name": "sensor_chip", "id": "100A50500A2010072364012000000000",
+            "hardware_version": "IMX500", "temperature": 38,
+            "loader_version": "020301", "loader_hash": "", "update_date_loader": "",
+            "firmware_version": "010707", "firmware_hash": "", "update_date_firmware": "",
+            "ai_models": [
+                { "version": "0308000000000100", "hash": "", "update_date": "2025-01-01T00:00:00.000Z" }
+            ]
+        }
+    ]
+}"#;
+
+const DEVICE_STATES: &str = r#"{
+    "power_states": { "source": [{ "type": 0, "level": 100 }], "in_use": 0, "is_battery_low": false },
+    "process_state": "Idle",
+    "hours_meter": 120,
+    "bootup_reason": 0,
+    "last_bootup_time": ""
+}"#;
+
+const ELOG: &str = r#"{"$system/event_log": {
+    "serial": "SN001", "level": 1, "timestamp": "2025-01-01T10:00:00Z",
+    "component_id": 100, "component_name": "Main", "event_id": 4096,
+    "event_description": "Critical error"
+}}"#;
+
+const RPC_RESPONSE: &str = r#"{"direct-command-response": {"status": "ok", "reqid": "42",
+    "response": "{\"res_info\":{\"code\":0,\"detail_msg\":\"ok\"}}"}}"#;
+
+const GATEWAY_TELEMETRY: &str =
+    r#"{"camera-1": [{"ts": 1700000000000, "values": {"temperature": 42}}]}"#;
+
+/// State report as the device sends it, the values of the keys are JSON strings
+fn state_report() -> String {
+    serde_json::json!({
+        "state/$system/device_info": DEVICE_INFO,
+        "state/$system/device_states": DEVICE_STATES,
+        "state/$agent/report-status-interval-min": 3,
+        "state/$agent/report-status-interval-max": 180,
+    })
+    .to_string()
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let state = state_report();
+    let corpus = [
+        ("state report", "v1/devices/me/attributes", state.as_str()),
+        ("elog", "v1/devices/me/telemetry", ELOG),
+        (
+            "connect request",
+            "v1/devices/me/attributes/request/1000",
+            "{}",
+        ),
+        (
+            "rpc response",
+            "v1/devices/me/rpc/response/42",
+            RPC_RESPONSE,
+        ),
+        (
+            "gateway telemetry",
+            "v1/gateway/telemetry",
+            GATEWAY_TELEMETRY,
+        ),
+        (
+            "non-EVP",
+            "factory/line-3/camera-12/status",
+            r#"{"ok": true}"#,
+        ),
+    ];
+
+    let mut group = c.benchmark_group("EvpMsg::parse");
+    for (name, topic, payload) in corpus {
+        group.throughput(Throughput::Bytes(payload.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| EvpMsg::parse(black_box(topic), black_box(payload)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use {
+    criterion::{Criterion, black_box, criterion_group, criterion_main},
+    device_monitor_core::mqtt_ctrl::evp::topic::classify,
+};
+
+/// Topics received from a device and a gateway, the telemetry first as the most frequent
+const TOPICS: [&str; 8] = [
+    "v1/devices/me/telemetry",
+    "v1/devices/me/attributes",
+    "v1/devices/me/attributes/request/1000",
+    "v1/devices/me/rpc/request/42",
+    "v1/devices/me/rpc/response/42",
+    "v1/gateway/telemetry",
+    "v1/gateway/attributes/response",
+    "factory/line-3/camera-12/status",
+];
+
+fn bench_classify(c: &mut Criterion) {
+    for topic in TOPICS {
+        c.bench_function(&format!("classify {topic}"), |b| {
+            b.iter(|| classify(black_box(topic)))
+        });
+    }
+}
+
+criterion_group!(benches, bench_classify);
+criterion_main!(benches);
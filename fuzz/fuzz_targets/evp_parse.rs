@@ -0,0 +1,38 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#![no_main]
+
+use {
+    device_monitor_core::{
+        error::DMError,
+        mqtt_ctrl::evp::{EvpMsg, MalformedMsg},
+    },
+    libfuzzer_sys::fuzz_target,
+};
+
+// The first line of the input is the topic, the rest is the payload
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    let (topic, payload) = s.split_once('\n').unwrap_or((s, ""));
+
+    if let Err(e) = EvpMsg::parse(topic, payload) {
+        assert!(matches!(e.current_context(), DMError::ParserError));
+        assert!(e.downcast_ref::<MalformedMsg>().is_some());
+    }
+});
@@ -0,0 +1,53 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#![no_main]
+
+use {
+    device_monitor_core::{
+        ai_model::AiModel,
+        mqtt_ctrl::evp::{
+            device_info::{
+                DeviceCapabilities, DeviceInfo, DeviceReserved, DeviceStates, NetworkSettings,
+                SystemSettings, WirelessSettings,
+            },
+            elog::Elog,
+            evp_state::{AgentSystemInfo, DeploymentStatus},
+        },
+        ota::FirmwareProperty,
+    },
+    libfuzzer_sys::fuzz_target,
+};
+
+// The payload of a state report key, as the device sends it
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let _ = serde_json::from_str::<DeviceInfo>(s);
+    let _ = serde_json::from_str::<DeviceStates>(s);
+    let _ = serde_json::from_str::<DeviceCapabilities>(s);
+    let _ = serde_json::from_str::<DeviceReserved>(s);
+    let _ = serde_json::from_str::<SystemSettings>(s);
+    let _ = serde_json::from_str::<NetworkSettings>(s);
+    let _ = serde_json::from_str::<WirelessSettings>(s);
+    let _ = serde_json::from_str::<FirmwareProperty>(s);
+    let _ = serde_json::from_str::<AiModel>(s);
+    let _ = AgentSystemInfo::parse(s);
+    let _ = DeploymentStatus::parse(s);
+    let _ = Elog::parse(s);
+});
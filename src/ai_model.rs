@@ -17,7 +17,7 @@ limitations under the License.
 #[allow(unused)]
 use {
     crate::{
-        app::ConfigKey,
+        device_config::ConfigKey,
         error::DMError,
         mqtt_ctrl::evp::ProcessState,
         mqtt_ctrl::evp::evp_state::UUID,
@@ -110,7 +110,7 @@ pub fn parse_ai_model_configuration(config_keys: &[String]) -> Result<String, DM
     };
 
     let req_id = ReqInfo {
-        req_id: UUID::new().uuid().to_string(),
+        req_id: UUID::new_string(),
     };
 
     let mut targets = vec![];
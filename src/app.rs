@@ -14,25 +14,74 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+pub mod confirm;
+pub mod error_log;
+pub mod screens;
 pub mod ui;
+pub mod verify;
 
 use crate::ai_model::parse_ai_model_configuration;
+use crate::audit::with_audit_log;
+use crate::clipboard;
+use crate::compare;
+use crate::debug_log::DebugLog;
+pub use crate::device_config::{ConfigKey, DirectCommand, MainWindowFocus};
+use crate::dtmi::{self, DtdlModel};
+use crate::fleet::{self, FleetColumn, FleetDevice};
+use crate::i18n::{tr, trf};
+use crate::inventory::Inventory;
+use crate::notes::{self, DeviceNote, DeviceNotes, NOTES_FILE, NotesEditor};
+use crate::profile::{FrameProfile, FrameTimes};
+use crate::report::{DeviceReport, ReportFormat};
+use crate::script::{SCRIPT_EXTENSION, Script, ScriptRunner, ScriptTarget};
+use confirm::{ConfirmAction, Confirmation};
+use error_log::{ErrorEvent, ErrorLog, ErrorSource, Severity};
+use screens::Screen;
+use verify::{ApplyVerify, VerifyPhase};
 #[allow(unused)]
 use {
     super::{
         app,
         azurite::{
-            AzuriteAction, try_reinit_azurite_storage, with_azurite_storage,
-            with_azurite_storage_mut,
+            AzuriteAction, AzuriteConfig, BlobConflict, BlobPage, OverwritePolicy, UiBlob,
+            auto_download::AutoDownloader,
+            azurite_config,
+            defaults::{DEFAULTS_FILE, TokenProviderDefaults, TokenProviderPurpose},
+            health::HealthMonitor,
+            sas::SasInfo,
+            with_azurite_storage, with_azurite_storage_mut,
+            worker::{AzuriteJob, AzuriteJobResult, UploadPurpose, download_label},
         },
+        device_config,
         error::{DMError, DMErrorExt},
-        mqtt_ctrl::evp::module::ModuleInfo,
-        mqtt_ctrl::{MqttCtrl, with_mqtt_ctrl, with_mqtt_ctrl_mut},
-        ota::{FirmwareProperty, parse_ota_configuration},
+        jobs::{Job, JobQueue, JobRecord, JobResult, JobStatus},
+        mqtt_ctrl::evp::{
+            clock::TimeZoneMode,
+            evp_state::{UUID, is_error_status},
+            module::{ModuleInfo, deployment_module_hashes},
+        },
+        mqtt_ctrl::{MqttCtrl, PublishOptions, with_mqtt_ctrl, with_mqtt_ctrl_mut},
+        ota::{
+            FirmwareProperty, firmware_of_configuration,
+            package::{PackageMetadata, parse_package},
+            parse_ota_configuration,
+            rollback::{OtaSnapshot, blob_of_url, chip_id_of_name},
+            target_config_keys, target_of_config_key,
+            wizard::{OtaProgress, OtaWizard, OtaWizardStep, target_name},
+        },
+    },
+    crate::mqtt_ctrl::evp::configure::{
+        dirty_config_keys, parse_network_settings, parse_wireless_settings,
+    },
+    crate::mqtt_ctrl::evp::edge_app::{DETECTION_CONFIG_KEYS, EdgeAppInfo},
+    crate::provisioning::{
+        ntp::{NtpCheck, NtpReport},
+        proxy::{ProxyCheck, ProxyReport},
+        static_ip::{StaticIpWizard, StaticIpWizardStep},
+        wifi::{WifiWizard, WifiWizardStep},
     },
-    crate::mqtt_ctrl::evp::edge_app::EdgeAppInfo,
-    chrono::Local,
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    chrono::{Local, Utc},
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
     error_stack::{Report, Result},
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
     ratatui::{
@@ -54,16 +103,63 @@ use {
         text::{Line, Span, Text},
         widgets::{Block, Borders, List, ListItem, Paragraph, Widget},
     },
+    serde_json::Value,
     std::{
-        collections::HashMap,
+        collections::{BTreeSet, HashMap},
         fmt::Display,
         io,
         sync::{Mutex, OnceLock},
         time::{Duration, Instant},
     },
-    ui::*,
+    ui::{
+        ui_token_provider_blobs::{BlobDownload, BlobListState},
+        *,
+    },
 };
 
+/// Screens kept in the navigation stack, the oldest ones above Main are dropped
+const MAX_SCREEN_DEPTH: usize = 16;
+
+/// storage_name keys filled from the default token provider of their purpose, when the
+/// destination or method key next to them selects the cloud storage
+const DEFAULT_STORAGE_NAMES: [(ConfigKey, ConfigKey, TokenProviderPurpose); 7] = [
+    (
+        ConfigKey::AllLogSettingDestination,
+        ConfigKey::AllLogSettingStorageName,
+        TokenProviderPurpose::DeviceLogs,
+    ),
+    (
+        ConfigKey::MainLogSettingDestination,
+        ConfigKey::MainLogSettingStorageName,
+        TokenProviderPurpose::DeviceLogs,
+    ),
+    (
+        ConfigKey::SensorLogSettingDestination,
+        ConfigKey::SensorLogSettingStorageName,
+        TokenProviderPurpose::DeviceLogs,
+    ),
+    (
+        ConfigKey::CompanionFwLogSettingDestination,
+        ConfigKey::CompanionFwLogSettingStorageName,
+        TokenProviderPurpose::DeviceLogs,
+    ),
+    (
+        ConfigKey::CompanionAppLogSettingDestination,
+        ConfigKey::CompanionAppLogSettingStorageName,
+        TokenProviderPurpose::DeviceLogs,
+    ),
+    (
+        ConfigKey::CommonSettingsPSITMethod,
+        ConfigKey::CommonSettingsPSITStorageName,
+        TokenProviderPurpose::InputTensor,
+    ),
+    (
+        ConfigKey::CommonSettingsPSMetadataMethod,
+        ConfigKey::CommonSettingsPSMetadataStorageName,
+        TokenProviderPurpose::Metadata,
+    ),
+];
+
 /// Default timeout for event polling in milliseconds
 const DEFAULT_EVENT_POLL_TIMEOUT: u64 = 250;
 
@@ -109,8 +205,29 @@ where
 }
 
 /// Application configuration structure containing broker settings
+#[derive(Default)]
 pub struct AppConfig<'a> {
     pub broker: &'a str,
+    /// Directory where blobs uploaded by the device are downloaded automatically
+    pub download_dir: Option<&'a str>,
+    /// Directory, URL or file the DTDL models are read from
+    pub dtmi_repository: Option<&'a str>,
+    /// File of the firmware and AI model versions expected on the device
+    pub inventory: Option<&'a str>,
+    /// Directory of the daemon snapshot directories, one per device of the fleet
+    pub fleet_dir: Option<&'a str>,
+    /// Starts on the kiosk display instead of the main screen
+    pub kiosk: bool,
+    /// The configurations and RPCs are only reviewed, see MqttCtrl::set_dry_run()
+    pub dry_run: bool,
+    /// Nothing can be changed on the device or the storage, see MqttCtrl::set_read_only()
+    pub read_only: bool,
+    /// The messages of the broker are delayed, see MqttCtrl::set_slow_network()
+    pub slow_network: bool,
+    /// Overlays the time spent in each step of the frames
+    pub profile_frame: bool,
+    /// Log file of device-monitor itself, tailed by the Debug Log screen
+    pub log_file: Option<&'a str>,
 }
 
 /// Different screens/views available in the device monitor application
@@ -128,6 +245,8 @@ pub enum DMScreen {
     /// Main dashboard view showing device information
     #[default]
     Main,
+    /// A few key indicators in large characters, for monitors on the wall
+    Kiosk,
     /// Module details view
     Module,
     /// Configuration editing screen
@@ -142,614 +261,89 @@ pub enum DMScreen {
     TokenProvider,
     /// Token provider blob viewer
     TokenProviderBlobs,
+    /// Log files uploaded to the token provider container
+    LogFiles,
+    /// Pager of a downloaded log file
+    LogViewer,
     /// Event log viewer
     Elog,
+    /// Edge application instance selection when several instances are deployed
+    EdgeAppInstances,
     /// Edge application management
     EdgeApp(DMScreenState),
     /// OTA firmware update screen
     Ota,
     /// OTA firmware update configuration screen
     OtaConfig(DMScreenState),
+    /// Guided OTA: upload, configure and monitor in one place
+    OtaWizard,
+    /// Guided Wi-Fi provisioning: input the credentials, send and monitor them
+    WifiWizard,
+    /// Guided static IP provisioning, checked and verified after the reboot
+    StaticIpWizard,
     /// AI Model management screen
     AiModel,
     /// AiModel configuration screen
     AiModelConfig(DMScreenState),
+    /// Azurite/Azure storage endpoint settings screen
+    StorageSettings,
+    /// Automatically downloaded blobs
+    Downloads,
+    /// Publishes arbitrary MQTT messages
+    Publish,
+    /// Retained messages held by the broker
+    Retained,
+    /// Topic filters subscribed on the broker
+    Subscriptions,
+    /// DTDL model referenced by the DeviceReserved schema
+    Dtmi,
+    /// Wireless signal and network diagnostics
+    Network,
+    /// Test sequences run against the device
+    Scripts,
+    /// Background jobs of the session
+    Jobs,
+    /// Reboots of the device during the session
+    Reboots,
+    /// Operations changing the device or the storage, from the audit log
+    Audit,
+    /// Firmware and AI model versions compared with the expected inventory
+    Inventory,
+    /// Firmware versions, settings and deployment status side by side with another device
+    Compare,
+    /// Summary of the devices of the fleet, one row per device
+    Fleet,
+    /// Log settings of the system settings, one row per filter
+    LogSettings,
+    /// Log file of device-monitor itself, followed as it grows
+    DebugLog,
     /// Exit confirmation dialog
     Exiting,
 }
 
-#[derive(Debug, Default, PartialEq, PartialOrd, Clone)]
-#[repr(usize)]
-#[allow(unused)]
-pub enum DirectCommand {
-    Reboot = 0,
-    GetDirectImage,
-    FactoryReset,
-    ReadSensorRegister,
-    WriteSensorRegister,
-    ShutDown,
-
-    /// Storage token request command from the device
-    StorageTokenRequest(String, String),
-
-    #[default]
-    Invalid,
-}
-
-impl std::fmt::Display for DirectCommand {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            DirectCommand::Reboot => write!(f, "Reboot"),
-            DirectCommand::GetDirectImage => write!(f, "GetDirectImage"),
-            DirectCommand::FactoryReset => write!(f, "FactoryReset"),
-            DirectCommand::ReadSensorRegister => write!(f, "ReadSensorRegister"),
-            DirectCommand::WriteSensorRegister => write!(f, "WriteSensorRegister"),
-            DirectCommand::ShutDown => write!(f, "ShutDown"),
-            DirectCommand::StorageTokenRequest(key, filename) => {
-                write!(f, "StorageTokenRequest({}, {})", key, filename)
-            }
-            DirectCommand::Invalid => write!(f, "InvalidCommand"),
-        }
-    }
-}
-
-#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
-#[repr(usize)]
-#[allow(unused)]
-pub enum DirectCommandPara {
-    GetDirectImageSensorName = 0,
-    GetDirectImageNetworkId,
-
-    #[default]
-    Invalid,
-}
-
-/// Configuration keys for the device
-/// These keys are used to identify the configuration parameters
-/// and are used to parse the configuration file
-///
-/// IMPORTANT: Don't change the order of the keys!
-#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
-#[repr(usize)]
-pub enum ConfigKey {
-    //AgentState
-    ReportStatusIntervalMin = 0,
-    ReportStatusIntervalMax,
-
-    //SystemSettings
-    LedEnabled,
-    TemperatureUpdateInterval,
-    AllLogSettingLevel,
-    AllLogSettingDestination,
-    AllLogSettingStorageName,
-    AllLogSettingPath,
-    MainLogSettingLevel,
-    MainLogSettingDestination,
-    MainLogSettingStorageName,
-    MainLogSettingPath,
-    SensorLogSettingLevel,
-    SensorLogSettingDestination,
-    SensorLogSettingStorageName,
-    SensorLogSettingPath,
-    CompanionFwLogSettingLevel,
-    CompanionFwLogSettingDestination,
-    CompanionFwLogSettingStorageName,
-    CompanionFwLogSettingPath,
-    CompanionAppLogSettingLevel,
-    CompanionAppLogSettingDestination,
-    CompanionAppLogSettingStorageName,
-    CompanionAppLogSettingPath,
-
-    //Network settings
-    IpMethod,
-    NtpUrl,
-    StaticIpv4Ip,
-    StaticIpv4SubnetMask,
-    StaticIpv4Gateway,
-    StaticIpv4Dns,
-    StaticIpv6Ip,
-    StaticIpv6SubnetMask,
-    StaticIpv6Gateway,
-    StaticIpv6Dns,
-    ProxyUrl,
-    ProxyPort,
-    ProxyUserName,
-    ProxyPassword,
-
-    // Wireless settings
-    StaSsid,
-    StaPassword,
-    StaEncryption,
-
-    // DirectCommandPara
-    DirectGetImageSensorName,
-    DirectGetImageNetworkId,
-
-    // Edge App
-    CommonSettingsProcessState,
-    CommonSettingsLogLevel,
-    CommonSettingsISNumberOfIterations,
-    CommonSettingsPQCameraImageSizeWidth,
-    CommonSettingsPQCameraImageSizeHeight,
-    CommonSettingsPQCameraImageSizeScalingPolicy,
-    CommonSettingsPQFrameRateNum,
-    CommonSettingsPQFrameRateDenom,
-    CommonSettingsPQDigitalZoom,
-    CommonSettingsPQCameraImageFlipHorizontal,
-    CommonSettingsPQCameraImageFlipVertical,
-    CommonSettingsPQExposureMode,
-    // Auto exposure
-    CommonSettingsPQAeMaxExposureTime,
-    CommonSettingsPQAeMinExposureTime,
-    CommonSettingsPQAeMaxGain,
-    CommonSettingsPQAeConvergenceSpeed,
-    CommonSettingsPQEvCompensation,
-    // Auto exposure anti-flicker
-    CommonSettingsPQAeAntiFlickerMode,
-    // Manual exposure
-    CommonSettingsPQMeExposureTime,
-    CommonSettingsPQMeGain,
-    CommonSettingsPQWhiteBalanceMode,
-    // Auto white balance
-    CommonSettingsPQAwbConvergenceSpeed,
-    // Manual white balance preset
-    CommonSettingsPQMWBPColorTemperature,
-    // Manual white balance gain
-    CommonSettingsPQMWBGRed,
-    CommonSettingsPQMWBGBlue,
-    // Image cropping
-    CommonSettingsPQICLeft,
-    CommonSettingsPQICTop,
-    CommonSettingsPQICWidth,
-    CommonSettingsPQICHeight,
-    // Image rotation
-    CommonSettingsPQImageRotation,
-
-    // Port settings
-    CommonSettingsPSMetadataMethod,
-    CommonSettingsPSMetadataStorageName,
-    CommonSettingsPSMetadataEndpoint,
-    CommonSettingsPSMetadataPath,
-    CommonSettingsPSMetadataEnabled,
-
-    // Port settings for input tensor
-    CommonSettingsPSITMethod,
-    CommonSettingsPSITStorageName,
-    CommonSettingsPSITEndpoint,
-    CommonSettingsPSITPath,
-    CommonSettingsPSITEnabled,
-
-    // Codec settings
-    CommonSettingsCSFormat,
-
-    CommonSettingsNumberOfInferencePerMessage,
-    CommonSettingsUploadInterval,
-
-    // OTA
-    OtaVersion,
-    OtaMainChipLoaderChip,
-    OtaMainChipLoaderVersion,
-    OtaMainChipLoaderPackageUrl,
-    OtaMainChipLoaderHash,
-    OtaMainChipLoaderSize,
-    OtaMainChipFirmwareChip,
-    OtaMainChipFirmwareVersion,
-    OtaMainChipFirmwarePackageUrl,
-    OtaMainChipFirmwareHash,
-    OtaMainChipFirmwareSize,
-
-    OtaCompanionChipLoaderChip,
-    OtaCompanionChipLoaderVersion,
-    OtaCompanionChipLoaderPackageUrl,
-    OtaCompanionChipLoaderHash,
-    OtaCompanionChipLoaderSize,
-    OtaCompanionChipFirmwareChip,
-    OtaCompanionChipFirmwareVersion,
-    OtaCompanionChipFirmwarePackageUrl,
-    OtaCompanionChipFirmwareHash,
-    OtaCompanionChipFirmwareSize,
-
-    OtaSensorChipLoaderChip,
-    OtaSensorChipLoaderVersion,
-    OtaSensorChipLoaderPackageUrl,
-    OtaSensorChipLoaderHash,
-    OtaSensorChipLoaderSize,
-    OtaSensorChipFirmwareChip,
-    OtaSensorChipFirmwareVersion,
-    OtaSensorChipFirmwarePackageUrl,
-    OtaSensorChipFirmwareHash,
-    OtaSensorChipFirmwareSize,
-
-    // Ai Model
-    AiModel0Chip,
-    AiModel0Version,
-    AiModel0PackageUrl,
-    AiModel0Hash,
-    AiModel0Size,
-
-    AiModel1Chip,
-    AiModel1Version,
-    AiModel1PackageUrl,
-    AiModel1Hash,
-    AiModel1Size,
-
-    AiModel2Chip,
-    AiModel2Version,
-    AiModel2PackageUrl,
-    AiModel2Hash,
-    AiModel2Size,
-
-    AiModel3Chip,
-    AiModel3Version,
-    AiModel3PackageUrl,
-    AiModel3Hash,
-    AiModel3Size,
-
-    #[default]
-    Invalid,
-}
-
-impl From<ConfigKey> for usize {
-    fn from(value: ConfigKey) -> Self {
-        value as usize
-    }
-}
-
-impl From<usize> for ConfigKey {
-    fn from(value: usize) -> Self {
-        if value >= ConfigKey::size() {
-            return ConfigKey::Invalid;
-        }
-
-        // SAFETY: We've verified that value is within the valid range for ConfigKey variants
-        unsafe { std::mem::transmute(value) }
-    }
-}
-
-impl Display for ConfigKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let msg = match self {
-            ConfigKey::ReportStatusIntervalMin => "report-status-interval-min",
-            ConfigKey::ReportStatusIntervalMax => "report-status-interval-max",
-            ConfigKey::LedEnabled => "led_enabled",
-            ConfigKey::TemperatureUpdateInterval => "temperature_update_interval",
-            ConfigKey::AllLogSettingLevel => "log.all.level",
-            ConfigKey::AllLogSettingDestination => "log.all.destination",
-            ConfigKey::AllLogSettingStorageName => "log.all.storage_name",
-            ConfigKey::AllLogSettingPath => "log.all.path",
-            ConfigKey::MainLogSettingLevel => "log.main.level",
-            ConfigKey::MainLogSettingDestination => "log.main.destination",
-            ConfigKey::MainLogSettingStorageName => "log.main.storage_name",
-            ConfigKey::MainLogSettingPath => "log.main.path",
-            ConfigKey::SensorLogSettingLevel => "log.sensor.level",
-            ConfigKey::SensorLogSettingDestination => "log.sensor.destination",
-            ConfigKey::SensorLogSettingStorageName => "log.sensor.storage_name",
-            ConfigKey::SensorLogSettingPath => "log.sensor.path",
-            ConfigKey::CompanionFwLogSettingLevel => "log.companion_fw.level",
-            ConfigKey::CompanionFwLogSettingDestination => "log.companion_fw.destination",
-            ConfigKey::CompanionFwLogSettingStorageName => "log.companion_fw.storage_name",
-            ConfigKey::CompanionFwLogSettingPath => "log.companion_fw.path",
-            ConfigKey::CompanionAppLogSettingLevel => "log.companion_app.level",
-            ConfigKey::CompanionAppLogSettingDestination => "log.companion_app.destination",
-            ConfigKey::CompanionAppLogSettingStorageName => "log.companion_app.storage_name",
-            ConfigKey::CompanionAppLogSettingPath => "log.companion_app.path",
-
-            ConfigKey::IpMethod => "ip_method",
-            ConfigKey::NtpUrl => "ntp_url",
-            ConfigKey::StaticIpv4Ip => "static_ipv4_ip",
-            ConfigKey::StaticIpv4SubnetMask => "static_ipv4_subnet_mask",
-            ConfigKey::StaticIpv4Gateway => "static_ipv4_gateway",
-            ConfigKey::StaticIpv4Dns => "static_ipv4_dns",
-            ConfigKey::StaticIpv6Ip => "static_ipv6_ip",
-            ConfigKey::StaticIpv6SubnetMask => "static_ipv6_subnet_mask",
-            ConfigKey::StaticIpv6Gateway => "static_ipv6_gateway",
-            ConfigKey::StaticIpv6Dns => "static_ipv6_dns",
-            ConfigKey::ProxyUrl => "proxy_url",
-            ConfigKey::ProxyPort => "proxy_port",
-            ConfigKey::ProxyUserName => "proxy_user_name",
-            ConfigKey::ProxyPassword => "proxy_password",
-            ConfigKey::StaSsid => "station_mode_ssid",
-            ConfigKey::StaPassword => "station_mode_password",
-            ConfigKey::StaEncryption => "station_mode_encryption",
-            ConfigKey::DirectGetImageSensorName => "sensor_name",
-            ConfigKey::DirectGetImageNetworkId => "network_id",
-
-            ConfigKey::CommonSettingsProcessState => "process_state",
-            ConfigKey::CommonSettingsLogLevel => "log_level",
-            ConfigKey::CommonSettingsISNumberOfIterations => "number_of_iterations",
-            ConfigKey::CommonSettingsPQCameraImageSizeWidth => "PQ.camera_image_size.width",
-            ConfigKey::CommonSettingsPQCameraImageSizeHeight => "PQ.camera_image_size.height",
-            ConfigKey::CommonSettingsPQCameraImageSizeScalingPolicy => {
-                "PQ.camera_image_size.scaling_policy"
-            }
-            ConfigKey::CommonSettingsPQFrameRateNum => "PQ.frame_rate.num",
-            ConfigKey::CommonSettingsPQFrameRateDenom => "PQ.frame_rate.denom",
-            ConfigKey::CommonSettingsPQDigitalZoom => "PQ.digital_zoom",
-            ConfigKey::CommonSettingsPQCameraImageFlipHorizontal => {
-                "PQ.camera_image_flip_horizontal"
-            }
-            ConfigKey::CommonSettingsPQCameraImageFlipVertical => "PQ.camera_image_flip_vertical",
-            ConfigKey::CommonSettingsPQExposureMode => "PQ.exposure_mode",
-            ConfigKey::CommonSettingsPQAeMaxExposureTime => "PQ.auto_exposure.max_exposure_time",
-            ConfigKey::CommonSettingsPQAeMinExposureTime => "PQ.auto_exposure.min_exposure_time",
-            ConfigKey::CommonSettingsPQAeMaxGain => "PQ.auto_exposure.max_gain",
-            ConfigKey::CommonSettingsPQAeConvergenceSpeed => "PQ.auto_exposure.convergence_speed",
-            ConfigKey::CommonSettingsPQEvCompensation => "PQ.ev_compensation",
-
-            ConfigKey::CommonSettingsPQAeAntiFlickerMode => "PQ.ae_anti_flicker_mode",
-            ConfigKey::CommonSettingsPQMeExposureTime => "PQ.manual_exposure.exposure_time",
-            ConfigKey::CommonSettingsPQMeGain => "PQ.manual_exposure.gain",
-            ConfigKey::CommonSettingsPQWhiteBalanceMode => "PQ.white_balance_mode",
-            ConfigKey::CommonSettingsPQAwbConvergenceSpeed => "PQ.auto_wb.convergence_speed",
-            ConfigKey::CommonSettingsPQMWBPColorTemperature => "PQ.manual_wb.color_temperature",
-            ConfigKey::CommonSettingsPQMWBGRed => "PQ.manual_wb.gain_red",
-            ConfigKey::CommonSettingsPQMWBGBlue => "PQ.manual_wb.gain_blue",
-            ConfigKey::CommonSettingsPQICLeft => "PQ.image_cropping.left",
-            ConfigKey::CommonSettingsPQICTop => "PQ.image_cropping.top",
-            ConfigKey::CommonSettingsPQICWidth => "PQ.image_cropping.width",
-            ConfigKey::CommonSettingsPQICHeight => "PQ.image_cropping.height",
-            ConfigKey::CommonSettingsPQImageRotation => "PQ.image_rotation",
-            ConfigKey::CommonSettingsPSMetadataMethod => "port_settings.OT.method",
-            ConfigKey::CommonSettingsPSMetadataStorageName => "port_settings.OT.storage_name",
-            ConfigKey::CommonSettingsPSMetadataEndpoint => "port_settings.OT.endpoint",
-            ConfigKey::CommonSettingsPSMetadataPath => "port_settings.OT.path",
-            ConfigKey::CommonSettingsPSMetadataEnabled => "port_settings.OT.enabled",
-            ConfigKey::CommonSettingsPSITMethod => "port_settings.IT.method",
-            ConfigKey::CommonSettingsPSITStorageName => "port_settings.IT.storage_name",
-            ConfigKey::CommonSettingsPSITEndpoint => "port_settings.IT.endpoint",
-            ConfigKey::CommonSettingsPSITPath => "port_settings.IT.path",
-            ConfigKey::CommonSettingsPSITEnabled => "port_settings.IT.enabled",
-            ConfigKey::CommonSettingsCSFormat => "codec_settings.format",
-            ConfigKey::CommonSettingsNumberOfInferencePerMessage => {
-                "number_of_inference_per_message"
-            }
-            ConfigKey::CommonSettingsUploadInterval => "upload_interval",
-
-            ConfigKey::OtaVersion => "version",
-            ConfigKey::OtaMainChipLoaderChip => "main_chip.loader.chip",
-            ConfigKey::OtaMainChipLoaderVersion => "main_chip.loader.version",
-            ConfigKey::OtaMainChipLoaderPackageUrl => "main_chip.loader.package_url",
-            ConfigKey::OtaMainChipLoaderHash => "main_chip.loader.hash",
-            ConfigKey::OtaMainChipLoaderSize => "main_chip.loader.size",
-            ConfigKey::OtaMainChipFirmwareChip => "main_chip.firmware.chip",
-            ConfigKey::OtaMainChipFirmwareVersion => "main_chip.firmware.version",
-            ConfigKey::OtaMainChipFirmwarePackageUrl => "main_chip.firmware.package_url",
-            ConfigKey::OtaMainChipFirmwareHash => "main_chip.firmware.hash",
-            ConfigKey::OtaMainChipFirmwareSize => "main_chip.firmware.size",
-
-            ConfigKey::OtaCompanionChipLoaderChip => "companion_chip.loader.chip",
-            ConfigKey::OtaCompanionChipLoaderVersion => "companion_chip.loader.version",
-            ConfigKey::OtaCompanionChipLoaderPackageUrl => "companion_chip.loader.package_url",
-            ConfigKey::OtaCompanionChipLoaderHash => "companion_chip.loader.hash",
-            ConfigKey::OtaCompanionChipLoaderSize => "companion_chip.loader.size",
-            ConfigKey::OtaCompanionChipFirmwareChip => "companion_chip.firmware.chip",
-            ConfigKey::OtaCompanionChipFirmwareVersion => "companion_chip.firmware.version",
-            ConfigKey::OtaCompanionChipFirmwarePackageUrl => "companion_chip.firmware.package_url",
-            ConfigKey::OtaCompanionChipFirmwareHash => "companion_chip.firmware.hash",
-            ConfigKey::OtaCompanionChipFirmwareSize => "companion_chip.firmware.size",
-
-            ConfigKey::OtaSensorChipLoaderChip => "sensor_chip.loader.chip",
-            ConfigKey::OtaSensorChipLoaderVersion => "sensor_chip.loader.version",
-            ConfigKey::OtaSensorChipLoaderPackageUrl => "sensor_chip.loader.package_url",
-            ConfigKey::OtaSensorChipLoaderHash => "sensor_chip.loader.hash",
-            ConfigKey::OtaSensorChipLoaderSize => "sensor_chip.loader.size",
-            ConfigKey::OtaSensorChipFirmwareChip => "sensor_chip.firmware.chip",
-            ConfigKey::OtaSensorChipFirmwareVersion => "sensor_chip.firmware.version",
-            ConfigKey::OtaSensorChipFirmwarePackageUrl => "sensor_chip.firmware.package_url",
-            ConfigKey::OtaSensorChipFirmwareHash => "sensor_chip.firmware.hash",
-            ConfigKey::OtaSensorChipFirmwareSize => "sensor_chip.firmware.size",
-
-            ConfigKey::AiModel0Chip => "ai_model[0].chip",
-            ConfigKey::AiModel0Version => "ai_model[0].version",
-            ConfigKey::AiModel0PackageUrl => "ai_model[0].package_url",
-            ConfigKey::AiModel0Hash => "ai_model[0].hash",
-            ConfigKey::AiModel0Size => "ai_model[0].size",
-
-            ConfigKey::AiModel1Chip => "ai_model[1].chip",
-            ConfigKey::AiModel1Version => "ai_model[1].version",
-            ConfigKey::AiModel1PackageUrl => "ai_model[1].package_url",
-            ConfigKey::AiModel1Hash => "ai_model[1].hash",
-            ConfigKey::AiModel1Size => "ai_model[1].size",
-
-            ConfigKey::AiModel2Chip => "ai_model[2].chip",
-            ConfigKey::AiModel2Version => "ai_model[2].version",
-            ConfigKey::AiModel2PackageUrl => "ai_model[2].package_url",
-            ConfigKey::AiModel2Hash => "ai_model[2].hash",
-            ConfigKey::AiModel2Size => "ai_model[2].size",
-
-            ConfigKey::AiModel3Chip => "ai_model[3].chip",
-            ConfigKey::AiModel3Version => "ai_model[3].version",
-            ConfigKey::AiModel3PackageUrl => "ai_model[3].package_url",
-            ConfigKey::AiModel3Hash => "ai_model[3].hash",
-            ConfigKey::AiModel3Size => "ai_model[3].size",
-            _ => "Invalid",
-        };
-
-        write!(f, "{}", msg)
-    }
-}
-
-impl ConfigKey {
-    // Returns the number of configuration keys including the invalid key
-    // Note ConfigKey is used as index in the config_keys vector starting from 0
-    pub fn size() -> usize {
-        ConfigKey::Invalid as usize + 1
-    }
-
-    pub fn note(&self) -> &'static str {
-        match self {
-            ConfigKey::AllLogSettingLevel => {
-                "0: critical, 1: error, 2: warning, 3: info, 4: debug, 5: trace"
-            }
-            ConfigKey::AllLogSettingDestination => "0: uart, 1: cloud_storage",
-            ConfigKey::AllLogSettingStorageName => "EVP Token provider ID.",
-            ConfigKey::MainLogSettingLevel => {
-                "0: critical, 1: error, 2: warning, 3: info, 4: debug, 5: trace"
-            }
-            ConfigKey::MainLogSettingDestination => "0: uart, 1: cloud_storage",
-            ConfigKey::MainLogSettingStorageName => "EVP Token provider ID.",
-            ConfigKey::SensorLogSettingLevel => {
-                "0: critical, 1: error, 2: warning, 3: info, 4: debug, 5: trace"
-            }
-            ConfigKey::SensorLogSettingDestination => "0: uart, 1: cloud_storage",
-            ConfigKey::SensorLogSettingStorageName => "EVP Token provider ID.",
-            ConfigKey::CompanionFwLogSettingLevel => "Log level for companion firmware log",
-            ConfigKey::CompanionFwLogSettingDestination => "0: uart, 1: cloud_storage",
-
-            ConfigKey::CompanionFwLogSettingStorageName => "EVP Token provider ID.",
-            ConfigKey::CompanionAppLogSettingLevel => {
-                "0: critical, 1: error, 2: warning, 3: info, 4: debug, 5: trace"
-            }
-            ConfigKey::CompanionAppLogSettingDestination => "0: uart, 1: cloud_storage",
-            ConfigKey::CompanionAppLogSettingStorageName => "EVP Token provider ID.",
-
-            // Network settings
-            ConfigKey::IpMethod => "0: dhcp, 1: static",
-            ConfigKey::NtpUrl => "Domain name or IP address",
-            ConfigKey::ProxyUrl => "Domain name or IP address",
-            ConfigKey::StaEncryption => "0: wpa2_psk, 1: wpa3_psk, 2: wpa2_wpa3_psk'",
-
-            // Edge App
-            ConfigKey::CommonSettingsProcessState => "0: stopped, 1: running",
-            ConfigKey::CommonSettingsLogLevel => {
-                "0: critical, 1: error, 2: warning, 3: info, 4: debug, 5: trace"
-            }
-            ConfigKey::CommonSettingsPQCameraImageSizeScalingPolicy => {
-                "1: sensitivity, 2: resolution"
-            }
-            ConfigKey::CommonSettingsPQCameraImageFlipHorizontal => "0: normal, 1: flip",
-            ConfigKey::CommonSettingsPQCameraImageFlipVertical => "0: normal, 1: flip",
-            ConfigKey::CommonSettingsPQExposureMode => "0: auto, 1: manual",
-            ConfigKey::CommonSettingsPQAeAntiFlickerMode => "0: off, 1: auto, 2: 50Hz, 3: 60Hz",
-            ConfigKey::CommonSettingsPQWhiteBalanceMode => "0: auto, 1: preset",
-            ConfigKey::CommonSettingsPQAwbConvergenceSpeed => "4300K ~ 5600K",
-            ConfigKey::CommonSettingsPQMWBPColorTemperature => {
-                "0: 3200K, 1: 4300K, 2: 5600K, 3: 6500K"
-            }
-
-            ConfigKey::CommonSettingsPQMWBGRed | ConfigKey::CommonSettingsPQMWBGBlue => {
-                "manual white balance"
-            }
-
-            ConfigKey::CommonSettingsPSMetadataEndpoint
-            | ConfigKey::CommonSettingsPSMetadataPath
-            | ConfigKey::CommonSettingsPSMetadataEnabled => "output tensor/ metadata",
-
-            ConfigKey::CommonSettingsPSITEndpoint
-            | ConfigKey::CommonSettingsPSITPath
-            | ConfigKey::CommonSettingsPSITEnabled => " input tensor / raw data",
-
-            ConfigKey::CommonSettingsPQImageRotation => {
-                "0: none, 1: clockwise 90 degrees, 2: clockwise 180 degrees, 3: clockwise 270 degrees"
-            }
-            ConfigKey::CommonSettingsPSMetadataMethod => {
-                "0: evp telemetry, 1: blob storage, 2: http storage"
-            }
-            ConfigKey::CommonSettingsPSMetadataStorageName => "EVP Token provider ID.",
-            ConfigKey::CommonSettingsPSITMethod => {
-                "0: evp telemetry, 1: blob storage, 2: http storage"
-            }
-            ConfigKey::CommonSettingsPSITStorageName => "EVP Token provider ID.",
-            ConfigKey::CommonSettingsCSFormat => "1: jpeg",
-
-            ConfigKey::OtaMainChipLoaderChip | ConfigKey::OtaMainChipFirmwareChip => {
-                "default: ApFw"
-            }
-            ConfigKey::OtaCompanionChipLoaderChip | ConfigKey::OtaCompanionChipFirmwareChip => {
-                "default: AI-ISP"
-            }
-            ConfigKey::OtaSensorChipLoaderChip | ConfigKey::OtaSensorChipFirmwareChip => {
-                "default: IMX500"
-            }
-            _ => "",
-        }
+/// Moving to a screen already in the stack goes back to it, so that moving between screens
+/// repeatedly does not grow the stack
+fn push_screen(screens: &mut Vec<Box<dyn Screen>>, next_screen: Box<dyn Screen>) {
+    let kind = std::mem::discriminant(&next_screen.id());
+    if let Some(i) = screens
+        .iter()
+        .position(|s| std::mem::discriminant(&s.id()) == kind)
+    {
+        screens.truncate(i);
     }
-
-    pub fn is_sas_url_entry(&self) -> bool {
-        matches!(
-            self,
-            ConfigKey::OtaMainChipLoaderPackageUrl
-                | ConfigKey::OtaMainChipFirmwarePackageUrl
-                | ConfigKey::OtaCompanionChipLoaderPackageUrl
-                | ConfigKey::OtaCompanionChipFirmwarePackageUrl
-                | ConfigKey::OtaSensorChipLoaderPackageUrl
-                | ConfigKey::OtaSensorChipFirmwarePackageUrl
-                | ConfigKey::AiModel0PackageUrl
-                | ConfigKey::AiModel1PackageUrl
-                | ConfigKey::AiModel2PackageUrl
-                | ConfigKey::AiModel3PackageUrl
-        )
+    screens.push(next_screen);
+    if screens.len() > MAX_SCREEN_DEPTH {
+        screens.remove(1);
     }
 }
 
-/// Focus areas within the main window for navigation
-#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
-pub enum MainWindowFocus {
-    #[default]
-    MainChip,
-    CompanionChip,
-    SensorChip,
-    DeviceManifest,
-    AgentState,
-    DeploymentStatus,
-    DeviceReserved,
-    DeviceState,
-    DeviceCapabilities,
-    SystemSettings,
-    NetworkSettings,
-    WirelessSettings,
-}
-
-impl MainWindowFocus {
-    /// Navigation order for main window focus areas
-    const NAVIGATION_ORDER: [MainWindowFocus; 12] = [
-        MainWindowFocus::MainChip,
-        MainWindowFocus::CompanionChip,
-        MainWindowFocus::SensorChip,
-        MainWindowFocus::DeviceManifest,
-        MainWindowFocus::AgentState,
-        MainWindowFocus::DeploymentStatus,
-        MainWindowFocus::DeviceReserved,
-        MainWindowFocus::DeviceState,
-        MainWindowFocus::DeviceCapabilities,
-        MainWindowFocus::SystemSettings,
-        MainWindowFocus::NetworkSettings,
-        MainWindowFocus::WirelessSettings,
-    ];
-
-    /// Get the next focus in navigation order
-    pub fn next(&self) -> Self {
-        let current_index = Self::NAVIGATION_ORDER
-            .iter()
-            .position(|&focus| focus == *self)
-            .unwrap_or(0);
-        let next_index = (current_index + 1) % Self::NAVIGATION_ORDER.len();
-        Self::NAVIGATION_ORDER[next_index]
-    }
-
-    /// Get the previous focus in navigation order
-    pub fn previous(&self) -> Self {
-        let current_index = Self::NAVIGATION_ORDER
-            .iter()
-            .position(|&focus| focus == *self)
-            .unwrap_or(0);
-        let prev_index = if current_index == 0 {
-            Self::NAVIGATION_ORDER.len() - 1
-        } else {
-            current_index - 1
-        };
-        Self::NAVIGATION_ORDER[prev_index]
-    }
-    pub fn user_config_file(&self) -> &'static str {
-        match self {
-            MainWindowFocus::DeploymentStatus => "edge_app_deploy.json",
-            MainWindowFocus::SystemSettings => "system_settings.json",
-            MainWindowFocus::NetworkSettings => "network_settings.json",
-            MainWindowFocus::WirelessSettings => "wireless_settings.json",
-            MainWindowFocus::MainChip
-            | MainWindowFocus::SensorChip
-            | MainWindowFocus::CompanionChip => "{ota_fw, ota_ai_model}.json",
-            _ => "configure.json",
+impl DMScreen {
+    /// Name displayed in the breadcrumbs, without the state of the screen
+    pub fn name(&self) -> String {
+        let name = format!("{:?}", self);
+        match name.split_once('(') {
+            Some((name, _)) => name.to_owned(),
+            None => name,
         }
     }
 }
@@ -757,21 +351,100 @@ impl MainWindowFocus {
 /// Main application state and controller
 pub struct App {
     exit: bool,
-    screens: Vec<DMScreen>,
+    /// Navigation stack, from Main to the current screen
+    screens: Vec<Box<dyn Screen>>,
     main_window_focus: MainWindowFocus,
     config_keys: Vec<String>,
+    /// Config keys edited by the user since the screen was opened, only these are sent
+    config_keys_dirty: Vec<bool>,
     config_key_focus: usize,
     config_key_focus_start: usize,
     config_key_focus_end: usize,
+    /// Sections of the configuration keys whose keys are hidden, by group
+    collapsed_groups: BTreeSet<&'static str>,
     /// Since companion chip and sensor chip shares the same display region in main ui,
     /// tracks the last focused chip because the companion chip and sensor chip share the same
     /// display region.
     last_config_companion_sensor: usize,
     config_key_editable: bool,
     config_result: Option<Result<String, DMError>>,
-    app_error: Option<String>,
-    token_provider_for_config: Option<ConfigKey>,
-    blob_list_state: Option<ui::ui_token_provider_blobs::BlobListState>,
+    /// Errors of the session, the newest one is shown in the footer
+    errors: ErrorLog,
+    /// Connectivity of the Azurite storage, shown in the header
+    azurite_health: HealthMonitor,
+    /// Operations running in the background, the current one is shown with a spinner in the
+    /// header and all of them in the Jobs screen
+    jobs: JobQueue,
+    /// Job following the OTA sent from the OTA wizard until the device reports its result
+    ota_job: Option<u64>,
+    /// Configuration sent in "apply and verify" mode with the job following it
+    apply_verify: Option<(ApplyVerify, u64)>,
+    last_apply_verify: Option<ApplyVerify>,
+    /// Focused entry of the Errors popup when it is open
+    errors_focus: Option<usize>,
+    /// Destructive action waiting for the answer of the user, over any screen
+    confirmation: Option<Confirmation>,
+    /// Whether the Errors popup shows the full report of the focused entry
+    errors_expanded: bool,
+    /// Panes whose passwords are displayed in plaintext
+    revealed_panes: Vec<MainWindowFocus>,
+    /// Token providers whose UUID is filled in the storage_name keys of each purpose
+    token_provider_defaults: TokenProviderDefaults,
+    /// Directory the blobs are downloaded to from the blobs screen, the current directory
+    /// if None
+    blob_download_dir: Option<String>,
+    ota_wizard: OtaWizard,
+    /// "host:port" of the MQTT broker, requested through the proxy by the proxy check
+    broker: String,
+    /// Metadata of the packages uploaded from local files, keyed by blob name
+    package_metadata: HashMap<String, PackageMetadata>,
+    /// Targets of the last OTA sent, used for rollback
+    ota_snapshot: Option<OtaSnapshot>,
+    /// Raw JSON of the deployment status when the deployment detail popup is open
+    deployment_detail: Option<ui::json_tree::JsonTree>,
+    /// Hashes of the modules deployed by device-monitor, keyed by module ID
+    deployed_module_hashes: HashMap<UUID, String>,
+    /// Popup to select a local module file, kept to reopen in the last directory
+    file_browser: Option<ui::ui_file_browser::FileBrowserState>,
+    /// Instance selected last, the EdgeApp screen shows and configures it
+    edge_app_instance: Option<String>,
+    /// Popup searching the device state from the main screen
+    search: Option<ui::ui_search::SearchState>,
+    /// Labels and notes of the devices, saved in the configuration directory
+    device_notes: DeviceNotes,
+    /// Key of the notes of the connected device, None until it reports its identity
+    device_key: Option<String>,
+    /// Popup editing the notes of the connected device
+    notes_editor: Option<NotesEditor>,
+    /// Popup displaying every field of the focused main screen pane
+    pane_detail: Option<ui::ui_pane_detail::PaneDetail>,
+    /// Time zone of the elog times, last_bootup_time and last connected time
+    pub time_zone: TimeZoneMode,
+    /// Components whose elog errors spiked beyond their baseline, the latest first
+    elog_anomalies: Vec<String>,
+    publish_history: ui::ui_publish::PublishHistory,
+    /// Options of the configuration previewed, changed before sending it
+    send_options: Option<PublishOptions>,
+    /// Browser of the Scripts screen, kept to reopen in the last directory
+    script_browser: Option<ui::ui_file_browser::FileBrowserState>,
+    /// Last script run, kept to show its log after it ends
+    script_runner: Option<ScriptRunner>,
+    auto_downloader: AutoDownloader,
+    dtmi_repository: String,
+    inventory_file: String,
+    /// State snapshot of the device the connected one is compared with
+    compare_file: String,
+    fleet_dir: String,
+    /// Shown in the header, nothing is sent to the device
+    dry_run: bool,
+    /// Shown in the header, the changes are refused
+    read_only: bool,
+    /// Shown in the header, the messages are delayed on purpose
+    slow_network: bool,
+    /// Times of the latest frames, with --profile-frame
+    frame_profile: Option<FrameProfile>,
+    /// Tail of the --log file, None without it
+    debug_log: Option<DebugLog>,
 }
 
 impl App {
@@ -782,54 +455,168 @@ impl App {
                 | ConfigKey::MainLogSettingStorageName
                 | ConfigKey::SensorLogSettingStorageName
                 | ConfigKey::CompanionFwLogSettingStorageName
+                | ConfigKey::CompanionAppLogSettingStorageName
         )
     }
     /// Creates a new application instance with the given configuration
-    pub fn new(_cfg: AppConfig) -> Result<Self, DMError> {
+    pub fn new(cfg: AppConfig) -> Result<Self, DMError> {
         Ok(Self {
             exit: false,
-            screens: vec![DMScreen::Main],
+            screens: if cfg.kiosk {
+                vec![
+                    Box::new(screens::MainScreen),
+                    Box::new(screens::KioskScreen),
+                ]
+            } else {
+                vec![Box::new(screens::MainScreen)]
+            },
             main_window_focus: MainWindowFocus::default(),
+            dry_run: cfg.dry_run,
+            read_only: cfg.read_only,
+            slow_network: cfg.slow_network,
+            frame_profile: cfg.profile_frame.then(FrameProfile::default),
+            debug_log: cfg.log_file.map(DebugLog::new),
             // Initialize config keys with empty strings excluding the invalid key
             config_keys: (0..ConfigKey::size()).map(|_| String::new()).collect(),
+            config_keys_dirty: vec![false; ConfigKey::size()],
             config_key_focus: 0,
             config_key_focus_start: 0,
             config_key_focus_end: 0,
+            collapsed_groups: BTreeSet::new(),
             config_key_editable: false,
             last_config_companion_sensor: MainWindowFocus::CompanionChip as usize,
             config_result: None,
-            app_error: None,
-            token_provider_for_config: None,
-            blob_list_state: None,
+            errors: ErrorLog::default(),
+            azurite_health: HealthMonitor::default(),
+            jobs: JobQueue::default(),
+            ota_job: None,
+            apply_verify: None,
+            last_apply_verify: None,
+            errors_focus: None,
+            confirmation: None,
+            errors_expanded: false,
+            revealed_panes: Vec::new(),
+            token_provider_defaults: TokenProviderDefaults::load(&Self::defaults_file())
+                .unwrap_or_else(|e| {
+                    jerror!(
+                        func = "App::new()",
+                        line = line!(),
+                        error = format!("{:?}", e)
+                    );
+                    TokenProviderDefaults::default()
+                }),
+            blob_download_dir: None,
+            file_browser: None,
+            edge_app_instance: None,
+            ota_wizard: OtaWizard::new(),
+            broker: match cfg.broker.split_once(':') {
+                Some(_) => cfg.broker.to_owned(),
+                None => format!("{}:1883", cfg.broker),
+            },
+            package_metadata: HashMap::new(),
+            ota_snapshot: None,
+            deployment_detail: None,
+            deployed_module_hashes: HashMap::new(),
+            search: None,
+            device_notes: DeviceNotes::load(&Self::notes_file()).unwrap_or_else(|e| {
+                jerror!(
+                    func = "App::new()",
+                    line = line!(),
+                    error = format!("{:?}", e)
+                );
+                DeviceNotes::default()
+            }),
+            device_key: None,
+            notes_editor: None,
+            pane_detail: None,
+            time_zone: TimeZoneMode::default(),
+            elog_anomalies: vec![],
+            publish_history: ui::ui_publish::PublishHistory::default(),
+            send_options: None,
+            script_browser: None,
+            script_runner: None,
+            auto_downloader: AutoDownloader::new(cfg.download_dir),
+            dtmi_repository: cfg
+                .dtmi_repository
+                .unwrap_or(dtmi::DEFAULT_REPOSITORY)
+                .to_owned(),
+            inventory_file: cfg.inventory.unwrap_or_default().to_owned(),
+            compare_file: String::new(),
+            fleet_dir: cfg.fleet_dir.unwrap_or_default().to_owned(),
         })
     }
 
     /// Returns the configuration directory path, checking environment variables in order:
     /// DM_CONFIG_DIR, HOME, PWD
     pub fn config_dir() -> String {
-        if let Ok(config_dir) = std::env::var("DM_CONFIG_DIR") {
-            config_dir
-        } else if let Ok(config_dir) = std::env::var("HOME") {
-            config_dir
-        } else {
-            std::env::var("PWD").unwrap().to_owned()
-        }
+        device_config::config_dir()
     }
 
     pub fn current_screen(&self) -> DMScreen {
-        self.screens.last().unwrap().to_owned()
+        self.screens.last().unwrap().id()
     }
 
-    pub fn dm_screen_update(&mut self, screen: DMScreen) {
+    pub fn dm_screen_update(&mut self, screen: impl Screen) {
         if self.screens.len() > 1 {
             self.screens.pop();
         }
-        self.screens.push(screen);
+        self.screens.push(Box::new(screen));
+    }
+
+    /// The nearest screen of the type in the stack, e.g. to read its state for the key hints
+    pub fn screen<S: Screen>(&self) -> Option<&S> {
+        self.screens
+            .iter()
+            .rev()
+            .find_map(|s| screens::downcast_ref(s.as_ref()))
+    }
+
+    /// The nearest screen of the type in the stack, e.g. to apply the result of a job to it
+    fn screen_mut<S: Screen>(&mut self) -> Option<&mut S> {
+        self.screens
+            .iter_mut()
+            .rev()
+            .find_map(|s| screens::downcast_mut(s.as_mut()))
+    }
+
+    /// The message is looked up in the catalog, a formatted one is translated by trf()
+    pub fn push_warning(&mut self, source: ErrorSource, message: impl Into<String>) {
+        let message = message.into();
+        self.errors.push(ErrorEvent::new(
+            source,
+            Severity::Warning,
+            tr(&message).to_owned(),
+        ));
+    }
+
+    pub fn push_error(&mut self, source: ErrorSource, message: impl Into<String>) {
+        let message = message.into();
+        self.errors.push(ErrorEvent::new(
+            source,
+            Severity::Error,
+            tr(&message).to_owned(),
+        ));
+    }
+
+    pub fn push_report(&mut self, source: ErrorSource, report: &Report<DMError>) {
+        self.errors.push(ErrorEvent::from_report(source, report));
+    }
+
+    /// Records the report, the message tells what failed
+    pub fn push_failure(&mut self, source: ErrorSource, what: &str, report: &Report<DMError>) {
+        let mut event = ErrorEvent::from_report(source, report);
+        event.message = format!("{}: {}", tr(what), event.message);
+        self.errors.push(event);
+    }
+
+    /// Screens from Main to the current one
+    pub fn screens(&self) -> Vec<DMScreen> {
+        self.screens.iter().map(|s| s.id()).collect()
     }
 
-    pub fn dm_screen_move_to(&mut self, next_screen: DMScreen) {
-        self.screens.push(next_screen);
-        self.app_error = None;
+    pub fn dm_screen_move_to(&mut self, next_screen: impl Screen) {
+        push_screen(&mut self.screens, Box::new(next_screen));
+        self.errors.dismiss();
         with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.info = None);
     }
 
@@ -857,10 +644,92 @@ impl App {
         self.config_keys[size_key as usize] = module.size.to_string();
     }
 
-    pub fn update_sas_url_entries(&mut self) {
+    /// Warns about package URLs of the configuration whose SAS has already expired
+    pub fn warn_expired_sas_urls(&mut self) {
+        let now = Utc::now();
+        let expired: Vec<String> = (self.config_key_focus_start..=self.config_key_focus_end)
+            .map(ConfigKey::from)
+            .filter(|k| k.is_sas_url_entry())
+            .filter_map(|k| {
+                let sas = SasInfo::parse(&self.config_keys[usize::from(k)])?;
+                sas.is_expired(now)
+                    .then(|| format!("{} ({})", k, sas.countdown(now)))
+            })
+            .collect();
+
+        if !expired.is_empty() {
+            self.push_warning(
+                ErrorSource::Azurite,
+                trf("SAS URL already expired: {0}", &[&expired.join(", ")]),
+            );
+        }
+    }
+
+    /// Issues a new SAS URL for the OTA package of the focused entry without selecting the
+    /// module again. The hash and size are computed again from the blob content.
+    pub fn refresh_sas_url_entry(&mut self) {
+        let url_key = ConfigKey::from(self.config_key_focus);
+        let Some((chip_id, component)) = target_of_config_key(url_key) else {
+            return;
+        };
+        let [_, _, url_key_of_target, hash_key, size_key] = target_config_keys(chip_id, component);
+        if url_key != url_key_of_target {
+            return;
+        }
+
+        let url = self.config_keys[usize::from(url_key)].clone();
+        let Some((container, blob)) = blob_of_url(&url, &azurite_config().account_name) else {
+            self.push_warning(ErrorSource::Azurite, "No package URL to refresh.");
+            return;
+        };
+
+        match with_azurite_storage_mut(|azurite_storage| {
+            let id = azurite_storage
+                .module_of_blob(&container, &blob)
+                .map(|m| m.id.clone())?;
+            Some(azurite_storage.refresh_module(&id))
+        }) {
+            Some(Some(Ok(module))) => {
+                let changed = self.config_keys[usize::from(hash_key)] != module.hash_base64
+                    || self.config_keys[usize::from(size_key)] != module.size.to_string();
+                self.update_ota_config_for_url(url_key, hash_key, size_key, &module);
+
+                if changed {
+                    self.push_warning(
+                        ErrorSource::Azurite,
+                        trf(
+                            "{0} has changed, its hash and size have been updated.",
+                            &[&blob],
+                        ),
+                    );
+                } else {
+                    with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                        mqtt_ctrl.info = Some(trf("New SAS URL issued for {0}", &[&blob]));
+                    });
+                }
+            }
+            Some(Some(Err(e))) => {
+                self.push_failure(ErrorSource::Azurite, "Failed to refresh SAS URL", &e);
+            }
+            Some(None) => {
+                self.push_warning(
+                    ErrorSource::Azurite,
+                    trf(
+                        "{0}/{1} is not a known Azurite module.",
+                        &[&container, &blob],
+                    ),
+                );
+            }
+            None => self.push_error(ErrorSource::Azurite, "Azurite storage not available"),
+        }
+    }
+
+    /// Fills the package url, hash and size of the focused entry with the selected module.
+    /// Returns a warning when the selected OTA package targets another chip.
+    pub fn update_sas_url_entries(&mut self) -> Option<String> {
         let config_key = ConfigKey::from(self.config_key_focus);
         if !config_key.is_sas_url_entry() {
-            return;
+            return None;
         }
 
         with_azurite_storage(|az| {
@@ -950,14 +819,37 @@ impl App {
                 }
             }
         });
+
+        self.update_ota_config_for_package(config_key)
     }
 
-    pub fn dm_screen_move_back(&mut self) {
-        if self.screens.len() > 1 {
-            self.screens.pop();
+    /// Auto-fills the version of the OTA target from the package metadata
+    fn update_ota_config_for_package(&mut self, url_key: ConfigKey) -> Option<String> {
+        let (chip_id, component) = target_of_config_key(url_key)?;
+        let blob_name = with_azurite_storage(|az| az.current_module().map(|m| m.blob_name.clone()))
+            .flatten()?;
+        let metadata = self.package_metadata.get(&blob_name)?;
+        let [_, version_key, ..] = target_config_keys(chip_id, component);
+
+        if let Some(version) = metadata.version.as_ref() {
+            self.config_keys[usize::from(version_key)] = version.clone();
+            if self.config_keys[usize::from(ConfigKey::OtaVersion)].is_empty() {
+                self.config_keys[usize::from(ConfigKey::OtaVersion)] = version.clone();
+            }
         }
 
-        self.app_error = None;
+        metadata.mismatch_warning(chip_id)
+    }
+
+    pub fn dm_screen_move_back(&mut self) {
+        self.dm_screen_jump_back(self.screens.len().saturating_sub(1));
+    }
+
+    /// Goes back to the screen at `depth` of the stack, 1 being Main
+    pub fn dm_screen_jump_back(&mut self, depth: usize) {
+        self.screens.truncate(depth.max(1));
+
+        self.errors.dismiss();
         with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.info = None);
 
         // Clear the config keys and ModuleInfo when moving back to Main
@@ -974,6 +866,21 @@ impl App {
         }
     }
 
+    /// Keys the focus can stop at: the keys of the expanded sections and the first key of
+    /// the collapsed ones, which stands for their header
+    fn config_focus_stops(&self) -> Vec<usize> {
+        ConfigKey::sections(self.config_key_focus_start, self.config_key_focus_end)
+            .into_iter()
+            .flat_map(|(group, first, last)| {
+                if self.collapsed_groups.contains(group) {
+                    first..=first
+                } else {
+                    first..=last
+                }
+            })
+            .collect()
+    }
+
     pub fn config_focus_up(&mut self) {
         jdebug!(
             func = "config_focus_up",
@@ -981,10 +888,10 @@ impl App {
             end = self.config_key_focus_end,
             current = self.config_key_focus
         );
-        if self.config_key_focus == self.config_key_focus_start {
-            self.config_key_focus = self.config_key_focus_end;
-        } else {
-            self.config_key_focus -= 1;
+        let stops = self.config_focus_stops();
+        match stops.iter().rposition(|&i| i < self.config_key_focus) {
+            Some(pos) => self.config_key_focus = stops[pos],
+            None => self.config_key_focus = stops.last().copied().unwrap_or(self.config_key_focus),
         }
     }
 
@@ -995,1035 +902,1865 @@ impl App {
             end = self.config_key_focus_end,
             current = self.config_key_focus
         );
-        if self.config_key_focus == self.config_key_focus_end {
-            self.config_key_focus = self.config_key_focus_start;
+        let stops = self.config_focus_stops();
+        match stops.iter().position(|&i| i > self.config_key_focus) {
+            Some(pos) => self.config_key_focus = stops[pos],
+            None => self.config_key_focus = stops.first().copied().unwrap_or(self.config_key_focus),
+        }
+    }
+
+    /// Section of the focused key, as (group, first key, last key)
+    fn config_focus_section(&self) -> Option<(&'static str, usize, usize)> {
+        ConfigKey::sections(self.config_key_focus_start, self.config_key_focus_end)
+            .into_iter()
+            .find(|(_, first, last)| (*first..=*last).contains(&self.config_key_focus))
+    }
+
+    /// Moves the focus to the first key of the next section, or of the previous one
+    pub fn config_focus_section_jump(&mut self, next: bool) {
+        let sections = ConfigKey::sections(self.config_key_focus_start, self.config_key_focus_end);
+        let Some(pos) = sections
+            .iter()
+            .position(|(_, first, last)| (*first..=*last).contains(&self.config_key_focus))
+        else {
+            return;
+        };
+
+        let (_, first, _) = sections[pos];
+        let target = if next {
+            (pos + 1) % sections.len()
+        } else if self.config_key_focus != first {
+            // The first key of the focused section comes before the previous section
+            pos
         } else {
-            self.config_key_focus += 1;
+            (pos + sections.len() - 1) % sections.len()
+        };
+        self.config_key_focus = sections[target].1;
+    }
+
+    /// Hides the keys of the focused section, or shows them again
+    pub fn config_toggle_section(&mut self) {
+        let Some((group, first, _)) = self.config_focus_section() else {
+            return;
+        };
+
+        if !self.collapsed_groups.remove(group) {
+            self.collapsed_groups.insert(group);
+            self.config_key_focus = first;
+        }
+    }
+
+    pub fn is_section_collapsed(&self, group: &str) -> bool {
+        self.collapsed_groups.contains(group)
+    }
+
+    /// Sets the focused config key back to the value reported by the device, or unsets it
+    /// when the device doesn't report one
+    pub fn config_reset_focused(&mut self) {
+        let config_key = ConfigKey::from(self.config_key_focus);
+        if self.is_section_collapsed(config_key.group()) {
+            return;
+        }
+
+        let reported = with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.reported_config_value(config_key));
+        self.config_keys[self.config_key_focus] = reported.unwrap_or_default();
+        self.config_keys_dirty[self.config_key_focus] = false;
+    }
+
+    /// Unsets the focused config key, it is left out of the generated configuration
+    pub fn config_clear_focused(&mut self) {
+        let config_key = ConfigKey::from(self.config_key_focus);
+        if !self.is_section_collapsed(config_key.group()) {
+            self.config_keys[self.config_key_focus].clear();
+            self.config_keys_dirty[self.config_key_focus] = false;
         }
     }
 
+    /// Focused config key, marked as edited so that it is sent with the configuration
+    pub fn edit_focused_config_key(&mut self) -> &mut String {
+        self.config_keys_dirty[self.config_key_focus] = true;
+        &mut self.config_keys[self.config_key_focus]
+    }
+
+    /// Copy of the config keys where only the edited ones are set
+    pub fn dirty_config_keys(&self) -> Vec<String> {
+        dirty_config_keys(&self.config_keys, &self.config_keys_dirty)
+    }
+
     /// Clears all configuration input fields and resets the config result
     pub fn config_key_clear(&mut self) {
         self.config_keys = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        self.config_keys_dirty = vec![false; ConfigKey::size()];
         self.config_result = None;
     }
 
     pub fn switch_to_evp_module_screen(&mut self, action: AzuriteAction) {
-        // Retrieve module information from Azurite storage when moving to EvpModule screen
-        if let Some(result) =
-            with_azurite_storage_mut(|azurite_storage| azurite_storage.update_modules(None))
-        {
-            if let Err(e) = result {
-                self.app_error = Some(format!(
-                    "Failed to update modules from Azurite: {}",
-                    e.error_str().unwrap_or("Unknown error".to_owned())
-                ));
-            } else {
-                with_azurite_storage_mut(|azurite_storage| {
-                    azurite_storage.current_module_focus_init();
-                    azurite_storage.push_action(action);
-                });
-                self.dm_screen_move_to(DMScreen::EvpModule);
-            }
-        }
+        // Module information is retrieved from Azurite in the background, the list is
+        // updated when done
+        let Some(modules) = with_azurite_storage_mut(|azurite_storage| {
+            azurite_storage.current_module_focus_init();
+            azurite_storage.push_action(action);
+            azurite_storage.module_info_db().clone()
+        }) else {
+            return;
+        };
+
+        self.submit_azurite_job(AzuriteJob::UpdateModules(modules));
+        self.dm_screen_move_to(screens::EvpModuleScreen::default());
     }
 
-    pub fn switch_to_token_provider_screen(&mut self) {
-        // Scan token providers from Azurite storage when moving to TokenProvider screen
-        if let Some(result) =
-            with_azurite_storage_mut(|azurite_storage| azurite_storage.scan_upload_containers())
+    /// Opens the token provider list, ENTER sets the config key to the selected one
+    pub fn switch_to_token_provider_screen(&mut self, for_config: Option<ConfigKey>) {
+        // Token providers are scanned from Azurite in the background
+        if with_azurite_storage_mut(|azurite_storage| {
+            azurite_storage.current_token_provider_focus_init();
+        })
+        .is_none()
         {
-            if let Err(e) = result {
-                self.app_error = Some(format!(
-                    "Failed to scan token providers from Azurite: {}",
-                    e.error_str().unwrap_or("Unknown error".to_owned())
-                ));
-            } else {
-                with_azurite_storage_mut(|azurite_storage| {
-                    azurite_storage.current_token_provider_focus_init();
-                });
-                self.dm_screen_move_to(DMScreen::TokenProvider);
-            }
+            return;
         }
-    }
 
-    pub fn switch_to_edge_app_screen(&mut self) {
-        let has_instances = with_mqtt_ctrl(|mqtt_ctrl| {
-            if let Some(status) = mqtt_ctrl.deployment_status() {
-                !status.instances().is_empty()
-            } else {
-                false
-            }
+        self.submit_azurite_job(AzuriteJob::ScanContainers);
+        self.dm_screen_move_to(screens::TokenProviderScreen {
+            for_config,
+            ..Default::default()
         });
-        if has_instances {
-            self.dm_screen_move_to(DMScreen::EdgeApp(DMScreenState::Initial))
-        } else {
-            self.app_error = Some("No Edge App instances found.".to_owned());
-        }
     }
 
-    pub fn switch_to_elog_screen(&mut self) {
-        if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
-            self.dm_screen_move_to(DMScreen::Elog);
+    fn defaults_file() -> String {
+        format!("{}/{}", Self::config_dir(), DEFAULTS_FILE)
+    }
+
+    pub fn token_provider_defaults(&self) -> &TokenProviderDefaults {
+        &self.token_provider_defaults
+    }
+
+    /// Makes the focused token provider the default for the purpose, or clears it if it
+    /// already is, and saves the defaults
+    fn toggle_default_token_provider(&mut self, purpose: TokenProviderPurpose) {
+        let Some(uuid) = with_azurite_storage(|azurite_storage| {
+            azurite_storage
+                .get_current_token_provider_by_highlight()
+                .cloned()
+        })
+        .flatten() else {
+            return;
+        };
+
+        let is_default = self.token_provider_defaults.toggle(purpose, &uuid);
+        if let Err(e) = self.token_provider_defaults.save(&Self::defaults_file()) {
+            self.push_failure(
+                ErrorSource::Azurite,
+                "Failed to save token provider defaults",
+                &e,
+            );
         } else {
-            self.app_error = Some("Device is not connected.".to_owned());
+            with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                mqtt_ctrl.info = Some(if is_default {
+                    trf(
+                        "{0} is the default for {1}",
+                        &[&uuid.uuid(), &tr(purpose.name())],
+                    )
+                } else {
+                    trf("No default for {0}", &[&tr(purpose.name())])
+                })
+            });
         }
     }
 
-    pub fn switch_to_direct_command_screen(&mut self) {
-        if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
-            self.config_key_clear();
-            with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.direct_command_clear());
-            self.dm_screen_move_to(DMScreen::DirectCommand);
-        } else {
-            self.app_error = Some("Device is not connected.".to_owned());
+    /// Fills the empty storage_name keys whose destination is the cloud storage with the
+    /// default token provider of their purpose
+    fn fill_default_storage_names(&mut self) {
+        for (destination, storage_name, purpose) in DEFAULT_STORAGE_NAMES {
+            let Some(uuid) = self.token_provider_defaults.get(purpose) else {
+                continue;
+            };
+
+            if self.config_keys[usize::from(destination)].trim() == "1"
+                && self.config_keys[usize::from(storage_name)].is_empty()
+            {
+                self.config_keys[usize::from(storage_name)] = uuid.to_owned();
+                self.config_keys_dirty[usize::from(storage_name)] = true;
+            }
         }
     }
 
-    pub fn switch_to_config_screen(&mut self, user_config: bool) {
-        if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
-            self.config_key_clear();
-            if user_config {
-                match self.main_window_focus {
-                    MainWindowFocus::MainChip
-                    | MainWindowFocus::SensorChip
-                    | MainWindowFocus::CompanionChip
-                    | MainWindowFocus::DeploymentStatus
-                    | MainWindowFocus::SystemSettings
-                    | MainWindowFocus::NetworkSettings
-                    | MainWindowFocus::WirelessSettings => {
-                        self.dm_screen_move_to(DMScreen::ConfigurationUser);
-                    }
-                    _ => {}
-                }
-            } else {
-                match self.main_window_focus {
-                    MainWindowFocus::AgentState => {
-                        self.config_key_focus_start = ConfigKey::ReportStatusIntervalMin.into();
-                        self.config_key_focus_end = ConfigKey::ReportStatusIntervalMax.into();
-                        self.config_key_focus = self.config_key_focus_start;
-                        self.dm_screen_move_to(DMScreen::Configuration);
-                    }
-                    MainWindowFocus::SystemSettings => {
-                        self.config_key_focus_start = ConfigKey::LedEnabled.into();
-                        self.config_key_focus_end = ConfigKey::CompanionAppLogSettingPath.into();
-                        self.config_key_focus = self.config_key_focus_start;
-                        self.dm_screen_move_to(DMScreen::Configuration);
-                    }
-                    MainWindowFocus::NetworkSettings => {
-                        self.config_key_focus_start = ConfigKey::IpMethod.into();
-                        self.config_key_focus_end = ConfigKey::ProxyPassword.into();
-                        self.config_key_focus = self.config_key_focus_start;
-                        self.dm_screen_move_to(DMScreen::Configuration);
-                    }
-                    MainWindowFocus::WirelessSettings => {
-                        self.config_key_focus_start = ConfigKey::StaSsid.into();
-                        self.config_key_focus_end = ConfigKey::StaEncryption.into();
-                        self.config_key_focus = self.config_key_focus_start;
-                        self.dm_screen_move_to(DMScreen::Configuration);
-                    }
-                    _ => {}
+    /// Filters the token providers while it is typed
+    fn edit_token_provider_filter(&mut self, code: KeyCode) {
+        with_azurite_storage_mut(|azurite_storage| {
+            let mut filter = azurite_storage.token_provider_filter().to_owned();
+            match code {
+                KeyCode::Char(c) => filter.push(c),
+                KeyCode::Backspace => {
+                    filter.pop();
                 }
+                _ => return,
             }
-        } else {
-            self.app_error = Some("Device is not connected.".to_owned());
-        }
+            azurite_storage.set_token_provider_filter(&filter);
+        });
     }
 
-    fn switch_to_ota_config_screen(&mut self, state: DMScreenState) {
-        if state == DMScreenState::Initial {
-            self.config_key_clear();
-            self.config_key_focus_start = ConfigKey::OtaVersion.into();
-            self.config_key_focus_end = ConfigKey::OtaSensorChipFirmwareSize.into();
-            self.config_key_focus = self.config_key_focus_start;
-        }
-        self.dm_screen_move_to(DMScreen::OtaConfig(state));
+    /// Filters the modules while it is typed
+    fn edit_module_filter(&mut self, code: KeyCode) {
+        with_azurite_storage_mut(|azurite_storage| {
+            let mut filter = azurite_storage.module_filter().to_owned();
+            match code {
+                KeyCode::Char(c) => filter.push(c),
+                KeyCode::Backspace => {
+                    filter.pop();
+                }
+                _ => return,
+            }
+            azurite_storage.set_module_filter(&filter);
+        });
     }
 
-    fn switch_to_ai_model_config_screen(&mut self, state: DMScreenState) {
-        if state == DMScreenState::Initial {
-            self.config_key_clear();
-            self.config_key_focus_start = ConfigKey::AiModel0Chip.into();
-            self.config_key_focus_end = ConfigKey::AiModel3Size.into();
-            self.config_key_focus = self.config_key_focus_start;
+    /// Follows the progress of the downloads queued from the blobs screen
+    fn update_blob_downloads(&mut self) {
+        let records = self.jobs.records();
+        let Some(screen) = self.screen_mut::<screens::TokenProviderBlobsScreen>() else {
+            return;
+        };
+
+        let blob_state = screen.state_mut();
+        for (blob, download) in blob_state.downloads.iter_mut() {
+            if !download.is_active() {
+                continue;
+            }
+            let label = download_label(&blob_state.container_name, blob);
+            match records.iter().rev().find(|r| r.label == label) {
+                Some(record) if record.status == JobStatus::Running => {
+                    *download = BlobDownload::Running(record.progress.unwrap_or(0))
+                }
+                // Cancelled from the Jobs screen before it started
+                Some(record) if record.status == JobStatus::Cancelled => {
+                    *download = BlobDownload::Failed("cancelled".to_owned())
+                }
+                _ => {}
+            }
         }
-        self.dm_screen_move_to(DMScreen::AiModelConfig(state));
     }
 
-    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
-        match self.current_screen() {
-            DMScreen::Main => {
-                match key_event.code {
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        self.main_window_focus = self.main_window_focus.previous();
+    /// Queues a background job, telling the user when the same one is already queued or
+    /// running
+    fn submit_job(&mut self, job: Job) {
+        let label = job.label();
+        let background = job.is_background();
+        if !self.jobs.submit(job) && !background {
+            with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                mqtt_ctrl.info = Some(trf("{0} is already in progress, skipped", &[&label]))
+            });
+        }
+    }
+
+    pub fn submit_azurite_job(&mut self, job: AzuriteJob) {
+        self.submit_job(Job::Azurite(azurite_config(), job));
+    }
+
+    /// Uploads local files to the default container in the background. The files whose
+    /// blob already exists are uploaded with their policy, the others are reported.
+    fn submit_upload(
+        &mut self,
+        paths: Vec<String>,
+        purpose: UploadPurpose,
+        overwrite: HashMap<String, OverwritePolicy>,
+    ) {
+        let Some(modules) =
+            with_azurite_storage(|azurite_storage| azurite_storage.module_info_db().clone())
+        else {
+            self.push_error(ErrorSource::Azurite, "Azurite storage not available");
+            return;
+        };
+
+        self.submit_azurite_job(AzuriteJob::Upload {
+            paths,
+            purpose,
+            modules,
+            overwrite,
+        });
+    }
+
+    /// The job started last among the running ones
+    pub fn current_job(&self) -> Option<JobRecord> {
+        self.jobs.current()
+    }
+
+    /// Whether a job with the label is queued or running
+    pub fn is_job_active(&self, label: &str) -> bool {
+        self.jobs
+            .records()
+            .iter()
+            .any(|r| r.is_active() && r.label == label)
+    }
+
+    pub fn job_records(&self) -> Vec<JobRecord> {
+        self.jobs.records()
+    }
+
+    /// Applies the results of the jobs completed since the last update
+    fn update_jobs(&mut self) {
+        for result in self.jobs.poll() {
+            match result {
+                JobResult::Azurite(result) => self.apply_azurite_result(result),
+                JobResult::Report(Ok(path)) => with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                    mqtt_ctrl.info = Some(trf("Report saved to: {0}", &[&path]))
+                }),
+                JobResult::Report(Err(e)) => self.push_report(ErrorSource::File, &e),
+                JobResult::ProxyCheck(report) => {
+                    match report.failure() {
+                        Some(failure) => self.push_warning(ErrorSource::App, &failure),
+                        None => {
+                            with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.info = Some(report.summary()))
+                        }
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        self.main_window_focus = self.main_window_focus.next();
+                    if let Some(screen) = self.screen_mut::<screens::ConfigurationScreen>() {
+                        screen.proxy_check = Some(report);
                     }
-                    KeyCode::Right | KeyCode::Char('l') => match self.main_window_focus {
-                        MainWindowFocus::MainChip => {
-                            self.main_window_focus = MainWindowFocus::AgentState
-                        }
-                        MainWindowFocus::CompanionChip => {
-                            self.main_window_focus = MainWindowFocus::DeploymentStatus
-                        }
-                        MainWindowFocus::SensorChip => {
-                            self.main_window_focus = MainWindowFocus::DeviceReserved
-                        }
-                        MainWindowFocus::DeviceManifest => {
-                            self.main_window_focus = MainWindowFocus::DeviceCapabilities
-                        }
-                        MainWindowFocus::AgentState => {
-                            self.main_window_focus = MainWindowFocus::SystemSettings
-                        }
-                        MainWindowFocus::DeploymentStatus => {
-                            self.main_window_focus = MainWindowFocus::SystemSettings
-                        }
-                        MainWindowFocus::DeviceReserved => {
-                            self.main_window_focus = MainWindowFocus::NetworkSettings
-                        }
-                        MainWindowFocus::DeviceState => {
-                            self.main_window_focus = MainWindowFocus::WirelessSettings
-                        }
-                        MainWindowFocus::DeviceCapabilities => {
-                            self.main_window_focus = MainWindowFocus::WirelessSettings
-                        }
-                        MainWindowFocus::SystemSettings => {
-                            self.main_window_focus = MainWindowFocus::MainChip
-                        }
-                        MainWindowFocus::NetworkSettings => {
-                            self.main_window_focus = MainWindowFocus::CompanionChip
-                        }
-                        MainWindowFocus::WirelessSettings => {
-                            self.main_window_focus = MainWindowFocus::SensorChip
-                        }
-                    },
-                    KeyCode::Left | KeyCode::Char('h') => match self.main_window_focus {
-                        MainWindowFocus::MainChip => {
-                            self.main_window_focus = MainWindowFocus::SystemSettings
-                        }
-                        MainWindowFocus::CompanionChip => {
-                            self.main_window_focus = MainWindowFocus::SystemSettings
-                        }
-                        MainWindowFocus::SensorChip => {
-                            self.main_window_focus = MainWindowFocus::NetworkSettings
-                        }
-                        MainWindowFocus::DeviceManifest => {
-                            self.main_window_focus = MainWindowFocus::WirelessSettings
-                        }
-                        MainWindowFocus::AgentState => {
-                            self.main_window_focus = MainWindowFocus::MainChip
-                        }
-                        MainWindowFocus::DeploymentStatus => {
-                            self.main_window_focus = MainWindowFocus::MainChip
-                        }
-                        MainWindowFocus::DeviceReserved => {
-                            self.main_window_focus = MainWindowFocus::CompanionChip
-                        }
-                        MainWindowFocus::DeviceState => {
-                            self.main_window_focus = MainWindowFocus::SensorChip
-                        }
-                        MainWindowFocus::DeviceCapabilities => {
-                            self.main_window_focus = MainWindowFocus::DeviceManifest
-                        }
-                        MainWindowFocus::SystemSettings => {
-                            self.main_window_focus = MainWindowFocus::AgentState
-                        }
-                        MainWindowFocus::NetworkSettings => {
-                            self.main_window_focus = MainWindowFocus::DeploymentStatus
-                        }
-                        MainWindowFocus::WirelessSettings => {
-                            self.main_window_focus = MainWindowFocus::DeviceState
+                }
+                JobResult::NtpCheck(report) => {
+                    match report.failure() {
+                        Some(failure) => self.push_warning(ErrorSource::App, &failure),
+                        None => {
+                            with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.info = Some(report.summary()))
                         }
-                    },
-                    KeyCode::Enter => self.dm_screen_move_to(DMScreen::Module),
-                    KeyCode::Char('e') => self.switch_to_config_screen(false),
-                    KeyCode::Char('E') => self.switch_to_config_screen(true),
-                    KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                    KeyCode::Char('d') => self.switch_to_direct_command_screen(),
-                    KeyCode::Char('m') => self.switch_to_evp_module_screen(AzuriteAction::Deploy),
-                    KeyCode::Char('t') => self.switch_to_token_provider_screen(),
-                    KeyCode::Char('g') => self.switch_to_elog_screen(),
-                    KeyCode::Char('M') => self.switch_to_edge_app_screen(),
-                    KeyCode::Char('o') => self.dm_screen_move_to(DMScreen::Ota),
-                    KeyCode::Char('a') => self.dm_screen_move_to(DMScreen::AiModel),
-                    _ => {}
+                    }
+                    if let Some(screen) = self.screen_mut::<screens::ConfigurationScreen>() {
+                        screen.ntp_check = Some(report);
+                    }
                 }
-                // Since companion chip and sensor chip shares the same display region in main ui,
-                // last_config_companion_sensor is used to remember which is the last focused.
-                if self.main_window_focus == MainWindowFocus::CompanionChip
-                    || self.main_window_focus == MainWindowFocus::SensorChip
-                {
-                    self.last_config_companion_sensor = self.main_window_focus as usize;
+            }
+        }
+
+        self.update_blob_downloads();
+        self.update_ota_job();
+        self.update_apply_verify();
+    }
+
+    /// Reports the progress of the OTA sent from the OTA wizard to its job
+    fn update_ota_job(&mut self) {
+        let Some(id) = self.ota_job else {
+            return;
+        };
+
+        match with_mqtt_ctrl(|mqtt_ctrl| self.ota_wizard.progress(mqtt_ctrl.firmware())) {
+            OtaProgress::Waiting => {}
+            OtaProgress::InProgress(_, percent) => {
+                self.jobs
+                    .set_progress(id, percent.clamp(0, 100) as usize, 100)
+            }
+            OtaProgress::Done => {
+                self.jobs.finish(id, None);
+                self.ota_job = None;
+            }
+            OtaProgress::Failed(state) => {
+                self.jobs
+                    .finish(id, Some(format!("Device reported {:?}", state)));
+                self.ota_job = None;
+            }
+        }
+    }
+
+    /// Sends the previewed configuration, then waits for the device to reboot and checks
+    /// that the reported settings match the sent values
+    pub fn send_configuration_and_verify(&mut self) {
+        let Some(Ok(config)) = self.config_result.clone() else {
+            return;
+        };
+
+        if self.send_and_verify(&config, self.send_options) {
+            self.dm_screen_move_back();
+        }
+    }
+
+    /// Sends a network or wireless configuration and starts verifying it, returns whether
+    /// it has been sent
+    fn send_and_verify(&mut self, config: &str, options: Option<PublishOptions>) -> bool {
+        let reboots = with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.reboots().total());
+        let Some(verify) = ApplyVerify::new(config, reboots, Instant::now()) else {
+            self.push_warning(
+                ErrorSource::App,
+                "Only network and wireless settings are applied by a reboot, press (s) to send.",
+            );
+            return false;
+        };
+
+        let result = with_mqtt_ctrl_mut(|mqtt_ctrl| match options {
+            Some(options) => mqtt_ctrl.send_configure_with(config, options),
+            None => mqtt_ctrl.send_configure(config),
+        });
+        if let Err(e) = result {
+            self.push_failure(ErrorSource::Mqtt, "Failed to send configuration", &e);
+            return false;
+        }
+
+        if let Some((_, id)) = self.apply_verify.take() {
+            self.jobs
+                .finish(id, Some("Superseded by a new configuration".to_owned()));
+        }
+        let id = self
+            .jobs
+            .track(&format!("Apply and verify {}", verify.section));
+        self.apply_verify = Some((verify, id));
+        self.last_apply_verify = None;
+        true
+    }
+
+    /// Configuration being verified, or the last verified one
+    pub fn apply_verify_state(&self) -> Option<&ApplyVerify> {
+        self.apply_verify
+            .as_ref()
+            .map(|(verify, _)| verify)
+            .or(self.last_apply_verify.as_ref())
+    }
+
+    /// Follows the reboot of the device and the settings it reports after a configuration
+    /// sent with [`App::send_configuration_and_verify`]
+    fn update_apply_verify(&mut self) {
+        let Some((verify, id)) = self.apply_verify.as_mut() else {
+            return;
+        };
+
+        let (reboots, reported) = with_mqtt_ctrl(|mqtt_ctrl| {
+            let reported = match verify.section.as_str() {
+                "network_settings" => mqtt_ctrl
+                    .network_settings()
+                    .and_then(|s| serde_json::to_value(s).ok()),
+                "wireless_setting" => mqtt_ctrl
+                    .wireless_settings()
+                    .and_then(|s| serde_json::to_value(s).ok()),
+                _ => None,
+            };
+            (mqtt_ctrl.reboots().total(), reported)
+        });
+        verify.update(reboots, reported.as_ref(), Instant::now());
+
+        match verify.phase {
+            VerifyPhase::WaitingReboot => self.jobs.set_progress(*id, 0, 2),
+            VerifyPhase::WaitingReport => self.jobs.set_progress(*id, 1, 2),
+            VerifyPhase::Done | VerifyPhase::TimedOut => {
+                let failure = verify.failure();
+                let summary = verify.summary();
+                self.jobs.finish(*id, failure.clone());
+                self.last_apply_verify = self.apply_verify.take().map(|(verify, _)| verify);
+
+                match failure {
+                    Some(failure) => self.push_warning(ErrorSource::Mqtt, failure),
+                    None => with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.info = Some(summary)),
                 }
             }
+        }
+    }
 
-            DMScreen::Module => match key_event.code {
-                KeyCode::Enter | KeyCode::Esc => self.dm_screen_move_back(),
-                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                KeyCode::Char('e') => self.switch_to_config_screen(false),
-                KeyCode::Char('E') => self.switch_to_config_screen(true),
-                KeyCode::Char('d') => self.switch_to_direct_command_screen(),
-                KeyCode::Char('m') => self.switch_to_evp_module_screen(AzuriteAction::Deploy),
-                KeyCode::Char('t') => self.switch_to_token_provider_screen(),
-                KeyCode::Char('g') => self.switch_to_elog_screen(),
-                KeyCode::Char('o') => self.dm_screen_move_to(DMScreen::Ota),
-                _ => {}
-            },
+    fn set_azurite_modules(&mut self, modules: Result<HashMap<UUID, ModuleInfo>, DMError>) {
+        match modules {
+            Ok(modules) => {
+                with_azurite_storage_mut(|azurite_storage| {
+                    azurite_storage.set_module_info_db(modules)
+                });
+            }
+            Err(e) => self.push_failure(
+                ErrorSource::Azurite,
+                "Failed to update modules from Azurite",
+                &e,
+            ),
+        }
+    }
 
-            DMScreen::Exiting => {
-                match key_event.code {
-                    KeyCode::Char('y') => {
-                        self.exit = true;
-                    }
-                    KeyCode::Char('n') => {
-                        self.dm_screen_move_back();
-                        self.exit = false;
-                    }
-                    _ => {}
+    fn apply_azurite_result(&mut self, result: AzuriteJobResult) {
+        match result {
+            AzuriteJobResult::Downloaded {
+                container,
+                blob,
+                result,
+            } => {
+                let status = match &result {
+                    Ok(download) => BlobDownload::Done(download.path.clone()),
+                    Err(e) => BlobDownload::Failed(e.error_str().unwrap_or(e.to_string())),
                 };
-            }
-            DMScreen::ConfigurationUser => match key_event.code {
-                KeyCode::Esc if self.config_result.is_some() => self.config_result = None,
-                KeyCode::Char('s') => {
-                    if let Some(Ok(s)) = self.config_result.as_ref() {
-                        match with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.send_configure(s)) {
-                            Ok(()) => self.dm_screen_move_back(),
-                            Err(_) => {
-                                self.app_error = Some("Failed to send configuration!".to_owned())
-                            }
-                        }
-                    }
+                if let Some(screen) = self.screen_mut::<screens::TokenProviderBlobsScreen>()
+                    && screen.state_mut().container_name == container
+                {
+                    screen.state_mut().downloads.insert(blob, status);
                 }
-                KeyCode::Char('w') => match with_mqtt_ctrl(|mqtt_ctrl| {
-                    mqtt_ctrl.parse_configure(None, self.main_window_focus())
-                }) {
-                    Ok(s) => {
-                        if !s.is_empty() {
-                            self.config_result = Some(Ok(s));
-                        }
+
+                match result {
+                    Ok(download) => with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                        mqtt_ctrl.info =
+                            Some(format!("Blob downloaded to: {}", download.summary()));
+                    }),
+                    Err(e) => self.push_report(ErrorSource::Azurite, &e),
+                }
+            }
+            AzuriteJobResult::Modules(modules) => self.set_azurite_modules(modules),
+            AzuriteJobResult::TokenProviders(Ok(token_providers)) => {
+                with_azurite_storage_mut(|azurite_storage| {
+                    azurite_storage.set_token_providers(token_providers)
+                });
+            }
+            AzuriteJobResult::TokenProviders(Err(e)) => self.push_failure(
+                ErrorSource::Azurite,
+                "Failed to scan token providers from Azurite",
+                &e,
+            ),
+            AzuriteJobResult::Blobs {
+                container,
+                log_files,
+                marker,
+                result,
+            } => self.show_blobs(container, log_files, marker, result),
+            AzuriteJobResult::RemovedModules {
+                batch,
+                removals,
+                modules,
+            } => {
+                self.set_azurite_modules(modules);
+                if batch {
+                    let mut summary = ui::ui_deploy::BatchSummary::new("Remove");
+                    for (name, result) in removals.iter() {
+                        summary.push(
+                            name,
+                            result
+                                .as_ref()
+                                .err()
+                                .map(|e| e.error_str().unwrap_or(tr("Unknown error").to_owned())),
+                        );
                     }
-                    Err(e) => {
-                        self.config_result = Some(Err(e));
+                    if let Some(screen) = self.screen_mut::<screens::EvpModuleScreen>() {
+                        screen.batch_summary = Some(summary);
                     }
-                },
-                KeyCode::Esc => self.dm_screen_move_back(),
-                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                _ => {}
+                } else if let Some((name, Err(e))) = removals.first() {
+                    self.push_failure(
+                        ErrorSource::Azurite,
+                        &trf("Failed to remove module '{0}'", &[name]),
+                        e,
+                    );
+                }
+            }
+            AzuriteJobResult::RemovedTokenProvider { uuid, result } => match result {
+                Ok(()) => {
+                    with_azurite_storage_mut(|azurite_storage| {
+                        azurite_storage.forget_token_provider(&uuid)
+                    });
+                }
+                Err(e) => {
+                    self.push_failure(ErrorSource::Azurite, "Failed to remove token provider", &e)
+                }
             },
-            DMScreen::Configuration => match key_event.code {
-                KeyCode::Char(c) if self.config_key_editable => {
-                    let value: &mut String =
-                        self.config_keys.get_mut(self.config_key_focus).unwrap();
-                    value.push(c);
+            AzuriteJobResult::Reconfigured { url, result } => {
+                self.azurite_health.record(Some(&result), Instant::now());
+                // Shown unless the screen has been left meanwhile
+                if self.current_screen() == DMScreen::StorageSettings {
+                    self.config_result = Some(result.map(|containers| {
+                        format!("Connected to {}, {} container(s) found.", url, containers)
+                    }));
                 }
-                KeyCode::Backspace if self.config_key_editable => {
-                    let value: &mut String =
-                        self.config_keys.get_mut(self.config_key_focus).unwrap();
-                    value.pop();
+            }
+            AzuriteJobResult::Health(result) => {
+                self.azurite_health.record(Some(&result), Instant::now())
+            }
+            AzuriteJobResult::AutoDownloaded(downloads) => {
+                let downloaded = self.auto_downloader.apply(downloads);
+                if downloaded > 0 {
+                    with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                        mqtt_ctrl.info =
+                            Some(format!("{} blob(s) downloaded automatically", downloaded))
+                    });
                 }
-                KeyCode::Esc if self.config_key_editable => self.config_key_editable = false,
-                KeyCode::Esc if self.config_result.is_some() => self.config_result = None,
-                KeyCode::Esc => self.dm_screen_move_back(),
-                KeyCode::Enter if self.config_key_editable => self.config_key_editable = false,
-                KeyCode::Char('s') => {
-                    if let Some(Ok(s)) = self.config_result.as_ref() {
-                        match with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.send_configure(s)) {
-                            Ok(()) => self.dm_screen_move_back(),
-                            Err(_) => {
-                                self.app_error = Some("Failed to send configuration!".to_owned())
-                            }
+            }
+            AzuriteJobResult::Uploaded {
+                purpose,
+                uploads,
+                conflicts,
+                modules,
+            } => {
+                if purpose != UploadPurpose::OtaFirmware {
+                    for (path, result) in uploads.iter() {
+                        if let Ok(blob) = result {
+                            self.remember_package_metadata(path, blob);
                         }
                     }
                 }
-                KeyCode::Up | KeyCode::Char('k') => self.config_focus_up(),
-                KeyCode::Down | KeyCode::Char('j') => self.config_focus_down(),
-                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                KeyCode::Tab => self.config_focus_down(),
-                KeyCode::Char('i') | KeyCode::Char('a') => {
-                    let current_config_key = ConfigKey::from(self.config_key_focus);
-                    if Self::is_log_storage_config_key(current_config_key) {
-                        self.token_provider_for_config = Some(current_config_key);
-                        self.switch_to_token_provider_screen();
-                    } else {
-                        self.config_key_editable = true;
-                    }
+                if !conflicts.is_empty() {
+                    self.ask_overwrite(purpose.clone(), conflicts);
                 }
-                //Previous screen is used to judge what to be configured.
-                KeyCode::Char('w') => match with_mqtt_ctrl(|mqtt_ctrl| {
-                    mqtt_ctrl.parse_configure(Some(&self.config_keys), self.main_window_focus())
-                }) {
-                    Ok(s) => {
-                        if !s.is_empty() {
-                            self.config_result = Some(Ok(s));
-                        }
-                    }
-                    Err(e) => {
-                        self.config_result = Some(Err(e));
-                    }
-                },
-                _ => {}
-            },
+                let found = modules.as_ref().ok().cloned();
+                self.set_azurite_modules(modules);
 
-            DMScreen::DirectCommand => {
-                let command = with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.get_direct_command());
-                match command {
-                    Some(DirectCommand::GetDirectImage) => {
-                        let has_request = with_mqtt_ctrl(|mqtt_ctrl| {
-                            mqtt_ctrl.direct_command_request().is_some()
+                match purpose {
+                    UploadPurpose::Module => {
+                        with_azurite_storage_mut(|azurite_storage| {
+                            azurite_storage.new_module_mut().clear()
                         });
-
-                        if !has_request {
-                            match key_event.code {
-                                KeyCode::Char(c) if self.config_key_editable => {
-                                    let value: &mut String =
-                                        self.config_keys.get_mut(self.config_key_focus).unwrap();
-                                    value.push(c);
-                                }
-                                KeyCode::Backspace if self.config_key_editable => {
-                                    let value: &mut String =
-                                        self.config_keys.get_mut(self.config_key_focus).unwrap();
-                                    value.pop();
-                                }
-                                KeyCode::Esc if self.config_key_editable => {
-                                    self.config_key_editable = false
-                                }
-                                KeyCode::Esc => self.dm_screen_move_back(),
-                                KeyCode::Enter if self.config_key_editable => {
-                                    self.config_key_editable = false
-                                }
-                                KeyCode::Tab => self.config_focus_down(),
-                                KeyCode::Char('i') | KeyCode::Char('a') => {
-                                    self.config_key_editable = true
-                                }
-                                KeyCode::Up | KeyCode::Char('k') => self.config_focus_up(),
-                                KeyCode::Down | KeyCode::Char('j') => self.config_focus_down(),
-                                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                                KeyCode::Char('s') => with_mqtt_ctrl_mut(|mqtt_ctrl| {
-                                    let _ = mqtt_ctrl.send_rpc_direct_get_image(&self.config_keys);
-                                }),
-
-                                _ => {}
-                            }
-                        } else {
-                            match key_event.code {
-                                KeyCode::Esc => self.dm_screen_move_back(),
-                                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                                KeyCode::Char('w') => match with_mqtt_ctrl_mut(|mqtt_ctrl| {
-                                    mqtt_ctrl.save_direct_get_image()
-                                }) {
-                                    Ok(image_path) => {
-                                        with_mqtt_ctrl_mut(|mqtt_ctrl| {
-                                            mqtt_ctrl.info =
-                                                Some(format!("Image saved to: {}", image_path))
-                                        });
-                                    }
-                                    Err(e) => {
-                                        self.app_error = Some(format!(
-                                            "Failed to save preview image: {}",
-                                            e.error_str().unwrap_or("Unknown error".to_owned())
-                                        ));
-                                    }
-                                },
-                                _ => {}
-                            }
+                        if let Some((_, Err(e))) = uploads.first() {
+                            self.push_failure(ErrorSource::Azurite, "Failed to add new module", e);
                         }
                     }
-                    None => {
-                        jdebug!(
-                            func = "App::handle_key_event()",
-                            screen = "DirectCommand",
-                            line = line!()
-                        );
-                        match key_event.code {
-                            KeyCode::Char('r') => {
-                                jdebug!(func = "App::handle_key_event()", event = "Set Reboot",);
-                                with_mqtt_ctrl_mut(|ctrl| {
-                                    ctrl.set_direct_command(Some(DirectCommand::Reboot))
-                                })
-                            }
-                            KeyCode::Char('i') => {
-                                jdebug!(
-                                    func = "App::handle_key_event()",
-                                    event = "Set DirectGetImage",
-                                );
-                                with_mqtt_ctrl_mut(|ctrl| {
-                                    ctrl.set_direct_command(Some(DirectCommand::GetDirectImage))
-                                });
-                                self.config_key_focus_start =
-                                    ConfigKey::DirectGetImageSensorName.into();
-                                self.config_key_focus_end =
-                                    ConfigKey::DirectGetImageNetworkId.into();
-                                self.config_key_focus = self.config_key_focus_start;
-                            }
-                            KeyCode::Char('f') => {
-                                jdebug!(
-                                    func = "App::handle_key_event()",
-                                    event = "Set FactoryReset",
-                                );
-                                with_mqtt_ctrl_mut(|ctrl| {
-                                    ctrl.set_direct_command(Some(DirectCommand::FactoryReset))
-                                });
-                            }
-                            KeyCode::Esc => self.dm_screen_move_back(),
-                            KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-
-                            _ => {}
+                    UploadPurpose::Batch if uploads.is_empty() => {}
+                    UploadPurpose::Batch => {
+                        let mut summary = ui::ui_deploy::BatchSummary::new("Upload");
+                        for (path, result) in uploads.iter() {
+                            let name = std::path::Path::new(path)
+                                .file_name()
+                                .map(|n| n.to_string_lossy().to_string())
+                                .unwrap_or(path.clone());
+                            summary.push(
+                                &name,
+                                result.as_ref().err().map(|e| {
+                                    e.error_str().unwrap_or(tr("Unknown error").to_owned())
+                                }),
+                            );
+                        }
+                        if let Some(screen) = self.screen_mut::<screens::EvpModuleScreen>() {
+                            screen.batch_summary = Some(summary);
                         }
                     }
-                    _ => match key_event.code {
-                        KeyCode::Esc => self.dm_screen_move_back(),
-                        KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                        _ => {}
-                    },
+                    UploadPurpose::OtaFirmware => {
+                        self.ota_wizard_uploaded(uploads, found.unwrap_or_default())
+                    }
                 }
             }
+        }
+    }
 
-            DMScreen::Elog => match key_event.code {
-                KeyCode::Esc => self.dm_screen_move_back(),
-                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-
-                KeyCode::Char('w') => {
-                    match with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.save_elogs()) {
-                        Ok(elog_path) => {
-                            with_mqtt_ctrl_mut(|mqtt_ctrl| {
-                                mqtt_ctrl.info = Some(format!("Elog saved to: {}", elog_path))
-                            });
-                        }
-                        Err(e) => {
-                            self.app_error =
-                                Some(e.error_str().unwrap_or("Unknown error".to_owned()));
-                        }
-                    }
+    /// Moves to the blobs or log files screen once the first page of the blobs of the
+    /// focused token provider is listed, unless another screen has been opened meanwhile.
+    /// The next pages are added to the list of the screen.
+    fn show_blobs(
+        &mut self,
+        container: String,
+        log_files: bool,
+        marker: Option<String>,
+        result: Result<BlobPage, DMError>,
+    ) {
+        let page = match result {
+            Ok(page) => page,
+            Err(e) => {
+                if let Some(state) = self.listed_blobs(&container, log_files, &marker) {
+                    state.loading = false;
                 }
-                _ => {}
-            },
+                let what = if log_files { "log files" } else { "blobs" };
+                return self.push_failure(
+                    ErrorSource::Azurite,
+                    &trf("Failed to list {0}", &[&tr(what)]),
+                    &e,
+                );
+            }
+        };
 
-            DMScreen::EvpModule => match key_event.code {
-                KeyCode::Char(c)
-                    if with_azurite_storage(|storage| {
-                        storage.action() == Some(AzuriteAction::Add)
-                    })
-                    .unwrap_or(false) =>
-                {
-                    with_azurite_storage_mut(|azurite_storage| {
-                        azurite_storage.new_module_mut().push(c);
-                    });
-                }
+        let blobs: Vec<UiBlob> = if log_files {
+            page.blobs
+                .into_iter()
+                .filter(|b| ui::ui_log_files::is_log_blob(&b.name))
+                .collect()
+        } else {
+            page.blobs
+        };
 
-                KeyCode::Esc
-                    if with_azurite_storage(|storage| {
-                        storage.action() == Some(AzuriteAction::Add)
-                    })
-                    .unwrap_or(false) =>
-                {
-                    with_azurite_storage_mut(|azurite_storage| {
-                        azurite_storage.pop_action();
-                        azurite_storage.new_module_mut().clear();
-                    });
-                }
+        let job = if let Some(state) = self.listed_blobs(&container, log_files, &marker) {
+            state.append(blobs, page.next_marker);
+            Self::more_blobs_job(state, log_files)
+        } else if marker.is_none() && self.current_screen() == DMScreen::TokenProvider {
+            let mut state = BlobListState::new(container);
+            state.append(blobs, page.next_marker);
+            let job = Self::more_blobs_job(&mut state, log_files);
+            if log_files {
+                self.dm_screen_move_to(screens::LogFilesScreen::new(state));
+            } else {
+                self.dm_screen_move_to(screens::TokenProviderBlobsScreen::new(state));
+            }
+            job
+        } else {
+            return;
+        };
 
-                KeyCode::Backspace
-                    if with_azurite_storage(|storage| {
-                        storage.action() == Some(AzuriteAction::Add)
-                    })
-                    .unwrap_or(false) =>
-                {
-                    with_azurite_storage_mut(|azurite_storage| {
-                        azurite_storage.new_module_mut().pop();
-                    });
+        // Pages without log files leave the selection at the end
+        if let Some(job) = job {
+            self.submit_azurite_job(job);
+        }
+    }
+
+    /// List of the blobs or log files screen which the page after the marker continues
+    fn listed_blobs(
+        &mut self,
+        container: &str,
+        log_files: bool,
+        marker: &Option<String>,
+    ) -> Option<&mut BlobListState> {
+        marker.as_ref()?;
+        let state = if log_files {
+            self.screen_mut::<screens::LogFilesScreen>()?.state_mut()
+        } else {
+            self.screen_mut::<screens::TokenProviderBlobsScreen>()?
+                .state_mut()
+        };
+        Some(state)
+            .filter(|state| state.container_name == container && state.next_marker == *marker)
+    }
+
+    /// Lists the next page of the blobs or log files screen once the selection nears the
+    /// end of the listed blobs
+    pub fn list_more_blobs(&mut self, state: &mut BlobListState, log_files: bool) {
+        if let Some(job) = Self::more_blobs_job(state, log_files) {
+            self.submit_azurite_job(job);
+        }
+    }
+
+    fn more_blobs_job(state: &mut BlobListState, log_files: bool) -> Option<AzuriteJob> {
+        let marker = state.next_page()?.to_owned();
+        state.loading = true;
+        Some(AzuriteJob::ListBlobs {
+            container: state.container_name.clone(),
+            log_files,
+            marker: Some(marker),
+        })
+    }
+
+    /// IDs of the deployed instances, in the order listed by the instance selector
+    fn edge_app_instance_ids() -> Vec<String> {
+        with_mqtt_ctrl(|mqtt_ctrl| {
+            mqtt_ctrl
+                .deployment_status()
+                .map(|status| {
+                    status
+                        .sorted_instances()
+                        .iter()
+                        .map(|(id, _)| id.uuid().to_owned())
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn switch_to_edge_app_screen(&mut self) {
+        let instances = Self::edge_app_instance_ids();
+        match instances.len() {
+            0 => self.push_warning(ErrorSource::Mqtt, "No Edge App instances found."),
+            1 => {
+                self.edge_app_instance = instances.into_iter().next();
+                self.dm_screen_move_to(screens::EdgeAppScreen(DMScreenState::Initial))
+            }
+            _ => {
+                // Start from the instance selected last time
+                let focus = self
+                    .edge_app_instance
+                    .as_ref()
+                    .and_then(|id| instances.iter().position(|i| i == id))
+                    .unwrap_or(0);
+                self.dm_screen_move_to(screens::EdgeAppInstancesScreen::new(focus))
+            }
+        }
+    }
+
+    pub fn switch_to_elog_screen(&mut self) {
+        if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
+            self.dm_screen_move_to(screens::ElogScreen::default());
+        } else {
+            self.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+        }
+    }
+
+    pub fn switch_to_direct_command_screen(&mut self) {
+        if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
+            self.config_key_clear();
+            with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.direct_command_clear());
+            self.dm_screen_move_to(screens::DirectCommandScreen);
+        } else {
+            self.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+        }
+    }
+
+    pub fn switch_to_config_screen(&mut self, user_config: bool) {
+        if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
+            self.config_key_clear();
+            if user_config {
+                match self.main_window_focus {
+                    MainWindowFocus::MainChip
+                    | MainWindowFocus::SensorChip
+                    | MainWindowFocus::CompanionChip
+                    | MainWindowFocus::DeploymentStatus
+                    | MainWindowFocus::SystemSettings
+                    | MainWindowFocus::NetworkSettings
+                    | MainWindowFocus::WirelessSettings => {
+                        self.dm_screen_move_to(screens::ConfigurationUserScreen);
+                    }
+                    _ => {}
+                }
+            } else {
+                match self.main_window_focus {
+                    MainWindowFocus::AgentState => {
+                        self.config_key_focus_start = ConfigKey::ReportStatusIntervalMin.into();
+                        self.config_key_focus_end = ConfigKey::ReportStatusIntervalMax.into();
+                        self.config_key_focus = self.config_key_focus_start;
+                        self.dm_screen_move_to(screens::ConfigurationScreen::default());
+                    }
+                    MainWindowFocus::SystemSettings => {
+                        self.config_key_focus_start = ConfigKey::LedEnabled.into();
+                        self.config_key_focus_end = ConfigKey::CompanionAppLogSettingPath.into();
+                        self.config_key_focus = self.config_key_focus_start;
+                        self.dm_screen_move_to(screens::ConfigurationScreen::default());
+                    }
+                    MainWindowFocus::NetworkSettings => {
+                        self.config_key_focus_start = ConfigKey::IpMethod.into();
+                        self.config_key_focus_end = ConfigKey::ProxyPassword.into();
+                        self.config_key_focus = self.config_key_focus_start;
+                        self.dm_screen_move_to(screens::ConfigurationScreen::default());
+                    }
+                    MainWindowFocus::WirelessSettings => {
+                        self.config_key_focus_start = ConfigKey::StaSsid.into();
+                        self.config_key_focus_end = ConfigKey::StaEncryption.into();
+                        self.config_key_focus = self.config_key_focus_start;
+                        self.dm_screen_move_to(screens::ConfigurationScreen::default());
+                    }
+                    _ => {}
                 }
+            }
+        } else {
+            self.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+        }
+    }
+
+    fn switch_to_ota_config_screen(&mut self, state: DMScreenState) {
+        if state == DMScreenState::Initial {
+            self.config_key_clear();
+            self.config_key_focus_start = ConfigKey::OtaVersion.into();
+            self.config_key_focus_end = ConfigKey::OtaSensorChipFirmwareSize.into();
+            self.config_key_focus = self.config_key_focus_start;
+        }
+        self.dm_screen_move_to(screens::OtaConfigScreen(state));
+    }
+
+    /// Parses the local package uploaded to Azurite so that OTA fields can be auto-filled
+    /// Parses the package uploaded as the blob, its metadata is kept by blob name
+    fn remember_package_metadata(&mut self, file_path: &str, blob: &str) -> PackageMetadata {
+        let metadata = parse_package(file_path).unwrap_or_else(|e| {
+            jerror!(
+                func = "App::remember_package_metadata()",
+                line = line!(),
+                error = format!("{:?}", e)
+            );
+            PackageMetadata::default()
+        });
+
+        self.package_metadata
+            .insert(blob.to_owned(), metadata.clone());
+
+        metadata
+    }
+
+    /// Uploads the local module file selected in the file browser to Azurite.
+    /// The browser is closed at once, the list is updated when the upload completes.
+    fn add_module(&mut self, path: &std::path::Path) {
+        let new_module_path = path.to_string_lossy().to_string();
+        with_azurite_storage_mut(|azurite_storage| {
+            *azurite_storage.new_module_mut() = new_module_path.clone();
+            azurite_storage.pop_action();
+        });
+        self.submit_upload(vec![new_module_path], UploadPurpose::Module, HashMap::new());
+    }
+
+    /// Uploads all the files marked in the file browser, the result of each of them is shown
+    /// when the uploads complete
+    fn add_marked_modules(&mut self, paths: Vec<std::path::PathBuf>) {
+        with_azurite_storage_mut(|azurite_storage| azurite_storage.pop_action());
+        self.submit_upload(
+            paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            UploadPurpose::Batch,
+            HashMap::new(),
+        );
+    }
+
+    /// Runs the destructive action the user confirmed
+    fn run_confirmed(&mut self, action: ConfirmAction) {
+        match action {
+            ConfirmAction::FactoryReset => {
+                jdebug!(func = "App::run_confirmed()", event = "Set FactoryReset");
+                with_mqtt_ctrl_mut(|ctrl| {
+                    ctrl.set_direct_command(Some(DirectCommand::FactoryReset))
+                });
+            }
+            ConfirmAction::RemoveModule(module_name) => {
+                self.submit_remove_modules(vec![module_name], false)
+            }
+            ConfirmAction::RemoveMarkedModules => self.remove_marked_modules(),
+            ConfirmAction::RemoveTokenProvider(uuid) => {
+                if let Some(Some(container)) = with_azurite_storage(|azurite_storage| {
+                    azurite_storage
+                        .token_providers()
+                        .get(&uuid)
+                        .map(|tp| tp.container.clone())
+                }) {
+                    self.submit_azurite_job(AzuriteJob::RemoveTokenProvider { uuid, container });
+                }
+            }
+            ConfirmAction::Undeploy(deploy) => self.send_deployment(&deploy),
+            ConfirmAction::OverwriteBlobs(purpose, conflicts) => {
+                let overwrite = conflicts
+                    .into_iter()
+                    .map(|c| (c.path, OverwritePolicy::IfMatch(c.etag)))
+                    .collect::<HashMap<_, _>>();
+                self.submit_upload(overwrite.keys().cloned().collect(), purpose, overwrite);
+            }
+            ConfirmAction::RenameBlobs(purpose, conflicts) => {
+                let overwrite = conflicts
+                    .into_iter()
+                    .map(|c| (c.path, OverwritePolicy::Rename))
+                    .collect::<HashMap<_, _>>();
+                self.submit_upload(overwrite.keys().cloned().collect(), purpose, overwrite);
+            }
+        }
+    }
+
+    /// Asks whether the blobs of the same names as uploaded files are replaced, the files
+    /// renamed or the uploads aborted
+    fn ask_overwrite(&mut self, purpose: UploadPurpose, conflicts: Vec<BlobConflict>) {
+        let message = match conflicts.as_slice() {
+            [conflict] => trf(
+                "{0} already exists in the storage, overwrite it?",
+                &[&conflict.blob],
+            ),
+            _ => trf(
+                "{0} files already exist in the storage ({1}), overwrite them?",
+                &[
+                    &conflicts.len(),
+                    &conflicts
+                        .iter()
+                        .map(|c| c.blob.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ],
+            ),
+        };
+
+        self.confirmation = Some(
+            Confirmation::new(
+                ConfirmAction::OverwriteBlobs(purpose.clone(), conflicts.clone()),
+                message,
+            )
+            .with_alternative(
+                'r',
+                "upload with a new name",
+                ConfirmAction::RenameBlobs(purpose, conflicts),
+            ),
+        );
+    }
+
+    fn send_deployment(&mut self, deploy: &str) {
+        match with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.send_configure(deploy)) {
+            Ok(()) => {
+                self.deployed_module_hashes
+                    .extend(deployment_module_hashes(deploy));
+                self.dm_screen_move_back();
+            }
+            Err(_) => {
+                self.push_error(ErrorSource::Mqtt, "Failed to send deployment.");
+            }
+        }
+    }
+
+    /// Removes module blobs in the background, the result of each of them is shown for the
+    /// modules marked in the module list
+    fn submit_remove_modules(&mut self, names: Vec<String>, batch: bool) {
+        let Some(modules) =
+            with_azurite_storage(|azurite_storage| azurite_storage.module_info_db().clone())
+        else {
+            self.push_error(ErrorSource::Azurite, "Azurite storage is not available");
+            return;
+        };
+
+        self.submit_azurite_job(AzuriteJob::RemoveModules {
+            names,
+            modules,
+            batch,
+        });
+    }
+
+    fn remove_marked_modules(&mut self) {
+        let Some(screen) = self.screen_mut::<screens::EvpModuleScreen>() else {
+            return;
+        };
+        let marked = std::mem::take(&mut screen.marked);
+        self.submit_remove_modules(marked.into_iter().collect(), true);
+    }
+
+    fn switch_to_dtmi_screen(&mut self) {
+        self.config_key_clear();
+        self.config_keys[usize::from(ConfigKey::DtmiRepository)] = self.dtmi_repository.clone();
+        self.config_key_focus_start = ConfigKey::DtmiRepository.into();
+        self.config_key_focus_end = ConfigKey::DtmiRepository.into();
+        self.config_key_focus = self.config_key_focus_start;
+        let screen = screens::DtmiScreen::open(self);
+        self.dm_screen_move_to(screen);
+    }
+
+    fn switch_to_inventory_screen(&mut self) {
+        self.config_key_clear();
+        self.config_keys[usize::from(ConfigKey::InventoryFile)] = self.inventory_file.clone();
+        self.config_key_focus_start = ConfigKey::InventoryFile.into();
+        self.config_key_focus_end = ConfigKey::InventoryFile.into();
+        self.config_key_focus = self.config_key_focus_start;
+        let screen = screens::InventoryScreen::open(self);
+        self.dm_screen_move_to(screen);
+    }
+
+    pub fn switch_to_log_settings_screen(&mut self) {
+        if !with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
+            self.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+            return;
+        }
+
+        self.config_key_clear();
+        self.config_key_focus_start = ConfigKey::AllLogSettingLevel.into();
+        self.config_key_focus_end = ConfigKey::CompanionAppLogSettingPath.into();
+        self.config_key_focus = self.config_key_focus_start;
+
+        // The current values are prefilled so that only the cells to change need editing
+        let current = with_mqtt_ctrl(|mqtt_ctrl| {
+            mqtt_ctrl
+                .system_settings()
+                .and_then(|s| s.log_settings())
+                .map(|log_settings| {
+                    log_settings
+                        .iter()
+                        .filter_map(|l| {
+                            let row = ui::ui_log_settings::LOG_FILTERS
+                                .iter()
+                                .position(|f| *f == l.filter())?;
+                            Some((
+                                row,
+                                [
+                                    l.level().to_string(),
+                                    l.destination().to_string(),
+                                    l.storage_name().to_owned(),
+                                    l.path().to_owned(),
+                                ],
+                            ))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        });
+        for (row, values) in current {
+            for (col, value) in values.into_iter().enumerate() {
+                self.config_keys[self.config_key_focus_start + row * 4 + col] = value;
+            }
+        }
+
+        self.dm_screen_move_to(screens::LogSettingsScreen::default());
+    }
+
+    /// Moves the focused cell of the Log Settings table, wrapping around rows and columns
+    fn log_settings_move(&mut self, rows: isize, cols: isize) {
+        let cell = self.config_key_focus - self.config_key_focus_start;
+        let row = (cell / 4) as isize + rows;
+        let col = (cell % 4) as isize + cols;
+        let filters = ui::ui_log_settings::LOG_FILTERS.len() as isize;
+        self.config_key_focus = self.config_key_focus_start
+            + (row.rem_euclid(filters) * 4 + col.rem_euclid(4)) as usize;
+    }
+
+    fn preview_log_settings(&mut self) {
+        match with_mqtt_ctrl(|mqtt_ctrl| {
+            mqtt_ctrl.parse_configure(
+                Some(&self.dirty_config_keys()),
+                MainWindowFocus::SystemSettings,
+            )
+        }) {
+            Ok(s) => {
+                if !s.is_empty() {
+                    self.preview_configuration(s);
+                }
+            }
+            Err(e) => self.config_result = Some(Err(e)),
+        }
+    }
+
+    fn switch_to_compare_screen(&mut self) {
+        self.config_key_clear();
+        self.config_keys[usize::from(ConfigKey::CompareFile)] = self.compare_file.clone();
+        self.config_key_focus_start = ConfigKey::CompareFile.into();
+        self.config_key_focus_end = ConfigKey::CompareFile.into();
+        self.config_key_focus = self.config_key_focus_start;
+        let screen = screens::CompareScreen::open(self);
+        self.dm_screen_move_to(screen);
+    }
+
+    fn switch_to_fleet_screen(&mut self) {
+        self.config_key_clear();
+        self.config_keys[usize::from(ConfigKey::FleetDir)] = self.fleet_dir.clone();
+        self.config_key_focus_start = ConfigKey::FleetDir.into();
+        self.config_key_focus_end = ConfigKey::FleetDir.into();
+        self.config_key_focus = self.config_key_focus_start;
+        let screen = screens::FleetScreen::open(self);
+        self.dm_screen_move_to(screen);
+    }
+
+    fn switch_to_debug_log_screen(&mut self) {
+        self.dm_screen_move_to(screens::DebugLogScreen);
+        self.update_debug_log();
+    }
+
+    pub fn debug_log(&self) -> Option<&DebugLog> {
+        self.debug_log.as_ref()
+    }
+
+    /// Reads the lines appended to the log file, only while they are displayed
+    fn update_debug_log(&mut self) {
+        if self.current_screen() == DMScreen::DebugLog
+            && let Some(debug_log) = self.debug_log.as_mut()
+        {
+            debug_log.refresh();
+        }
+    }
+
+    fn switch_to_retained_screen(&mut self) {
+        self.dm_screen_move_to(screens::RetainedScreen::open());
+    }
+
+    fn notes_file() -> String {
+        format!("{}/{}", Self::config_dir(), NOTES_FILE)
+    }
+
+    /// Labels and notes of the connected device
+    pub fn device_note(&self) -> Option<&DeviceNote> {
+        self.device_notes.get(self.device_key.as_deref()?)
+    }
+
+    fn update_device_key(&mut self) {
+        self.device_key = with_mqtt_ctrl(|mqtt_ctrl| notes::device_key(mqtt_ctrl.device_info()));
+    }
+
+    fn open_notes_editor(&mut self) {
+        let Some(key) = self.device_key.as_deref() else {
+            with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                mqtt_ctrl.info = Some(tr("The device has not reported its identity yet").to_owned())
+            });
+            return;
+        };
+        self.notes_editor = Some(NotesEditor::new(key, self.device_notes.get(key)));
+    }
+
+    /// Saves the notes of the editor, the popup stays open if they cannot be written
+    fn save_notes(&mut self) {
+        let Some(editor) = self.notes_editor.as_ref() else {
+            return;
+        };
+
+        let mut device_notes = self.device_notes.clone();
+        device_notes.set(&editor.key, editor.to_note());
+        if let Err(e) = device_notes.save(&Self::notes_file()) {
+            self.push_failure(ErrorSource::File, "Failed to save the device notes", &e);
+            return;
+        }
+        self.device_notes = device_notes;
+        self.notes_editor = None;
+        with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.info = Some(tr("Device notes saved").to_owned()));
+    }
+
+    fn open_search(&mut self) {
+        let entries = with_mqtt_ctrl(ui::ui_search::collect_entries);
+        self.search = Some(ui::ui_search::SearchState::new(entries));
+    }
+
+    fn open_deployment_detail(&mut self) {
+        let raw = with_mqtt_ctrl(|mqtt_ctrl| {
+            mqtt_ctrl
+                .deployment_status()
+                .map(|status| status.raw().to_owned())
+        });
+        self.deployment_detail = Some(
+            raw.map(|raw| ui::json_tree::JsonTree::new(ui::json_tree::parse_json(&raw)))
+                .unwrap_or_default(),
+        );
+    }
+
+    fn open_pane_detail(&mut self) {
+        let focus = self.main_window_focus;
+        let value = with_mqtt_ctrl(|mqtt_ctrl| ui::ui_search::pane_json(mqtt_ctrl, focus));
+        self.pane_detail = Some(ui::ui_pane_detail::PaneDetail::new(
+            focus,
+            value,
+            self.secrets_revealed(focus),
+        ));
+    }
+
+    fn switch_to_scripts_screen(&mut self) {
+        let browser = match self.script_browser.take() {
+            Some(browser) => Ok(browser),
+            None => ui::ui_file_browser::FileBrowserState::from_current_dir(),
+        };
+
+        let browser = browser.and_then(|mut browser| {
+            // Reread the directory so that newly written scripts are listed
+            browser.filter = SCRIPT_EXTENSION.to_owned();
+            browser.refresh().map(|_| browser)
+        });
+
+        match browser {
+            Ok(browser) => {
+                self.script_browser = Some(browser);
+                self.dm_screen_move_to(screens::ScriptsScreen);
+            }
+            Err(e) => self.push_report(ErrorSource::File, &e),
+        }
+    }
+
+    fn run_script(&mut self, path: &std::path::Path) {
+        if self
+            .script_runner
+            .as_ref()
+            .is_some_and(|r| r.outcome().is_none())
+        {
+            self.push_warning(
+                ErrorSource::Script,
+                "A script is running, (c) to cancel it.",
+            );
+            return;
+        }
+
+        match Script::load(path) {
+            Ok(script) => self.script_runner = Some(ScriptRunner::new(script)),
+            Err(e) => self.push_report(ErrorSource::Script, &e),
+        }
+    }
+
+    /// Runs the steps of the script until one has to wait
+    fn update_script(&mut self) {
+        if let Some(runner) = self.script_runner.as_mut()
+            && runner.outcome().is_none()
+        {
+            with_mqtt_ctrl_mut(|mqtt_ctrl| runner.step(mqtt_ctrl, Instant::now()));
+        }
+    }
+
+    fn handle_file_browser_key(&mut self, code: KeyCode) {
+        let Some(browser) = self.file_browser.as_mut() else {
+            return;
+        };
+
+        if browser.filter_editing {
+            match code {
+                KeyCode::Enter | KeyCode::Esc => browser.filter_editing = false,
+                KeyCode::Backspace => {
+                    browser.filter.pop();
+                }
+                KeyCode::Char(c) => browser.filter.push(c),
+                _ => return,
+            }
+
+            if let Err(e) = browser.refresh() {
+                self.push_report(ErrorSource::File, &e);
+            }
+            return;
+        }
+
+        if code == KeyCode::Enter && !browser.marked.is_empty() {
+            let paths = std::mem::take(&mut browser.marked).into_iter().collect();
+            self.add_marked_modules(paths);
+            return;
+        }
+
+        let result = match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                browser.move_up();
+                Ok(None)
+            }
+            KeyCode::Char(' ') => {
+                browser.toggle_mark();
+                Ok(None)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                browser.move_down();
+                Ok(None)
+            }
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => {
+                browser.parent().map(|_| None)
+            }
+            KeyCode::Char('.') => browser.toggle_hidden().map(|_| None),
+            KeyCode::Char('/') => {
+                browser.filter_editing = true;
+                Ok(None)
+            }
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => browser.enter(),
+            KeyCode::Esc => {
+                with_azurite_storage_mut(|azurite_storage| {
+                    azurite_storage.pop_action();
+                });
+                Ok(None)
+            }
+            _ => Ok(None),
+        };
+
+        self.errors.dismiss();
+        match result {
+            Ok(Some(path)) => self.add_module(&path),
+            Ok(None) => {}
+            Err(e) => {
+                self.push_report(ErrorSource::File, &e);
+            }
+        }
+    }
+
+    /// Uploads the firmware file selected in the OTA wizard and auto-fills its values
+    fn ota_wizard_upload(&mut self) {
+        let Some(file_name) = self.ota_wizard.file_name().map(|s| s.to_owned()) else {
+            self.push_warning(ErrorSource::App, "Please input the firmware file path.");
+            return;
+        };
+        let file_path = self.ota_wizard.file_path.trim().to_owned();
+
+        jdebug!(func = "App::ota_wizard_upload()", file = file_name);
+        self.submit_upload(vec![file_path], UploadPurpose::OtaFirmware, HashMap::new());
+    }
+
+    /// Auto-fills the OTA wizard once the firmware upload completes
+    fn ota_wizard_uploaded(
+        &mut self,
+        uploads: Vec<(String, Result<String, DMError>)>,
+        modules: HashMap<UUID, ModuleInfo>,
+    ) {
+        let Some((file_path, result)) = uploads.into_iter().next() else {
+            return;
+        };
+
+        // Another file may have been selected meanwhile
+        if self.ota_wizard.file_path.trim() != file_path {
+            return;
+        }
+
+        let file_name = match result {
+            Ok(blob) => blob,
+            Err(e) => {
+                self.push_failure(ErrorSource::Azurite, "Failed to upload firmware", &e);
+                return;
+            }
+        };
+
+        match modules.into_values().find(|m| m.blob_name == file_name) {
+            Some(module) => {
+                let metadata = self.remember_package_metadata(&file_path, &file_name);
+                self.ota_wizard.set_uploaded_module(module, metadata);
+            }
+            None => {
+                self.push_error(
+                    ErrorSource::Azurite,
+                    trf("Uploaded firmware {0} not found", &[&file_name]),
+                );
+            }
+        }
+    }
+
+    /// Generates the OTA configuration from the values collected by the OTA wizard
+    fn ota_wizard_generate(&mut self) {
+        self.config_key_clear();
+
+        match self
+            .ota_wizard
+            .apply_to_config_keys(&mut self.config_keys)
+            .and_then(|_| parse_ota_configuration(&self.config_keys))
+        {
+            Ok(payload) => self.ota_wizard.set_payload(payload),
+            Err(e) => {
+                self.push_failure(ErrorSource::App, "Failed to generate OTA configuration", &e);
+            }
+        }
+    }
+
+    fn ota_wizard_send(&mut self) {
+        let Some(payload) = self.ota_wizard.payload.clone() else {
+            return;
+        };
+
+        if let Some(module) = self.ota_wizard.module.as_ref()
+            && let Some(sas) = SasInfo::parse(&module.sas_url)
+            && sas.is_expired(Utc::now())
+        {
+            let text = format!(
+                "SAS URL of the OTA package expired {}",
+                sas.countdown(Utc::now())
+            );
+            self.push_warning(ErrorSource::Azurite, text);
+        }
+
+        let snapshot = Self::ota_snapshot_for(&payload);
+        match with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.send_configure(&payload)) {
+            Ok(_) => {
+                self.ota_wizard.step = OtaWizardStep::Progress;
+                self.ota_snapshot = snapshot;
+
+                if let Some(id) = self.ota_job.take() {
+                    self.jobs
+                        .finish(id, Some("Superseded by a new OTA".to_owned()));
+                }
+                let (chip_id, component) = self.ota_wizard.target();
+                self.ota_job = Some(
+                    self.jobs
+                        .track(&format!("OTA {}", target_name(chip_id, component))),
+                );
+            }
+            Err(e) => {
+                self.push_failure(ErrorSource::Mqtt, "Failed to send OTA configuration", &e);
+            }
+        }
+    }
+
+    /// Hashes of the modules running on the device according to DeploymentStatus
+    pub fn running_module_hashes(&self) -> Vec<String> {
+        let running_ids: Vec<UUID> = with_mqtt_ctrl(|mqtt_ctrl| {
+            mqtt_ctrl
+                .deployment_status()
+                .map(|d| {
+                    d.modules()
+                        .iter()
+                        .filter(|(_, m)| !is_error_status(m.status()))
+                        .map(|(id, _)| id.clone())
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+
+        let mut hashes: Vec<String> = running_ids
+            .iter()
+            .filter_map(|id| self.deployed_module_hashes.get(id).cloned())
+            .collect();
+
+        // Modules deployed from the module list keep their ID as long as the blob exists
+        hashes.extend(
+            with_azurite_storage(|azurite_storage| {
+                azurite_storage
+                    .module_info_db()
+                    .values()
+                    .filter(|m| running_ids.contains(&m.id))
+                    .map(|m| m.hash.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default(),
+        );
+
+        hashes
+    }
+
+    /// Records the state of the targets updated by the OTA configuration `config`
+    /// before it is sent
+    fn ota_snapshot_for(config: &str) -> Option<OtaSnapshot> {
+        let sent = firmware_of_configuration(config)?;
+
+        Some(with_mqtt_ctrl(|mqtt_ctrl| {
+            OtaSnapshot::new(&sent, mqtt_ctrl.device_info(), mqtt_ctrl.firmware())
+        }))
+    }
+
+    /// Generates an OTA configuration deploying the packages used before the last OTA
+    fn ota_rollback(&mut self) {
+        let Some(snapshot) = self.ota_snapshot.as_ref() else {
+            self.push_warning(ErrorSource::App, "No OTA has been sent yet.");
+            return;
+        };
+
+        let targets = snapshot.rollback_targets();
+        if targets.is_empty() {
+            self.push_warning(ErrorSource::App, "No previously deployed package recorded.");
+            return;
+        }
+
+        let version = format!("rollback-{}", snapshot.time.format("%Y%m%d%H%M%S"));
+        let account_name = azurite_config().account_name;
+        let mut missing = vec![];
+
+        self.config_key_clear();
+
+        for target in targets.iter() {
+            let (Some(chip_id), Some((container, blob))) = (
+                chip_id_of_name(&target.chip),
+                target
+                    .package_url
+                    .as_deref()
+                    .and_then(|url| blob_of_url(url, &account_name)),
+            ) else {
+                missing.push(target.chip.clone());
+                continue;
+            };
+
+            // The previous SAS URL has likely expired, so issue a new one if the package is
+            // still present
+            match with_azurite_storage(|azurite_storage| -> Result<Option<String>, DMError> {
+                if !azurite_storage
+                    .list_blobs(&container)?
+                    .iter()
+                    .any(|b| b.name == blob)
+                {
+                    return Ok(None);
+                }
+
+                azurite_storage
+                    .get_sas_url(&container, &blob, None, None)
+                    .map(Some)
+            }) {
+                Some(Ok(Some(sas_url))) => {
+                    let [chip_key, version_key, url_key, hash_key, size_key] =
+                        target_config_keys(chip_id, target.component);
+
+                    self.config_keys[usize::from(chip_key)] = target.chip.clone();
+                    self.config_keys[usize::from(version_key)] =
+                        target.version.clone().unwrap_or_default();
+                    self.config_keys[usize::from(url_key)] = sas_url;
+                    self.config_keys[usize::from(hash_key)] =
+                        target.hash.clone().unwrap_or_default();
+                    self.config_keys[usize::from(size_key)] =
+                        target.size.map(|s| s.to_string()).unwrap_or_default();
+                }
+                Some(Err(e)) => {
+                    self.push_failure(ErrorSource::Azurite, "Failed to find previous package", &e);
+                    return;
+                }
+                Some(Ok(None)) => missing.push(blob),
+                None => {
+                    self.push_error(ErrorSource::Azurite, "Azurite storage not available");
+                    return;
+                }
+            }
+        }
+
+        if missing.len() == targets.len() {
+            self.push_error(
+                ErrorSource::Azurite,
+                trf(
+                    "Previous packages not found in Azurite: {0}",
+                    &[&missing.join(", ")],
+                ),
+            );
+            return;
+        }
+
+        self.config_keys[usize::from(ConfigKey::OtaVersion)] = version;
+        self.config_key_focus_start = ConfigKey::OtaVersion.into();
+        self.config_key_focus_end = ConfigKey::OtaSensorChipFirmwareSize.into();
+        self.config_key_focus = self.config_key_focus_start;
+        self.config_result = Some(parse_ota_configuration(&self.config_keys));
+
+        // Esc on the result goes back to the editable config so that it can be adjusted
+        self.dm_screen_move_to(screens::OtaConfigScreen(DMScreenState::Initial));
+        self.dm_screen_move_to(screens::OtaConfigScreen(DMScreenState::Completed));
+
+        if !missing.is_empty() {
+            self.push_error(
+                ErrorSource::Azurite,
+                trf(
+                    "Previous packages not found in Azurite, skipped: {0}",
+                    &[&missing.join(", ")],
+                ),
+            );
+        }
+    }
+
+    fn switch_to_ai_model_config_screen(&mut self, state: DMScreenState) {
+        if state == DMScreenState::Initial {
+            self.config_key_clear();
+            self.config_key_focus_start = ConfigKey::AiModel0Chip.into();
+            self.config_key_focus_end = ConfigKey::AiModel3Size.into();
+            self.config_key_focus = self.config_key_focus_start;
+        }
+        self.dm_screen_move_to(screens::AiModelConfigScreen(state));
+    }
+
+    fn switch_to_storage_settings_screen(&mut self) {
+        let config = azurite_config();
+        self.config_key_clear();
+        self.config_keys[usize::from(ConfigKey::StorageSettingsUrl)] = config.url;
+        self.config_keys[usize::from(ConfigKey::StorageSettingsAccountName)] = config.account_name;
+        self.config_keys[usize::from(ConfigKey::StorageSettingsAccountKey)] = config.account_key;
+        self.config_key_focus_start = ConfigKey::StorageSettingsUrl.into();
+        self.config_key_focus_end = ConfigKey::StorageSettingsAccountKey.into();
+        self.config_key_focus = self.config_key_focus_start;
+        self.dm_screen_move_to(screens::StorageSettingsScreen);
+    }
+
+    /// Shows the configuration before sending it with the default publish options
+    fn preview_configuration(&mut self, config: String) {
+        self.config_result = Some(Ok(config));
+        self.send_options = Some(with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.publish_options()));
+    }
+
+    fn switch_to_publish_screen(&mut self) {
+        self.config_key_clear();
+        if let Some((topic, payload)) = self.publish_history.iter().next() {
+            self.config_keys[usize::from(ConfigKey::PublishTopic)] = topic.clone();
+            self.config_keys[usize::from(ConfigKey::PublishPayload)] = payload.clone();
+        }
+        self.config_key_focus_start = ConfigKey::PublishTopic.into();
+        self.config_key_focus_end = ConfigKey::PublishPayload.into();
+        self.config_key_focus = self.config_key_focus_start;
+        self.dm_screen_move_to(screens::PublishScreen);
+    }
+
+    /// Publishes the message of the console, payloads which are not JSON only when forced
+    fn publish_message(&mut self, force: bool) {
+        let topic = self.config_keys[usize::from(ConfigKey::PublishTopic)]
+            .trim()
+            .to_owned();
+        let payload = self.config_keys[usize::from(ConfigKey::PublishPayload)].clone();
+
+        let result = if topic.is_empty() {
+            Err(Report::new(DMError::InvalidData).attach_printable("Topic is empty"))
+        } else if let (false, Err(e)) = (force, ui::ui_publish::validate_payload(&payload)) {
+            Err(Report::new(DMError::InvalidData).attach_printable(format!(
+                "Invalid JSON payload, (W) to publish anyway: {}",
+                e
+            )))
+        } else {
+            with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.publish(&topic, &payload))
+                .map(|_| format!("Published {} bytes to {}", payload.len(), topic))
+        };
+
+        if result.is_ok() {
+            self.publish_history.push(&topic, &payload);
+        }
+        self.config_result = Some(result);
+    }
+
+    fn recall_published(&mut self, older: bool) {
+        let entry = if older {
+            self.publish_history.older()
+        } else {
+            self.publish_history.newer()
+        };
+
+        if let Some((topic, payload)) = entry.cloned() {
+            self.config_keys[usize::from(ConfigKey::PublishTopic)] = topic;
+            self.config_keys[usize::from(ConfigKey::PublishPayload)] = payload;
+            self.config_result = None;
+        }
+    }
+
+    /// Checks the edited storage settings in the background, they are applied once the new
+    /// endpoint answers
+    fn apply_storage_settings(&mut self) {
+        let value = |key: ConfigKey| self.config_keys[usize::from(key)].trim().to_owned();
+        let config = AzuriteConfig {
+            url: value(ConfigKey::StorageSettingsUrl),
+            account_name: value(ConfigKey::StorageSettingsAccountName),
+            account_key: value(ConfigKey::StorageSettingsAccountKey),
+            read_only: self.read_only,
+        };
+        self.config_result = None;
+        self.submit_azurite_job(AzuriteJob::Reconfigure(config));
+    }
+
+    /// What the 'y' key copies on the current screen: the generated configuration, the
+    /// focused field, the SAS URL of the focused module or the field of a pane detail
+    fn yank_target(&self) -> Option<(String, String)> {
+        let screen = self.current_screen();
+        let config = match &self.config_result {
+            Some(Ok(config)) => Some(config.clone()),
+            _ => None,
+        };
+
+        match screen {
+            DMScreen::Configuration
+            | DMScreen::ConfigurationUser
+            | DMScreen::EdgeApp(_)
+            | DMScreen::OtaConfig(_)
+            | DMScreen::AiModelConfig(_)
+            | DMScreen::StorageSettings
+            | DMScreen::Publish => {
+                if let Some(config) = config {
+                    return Some((tr("configuration").to_owned(), config));
+                }
+
+                // These screens have no input field
+                if matches!(
+                    screen,
+                    DMScreen::ConfigurationUser | DMScreen::EdgeApp(DMScreenState::Initial)
+                ) {
+                    return None;
+                }
+
+                self.config_keys
+                    .get(self.config_key_focus)
+                    .filter(|v| !v.is_empty())
+                    .map(|v| {
+                        (
+                            ConfigKey::from(self.config_key_focus).to_string(),
+                            v.clone(),
+                        )
+                    })
+            }
+            DMScreen::EvpModule => {
+                if let Some(config) = config {
+                    return Some((tr("deployment").to_owned(), config));
+                }
+
+                with_azurite_storage(|azurite_storage| {
+                    if azurite_storage.action() == Some(AzuriteAction::Add) {
+                        return None;
+                    }
+
+                    azurite_storage
+                        .current_module()
+                        .map(|m| (trf("SAS URL of {0}", &[&m.blob_name]), m.sas_url.clone()))
+                })
+                .flatten()
+            }
+            DMScreen::Main => self
+                .pane_detail
+                .as_ref()
+                .and_then(|detail| detail.yank_target()),
+            _ => None,
+        }
+    }
+
+    pub fn secrets_revealed(&self, focus: MainWindowFocus) -> bool {
+        self.revealed_panes.contains(&focus)
+    }
+
+    /// Switches the times between local time, UTC and device time
+    fn toggle_time_zone(&mut self) {
+        self.time_zone = self.time_zone.next();
+        let name = tr(self.time_zone.name());
+        with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.info = Some(trf("Times shown in {0}", &[&name])));
+    }
+
+    /// Shows or masks the passwords of the focused pane
+    fn toggle_secrets_revealed(&mut self) {
+        let focus = self.main_window_focus;
+        if !matches!(
+            focus,
+            MainWindowFocus::NetworkSettings | MainWindowFocus::WirelessSettings
+        ) {
+            return;
+        }
+
+        if let Some(i) = self.revealed_panes.iter().position(|f| *f == focus) {
+            self.revealed_panes.remove(i);
+        } else {
+            self.revealed_panes.push(focus);
+        }
+    }
+
+    /// Checks from this machine that the proxy of the network settings being edited is
+    /// reachable and tunnels to the broker, before the device is configured to use it
+    pub fn check_proxy(&mut self) {
+        match ProxyCheck::from_config_keys(&self.config_keys, Some(&self.broker)) {
+            Ok(check) => self.submit_job(Job::ProxyCheck(check)),
+            Err(e) => self.push_failure(ErrorSource::App, "Cannot check the proxy", &e),
+        }
+    }
+
+    /// Sends an SNTP query to the ntp_url of the network settings being edited, catching
+    /// typos before the device is configured with it
+    pub fn check_ntp_server(&mut self) {
+        match NtpCheck::from_config_keys(&self.config_keys) {
+            Ok(check) => self.submit_job(Job::NtpCheck(check)),
+            Err(e) => self.push_failure(ErrorSource::App, "Cannot query the NTP server", &e),
+        }
+    }
 
-                KeyCode::Enter
-                    if with_azurite_storage(|storage| {
-                        storage.action() == Some(AzuriteAction::Add)
-                    })
-                    .unwrap_or(false) =>
-                {
-                    if let Some((_new_module_path, push_result)) =
-                        with_azurite_storage_mut(|azurite_storage| {
-                            let new_module_path = azurite_storage.new_module().to_owned();
-                            let push_result = azurite_storage.push_blob(None, &new_module_path);
-                            (new_module_path, push_result)
-                        })
-                    {
-                        if let Err(e) = push_result {
-                            self.app_error = Some(format!(
-                                "Failed to add new module: {}",
-                                e.error_str().unwrap_or("Unknown error".to_owned())
-                            ));
-                        } else {
-                            with_azurite_storage_mut(|azurite_storage| {
-                                azurite_storage.update_modules(None).unwrap_or_else(|e| {
-                                    // Can't set app_error from here, so just log it
-                                    jerror!("Failed to update modules: {}", e);
-                                });
-                                azurite_storage.pop_action();
-                                azurite_storage.new_module_mut().clear();
-                            });
-                        }
-                    }
-                }
+    /// Writes a Markdown report of the device to the current directory
+    fn save_report(&mut self) {
+        // The state is taken now, rendering and writing the report is done in the background
+        let report = with_mqtt_ctrl(DeviceReport::new);
+        self.submit_job(Job::SaveReport(Box::new(report), ReportFormat::Markdown));
+    }
 
-                KeyCode::Enter => {
-                    if with_azurite_storage(|storage| {
-                        storage.action() == Some(AzuriteAction::Select)
-                    })
-                    .unwrap_or(false)
-                    {
-                        self.update_sas_url_entries();
-                        self.dm_screen_move_back();
-                    }
-                }
+    pub fn azurite_health(&self) -> &HealthMonitor {
+        &self.azurite_health
+    }
 
-                KeyCode::Char('a') => {
-                    with_azurite_storage_mut(|azurite_storage| {
-                        azurite_storage.push_action(AzuriteAction::Add);
-                    });
-                }
+    /// Checks the Azurite connectivity when due, reinitializing the storage if needed
+    fn update_azurite_health(&mut self) {
+        let now = Instant::now();
+        if self.azurite_health.next_check(now).is_some() {
+            return;
+        }
 
-                KeyCode::Char('r') => {
-                    if let Some(module_name) = with_azurite_storage(|azurite_storage| {
-                        azurite_storage
-                            .current_module()
-                            .map(|m| m.blob_name.clone())
-                    })
-                    .flatten()
-                    {
-                        let remove_result = with_azurite_storage_mut(|azurite_storage| {
-                            azurite_storage.remove_blob(None, &module_name)
-                        });
+        // The worker reinitializes the storage if it is missing, the result is recorded in
+        // update_jobs()
+        self.azurite_health.start_check(now);
+        self.submit_azurite_job(AzuriteJob::CheckConnection);
+    }
 
-                        if let Some(Err(e)) = remove_result {
-                            self.app_error = Some(format!(
-                                "Failed to remove module '{}': {}",
-                                module_name,
-                                e.error_str().unwrap_or("Unknown error".to_owned())
-                            ));
-                        } else {
-                            with_azurite_storage_mut(|azurite_storage| {
-                                azurite_storage.update_modules(None).unwrap_or_else(|e| {
-                                    jerror!("Failed to update modules: {}", e);
-                                });
-                            });
-                        }
-                    }
-                }
+    pub fn elog_anomalies(&self) -> &[String] {
+        &self.elog_anomalies
+    }
 
-                KeyCode::Esc if self.config_result.is_some() => self.config_result = None,
-                KeyCode::Char('d')
-                    if with_azurite_storage(|az| az.action() == Some(AzuriteAction::Deploy))
-                        .unwrap_or(false) =>
-                {
-                    if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
-                        if let Some(deployment_json) = with_azurite_storage(|azurite_storage| {
-                            azurite_storage
-                                .current_module()
-                                .map(|m| m.deployment_json())
-                        })
-                        .flatten()
-                        {
-                            self.config_result = Some(deployment_json);
-                        }
-                    } else {
-                        self.app_error = Some("Device is not connected.".to_owned());
-                    }
-                }
+    /// Records the temperature alarms raised as warnings, the info line tells the cleared ones
+    fn update_temperature_alarms(&mut self) {
+        for notice in with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.take_temperature_notices()) {
+            if notice.raised {
+                self.push_warning(ErrorSource::Mqtt, notice.message);
+            }
+        }
+    }
 
-                KeyCode::Char('u')
-                    if with_azurite_storage(|az| az.action() == Some(AzuriteAction::Deploy))
-                        .unwrap_or(false) =>
-                {
-                    if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
-                        self.config_result = Some(ModuleInfo::undeployment_json());
-                    } else {
-                        self.app_error = Some("Device is not connected.".to_owned());
-                    }
-                }
+    fn update_elog_anomalies(&mut self) {
+        self.elog_anomalies = with_mqtt_ctrl(|mqtt_ctrl| {
+            mqtt_ctrl
+                .elog_stats()
+                .anomalies(Local::now())
+                .iter()
+                .map(|component| component.name())
+                .collect()
+        });
+    }
 
-                KeyCode::Char('s') => {
-                    if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
-                        if let Some(Ok(deploy)) = &self.config_result {
-                            match with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.send_configure(deploy)) {
-                                Ok(()) => self.dm_screen_move_back(),
-                                Err(_) => {
-                                    self.app_error = Some("Failed to send deployment.".to_owned());
-                                }
-                            }
-                        }
-                    } else {
-                        self.app_error = Some("Device is not connected.".to_owned());
-                    }
-                }
+    fn yank(&mut self, name: &str, text: &str) {
+        match clipboard::copy(text) {
+            Ok(()) => with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                mqtt_ctrl.info = Some(trf("Copied {0} to clipboard", &[&name]))
+            }),
+            Err(e) => {
+                self.push_report(ErrorSource::App, &e);
+            }
+        }
+    }
 
-                KeyCode::Esc => self.dm_screen_move_back(),
-                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                KeyCode::Up | KeyCode::Char('k') => {
-                    with_azurite_storage_mut(|azurite_storage| {
-                        azurite_storage.current_module_focus_up();
-                    });
-                }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    with_azurite_storage_mut(|azurite_storage| {
-                        azurite_storage.current_module_focus_down();
-                    });
-                }
-                _ => {}
-            },
-            DMScreen::TokenProvider => match key_event.code {
-                KeyCode::Enter if self.token_provider_for_config.is_some() => {
-                    if let Some(uuid_string) = with_azurite_storage(|azurite_storage| {
-                        azurite_storage
-                            .current_token_provider()
-                            .map(|tp| tp.uuid.uuid().to_string())
-                    })
-                    .flatten()
-                    {
-                        if let Some(config_key) = self.token_provider_for_config.take() {
-                            self.config_keys[usize::from(config_key)] = uuid_string;
-                            self.dm_screen_move_back();
-                        }
-                    }
-                }
-                KeyCode::Char('a') => {
-                    if let Some(Err(e)) = with_azurite_storage_mut(|azurite_storage| {
-                        azurite_storage.add_token_provider()
-                    }) {
-                        self.app_error = Some(format!(
-                            "Failed to add new token provider: {}",
-                            e.error_str().unwrap_or("Unknown error".to_owned())
-                        ));
-                    }
-                }
-                KeyCode::Char('d') => {
-                    if let Some(uuid) = with_azurite_storage(|azurite_storage| {
-                        azurite_storage
-                            .current_token_provider()
-                            .map(|tp| tp.uuid.clone())
-                    })
-                    .flatten()
-                    {
-                        if let Some(Err(e)) = with_azurite_storage_mut(|azurite_storage| {
-                            azurite_storage.remove_token_provider(&uuid)
-                        }) {
-                            self.app_error = Some(format!(
-                                "Failed to remove token provider: {}",
-                                e.error_str().unwrap_or("Unknown error".to_owned())
-                            ));
-                        }
-                    }
-                }
-                KeyCode::Esc => {
-                    self.token_provider_for_config = None;
-                    self.dm_screen_move_back();
-                }
-                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                KeyCode::Up | KeyCode::Char('k') => {
-                    with_azurite_storage_mut(|azurite_storage| {
-                        azurite_storage.current_token_provider_focus_up();
-                    });
-                }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    with_azurite_storage_mut(|azurite_storage| {
-                        azurite_storage.current_token_provider_focus_down();
-                    });
-                }
-                KeyCode::Char('s') => {
-                    if let Some(token_provider) = with_azurite_storage(|azurite_storage| {
-                        azurite_storage.current_token_provider().cloned()
-                    })
-                    .flatten()
-                    {
-                        let container_name = token_provider.container.clone();
-
-                        // Fetch blobs for the selected token provider
-                        match with_azurite_storage(|azurite_storage| {
-                            azurite_storage.list_blobs_for_ui(&container_name)
-                        }) {
-                            Some(Ok(blobs)) => {
-                                let mut blob_state =
-                                    ui::ui_token_provider_blobs::BlobListState::new(container_name);
-                                blob_state.blobs = blobs;
-                                self.blob_list_state = Some(blob_state);
-                                self.dm_screen_move_to(DMScreen::TokenProviderBlobs);
-                            }
-                            Some(Err(e)) => {
-                                self.app_error = Some(format!(
-                                    "Failed to list blobs: {}",
-                                    e.error_str().unwrap_or("Unknown error".to_owned())
-                                ));
-                            }
-                            None => {
-                                self.app_error = Some("Azurite storage not available".to_owned());
-                            }
-                        }
-                    }
-                }
-                _ => {}
-            },
-            DMScreen::TokenProviderBlobs => match key_event.code {
-                KeyCode::Esc => {
-                    self.blob_list_state = None;
-                    self.dm_screen_move_back();
-                }
-                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                KeyCode::Up | KeyCode::Char('k') => {
-                    if let Some(ref mut blob_state) = self.blob_list_state {
-                        blob_state.move_up();
-                    }
-                }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    if let Some(ref mut blob_state) = self.blob_list_state {
-                        blob_state.move_down();
-                    }
-                }
-                KeyCode::Enter => {
-                    if let Some(ref blob_state) = self.blob_list_state {
-                        if let Some(blob) = blob_state.current_blob() {
-                            let container_name = blob_state.container_name.clone();
-                            let blob_name = blob.name.clone();
-
-                            match with_azurite_storage(|azurite_storage| {
-                                azurite_storage
-                                    .download_blob_to_current_dir(&container_name, &blob_name)
-                            }) {
-                                Some(Ok(file_path)) => {
-                                    with_mqtt_ctrl_mut(|mqtt_ctrl| {
-                                        mqtt_ctrl.info =
-                                            Some(format!("Blob downloaded to: {}", file_path));
-                                    });
-                                }
-                                Some(Err(e)) => {
-                                    self.app_error =
-                                        Some(e.error_str().unwrap_or("Unknown error".to_owned()));
-                                }
-                                None => {
-                                    self.app_error =
-                                        Some("Azurite storage not available".to_owned());
-                                }
-                            }
-                        }
-                    }
+    pub fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if let Some(confirmation) = self.confirmation.as_mut() {
+            match confirmation.handle_key(key_event.code) {
+                Some(true) => {
+                    let action = confirmation.action.clone();
+                    self.confirmation = None;
+                    self.run_confirmed(action);
                 }
-                _ => {}
-            },
-            DMScreen::EdgeApp(state) => match state {
-                DMScreenState::Initial => match key_event.code {
-                    KeyCode::Esc => self.dm_screen_move_back(),
-                    KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                    KeyCode::Char('e') => {
-                        self.config_key_focus_start = ConfigKey::CommonSettingsProcessState.into();
-                        self.config_key_focus_end = ConfigKey::CommonSettingsUploadInterval.into();
-                        self.config_key_focus = self.config_key_focus_start;
-                        self.dm_screen_move_to(DMScreen::EdgeApp(DMScreenState::Configuring));
-                    }
-                    _ => {}
-                },
-                DMScreenState::Configuring => match key_event.code {
-                    KeyCode::Char(c) if self.config_key_editable => {
-                        let value: &mut String =
-                            self.config_keys.get_mut(self.config_key_focus).unwrap();
-                        value.push(c);
-                    }
-                    KeyCode::Backspace if self.config_key_editable => {
-                        let value: &mut String =
-                            self.config_keys.get_mut(self.config_key_focus).unwrap();
-                        value.pop();
-                    }
-                    KeyCode::Esc | KeyCode::Enter if self.config_key_editable => {
-                        self.config_key_editable = false
-                    }
-                    KeyCode::Esc if self.config_result.is_some() => self.config_result = None,
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        self.config_focus_up();
-                    }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        self.config_focus_down();
-                    }
-                    KeyCode::Char('w') => {
-                        let mut edge_app_result = None;
+                Some(false) => self.confirmation = None,
+                None => {}
+            }
+            return;
+        }
 
-                        with_mqtt_ctrl(|ctrl| {
-                            if let Some(edge_app) = ctrl.edge_app() {
-                                edge_app_result = Some(edge_app.parse_configure(&self.config_keys));
-                            }
-                        });
+        if key_event.code == KeyCode::Char('y')
+            && !self.config_key_editable
+            && self.search.is_none()
+            && self.notes_editor.is_none()
+            && let Some((name, text)) = self.yank_target()
+        {
+            self.yank(&name, &text);
+            return;
+        }
 
-                        if let Some(result) = edge_app_result {
-                            self.config_result = match result {
-                                Ok(s) => Some(Ok(s)),
-                                Err(e) => Some(Err(e)),
-                            };
-                            self.dm_screen_move_to(DMScreen::EdgeApp(DMScreenState::Completed));
-                        } else {
-                            self.app_error = Some("No Edge App instances found.".to_owned());
-                            self.dm_screen_move_back();
-                        }
-                    }
-                    KeyCode::Char('i') | KeyCode::Char('a') => self.config_key_editable = true,
-                    KeyCode::Esc => self.dm_screen_move_back(),
-                    KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                    _ => {}
-                },
-                DMScreenState::Completed => match key_event.code {
-                    KeyCode::Char('s') => {
-                        // Send the configuration, go back to the default state
-                        self.config_key_clear();
-                        self.dm_screen_move_back();
-                        self.dm_screen_move_back();
-                    }
-                    KeyCode::Esc => self.dm_screen_move_back(),
-                    KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                    _ => {}
-                },
-            },
-            DMScreen::Ota => match key_event.code {
-                KeyCode::Esc => self.dm_screen_move_back(),
-                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                KeyCode::Char('d') => {
-                    let is_device_connected =
-                        with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected());
-                    if is_device_connected {
-                        self.switch_to_ota_config_screen(DMScreenState::Initial);
-                    } else {
-                        self.app_error = Some("Device is not connected.".to_owned());
-                    }
-                }
-                _ => {}
-            },
-            DMScreen::OtaConfig(state) => match state {
-                DMScreenState::Initial => match key_event.code {
-                    KeyCode::Char(c) if self.config_key_editable => {
-                        let value: &mut String =
-                            self.config_keys.get_mut(self.config_key_focus).unwrap();
-                        value.push(c);
-                    }
-                    KeyCode::Backspace if self.config_key_editable => {
-                        let value: &mut String =
-                            self.config_keys.get_mut(self.config_key_focus).unwrap();
-                        value.pop();
-                    }
-                    KeyCode::Esc if self.config_key_editable => self.config_key_editable = false,
-                    KeyCode::Esc => self.dm_screen_move_back(),
-                    KeyCode::Enter if self.config_key_editable => self.config_key_editable = false,
-                    KeyCode::Up | KeyCode::Char('k') => self.config_focus_up(),
-                    KeyCode::Down | KeyCode::Char('j') => self.config_focus_down(),
-                    KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                    KeyCode::Char('i') | KeyCode::Char('a') => {
-                        if ConfigKey::from(self.config_key_focus).is_sas_url_entry() {
-                            self.switch_to_evp_module_screen(AzuriteAction::Select);
-                        } else {
-                            self.config_key_editable = true;
-                        }
-                    }
-                    KeyCode::Char('w') => {
-                        self.config_result = Some(parse_ota_configuration(&self.config_keys));
-                        // we don't use configuring state here
-                        self.dm_screen_move_to(DMScreen::OtaConfig(DMScreenState::Completed));
-                    }
-                    _ => {}
-                },
-                DMScreenState::Configuring => {
-                    // Handle configuration logic here
-                }
-                DMScreenState::Completed => match key_event.code {
-                    // Handle completion logic here
-                    KeyCode::Esc => {
-                        self.config_result = None;
-                        self.dm_screen_move_back();
-                    }
-                    KeyCode::Char('s') => {
-                        with_mqtt_ctrl_mut(|mqtt_ctrl| {
-                            if let Some(Ok(config)) = &self.config_result {
-                                if let Err(e) = mqtt_ctrl.send_configure(config) {
-                                    with_global_app_mut(|app| {
-                                        app.app_error = Some(format!(
-                                            "Failed to send OTA configuration: {}",
-                                            e.error_str().unwrap_or("Unknown error".to_owned())
-                                        ));
-                                    });
-                                }
-                            }
-                        });
-                        self.dm_screen_move_back();
-                        self.dm_screen_move_back();
-                    }
-                    KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                    _ => {}
-                },
-            },
-            DMScreen::AiModel => match key_event.code {
-                KeyCode::Esc => self.dm_screen_move_back(),
-                KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                KeyCode::Char('d') => {
-                    let is_device_connected =
-                        with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected());
-                    if is_device_connected {
-                        self.switch_to_ai_model_config_screen(DMScreenState::Initial);
-                    } else {
-                        self.app_error = Some("Device is not connected.".to_owned());
-                    }
-                }
-                _ => {}
-            },
-            DMScreen::AiModelConfig(state) => match state {
-                DMScreenState::Initial => match key_event.code {
-                    KeyCode::Char(c) if self.config_key_editable => {
-                        let value: &mut String =
-                            self.config_keys.get_mut(self.config_key_focus).unwrap();
-                        value.push(c);
-                    }
-                    KeyCode::Backspace if self.config_key_editable => {
-                        let value: &mut String =
-                            self.config_keys.get_mut(self.config_key_focus).unwrap();
-                        value.pop();
-                    }
-                    KeyCode::Esc if self.config_key_editable => self.config_key_editable = false,
-                    KeyCode::Esc => self.dm_screen_move_back(),
-                    KeyCode::Enter if self.config_key_editable => self.config_key_editable = false,
-                    KeyCode::Up | KeyCode::Char('k') => self.config_focus_up(),
-                    KeyCode::Down | KeyCode::Char('j') => self.config_focus_down(),
-                    KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                    KeyCode::Char('i') | KeyCode::Char('a') => {
-                        if ConfigKey::from(self.config_key_focus).is_sas_url_entry() {
-                            self.switch_to_evp_module_screen(AzuriteAction::Select);
-                        } else {
-                            self.config_key_editable = true;
-                        }
-                    }
-                    KeyCode::Char('w') => {
-                        self.config_result = Some(parse_ai_model_configuration(&self.config_keys));
-                        // we don't use configuring state here
-                        self.dm_screen_move_to(DMScreen::AiModelConfig(DMScreenState::Completed));
-                    }
-                    _ => {}
-                },
-                DMScreenState::Configuring => {}
-                DMScreenState::Completed => match key_event.code {
-                    KeyCode::Esc => {
-                        self.config_result = None;
-                        self.dm_screen_move_back();
-                    }
-                    KeyCode::Char('s') => {
-                        with_mqtt_ctrl_mut(|mqtt_ctrl| {
-                            if let Some(Ok(config)) = &self.config_result {
-                                if let Err(e) = mqtt_ctrl.send_configure(config) {
-                                    with_global_app_mut(|app| {
-                                        app.app_error = Some(format!(
-                                            "Failed to send AiModel deployment configuration: {}",
-                                            e.error_str().unwrap_or("Unknown error".to_owned())
-                                        ));
-                                    });
-                                }
-                            }
-                        });
-                        self.dm_screen_move_back();
-                        self.dm_screen_move_back();
-                    }
-                    KeyCode::Char('q') => self.dm_screen_move_to(DMScreen::Exiting),
-                    _ => {}
-                },
-            },
+        // Alt+<n> goes back to the n-th screen of the breadcrumbs
+        if key_event.modifiers.contains(KeyModifiers::ALT)
+            && let KeyCode::Char(c @ '1'..='9') = key_event.code
+        {
+            let depth = c as usize - '0' as usize;
+            if depth < self.screens.len() {
+                self.dm_screen_jump_back(depth);
+            }
+            return;
+        }
+
+        let index = self.screens.len() - 1;
+        let handling = Box::new(screens::Handling(self.current_screen()));
+        let mut screen = std::mem::replace(&mut self.screens[index], handling);
+        screen.handle_key(self, key_event);
+
+        // Put back in its place unless the key moved away from it
+        if let Some(slot) = self.screens.get_mut(index)
+            && screens::downcast_ref::<screens::Handling>(slot.as_ref()).is_some()
+        {
+            *slot = screen;
         }
     }
 
@@ -2034,12 +2771,31 @@ impl App {
     pub fn main_window_focus(&self) -> MainWindowFocus {
         self.main_window_focus
     }
+
+    pub fn auto_downloader(&self) -> &AutoDownloader {
+        &self.auto_downloader
+    }
+
+    /// Watches the uploads issued to the device and downloads the uploaded blobs
+    fn update_auto_download(&mut self) {
+        for (container, blob_name) in
+            with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.take_issued_uploads())
+        {
+            self.auto_downloader.watch(&container, &blob_name);
+        }
+
+        // Listing the containers and downloading is done by the worker, the downloads are
+        // recorded in update_jobs()
+        if self.auto_downloader.should_poll()
+            && let Some((pending, dir)) = self.auto_downloader.start_poll()
+        {
+            self.submit_azurite_job(AzuriteJob::AutoDownload { pending, dir });
+        }
+    }
 }
 
 impl Widget for &App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let draw_start = Instant::now();
-
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
@@ -2054,103 +2810,21 @@ impl Widget for &App {
             jerror!(func = "App::render()", error = format!("{:?}", e));
         }
 
-        // Draw main content based on current screen
-        match self.current_screen() {
-            DMScreen::Main => {
-                if let Err(e) = ui_main::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-                jinfo!(
-                    event = "TIME_MEASURE",
-                    draw_main_time = format!("{}ms", draw_start.elapsed().as_millis())
-                )
-            }
-            DMScreen::Module => {
-                if let Err(e) = ui_module::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-                jinfo!(
-                    event = "TIME_MEASURE",
-                    draw_module_time = format!("{}ms", draw_start.elapsed().as_millis())
-                )
-            }
-            DMScreen::Configuration => {
-                if let Err(e) = ui_config::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
-            DMScreen::ConfigurationUser => {
-                if let Err(e) = ui_config_user::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
-            DMScreen::DirectCommand => {
-                if let Err(e) = ui_directcmd::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
-            DMScreen::EvpModule => {
-                if let Err(e) = ui_deploy::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
-            DMScreen::TokenProvider => {
-                if let Err(e) = ui_token_provider::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
-            DMScreen::TokenProviderBlobs => {
-                if let Some(ref blob_state) = self.blob_list_state {
-                    if let Err(e) = ui::ui_token_provider_blobs::draw(chunks[1], buf, blob_state) {
-                        jerror!(func = "App::render()", error = format!("{:?}", e));
-                    }
-                }
-            }
-            DMScreen::Elog => {
-                if let Err(e) = ui_elog::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
-            DMScreen::EdgeApp(_) => {
-                if let Err(e) = ui_edge_app::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
-            DMScreen::Ota => {
-                if let Err(e) = ui::ui_ota::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
-            DMScreen::Exiting => {
-                if let Err(e) = ui_exit::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-                jinfo!(
-                    event = "TIME_MEASURE",
-                    draw_exit_time = format!("{}ms", draw_start.elapsed().as_millis())
-                )
-            }
+        if let Err(e) = self.screens.last().unwrap().draw(chunks[1], buf, self) {
+            jerror!(func = "App::render()", error = format!("{:?}", e));
+        }
 
-            DMScreen::OtaConfig(_) => {
-                if let Err(e) = ui_ota_config::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
-            DMScreen::AiModel => {
-                if let Err(e) = ui::ui_ai_model::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
-            DMScreen::AiModelConfig(_state) => {
-                if let Err(e) = ui::ui_ai_model_config::draw(chunks[1], buf, self) {
-                    jerror!(func = "App::render()", error = format!("{:?}", e));
-                }
-            }
+        if let Err(e) = ui::ui_confirm::draw(chunks[1], buf, self) {
+            jerror!(func = "App::render()", error = format!("{:?}", e));
         }
 
         if let Err(e) = ui_foot::draw(chunks[2], buf, self) {
             jerror!(func = "App::render()", error = format!("{:?}", e));
         }
+
+        if let Err(e) = ui::ui_profile::draw(chunks[1], buf, self) {
+            jerror!(func = "App::render()", error = format!("{:?}", e));
+        }
     }
 }
 
@@ -2181,18 +2855,32 @@ pub fn update() -> Result<(), DMError> {
     with_global_app_mut(|app| {
         if let Err(e) = with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.update()) {
             jerror!(func = "update()", error = format!("{:?}", e));
-            app.app_error = Some(e.error_str().unwrap_or("Update error!".to_owned()));
+            app.push_report(ErrorSource::Mqtt, &e);
         }
 
-        // Try to reinitialize AzuriteStorage if it's currently None
-        if with_azurite_storage(|_| true).is_none() && try_reinit_azurite_storage() {
-            jinfo!("AzuriteStorage successfully reinitialized during update cycle");
-        }
+        app.update_azurite_health();
+        app.update_elog_anomalies();
+        app.update_device_key();
+        app.update_temperature_alarms();
+        app.update_jobs();
+
+        app.update_auto_download();
+        app.update_script();
+        app.update_debug_log();
 
         Ok(())
     })
 }
 
+/// Records the times of the frame just drawn, if the frames are profiled
+pub fn record_frame(times: FrameTimes) {
+    with_global_app_mut(|app| {
+        if let Some(profile) = app.frame_profile.as_mut() {
+            profile.record(times);
+        }
+    })
+}
+
 /// Draw the global App instance to a terminal frame
 pub fn draw(frame: &mut Frame) {
     with_global_app(|app| {
@@ -2229,6 +2917,57 @@ mod tests {
         assert_eq!(ConfigKey::size(), expected_size);
     }
 
+    #[test]
+    fn test_config_key_table() {
+        assert_eq!(ConfigKey::TABLE.len(), ConfigKey::Invalid as usize);
+        assert_eq!(
+            ConfigKey::CommonSettingsPQMeExposureTime.to_string(),
+            "PQ.manual_exposure.exposure_time"
+        );
+        assert_eq!(ConfigKey::Invalid.to_string(), "Invalid");
+        assert_eq!(ConfigKey::Invalid.note(), "");
+
+        assert_eq!(ConfigKey::StaticIpv4Dns.group(), "IPv4");
+        assert_eq!(ConfigKey::MainLogSettingLevel.choices().len(), 6);
+        assert!(ConfigKey::IpMethod.choices().is_empty());
+        assert!(ConfigKey::AiModel2PackageUrl.is_sas_url_entry());
+        assert!(!ConfigKey::AiModel2Hash.is_sas_url_entry());
+        assert_eq!(
+            ConfigKey::CustomRpcInstance.default_value(),
+            Some("$system")
+        );
+        assert_eq!(ConfigKey::CustomRpcMethod.default_value(), None);
+    }
+
+    #[test]
+    fn test_config_key_check() {
+        assert_eq!(ConfigKey::ProxyPort.check(""), None);
+        assert_eq!(ConfigKey::ProxyPort.check("8080"), None);
+        assert!(ConfigKey::ProxyPort.check("port").is_some());
+        assert_eq!(ConfigKey::LedEnabled.check("true"), None);
+        assert!(ConfigKey::LedEnabled.check("1").is_some());
+        assert_eq!(ConfigKey::AllLogSettingDestination.check("1"), None);
+        assert!(ConfigKey::AllLogSettingDestination.check("2").is_some());
+        assert_eq!(
+            ConfigKey::CustomSettings.check(r#"{"threshold": 0.3}"#),
+            None
+        );
+        assert!(ConfigKey::CustomSettings.check("{").is_some());
+        assert_eq!(ConfigKey::NtpUrl.check("anything"), None);
+    }
+
+    #[test]
+    fn test_field_state() {
+        use crate::device_config::FieldState;
+
+        assert_eq!(FieldState::of("", Some("1")), FieldState::Unset);
+        assert_eq!(FieldState::of("  ", None), FieldState::Unset);
+        assert_eq!(FieldState::of("1", Some("1")), FieldState::Unchanged);
+        assert_eq!(FieldState::of(" lab ", Some("lab")), FieldState::Unchanged);
+        assert_eq!(FieldState::of("0", Some("1")), FieldState::Modified);
+        assert_eq!(FieldState::of("0", None), FieldState::Modified);
+    }
+
     #[test]
     fn test_main_window_focus_navigation() {
         let focus = MainWindowFocus::MainChip;
@@ -2257,6 +2996,7 @@ mod tests {
     fn test_app_new_and_basic_properties() {
         let cfg = AppConfig {
             broker: "localhost:1883",
+            ..Default::default()
         };
         let app = App::new(cfg).unwrap();
 
@@ -2268,9 +3008,28 @@ mod tests {
         assert_eq!(app.main_window_focus(), MainWindowFocus::MainChip);
     }
 
+    #[test]
+    fn test_app_new_kiosk() {
+        let mut app = App::new(AppConfig {
+            broker: "b",
+            kiosk: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        // The full UI is one step back
+        assert_eq!(app.current_screen(), DMScreen::Kiosk);
+        app.dm_screen_move_back();
+        assert_eq!(app.current_screen(), DMScreen::Main);
+    }
+
     #[test]
     fn test_config_key_clear_and_result_reset() {
-        let mut app = App::new(AppConfig { broker: "b" }).unwrap();
+        let mut app = App::new(AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
         // modify keys and result
         app.config_keys[0] = "value".to_string();
         app.config_result = Some(Ok("ok".to_string()));
@@ -2283,9 +3042,37 @@ mod tests {
         assert!(app.config_result.is_none());
     }
 
+    #[test]
+    fn test_config_keys_dirty() {
+        let mut app = App::new(AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
+        // Prefilled, e.g. with the reported values, but not edited
+        app.config_keys[usize::from(ConfigKey::ProxyUrl)] = "proxy.lan".to_owned();
+        app.config_key_focus = ConfigKey::ProxyPort.into();
+        app.edit_focused_config_key().push_str("8080");
+
+        let keys = app.dirty_config_keys();
+        assert_eq!(keys[usize::from(ConfigKey::ProxyUrl)], "");
+        assert_eq!(keys[usize::from(ConfigKey::ProxyPort)], "8080");
+
+        app.config_clear_focused();
+        assert!(app.dirty_config_keys().iter().all(|k| k.is_empty()));
+
+        app.edit_focused_config_key().push('1');
+        app.config_key_clear();
+        assert!(app.config_keys_dirty.iter().all(|dirty| !dirty));
+    }
+
     #[test]
     fn test_config_focus_navigation_wraps() {
-        let mut app = App::new(AppConfig { broker: "b" }).unwrap();
+        let mut app = App::new(AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
         app.config_key_focus_start = 2;
         app.config_key_focus_end = 4;
         app.config_key_focus = app.config_key_focus_start;
@@ -2299,9 +3086,51 @@ mod tests {
         assert_eq!(app.config_key_focus, app.config_key_focus_start);
     }
 
+    #[test]
+    fn test_config_focus_sections() {
+        let mut app = App::new(AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
+        app.config_key_focus_start = ConfigKey::IpMethod.into();
+        app.config_key_focus_end = ConfigKey::ProxyPassword.into();
+        app.config_key_focus = ConfigKey::NtpUrl.into();
+        let focus = |app: &App| ConfigKey::from(app.config_key_focus);
+
+        app.config_focus_section_jump(true);
+        assert_eq!(focus(&app), ConfigKey::StaticIpv4Ip);
+        app.config_focus_section_jump(false);
+        assert_eq!(focus(&app), ConfigKey::IpMethod);
+        app.config_focus_section_jump(false);
+        assert_eq!(focus(&app), ConfigKey::ProxyUrl);
+
+        // A collapsed section is a single stop at its first key
+        app.config_key_focus = ConfigKey::StaticIpv4Gateway.into();
+        app.config_toggle_section();
+        assert!(app.is_section_collapsed("IPv4"));
+        assert_eq!(focus(&app), ConfigKey::StaticIpv4Ip);
+        app.config_focus_down();
+        assert_eq!(focus(&app), ConfigKey::StaticIpv6Ip);
+        app.config_focus_up();
+        assert_eq!(focus(&app), ConfigKey::StaticIpv4Ip);
+        app.config_focus_up();
+        assert_eq!(focus(&app), ConfigKey::NtpUrl);
+
+        app.config_focus_down();
+        app.config_toggle_section();
+        assert!(!app.is_section_collapsed("IPv4"));
+        app.config_focus_down();
+        assert_eq!(focus(&app), ConfigKey::StaticIpv4SubnetMask);
+    }
+
     #[test]
     fn test_handle_key_event_changes_focus() {
-        let mut app = App::new(AppConfig { broker: "b" }).unwrap();
+        let mut app = App::new(AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Simulate Down key press
         let down_event = KeyEvent::new(KeyCode::Down, crossterm::event::KeyModifiers::NONE);
@@ -2315,6 +3144,77 @@ mod tests {
         assert_eq!(app.main_window_focus(), MainWindowFocus::MainChip);
     }
 
+    #[test]
+    fn test_push_screen_bounds_the_stack() {
+        let mut screens: Vec<Box<dyn Screen>> = vec![Box::new(screens::MainScreen)];
+        push_screen(&mut screens, Box::new(screens::OtaScreen));
+        push_screen(
+            &mut screens,
+            Box::new(screens::OtaConfigScreen(DMScreenState::Initial)),
+        );
+        push_screen(
+            &mut screens,
+            Box::new(screens::TokenProviderScreen::default()),
+        );
+        push_screen(
+            &mut screens,
+            Box::new(screens::OtaConfigScreen(DMScreenState::Configuring)),
+        );
+        assert_eq!(
+            screens.iter().map(|s| s.id()).collect::<Vec<_>>(),
+            vec![
+                DMScreen::Main,
+                DMScreen::Ota,
+                DMScreen::OtaConfig(DMScreenState::Configuring)
+            ]
+        );
+        assert_eq!(screens[2].id().name(), "OtaConfig");
+
+        for _ in 0..MAX_SCREEN_DEPTH {
+            push_screen(&mut screens, Box::new(screens::ElogScreen::default()));
+            push_screen(&mut screens, Box::new(screens::OtaScreen));
+        }
+        assert!(screens.len() <= MAX_SCREEN_DEPTH);
+        assert_eq!(screens[0].id(), DMScreen::Main);
+    }
+
+    #[test]
+    fn test_log_settings_navigation() {
+        let mut app = App::new(AppConfig {
+            broker: "localhost:1883",
+            ..Default::default()
+        })
+        .unwrap();
+        app.config_key_focus_start = ConfigKey::AllLogSettingLevel.into();
+        app.config_key_focus_end = ConfigKey::CompanionAppLogSettingPath.into();
+        app.config_key_focus = app.config_key_focus_start;
+
+        app.log_settings_move(-1, 0);
+        assert_eq!(
+            ConfigKey::from(app.config_key_focus),
+            ConfigKey::CompanionAppLogSettingLevel
+        );
+        app.log_settings_move(0, -1);
+        assert_eq!(
+            ConfigKey::from(app.config_key_focus),
+            ConfigKey::CompanionAppLogSettingPath
+        );
+        app.log_settings_move(1, -2);
+        assert_eq!(
+            ConfigKey::from(app.config_key_focus),
+            ConfigKey::AllLogSettingDestination
+        );
+
+        // The destination dropdown, from the first choice to the last one
+        let mut screen = screens::LogSettingsScreen::default();
+        screen.handle_key(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(screen.choice, Some(0));
+        screen.handle_key(&mut app, KeyEvent::from(KeyCode::Up));
+        screen.handle_key(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.config_keys[app.config_key_focus], "1");
+        assert_eq!(screen.choice, None);
+    }
+
     #[test]
     #[serial]
     fn test_config_dir_prefers_env_var() {
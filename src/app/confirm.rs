@@ -0,0 +1,166 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        azurite::{BlobConflict, worker::UploadPurpose},
+        mqtt_ctrl::evp::evp_state::UUID,
+    },
+    crossterm::event::KeyCode,
+};
+
+/// Destructive action run once confirmed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmAction {
+    FactoryReset,
+    RemoveModule(String),
+    RemoveMarkedModules,
+    RemoveTokenProvider(UUID),
+    /// Sends the previewed deployment removing all the modules
+    Undeploy(String),
+    /// Uploads the files again, replacing their blobs unless they changed meanwhile
+    OverwriteBlobs(UploadPurpose, Vec<BlobConflict>),
+    /// Uploads the files again under free blob names
+    RenameBlobs(UploadPurpose, Vec<BlobConflict>),
+}
+
+/// Question asked before a destructive action
+#[derive(Debug, Clone, PartialEq)]
+pub struct Confirmation {
+    pub action: ConfirmAction,
+    pub message: String,
+    /// Name to type to confirm, instead of answering y
+    pub expected: Option<String>,
+    pub typed: String,
+    /// Key and name of another action which can be chosen instead, e.g. renaming instead
+    /// of overwriting
+    pub alternative: Option<(char, &'static str, ConfirmAction)>,
+}
+
+impl Confirmation {
+    pub fn new(action: ConfirmAction, message: impl Into<String>) -> Self {
+        Self {
+            action,
+            message: message.into(),
+            expected: None,
+            typed: String::new(),
+            alternative: None,
+        }
+    }
+
+    pub fn with_alternative(
+        mut self,
+        key: char,
+        name: &'static str,
+        action: ConfirmAction,
+    ) -> Self {
+        self.alternative = Some((key, name, action));
+        self
+    }
+
+    /// Requires the resource name to be typed, for the actions that cannot be undone
+    pub fn with_expected(mut self, expected: &str) -> Self {
+        self.expected = Some(expected.to_owned());
+        self
+    }
+
+    /// Some(true) when the action is confirmed, Some(false) when it is cancelled.
+    /// The alternative replaces the action when its key is pressed.
+    pub fn handle_key(&mut self, code: KeyCode) -> Option<bool> {
+        match (&self.expected, code) {
+            (None, KeyCode::Char(c))
+                if self.alternative.as_ref().is_some_and(|(key, ..)| *key == c) =>
+            {
+                if let Some((_, _, action)) = self.alternative.take() {
+                    self.action = action;
+                }
+                Some(true)
+            }
+            (_, KeyCode::Esc) => Some(false),
+            (Some(expected), KeyCode::Enter) => Some(self.typed == *expected).filter(|ok| *ok),
+            (Some(_), KeyCode::Char(c)) => {
+                self.typed.push(c);
+                None
+            }
+            (Some(_), KeyCode::Backspace) => {
+                self.typed.pop();
+                None
+            }
+            (None, KeyCode::Char('y') | KeyCode::Char('Y')) => Some(true),
+            (None, KeyCode::Char('n') | KeyCode::Char('N')) => Some(false),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_key() {
+        let mut confirmation = Confirmation::new(ConfirmAction::RemoveMarkedModules, "Remove?");
+        assert_eq!(confirmation.handle_key(KeyCode::Enter), None);
+        assert_eq!(confirmation.handle_key(KeyCode::Char('y')), Some(true));
+        assert_eq!(confirmation.handle_key(KeyCode::Char('n')), Some(false));
+
+        let mut confirmation =
+            Confirmation::new(ConfirmAction::FactoryReset, "Reset?").with_expected("ab");
+        assert_eq!(confirmation.handle_key(KeyCode::Char('y')), None);
+        assert_eq!(confirmation.handle_key(KeyCode::Enter), None);
+        confirmation.handle_key(KeyCode::Backspace);
+        confirmation.handle_key(KeyCode::Char('a'));
+        confirmation.handle_key(KeyCode::Char('b'));
+        assert_eq!(confirmation.handle_key(KeyCode::Enter), Some(true));
+        assert_eq!(confirmation.handle_key(KeyCode::Esc), Some(false));
+    }
+
+    #[test]
+    fn test_handle_key_alternative() {
+        let conflicts = vec![BlobConflict {
+            path: "/tmp/fw.bin".to_owned(),
+            blob: "fw.bin".to_owned(),
+            etag: "0x8D".to_owned(),
+        }];
+        let confirmation = Confirmation::new(
+            ConfirmAction::OverwriteBlobs(UploadPurpose::Module, conflicts.clone()),
+            "Overwrite?",
+        )
+        .with_alternative(
+            'r',
+            "rename",
+            ConfirmAction::RenameBlobs(UploadPurpose::Module, conflicts.clone()),
+        );
+
+        let mut overwrite = confirmation.clone();
+        assert_eq!(overwrite.handle_key(KeyCode::Char('y')), Some(true));
+        assert!(matches!(
+            overwrite.action,
+            ConfirmAction::OverwriteBlobs(..)
+        ));
+
+        let mut rename = confirmation.clone();
+        assert_eq!(rename.handle_key(KeyCode::Char('r')), Some(true));
+        assert_eq!(
+            rename.action,
+            ConfirmAction::RenameBlobs(UploadPurpose::Module, conflicts)
+        );
+
+        let mut abort = confirmation;
+        assert_eq!(abort.handle_key(KeyCode::Char('n')), Some(false));
+    }
+}
@@ -0,0 +1,190 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::{DMError, DMErrorExt},
+    chrono::{DateTime, Local},
+    error_stack::Report,
+    std::{collections::VecDeque, fmt::Display},
+};
+
+/// Errors kept for the Errors popup
+const HISTORY_SIZE: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorSource {
+    App,
+    Mqtt,
+    Azurite,
+    File,
+    Script,
+}
+
+impl Display for ErrorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ErrorSource::App => "app",
+            ErrorSource::Mqtt => "mqtt",
+            ErrorSource::Azurite => "azurite",
+            ErrorSource::File => "file",
+            ErrorSource::Script => "script",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    /// The request could not be done as is, e.g. the device is not connected
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "WARN"),
+            Severity::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorEvent {
+    pub source: ErrorSource,
+    pub severity: Severity,
+    pub time: DateTime<Local>,
+    pub message: String,
+    pub action: Option<&'static str>,
+    /// Full error_stack report, with the frames of the lower layers
+    pub report: Option<String>,
+}
+
+impl ErrorEvent {
+    pub fn new(source: ErrorSource, severity: Severity, message: String) -> Self {
+        Self {
+            source,
+            severity,
+            time: Local::now(),
+            message,
+            action: suggested_action(source, None),
+            report: None,
+        }
+    }
+
+    pub fn from_report(source: ErrorSource, report: &Report<DMError>) -> Self {
+        let message = report
+            .error_str()
+            .unwrap_or_else(|| report.current_context().to_string());
+
+        Self {
+            action: suggested_action(source, Some(report.current_context())),
+            report: Some(format!("{report:?}")),
+            ..Self::new(source, Severity::Error, message)
+        }
+    }
+}
+
+fn suggested_action(source: ErrorSource, error: Option<&DMError>) -> Option<&'static str> {
+    match (source, error) {
+        (_, Some(DMError::Timeout)) => Some("Check that the device is online and retry"),
+        (_, Some(DMError::ReadOnly)) => {
+            Some("Restart device-monitor without --read-only to change the device")
+        }
+        (ErrorSource::Mqtt, Some(DMError::IOError)) => {
+            Some("Check that the broker is running, then restart device-monitor")
+        }
+        (ErrorSource::Mqtt, Some(DMError::ParserError)) => {
+            Some("The device sent a malformed message, check the topic and key in the report")
+        }
+        (ErrorSource::Mqtt, _) => Some("Check that the device is connected to the broker"),
+        (ErrorSource::Azurite, _) => Some("Check that Azurite is running at --azurite-url"),
+        (ErrorSource::File, _) => Some("Check the path and its permissions"),
+        (ErrorSource::Script, Some(DMError::ParserError)) => Some("Fix the line of the script"),
+        _ => None,
+    }
+}
+
+/// Errors of the session, newest first
+#[derive(Debug, Default)]
+pub struct ErrorLog {
+    events: VecDeque<ErrorEvent>,
+    /// The newest error is displayed in the footer until dismissed
+    current: bool,
+}
+
+impl ErrorLog {
+    pub fn push(&mut self, event: ErrorEvent) {
+        self.events.push_front(event);
+        self.events.truncate(HISTORY_SIZE);
+        self.current = true;
+    }
+
+    pub fn current(&self) -> Option<&ErrorEvent> {
+        self.current.then(|| self.events.front()).flatten()
+    }
+
+    pub fn dismiss(&mut self) {
+        self.current = false;
+    }
+
+    pub fn get(&self, index: usize) -> Option<&ErrorEvent> {
+        self.events.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ErrorEvent> {
+        self.events.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_log() {
+        let mut errors = ErrorLog::default();
+        assert!(errors.current().is_none());
+
+        errors.push(ErrorEvent::new(
+            ErrorSource::Mqtt,
+            Severity::Warning,
+            "Device is not connected.".to_owned(),
+        ));
+        let report = Report::new(DMError::IOError).attach_printable("Failed to list blobs");
+        errors.push(ErrorEvent::from_report(ErrorSource::Azurite, &report));
+
+        let current = errors.current().unwrap();
+        assert_eq!(current.message, "Failed to list blobs");
+        assert_eq!(current.severity, Severity::Error);
+        assert!(current.report.as_ref().unwrap().contains("IO error"));
+        assert!(current.action.unwrap().contains("Azurite"));
+
+        errors.dismiss();
+        assert!(errors.current().is_none());
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.get(1).unwrap().source, ErrorSource::Mqtt);
+    }
+}
@@ -0,0 +1,120 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct AiModelScreen;
+
+impl Screen for AiModelScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::AiModel
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Char('d') => {
+                let is_device_connected =
+                    with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected());
+                if is_device_connected {
+                    app.switch_to_ai_model_config_screen(DMScreenState::Initial);
+                } else {
+                    app.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_ai_model::draw(area, buf, app)
+    }
+}
+
+pub struct AiModelConfigScreen(pub DMScreenState);
+
+impl Screen for AiModelConfigScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::AiModelConfig(self.0)
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match self.0 {
+            DMScreenState::Initial => match key_event.code {
+                KeyCode::Char(c) if app.config_key_editable => {
+                    let value: &mut String = app.config_keys.get_mut(app.config_key_focus).unwrap();
+                    value.push(c);
+                }
+                KeyCode::Backspace if app.config_key_editable => {
+                    let value: &mut String = app.config_keys.get_mut(app.config_key_focus).unwrap();
+                    value.pop();
+                }
+                KeyCode::Esc if app.config_key_editable => app.config_key_editable = false,
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Enter if app.config_key_editable => app.config_key_editable = false,
+                KeyCode::Up | KeyCode::Char('k') => app.config_focus_up(),
+                KeyCode::Down | KeyCode::Char('j') => app.config_focus_down(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                KeyCode::Char('i') | KeyCode::Char('a') => {
+                    if ConfigKey::from(app.config_key_focus).is_sas_url_entry() {
+                        app.switch_to_evp_module_screen(AzuriteAction::Select);
+                    } else {
+                        app.config_key_editable = true;
+                    }
+                }
+                KeyCode::Char('w') => {
+                    app.warn_expired_sas_urls();
+                    app.config_result = Some(parse_ai_model_configuration(&app.config_keys));
+                    // we don't use configuring state here
+                    app.dm_screen_move_to(AiModelConfigScreen(DMScreenState::Completed));
+                }
+                _ => {}
+            },
+            DMScreenState::Configuring => {}
+            DMScreenState::Completed => match key_event.code {
+                KeyCode::Esc => {
+                    app.config_result = None;
+                    app.dm_screen_move_back();
+                }
+                KeyCode::Char('s') => {
+                    with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                        if let Some(Ok(config)) = &app.config_result {
+                            if let Err(e) = mqtt_ctrl.send_configure(config) {
+                                with_global_app_mut(|app| {
+                                    app.push_failure(
+                                        ErrorSource::Mqtt,
+                                        "Failed to send AiModel deployment configuration",
+                                        &e,
+                                    );
+                                });
+                            }
+                        }
+                    });
+                    app.dm_screen_move_back();
+                    app.dm_screen_move_back();
+                }
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_ai_model_config::draw(area, buf, app)
+    }
+}
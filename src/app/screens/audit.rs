@@ -0,0 +1,49 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+#[derive(Default)]
+pub struct AuditScreen {
+    /// Row of the operation list, newest operation first
+    focus: usize,
+}
+
+impl Screen for AuditScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Audit
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.focus = self.focus.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let count = with_audit_log(|audit_log| audit_log.recent().len()).unwrap_or(0);
+                if self.focus + 1 < count {
+                    self.focus += 1;
+                }
+            }
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_audit::draw(area, buf, app, self.focus)
+    }
+}
@@ -0,0 +1,118 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct CompareScreen {
+    /// State snapshot read from the reference file of the App
+    reference: Option<Result<Value, DMError>>,
+    /// First row displayed
+    offset: usize,
+    /// Only the fields which differ are listed
+    diff_only: bool,
+}
+
+impl CompareScreen {
+    pub fn open(app: &App) -> Self {
+        let mut screen = Self {
+            reference: None,
+            offset: 0,
+            diff_only: false,
+        };
+        screen.load_reference(app);
+        screen
+    }
+
+    /// Reads the reference state again, it may have been saved by another session since
+    fn load_reference(&mut self, app: &App) {
+        self.reference = if app.compare_file.is_empty() {
+            None
+        } else {
+            Some(crate::compare::load_reference(&app.compare_file))
+        };
+    }
+
+    /// Saves the state of the connected device as the reference, e.g. from the unit which
+    /// works before connecting the one which does not
+    fn save_reference(&mut self, app: &mut App) {
+        if app.compare_file.is_empty() {
+            app.push_warning(ErrorSource::App, "Enter the reference file first");
+            return;
+        }
+
+        let state = with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.state());
+        match crate::compare::save_reference(&app.compare_file, &state) {
+            Ok(()) => {
+                with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                    mqtt_ctrl.info = Some(trf("Reference saved to: {0}", &[&app.compare_file]))
+                });
+                self.load_reference(app);
+            }
+            Err(e) => app.push_failure(ErrorSource::File, "Failed to save the reference", &e),
+        }
+    }
+}
+
+impl Screen for CompareScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Compare
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        let file_index = usize::from(ConfigKey::CompareFile);
+
+        match key_event.code {
+            KeyCode::Char(c) if app.config_key_editable => app.config_keys[file_index].push(c),
+            KeyCode::Backspace if app.config_key_editable => {
+                app.config_keys[file_index].pop();
+            }
+            KeyCode::Esc if app.config_key_editable => {
+                app.config_key_editable = false;
+                app.config_keys[file_index] = app.compare_file.clone();
+            }
+            KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false;
+                app.compare_file = app.config_keys[file_index].trim().to_owned();
+                self.offset = 0;
+                self.load_reference(app);
+            }
+            KeyCode::Char('i') | KeyCode::Char('e') => app.config_key_editable = true,
+            KeyCode::Char('r') => self.load_reference(app),
+            KeyCode::Char('s') => self.save_reference(app),
+            KeyCode::Char('d') => {
+                self.diff_only = !self.diff_only;
+                self.offset = 0;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.offset = self.offset.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.offset += 1,
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_compare::draw(
+            area,
+            buf,
+            app,
+            self.reference.as_ref(),
+            self.diff_only,
+            self.offset,
+        )
+    }
+}
@@ -0,0 +1,185 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct ConfigurationUserScreen;
+
+impl Screen for ConfigurationUserScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::ConfigurationUser
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Esc if app.config_result.is_some() => app.config_result = None,
+            KeyCode::Char('Q') if app.config_result.is_some() => {
+                if let Some(options) = app.send_options.as_mut() {
+                    options.next_qos();
+                }
+            }
+            KeyCode::Char('R') if app.config_result.is_some() => {
+                if let Some(options) = app.send_options.as_mut() {
+                    options.retain = !options.retain;
+                }
+            }
+            KeyCode::Char('v') if app.config_result.is_some() => {
+                app.send_configuration_and_verify()
+            }
+            KeyCode::Char('s') => {
+                if let Some(Ok(s)) = app.config_result.as_ref() {
+                    let options = app.send_options;
+                    match with_mqtt_ctrl_mut(|mqtt_ctrl| match options {
+                        Some(options) => mqtt_ctrl.send_configure_with(s, options),
+                        None => mqtt_ctrl.send_configure(s),
+                    }) {
+                        Ok(()) => app.dm_screen_move_back(),
+                        Err(_) => {
+                            app.push_error(ErrorSource::Mqtt, "Failed to send configuration!")
+                        }
+                    }
+                }
+            }
+            KeyCode::Char('w') => match with_mqtt_ctrl(|mqtt_ctrl| {
+                mqtt_ctrl.parse_configure(None, app.main_window_focus())
+            }) {
+                Ok(s) => {
+                    if !s.is_empty() {
+                        app.preview_configuration(s);
+                    }
+                }
+                Err(e) => {
+                    app.config_result = Some(Err(e));
+                }
+            },
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_config_user::draw(area, buf, app)
+    }
+}
+
+#[derive(Default)]
+pub struct ConfigurationScreen {
+    /// Result of the proxy check of the network settings being edited
+    pub proxy_check: Option<ProxyReport>,
+    /// Result of the NTP query of the network settings being edited
+    pub ntp_check: Option<NtpReport>,
+}
+
+impl Screen for ConfigurationScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Configuration
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char(c) if app.config_key_editable => app.edit_focused_config_key().push(c),
+            KeyCode::Backspace if app.config_key_editable => {
+                app.edit_focused_config_key().pop();
+            }
+            KeyCode::Esc | KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false;
+                app.fill_default_storage_names();
+            }
+            KeyCode::Esc if app.config_result.is_some() => app.config_result = None,
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('Q') if app.config_result.is_some() => {
+                if let Some(options) = app.send_options.as_mut() {
+                    options.next_qos();
+                }
+            }
+            KeyCode::Char('R') if app.config_result.is_some() => {
+                if let Some(options) = app.send_options.as_mut() {
+                    options.retain = !options.retain;
+                }
+            }
+            KeyCode::Char('v') if app.config_result.is_some() => {
+                app.send_configuration_and_verify()
+            }
+            KeyCode::Char('s') => {
+                if let Some(Ok(s)) = app.config_result.as_ref() {
+                    let options = app.send_options;
+                    match with_mqtt_ctrl_mut(|mqtt_ctrl| match options {
+                        Some(options) => mqtt_ctrl.send_configure_with(s, options),
+                        None => mqtt_ctrl.send_configure(s),
+                    }) {
+                        Ok(()) => app.dm_screen_move_back(),
+                        Err(_) => {
+                            app.push_error(ErrorSource::Mqtt, "Failed to send configuration!")
+                        }
+                    }
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => app.config_focus_up(),
+            KeyCode::Down | KeyCode::Char('j') => app.config_focus_down(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Tab => app.config_focus_down(),
+            KeyCode::Char('J') => app.config_focus_section_jump(true),
+            KeyCode::Char('K') => app.config_focus_section_jump(false),
+            KeyCode::Char(' ') => app.config_toggle_section(),
+            KeyCode::Char('r') if app.config_result.is_none() => app.config_reset_focused(),
+            KeyCode::Char('x') if app.config_result.is_none() => app.config_clear_focused(),
+            KeyCode::Char('p') if app.main_window_focus() == MainWindowFocus::NetworkSettings => {
+                self.proxy_check = None;
+                app.check_proxy();
+            }
+            KeyCode::Char('n') if app.main_window_focus() == MainWindowFocus::NetworkSettings => {
+                self.ntp_check = None;
+                app.check_ntp_server();
+            }
+            KeyCode::Char('i') | KeyCode::Char('a') => {
+                let current_config_key = ConfigKey::from(app.config_key_focus);
+                if app.is_section_collapsed(current_config_key.group()) {
+                    app.config_toggle_section();
+                } else if App::is_log_storage_config_key(current_config_key) {
+                    app.switch_to_token_provider_screen(Some(current_config_key));
+                } else {
+                    app.config_key_editable = true;
+                }
+            }
+            //Previous screen is used to judge what to be configured.
+            KeyCode::Char('w') => match with_mqtt_ctrl(|mqtt_ctrl| {
+                mqtt_ctrl.parse_configure(Some(&app.dirty_config_keys()), app.main_window_focus())
+            }) {
+                Ok(s) => {
+                    if !s.is_empty() {
+                        app.preview_configuration(s);
+                    }
+                }
+                Err(e) => {
+                    app.config_result = Some(Err(e));
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_config::draw(
+            area,
+            buf,
+            app,
+            self.ntp_check.as_ref(),
+            self.proxy_check.as_ref(),
+        )
+    }
+}
@@ -0,0 +1,54 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct DebugLogScreen;
+
+impl Screen for DebugLogScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::DebugLog
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        let Some(debug_log) = app.debug_log.as_mut() else {
+            match key_event.code {
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            }
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => debug_log.scroll_up(1),
+            KeyCode::Down | KeyCode::Char('j') => debug_log.scroll_down(1),
+            KeyCode::PageUp => debug_log.scroll_up(ui::ui_log_viewer::PAGE_LINES),
+            KeyCode::PageDown => debug_log.scroll_down(ui::ui_log_viewer::PAGE_LINES),
+            KeyCode::Char('g') => debug_log.scroll_top(),
+            KeyCode::Char('G') => debug_log.follow(),
+            KeyCode::Char('l') => debug_log.cycle_level(),
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_debug_log::draw(area, buf, app)
+    }
+}
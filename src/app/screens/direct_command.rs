@@ -0,0 +1,193 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct DirectCommandScreen;
+
+impl Screen for DirectCommandScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::DirectCommand
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        let command = with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.get_direct_command());
+        match command {
+            Some(DirectCommand::GetDirectImage) => {
+                let has_request =
+                    with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.direct_command_request().is_some());
+
+                if !has_request {
+                    match key_event.code {
+                        KeyCode::Char(c) if app.config_key_editable => {
+                            let value: &mut String =
+                                app.config_keys.get_mut(app.config_key_focus).unwrap();
+                            value.push(c);
+                        }
+                        KeyCode::Backspace if app.config_key_editable => {
+                            let value: &mut String =
+                                app.config_keys.get_mut(app.config_key_focus).unwrap();
+                            value.pop();
+                        }
+                        KeyCode::Esc if app.config_key_editable => app.config_key_editable = false,
+                        KeyCode::Esc => app.dm_screen_move_back(),
+                        KeyCode::Enter if app.config_key_editable => {
+                            app.config_key_editable = false
+                        }
+                        KeyCode::Tab => app.config_focus_down(),
+                        KeyCode::Char('i') | KeyCode::Char('a') => app.config_key_editable = true,
+                        KeyCode::Up | KeyCode::Char('k') => app.config_focus_up(),
+                        KeyCode::Down | KeyCode::Char('j') => app.config_focus_down(),
+                        KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                        KeyCode::Char('s') => with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                            let _ = mqtt_ctrl.send_rpc_direct_get_image(&app.config_keys);
+                        }),
+
+                        _ => {}
+                    }
+                } else {
+                    match key_event.code {
+                        KeyCode::Esc => app.dm_screen_move_back(),
+                        KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                        KeyCode::Char('w') => {
+                            match with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.save_direct_get_image())
+                            {
+                                Ok(image_path) => {
+                                    with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                                        mqtt_ctrl.info =
+                                            Some(format!("Image saved to: {}", image_path))
+                                    });
+                                }
+                                Err(e) => {
+                                    app.push_failure(
+                                        ErrorSource::File,
+                                        "Failed to save preview image",
+                                        &e,
+                                    );
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Some(DirectCommand::Custom) => {
+                let has_request =
+                    with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.direct_command_request().is_some());
+
+                match key_event.code {
+                    KeyCode::Char(c) if app.config_key_editable => {
+                        let value: &mut String =
+                            app.config_keys.get_mut(app.config_key_focus).unwrap();
+                        value.push(c);
+                    }
+                    KeyCode::Backspace if app.config_key_editable => {
+                        let value: &mut String =
+                            app.config_keys.get_mut(app.config_key_focus).unwrap();
+                        value.pop();
+                    }
+                    KeyCode::Esc | KeyCode::Enter if app.config_key_editable => {
+                        app.config_key_editable = false
+                    }
+                    KeyCode::Esc => app.dm_screen_move_back(),
+                    KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                    // Back to the fields, which are kept to send a variant
+                    KeyCode::Char('e') if has_request => with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                        mqtt_ctrl.direct_command_clear();
+                        mqtt_ctrl.set_direct_command(Some(DirectCommand::Custom));
+                    }),
+                    _ if has_request => {}
+                    KeyCode::Char('i') | KeyCode::Char('a') => app.config_key_editable = true,
+                    KeyCode::Tab | KeyCode::Down | KeyCode::Char('j') => app.config_focus_down(),
+                    KeyCode::Up | KeyCode::Char('k') => app.config_focus_up(),
+                    KeyCode::Char('s') => {
+                        with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.send_rpc_custom(&app.config_keys))
+                    }
+                    _ => {}
+                }
+            }
+            None => {
+                jdebug!(
+                    func = "App::handle_key_event()",
+                    screen = "DirectCommand",
+                    line = line!()
+                );
+                match key_event.code {
+                    KeyCode::Char('r') => {
+                        jdebug!(func = "App::handle_key_event()", event = "Set Reboot",);
+                        with_mqtt_ctrl_mut(|ctrl| {
+                            ctrl.set_direct_command(Some(DirectCommand::Reboot))
+                        })
+                    }
+                    KeyCode::Char('i') => {
+                        jdebug!(
+                            func = "App::handle_key_event()",
+                            event = "Set DirectGetImage",
+                        );
+                        with_mqtt_ctrl_mut(|ctrl| {
+                            ctrl.set_direct_command(Some(DirectCommand::GetDirectImage))
+                        });
+                        app.config_key_focus_start = ConfigKey::DirectGetImageSensorName.into();
+                        app.config_key_focus_end = ConfigKey::DirectGetImageNetworkId.into();
+                        app.config_key_focus = app.config_key_focus_start;
+                    }
+                    KeyCode::Char('c') => {
+                        jdebug!(func = "App::handle_key_event()", event = "Set Custom",);
+                        with_mqtt_ctrl_mut(|ctrl| {
+                            ctrl.set_direct_command(Some(DirectCommand::Custom))
+                        });
+                        app.config_key_focus_start = ConfigKey::CustomRpcMethod.into();
+                        app.config_key_focus_end = ConfigKey::CustomRpcParams.into();
+                        app.config_key_focus = app.config_key_focus_start;
+                    }
+                    KeyCode::Char('f') => {
+                        // Typing the device ID makes sure the reset targets the intended device
+                        let device_id = with_mqtt_ctrl(|ctrl| {
+                            ctrl.device_info()
+                                .and_then(|info| info.main_chip())
+                                .map(|chip| chip.id().to_owned())
+                        })
+                        .filter(|id| !id.is_empty())
+                        .unwrap_or("FactoryReset".to_owned());
+                        app.confirmation = Some(
+                            Confirmation::new(
+                                ConfirmAction::FactoryReset,
+                                tr(
+                                    "Factory reset the device? All its settings and deployments are erased.",
+                                ),
+                            )
+                            .with_expected(&device_id),
+                        );
+                    }
+                    KeyCode::Esc => app.dm_screen_move_back(),
+                    KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+
+                    _ => {}
+                }
+            }
+            _ => match key_event.code {
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_directcmd::draw(area, buf, app)
+    }
+}
@@ -0,0 +1,91 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct DtmiScreen {
+    model: Option<Result<DtdlModel, DMError>>,
+    scroll: u16,
+}
+
+impl DtmiScreen {
+    pub fn open(app: &App) -> Self {
+        let mut screen = Self {
+            model: None,
+            scroll: 0,
+        };
+        screen.fetch(app);
+        screen
+    }
+
+    /// Reads the model the device claims to implement from the repository
+    fn fetch(&mut self, app: &App) {
+        self.scroll = 0;
+        let schema = with_mqtt_ctrl(|mqtt_ctrl| {
+            mqtt_ctrl
+                .device_reserved()
+                .map(|reserved| reserved.schema().to_owned())
+        });
+
+        self.model = Some(match schema {
+            Some(dtmi) => crate::dtmi::fetch(&app.dtmi_repository, &dtmi)
+                .and_then(|model| DtdlModel::parse(&model, &dtmi)),
+            None => Err(Report::new(DMError::InvalidData)
+                .attach_printable("The device has not reported its DTMI yet")),
+        });
+    }
+}
+
+impl Screen for DtmiScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Dtmi
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        let code = key_event.code;
+        let repository_index = usize::from(ConfigKey::DtmiRepository);
+
+        match code {
+            KeyCode::Char(c) if app.config_key_editable => {
+                app.config_keys[repository_index].push(c)
+            }
+            KeyCode::Backspace if app.config_key_editable => {
+                app.config_keys[repository_index].pop();
+            }
+            KeyCode::Esc if app.config_key_editable => {
+                app.config_key_editable = false;
+                app.config_keys[repository_index] = app.dtmi_repository.clone();
+            }
+            KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false;
+                app.dtmi_repository = app.config_keys[repository_index].trim().to_owned();
+                self.fetch(app);
+            }
+            KeyCode::Char('i') | KeyCode::Char('e') => app.config_key_editable = true,
+            KeyCode::Char('r') => self.fetch(app),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_dtmi::draw(area, buf, app, self.model.as_ref(), self.scroll)
+    }
+}
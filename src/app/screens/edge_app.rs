@@ -0,0 +1,200 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct EdgeAppInstancesScreen {
+    focus: usize,
+}
+
+impl EdgeAppInstancesScreen {
+    pub fn new(focus: usize) -> Self {
+        Self { focus }
+    }
+}
+
+impl Screen for EdgeAppInstancesScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::EdgeAppInstances
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Up | KeyCode::Char('k') => self.focus = self.focus.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j')
+                if self.focus + 1 < App::edge_app_instance_ids().len() =>
+            {
+                self.focus += 1;
+            }
+            KeyCode::Enter => {
+                if let Some(instance_id) = App::edge_app_instance_ids().into_iter().nth(self.focus)
+                {
+                    app.edge_app_instance = Some(instance_id);
+                    app.dm_screen_move_to(EdgeAppScreen(DMScreenState::Initial));
+                } else {
+                    app.push_warning(ErrorSource::Mqtt, "No Edge App instances found.");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_edge_app_instances::draw(area, buf, app, self.focus)
+    }
+}
+
+pub struct EdgeAppScreen(pub DMScreenState);
+
+impl Screen for EdgeAppScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::EdgeApp(self.0)
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match self.0 {
+            DMScreenState::Initial => match key_event.code {
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                KeyCode::Char('e') => {
+                    app.config_key_focus_start = ConfigKey::CommonSettingsProcessState.into();
+                    app.config_key_focus_end = ConfigKey::CustomSettings.into();
+                    app.config_key_focus = app.config_key_focus_start;
+
+                    // Start from the custom settings reported by the instance
+                    let custom_settings = usize::from(ConfigKey::CustomSettings);
+                    if app.config_keys[custom_settings].is_empty()
+                        && let Some(instance_id) = app.edge_app_instance.as_deref()
+                        && let Some(custom) = with_mqtt_ctrl(|mqtt_ctrl| {
+                            mqtt_ctrl.edge_app(instance_id).and_then(|edge_app| {
+                                edge_app
+                                    .module()
+                                    .custom_settings()
+                                    .and_then(|c| c.custom())
+                                    .map(|c| c.to_owned())
+                            })
+                        })
+                    {
+                        app.config_keys[custom_settings] = custom;
+                    }
+
+                    // Detection parameters currently used by the instance
+                    if let Some(instance_id) = app.edge_app_instance.as_deref()
+                        && let Some(values) = with_mqtt_ctrl(|mqtt_ctrl| {
+                            mqtt_ctrl
+                                .edge_app(instance_id)
+                                .and_then(|edge_app| edge_app.module().custom_settings())
+                                .and_then(|c| c.ai_model_detection())
+                                .map(|detection| detection.config_values())
+                        })
+                    {
+                        for (config_key, value) in DETECTION_CONFIG_KEYS.iter().zip(values) {
+                            let entry = &mut app.config_keys[usize::from(*config_key)];
+                            if entry.is_empty()
+                                && let Some(value) = value
+                            {
+                                *entry = value;
+                            }
+                        }
+                    }
+                    app.dm_screen_move_to(EdgeAppScreen(DMScreenState::Configuring));
+                }
+                _ => {}
+            },
+            DMScreenState::Configuring => match key_event.code {
+                KeyCode::Char(c) if app.config_key_editable => {
+                    let value: &mut String = app.config_keys.get_mut(app.config_key_focus).unwrap();
+                    value.push(c);
+                }
+                KeyCode::Backspace if app.config_key_editable => {
+                    let value: &mut String = app.config_keys.get_mut(app.config_key_focus).unwrap();
+                    value.pop();
+                }
+                KeyCode::Esc | KeyCode::Enter if app.config_key_editable => {
+                    app.config_key_editable = false;
+                    app.fill_default_storage_names();
+                }
+                KeyCode::Esc if app.config_result.is_some() => app.config_result = None,
+                KeyCode::Up | KeyCode::Char('k') => {
+                    app.config_focus_up();
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    app.config_focus_down();
+                }
+                KeyCode::Char('w') => {
+                    let mut edge_app_result = None;
+
+                    if let Some(instance_id) = app.edge_app_instance.as_deref() {
+                        with_mqtt_ctrl(|ctrl| {
+                            edge_app_result = Some(match ctrl.edge_app(instance_id) {
+                                Some(edge_app) => edge_app.parse_configure(&app.config_keys),
+                                None => {
+                                    EdgeAppInfo::new(instance_id).parse_configure(&app.config_keys)
+                                }
+                            });
+                        });
+                    }
+
+                    if let Some(result) = edge_app_result {
+                        app.config_result = match result {
+                            Ok(s) => Some(Ok(s)),
+                            Err(e) => Some(Err(e)),
+                        };
+                        app.dm_screen_move_to(EdgeAppScreen(DMScreenState::Completed));
+                    } else {
+                        app.push_warning(ErrorSource::Mqtt, "No Edge App instances found.");
+                        app.dm_screen_move_back();
+                    }
+                }
+                KeyCode::Char('i') | KeyCode::Char('a') => app.config_key_editable = true,
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+            DMScreenState::Completed => match key_event.code {
+                KeyCode::Char('s') => {
+                    // Send the configuration to the selected instance, go back to the default state
+                    if let Some(Ok(config)) = &app.config_result
+                        && let Err(e) =
+                            with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.send_configure(config))
+                    {
+                        app.push_failure(
+                            ErrorSource::Mqtt,
+                            "Failed to send EdgeApp configuration",
+                            &e,
+                        );
+                        return;
+                    }
+
+                    app.config_result = None;
+                    app.config_key_clear();
+                    app.dm_screen_move_back();
+                    app.dm_screen_move_back();
+                }
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_edge_app::draw(area, buf, app)
+    }
+}
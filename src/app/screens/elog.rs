@@ -0,0 +1,67 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {super::*, crate::app::ui::ui_elog::ElogTab};
+
+#[derive(Default)]
+pub struct ElogScreen {
+    tab: ElogTab,
+}
+
+impl Screen for ElogScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Elog
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Tab => self.tab = self.tab.toggle(),
+            KeyCode::Char('z') => app.toggle_time_zone(),
+
+            KeyCode::Char('w') => match with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.save_elogs()) {
+                Ok(elog_path) => {
+                    with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                        mqtt_ctrl.info = Some(trf("Elog saved to: {0}", &[&elog_path]))
+                    });
+                }
+                Err(e) => {
+                    app.push_report(ErrorSource::File, &e);
+                }
+            },
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                let result = if key_event.code == KeyCode::Char('c') {
+                    with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.save_elogs_csv())
+                } else {
+                    with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.save_metrics_csv())
+                };
+                match result {
+                    Ok(path) => with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                        mqtt_ctrl.info = Some(trf("CSV saved to: {0}", &[&path]))
+                    }),
+                    Err(e) => app.push_report(ErrorSource::File, &e),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_elog::draw(area, buf, app, self.tab)
+    }
+}
@@ -0,0 +1,203 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {super::*, crate::app::ui::ui_deploy::BatchSummary};
+
+#[derive(Default)]
+pub struct EvpModuleScreen {
+    /// Blob names of the modules marked in the list
+    pub marked: BTreeSet<String>,
+    /// Result of the last batch upload or removal, until a key is pressed
+    pub batch_summary: Option<BatchSummary>,
+    /// Module deployed again after the warning that it is already running
+    noop_deploy_warned: Option<String>,
+}
+
+impl Screen for EvpModuleScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::EvpModule
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            _ if self.batch_summary.is_some() => self.batch_summary = None,
+
+            _ if with_azurite_storage(|storage| storage.action() == Some(AzuriteAction::Add))
+                .unwrap_or(false) =>
+            {
+                app.handle_file_browser_key(key_event.code);
+            }
+
+            KeyCode::Esc | KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false
+            }
+            code @ (KeyCode::Char(_) | KeyCode::Backspace) if app.config_key_editable => {
+                app.edit_module_filter(code)
+            }
+            KeyCode::Char('/') if app.config_result.is_none() => app.config_key_editable = true,
+            KeyCode::Char('o') if app.config_result.is_none() => {
+                with_azurite_storage_mut(|azurite_storage| azurite_storage.sort_modules_by_next());
+            }
+
+            KeyCode::Enter => {
+                if with_azurite_storage(|storage| storage.action() == Some(AzuriteAction::Select))
+                    .unwrap_or(false)
+                {
+                    let warning = app.update_sas_url_entries();
+                    app.dm_screen_move_back();
+                    if let Some(warning) = warning {
+                        app.push_warning(ErrorSource::App, warning);
+                    }
+                }
+            }
+
+            KeyCode::Char('a') => {
+                let browser = match app.file_browser.take() {
+                    Some(mut browser) => browser.refresh().map(|_| browser),
+                    None => ui::ui_file_browser::FileBrowserState::from_current_dir(),
+                };
+
+                match browser {
+                    Ok(browser) => {
+                        app.file_browser = Some(browser);
+                        with_azurite_storage_mut(|azurite_storage| {
+                            azurite_storage.push_action(AzuriteAction::Add);
+                        });
+                    }
+                    Err(e) => {
+                        app.push_failure(ErrorSource::File, "Failed to open file browser", &e);
+                    }
+                }
+            }
+
+            KeyCode::Char(' ') if app.config_result.is_none() => {
+                if let Some(module_name) = with_azurite_storage(|azurite_storage| {
+                    azurite_storage
+                        .current_module()
+                        .map(|m| m.blob_name.clone())
+                })
+                .flatten()
+                    && !self.marked.remove(&module_name)
+                {
+                    self.marked.insert(module_name);
+                }
+            }
+
+            KeyCode::Char('r') if !self.marked.is_empty() => {
+                app.confirmation = Some(Confirmation::new(
+                    ConfirmAction::RemoveMarkedModules,
+                    trf(
+                        "Remove the {0} marked modules from the storage?",
+                        &[&self.marked.len()],
+                    ),
+                ));
+            }
+
+            KeyCode::Char('r') => {
+                if let Some(module_name) = with_azurite_storage(|azurite_storage| {
+                    azurite_storage
+                        .current_module()
+                        .map(|m| m.blob_name.clone())
+                })
+                .flatten()
+                {
+                    app.confirmation = Some(Confirmation::new(
+                        ConfirmAction::RemoveModule(module_name.clone()),
+                        trf("Remove {0} from the storage?", &[&module_name]),
+                    ));
+                }
+            }
+
+            KeyCode::Esc if app.config_result.is_some() => app.config_result = None,
+            KeyCode::Char('d')
+                if with_azurite_storage(|az| az.action() == Some(AzuriteAction::Deploy))
+                    .unwrap_or(false) =>
+            {
+                if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
+                    if let Some(module) = with_azurite_storage(|azurite_storage| {
+                        azurite_storage.current_module().cloned()
+                    })
+                    .flatten()
+                    {
+                        let module_id = module.id.uuid().to_owned();
+                        if app.running_module_hashes().contains(&module.hash)
+                            && self.noop_deploy_warned.as_ref() != Some(&module_id)
+                        {
+                            self.noop_deploy_warned = Some(module_id);
+                            app.push_warning(ErrorSource::App, trf(
+                                "{0} is already running on the device, press (d) again to deploy anyway.",
+                                &[&module.blob_name],
+                            ));
+                        } else {
+                            self.noop_deploy_warned = None;
+                            app.config_result = Some(module.deployment_json());
+                        }
+                    }
+                } else {
+                    app.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+                }
+            }
+
+            KeyCode::Char('u')
+                if with_azurite_storage(|az| az.action() == Some(AzuriteAction::Deploy))
+                    .unwrap_or(false) =>
+            {
+                if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
+                    app.config_result = Some(ModuleInfo::undeployment_json());
+                } else {
+                    app.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+                }
+            }
+
+            KeyCode::Char('s') => {
+                if with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected()) {
+                    if let Some(Ok(deploy)) = &app.config_result {
+                        let deploy = deploy.clone();
+                        if deployment_module_hashes(&deploy).is_empty() {
+                            app.confirmation = Some(Confirmation::new(
+                                ConfirmAction::Undeploy(deploy),
+                                tr("Remove all the modules deployed on the device?"),
+                            ));
+                        } else {
+                            app.send_deployment(&deploy);
+                        }
+                    }
+                } else {
+                    app.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+                }
+            }
+
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Up | KeyCode::Char('k') => {
+                with_azurite_storage_mut(|azurite_storage| {
+                    azurite_storage.current_module_focus_up();
+                });
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                with_azurite_storage_mut(|azurite_storage| {
+                    azurite_storage.current_module_focus_down();
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_deploy::draw(area, buf, app, &self.marked, self.batch_summary.as_ref())
+    }
+}
@@ -0,0 +1,44 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+/// Exit confirmation
+pub struct ExitingScreen;
+
+impl Screen for ExitingScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Exiting
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('y') => {
+                app.exit = true;
+            }
+            KeyCode::Char('n') => {
+                app.dm_screen_move_back();
+                app.exit = false;
+            }
+            _ => {}
+        };
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_exit::draw(area, buf, app)
+    }
+}
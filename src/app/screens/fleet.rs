@@ -0,0 +1,130 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct FleetScreen {
+    /// Devices read from the fleet directory of the App
+    pub fleet: Option<Result<Vec<FleetDevice>, DMError>>,
+    /// Row focused, in the sorted order
+    pub focus: usize,
+    pub sort: FleetColumn,
+    pub descending: bool,
+}
+
+impl FleetScreen {
+    pub fn open(app: &App) -> Self {
+        let mut screen = Self {
+            fleet: None,
+            focus: 0,
+            sort: FleetColumn::Device,
+            descending: false,
+        };
+        screen.load(app);
+        screen
+    }
+
+    /// Reads the newest snapshots again, the daemons keep writing them
+    fn load(&mut self, app: &App) {
+        self.fleet = if app.fleet_dir.is_empty() {
+            None
+        } else {
+            Some(crate::fleet::load_fleet(
+                &app.fleet_dir,
+                Local::now().fixed_offset(),
+            ))
+        };
+    }
+
+    /// Devices in the sorted order, the device of this session among them
+    pub fn rows(&self) -> Vec<FleetDevice> {
+        let mut rows = vec![with_mqtt_ctrl(|mqtt_ctrl| {
+            FleetDevice::from_state(
+                "this device",
+                &mqtt_ctrl.state(),
+                mqtt_ctrl.elogs(),
+                Local::now().fixed_offset(),
+            )
+        })];
+        if let Some(Ok(devices)) = &self.fleet {
+            rows.extend(devices.iter().cloned());
+        }
+        crate::fleet::sort(&mut rows, self.sort, self.descending);
+        rows
+    }
+
+    /// Drills into the focused device: the main screen for the device of this session, the
+    /// Compare screen with the newest snapshot as reference for the others
+    fn open_device(&self, app: &mut App) {
+        let Some(device) = self.rows().into_iter().nth(self.focus) else {
+            return;
+        };
+
+        match device.snapshot {
+            None => app.dm_screen_jump_back(1),
+            Some(path) => {
+                app.compare_file = path.to_string_lossy().into_owned();
+                app.switch_to_compare_screen();
+            }
+        }
+    }
+}
+
+impl Screen for FleetScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Fleet
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        let dir_index = usize::from(ConfigKey::FleetDir);
+
+        match key_event.code {
+            KeyCode::Char(c) if app.config_key_editable => app.config_keys[dir_index].push(c),
+            KeyCode::Backspace if app.config_key_editable => {
+                app.config_keys[dir_index].pop();
+            }
+            KeyCode::Esc if app.config_key_editable => {
+                app.config_key_editable = false;
+                app.config_keys[dir_index] = app.fleet_dir.clone();
+            }
+            KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false;
+                app.fleet_dir = app.config_keys[dir_index].trim().to_owned();
+                self.focus = 0;
+                self.load(app);
+            }
+            KeyCode::Char('i') | KeyCode::Char('e') => app.config_key_editable = true,
+            KeyCode::Char('r') => self.load(app),
+            KeyCode::Up | KeyCode::Char('k') => self.focus = self.focus.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let rows = self.rows().len();
+                self.focus = (self.focus + 1).min(rows.saturating_sub(1));
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.sort = self.sort.prev(),
+            KeyCode::Right | KeyCode::Char('l') => self.sort = self.sort.next(),
+            KeyCode::Char('o') => self.descending = !self.descending,
+            KeyCode::Enter => self.open_device(app),
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_fleet::draw(area, buf, app, self)
+    }
+}
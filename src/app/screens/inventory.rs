@@ -0,0 +1,74 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct InventoryScreen {
+    inventory: Option<Result<Inventory, DMError>>,
+}
+
+impl InventoryScreen {
+    pub fn open(app: &App) -> Self {
+        let mut screen = Self { inventory: None };
+        screen.load(app);
+        screen
+    }
+
+    /// Reads the inventory file again, it may have been edited since
+    fn load(&mut self, app: &App) {
+        self.inventory = if app.inventory_file.is_empty() {
+            None
+        } else {
+            Some(Inventory::load(&app.inventory_file))
+        };
+    }
+}
+
+impl Screen for InventoryScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Inventory
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        let file_index = usize::from(ConfigKey::InventoryFile);
+
+        match key_event.code {
+            KeyCode::Char(c) if app.config_key_editable => app.config_keys[file_index].push(c),
+            KeyCode::Backspace if app.config_key_editable => {
+                app.config_keys[file_index].pop();
+            }
+            KeyCode::Esc if app.config_key_editable => {
+                app.config_key_editable = false;
+                app.config_keys[file_index] = app.inventory_file.clone();
+            }
+            KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false;
+                app.inventory_file = app.config_keys[file_index].trim().to_owned();
+                self.load(app);
+            }
+            KeyCode::Char('i') | KeyCode::Char('e') => app.config_key_editable = true,
+            KeyCode::Char('r') => self.load(app),
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_inventory::draw(area, buf, app, self.inventory.as_ref())
+    }
+}
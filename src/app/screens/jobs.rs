@@ -0,0 +1,64 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+#[derive(Default)]
+pub struct JobsScreen {
+    /// Row of the job list, newest job first
+    focus: usize,
+}
+
+impl JobsScreen {
+    fn focus_down(&mut self, app: &App) {
+        if self.focus + 1 < app.jobs.records().len() {
+            self.focus += 1;
+        }
+    }
+
+    /// Cancels the focused job if it has not started yet
+    fn cancel_focused_job(&self, app: &mut App) {
+        let Some(record) = app.jobs.records().into_iter().rev().nth(self.focus) else {
+            return;
+        };
+
+        if !app.jobs.cancel(record.id) {
+            app.push_warning(ErrorSource::App, "Only queued jobs can be cancelled");
+        }
+    }
+}
+
+impl Screen for JobsScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Jobs
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.focus = self.focus.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.focus_down(app),
+            KeyCode::Char('c') => self.cancel_focused_job(app),
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_jobs::draw(area, buf, app, self.focus)
+    }
+}
@@ -0,0 +1,126 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+#[derive(Default)]
+pub struct LogSettingsScreen {
+    /// Choice highlighted in the dropdown of the focused cell, while it is open
+    pub choice: Option<usize>,
+}
+
+impl LogSettingsScreen {
+    /// Opens the dropdown, the token provider list or the text input of the focused cell
+    fn edit(&mut self, app: &mut App) {
+        let config_key = ConfigKey::from(app.config_key_focus);
+        if !config_key.choices().is_empty() {
+            let current = app.config_keys[app.config_key_focus].parse().unwrap_or(0);
+            self.choice = Some(current);
+        } else if App::is_log_storage_config_key(config_key) {
+            app.switch_to_token_provider_screen(Some(config_key));
+        } else {
+            app.config_key_editable = true;
+        }
+    }
+
+    fn choice_move(&mut self, app: &App, down: bool) {
+        let choices = ConfigKey::from(app.config_key_focus).choices().len();
+        if let Some(choice) = self.choice.as_mut()
+            && choices > 0
+        {
+            *choice = if down {
+                (*choice + 1) % choices
+            } else {
+                (*choice + choices - 1) % choices
+            };
+        }
+    }
+
+    fn choose(&mut self, app: &mut App) {
+        if let Some(choice) = self.choice.take() {
+            *app.edit_focused_config_key() = choice.to_string();
+        }
+        app.fill_default_storage_names();
+    }
+}
+
+impl Screen for LogSettingsScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::LogSettings
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char(c) if app.config_key_editable => app.edit_focused_config_key().push(c),
+            KeyCode::Backspace if app.config_key_editable => {
+                app.edit_focused_config_key().pop();
+            }
+            KeyCode::Esc | KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false;
+                app.fill_default_storage_names();
+            }
+            KeyCode::Up | KeyCode::Char('k') if self.choice.is_some() => {
+                self.choice_move(app, false)
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.choice.is_some() => {
+                self.choice_move(app, true)
+            }
+            KeyCode::Enter if self.choice.is_some() => self.choose(app),
+            KeyCode::Esc if self.choice.is_some() => self.choice = None,
+            KeyCode::Esc if app.config_result.is_some() => app.config_result = None,
+            KeyCode::Char('Q') if app.config_result.is_some() => {
+                if let Some(options) = app.send_options.as_mut() {
+                    options.next_qos();
+                }
+            }
+            KeyCode::Char('R') if app.config_result.is_some() => {
+                if let Some(options) = app.send_options.as_mut() {
+                    options.retain = !options.retain;
+                }
+            }
+            KeyCode::Char('s') => {
+                if let Some(Ok(s)) = app.config_result.as_ref() {
+                    let options = app.send_options;
+                    match with_mqtt_ctrl_mut(|mqtt_ctrl| match options {
+                        Some(options) => mqtt_ctrl.send_configure_with(s, options),
+                        None => mqtt_ctrl.send_configure(s),
+                    }) {
+                        Ok(()) => app.dm_screen_move_back(),
+                        Err(_) => {
+                            app.push_error(ErrorSource::Mqtt, "Failed to send configuration!")
+                        }
+                    }
+                }
+            }
+            _ if app.config_result.is_some() => {}
+            KeyCode::Up | KeyCode::Char('k') => app.log_settings_move(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => app.log_settings_move(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => app.log_settings_move(0, -1),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => app.log_settings_move(0, 1),
+            KeyCode::Enter | KeyCode::Char('i') | KeyCode::Char('a') => self.edit(app),
+            KeyCode::Char('x') => app.edit_focused_config_key().clear(),
+            KeyCode::Char('w') => app.preview_log_settings(),
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_log_settings::draw(area, buf, app, self.choice)
+    }
+}
@@ -0,0 +1,157 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::*,
+    crate::app::ui::{ui_log_viewer::LogViewerState, ui_token_provider_blobs::BlobListState},
+};
+
+pub struct LogFilesScreen {
+    state: BlobListState,
+}
+
+impl LogFilesScreen {
+    pub fn new(state: BlobListState) -> Self {
+        Self { state }
+    }
+
+    pub fn state_mut(&mut self) -> &mut BlobListState {
+        &mut self.state
+    }
+}
+
+impl Screen for LogFilesScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::LogFiles
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.state.move_up();
+                app.list_more_blobs(&mut self.state, true);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.state.move_down();
+                app.list_more_blobs(&mut self.state, true);
+            }
+            KeyCode::Enter => {
+                if let Some(blob) = self.state.current_blob() {
+                    let container_name = self.state.container_name.clone();
+                    let blob_name = blob.name.clone();
+
+                    match with_azurite_storage(|azurite_storage| {
+                        azurite_storage.get_blob(Some(&container_name), &blob_name)
+                    }) {
+                        Some(Ok(content)) => app.dm_screen_move_to(LogViewerScreen {
+                            state: LogViewerState::new(&blob_name, &content),
+                        }),
+                        Some(Err(e)) => {
+                            app.push_failure(
+                                ErrorSource::Azurite,
+                                "Failed to download log file",
+                                &e,
+                            );
+                        }
+                        None => {
+                            app.push_error(ErrorSource::Azurite, "Azurite storage not available");
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
+        ui::ui_log_files::draw(area, buf, &self.state)
+    }
+}
+
+pub struct LogViewerScreen {
+    pub state: LogViewerState,
+}
+
+impl Screen for LogViewerScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::LogViewer
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        let viewer = &mut self.state;
+
+        if viewer.search_editing {
+            match key_event.code {
+                KeyCode::Char(c) => viewer.search.push(c),
+                KeyCode::Backspace => {
+                    viewer.search.pop();
+                }
+                KeyCode::Enter => {
+                    viewer.search_editing = false;
+                    if !viewer.search.is_empty() && !viewer.search_first() {
+                        app.errors.push(ErrorEvent::new(
+                            ErrorSource::App,
+                            Severity::Warning,
+                            format!("Pattern not found: {}", viewer.search),
+                        ));
+                    }
+                }
+                KeyCode::Esc => viewer.search_editing = false,
+                _ => {}
+            }
+        } else {
+            match key_event.code {
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                KeyCode::Up | KeyCode::Char('k') => viewer.scroll_up(1),
+                KeyCode::Down | KeyCode::Char('j') => viewer.scroll_down(1),
+                KeyCode::PageUp => viewer.scroll_up(ui::ui_log_viewer::PAGE_LINES),
+                KeyCode::PageDown | KeyCode::Char(' ') => {
+                    viewer.scroll_down(ui::ui_log_viewer::PAGE_LINES)
+                }
+                KeyCode::Char('g') => viewer.scroll_top(),
+                KeyCode::Char('G') => viewer.scroll_bottom(),
+                KeyCode::Char('/') => {
+                    viewer.search.clear();
+                    viewer.search_editing = true;
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') if !viewer.search.is_empty() => {
+                    let found = if key_event.code == KeyCode::Char('n') {
+                        viewer.search_next()
+                    } else {
+                        viewer.search_prev()
+                    };
+
+                    if !found {
+                        app.errors.push(ErrorEvent::new(
+                            ErrorSource::App,
+                            Severity::Warning,
+                            format!("Pattern not found: {}", viewer.search),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
+        ui::ui_log_viewer::draw(area, buf, &self.state)
+    }
+}
@@ -0,0 +1,390 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+use crate::mqtt_ctrl::traffic::TopicFamily;
+
+/// Main screen, with the search and errors popups, the deployment status and the pane details
+pub struct MainScreen;
+
+impl Screen for MainScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Main
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        if app.search.is_some() {
+            return handle_search_key(app, key_event.code);
+        }
+        if app.notes_editor.is_some() {
+            return handle_notes_key(app, key_event.code);
+        }
+        if app.errors_focus.is_some() {
+            return handle_errors_key(app, key_event.code);
+        }
+        if app.deployment_detail.is_some() {
+            return handle_deployment_detail_key(app, key_event.code);
+        }
+        if app.pane_detail.is_some() {
+            return handle_pane_detail_key(app, key_event.code);
+        }
+
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.main_window_focus = app.main_window_focus.previous();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                app.main_window_focus = app.main_window_focus.next();
+            }
+            KeyCode::Right | KeyCode::Char('l') => match app.main_window_focus {
+                MainWindowFocus::MainChip => app.main_window_focus = MainWindowFocus::AgentState,
+                MainWindowFocus::CompanionChip => {
+                    app.main_window_focus = MainWindowFocus::DeploymentStatus
+                }
+                MainWindowFocus::SensorChip => {
+                    app.main_window_focus = MainWindowFocus::DeviceReserved
+                }
+                MainWindowFocus::DeviceManifest => {
+                    app.main_window_focus = MainWindowFocus::DeviceCapabilities
+                }
+                MainWindowFocus::AgentState => {
+                    app.main_window_focus = MainWindowFocus::SystemSettings
+                }
+                MainWindowFocus::DeploymentStatus => {
+                    app.main_window_focus = MainWindowFocus::SystemSettings
+                }
+                MainWindowFocus::DeviceReserved => {
+                    app.main_window_focus = MainWindowFocus::NetworkSettings
+                }
+                MainWindowFocus::DeviceState => {
+                    app.main_window_focus = MainWindowFocus::WirelessSettings
+                }
+                MainWindowFocus::DeviceCapabilities => {
+                    app.main_window_focus = MainWindowFocus::WirelessSettings
+                }
+                MainWindowFocus::SystemSettings => {
+                    app.main_window_focus = MainWindowFocus::MainChip
+                }
+                MainWindowFocus::NetworkSettings => {
+                    app.main_window_focus = MainWindowFocus::CompanionChip
+                }
+                MainWindowFocus::WirelessSettings => {
+                    app.main_window_focus = MainWindowFocus::SensorChip
+                }
+            },
+            KeyCode::Left | KeyCode::Char('h') => match app.main_window_focus {
+                MainWindowFocus::MainChip => {
+                    app.main_window_focus = MainWindowFocus::SystemSettings
+                }
+                MainWindowFocus::CompanionChip => {
+                    app.main_window_focus = MainWindowFocus::SystemSettings
+                }
+                MainWindowFocus::SensorChip => {
+                    app.main_window_focus = MainWindowFocus::NetworkSettings
+                }
+                MainWindowFocus::DeviceManifest => {
+                    app.main_window_focus = MainWindowFocus::WirelessSettings
+                }
+                MainWindowFocus::AgentState => app.main_window_focus = MainWindowFocus::MainChip,
+                MainWindowFocus::DeploymentStatus => {
+                    app.main_window_focus = MainWindowFocus::MainChip
+                }
+                MainWindowFocus::DeviceReserved => {
+                    app.main_window_focus = MainWindowFocus::CompanionChip
+                }
+                MainWindowFocus::DeviceState => app.main_window_focus = MainWindowFocus::SensorChip,
+                MainWindowFocus::DeviceCapabilities => {
+                    app.main_window_focus = MainWindowFocus::DeviceManifest
+                }
+                MainWindowFocus::SystemSettings => {
+                    app.main_window_focus = MainWindowFocus::AgentState
+                }
+                MainWindowFocus::NetworkSettings => {
+                    app.main_window_focus = MainWindowFocus::DeploymentStatus
+                }
+                MainWindowFocus::WirelessSettings => {
+                    app.main_window_focus = MainWindowFocus::DeviceState
+                }
+            },
+            KeyCode::Enter if app.main_window_focus == MainWindowFocus::DeploymentStatus => {
+                app.open_deployment_detail()
+            }
+            KeyCode::Enter if app.main_window_focus == MainWindowFocus::DeviceReserved => {
+                app.switch_to_dtmi_screen()
+            }
+            KeyCode::Enter => app.open_pane_detail(),
+            KeyCode::Char('e') => app.switch_to_config_screen(false),
+            KeyCode::Char('E') => app.switch_to_config_screen(true),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Char('d') => app.switch_to_direct_command_screen(),
+            KeyCode::Char('m') => app.switch_to_evp_module_screen(AzuriteAction::Deploy),
+            KeyCode::Char('t') => app.switch_to_token_provider_screen(None),
+            KeyCode::Char('g') => app.switch_to_elog_screen(),
+            KeyCode::Char('M') => app.switch_to_edge_app_screen(),
+            KeyCode::Char('o') => app.dm_screen_move_to(OtaScreen),
+            KeyCode::Char('a') => app.dm_screen_move_to(AiModelScreen),
+            KeyCode::Char('s') => app.switch_to_storage_settings_screen(),
+            KeyCode::Char('D') => app.dm_screen_move_to(DownloadsScreen),
+            KeyCode::Char('J') => app.dm_screen_move_to(JobsScreen::default()),
+            KeyCode::Char('b') => app.dm_screen_move_to(RebootsScreen),
+            KeyCode::Char('A') => app.dm_screen_move_to(AuditScreen::default()),
+            KeyCode::Char('I') => app.switch_to_inventory_screen(),
+            KeyCode::Char('C') => app.switch_to_compare_screen(),
+            KeyCode::Char('F') => app.switch_to_fleet_screen(),
+            KeyCode::Char('L') => app.switch_to_log_settings_screen(),
+            KeyCode::Char('G') => app.switch_to_debug_log_screen(),
+            KeyCode::Char('x') if app.errors.is_empty() => {
+                with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                    mqtt_ctrl.info = Some(tr("No errors recorded").to_owned())
+                });
+            }
+            KeyCode::Char('x') => {
+                app.errors.dismiss();
+                app.errors_focus = Some(0);
+                app.errors_expanded = false;
+            }
+            KeyCode::Char('/') => app.open_search(),
+            KeyCode::Char('N') => app.open_notes_editor(),
+            KeyCode::Char('v') => app.toggle_secrets_revealed(),
+            KeyCode::Char('z') => app.toggle_time_zone(),
+            KeyCode::Char('w') => app.save_report(),
+            KeyCode::Char('n') => app.dm_screen_move_to(NetworkScreen),
+            KeyCode::Char('S') => app.switch_to_scripts_screen(),
+            KeyCode::Char('p') => app.switch_to_publish_screen(),
+            KeyCode::Char('R') => app.switch_to_retained_screen(),
+            KeyCode::Char('u') => {
+                app.config_key_clear();
+                app.dm_screen_move_to(SubscriptionsScreen::default())
+            }
+            _ => {}
+        }
+        // Since companion chip and sensor chip shares the same display region in main ui,
+        // last_config_companion_sensor is used to remember which is the last focused.
+        if app.main_window_focus == MainWindowFocus::CompanionChip
+            || app.main_window_focus == MainWindowFocus::SensorChip
+        {
+            app.last_config_companion_sensor = app.main_window_focus as usize;
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        // The popups are drawn over the panes
+        ui::ui_main::draw(area, buf, app)?;
+        ui::ui_deployment_detail::draw(area, buf, app)?;
+        ui::ui_pane_detail::draw(area, buf, app)?;
+        ui::ui_errors::draw(area, buf, app)?;
+        ui::ui_search::draw(area, buf, app)?;
+        ui::ui_notes::draw(area, buf, app)
+    }
+}
+
+/// One pane of the main screen in full size, with the messages not updating the device state
+#[derive(Default)]
+pub struct ModuleScreen {
+    /// Row of the message list
+    traffic_focus: usize,
+    collapsed_families: Vec<TopicFamily>,
+}
+
+impl ModuleScreen {
+    fn traffic_rows(&self) -> Vec<ui::ui_module::TrafficRow> {
+        with_mqtt_ctrl(|mqtt_ctrl| {
+            ui::ui_module::traffic_rows(mqtt_ctrl.traffic(), &self.collapsed_families)
+        })
+    }
+
+    /// Folds or unfolds the family of the focused row, keeping the focus on the family
+    fn toggle_traffic_family(&mut self) {
+        let Some(family) = self
+            .traffic_rows()
+            .get(self.traffic_focus)
+            .map(|row| row.family())
+        else {
+            return;
+        };
+
+        if let Some(i) = self.collapsed_families.iter().position(|f| *f == family) {
+            self.collapsed_families.remove(i);
+        } else {
+            self.collapsed_families.push(family);
+        }
+
+        self.traffic_focus = self
+            .traffic_rows()
+            .iter()
+            .position(|row| *row == ui::ui_module::TrafficRow::Family(family))
+            .unwrap_or(0);
+    }
+}
+
+impl Screen for ModuleScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Module
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Enter | KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.traffic_focus = self.traffic_focus.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j')
+                if self.traffic_focus + 1 < self.traffic_rows().len() =>
+            {
+                self.traffic_focus += 1;
+            }
+            KeyCode::Char(' ') => self.toggle_traffic_family(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Char('e') => app.switch_to_config_screen(false),
+            KeyCode::Char('E') => app.switch_to_config_screen(true),
+            KeyCode::Char('d') => app.switch_to_direct_command_screen(),
+            KeyCode::Char('m') => app.switch_to_evp_module_screen(AzuriteAction::Deploy),
+            KeyCode::Char('t') => app.switch_to_token_provider_screen(None),
+            KeyCode::Char('g') => app.switch_to_elog_screen(),
+            KeyCode::Char('o') => app.dm_screen_move_to(OtaScreen),
+            KeyCode::Char('v') => app.toggle_secrets_revealed(),
+            KeyCode::Char('z') => app.toggle_time_zone(),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_module::draw(area, buf, app, &self.collapsed_families, self.traffic_focus)
+    }
+}
+
+fn handle_notes_key(app: &mut App, code: KeyCode) {
+    let Some(editor) = app.notes_editor.as_mut() else {
+        return;
+    };
+
+    match code {
+        KeyCode::Esc => app.notes_editor = None,
+        KeyCode::Enter => app.save_notes(),
+        KeyCode::Tab | KeyCode::Up | KeyCode::Down => editor.toggle_field(),
+        KeyCode::Backspace => editor.pop(),
+        KeyCode::Char(c) => editor.push(c),
+        _ => {}
+    }
+}
+
+fn handle_search_key(app: &mut App, code: KeyCode) {
+    let Some(search) = app.search.as_mut() else {
+        return;
+    };
+
+    match code {
+        KeyCode::Esc => app.search = None,
+        KeyCode::Up => search.move_up(),
+        KeyCode::Down => search.move_down(),
+        KeyCode::Backspace => search.pop(),
+        KeyCode::Char(c) => search.push(c),
+        KeyCode::Enter => {
+            if let Some(hit) = search.selected_hit().cloned() {
+                app.main_window_focus = hit.focus;
+                if matches!(
+                    hit.focus,
+                    MainWindowFocus::CompanionChip | MainWindowFocus::SensorChip
+                ) {
+                    app.last_config_companion_sensor = hit.focus as usize;
+                }
+                with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                    mqtt_ctrl.info = Some(trf(
+                        "Found {0} = {1} in {2}",
+                        &[
+                            &hit.key,
+                            &hit.value,
+                            &tr(ui::ui_search::pane_name(hit.focus)),
+                        ],
+                    ))
+                });
+            }
+            app.search = None;
+        }
+        _ => {}
+    }
+}
+
+fn handle_errors_key(app: &mut App, code: KeyCode) {
+    let Some(focus) = app.errors_focus else {
+        return;
+    };
+
+    match code {
+        KeyCode::Esc | KeyCode::Char('x') => app.errors_focus = None,
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.errors_focus = Some(focus.saturating_sub(1));
+            app.errors_expanded = false;
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.errors_focus = Some((focus + 1).min(app.errors.len().saturating_sub(1)));
+            app.errors_expanded = false;
+        }
+        KeyCode::Enter => app.errors_expanded = !app.errors_expanded,
+        KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+        _ => {}
+    }
+}
+
+fn handle_deployment_detail_key(app: &mut App, code: KeyCode) {
+    let Some(tree) = app.deployment_detail.as_mut() else {
+        return;
+    };
+    if tree.handle_key(code) {
+        return;
+    }
+
+    match code {
+        KeyCode::Enter | KeyCode::Esc => app.deployment_detail = None,
+        KeyCode::Char('c') => {
+            if let Some(path) = tree.selected_path() {
+                app.yank(tr("path"), &path);
+            }
+        }
+        KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+        _ => {}
+    }
+}
+
+fn handle_pane_detail_key(app: &mut App, code: KeyCode) {
+    let Some(detail) = app.pane_detail.as_mut() else {
+        return;
+    };
+
+    // The tree of the raw JSON takes the keys it uses, all of them while searching
+    if detail.show_raw && detail.tree.handle_key(code) {
+        return;
+    }
+
+    match code {
+        KeyCode::Enter | KeyCode::Esc => app.pane_detail = None,
+        KeyCode::Up | KeyCode::Char('k') => detail.move_up(),
+        KeyCode::Down | KeyCode::Char('j') => detail.move_down(),
+        KeyCode::Char('r') => detail.toggle_raw(),
+        KeyCode::Char('c') if detail.show_raw => {
+            if let Some(path) = detail.tree.selected_path() {
+                app.yank(tr("path"), &path);
+            }
+        }
+        KeyCode::Char('f') => {
+            app.pane_detail = None;
+            app.dm_screen_move_to(ModuleScreen::default());
+        }
+        KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+        _ => {}
+    }
+}
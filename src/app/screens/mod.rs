@@ -0,0 +1,119 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+mod ai_model;
+mod audit;
+mod compare;
+mod config;
+mod debug_log;
+mod direct_command;
+mod dtmi;
+mod edge_app;
+mod elog;
+mod evp_module;
+mod exiting;
+mod fleet;
+mod inventory;
+mod jobs;
+mod kiosk;
+mod log_settings;
+mod log_viewer;
+mod main;
+mod network;
+mod ota;
+mod publish;
+mod reboots;
+mod retained;
+mod scripts;
+mod storage;
+mod subscriptions;
+mod token_provider;
+
+pub use {
+    ai_model::{AiModelConfigScreen, AiModelScreen},
+    audit::AuditScreen,
+    compare::CompareScreen,
+    config::{ConfigurationScreen, ConfigurationUserScreen},
+    debug_log::DebugLogScreen,
+    direct_command::DirectCommandScreen,
+    dtmi::DtmiScreen,
+    edge_app::{EdgeAppInstancesScreen, EdgeAppScreen},
+    elog::ElogScreen,
+    evp_module::EvpModuleScreen,
+    exiting::ExitingScreen,
+    fleet::FleetScreen,
+    inventory::InventoryScreen,
+    jobs::JobsScreen,
+    kiosk::KioskScreen,
+    log_settings::LogSettingsScreen,
+    log_viewer::{LogFilesScreen, LogViewerScreen},
+    main::{MainScreen, ModuleScreen},
+    network::{NetworkScreen, StaticIpWizardScreen, WifiWizardScreen},
+    ota::{OtaConfigScreen, OtaScreen, OtaWizardScreen},
+    publish::PublishScreen,
+    reboots::RebootsScreen,
+    retained::RetainedScreen,
+    scripts::ScriptsScreen,
+    storage::{DownloadsScreen, StorageSettingsScreen},
+    subscriptions::SubscriptionsScreen,
+    token_provider::{TokenProviderBlobsScreen, TokenProviderScreen},
+};
+
+use super::*;
+use std::any::Any;
+
+/// Screen of the navigation stack of the App, with the state of its visit, its key handling
+/// and its drawing.
+///
+/// A screen is added by implementing this trait in a module of `app::screens` and moving to
+/// it with `App::dm_screen_move_to()`. The state shared with other screens or followed in the
+/// background, e.g. the config keys or the OTA wizard, stays in App.
+pub trait Screen: Any {
+    /// Kind of the screen, for the breadcrumbs, the key hints and the checks of the
+    /// current screen
+    fn id(&self) -> DMScreen;
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent);
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError>;
+}
+
+/// Takes the place of the current screen in the stack while it handles a key, so that the
+/// screen and the App can be borrowed together
+pub struct Handling(pub DMScreen);
+
+impl Screen for Handling {
+    fn id(&self) -> DMScreen {
+        self.0
+    }
+
+    fn handle_key(&mut self, _app: &mut App, _key_event: KeyEvent) {}
+
+    fn draw(&self, _area: Rect, _buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
+        Ok(())
+    }
+}
+
+/// Screen of the type, whatever its state
+pub fn downcast_ref<S: Screen>(screen: &dyn Screen) -> Option<&S> {
+    let screen: &dyn Any = screen;
+    screen.downcast_ref()
+}
+
+pub fn downcast_mut<S: Screen>(screen: &mut dyn Screen) -> Option<&mut S> {
+    let screen: &mut dyn Any = screen;
+    screen.downcast_mut()
+}
@@ -0,0 +1,220 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct NetworkScreen;
+
+impl Screen for NetworkScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Network
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Char('w') => {
+                let is_device_connected =
+                    with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected());
+                if is_device_connected {
+                    app.dm_screen_move_to(WifiWizardScreen {
+                        wizard: WifiWizard::new(),
+                    });
+                } else {
+                    app.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+                }
+            }
+            KeyCode::Char('i') => {
+                let is_device_connected =
+                    with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected());
+                if is_device_connected {
+                    let wizard = with_mqtt_ctrl(|mqtt_ctrl| {
+                        StaticIpWizard::new(mqtt_ctrl.network_settings())
+                    });
+                    app.dm_screen_move_to(StaticIpWizardScreen { wizard });
+                } else {
+                    app.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_network::draw(area, buf, app)
+    }
+}
+
+pub struct WifiWizardScreen {
+    pub wizard: WifiWizard,
+}
+
+impl WifiWizardScreen {
+    fn generate(&mut self, app: &mut App) {
+        let errors = self.wizard.validate();
+        if !errors.is_empty() {
+            app.push_warning(ErrorSource::App, errors.join(", "));
+            return;
+        }
+
+        app.config_key_clear();
+        self.wizard.apply_to_config_keys(&mut app.config_keys);
+        match parse_wireless_settings(&app.config_keys) {
+            Ok(payload) => self.wizard.set_payload(payload),
+            Err(e) => {
+                app.push_failure(
+                    ErrorSource::App,
+                    "Failed to generate wireless configuration",
+                    &e,
+                );
+            }
+        }
+    }
+
+    fn send(&mut self, app: &mut App) {
+        let Some(payload) = self.wizard.payload.clone() else {
+            return;
+        };
+
+        match with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.send_configure(&payload)) {
+            Ok(_) => self.wizard.set_sent(Local::now()),
+            Err(e) => {
+                app.push_failure(
+                    ErrorSource::Mqtt,
+                    "Failed to send wireless configuration",
+                    &e,
+                );
+            }
+        }
+    }
+}
+
+impl Screen for WifiWizardScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::WifiWizard
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match self.wizard.step {
+            WifiWizardStep::Credentials => match key_event.code {
+                KeyCode::Up | KeyCode::BackTab => self.wizard.focus_up(),
+                KeyCode::Down | KeyCode::Tab => self.wizard.focus_down(),
+                KeyCode::Char(' ') if self.wizard.is_encryption_focused() => {
+                    self.wizard.next_encryption()
+                }
+                KeyCode::Char(c) => self.wizard.push(c),
+                KeyCode::Backspace => self.wizard.pop(),
+                KeyCode::Enter => self.generate(app),
+                KeyCode::Esc => app.dm_screen_move_back(),
+                _ => {}
+            },
+            WifiWizardStep::Preview => match key_event.code {
+                KeyCode::Char('s') => self.send(app),
+                KeyCode::Esc => {
+                    self.wizard.step_back();
+                }
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+            WifiWizardStep::Monitor => match key_event.code {
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
+        ui::ui_wifi_wizard::draw(area, buf, &self.wizard)
+    }
+}
+
+pub struct StaticIpWizardScreen {
+    pub wizard: StaticIpWizard,
+}
+
+impl StaticIpWizardScreen {
+    fn generate(&mut self, app: &mut App) {
+        let errors = self.wizard.validate();
+        if !errors.is_empty() {
+            app.push_warning(ErrorSource::App, errors.join(", "));
+            return;
+        }
+
+        app.config_key_clear();
+        self.wizard.apply_to_config_keys(&mut app.config_keys);
+        match parse_network_settings(&app.config_keys) {
+            Ok(payload) => self.wizard.set_payload(payload),
+            Err(e) => {
+                app.push_failure(
+                    ErrorSource::App,
+                    "Failed to generate network configuration",
+                    &e,
+                );
+            }
+        }
+    }
+
+    fn send(&mut self, app: &mut App) {
+        let Some(payload) = self.wizard.payload.clone() else {
+            return;
+        };
+
+        if app.send_and_verify(&payload, None) {
+            self.wizard.step = StaticIpWizardStep::Verify;
+        }
+    }
+}
+
+impl Screen for StaticIpWizardScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::StaticIpWizard
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match self.wizard.step {
+            StaticIpWizardStep::Settings => match key_event.code {
+                KeyCode::Up | KeyCode::BackTab => self.wizard.focus_up(),
+                KeyCode::Down | KeyCode::Tab => self.wizard.focus_down(),
+                KeyCode::Char(' ') if self.wizard.focus == 0 => self.wizard.toggle_ip_method(),
+                KeyCode::Char(c) => self.wizard.push(c),
+                KeyCode::Backspace => self.wizard.pop(),
+                KeyCode::Enter => self.generate(app),
+                KeyCode::Esc => app.dm_screen_move_back(),
+                _ => {}
+            },
+            StaticIpWizardStep::Preview => match key_event.code {
+                KeyCode::Char('s') => self.send(app),
+                KeyCode::Esc => {
+                    self.wizard.step_back();
+                }
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+            StaticIpWizardStep::Verify => match key_event.code {
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_static_ip_wizard::draw(area, buf, app, &self.wizard)
+    }
+}
@@ -0,0 +1,222 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct OtaScreen;
+
+impl Screen for OtaScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Ota
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Char('d') => {
+                let is_device_connected =
+                    with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected());
+                if is_device_connected {
+                    app.switch_to_ota_config_screen(DMScreenState::Initial);
+                } else {
+                    app.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+                }
+            }
+            KeyCode::Char('r') => {
+                let is_device_connected =
+                    with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected());
+                if is_device_connected {
+                    app.ota_rollback();
+                } else {
+                    app.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+                }
+            }
+            KeyCode::Char('w') => {
+                let is_device_connected =
+                    with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.is_device_connected());
+                if is_device_connected {
+                    app.ota_wizard = OtaWizard::new();
+                    app.dm_screen_move_to(OtaWizardScreen);
+                } else {
+                    app.push_warning(ErrorSource::Mqtt, "Device is not connected.");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_ota::draw(area, buf, app)
+    }
+}
+
+pub struct OtaWizardScreen;
+
+impl Screen for OtaWizardScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::OtaWizard
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match app.ota_wizard.step {
+            OtaWizardStep::Target => match key_event.code {
+                KeyCode::Up | KeyCode::Char('k') => app.ota_wizard.target_focus_up(),
+                KeyCode::Down | KeyCode::Char('j') => app.ota_wizard.target_focus_down(),
+                KeyCode::Enter => app.ota_wizard.step = OtaWizardStep::File,
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+            OtaWizardStep::File => match key_event.code {
+                KeyCode::Char(c) => app.ota_wizard.file_path.push(c),
+                KeyCode::Backspace => {
+                    app.ota_wizard.file_path.pop();
+                }
+                KeyCode::Enter => app.ota_wizard_upload(),
+                KeyCode::Esc => {
+                    app.ota_wizard.step_back();
+                }
+                _ => {}
+            },
+            OtaWizardStep::Review if app.ota_wizard.version_editable => match key_event.code {
+                KeyCode::Char(c) => app.ota_wizard.version.push(c),
+                KeyCode::Backspace => {
+                    app.ota_wizard.version.pop();
+                }
+                KeyCode::Enter | KeyCode::Esc => app.ota_wizard.version_editable = false,
+                _ => {}
+            },
+            OtaWizardStep::Review => match key_event.code {
+                KeyCode::Char('i') | KeyCode::Char('a') => app.ota_wizard.version_editable = true,
+                KeyCode::Enter => app.ota_wizard_generate(),
+                KeyCode::Esc => {
+                    app.ota_wizard.step_back();
+                }
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+            OtaWizardStep::Send => match key_event.code {
+                KeyCode::Char('s') => app.ota_wizard_send(),
+                KeyCode::Esc => {
+                    app.ota_wizard.step_back();
+                }
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+            OtaWizardStep::Progress => match key_event.code {
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Char('o') => {
+                    app.dm_screen_move_back();
+                    if app.current_screen() != DMScreen::Ota {
+                        app.dm_screen_move_to(OtaScreen);
+                    }
+                }
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_ota_wizard::draw(area, buf, app)
+    }
+}
+
+pub struct OtaConfigScreen(pub DMScreenState);
+
+impl Screen for OtaConfigScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::OtaConfig(self.0)
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match self.0 {
+            DMScreenState::Initial => match key_event.code {
+                KeyCode::Char(c) if app.config_key_editable => {
+                    let value: &mut String = app.config_keys.get_mut(app.config_key_focus).unwrap();
+                    value.push(c);
+                }
+                KeyCode::Backspace if app.config_key_editable => {
+                    let value: &mut String = app.config_keys.get_mut(app.config_key_focus).unwrap();
+                    value.pop();
+                }
+                KeyCode::Esc if app.config_key_editable => app.config_key_editable = false,
+                KeyCode::Esc => app.dm_screen_move_back(),
+                KeyCode::Enter if app.config_key_editable => app.config_key_editable = false,
+                KeyCode::Up | KeyCode::Char('k') => app.config_focus_up(),
+                KeyCode::Down | KeyCode::Char('j') => app.config_focus_down(),
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                KeyCode::Char('i') | KeyCode::Char('a') => {
+                    if ConfigKey::from(app.config_key_focus).is_sas_url_entry() {
+                        app.switch_to_evp_module_screen(AzuriteAction::Select);
+                    } else {
+                        app.config_key_editable = true;
+                    }
+                }
+                KeyCode::Char('r') => app.refresh_sas_url_entry(),
+                KeyCode::Char('w') => {
+                    app.warn_expired_sas_urls();
+                    app.config_result = Some(parse_ota_configuration(&app.config_keys));
+                    // we don't use configuring state here
+                    app.dm_screen_move_to(OtaConfigScreen(DMScreenState::Completed));
+                }
+                _ => {}
+            },
+            DMScreenState::Configuring => {
+                // Handle configuration logic here
+            }
+            DMScreenState::Completed => match key_event.code {
+                // Handle completion logic here
+                KeyCode::Esc => {
+                    app.config_result = None;
+                    app.dm_screen_move_back();
+                }
+                KeyCode::Char('s') => {
+                    let snapshot = match &app.config_result {
+                        Some(Ok(config)) => App::ota_snapshot_for(config),
+                        _ => None,
+                    };
+                    with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                        if let Some(Ok(config)) = &app.config_result {
+                            match mqtt_ctrl.send_configure(config) {
+                                Ok(_) => app.ota_snapshot = snapshot,
+                                Err(e) => {
+                                    with_global_app_mut(|app| {
+                                        app.push_failure(
+                                            ErrorSource::Mqtt,
+                                            "Failed to send OTA configuration",
+                                            &e,
+                                        );
+                                    });
+                                }
+                            }
+                        }
+                    });
+                    app.dm_screen_move_back();
+                    app.dm_screen_move_back();
+                }
+                KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+                _ => {}
+            },
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_ota_config::draw(area, buf, app)
+    }
+}
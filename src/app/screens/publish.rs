@@ -0,0 +1,60 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct PublishScreen;
+
+impl Screen for PublishScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Publish
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char(c) if app.config_key_editable => {
+                let value: &mut String = app.config_keys.get_mut(app.config_key_focus).unwrap();
+                value.push(c);
+            }
+            KeyCode::Backspace if app.config_key_editable => {
+                let value: &mut String = app.config_keys.get_mut(app.config_key_focus).unwrap();
+                value.pop();
+            }
+            KeyCode::Esc | KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false
+            }
+            KeyCode::Esc if app.config_result.is_some() => app.config_result = None,
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Up | KeyCode::Char('k') => app.config_focus_up(),
+            KeyCode::Down | KeyCode::Char('j') => app.config_focus_down(),
+            KeyCode::Char('i') | KeyCode::Char('a') => {
+                app.config_result = None;
+                app.config_key_editable = true
+            }
+            KeyCode::Char('p') => app.recall_published(true),
+            KeyCode::Char('n') => app.recall_published(false),
+            KeyCode::Char('w') => app.publish_message(false),
+            KeyCode::Char('W') => app.publish_message(true),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_publish::draw(area, buf, app)
+    }
+}
@@ -0,0 +1,118 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct RetainedScreen {
+    focus: usize,
+    /// Topics whose retained message is cleared at once
+    marked: BTreeSet<String>,
+}
+
+impl RetainedScreen {
+    /// The retained messages are scanned again each time the screen is opened
+    pub fn open() -> Self {
+        let mut screen = Self {
+            focus: 0,
+            marked: BTreeSet::new(),
+        };
+        screen.scan();
+        screen
+    }
+
+    fn scan(&mut self) {
+        self.focus = 0;
+        self.marked.clear();
+        with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.scan_retained());
+    }
+
+    /// Topic of the focused retained message
+    fn focused(&self) -> Option<String> {
+        with_mqtt_ctrl(|mqtt_ctrl| {
+            mqtt_ctrl
+                .retained()
+                .iter()
+                .nth(self.focus)
+                .map(|(topic, _)| topic.clone())
+        })
+    }
+
+    /// Clears the marked retained messages, or the focused one if none is marked
+    fn clear_messages(&mut self, app: &mut App) {
+        let topics = if self.marked.is_empty() {
+            self.focused().into_iter().collect()
+        } else {
+            std::mem::take(&mut self.marked)
+        };
+
+        let mut errors = vec![];
+        for topic in &topics {
+            if let Err(e) = with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.clear_retained(topic)) {
+                errors.push(format!(
+                    "{}: {}",
+                    topic,
+                    e.error_str().unwrap_or("Unknown error".to_owned())
+                ));
+            }
+        }
+
+        let count = with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.retained().len());
+        self.focus = self.focus.min(count.saturating_sub(1));
+        if errors.is_empty() {
+            with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                mqtt_ctrl.info = Some(trf("Cleared {0} retained message(s)", &[&topics.len()]))
+            });
+        } else {
+            app.push_error(
+                ErrorSource::Mqtt,
+                trf("Failed to clear {0}", &[&errors.join(", ")]),
+            );
+        }
+    }
+}
+
+impl Screen for RetainedScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Retained
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        let code = key_event.code;
+        let count = with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.retained().len());
+
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.focus = self.focus.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') if self.focus + 1 < count => self.focus += 1,
+            KeyCode::Char(' ') => {
+                if let Some(topic) = self.focused()
+                    && !self.marked.remove(&topic)
+                {
+                    self.marked.insert(topic);
+                }
+            }
+            KeyCode::Char('c') => self.clear_messages(app),
+            KeyCode::Char('r') => self.scan(),
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
+        ui::ui_retained::draw(area, buf, self.focus, &self.marked)
+    }
+}
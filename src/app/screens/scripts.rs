@@ -0,0 +1,74 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct ScriptsScreen;
+
+impl Screen for ScriptsScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Scripts
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        let code = key_event.code;
+        let Some(browser) = app.script_browser.as_mut() else {
+            return;
+        };
+
+        let result = match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                browser.move_up();
+                Ok(None)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                browser.move_down();
+                Ok(None)
+            }
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => {
+                browser.parent().map(|_| None)
+            }
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => browser.enter(),
+            KeyCode::Char('c') => {
+                if let Some(runner) = app.script_runner.as_mut() {
+                    runner.cancel();
+                }
+                Ok(None)
+            }
+            KeyCode::Esc => {
+                app.dm_screen_move_back();
+                Ok(None)
+            }
+            KeyCode::Char('q') => {
+                app.dm_screen_move_to(ExitingScreen);
+                Ok(None)
+            }
+            _ => Ok(None),
+        };
+
+        app.errors.dismiss();
+        match result {
+            Ok(Some(path)) => app.run_script(&path),
+            Ok(None) => {}
+            Err(e) => app.push_report(ErrorSource::Script, &e),
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_scripts::draw(area, buf, app)
+    }
+}
@@ -0,0 +1,74 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+pub struct StorageSettingsScreen;
+
+impl Screen for StorageSettingsScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::StorageSettings
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char(c) if app.config_key_editable => {
+                let value: &mut String = app.config_keys.get_mut(app.config_key_focus).unwrap();
+                value.push(c);
+            }
+            KeyCode::Backspace if app.config_key_editable => {
+                let value: &mut String = app.config_keys.get_mut(app.config_key_focus).unwrap();
+                value.pop();
+            }
+            KeyCode::Esc | KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false
+            }
+            KeyCode::Esc if app.config_result.is_some() => app.config_result = None,
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Up | KeyCode::Char('k') => app.config_focus_up(),
+            KeyCode::Down | KeyCode::Char('j') => app.config_focus_down(),
+            KeyCode::Char('i') | KeyCode::Char('a') => app.config_key_editable = true,
+            KeyCode::Char('w') => app.apply_storage_settings(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_storage_settings::draw(area, buf, app)
+    }
+}
+
+pub struct DownloadsScreen;
+
+impl Screen for DownloadsScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Downloads
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_downloads::draw(area, buf, app)
+    }
+}
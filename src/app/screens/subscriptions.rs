@@ -0,0 +1,102 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use super::*;
+
+#[derive(Default)]
+pub struct SubscriptionsScreen {
+    focus: usize,
+}
+
+impl Screen for SubscriptionsScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::Subscriptions
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        let code = key_event.code;
+        let filter_index = usize::from(ConfigKey::SubscriptionFilter);
+        let count = with_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.subscriptions().len());
+
+        let result = match code {
+            KeyCode::Char(c) if app.config_key_editable => {
+                app.config_keys[filter_index].push(c);
+                Ok(())
+            }
+            KeyCode::Backspace if app.config_key_editable => {
+                app.config_keys[filter_index].pop();
+                Ok(())
+            }
+            KeyCode::Esc if app.config_key_editable => {
+                app.config_key_editable = false;
+                Ok(())
+            }
+            KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false;
+                let filter = app.config_keys[filter_index].trim().to_owned();
+                with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.subscribe(&filter))
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.focus = self.focus.saturating_sub(1);
+                Ok(())
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.focus + 1 < count {
+                    self.focus += 1;
+                }
+                Ok(())
+            }
+            KeyCode::Char('a') | KeyCode::Char('i') => {
+                app.config_keys[filter_index].clear();
+                app.config_key_editable = true;
+                Ok(())
+            }
+            KeyCode::Char('d') => {
+                let filter = with_mqtt_ctrl(|mqtt_ctrl| {
+                    mqtt_ctrl
+                        .subscriptions()
+                        .iter()
+                        .nth(self.focus)
+                        .map(|s| s.filter.clone())
+                });
+                let result = match filter {
+                    Some(filter) => with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.unsubscribe(&filter)),
+                    None => Ok(()),
+                };
+                self.focus = self.focus.min(count.saturating_sub(2));
+                result
+            }
+            KeyCode::Esc => {
+                app.dm_screen_move_back();
+                Ok(())
+            }
+            KeyCode::Char('q') => {
+                app.dm_screen_move_to(ExitingScreen);
+                Ok(())
+            }
+            _ => Ok(()),
+        };
+
+        if let Err(e) = result {
+            app.push_report(ErrorSource::Mqtt, &e);
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_subscriptions::draw(area, buf, app, self.focus)
+    }
+}
@@ -0,0 +1,303 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::*,
+    crate::app::ui::{
+        ui_token_provider::TokenProviderForm,
+        ui_token_provider_blobs::{BlobDownload, BlobListState},
+    },
+};
+
+#[derive(Default)]
+pub struct TokenProviderScreen {
+    /// Popup creating a token provider
+    pub form: Option<TokenProviderForm>,
+    /// Config key set to the token provider selected with ENTER
+    pub for_config: Option<ConfigKey>,
+}
+
+impl TokenProviderScreen {
+    /// Popup creating a token provider
+    fn handle_form_key(&mut self, app: &mut App, code: KeyCode) {
+        let Some(form) = self.form.as_mut() else {
+            return;
+        };
+
+        match code {
+            KeyCode::Esc => self.form = None,
+            KeyCode::Up | KeyCode::BackTab => form.focus_up(),
+            KeyCode::Down | KeyCode::Tab => form.focus_down(),
+            KeyCode::Backspace => form.pop(),
+            KeyCode::Char(c) => form.push(c),
+            KeyCode::Enter => self.add_token_provider(app),
+            _ => {}
+        }
+    }
+
+    /// Creates the token provider input in the popup, which is kept open on failure
+    fn add_token_provider(&mut self, app: &mut App) {
+        let Some(form) = self.form.as_ref() else {
+            return;
+        };
+        let label = form.label();
+        let container = form.container().map(str::to_owned);
+
+        match with_azurite_storage_mut(|azurite_storage| {
+            azurite_storage.add_token_provider(container.as_deref(), label)
+        }) {
+            Some(Ok(_)) => self.form = None,
+            Some(Err(e)) => {
+                app.push_failure(ErrorSource::Azurite, "Failed to add new token provider", &e)
+            }
+            None => app.push_error(ErrorSource::Azurite, "Azurite storage not available"),
+        }
+    }
+}
+
+impl Screen for TokenProviderScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::TokenProvider
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        if self.form.is_some() {
+            return self.handle_form_key(app, key_event.code);
+        }
+
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false
+            }
+            code @ (KeyCode::Char(_) | KeyCode::Backspace) if app.config_key_editable => {
+                app.edit_token_provider_filter(code)
+            }
+            KeyCode::Enter if self.for_config.is_some() => {
+                if let Some(uuid_string) = with_azurite_storage(|azurite_storage| {
+                    azurite_storage
+                        .current_token_provider()
+                        .map(|tp| tp.uuid.uuid().to_string())
+                })
+                .flatten()
+                {
+                    if let Some(config_key) = self.for_config.take() {
+                        app.config_keys[usize::from(config_key)] = uuid_string;
+                        app.config_keys_dirty[usize::from(config_key)] = true;
+                        app.dm_screen_move_back();
+                    }
+                }
+            }
+            KeyCode::Char('a') => {
+                self.form = Some(ui::ui_token_provider::TokenProviderForm::default())
+            }
+            KeyCode::Char('f') | KeyCode::Char('/') => app.config_key_editable = true,
+            KeyCode::Char('1') => {
+                app.toggle_default_token_provider(TokenProviderPurpose::DeviceLogs)
+            }
+            KeyCode::Char('2') => {
+                app.toggle_default_token_provider(TokenProviderPurpose::InputTensor)
+            }
+            KeyCode::Char('3') => app.toggle_default_token_provider(TokenProviderPurpose::Metadata),
+            KeyCode::Char('d') => {
+                if let Some(uuid) = with_azurite_storage(|azurite_storage| {
+                    azurite_storage
+                        .current_token_provider()
+                        .map(|tp| tp.uuid.clone())
+                })
+                .flatten()
+                {
+                    app.confirmation = Some(Confirmation::new(
+                        ConfirmAction::RemoveTokenProvider(uuid.clone()),
+                        trf(
+                            "Remove token provider {0} and its upload container?",
+                            &[&uuid.uuid()],
+                        ),
+                    ));
+                }
+            }
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Up | KeyCode::Char('k') => {
+                with_azurite_storage_mut(|azurite_storage| {
+                    azurite_storage.current_token_provider_focus_up();
+                });
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                with_azurite_storage_mut(|azurite_storage| {
+                    azurite_storage.current_token_provider_focus_down();
+                });
+            }
+            KeyCode::Char(c @ ('s' | 'l')) => {
+                // Blobs are listed in the background, the screen is switched when done
+                if let Some(token_provider) = with_azurite_storage(|azurite_storage| {
+                    azurite_storage.current_token_provider().cloned()
+                })
+                .flatten()
+                {
+                    app.submit_azurite_job(AzuriteJob::ListBlobs {
+                        container: token_provider.container.clone(),
+                        log_files: c == 'l',
+                        marker: None,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_token_provider::draw(area, buf, app, self.form.as_ref())
+    }
+}
+
+pub struct TokenProviderBlobsScreen {
+    state: BlobListState,
+    /// Download directory being typed
+    pub download_dir_input: Option<String>,
+}
+
+impl TokenProviderBlobsScreen {
+    pub fn new(state: BlobListState) -> Self {
+        Self {
+            state,
+            download_dir_input: None,
+        }
+    }
+
+    pub fn state_mut(&mut self) -> &mut BlobListState {
+        &mut self.state
+    }
+
+    /// Filters the blobs while it is typed, more blobs are listed when few of them match
+    fn edit_filter(&mut self, app: &mut App, code: KeyCode) {
+        let mut filter = self.state.filter.clone();
+        match code {
+            KeyCode::Char(c) => filter.push(c),
+            KeyCode::Backspace => {
+                filter.pop();
+            }
+            _ => return,
+        }
+        self.state.set_filter(&filter);
+        app.list_more_blobs(&mut self.state, false);
+    }
+
+    /// Queues the download of the marked blobs, or else the focused one, to the download
+    /// directory. They run in the background a few at a time, a download interrupted before
+    /// resumes from where it stopped.
+    fn download_blobs(&mut self, app: &mut App) {
+        let blob_state = &mut self.state;
+        let container = blob_state.container_name.clone();
+        let mut jobs = vec![];
+        for blob in blob_state.take_downloads() {
+            // Already queued or running
+            if blob_state
+                .downloads
+                .get(&blob)
+                .is_some_and(|d| d.is_active())
+            {
+                continue;
+            }
+            blob_state
+                .downloads
+                .insert(blob.clone(), BlobDownload::Queued);
+            jobs.push(AzuriteJob::Download {
+                container: container.clone(),
+                blob,
+                dir: app.blob_download_dir.clone(),
+            });
+        }
+
+        for job in jobs {
+            app.submit_azurite_job(job);
+        }
+    }
+
+    /// Edits the download directory being typed, ENTER sets it and an empty one means the
+    /// current directory
+    fn input_download_dir(&mut self, app: &mut App, code: KeyCode) {
+        let Some(input) = self.download_dir_input.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let dir = input.trim().to_owned();
+                app.blob_download_dir = Some(dir).filter(|d| !d.is_empty());
+                self.download_dir_input = None;
+            }
+            KeyCode::Esc => self.download_dir_input = None,
+            _ => {}
+        }
+    }
+}
+
+impl Screen for TokenProviderBlobsScreen {
+    fn id(&self) -> DMScreen {
+        DMScreen::TokenProviderBlobs
+    }
+
+    fn handle_key(&mut self, app: &mut App, key_event: KeyEvent) {
+        if self.download_dir_input.is_some() {
+            return self.input_download_dir(app, key_event.code);
+        }
+
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Enter if app.config_key_editable => {
+                app.config_key_editable = false
+            }
+            code @ (KeyCode::Char(_) | KeyCode::Backspace) if app.config_key_editable => {
+                self.edit_filter(app, code)
+            }
+            KeyCode::Char('/') => app.config_key_editable = true,
+            KeyCode::Char('c') => {
+                self.download_dir_input = Some(app.blob_download_dir.clone().unwrap_or_default())
+            }
+            KeyCode::Char('o') => self.state.sort_by_next(),
+            KeyCode::Esc => app.dm_screen_move_back(),
+            KeyCode::Char('q') => app.dm_screen_move_to(ExitingScreen),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.state.move_up();
+                app.list_more_blobs(&mut self.state, false);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.state.move_down();
+                app.list_more_blobs(&mut self.state, false);
+            }
+            KeyCode::Enter => self.download_blobs(app),
+            KeyCode::Char(' ') => {
+                self.state.toggle_mark();
+                app.list_more_blobs(&mut self.state, false);
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&self, area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+        ui::ui_token_provider_blobs::draw(
+            area,
+            buf,
+            &self.state,
+            app.config_key_editable,
+            app.blob_download_dir.as_deref(),
+            self.download_dir_input.as_deref(),
+        )
+    }
+}
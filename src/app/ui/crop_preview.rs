@@ -0,0 +1,376 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{list_items_push_text_focus, normal_block},
+    crate::{
+        app::ConfigKey,
+        i18n::{tr, trf},
+    },
+    ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        prelude::{Color, Style},
+        text::{Line, Span},
+        widgets::{Paragraph, Widget},
+    },
+};
+
+/// Entries of the PQ settings drawn by the preview
+pub const CROP_PREVIEW_KEYS: [ConfigKey; 7] = [
+    ConfigKey::CommonSettingsPQCameraImageSizeWidth,
+    ConfigKey::CommonSettingsPQCameraImageSizeHeight,
+    ConfigKey::CommonSettingsPQDigitalZoom,
+    ConfigKey::CommonSettingsPQICLeft,
+    ConfigKey::CommonSettingsPQICTop,
+    ConfigKey::CommonSettingsPQICWidth,
+    ConfigKey::CommonSettingsPQICHeight,
+];
+
+/// Terminal cells are about twice as high as wide
+const CELL_ASPECT: f64 = 2.0;
+
+/// Rectangle in the coordinates of the camera image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub left: f64,
+    pub top: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Region {
+    fn right(&self) -> f64 {
+        self.left + self.width
+    }
+
+    fn bottom(&self) -> f64 {
+        self.top + self.height
+    }
+
+    fn contains(&self, other: &Region) -> bool {
+        other.left >= self.left
+            && other.top >= self.top
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+}
+
+/// camera_image_size, digital_zoom and image_cropping of the PQ settings, drawn as an ASCII
+/// frame so that the crop coordinates can be checked before they are sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct CropPreview {
+    pub image: Region,
+    pub zoom: Option<f32>,
+    pub cropping: Option<Region>,
+}
+
+impl CropPreview {
+    /// Reads the values with `value`, e.g. the entered value or else the reported one.
+    /// Fails when the camera image size is unknown or a value is not a number.
+    pub fn new(value: impl Fn(ConfigKey) -> Option<String>) -> Result<Self, String> {
+        let number = |config_key: ConfigKey| -> Result<Option<f64>, String> {
+            match value(config_key) {
+                Some(v) if !v.trim().is_empty() => v
+                    .trim()
+                    .parse::<f64>()
+                    .map(Some)
+                    .map_err(|_| trf("{0} is not a number: '{1}'", &[&config_key, &v.trim()])),
+                _ => Ok(None),
+            }
+        };
+
+        let (Some(width), Some(height)) = (
+            number(ConfigKey::CommonSettingsPQCameraImageSizeWidth)?,
+            number(ConfigKey::CommonSettingsPQCameraImageSizeHeight)?,
+        ) else {
+            return Err(tr("camera_image_size is not set, the crop can't be drawn").to_owned());
+        };
+        if width <= 0.0 || height <= 0.0 {
+            return Err(trf(
+                "Invalid camera_image_size: {0}x{1}",
+                &[&width, &height],
+            ));
+        }
+
+        let zoom = number(ConfigKey::CommonSettingsPQDigitalZoom)?.map(|z| z as f32);
+        let cropping = match (
+            number(ConfigKey::CommonSettingsPQICLeft)?,
+            number(ConfigKey::CommonSettingsPQICTop)?,
+            number(ConfigKey::CommonSettingsPQICWidth)?,
+            number(ConfigKey::CommonSettingsPQICHeight)?,
+        ) {
+            (None, None, None, None) => None,
+            (left, top, w, h) => Some(Region {
+                left: left.unwrap_or(0.0),
+                top: top.unwrap_or(0.0),
+                width: w.unwrap_or(width),
+                height: h.unwrap_or(height),
+            }),
+        };
+
+        Ok(Self {
+            image: Region {
+                left: 0.0,
+                top: 0.0,
+                width,
+                height,
+            },
+            zoom,
+            cropping,
+        })
+    }
+
+    /// Part of the image kept by the digital zoom, centered
+    pub fn zoom_region(&self) -> Option<Region> {
+        let zoom = self.zoom.filter(|z| *z > 1.0)? as f64;
+        let width = self.image.width / zoom;
+        let height = self.image.height / zoom;
+        Some(Region {
+            left: (self.image.width - width) / 2.0,
+            top: (self.image.height - height) / 2.0,
+            width,
+            height,
+        })
+    }
+
+    /// Problems found in the values, empty when they look sane
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = vec![];
+        if let Some(zoom) = self.zoom
+            && zoom < 1.0
+        {
+            warnings.push(trf("digital_zoom {0} is less than 1.0", &[&zoom]));
+        }
+
+        if let Some(cropping) = self.cropping.as_ref() {
+            if cropping.width <= 0.0 || cropping.height <= 0.0 {
+                warnings.push(tr("image_cropping is empty").to_owned());
+            } else if !self.image.contains(cropping) {
+                warnings.push(trf(
+                    "image_cropping {0}x{1}@({2},{3}) exceeds camera_image_size {4}x{5}",
+                    &[
+                        &cropping.width,
+                        &cropping.height,
+                        &cropping.left,
+                        &cropping.top,
+                        &self.image.width,
+                        &self.image.height,
+                    ],
+                ));
+            } else if let Some(zoom_region) = self.zoom_region()
+                && !zoom_region.contains(cropping)
+            {
+                warnings
+                    .push(tr("image_cropping goes beyond the digitally zoomed region").to_owned());
+            }
+        }
+        warnings
+    }
+
+    /// ASCII frame fitting in `cols` x `rows` cells: the camera image with '-' and '|', the
+    /// zoomed region with '.' and the crop with '#'
+    pub fn frame(&self, cols: usize, rows: usize) -> Vec<String> {
+        if cols < 3 || rows < 3 {
+            return vec![];
+        }
+
+        // Same scale horizontally and vertically, taking the height of the cells into account
+        let scale = ((cols - 1) as f64 / self.image.width)
+            .min((rows - 1) as f64 * CELL_ASPECT / self.image.height);
+        let x_of = |x: f64| ((x * scale).round().max(0.0) as usize).min(cols - 1);
+        let y_of = |y: f64| ((y * scale / CELL_ASPECT).round().max(0.0) as usize).min(rows - 1);
+
+        let width = x_of(self.image.width) + 1;
+        let height = y_of(self.image.height) + 1;
+        let mut grid = vec![vec![' '; width]; height];
+
+        let mut draw = |region: &Region, horizontal: char, vertical: char, corner: char| {
+            // Regions beyond the image are clipped to its frame
+            let (x0, x1) = (
+                x_of(region.left).min(width - 1),
+                x_of(region.right()).min(width - 1),
+            );
+            let (y0, y1) = (
+                y_of(region.top).min(height - 1),
+                y_of(region.bottom()).min(height - 1),
+            );
+            for x in x0..=x1 {
+                grid[y0][x] = horizontal;
+                grid[y1][x] = horizontal;
+            }
+            for row in grid.iter_mut().take(y1 + 1).skip(y0) {
+                row[x0] = vertical;
+                row[x1] = vertical;
+            }
+            for (x, y) in [(x0, y0), (x1, y0), (x0, y1), (x1, y1)] {
+                grid[y][x] = corner;
+            }
+        };
+
+        draw(&self.image, '-', '|', '+');
+        if let Some(zoom_region) = self.zoom_region() {
+            draw(&zoom_region, '.', ':', '.');
+        }
+        if let Some(cropping) = self.cropping.as_ref()
+            && cropping.width > 0.0
+            && cropping.height > 0.0
+        {
+            draw(cropping, '#', '#', '#');
+        }
+
+        grid.into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect()
+    }
+}
+
+/// Draws the preview of the values, `value` gives the entered or else the reported value
+pub fn draw(value: impl Fn(ConfigKey) -> Option<String>, area: Rect, buf: &mut Buffer) {
+    let block = normal_block(" Crop Preview ");
+    let preview = match CropPreview::new(value) {
+        Ok(preview) => preview,
+        Err(e) => {
+            Paragraph::new(Span::styled(e, Style::default().fg(Color::Yellow)))
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+    };
+
+    let warnings = preview.warnings();
+    // Subtract 2 for border, the legend and the warnings are below the frame
+    let rows = (area.height.saturating_sub(2) as usize).saturating_sub(1 + warnings.len());
+    let cols = area.width.saturating_sub(2) as usize;
+
+    let mut lines: Vec<Line> = preview
+        .frame(cols, rows)
+        .into_iter()
+        .map(Line::from)
+        .collect();
+    lines.push(Line::from(Span::styled(
+        tr("'-' camera image, '.' digital zoom, '#' image cropping"),
+        Style::default().fg(Color::Gray),
+    )));
+    for warning in warnings {
+        lines.push(Line::from(Span::styled(
+            warning,
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    Paragraph::new(lines).block(block).render(area, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preview(values: &[(ConfigKey, &str)]) -> Result<CropPreview, String> {
+        CropPreview::new(|config_key| {
+            values
+                .iter()
+                .find(|(k, _)| *k == config_key)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    const IMAGE: [(ConfigKey, &str); 2] = [
+        (ConfigKey::CommonSettingsPQCameraImageSizeWidth, "2028"),
+        (ConfigKey::CommonSettingsPQCameraImageSizeHeight, "1520"),
+    ];
+
+    #[test]
+    fn test_crop_preview_values() {
+        assert!(preview(&[]).unwrap_err().contains("camera_image_size"));
+        let mut values = IMAGE.to_vec();
+        values.push((ConfigKey::CommonSettingsPQICLeft, "abc"));
+        assert!(preview(&values).unwrap_err().contains("not a number"));
+
+        let mut values = IMAGE.to_vec();
+        values.push((ConfigKey::CommonSettingsPQICWidth, "1000"));
+        values.push((ConfigKey::CommonSettingsPQDigitalZoom, "2.0"));
+        let p = preview(&values).unwrap();
+        // The missing coordinates default to the whole image
+        assert_eq!(
+            p.cropping,
+            Some(Region {
+                left: 0.0,
+                top: 0.0,
+                width: 1000.0,
+                height: 1520.0
+            })
+        );
+        assert_eq!(
+            p.zoom_region(),
+            Some(Region {
+                left: 507.0,
+                top: 380.0,
+                width: 1014.0,
+                height: 760.0
+            })
+        );
+        assert_eq!(
+            p.warnings(),
+            vec!["image_cropping goes beyond the digitally zoomed region"]
+        );
+    }
+
+    #[test]
+    fn test_crop_preview_warnings() {
+        let mut values = IMAGE.to_vec();
+        values.push((ConfigKey::CommonSettingsPQDigitalZoom, "0.5"));
+        values.push((ConfigKey::CommonSettingsPQICLeft, "1500"));
+        values.push((ConfigKey::CommonSettingsPQICWidth, "1000"));
+        let warnings = preview(&values).unwrap().warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("less than 1.0"));
+        assert!(warnings[1].contains("exceeds camera_image_size 2028x1520"));
+
+        let mut values = IMAGE.to_vec();
+        values.push((ConfigKey::CommonSettingsPQICLeft, "500"));
+        values.push((ConfigKey::CommonSettingsPQICTop, "400"));
+        values.push((ConfigKey::CommonSettingsPQICWidth, "1000"));
+        values.push((ConfigKey::CommonSettingsPQICHeight, "700"));
+        assert!(preview(&values).unwrap().warnings().is_empty());
+    }
+
+    #[test]
+    fn test_crop_preview_frame() {
+        let mut values = vec![
+            (ConfigKey::CommonSettingsPQCameraImageSizeWidth, "400"),
+            (ConfigKey::CommonSettingsPQCameraImageSizeHeight, "200"),
+        ];
+        values.push((ConfigKey::CommonSettingsPQICLeft, "100"));
+        values.push((ConfigKey::CommonSettingsPQICTop, "40"));
+        values.push((ConfigKey::CommonSettingsPQICWidth, "200"));
+        values.push((ConfigKey::CommonSettingsPQICHeight, "120"));
+        let frame = preview(&values).unwrap().frame(21, 20);
+        assert_eq!(
+            frame,
+            vec![
+                "+-------------------+",
+                "|    ###########    |",
+                "|    #         #    |",
+                "|    #         #    |",
+                "|    ###########    |",
+                "+-------------------+",
+            ]
+        );
+        assert!(preview(&values).unwrap().frame(2, 20).is_empty());
+    }
+}
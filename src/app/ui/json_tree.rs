@@ -0,0 +1,481 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crossterm::event::KeyCode,
+    ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{Block, Paragraph, Widget},
+    },
+    serde_json::Value,
+    std::collections::HashSet,
+};
+
+/// Levels of the tree expanded when it is opened, the objects and arrays below are collapsed
+pub const EXPANDED_DEPTH: usize = 2;
+
+/// One visible node of the tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeRow {
+    /// e.g. "a.b[0].c", the keys of the search popup
+    pub path: String,
+    pub depth: usize,
+    /// Key in the object or "[i]" in the array
+    pub label: String,
+    /// The value as JSON, or its size for objects and arrays
+    pub text: String,
+    /// Whether the node is collapsed, None for scalars
+    pub collapsed: Option<bool>,
+}
+
+impl TreeRow {
+    fn matches(&self, query: &str) -> bool {
+        self.label.to_lowercase().contains(query)
+            || (self.collapsed.is_none() && self.text.to_lowercase().contains(query))
+    }
+
+    fn line(&self) -> String {
+        let marker = match self.collapsed {
+            Some(true) => "▸ ",
+            Some(false) => "▾ ",
+            None => "  ",
+        };
+        format!(
+            "{}{}{}: {}",
+            "  ".repeat(self.depth),
+            marker,
+            self.label,
+            self.text
+        )
+    }
+}
+
+/// Value of a text, a string when it isn't JSON
+pub fn parse_json(text: &str) -> Value {
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_owned()))
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn summary(value: &Value) -> String {
+    match value {
+        Value::Object(o) if o.len() == 1 => "{1 key}".to_owned(),
+        Value::Object(o) => format!("{{{} keys}}", o.len()),
+        Value::Array(a) if a.len() == 1 => "[1 item]".to_owned(),
+        Value::Array(a) => format!("[{} items]", a.len()),
+        v => v.to_string(),
+    }
+}
+
+struct Walker<'a, F> {
+    collapsed: &'a HashSet<String>,
+    all: bool,
+    parents: Vec<String>,
+    f: &'a mut F,
+}
+
+impl<F: FnMut(TreeRow, &[String])> Walker<'_, F> {
+    fn visit(&mut self, value: &Value, path: String, label: String, depth: usize) {
+        let container = matches!(value, Value::Object(_) | Value::Array(_));
+        let collapsed = self.collapsed.contains(&path);
+        (self.f)(
+            TreeRow {
+                path: path.clone(),
+                depth,
+                label,
+                text: summary(value),
+                collapsed: container.then_some(collapsed),
+            },
+            &self.parents,
+        );
+        if collapsed && !self.all {
+            return;
+        }
+
+        self.parents.push(path.clone());
+        match value {
+            Value::Object(o) => {
+                for (k, v) in o {
+                    self.visit(v, child_path(&path, k), k.clone(), depth + 1);
+                }
+            }
+            Value::Array(a) => {
+                for (i, v) in a.iter().enumerate() {
+                    let label = format!("[{i}]");
+                    self.visit(v, format!("{path}{label}"), label, depth + 1);
+                }
+            }
+            _ => {}
+        }
+        self.parents.pop();
+    }
+}
+
+/// JSON value displayed as a tree whose objects and arrays can be collapsed, one line per node
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct JsonTree {
+    value: Value,
+    /// Paths of the collapsed objects and arrays
+    collapsed: HashSet<String>,
+    selected: usize,
+    /// Text typed after '/', None when not searching
+    search: Option<String>,
+    /// Last text searched, for 'n'
+    query: String,
+}
+
+impl JsonTree {
+    /// Tree showing the first EXPANDED_DEPTH levels
+    pub fn new(value: Value) -> Self {
+        let mut tree = Self::expanded(value);
+        let mut collapsed = HashSet::new();
+        tree.walk(true, &mut |row, _| {
+            if row.collapsed.is_some() && row.depth + 1 >= EXPANDED_DEPTH {
+                collapsed.insert(row.path.clone());
+            }
+        });
+        tree.collapsed = collapsed;
+        tree
+    }
+
+    /// Tree with every node expanded, for the panes which can't be focused
+    pub fn expanded(value: Value) -> Self {
+        Self {
+            value,
+            ..Default::default()
+        }
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Visits the nodes in document order with the paths of their parents, skipping the
+    /// children of the collapsed nodes unless `all` is set
+    fn walk(&self, all: bool, f: &mut impl FnMut(TreeRow, &[String])) {
+        let mut walker = Walker {
+            collapsed: &self.collapsed,
+            all,
+            parents: vec![],
+            f,
+        };
+
+        // The root itself is only displayed when it is a scalar
+        match &self.value {
+            Value::Object(o) => {
+                for (k, v) in o {
+                    walker.visit(v, k.clone(), k.clone(), 0);
+                }
+            }
+            Value::Array(a) => {
+                for (i, v) in a.iter().enumerate() {
+                    let label = format!("[{i}]");
+                    walker.visit(v, label.clone(), label, 0);
+                }
+            }
+            v => walker.visit(v, String::new(), "value".to_owned(), 0),
+        }
+    }
+
+    pub fn rows(&self) -> Vec<TreeRow> {
+        let mut rows = vec![];
+        self.walk(false, &mut |row, _| rows.push(row));
+        rows
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_row(&self) -> Option<TreeRow> {
+        self.rows().into_iter().nth(self.selected)
+    }
+
+    /// Path of the selected node, what (c) copies
+    pub fn selected_path(&self) -> Option<String> {
+        self.selected_row().map(|row| row.path)
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        self.selected = (self.selected + 1).min(self.rows().len().saturating_sub(1));
+    }
+
+    pub fn toggle(&mut self) {
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        if row.collapsed.is_some() && !self.collapsed.remove(&row.path) {
+            self.collapsed.insert(row.path);
+        }
+    }
+
+    pub fn expand(&mut self) {
+        if let Some(row) = self.selected_row() {
+            self.collapsed.remove(&row.path);
+        }
+    }
+
+    /// Collapses the selected node, or selects its parent when it can't be collapsed
+    pub fn collapse(&mut self) {
+        let rows = self.rows();
+        let Some(row) = rows.get(self.selected) else {
+            return;
+        };
+        if row.collapsed == Some(false) {
+            self.collapsed.insert(row.path.clone());
+        } else if let Some(parent) = rows[..self.selected]
+            .iter()
+            .rposition(|r| r.depth < row.depth)
+        {
+            self.selected = parent;
+        }
+    }
+
+    /// Selects the first node after the selected one whose key or value contains the last
+    /// searched text, expanding its parents. Returns whether one was found.
+    pub fn next_match(&mut self, include_selected: bool) -> bool {
+        if self.query.is_empty() {
+            return false;
+        }
+        let query = self.query.to_lowercase();
+        let current = self.selected_path();
+
+        let mut nodes = vec![];
+        self.walk(true, &mut |row, parents| {
+            nodes.push((row.path.clone(), row.matches(&query), parents.to_vec()))
+        });
+        let start = current
+            .and_then(|path| nodes.iter().position(|(p, ..)| *p == path))
+            .map(|i| if include_selected { i } else { i + 1 })
+            .unwrap_or(0);
+
+        // Wraps around to the top
+        let Some((path, _, parents)) = nodes[start.min(nodes.len())..]
+            .iter()
+            .chain(nodes[..start.min(nodes.len())].iter())
+            .find(|(_, matched, _)| *matched)
+            .cloned()
+        else {
+            return false;
+        };
+
+        for parent in parents {
+            self.collapsed.remove(&parent);
+        }
+        self.selected = self
+            .rows()
+            .iter()
+            .position(|row| row.path == path)
+            .unwrap_or(0);
+        true
+    }
+
+    /// Handles the keys moving in the tree, returns false for the keys it doesn't use
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        if let Some(search) = self.search.as_mut() {
+            match code {
+                KeyCode::Enter | KeyCode::Esc => self.search = None,
+                KeyCode::Backspace => {
+                    search.pop();
+                    self.query = search.clone();
+                }
+                KeyCode::Char(c) => {
+                    search.push(c);
+                    self.query = search.clone();
+                    self.next_match(true);
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => self.move_down(),
+            KeyCode::Char(' ') => self.toggle(),
+            KeyCode::Right | KeyCode::Char('l') => self.expand(),
+            KeyCode::Left | KeyCode::Char('h') => self.collapse(),
+            KeyCode::Char('/') => self.search = Some(String::new()),
+            KeyCode::Char('n') => {
+                self.next_match(false);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Lines of the visible nodes, the selected one highlighted when the tree has the focus
+    pub fn lines(&self, focus: bool) -> Vec<Line<'static>> {
+        let query = self.query.to_lowercase();
+        self.rows()
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let style = if focus && i == self.selected {
+                    Style::default().fg(Color::Black).bg(Color::White)
+                } else if focus && !query.is_empty() && row.matches(&query) {
+                    Style::default().fg(Color::Yellow)
+                } else if row.collapsed.is_some() {
+                    Style::default().bold()
+                } else {
+                    Style::default()
+                };
+                Line::from(Span::styled(row.line(), style))
+            })
+            .collect()
+    }
+
+    /// Draws the tree scrolled to the selected node, with the search being typed in the title
+    pub fn render(&self, area: Rect, buf: &mut Buffer, block: Block, focus: bool) {
+        let height = area.height.saturating_sub(2) as usize;
+        let offset = if focus {
+            (self.selected + 1).saturating_sub(height)
+        } else {
+            0
+        };
+        let block = match &self.search {
+            Some(search) => block.title_bottom(format!(" /{search}| ")),
+            None => block,
+        };
+
+        Paragraph::new(self.lines(focus))
+            .scroll((offset as u16, 0))
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Value {
+        serde_json::json!({
+            "res_info": {"res_id": "1", "code": 0},
+            "instances": {
+                "a": {"status": "ok", "modules": ["m1", "m2"]}
+            },
+            "name": "camera"
+        })
+    }
+
+    fn paths(tree: &JsonTree) -> Vec<String> {
+        tree.rows().into_iter().map(|row| row.path).collect()
+    }
+
+    #[test]
+    fn test_rows() {
+        let tree = JsonTree::new(sample());
+        // The objects of the second level are collapsed
+        assert_eq!(
+            paths(&tree),
+            vec![
+                "instances",
+                "instances.a",
+                "name",
+                "res_info",
+                "res_info.code",
+                "res_info.res_id"
+            ]
+        );
+        let rows = tree.rows();
+        assert_eq!(rows[1].line(), "  ▸ a: {2 keys}");
+        assert_eq!(rows[2].line(), "  name: \"camera\"");
+
+        let tree = JsonTree::expanded(sample());
+        assert!(paths(&tree).contains(&"instances.a.modules[1]".to_owned()));
+
+        let tree = JsonTree::new(parse_json("not json"));
+        assert_eq!(tree.rows()[0].line(), "  value: \"not json\"");
+    }
+
+    #[test]
+    fn test_expand_and_collapse() {
+        let mut tree = JsonTree::new(sample());
+        tree.move_down();
+        tree.toggle();
+        assert_eq!(tree.rows().len(), 8);
+        tree.move_down();
+        assert_eq!(tree.selected_path().as_deref(), Some("instances.a.modules"));
+
+        // Collapsing a leaf selects its parent, then collapses it
+        tree.collapse();
+        assert_eq!(tree.selected_path().as_deref(), Some("instances.a"));
+        tree.collapse();
+        assert_eq!(tree.rows().len(), 6);
+        assert!(tree.handle_key(KeyCode::Right));
+        assert_eq!(tree.rows().len(), 8);
+        assert!(!tree.handle_key(KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn test_search() {
+        let mut tree = JsonTree::new(sample());
+        tree.handle_key(KeyCode::Char('/'));
+        assert!(tree.is_searching());
+        "m2".chars().for_each(|c| {
+            tree.handle_key(KeyCode::Char(c));
+        });
+        tree.handle_key(KeyCode::Enter);
+        assert!(!tree.is_searching());
+        // The collapsed parents were expanded
+        assert_eq!(
+            tree.selected_path().as_deref(),
+            Some("instances.a.modules[1]")
+        );
+
+        tree.handle_key(KeyCode::Char('/'));
+        "code".chars().for_each(|c| {
+            tree.handle_key(KeyCode::Char(c));
+        });
+        tree.handle_key(KeyCode::Esc);
+        assert_eq!(tree.selected_path().as_deref(), Some("res_info.code"));
+
+        // Wraps around to the only match
+        assert!(tree.next_match(false));
+        assert_eq!(tree.selected_path().as_deref(), Some("res_info.code"));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut tree = JsonTree::new(sample());
+        tree.move_down();
+        let area = Rect::new(0, 0, 40, 5);
+        let mut buf = Buffer::empty(area);
+        tree.render(area, &mut buf, Block::bordered().title(" Raw JSON "), true);
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("▸ a: {2 keys}"));
+    }
+}
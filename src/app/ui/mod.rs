@@ -14,43 +14,86 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+pub mod crop_preview;
+pub mod json_tree;
 pub mod ui_ai_model;
 pub mod ui_ai_model_config;
+pub mod ui_audit;
+pub mod ui_compare;
 pub mod ui_config;
 pub mod ui_config_user;
+pub mod ui_confirm;
+pub mod ui_debug_log;
 pub mod ui_deploy;
+pub mod ui_deployment_detail;
 pub mod ui_directcmd;
+pub mod ui_downloads;
+pub mod ui_dtmi;
 pub mod ui_edge_app;
+pub mod ui_edge_app_instances;
 pub mod ui_elog;
+pub mod ui_errors;
 pub mod ui_exit;
+pub mod ui_file_browser;
+pub mod ui_fleet;
 pub mod ui_foot;
 pub mod ui_head;
+pub mod ui_inventory;
+pub mod ui_jobs;
+pub mod ui_kiosk;
+pub mod ui_log_files;
+pub mod ui_log_settings;
+pub mod ui_log_viewer;
 pub mod ui_main;
 pub mod ui_module;
+pub mod ui_network;
+pub mod ui_notes;
 pub mod ui_ota;
 pub mod ui_ota_config;
+pub mod ui_ota_wizard;
+pub mod ui_pane_detail;
+pub mod ui_profile;
+pub mod ui_publish;
+pub mod ui_reboots;
+pub mod ui_retained;
+pub mod ui_scripts;
+pub mod ui_search;
+pub mod ui_static_ip_wizard;
+pub mod ui_storage_settings;
+pub mod ui_subscriptions;
 pub mod ui_token_provider;
 pub mod ui_token_provider_blobs;
+pub mod ui_wifi_wizard;
+
+#[cfg(test)]
+mod snapshot_tests;
 
 #[allow(unused)]
 use {
     crate::{
         app::{App, DMScreen},
+        azurite::sas::SasInfo,
         error::DMError,
+        i18n::{format_datetime, format_elapsed, tr, tr_hint, tr_padded, trf},
         mqtt_ctrl::{
             MqttCtrl,
+            battery::{BatteryTracker, LOW_BATTERY_LEFT, battery_gauge},
             evp::device_info::{ChipInfo, DeviceInfo},
             evp::evp_state::{AgentDeviceConfig, AgentSystemInfo, UUID},
             evp::{
+                clock::{self, ClockDrift, TimeZoneMode},
+                device_info::mask_secret,
                 device_info::{
                     DeviceCapabilities, DeviceReserved, DeviceStates, NetworkSettings,
                     SystemSettings, WirelessSettings,
                 },
-                evp_state::DeploymentStatus,
+                evp_state::{DeploymentStatus, is_error_status},
             },
+            reboots::format_uptime,
         },
     },
     base64::{Engine as _, engine::general_purpose},
+    chrono::FixedOffset,
     crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
     error_stack::{Report, Result},
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
@@ -82,6 +125,23 @@ use {
     },
 };
 
+/// Frames of the spinner shown on operations in progress
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Spinner frame for an operation running for `elapsed`
+pub fn spinner(elapsed: std::time::Duration) -> char {
+    SPINNER[(elapsed.as_millis() / 100) as usize % SPINNER.len()]
+}
+
+/// Time reported by the device in the selected time zone, shown as reported when it isn't in
+/// RFC 3339
+pub fn format_device_time(time: &str, zone: TimeZoneMode, device: FixedOffset) -> String {
+    match clock::parse_device_time(time) {
+        Some(time) => format_datetime(&zone.convert(&time, device)),
+        None => time.to_owned(),
+    }
+}
+
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -102,6 +162,13 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
+/// Text padded with spaces to `width` columns of the terminal, where a Japanese character
+/// takes two
+pub fn pad_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(Span::raw(text).width());
+    format!("{}{}", text, " ".repeat(padding))
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum BlockType {
     Normal,
@@ -110,14 +177,17 @@ pub enum BlockType {
 
 pub fn normal_block(title: &str) -> Block<'_> {
     Block::default()
-        .title(Span::styled(title, Style::new().fg(Color::Yellow)))
+        .title(Span::styled(
+            tr_padded(title),
+            Style::new().fg(Color::Yellow),
+        ))
         .borders(Borders::ALL)
 }
 
 pub fn focus_block(title: &str) -> Block<'_> {
     Block::default()
         .title(Span::styled(
-            title,
+            tr_padded(title),
             Style::new().fg(Color::LightYellow).bold(),
         ))
         .borders(Borders::ALL)
@@ -125,6 +195,24 @@ pub fn focus_block(title: &str) -> Block<'_> {
         .bold()
 }
 
+/// Filter input of the blob name in the module and blob lists
+pub fn draw_filter(filter: &str, editing: bool, matching: usize, area: Rect, buf: &mut Buffer) {
+    let (block, text) = if editing {
+        (focus_block(" Filter "), format!("{}|", filter))
+    } else if filter.is_empty() {
+        (
+            normal_block(" Filter "),
+            tr("(/) to filter by blob name, (o) to sort by another column").to_owned(),
+        )
+    } else {
+        (
+            normal_block(" Filter "),
+            trf("{0}  ({1} matching)", &[&filter, &matching]),
+        )
+    };
+    Paragraph::new(text).block(block).render(area, buf);
+}
+
 pub fn list_items_push_text_focus(list_items: &mut Vec<ListItem>, value: &str, focus: bool) {
     if focus {
         list_items.push(ListItem::new(Span::styled(
@@ -140,16 +228,39 @@ pub fn list_items_push_text_focus(list_items: &mut Vec<ListItem>, value: &str, f
 }
 
 pub fn list_items_push_focus(list_items: &mut Vec<ListItem>, name: &str, value: &str, focus: bool) {
-    list_items_push_text_focus(list_items, &format!("{:<35} : {}", name, value), focus);
+    list_items_push_text_focus(
+        list_items,
+        &format!("{} : {}", pad_width(name, 35), value),
+        focus,
+    );
 }
 
 pub fn list_items_push(list_items: &mut Vec<ListItem>, name: &str, value: &str) {
     list_items.push(ListItem::new(Span::styled(
-        format!("{:<35} : {}", name, value),
+        format!("{} : {}", pad_width(name, 35), value),
         Style::default(),
     )));
 }
 
+/// Pushes the permissions and the expiry countdown of a SAS URL, in red once expired.
+/// Nothing is pushed for URLs without SAS parameters.
+pub fn list_items_push_sas(list_items: &mut Vec<ListItem>, name: &str, url: &str) {
+    let Some(sas) = SasInfo::parse(url) else {
+        return;
+    };
+
+    let now = chrono::Utc::now();
+    let color = if sas.is_expired(now) {
+        Color::Red
+    } else {
+        Color::White
+    };
+    list_items.push(ListItem::new(Span::styled(
+        format!("{} : {}", pad_width(name, 35), sas.summary(now)),
+        Style::default().fg(color),
+    )));
+}
+
 pub fn list_items_push_blank(list_items: &mut Vec<ListItem>) {
     list_items.push(ListItem::new(Span::styled("", Style::default())));
 }
@@ -166,84 +277,64 @@ pub fn list_items_push_dynamic(
     )));
 }
 
+/// Header, payload and signature of the device manifest, a JWT. None when it isn't one.
+pub fn decode_device_manifest(manifest: &str) -> Option<Value> {
+    let parts: Vec<&str> = manifest.split('.').collect();
+    jdebug!(func = "decode_device_manifest", parts = parts.len());
+    let [header, payload, signature] = parts[..] else {
+        return None;
+    };
+
+    let decode_part = |part: &str| {
+        general_purpose::URL_SAFE_NO_PAD
+            .decode(part)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+    };
+
+    Some(serde_json::json!({
+        "header": decode_part(header)?,
+        "payload": decode_part(payload)?,
+        "signature": signature,
+    }))
+}
+
 pub fn draw_device_manifest(
     area: Rect,
     buf: &mut Buffer,
     device_info: Option<&DeviceInfo>,
     block_type: BlockType,
 ) -> Result<(), DMError> {
-    if let Some(device_info) = device_info {
-        let mut device_manifest_str = String::new();
-        if let Some(s) = device_info.device_manifest() {
-            let parts: Vec<&str> = s.split('.').collect();
-            jdebug!(func = "draw_device_manifest", manifest = s);
-            jdebug!(func = "draw_device_manifest", parts = parts.len());
-            if parts.len() != 3 {
-                device_manifest_str = "Invalid JWT".to_owned();
-            } else {
-                let decode_part = |part: &str| {
-                    general_purpose::URL_SAFE_NO_PAD
-                        .decode(part)
-                        .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
-                };
-
-                let pretty_string = |json_str: &str| {
-                    let json_value: Value = serde_json::from_str(json_str).unwrap_or_default();
-                    serde_json::to_string_pretty(&json_value)
-                        .unwrap_or_else(|_| "Invalid JSON".to_owned())
-                };
-
-                let mut header = String::new();
-                if let Ok(h) = decode_part(parts[0]) {
-                    header = pretty_string(&h);
-                }
-
-                let mut payload = String::new();
-                if let Ok(p) = decode_part(parts[1]) {
-                    payload = pretty_string(&p);
-                }
-
-                let signature = parts[2].to_string();
-                if !header.is_empty() && !payload.is_empty() {
-                    device_manifest_str = format!(
-                        "Header:\n{}\n\nPayload:\n{}\n\nSignature:\n{{\n    {}\n}}",
-                        header, payload, signature
-                    );
-                } else {
-                    device_manifest_str = "Invalid JWT".to_owned();
-                }
-            }
+    let title = " DEVICE MANIFEST ";
+    let block = match block_type {
+        BlockType::Normal => normal_block(title),
+        BlockType::Focus => focus_block(title),
+    };
+
+    match device_info.map(|d| d.device_manifest().map(decode_device_manifest)) {
+        Some(Some(Some(manifest))) => {
+            json_tree::JsonTree::expanded(manifest).render(area, buf, block, false)
         }
-
-        let title = " DEVICE MANIFEST ";
-        let block = match block_type {
-            BlockType::Normal => normal_block(title),
-            BlockType::Focus => focus_block(title),
-        };
-
-        Paragraph::new(device_manifest_str)
+        Some(Some(None)) => Paragraph::new(tr("Invalid JWT"))
             .block(block)
-            .render(area, buf);
-    } else {
-        let title = " DEVICE MANIFEST ";
-        let block = match block_type {
-            BlockType::Normal => normal_block(title),
-            BlockType::Focus => focus_block(title),
-        };
-        Paragraph::new("No data available")
+            .render(area, buf),
+        Some(None) => Paragraph::new("").block(block).render(area, buf),
+        None => Paragraph::new(tr("No data available"))
             .block(block)
-            .render(area, buf);
+            .render(area, buf),
     }
 
     Ok(())
 }
 
+/// Draws the chip pane, with a red border while the temperature alarm of the chip is raised
 pub fn draw_chip_info(
     area: Rect,
     buf: &mut Buffer,
     device_info: Option<&DeviceInfo>,
     chip_name: &str,
     block_type: BlockType,
+    alarm: bool,
 ) -> Result<(), DMError> {
     if let Some(device_info) = device_info {
         let mut list_items = Vec::<ListItem>::new();
@@ -319,10 +410,13 @@ pub fn draw_chip_info(
             }
 
             let title = format!(" {} ", chip_name.replace("_", " ").to_uppercase());
-            let block = match block_type {
+            let mut block = match block_type {
                 BlockType::Normal => normal_block(&title),
                 BlockType::Focus => focus_block(&title),
             };
+            if alarm {
+                block = block.border_style(Style::default().fg(Color::Red));
+            }
             List::new(list_items).block(block).render(area, buf);
         } else {
             let title = format!(" {} ", chip_name.replace("_", " ").to_uppercase());
@@ -330,7 +424,7 @@ pub fn draw_chip_info(
                 BlockType::Normal => normal_block(&title),
                 BlockType::Focus => focus_block(&title),
             };
-            Paragraph::new("No data available")
+            Paragraph::new(tr("No data available"))
                 .block(block)
                 .render(area, buf);
         }
@@ -340,7 +434,7 @@ pub fn draw_chip_info(
             BlockType::Normal => normal_block(&title),
             BlockType::Focus => focus_block(&title),
         };
-        Paragraph::new("No data available")
+        Paragraph::new(tr("No data available"))
             .block(block)
             .render(area, buf);
     }
@@ -420,7 +514,7 @@ pub fn draw_agent_state(
             BlockType::Normal => normal_block(title),
             BlockType::Focus => focus_block(title),
         };
-        Paragraph::new("No data available")
+        Paragraph::new(tr("No data available"))
             .block(block)
             .render(area, buf);
     }
@@ -438,31 +532,49 @@ pub fn draw_deployment_status(
         let mut list_items = Vec::<ListItem>::new();
         let width = 18;
 
+        // Entries in an error state are highlighted
+        let push_status = |list_items: &mut Vec<ListItem>, status: &str, failure: Option<&str>| {
+            if is_error_status(status) {
+                let style = Style::default().fg(Color::Red).bold();
+                list_items.push(ListItem::new(Span::styled(
+                    format!("{:<width$} : {}", "  status", status, width = width),
+                    style,
+                )));
+                list_items.push(ListItem::new(Span::styled(
+                    format!(
+                        "{:<width$} : {}",
+                        "  failure_message",
+                        failure.unwrap_or(""),
+                        width = width
+                    ),
+                    style,
+                )));
+            } else {
+                list_items_push_dynamic(list_items, width, "  status", status);
+                list_items_push_dynamic(
+                    list_items,
+                    width,
+                    "  failure_message",
+                    failure.unwrap_or(""),
+                );
+            }
+        };
+
         for (k, (uuid, instance)) in deployment_status.instances().iter().enumerate() {
             list_items_push_text_focus(&mut list_items, &format!("instance[{}]", k), false);
             list_items_push_dynamic(&mut list_items, width, "  uuid", uuid.uuid());
-            list_items_push_dynamic(&mut list_items, width, "  status", instance.status());
             list_items_push_dynamic(&mut list_items, width, "  module_id", instance.module_id());
-
-            list_items_push_dynamic(
+            push_status(
                 &mut list_items,
-                width,
-                "  failure_message",
-                instance.failure_message().unwrap_or(""),
+                instance.status(),
+                instance.failure_message(),
             );
         }
 
         for (k, (uuid, module)) in deployment_status.modules().iter().enumerate() {
             list_items_push_text_focus(&mut list_items, &format!("module[{}]", k), false);
             list_items_push_dynamic(&mut list_items, width, "  uuid", uuid.uuid());
-            list_items_push_dynamic(&mut list_items, width, "  status", module.status());
-
-            list_items_push_dynamic(
-                &mut list_items,
-                width,
-                "  failure_message",
-                module.failure_message().unwrap_or(""),
-            );
+            push_status(&mut list_items, module.status(), module.failure_message());
         }
 
         list_items_push_dynamic(
@@ -482,10 +594,15 @@ pub fn draw_deployment_status(
             deployment_status.reconcile_status().unwrap_or_default(),
         );
 
-        let title = " DEPLOYMENT STATUS ";
+        let failures = deployment_status.failures().len();
+        let title = if failures > 0 {
+            trf(" DEPLOYMENT STATUS ({0} failure(s)) ", &[&failures])
+        } else {
+            " DEPLOYMENT STATUS ".to_owned()
+        };
         let block = match block_type {
-            BlockType::Normal => normal_block(title),
-            BlockType::Focus => focus_block(title),
+            BlockType::Normal => normal_block(&title),
+            BlockType::Focus => focus_block(&title),
         };
 
         List::new(list_items).block(block).render(area, buf);
@@ -495,7 +612,7 @@ pub fn draw_deployment_status(
             BlockType::Normal => normal_block(title),
             BlockType::Focus => focus_block(title),
         };
-        Paragraph::new("No data available")
+        Paragraph::new(tr("No data available"))
             .block(block)
             .render(area, buf);
     }
@@ -548,7 +665,7 @@ pub fn draw_device_reserved(
             BlockType::Normal => normal_block(title),
             BlockType::Focus => focus_block(title),
         };
-        Paragraph::new("No data available")
+        Paragraph::new(tr("No data available"))
             .block(block)
             .render(area, buf);
     }
@@ -556,10 +673,25 @@ pub fn draw_device_reserved(
     Ok(())
 }
 
+/// Battery gauge with its state and the time to empty while discharging, None without battery
+fn battery_text(battery: &BatteryTracker) -> Option<(String, bool)> {
+    let (level, state) = (battery.level()?, battery.state()?);
+    let mut text = format!("{} {}", battery_gauge(level), tr(state.name()));
+    let left = battery.time_to_empty();
+    if let Some(left) = left {
+        text.push_str(&trf(", {0} left", &[&format_uptime(left)]));
+    }
+    Some((text, left.is_some_and(|left| left < LOW_BATTERY_LEFT)))
+}
+
 pub fn draw_device_states(
     area: Rect,
     buf: &mut Buffer,
     device_states: Option<&DeviceStates>,
+    battery: &BatteryTracker,
+    clock_drift: Option<ClockDrift>,
+    zone: TimeZoneMode,
+    device_offset: FixedOffset,
     block_type: BlockType,
 ) -> Result<(), DMError> {
     if let Some(device_states) = device_states {
@@ -588,6 +720,16 @@ pub fn draw_device_states(
                 .to_string()
                 .as_str(),
         );
+        match battery_text(battery) {
+            Some((text, true)) => list_items.push(ListItem::new(Span::styled(
+                format!("{:<padding$} : {}", "battery", text, padding = width),
+                Style::default().fg(Color::Red).bold(),
+            ))),
+            Some((text, false)) => {
+                list_items_push_dynamic(&mut list_items, width, "battery", &text)
+            }
+            None => {}
+        }
         list_items_push_dynamic(
             &mut list_items,
             width,
@@ -610,8 +752,26 @@ pub fn draw_device_states(
             &mut list_items,
             width,
             "last_bootup_time",
-            device_states.last_bootup_time(),
+            &format_device_time(device_states.last_bootup_time(), zone, device_offset),
         );
+        match clock_drift {
+            Some(drift) if drift.exceeds_threshold() => {
+                list_items.push(ListItem::new(Span::styled(
+                    format!(
+                        "{:<padding$} : {} {}",
+                        "clock_drift",
+                        drift,
+                        tr("(check ntp_url)"),
+                        padding = width
+                    ),
+                    Style::default().fg(Color::Red).bold(),
+                )));
+            }
+            Some(drift) => {
+                list_items_push_dynamic(&mut list_items, width, "clock_drift", &drift.to_string())
+            }
+            None => {}
+        }
 
         let title = " DEVICE STATE ";
         let block = match block_type {
@@ -625,7 +785,7 @@ pub fn draw_device_states(
             BlockType::Normal => normal_block(title),
             BlockType::Focus => focus_block(title),
         };
-        Paragraph::new("No data available")
+        Paragraph::new(tr("No data available"))
             .block(block)
             .render(area, buf);
     }
@@ -690,7 +850,7 @@ pub fn draw_device_capabilities(
             BlockType::Normal => normal_block(title),
             BlockType::Focus => focus_block(title),
         };
-        Paragraph::new("No data available")
+        Paragraph::new(tr("No data available"))
             .block(block)
             .render(area, buf);
     }
@@ -799,7 +959,7 @@ pub fn draw_system_settings(
             BlockType::Normal => normal_block(title),
             BlockType::Focus => focus_block(title),
         };
-        Paragraph::new("No data available")
+        Paragraph::new(tr("No data available"))
             .block(block)
             .render(area, buf);
     }
@@ -811,6 +971,7 @@ pub fn draw_network_settings(
     area: Rect,
     buf: &mut Buffer,
     network_settings: Option<&NetworkSettings>,
+    reveal_secrets: bool,
     block_type: BlockType,
 ) -> Result<(), DMError> {
     if let Some(network_settings) = network_settings {
@@ -877,6 +1038,11 @@ pub fn draw_network_settings(
                 list_items_push_dynamic(&mut list_items, width, "  user_name", user_name);
             }
             if let Some(password) = proxy_settings.password() {
+                let password = if reveal_secrets {
+                    password
+                } else {
+                    mask_secret(password)
+                };
                 list_items_push_dynamic(&mut list_items, width, "  password", password);
             }
         }
@@ -916,7 +1082,7 @@ pub fn draw_network_settings(
             BlockType::Normal => normal_block(title),
             BlockType::Focus => focus_block(title),
         };
-        Paragraph::new("No data available")
+        Paragraph::new(tr("No data available"))
             .block(block)
             .render(area, buf);
     }
@@ -928,6 +1094,7 @@ pub fn draw_wireless_settings(
     area: Rect,
     buf: &mut Buffer,
     wireless_settings: Option<&WirelessSettings>,
+    reveal_secrets: bool,
     block_type: BlockType,
 ) -> Result<(), DMError> {
     if let Some(wireless_settings) = wireless_settings {
@@ -945,12 +1112,12 @@ pub fn draw_wireless_settings(
         if let Some(station_setting) = wireless_settings.sta_mode_setting() {
             list_items_push_text_focus(&mut list_items, "station", false);
             list_items_push_dynamic(&mut list_items, width, "  ssid", station_setting.ssid());
-            list_items_push_dynamic(
-                &mut list_items,
-                width,
-                "  password",
-                station_setting.password(),
-            );
+            let password = if reveal_secrets {
+                station_setting.password()
+            } else {
+                mask_secret(station_setting.password())
+            };
+            list_items_push_dynamic(&mut list_items, width, "  password", password);
 
             list_items_push_dynamic(
                 &mut list_items,
@@ -995,7 +1162,7 @@ pub fn draw_wireless_settings(
             BlockType::Normal => normal_block(title),
             BlockType::Focus => focus_block(title),
         };
-        Paragraph::new("No data available")
+        Paragraph::new(tr("No data available"))
             .block(block)
             .render(area, buf);
     }
@@ -1010,6 +1177,55 @@ mod tests {
     use ratatui::layout::Rect;
     use ratatui::widgets::ListItem;
 
+    #[test]
+    fn test_decode_device_manifest() {
+        let encode = |s: &str| general_purpose::URL_SAFE_NO_PAD.encode(s);
+        let manifest = format!(
+            "{}.{}.sig",
+            encode(r#"{"alg":"ES256"}"#),
+            encode(r#"{"aud":"aitrios"}"#)
+        );
+
+        let decoded = decode_device_manifest(&manifest).unwrap();
+        assert_eq!(decoded["header"]["alg"], "ES256");
+        assert_eq!(decoded["payload"]["aud"], "aitrios");
+        assert_eq!(decoded["signature"], "sig");
+
+        assert!(decode_device_manifest("a.b").is_none());
+        assert!(decode_device_manifest(&format!("{}.!.sig", encode("{}"))).is_none());
+    }
+
+    #[test]
+    fn test_battery_text() {
+        use crate::mqtt_ctrl::evp::device_info::PowerStates;
+
+        let power_states = |in_use: i8, level: i8| -> PowerStates {
+            serde_json::from_str(&format!(
+                r#"{{"source":[{{"type":3,"level":{level}}}],"in_use":{in_use},"is_battery_low":false}}"#
+            ))
+            .unwrap()
+        };
+        let now = chrono::Local::now();
+        let mut battery = BatteryTracker::default();
+        assert_eq!(battery_text(&battery), None);
+
+        battery.record(&power_states(1, 60), now);
+        assert_eq!(
+            battery_text(&battery),
+            Some(("[██████░░░░] 60% charging".to_owned(), false))
+        );
+
+        battery.record(&power_states(3, 30), now);
+        battery.record(&power_states(3, 20), now + chrono::Duration::minutes(10));
+        assert_eq!(
+            battery_text(&battery),
+            Some((
+                "[██░░░░░░░░] 20% discharging, 00:20:00 left".to_owned(),
+                true
+            ))
+        );
+    }
+
     #[test]
     fn test_centered_rect() {
         // Outer rect 100x40, request 50% x and y -> inner rect should be 50x20 centered at (25,10)
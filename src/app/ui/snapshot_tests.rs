@@ -0,0 +1,255 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{ui_log_viewer::LogViewerState, ui_token_provider_blobs::BlobListState},
+    crate::{
+        app::{App, AppConfig, DMScreen, DMScreenState, screens::*},
+        mqtt_ctrl::{MqttCtrl, with_local_mqtt_ctrl, with_mqtt_ctrl},
+        provisioning::{static_ip::StaticIpWizard, wifi::WifiWizard},
+    },
+    ratatui::{Terminal, backend::TestBackend},
+};
+
+const WIDTH: u16 = 160;
+const HEIGHT: u16 = 48;
+
+/// Opens a screen for the App which draws it, some of them load their state from its settings
+type OpenScreen = fn(&App) -> Box<dyn Screen>;
+
+/// Every screen, with the name of its snapshot
+const SCREENS: [(&str, OpenScreen); 38] = [
+    ("main", |_| Box::new(MainScreen)),
+    ("kiosk", |_| Box::new(KioskScreen)),
+    ("module", |_| Box::new(ModuleScreen::default())),
+    (
+        "configuration",
+        |_| Box::new(ConfigurationScreen::default()),
+    ),
+    ("configuration_user", |_| Box::new(ConfigurationUserScreen)),
+    ("direct_command", |_| Box::new(DirectCommandScreen)),
+    ("evp_module", |_| Box::new(EvpModuleScreen::default())),
+    ("token_provider", |_| {
+        Box::new(TokenProviderScreen::default())
+    }),
+    ("token_provider_blobs", |_| {
+        Box::new(TokenProviderBlobsScreen::new(BlobListState::new(
+            "container".to_owned(),
+        )))
+    }),
+    ("log_files", |_| {
+        Box::new(LogFilesScreen::new(BlobListState::new(
+            "container".to_owned(),
+        )))
+    }),
+    ("log_viewer", |_| {
+        Box::new(LogViewerScreen {
+            state: LogViewerState::new("main.log", b"10:00:00 INFO started\n"),
+        })
+    }),
+    ("elog", |_| Box::new(ElogScreen::default())),
+    ("edge_app_instances", |_| {
+        Box::new(EdgeAppInstancesScreen::new(0))
+    }),
+    ("edge_app", |_| {
+        Box::new(EdgeAppScreen(DMScreenState::Initial))
+    }),
+    ("ota", |_| Box::new(OtaScreen)),
+    ("ota_config", |_| {
+        Box::new(OtaConfigScreen(DMScreenState::Initial))
+    }),
+    ("ota_wizard", |_| Box::new(OtaWizardScreen)),
+    ("wifi_wizard", |_| {
+        Box::new(WifiWizardScreen {
+            wizard: WifiWizard::new(),
+        })
+    }),
+    ("static_ip_wizard", |_| {
+        let wizard = with_mqtt_ctrl(|mqtt_ctrl| StaticIpWizard::new(mqtt_ctrl.network_settings()));
+        Box::new(StaticIpWizardScreen { wizard })
+    }),
+    ("ai_model", |_| Box::new(AiModelScreen)),
+    ("ai_model_config", |_| {
+        Box::new(AiModelConfigScreen(DMScreenState::Initial))
+    }),
+    ("storage_settings", |_| Box::new(StorageSettingsScreen)),
+    ("downloads", |_| Box::new(DownloadsScreen)),
+    ("publish", |_| Box::new(PublishScreen)),
+    ("retained", |_| Box::new(RetainedScreen::open())),
+    (
+        "subscriptions",
+        |_| Box::new(SubscriptionsScreen::default()),
+    ),
+    ("dtmi", |app| Box::new(DtmiScreen::open(app))),
+    ("network", |_| Box::new(NetworkScreen)),
+    ("scripts", |_| Box::new(ScriptsScreen)),
+    ("jobs", |_| Box::new(JobsScreen::default())),
+    ("reboots", |_| Box::new(RebootsScreen)),
+    ("audit", |_| Box::new(AuditScreen::default())),
+    ("inventory", |app| Box::new(InventoryScreen::open(app))),
+    ("compare", |app| Box::new(CompareScreen::open(app))),
+    ("fleet", |app| Box::new(FleetScreen::open(app))),
+    ("debug_log", |_| Box::new(DebugLogScreen)),
+    ("log_settings", |_| Box::new(LogSettingsScreen::default())),
+    ("exiting", |_| Box::new(ExitingScreen)),
+];
+
+/// Times of the session, replaced so that the snapshots don't depend on when they are taken
+const FILTERS: [(&str, &str); 3] = [
+    (r"\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}:\d{2}", "[datetime]"),
+    (r"\d+ day \d+h \d+m \d+s ago", "[elapsed]"),
+    (r"\b\d{2}:\d{2}:\d{2}\b", "[time]"),
+];
+
+const DEVICE_INFO: &str = r#"{
+    "device_manifest": "",
+    "chips": [
+        {
+            "name": "main_chip", "id": "100A50500A2010072364012000000000",
+            "hardware_version": "1.0", "temperature": 42,
+            "loader_version": "020301", "loader_hash": "", "update_date_loader": "",
+            "firmware_version": "D52408", "firmware_hash": "", "update_date_firmware": "",
+            "ai_models": []
+        },
+        {
+            "name": "sensor_chip", "id": "100A50500A2010072364012000000000",
+            "hardware_version": "IMX500", "temperature": 38,
+            "loader_version": "020301", "loader_hash": "", "update_date_loader": "",
+            "firmware_version": "010707", "firmware_hash": "", "update_date_firmware": "",
+            "ai_models": [
+                { "version": "0308000000000100", "hash": "", "update_date": "2025-01-01T00:00:00.000Z" }
+            ]
+        }
+    ]
+}"#;
+
+const DEVICE_STATES: &str = r#"{
+    "power_states": { "source": [{ "type": 0, "level": 100 }], "in_use": 0, "is_battery_low": false },
+    "process_state": "Idle",
+    "hours_meter": 120,
+    "bootup_reason": 0,
+    "last_bootup_time": ""
+}"#;
+
+const DEVICE_CAPABILITIES: &str = r#"{
+    "is_battery_supported": false,
+    "supported_wireless_mode": 1,
+    "is_periodic_supported": false,
+    "is_sensor_postprocess_supported": true
+}"#;
+
+const SYSTEM_SETTINGS: &str = r#"{
+    "req_info": { "req_id": "0" },
+    "led_enabled": true,
+    "temperature_update_interval": 10,
+    "log_settings": [
+        { "filter": "all", "level": 3, "destination": 0, "storage_name": "", "path": "" }
+    ],
+    "res_info": { "res_id": "0", "code": 0, "detail_msg": "ok" }
+}"#;
+
+const NETWORK_SETTINGS: &str = r#"{
+    "req_info": { "req_id": "0" },
+    "ip_method": 1,
+    "ntp_url": "pool.ntp.org",
+    "static_settings_ipv4": {
+        "ip_address": "192.168.28.39", "subnet_mask": "255.255.255.0",
+        "gateway_address": "192.168.28.1", "dns_address": "8.8.8.8"
+    },
+    "res_info": { "res_id": "0", "code": 0, "detail_msg": "ok" }
+}"#;
+
+const WIRELESS_SETTINGS: &str = r#"{
+    "req_info": { "req_id": "0" },
+    "sta_mode_setting": { "ssid": "lab", "password": "secret", "encryption": 2 },
+    "rssi": -48,
+    "connection_state": "connected",
+    "res_info": { "res_id": "0", "code": 0, "detail_msg": "ok" }
+}"#;
+
+const ELOG: &str = r#"{"$system/event_log": {
+    "serial": "SN001", "level": 1, "timestamp": "2025-01-01T10:00:00Z",
+    "component_id": 100, "component_name": "Main", "event_id": 4096,
+    "event_description": "Critical error"
+}}"#;
+
+/// Fixture device, fed to the MqttCtrl as the messages it would send
+fn fixture_mqtt_ctrl() -> MqttCtrl {
+    // Nothing listens on the port, the MqttCtrl is never updated
+    let mut mqtt_ctrl = MqttCtrl::new("127.0.0.1", 1).unwrap();
+
+    // The device sends the values of the state keys as JSON strings
+    let state = serde_json::json!({
+        "state/$system/device_info": DEVICE_INFO,
+        "state/$system/device_states": DEVICE_STATES,
+        "state/$system/device_capabilities": DEVICE_CAPABILITIES,
+        "state/$system/system_settings": SYSTEM_SETTINGS,
+        "state/$system/network_settings": NETWORK_SETTINGS,
+        "state/$system/wireless_setting": WIRELESS_SETTINGS,
+    });
+    mqtt_ctrl
+        .on_message("v1/devices/me/attributes", &state.to_string())
+        .unwrap();
+    mqtt_ctrl
+        .on_message("v1/devices/me/telemetry", ELOG)
+        .unwrap();
+    mqtt_ctrl
+}
+
+/// Renders the whole UI on the screen, as the terminal would display it
+fn render(open_screen: OpenScreen, mqtt_ctrl: MqttCtrl) -> String {
+    let mut app = App::new(AppConfig {
+        broker: "localhost",
+        ..Default::default()
+    })
+    .unwrap();
+
+    let (mut mqtt_ctrl, backend) = with_local_mqtt_ctrl(mqtt_ctrl, || {
+        let screen = open_screen(&app);
+        if screen.id() != DMScreen::Main {
+            app.screens.push(screen);
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        terminal
+            .draw(|frame| frame.render_widget(&app, frame.area()))
+            .unwrap();
+        terminal.backend().to_string()
+    });
+    mqtt_ctrl.exit();
+    backend
+}
+
+fn assert_screen(name: &str, open_screen: OpenScreen, mqtt_ctrl: MqttCtrl) {
+    let rendered = render(open_screen, mqtt_ctrl);
+    insta::with_settings!({ filters => FILTERS.to_vec() }, {
+        insta::assert_snapshot!(name, rendered);
+    });
+}
+
+#[test]
+fn test_snapshot_screens() {
+    for (name, open_screen) in SCREENS {
+        assert_screen(name, open_screen, fixture_mqtt_ctrl());
+    }
+}
+
+#[test]
+fn test_snapshot_main_disconnected() {
+    let mqtt_ctrl = MqttCtrl::new("127.0.0.1", 1).unwrap();
+    assert_screen("main_disconnected", |_| Box::new(MainScreen), mqtt_ctrl);
+}
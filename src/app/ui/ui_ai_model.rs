@@ -20,6 +20,7 @@ use {
         ai_model::{AiModel, Target},
         app::App,
         error::DMError,
+        i18n::{tr, tr_padded, trf},
         mqtt_ctrl::{evp::ProcessState, with_mqtt_ctrl},
     },
     ratatui::{
@@ -74,14 +75,14 @@ fn draw_info_section(area: Rect, buf: &mut Buffer, ai_model: &AiModel) -> Result
 
 fn draw_req_info(area: Rect, buf: &mut Buffer, ai_model: &AiModel) -> Result<(), DMError> {
     let block = Block::default()
-        .title("Request Info")
+        .title(tr("Request Info"))
         .borders(Borders::ALL)
         .border_set(border::THICK);
 
     let req_id = ai_model.req_info().map(|r| r.req_id.as_str()).unwrap_or("");
 
     let text = Text::from(vec![Line::from(vec![
-        Span::styled("Req ID: ", Style::default().fg(Color::Cyan)),
+        Span::styled(tr_padded("Req ID: "), Style::default().fg(Color::Cyan)),
         Span::raw(req_id),
     ])]);
 
@@ -93,7 +94,7 @@ fn draw_req_info(area: Rect, buf: &mut Buffer, ai_model: &AiModel) -> Result<(),
 
 fn draw_res_info(area: Rect, buf: &mut Buffer, ai_model: &AiModel) -> Result<(), DMError> {
     let block = Block::default()
-        .title("Response Info")
+        .title(tr("Response Info"))
         .borders(Borders::ALL)
         .border_set(border::THICK);
 
@@ -109,15 +110,15 @@ fn draw_res_info(area: Rect, buf: &mut Buffer, ai_model: &AiModel) -> Result<(),
 
     let text = Text::from(vec![
         Line::from(vec![
-            Span::styled("Res ID: ", Style::default().fg(Color::Cyan)),
+            Span::styled(tr_padded("Res ID: "), Style::default().fg(Color::Cyan)),
             Span::raw(res_id),
         ]),
         Line::from(vec![
-            Span::styled("Code: ", Style::default().fg(Color::Cyan)),
+            Span::styled(tr_padded("Code: "), Style::default().fg(Color::Cyan)),
             Span::raw(code),
         ]),
         Line::from(vec![
-            Span::styled("Detail: ", Style::default().fg(Color::Cyan)),
+            Span::styled(tr_padded("Detail: "), Style::default().fg(Color::Cyan)),
             Span::raw(detail_msg),
         ]),
     ]);
@@ -173,7 +174,7 @@ fn draw_target(
     index: usize,
 ) -> Result<(), DMError> {
     let block = Block::default()
-        .title(format!("Target {}", index))
+        .title(trf("Target {0}", &[&index]))
         .borders(Borders::ALL)
         .border_set(border::THICK);
 
@@ -188,26 +189,26 @@ fn draw_target(
 
         Text::from(vec![
             Line::from(vec![
-                Span::styled("Chip: ", Style::default().fg(Color::Cyan)),
+                Span::styled(tr_padded("Chip: "), Style::default().fg(Color::Cyan)),
                 Span::raw(chip),
             ]),
             Line::from(vec![
-                Span::styled("Version: ", Style::default().fg(Color::Cyan)),
+                Span::styled(tr_padded("Version: "), Style::default().fg(Color::Cyan)),
                 Span::raw(version),
             ]),
             Line::from(vec![
-                Span::styled("Progress: ", Style::default().fg(Color::Cyan)),
+                Span::styled(tr_padded("Progress: "), Style::default().fg(Color::Cyan)),
                 Span::raw(format!("{}%", progress)),
             ]),
             Line::from(vec![
-                Span::styled("State: ", Style::default().fg(Color::Cyan)),
+                Span::styled(tr_padded("State: "), Style::default().fg(Color::Cyan)),
                 Span::styled(
                     process_state,
                     get_state_color(target.process_state.as_ref()),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("URL: ", Style::default().fg(Color::Cyan)),
+                Span::styled(tr_padded("URL: "), Style::default().fg(Color::Cyan)),
                 Span::raw(if package_url.len() > 30 {
                     format!("{}...", &package_url[..27])
                 } else {
@@ -215,7 +216,7 @@ fn draw_target(
                 }),
             ]),
             Line::from(vec![
-                Span::styled("Hash: ", Style::default().fg(Color::Cyan)),
+                Span::styled(tr_padded("Hash: "), Style::default().fg(Color::Cyan)),
                 Span::raw(if hash.len() > 20 {
                     format!("{}...", &hash[..17])
                 } else {
@@ -223,12 +224,12 @@ fn draw_target(
                 }),
             ]),
             Line::from(vec![
-                Span::styled("Size: ", Style::default().fg(Color::Cyan)),
-                Span::raw(format!("{} bytes", size)),
+                Span::styled(tr_padded("Size: "), Style::default().fg(Color::Cyan)),
+                Span::raw(trf("{0} bytes", &[&size])),
             ]),
         ])
     } else {
-        Text::from("No target data")
+        Text::from(tr("No target data"))
     };
 
     let paragraph = Paragraph::new(text).block(block).alignment(Alignment::Left);
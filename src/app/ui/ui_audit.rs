@@ -0,0 +1,171 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::normal_block,
+    crate::{
+        app::App,
+        audit::{AuditEntry, AuditResult, with_audit_log},
+        error::DMError,
+        i18n::{format_datetime, tr, tr_padded, trf},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{List, ListItem, Paragraph, Widget, Wrap},
+    },
+    std::{collections::VecDeque, path::Path},
+};
+
+fn result_color(result: &AuditResult) -> Color {
+    match result {
+        AuditResult::Done => Color::Green,
+        AuditResult::DryRun => Color::Yellow,
+        AuditResult::Refused => Color::Magenta,
+        AuditResult::Failed(_) => Color::Red,
+    }
+}
+
+/// Lists the operations newest first, `focus` indexes that order, and the details of the
+/// focused one
+fn draw_audit(
+    entries: &VecDeque<AuditEntry>,
+    path: &Path,
+    focus: usize,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(7)])
+        .split(area);
+
+    let list_items: Vec<ListItem> = entries
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, entry)| {
+            let line = Line::from(vec![
+                Span::raw(format!("{}  ", format_datetime(&entry.time))),
+                Span::styled(
+                    format!("{:<8}", tr(entry.result.name())),
+                    Style::default().fg(result_color(&entry.result)),
+                ),
+                Span::raw(format!("{:<22} {}", entry.action, entry.target)),
+            ]);
+            if i == focus {
+                ListItem::new(line.bold().bg(Color::DarkGray))
+            } else {
+                ListItem::new(line)
+            }
+        })
+        .collect();
+
+    List::new(list_items)
+        .block(normal_block(&trf(
+            " Audit: {0} ({1} operations) ",
+            &[&path.display(), &entries.len()],
+        )))
+        .render(chunks[0], buf);
+
+    let mut lines = vec![];
+    if let Some(entry) = entries.iter().rev().nth(focus) {
+        lines.push(Line::from(format!(
+            "{}{}",
+            tr_padded("Who:     "),
+            entry.who
+        )));
+        lines.push(Line::from(format!(
+            "{}{}",
+            tr_padded("Target:  "),
+            entry.target
+        )));
+        lines.push(Line::from(trf(
+            "Payload: {0} bytes, SHA-256 {1}",
+            &[&entry.size, &entry.sha256],
+        )));
+        if let AuditResult::Failed(error) = &entry.result {
+            lines.push(Line::from(Span::styled(
+                format!("{}{}", tr_padded("Error:   "), error),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+    Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(normal_block(" Operation "))
+        .render(chunks[1], buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, _app: &App, focus: usize) -> Result<(), DMError> {
+    let drawn = with_audit_log(|audit_log| {
+        draw_audit(audit_log.recent(), audit_log.path(), focus, area, buf)
+    });
+    if drawn.is_none() {
+        Paragraph::new(Span::styled(
+            tr("The operations are not recorded"),
+            Style::default().fg(Color::DarkGray),
+        ))
+        .block(normal_block(" Audit "))
+        .render(area, buf);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    #[test]
+    fn test_draw_audit() {
+        let entry = |action: &str, result| AuditEntry {
+            time: Local::now(),
+            who: "alice".to_owned(),
+            action: action.to_owned(),
+            target: "v1/devices/me/attributes".to_owned(),
+            sha256: "44136fa3".to_owned(),
+            size: 2,
+            result,
+        };
+        let entries = VecDeque::from([
+            entry("configure", AuditResult::Done),
+            entry("rpc reboot", AuditResult::Failed("broker down".to_owned())),
+            entry("delete blob", AuditResult::Refused),
+        ]);
+
+        let area = Rect::new(0, 0, 100, 14);
+        let mut buf = Buffer::empty(area);
+        draw_audit(&entries, Path::new("audit.log"), 1, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Audit: audit.log (3 operations)"));
+        assert!(text.contains("failed  rpc reboot"));
+        assert!(text.contains("Who:     alice"));
+        assert!(text.contains("Payload: 2 bytes, SHA-256 44136fa3"));
+        assert!(text.contains("Error:   broker down"));
+
+        // Newest first
+        let refused = text.find("refused").unwrap();
+        let done = text.find("done").unwrap();
+        assert!(refused < done);
+    }
+}
@@ -0,0 +1,165 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{focus_block, normal_block, pad_width},
+    crate::{
+        app::{App, ConfigKey},
+        compare::{CompareRow, compare},
+        error::{DMError, DMErrorExt},
+        i18n::{tr, trf},
+        mqtt_ctrl::with_mqtt_ctrl,
+        script::ScriptTarget,
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{Paragraph, Widget},
+    },
+    serde_json::Value,
+};
+
+/// One line per field from `offset`, the differing ones in red, or in yellow when only one
+/// of the devices reports it
+fn draw_rows(rows: &[CompareRow], diff_only: bool, offset: usize, area: Rect, buf: &mut Buffer) {
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{} {} {}",
+            pad_width(tr("Field"), 44),
+            pad_width(tr("This device"), 28),
+            pad_width(tr("Reference"), 28)
+        ),
+        Style::default().bold(),
+    ))];
+
+    let differing = rows.iter().filter(|row| row.differs()).count();
+    for row in rows
+        .iter()
+        .filter(|row| !diff_only || row.differs())
+        .skip(offset)
+    {
+        let color = match (&row.device, &row.reference) {
+            _ if !row.differs() => Color::White,
+            (Some(_), Some(_)) => Color::Red,
+            _ => Color::Yellow,
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{:<44} {:<28} {:<28}",
+                row.key,
+                row.device.as_deref().unwrap_or("-"),
+                row.reference.as_deref().unwrap_or("-")
+            ),
+            Style::default().fg(color),
+        )));
+    }
+
+    let title = trf(" Differences: {0}/{1} ", &[&differing, &rows.len()]);
+    let block = if differing == 0 {
+        normal_block(&title).border_style(Style::default().fg(Color::Green))
+    } else {
+        normal_block(&title).border_style(Style::default().fg(Color::Red))
+    };
+    Paragraph::new(lines).block(block).render(area, buf);
+}
+
+pub fn draw(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &App,
+    reference: Option<&Result<Value, DMError>>,
+    diff_only: bool,
+    offset: usize,
+) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    let file = &app.config_keys[usize::from(ConfigKey::CompareFile)];
+    let (block, text) = if app.config_key_editable {
+        (focus_block(" Reference File "), format!("{}|", file))
+    } else {
+        (normal_block(" Reference File "), file.clone())
+    };
+    Paragraph::new(text).block(block).render(chunks[0], buf);
+
+    match reference {
+        Some(Ok(reference)) => {
+            let rows = with_mqtt_ctrl(|mqtt_ctrl| compare(&mqtt_ctrl.state(), reference));
+            draw_rows(&rows, diff_only, offset, chunks[1], buf);
+        }
+        Some(Err(e)) => Paragraph::new(Span::styled(
+            e.error_str().unwrap_or(tr("Unknown error").to_owned()),
+            Style::default().fg(Color::Red),
+        ))
+        .block(normal_block(" Differences "))
+        .render(chunks[1], buf),
+        None => Paragraph::new(Span::styled(
+            tr(
+                "Press (i) to enter the state snapshot of the reference device, or (s) to save this device as the reference",
+            ),
+            Style::default().fg(Color::DarkGray),
+        ))
+        .block(normal_block(" Differences "))
+        .render(chunks[1], buf),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_rows() {
+        let row = |key: &str, device: Option<&str>, reference: Option<&str>| CompareRow {
+            key: key.to_owned(),
+            device: device.map(|d| d.to_owned()),
+            reference: reference.map(|r| r.to_owned()),
+        };
+        let rows = [
+            row("main_chip.firmware_version", Some("D52408"), Some("D52408")),
+            row(
+                "sensor_chip.firmware_version",
+                Some("010706"),
+                Some("010707"),
+            ),
+            row("network_settings.ntp_url", None, Some("pool.ntp.org")),
+        ];
+
+        let area = Rect::new(0, 0, 110, 6);
+        let mut buf = Buffer::empty(area);
+        draw_rows(&rows, false, 0, area, &mut buf);
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Differences: 2/3"));
+        assert!(text.contains("main_chip.firmware_version"));
+        assert!(text.contains("010706                       010707"));
+        assert!(text.contains("-                            pool.ntp.org"));
+
+        let mut buf = Buffer::empty(area);
+        draw_rows(&rows, true, 0, area, &mut buf);
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Differences: 2/3"));
+        assert!(!text.contains("main_chip.firmware_version"));
+    }
+}
@@ -20,8 +20,10 @@ use {
     super::*,
     crate::{
         app::{App, ConfigKey, DMScreen, MainWindowFocus},
+        device_config::{ConfigKind, FieldState},
         error::{DMError, DMErrorExt},
-        mqtt_ctrl::MqttCtrl,
+        mqtt_ctrl::{MqttCtrl, evp::device_info::mask_secret, with_mqtt_ctrl},
+        provisioning::{ntp::NtpReport, proxy::ProxyReport},
     },
     crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
     error_stack::{Report, Result},
@@ -53,49 +55,92 @@ use {
     },
 };
 
-fn draw_wireless_settings(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
-    let focus = |config_key| ConfigKey::from(app.config_key_focus) == config_key;
-
-    let value = |config_key| {
-        let value = app
-            .config_keys
-            .get(usize::from(config_key))
-            .map(|s| s.as_str())
-            .unwrap_or_default();
+/// Pushes the keys of the focus range under a header per section, the keys of the
+/// collapsed sections are hidden and their header takes the focus instead. The name of a key
+/// is marked with '=' when its value is the one reported by the device and '*' when it
+/// differs.
+fn list_items_push_sections(list_items: &mut Vec<ListItem>, app: &App) {
+    let start = app.config_key_focus_start;
+    let sections = ConfigKey::sections(start, app.config_key_focus_end);
+    let reported: Vec<Option<String>> = with_mqtt_ctrl(|mqtt_ctrl| {
+        (start..=app.config_key_focus_end)
+            .map(|key| mqtt_ctrl.reported_config_value(ConfigKey::from(key)))
+            .collect()
+    });
+    for (i, (group, first, last)) in sections.into_iter().enumerate() {
+        if i > 0 {
+            list_items_push_blank(list_items);
+        }
 
-        if app.config_key_editable && focus(config_key) {
-            format!("{}|", value)
+        let collapsed = app.is_section_collapsed(group);
+        let header = if collapsed {
+            let set = (first..=last)
+                .filter(|k| !app.config_keys[*k].is_empty())
+                .count();
+            trf(
+                "▸ {0} ({1} keys, {2} set)",
+                &[&group, &(last - first + 1), &set],
+            )
         } else {
-            value.to_string()
-        }
-    };
+            format!("▾ {group}")
+        };
+        let style = if collapsed && app.config_key_focus == first {
+            Style::default().bg(Color::Gray).fg(Color::Black).bold()
+        } else {
+            Style::default().fg(Color::Cyan).bold()
+        };
+        list_items.push(ListItem::new(Span::styled(header, style)));
 
-    let mut list_items = Vec::<ListItem>::new();
-    list_items_push_focus(
-        &mut list_items,
-        "station_mode_ssid",
-        &value(ConfigKey::StaSsid),
-        focus(ConfigKey::StaSsid),
-    );
+        if collapsed {
+            continue;
+        }
 
-    list_items_push_focus(
-        &mut list_items,
-        "station_mode_password",
-        &value(ConfigKey::StaPassword),
-        focus(ConfigKey::StaPassword),
-    );
+        for key in first..=last {
+            let config_key = ConfigKey::from(key);
+            let focus = app.config_key_focus == key;
+            let reported = reported[key - start].as_deref();
+            let state = FieldState::of(&app.config_keys[key], reported);
+
+            let mut value = if app.config_key_editable && focus {
+                format!("{}|", app.config_keys[key])
+            } else {
+                app.config_keys[key].clone()
+            };
+            if state == FieldState::Modified
+                && let Some(reported) = reported
+            {
+                let reported = if config_key.kind() == ConfigKind::Secret {
+                    mask_secret(reported)
+                } else {
+                    reported
+                };
+                value.push_str(&trf("  (device: {0})", &[&reported]));
+            }
 
-    list_items_push_focus(
-        &mut list_items,
-        "station_mode_encryption",
-        &value(ConfigKey::StaEncryption),
-        focus(ConfigKey::StaEncryption),
-    );
+            let name = format!("{} {}", state.marker(), config_key);
+            if state == FieldState::Modified && !focus {
+                list_items.push(ListItem::new(Span::styled(
+                    format!("{:<35} : {}", name, value),
+                    Style::default().fg(Color::Yellow),
+                )));
+            } else {
+                list_items_push_focus(list_items, &name, &value, focus);
+            }
+        }
+    }
+}
 
-    list_items_push_blank(&mut list_items);
-    list_items_push_focus(&mut list_items, "Note", "", false);
+fn list_items_push_note(list_items: &mut Vec<ListItem>, app: &App) {
+    list_items_push_blank(list_items);
+    list_items_push_focus(list_items, tr("Note"), "", false);
     let comment = ConfigKey::from(app.config_key_focus).note();
-    list_items_push_focus(&mut list_items, "  Comment", comment, false);
+    list_items_push_focus(list_items, &tr_padded("  Comment"), comment, false);
+}
+
+fn draw_wireless_settings(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let mut list_items = Vec::<ListItem>::new();
+    list_items_push_sections(&mut list_items, app);
+    list_items_push_note(&mut list_items, app);
 
     List::new(list_items)
         .block(normal_block(" Configuration "))
@@ -103,122 +148,45 @@ fn draw_wireless_settings(area: Rect, buf: &mut Buffer, app: &App) -> Result<(),
     Ok(())
 }
 
-fn draw_network_settings(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
-    let focus = |config_key| ConfigKey::from(app.config_key_focus) == config_key;
-
-    let value = |config_key| {
-        if app.config_key_editable && focus(config_key) {
-            format!("{}|", &app.config_keys[usize::from(config_key)])
-        } else {
-            app.config_keys[usize::from(config_key)].to_string()
-        }
-    };
-
+fn draw_network_settings(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &App,
+    ntp_check: Option<&NtpReport>,
+    proxy_check: Option<&ProxyReport>,
+) -> Result<(), DMError> {
     let mut list_items = Vec::<ListItem>::new();
-
-    list_items_push_focus(
-        &mut list_items,
-        "ip_method",
-        &value(ConfigKey::IpMethod),
-        focus(ConfigKey::IpMethod),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "ntp_url",
-        &value(ConfigKey::NtpUrl),
-        focus(ConfigKey::NtpUrl),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "static_ipv4_ip",
-        &value(ConfigKey::StaticIpv4Ip),
-        focus(ConfigKey::StaticIpv4Ip),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "static_ipv4_subnet_mask",
-        &value(ConfigKey::StaticIpv4SubnetMask),
-        focus(ConfigKey::StaticIpv4SubnetMask),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "static_ipv4_gateway",
-        &value(ConfigKey::StaticIpv4Gateway),
-        focus(ConfigKey::StaticIpv4Gateway),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "static_ipv4_dns",
-        &value(ConfigKey::StaticIpv4Dns),
-        focus(ConfigKey::StaticIpv4Dns),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "static_ipv6_ip",
-        &value(ConfigKey::StaticIpv6Ip),
-        focus(ConfigKey::StaticIpv6Ip),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "static_ipv6_subnet_mask",
-        &value(ConfigKey::StaticIpv6SubnetMask),
-        focus(ConfigKey::StaticIpv6SubnetMask),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "static_ipv6_gateway",
-        &value(ConfigKey::StaticIpv6Gateway),
-        focus(ConfigKey::StaticIpv6Gateway),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "static_ipv6_dns",
-        &value(ConfigKey::StaticIpv6Dns),
-        focus(ConfigKey::StaticIpv6Dns),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "proxy_url",
-        &value(ConfigKey::ProxyUrl),
-        focus(ConfigKey::ProxyUrl),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "proxy_port",
-        &value(ConfigKey::ProxyPort),
-        focus(ConfigKey::ProxyPort),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "proxy_user_name",
-        &value(ConfigKey::ProxyUserName),
-        focus(ConfigKey::ProxyUserName),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "proxy_password",
-        &value(ConfigKey::ProxyPassword),
-        focus(ConfigKey::ProxyPassword),
-    );
+    list_items_push_sections(&mut list_items, app);
+    list_items_push_note(&mut list_items, app);
 
     list_items_push_blank(&mut list_items);
-    list_items_push_focus(&mut list_items, "Note", "", false);
+    match ntp_check {
+        Some(report) => {
+            list_items_push_focus(&mut list_items, tr("NTP check"), &report.summary(), false)
+        }
+        None => list_items_push_focus(
+            &mut list_items,
+            tr("NTP check"),
+            tr("(n) to query the NTP server from this machine before sending"),
+            false,
+        ),
+    }
 
-    let comment = ConfigKey::from(app.config_key_focus).note();
-    list_items_push_focus(&mut list_items, "  Comment", comment, false);
+    if let Some(report) = proxy_check {
+        list_items_push_blank(&mut list_items);
+        list_items_push_focus(&mut list_items, tr("Proxy check"), &report.summary(), false);
+        for step in report.steps.iter() {
+            list_items_push_focus(&mut list_items, &tr_padded("  Step"), &step.text(), false);
+        }
+    } else {
+        list_items_push_blank(&mut list_items);
+        list_items_push_focus(
+            &mut list_items,
+            tr("Proxy check"),
+            tr("(p) to check the proxy from this machine before sending"),
+            false,
+        );
+    }
 
     List::new(list_items)
         .block(normal_block(" Configuration "))
@@ -227,30 +195,8 @@ fn draw_network_settings(area: Rect, buf: &mut Buffer, app: &App) -> Result<(),
 }
 
 fn draw_agent_state(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
-    let focus = |config_key| ConfigKey::from(app.config_key_focus) == config_key;
-
-    let value = |config_key| {
-        if app.config_key_editable && focus(config_key) {
-            format!("{}|", &app.config_keys[usize::from(config_key)])
-        } else {
-            app.config_keys[usize::from(config_key)].to_string()
-        }
-    };
-
     let mut list_items = Vec::<ListItem>::new();
-    list_items_push_focus(
-        &mut list_items,
-        "report_status_interval_min",
-        &value(ConfigKey::ReportStatusIntervalMin),
-        focus(ConfigKey::ReportStatusIntervalMin),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "report_status_interval_max",
-        &value(ConfigKey::ReportStatusIntervalMax),
-        focus(ConfigKey::ReportStatusIntervalMax),
-    );
+    list_items_push_sections(&mut list_items, app);
 
     List::new(list_items)
         .block(normal_block(" Configuration "))
@@ -259,191 +205,9 @@ fn draw_agent_state(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMErr
 }
 
 fn draw_system_settings(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
-    let focus = |config_key| ConfigKey::from(app.config_key_focus) == config_key;
-
-    let value = |config_key| {
-        if app.config_key_editable && focus(config_key) {
-            format!("{}|", &app.config_keys[usize::from(config_key)])
-        } else {
-            app.config_keys[usize::from(config_key)].to_string()
-        }
-    };
-
     let mut list_items = Vec::<ListItem>::new();
-    list_items_push_focus(
-        &mut list_items,
-        "led_enabled",
-        &value(ConfigKey::LedEnabled),
-        focus(ConfigKey::LedEnabled),
-    );
-
-    list_items_push_focus(
-        &mut list_items,
-        "temperature_update_interval",
-        &value(ConfigKey::TemperatureUpdateInterval),
-        focus(ConfigKey::TemperatureUpdateInterval),
-    );
-
-    // all
-    {
-        list_items_push_focus(
-            &mut list_items,
-            "log.all.level",
-            &value(ConfigKey::AllLogSettingLevel),
-            focus(ConfigKey::AllLogSettingLevel),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.all.destination",
-            &value(ConfigKey::AllLogSettingDestination),
-            focus(ConfigKey::AllLogSettingDestination),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.all.storage_name",
-            &value(ConfigKey::AllLogSettingStorageName),
-            focus(ConfigKey::AllLogSettingStorageName),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.all.path",
-            &value(ConfigKey::AllLogSettingPath),
-            focus(ConfigKey::AllLogSettingPath),
-        );
-    }
-
-    // main
-    {
-        list_items_push_focus(
-            &mut list_items,
-            "log.main.level",
-            &value(ConfigKey::MainLogSettingLevel),
-            focus(ConfigKey::MainLogSettingLevel),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.main.destination",
-            &value(ConfigKey::MainLogSettingDestination),
-            focus(ConfigKey::MainLogSettingDestination),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.main.storage_name",
-            &value(ConfigKey::MainLogSettingStorageName),
-            focus(ConfigKey::MainLogSettingStorageName),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.main.path",
-            &value(ConfigKey::MainLogSettingPath),
-            focus(ConfigKey::MainLogSettingPath),
-        );
-    }
-
-    // sensor
-    {
-        list_items_push_focus(
-            &mut list_items,
-            "log.sensor.level",
-            &value(ConfigKey::SensorLogSettingLevel),
-            focus(ConfigKey::SensorLogSettingLevel),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.sensor.destination",
-            &value(ConfigKey::SensorLogSettingDestination),
-            focus(ConfigKey::SensorLogSettingDestination),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.sensor.storage_name",
-            &value(ConfigKey::SensorLogSettingStorageName),
-            focus(ConfigKey::SensorLogSettingStorageName),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.sensor.path",
-            &value(ConfigKey::SensorLogSettingPath),
-            focus(ConfigKey::SensorLogSettingPath),
-        );
-    }
-
-    // companion_fw
-    {
-        list_items_push_focus(
-            &mut list_items,
-            "log.fw.level",
-            &value(ConfigKey::CompanionFwLogSettingLevel),
-            focus(ConfigKey::CompanionFwLogSettingLevel),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.fw.destination",
-            &value(ConfigKey::CompanionFwLogSettingDestination),
-            focus(ConfigKey::CompanionFwLogSettingDestination),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.fw.storage_name",
-            &value(ConfigKey::CompanionFwLogSettingStorageName),
-            focus(ConfigKey::CompanionFwLogSettingStorageName),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.fw.path",
-            &value(ConfigKey::CompanionFwLogSettingPath),
-            focus(ConfigKey::CompanionFwLogSettingPath),
-        );
-    }
-
-    // companion_app
-    {
-        list_items_push_focus(
-            &mut list_items,
-            "log.app.level",
-            &value(ConfigKey::CompanionAppLogSettingLevel),
-            focus(ConfigKey::CompanionAppLogSettingLevel),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.app.destination",
-            &value(ConfigKey::CompanionAppLogSettingDestination),
-            focus(ConfigKey::CompanionAppLogSettingDestination),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.app.storage_name",
-            &value(ConfigKey::CompanionAppLogSettingStorageName),
-            focus(ConfigKey::CompanionAppLogSettingStorageName),
-        );
-
-        list_items_push_focus(
-            &mut list_items,
-            "log.app.path",
-            &value(ConfigKey::CompanionAppLogSettingPath),
-            focus(ConfigKey::CompanionAppLogSettingPath),
-        );
-    }
-
-    list_items_push_blank(&mut list_items);
-    list_items_push_focus(&mut list_items, "Note", "", false);
-
-    let comment = ConfigKey::from(app.config_key_focus).note();
-    list_items_push_focus(&mut list_items, "  Comment", comment, false);
+    list_items_push_sections(&mut list_items, app);
+    list_items_push_note(&mut list_items, app);
 
     List::new(list_items)
         .block(normal_block(" Configuration "))
@@ -451,11 +215,23 @@ fn draw_system_settings(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), D
     Ok(())
 }
 
-pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+/// Settings of the focused pane, the network ones with the results of the checks run from
+/// this machine
+pub fn draw(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &App,
+    ntp_check: Option<&NtpReport>,
+    proxy_check: Option<&ProxyReport>,
+) -> Result<(), DMError> {
     if let Some(result) = app.config_result.as_ref() {
         match result {
             Ok(s) => {
-                let block = normal_block("Configuration Result");
+                let title = match app.send_options {
+                    Some(options) => trf("Configuration Result ({0})", &[&options]),
+                    None => tr("Configuration Result").to_owned(),
+                };
+                let block = normal_block(&title);
                 let root = json::parse(s).unwrap();
 
                 if let Some((k, v)) = root.entries().next() {
@@ -484,7 +260,9 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
         match app.main_window_focus() {
             MainWindowFocus::AgentState => draw_agent_state(area, buf, app),
             MainWindowFocus::SystemSettings => draw_system_settings(area, buf, app),
-            MainWindowFocus::NetworkSettings => draw_network_settings(area, buf, app),
+            MainWindowFocus::NetworkSettings => {
+                draw_network_settings(area, buf, app, ntp_check, proxy_check)
+            }
             MainWindowFocus::WirelessSettings => draw_wireless_settings(area, buf, app),
             _ => Ok(()),
         }
@@ -500,17 +278,25 @@ mod tests {
     #[test]
     fn test_draw_variants() {
         // Create an App with default config via App::new
-        let mut app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let mut app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Prepare drawing area and buffer
         let area = Rect::new(0, 0, 40, 20);
         let mut buf = Buffer::empty(area);
 
         // Call the individual draw functions (should return Ok(()))
-        assert!(draw_wireless_settings(area, &mut buf, &app).is_ok());
-        assert!(draw_network_settings(area, &mut buf, &app).is_ok());
-        assert!(draw_agent_state(area, &mut buf, &app).is_ok());
-        assert!(draw_system_settings(area, &mut buf, &app).is_ok());
+        let mqtt_ctrl = MqttCtrl::new("127.0.0.1", 1).unwrap();
+        let (mut mqtt_ctrl, _) = crate::mqtt_ctrl::with_local_mqtt_ctrl(mqtt_ctrl, || {
+            assert!(draw_wireless_settings(area, &mut buf, &app).is_ok());
+            assert!(draw_network_settings(area, &mut buf, &app, None, None).is_ok());
+            assert!(draw_agent_state(area, &mut buf, &app).is_ok());
+            assert!(draw_system_settings(area, &mut buf, &app).is_ok());
+        });
+        mqtt_ctrl.exit();
 
         // Test the top-level draw when config_result is present (Ok)
         app.config_result = Some(Ok(
@@ -62,7 +62,11 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
             .split(area);
         match result {
             Ok(s) => {
-                let block = normal_block("Configuration Result");
+                let title = match app.send_options {
+                    Some(options) => trf("Configuration Result ({0})", &[&options]),
+                    None => tr("Configuration Result").to_owned(),
+                };
+                let block = normal_block(&title);
                 let root = json::parse(s).unwrap();
 
                 if let Some((k, v)) = root.entries().next() {
@@ -122,14 +126,21 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
             | MainWindowFocus::CompanionChip => {
                 block = normal_block(" Configuration for OTA");
                 note.push_str("\n\n");
-                note.push_str("  ota_fw.json is used for firmware OTA\n");
-                note.push_str("  ota_ai_model.json is used for AI Model OTA\n");
+                note.push_str(&format!(
+                    "  {}\n",
+                    trf("{0} is used for firmware OTA", &[&"ota_fw.json"])
+                ));
+                note.push_str(&format!(
+                    "  {}\n",
+                    trf("{0} is used for AI Model OTA", &[&"ota_ai_model.json"])
+                ));
             }
             _ => {}
         };
 
         let message = format!(
-            "\n  Please describe configuration in following json file:\n\n    {}/{}",
+            "\n  {}\n\n    {}/{}",
+            tr("Please describe configuration in following json file:"),
             App::config_dir(),
             focus.user_config_file()
         );
@@ -165,7 +176,11 @@ mod tests {
     #[test]
     fn test_draw_default_and_ok() {
         // Build an App via the public constructor
-        let mut app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let mut app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Prepare drawing area and buffer
         let area = Rect::new(0, 0, 60, 20);
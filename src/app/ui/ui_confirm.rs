@@ -0,0 +1,104 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{centered_rect, focus_block},
+    crate::{
+        app::{App, confirm::Confirmation},
+        error::DMError,
+        i18n::{tr, trf},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{Clear, Paragraph, Widget, Wrap},
+    },
+};
+
+pub fn draw_confirmation(confirmation: &Confirmation, area: Rect, buf: &mut Buffer) {
+    let popup_area = centered_rect(60, 30, area);
+    Clear.render(popup_area, buf);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            confirmation.message.clone(),
+            Style::default().fg(Color::White).bold(),
+        )),
+        Line::default(),
+    ];
+    match &confirmation.expected {
+        Some(expected) => {
+            // The name is highlighted where the translation places it
+            let prompt = tr("Type {0} and press ENTER to confirm, ESC to cancel.");
+            let (before, after) = prompt.split_once("{0}").unwrap_or((prompt, ""));
+            lines.push(Line::from(vec![
+                Span::raw(before),
+                Span::styled(expected.clone(), Style::default().fg(Color::Yellow).bold()),
+                Span::raw(after),
+            ]));
+            lines.push(Line::from(Span::styled(
+                format!("> {}", confirmation.typed),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        None => match &confirmation.alternative {
+            Some((key, name, _)) => lines.push(Line::from(trf(
+                "Press (y) to confirm, ({0}) to {1}, (n)/(ESC) to cancel.",
+                &[key, &tr(name)],
+            ))),
+            None => lines.push(Line::from(tr("Press (y) to confirm, (n)/(ESC) to cancel."))),
+        },
+    }
+
+    Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(focus_block(" Confirm "))
+        .render(popup_area, buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    if let Some(confirmation) = &app.confirmation {
+        draw_confirmation(confirmation, area, buf);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::confirm::ConfirmAction;
+
+    #[test]
+    fn test_draw_confirmation() {
+        let area = Rect::new(0, 0, 100, 30);
+        let mut buf = Buffer::empty(area);
+        let mut confirmation =
+            Confirmation::new(ConfirmAction::FactoryReset, "Factory reset the device?")
+                .with_expected("Aid-0001");
+        confirmation.typed = "Aid".to_owned();
+        draw_confirmation(&confirmation, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Factory reset the device?"));
+        assert!(text.contains("Type Aid-0001 and press ENTER"));
+        assert!(text.contains("> Aid"));
+    }
+}
@@ -0,0 +1,154 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::normal_block,
+    crate::{
+        app::App,
+        debug_log::{DebugLog, LogLevel},
+        error::DMError,
+        i18n::{tr, trf},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::{Line, Span},
+        widgets::{Paragraph, Widget},
+    },
+};
+
+fn level_color(level: LogLevel) -> Color {
+    match level {
+        LogLevel::Error => Color::Red,
+        LogLevel::Warn => Color::Yellow,
+        LogLevel::Info => Color::Green,
+        LogLevel::Debug | LogLevel::Trace => Color::DarkGray,
+    }
+}
+
+/// The lines passing the filter which fit, `scroll` lines above the newest one
+fn draw_lines(debug_log: &DebugLog, area: Rect, buf: &mut Buffer) {
+    let lines: Vec<_> = debug_log.lines().collect();
+    let height = area.height.saturating_sub(2) as usize;
+    let bottom = lines.len() - debug_log.scroll.min(lines.len().saturating_sub(height));
+    let top = bottom.saturating_sub(height);
+
+    let text: Vec<Line> = lines[top..bottom]
+        .iter()
+        .map(|l| {
+            Line::from(Span::styled(
+                l.text.as_str(),
+                Style::default().fg(level_color(l.level)),
+            ))
+        })
+        .collect();
+
+    let title = trf(
+        " Debug Log: {0} ({1} and above) ",
+        &[&debug_log.path().display(), &debug_log.level.name()],
+    );
+    Paragraph::new(text)
+        .block(normal_block(&title))
+        .render(area, buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(area);
+
+    let status = match app.debug_log() {
+        Some(debug_log) => {
+            draw_lines(debug_log, chunks[0], buf);
+            match &debug_log.error {
+                Some(e) => Span::styled(e.as_str(), Style::default().fg(Color::Red)),
+                None if debug_log.scroll == 0 => Span::styled(
+                    tr("Following the end of the log"),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                None => Span::styled(
+                    trf(
+                        "{0} lines above the end, (G) to follow",
+                        &[&debug_log.scroll],
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            }
+        }
+        None => {
+            Paragraph::new("")
+                .block(normal_block(" Debug Log "))
+                .render(chunks[0], buf);
+            Span::styled(
+                tr("Start device-monitor with --log <FILE> to follow its own log here"),
+                Style::default().fg(Color::DarkGray),
+            )
+        }
+    };
+    Paragraph::new(status).render(chunks[1], buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_draw_lines() {
+        let path = std::env::temp_dir().join(format!("dm-ui-debug-log-{}.log", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        for i in 0..10 {
+            writeln!(file, "10:00:0{} INFO line {}", i, i).unwrap();
+        }
+        writeln!(file, "10:00:10 ERROR failed").unwrap();
+        let mut debug_log = DebugLog::new(&path);
+        debug_log.poll().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let area = Rect::new(0, 0, 80, 5);
+        let render = |debug_log: &DebugLog| {
+            let mut buf = Buffer::empty(area);
+            draw_lines(debug_log, area, &mut buf);
+            buf.content().iter().map(|c| c.symbol()).collect::<String>()
+        };
+
+        // The newest lines fill the 3 rows
+        let text = render(&debug_log);
+        assert!(text.contains("TRACE and above"));
+        assert!(text.contains("line 8") && text.contains("ERROR failed"));
+        assert!(!text.contains("line 7"));
+
+        debug_log.scroll_up(2);
+        let text = render(&debug_log);
+        assert!(text.contains("line 6") && text.contains("line 8"));
+        assert!(!text.contains("line 9"));
+
+        // Scrolled past the top, the oldest lines stay displayed
+        debug_log.scroll_top();
+        assert!(render(&debug_log).contains("line 0"));
+
+        debug_log.level = LogLevel::Error;
+        debug_log.follow();
+        let text = render(&debug_log);
+        assert!(text.contains("ERROR failed") && !text.contains("line 9"));
+    }
+}
@@ -20,12 +20,13 @@ use {
     super::*,
     crate::{
         app::{App, DMScreen},
-        azurite::{AzuriteStorage, with_azurite_storage},
+        azurite::{AzuriteStorage, with_azurite_storage, worker::AzuriteJob},
         error::DMError,
         mqtt_ctrl::{
             MqttCtrl,
             evp::device_info::{ChipInfo, DeviceInfo},
             evp::evp_state::{AgentDeviceConfig, AgentSystemInfo, UUID},
+            evp::module::{ModuleInfo, module_duplicates, module_versions},
             evp::{
                 device_info::{
                     DeviceCapabilities, DeviceReserved, DeviceStates, NetworkSettings,
@@ -56,15 +57,97 @@ use {
         style::Stylize,
         symbols::border,
         text::{Line, Span, Text},
-        widgets::{Block, Borders, List, ListItem, Paragraph, Widget},
+        widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Widget, Wrap},
     },
     std::{
-        collections::HashMap,
+        collections::{BTreeSet, HashMap},
         io,
         time::{Duration, Instant},
     },
 };
 
+/// Result of one blob of a batch upload or removal
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResult {
+    pub name: String,
+    pub error: Option<String>,
+}
+
+/// Results of a batch upload or removal, displayed until a key is pressed
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSummary {
+    /// "Upload" or "Remove"
+    pub action: &'static str,
+    pub results: Vec<BatchResult>,
+}
+
+impl BatchSummary {
+    pub fn new(action: &'static str) -> Self {
+        Self {
+            action,
+            results: vec![],
+        }
+    }
+
+    pub fn push(&mut self, name: &str, error: Option<String>) {
+        self.results.push(BatchResult {
+            name: name.to_owned(),
+            error,
+        });
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.results.iter().filter(|r| r.error.is_none()).count()
+    }
+}
+
+fn do_batch_summary(summary: &BatchSummary, area: Rect, buf: &mut Buffer) {
+    let popup_area = centered_rect(80, 60, area);
+    Clear.render(popup_area, buf);
+
+    let lines: Vec<Line> = summary
+        .results
+        .iter()
+        .enumerate()
+        .map(|(i, r)| match &r.error {
+            None => Line::from(Span::styled(
+                format!(
+                    "[{}/{}] {} {}",
+                    i + 1,
+                    summary.results.len(),
+                    pad_width(tr("OK"), 5),
+                    r.name
+                ),
+                Style::default().fg(Color::Green),
+            )),
+            Some(e) => Line::from(Span::styled(
+                format!(
+                    "[{}/{}] {} {}: {}",
+                    i + 1,
+                    summary.results.len(),
+                    pad_width(tr("FAIL"), 5),
+                    r.name,
+                    e
+                ),
+                Style::default().fg(Color::Red),
+            )),
+        })
+        .collect();
+
+    let title = trf(
+        " Batch {0}: {1}/{2} succeeded ",
+        &[
+            &tr(summary.action),
+            &summary.succeeded(),
+            &summary.results.len(),
+        ],
+    );
+    Paragraph::new(lines)
+        .block(focus_block(&title))
+        .wrap(Wrap { trim: false })
+        .render(popup_area, buf);
+}
+
 pub fn do_deploy(
     area: Rect,
     buf: &mut Buffer,
@@ -72,14 +155,14 @@ pub fn do_deploy(
 ) -> Result<(), DMError> {
     let message = match config_result {
         Ok(config) => config.clone(),
-        Err(e) => e.error_str().unwrap_or("Unknown error".to_owned()),
+        Err(e) => e.error_str().unwrap_or(tr("Unknown error").to_owned()),
     };
 
     let paragraph = Paragraph::new(message)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" Deploy EdgeApp Request "),
+                .title(tr_padded(" Deploy EdgeApp Request ")),
         )
         .alignment(Alignment::Left);
     paragraph.render(area, buf);
@@ -87,71 +170,189 @@ pub fn do_deploy(
     Ok(())
 }
 
+/// Rows of the items filling `height` rows around the focused one: the focused item and
+/// the ones above it, then the ones below. Only these items are built.
+fn visible_rows<'a>(
+    len: usize,
+    focus: usize,
+    height: usize,
+    rows: impl Fn(usize) -> Vec<ListItem<'a>>,
+) -> Vec<ListItem<'a>> {
+    if len == 0 {
+        return vec![];
+    }
+    let focus = focus.min(len - 1);
+
+    let mut used = 0;
+    let mut above = vec![];
+    for id in (0..=focus).rev() {
+        let items = rows(id);
+        if used > 0 && used + items.len() > height {
+            break;
+        }
+        used += items.len();
+        above.push(items);
+    }
+
+    let mut list_items: Vec<ListItem> = above.into_iter().rev().flatten().collect();
+    for id in focus + 1..len {
+        if used >= height {
+            break;
+        }
+        let items = rows(id);
+        used += items.len();
+        list_items.extend(items);
+    }
+    list_items
+}
+
+fn module_items(
+    module_info_db: &HashMap<UUID, ModuleInfo>,
+    id: usize,
+    uuid: &UUID,
+    module_info: &ModuleInfo,
+    running_hashes: &[String],
+    focus: bool,
+    marked: bool,
+) -> Vec<ListItem<'static>> {
+    let mut list_items = Vec::<ListItem>::new();
+    let mark = if marked { "[x] " } else { "" };
+    let text = format!(
+        "{}No{:2}  ModuleID: {}  ContainerName: {}  BlobName: {}",
+        mark,
+        id + 1,
+        uuid.uuid(),
+        module_info.container_name,
+        module_info.blob_name,
+    );
+    list_items_push_text_focus(&mut list_items, &text, focus);
+
+    let text = format!("      {} {}", tr("Hash:"), module_info.hash);
+    list_items_push_text_focus(&mut list_items, &text, focus);
+
+    let text = format!("      {} {}", tr("URL:"), module_info.sas_url);
+    list_items_push_text_focus(&mut list_items, &text, focus);
+
+    if let Some(sas) = SasInfo::parse(&module_info.sas_url) {
+        let text = format!("      SAS: {}", sas.summary(chrono::Utc::now()));
+        list_items_push_text_focus(&mut list_items, &text, focus);
+    }
+
+    let text = format!("      {} {}", tr("Size:"), module_info.size);
+    list_items_push_text_focus(&mut list_items, &text, focus);
+
+    let names = |modules: Vec<&ModuleInfo>| {
+        modules
+            .iter()
+            .map(|m| m.blob_name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let versions = module_versions(module_info_db, module_info);
+    if !versions.is_empty() {
+        let text = trf("      Other versions: {0}", &[&names(versions)]);
+        list_items_push_text_focus(&mut list_items, &text, focus);
+    }
+
+    let duplicates = module_duplicates(module_info_db, module_info);
+    if !duplicates.is_empty() {
+        list_items.push(ListItem::new(Span::styled(
+            trf("      Same content as: {0}", &[&names(duplicates)]),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+
+    if running_hashes.contains(&module_info.hash) {
+        list_items.push(ListItem::new(Span::styled(
+            tr_padded("      Running on the device"),
+            Style::default().fg(Color::Green),
+        )));
+    }
+
+    list_items
+}
+
 fn do_list_modules(
     azure_storage: &AzuriteStorage,
+    running_hashes: &[String],
+    marked: &BTreeSet<String>,
+    listing: bool,
     area: Rect,
     buf: &mut Buffer,
 ) -> Result<(), DMError> {
-    let mut list_items = Vec::<ListItem>::new();
     let module_info_db = azure_storage.module_info_db();
-    let mut no = 1;
-    for (id, (uuid, module_info)) in module_info_db.iter().enumerate() {
-        let focus = id == azure_storage.current_module_id();
-        let text = format!(
-            "No{:2}  ModuleID: {}  ContainerName: {}  BlobName: {}",
-            no,
-            uuid.uuid(),
-            module_info.container_name,
-            module_info.blob_name,
-        );
-        list_items_push_text_focus(&mut list_items, &text, focus);
-
-        let text = format!("      Hash: {}", module_info.hash,);
-        list_items_push_text_focus(&mut list_items, &text, focus);
-
-        let text = format!("      URL: {}", module_info.sas_url,);
-        list_items_push_text_focus(&mut list_items, &text, focus);
+    let modules = azure_storage.listed_modules();
+    let focus = azure_storage.current_module_id();
 
-        let text = format!("      Size: {}", module_info.size,);
-        list_items_push_text_focus(&mut list_items, &text, focus);
+    // Subtract 2 for border
+    let height = area.height.saturating_sub(2) as usize;
+    let mut list_items = visible_rows(modules.len(), focus, height, |id| {
+        let (uuid, module_info) = modules[id];
+        module_items(
+            module_info_db,
+            id,
+            uuid,
+            module_info,
+            running_hashes,
+            id == focus,
+            marked.contains(&module_info.blob_name),
+        )
+    });
 
-        no += 1;
+    if list_items.is_empty() && listing {
+        list_items.push(ListItem::new(Span::styled(
+            tr("Listing the modules..."),
+            Style::default().fg(Color::Yellow),
+        )));
     }
 
-    let title = " Azurite Storage Modules ";
-    let block = normal_block(title);
+    let mut title = tr_padded(" Azurite Storage Modules ").into_owned();
+    if !marked.is_empty() {
+        title = trf(" Azurite Storage Modules ({0} marked) ", &[&marked.len()]);
+    }
+    title.push_str(&trf(
+        "(by {0}) ",
+        &[&tr(azure_storage.module_sort().name())],
+    ));
+    if listing {
+        title.push_str(&tr_padded("(listing...) "));
+    }
+    let block = normal_block(&title);
 
     List::new(list_items).block(block).render(area, buf);
     Ok(())
 }
 
-fn do_add(azure_storage: &AzuriteStorage, area: Rect, buf: &mut Buffer) -> Result<(), DMError> {
-    let pop_area = centered_rect(80, 25, area);
-
-    let popup_chunks = Layout::default()
+pub fn draw(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &App,
+    marked: &BTreeSet<String>,
+    batch_summary: Option<&BatchSummary>,
+) -> Result<(), DMError> {
+    let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .margin(1)
-        .constraints([
-            Constraint::Min(50),
-            Constraint::Min(30),
-            Constraint::Percentage(30),
-        ])
-        .split(pop_area);
-
-    Paragraph::new(format!("{}|", azure_storage.new_module()))
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .title(" Input New Module File Path ")
-                .borders(Borders::ALL)
-                .bg(Color::DarkGray),
+        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .split(area);
+    let (area, filter_area) = (chunks[0], chunks[1]);
+    with_azurite_storage(|azure_storage| {
+        draw_filter(
+            azure_storage.module_filter(),
+            app.config_key_editable,
+            azure_storage.listed_modules().len(),
+            filter_area,
+            buf,
         )
-        .render(popup_chunks[1], buf);
+    });
 
-    Ok(())
-}
+    let running_hashes = app.running_module_hashes();
+    let listing = app.is_job_active(&AzuriteJob::UpdateModules(HashMap::new()).label());
+    let do_list_modules =
+        |azure_storage: &AzuriteStorage, area: Rect, buf: &mut Buffer| -> Result<(), DMError> {
+            do_list_modules(azure_storage, &running_hashes, marked, listing, area, buf)
+        };
 
-pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
     if let Some(action) = with_azurite_storage(|azure_storage| azure_storage.action()) {
         match action {
             Some(AzuriteAction::Deploy) => {
@@ -167,14 +368,19 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                     .unwrap_or(Ok(()))?;
             }
             Some(AzuriteAction::Add) => {
-                with_azurite_storage(|azure_storage| do_add(azure_storage, area, buf))
+                with_azurite_storage(|azure_storage| do_list_modules(azure_storage, area, buf))
                     .unwrap_or(Ok(()))?;
+                ui_file_browser::draw(area, buf, app)?;
             }
 
             _ => {}
         }
     }
 
+    if let Some(summary) = batch_summary {
+        do_batch_summary(summary, area, buf);
+    }
+
     Ok(())
 }
 
@@ -199,4 +405,43 @@ mod tests {
             Err(error_stack::Report::new(crate::error::DMError::InvalidData));
         assert!(do_deploy(area, &mut buf, &err_res).is_ok());
     }
+
+    #[test]
+    fn test_batch_summary() {
+        let mut summary = BatchSummary::new("Remove");
+        summary.push("a.wasm", None);
+        summary.push("b.wasm", Some("not found".to_owned()));
+        assert_eq!(summary.succeeded(), 1);
+
+        let area = Rect::new(0, 0, 80, 20);
+        let mut buf = Buffer::empty(area);
+        do_batch_summary(&summary, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Batch Remove: 1/2 succeeded"));
+        assert!(text.contains("FAIL  b.wasm: not found"));
+    }
+
+    #[test]
+    fn test_visible_rows() {
+        let built = std::cell::RefCell::new(vec![]);
+        let rows = |id: usize| {
+            built.borrow_mut().push(id);
+            vec![ListItem::new(format!("{id}")); 3]
+        };
+
+        // The focused module is shown with the one above, the one below fills the last row
+        // and the ones far from it are not built
+        let items = visible_rows(1000, 500, 7, rows);
+        assert_eq!(items.len(), 9);
+        assert_eq!(*built.borrow(), vec![500, 499, 498, 501]);
+
+        // Near the top, the rows below the focused module fill the block
+        built.borrow_mut().clear();
+        let items = visible_rows(1000, 0, 7, rows);
+        assert_eq!(items.len(), 9);
+        assert_eq!(*built.borrow(), vec![0, 1, 2]);
+
+        assert!(visible_rows(0, 0, 7, rows).is_empty());
+    }
 }
@@ -0,0 +1,170 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{centered_rect, normal_block},
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::tr,
+        mqtt_ctrl::{
+            evp::evp_state::{DeploymentStatus, UUID, is_error_status},
+            with_mqtt_ctrl,
+        },
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{Clear, Paragraph, Widget, Wrap},
+    },
+};
+
+fn status_span(status: &str) -> Span<'static> {
+    if is_error_status(status) {
+        Span::styled(status.to_owned(), Style::default().fg(Color::Red).bold())
+    } else {
+        Span::styled(status.to_owned(), Style::default().fg(Color::Green))
+    }
+}
+
+/// Full details of every instance, with the status of the module it runs
+fn detail_lines(deployment_status: &DeploymentStatus) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+
+    let mut instances: Vec<_> = deployment_status.instances().iter().collect();
+    instances.sort_by_key(|(uuid, _)| uuid.uuid().to_owned());
+
+    for (uuid, instance) in instances {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("instance {}  ", uuid.uuid()),
+                Style::default().bold(),
+            ),
+            status_span(instance.status()),
+        ]));
+        if let Some(failure) = instance.failure_message() {
+            lines.push(Line::from(Span::styled(
+                format!("  failure_message : {}", failure),
+                Style::default().fg(Color::Red),
+            )));
+        }
+
+        let module = UUID::from(instance.module_id())
+            .ok()
+            .and_then(|id| deployment_status.modules().get(&id));
+        let mut module_line = vec![Span::raw(format!("  module {}  ", instance.module_id()))];
+        match module {
+            Some(module) => {
+                module_line.push(status_span(module.status()));
+                lines.push(Line::from(module_line));
+                if let Some(failure) = module.failure_message() {
+                    lines.push(Line::from(Span::styled(
+                        format!("  module failure  : {}", failure),
+                        Style::default().fg(Color::Red),
+                    )));
+                }
+            }
+            None => {
+                module_line.push(Span::styled(
+                    tr("not reported"),
+                    Style::default().fg(Color::Gray),
+                ));
+                lines.push(Line::from(module_line));
+            }
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::raw("reconcile_status : "),
+        status_span(deployment_status.reconcile_status().unwrap_or("N/A")),
+    ]));
+
+    lines
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let Some(tree) = app.deployment_detail.as_ref() else {
+        return Ok(());
+    };
+
+    let popup_area = centered_rect(90, 85, area);
+    Clear.render(popup_area, buf);
+
+    with_mqtt_ctrl(|mqtt_ctrl| {
+        let Some(deployment_status) = mqtt_ctrl.deployment_status() else {
+            Paragraph::new(tr("No data available"))
+                .block(normal_block(" Deployment Detail "))
+                .render(popup_area, buf);
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(popup_area);
+
+        Paragraph::new(detail_lines(deployment_status))
+            .wrap(Wrap { trim: false })
+            .block(normal_block(" Deployment Detail "))
+            .render(chunks[0], buf);
+
+        // Taken when the popup was opened, so that the folds and the selection are kept
+        tree.render(chunks[1], buf, normal_block(" Raw JSON "), true);
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detail_lines_shows_module_failure() {
+        let status = r#"{
+            "instances": {
+                "f3a018c5-1997-489a-8f1d-000000000001": {
+                    "status": "error",
+                    "moduleId": "f3a018c5-1997-489a-8f1d-a758df12977a",
+                    "failureMessage": "Module is not ready"
+                }
+            },
+            "modules": {
+                "f3a018c5-1997-489a-8f1d-a758df12977a": {
+                    "status": "error",
+                    "failureMessage": "Failed to load (error=11)"
+                }
+            },
+            "reconcileStatus": "ok"
+        }"#;
+
+        let deployment_status = DeploymentStatus::parse(status).unwrap();
+        let text: Vec<String> = detail_lines(&deployment_status)
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+
+        assert!(text.iter().any(|l| l.contains("Module is not ready")));
+        assert!(text.iter().any(|l| l.contains("Failed to load (error=11)")));
+        assert!(text.last().unwrap().contains("reconcile_status : ok"));
+    }
+}
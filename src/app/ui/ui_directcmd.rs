@@ -18,11 +18,12 @@ use crate::mqtt_ctrl::with_mqtt_ctrl;
 #[allow(unused)]
 use {
     super::centered_rect,
+    super::json_tree::{JsonTree, parse_json},
     super::*,
     crate::{
         app::{App, ConfigKey, DMScreen, DirectCommand, MainWindowFocus},
         error::{DMError, DMErrorExt},
-        mqtt_ctrl::MqttCtrl,
+        mqtt_ctrl::{MqttCtrl, evp::rpc_tracker::RpcState},
     },
     crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
     error_stack::{Report, Result},
@@ -81,15 +82,15 @@ pub fn draw_reboot(
             }
             Some(Err(e)) => e
                 .error_str()
-                .unwrap_or_else(|| "Failed to send reboot direct command".to_string()),
-            None => "Sending reboot command...".to_string(),
+                .unwrap_or_else(|| tr("Failed to send reboot direct command").to_owned()),
+            None => tr("Sending reboot command...").to_owned(),
         };
 
         let paragraph = Paragraph::new(message)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(" Direct Command Request "),
+                    .title(tr_padded(" Direct Command Request ")),
             )
             .alignment(Alignment::Left);
         paragraph.render(chunks[0], buf);
@@ -110,17 +111,17 @@ pub fn draw_reboot(
 
                 json::stringify_pretty(root, 4)
             }
-            Some(Err(e)) => e
-                .error_str()
-                .unwrap_or_else(|| "Failed to receive reboot direct command response".to_string()),
-            None => "Waiting for reboot response...".to_string(),
+            Some(Err(e)) => e.error_str().unwrap_or_else(|| {
+                tr("Failed to receive reboot direct command response").to_owned()
+            }),
+            None => tr("Waiting for reboot response...").to_owned(),
         };
 
         let paragraph = Paragraph::new(message)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(" Direct Command Response "),
+                    .title(tr_padded(" Direct Command Response ")),
             )
             .alignment(Alignment::Left);
         paragraph.render(chunks[1], buf);
@@ -157,7 +158,7 @@ pub fn draw_get_direct_image(
                     }
                 }
                 Err(e) => e.error_str().unwrap_or_else(|| {
-                    "Failed to send direct_get_image direct command".to_string()
+                    tr("Failed to send direct_get_image direct command").to_owned()
                 }),
             };
 
@@ -165,7 +166,7 @@ pub fn draw_get_direct_image(
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .title(" Direct Command Request "),
+                        .title(tr_padded(" Direct Command Request ")),
                 )
                 .alignment(Alignment::Left);
             paragraph.render(chunks[0], buf);
@@ -190,16 +191,16 @@ pub fn draw_get_direct_image(
                     }
                 }
                 Some(Err(e)) => e.error_str().unwrap_or_else(|| {
-                    "Failed to receive reboot direct command response".to_string()
+                    tr("Failed to receive reboot direct command response").to_owned()
                 }),
-                None => "Waiting for direct_get_image response...".to_string(),
+                None => tr("Waiting for direct_get_image response...").to_owned(),
             };
 
             let paragraph = Paragraph::new(message)
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .title(" Direct Command Response "),
+                        .title(tr_padded(" Direct Command Response ")),
                 )
                 .alignment(Alignment::Left);
             paragraph.render(chunks[1], buf);
@@ -271,15 +272,15 @@ pub fn draw_factory_reset(
             }
             Some(Err(e)) => e
                 .error_str()
-                .unwrap_or_else(|| "Failed to send factory_reset direct command".to_string()),
-            None => "Sending reboot command...".to_string(),
+                .unwrap_or_else(|| tr("Failed to send factory_reset direct command").to_owned()),
+            None => tr("Sending reboot command...").to_owned(),
         };
 
         let paragraph = Paragraph::new(message)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(" Direct Command Request "),
+                    .title(tr_padded(" Direct Command Request ")),
             )
             .alignment(Alignment::Left);
         paragraph.render(chunks[0], buf);
@@ -301,16 +302,16 @@ pub fn draw_factory_reset(
                 json::stringify_pretty(root, 4)
             }
             Some(Err(e)) => e.error_str().unwrap_or_else(|| {
-                "Failed to receive factory_reset direct command response".to_string()
+                tr("Failed to receive factory_reset direct command response").to_owned()
             }),
-            None => "Waiting for reboot response...".to_string(),
+            None => tr("Waiting for reboot response...").to_owned(),
         };
 
         let paragraph = Paragraph::new(message)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(" Direct Command Response "),
+                    .title(tr_padded(" Direct Command Response ")),
             )
             .alignment(Alignment::Left);
         paragraph.render(chunks[1], buf);
@@ -319,6 +320,153 @@ pub fn draw_factory_reset(
     Ok(())
 }
 
+pub fn draw_custom(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &App,
+    mqtt_ctrl: &MqttCtrl,
+) -> Result<(), DMError> {
+    let Some(result) = mqtt_ctrl.direct_command_request() else {
+        let focus = |config_key| ConfigKey::from(app.config_key_focus) == config_key;
+        let value = |config_key| {
+            let value = app
+                .config_keys
+                .get(usize::from(config_key))
+                .map(|s| s.as_str())
+                .unwrap_or_default();
+
+            if app.config_key_editable && focus(config_key) {
+                format!("{}|", value)
+            } else {
+                value.to_string()
+            }
+        };
+
+        let mut list_items = Vec::<ListItem>::new();
+        for key in app.config_key_focus_start..=app.config_key_focus_end {
+            let config_key = ConfigKey::from(key);
+            list_items_push_focus(
+                &mut list_items,
+                config_key.to_string().as_str(),
+                &value(config_key),
+                focus(config_key),
+            );
+        }
+        list_items_push_blank(&mut list_items);
+        let comment = ConfigKey::from(app.config_key_focus).note();
+        list_items_push_focus(&mut list_items, tr("Comment"), comment, false);
+
+        List::new(list_items)
+            .block(normal_block(" Custom RPC "))
+            .render(area, buf);
+        return Ok(());
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
+
+    // Draw request
+    {
+        let message = match result {
+            Ok(m) => json::parse(m)
+                .map(|j| json::stringify_pretty(j, 4))
+                .unwrap_or_else(|_| m.to_owned()),
+            Err(e) => e
+                .error_str()
+                .unwrap_or_else(|| tr("Failed to send custom RPC").to_owned()),
+        };
+
+        Paragraph::new(message)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(tr_padded(" Direct Command Request ")),
+            )
+            .alignment(Alignment::Left)
+            .render(chunks[0], buf);
+    }
+
+    // Draw response
+    {
+        let (title, message) = match mqtt_ctrl.direct_command_result() {
+            Some(Ok(m)) => {
+                let execute_time = mqtt_ctrl.direct_command_exec_time().unwrap_or_default();
+                let title = trf(" Direct Command Response ({0}ms) ", &[&execute_time]);
+                JsonTree::expanded(parse_json(&m.to_string())).render(
+                    chunks[1],
+                    buf,
+                    Block::default().borders(Borders::ALL).title(title),
+                    false,
+                );
+                return Ok(());
+            }
+            Some(Err(e)) => (
+                tr_padded(" Direct Command Response ").into_owned(),
+                e.error_str()
+                    .unwrap_or_else(|| tr("Failed to receive custom RPC response").to_owned()),
+            ),
+            None if result.is_err() => (
+                tr_padded(" Direct Command Response ").into_owned(),
+                String::new(),
+            ),
+            None => (
+                trf(
+                    " Direct Command Response ({0}ms) ",
+                    &[&mqtt_ctrl.direct_command_exec_time().unwrap_or_default()],
+                ),
+                tr("Waiting for response...").to_owned(),
+            ),
+        };
+
+        Paragraph::new(message)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .alignment(Alignment::Left)
+            .render(chunks[1], buf);
+    }
+
+    Ok(())
+}
+
+/// RPCs sent to the device, still awaited or finished
+pub fn draw_rpcs(area: Rect, buf: &mut Buffer, mqtt_ctrl: &MqttCtrl) {
+    let now = Instant::now();
+    let list_items: Vec<ListItem> = mqtt_ctrl
+        .rpcs()
+        .iter()
+        .map(|rpc| {
+            let (state, color) = match &rpc.state {
+                RpcState::Pending => (tr("waiting").to_owned(), Color::Yellow),
+                RpcState::Answered(response) => (
+                    response.res_info.code_str().to_owned(),
+                    if response.res_info.code() == 0 {
+                        Color::Green
+                    } else {
+                        Color::Red
+                    },
+                ),
+                RpcState::TimedOut => (tr("no response").to_owned(), Color::Red),
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::raw(format!(
+                    " {:<8} {:<24} {:>8}ms  ",
+                    rpc.req_id,
+                    rpc.method,
+                    rpc.elapsed(now).as_millis()
+                )),
+                Span::styled(state, Style::default().fg(color)),
+            ]))
+        })
+        .collect();
+
+    let title = trf(" RPCs ({0} pending) ", &[&mqtt_ctrl.rpcs().pending()]);
+    List::new(list_items)
+        .block(normal_block(&title))
+        .render(area, buf);
+}
+
 pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
     with_mqtt_ctrl(|mqtt_ctrl| -> Result<(), DMError> {
         match mqtt_ctrl.get_direct_command() {
@@ -327,33 +475,44 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                 draw_get_direct_image(area, buf, app, mqtt_ctrl)?
             }
             Some(DirectCommand::FactoryReset) => draw_factory_reset(area, buf, app, mqtt_ctrl)?,
+            Some(DirectCommand::Custom) => draw_custom(area, buf, app, mqtt_ctrl)?,
             None => {
-                let message = r#"
- What direct command do you want to send?
-
- You can use the following commands:
+                let message = vec![
+                    Line::default(),
+                    Line::from(tr_padded(" What direct command do you want to send?")),
+                    Line::default(),
+                    Line::from(tr_padded(" You can use the following commands:")),
+                    Line::default(),
+                    Line::from(tr_padded("   - Press 'r' to reboot the device.")),
+                    Line::from(tr_padded(
+                        "   - Press 'i' to retrieve preview image (DirectGetImage).",
+                    )),
+                    Line::from(tr_padded("   - Press 'f' to execute Factory Reset.")),
+                    Line::from(tr_padded("   - Press 'c' to send a custom RPC.")),
+                    Line::default(),
+                    Line::from(tr_padded(" Press 'Esc' to return to the main menu.")),
+                ];
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(14), Constraint::Min(3)])
+                    .split(area);
 
-   - Press 'r' to reboot the device.
-   - Press 'i' to retrieve preview image (DirectGetImage).
-   - Press 'f' to execute Factory Reset.
-
- Press 'Esc' to return to the main menu.
-"#;
                 let paragraph = Paragraph::new(message)
                     .block(
                         Block::default()
                             .borders(Borders::ALL)
-                            .title(" Direct Command "),
+                            .title(tr_padded(" Direct Command ")),
                     )
                     .alignment(Alignment::Left);
-                paragraph.render(area, buf);
+                paragraph.render(chunks[0], buf);
+                draw_rpcs(chunks[1], buf, mqtt_ctrl);
             }
             _ => {
-                let paragraph = Paragraph::new("Unsupported command")
+                let paragraph = Paragraph::new(tr("Unsupported command"))
                     .block(
                         Block::default()
                             .borders(Borders::ALL)
-                            .title(" Direct Command "),
+                            .title(tr_padded(" Direct Command ")),
                     )
                     .alignment(Alignment::Left);
                 paragraph.render(area, buf);
@@ -374,7 +533,11 @@ mod tests {
     fn test_draw_panics_when_mqtt_uninitialized() {
         // Construct a minimal App and drawing area. with_mqtt_ctrl inside draw()
         // should panic because the global MqttCtrl is not initialized in test.
-        let app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
         let area = Rect::new(0, 0, 40, 12);
         let mut buf = Buffer::empty(area);
 
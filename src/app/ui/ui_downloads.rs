@@ -0,0 +1,136 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{list_items_push_blank, list_items_push_text_focus, normal_block},
+    crate::{
+        app::App,
+        azurite::auto_download::{AutoDownloader, DownloadStatus},
+        error::DMError,
+        i18n::{format_datetime, tr, trf},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::{Line, Span},
+        widgets::{List, ListItem, Paragraph, Widget},
+    },
+};
+
+fn draw_pending(area: Rect, buf: &mut Buffer, downloader: &AutoDownloader) {
+    let mut list_items = Vec::<ListItem>::new();
+
+    for p in downloader.pending().iter() {
+        list_items_push_text_focus(
+            &mut list_items,
+            &format!(
+                "{}  {}/{}",
+                format_datetime(&p.issued_at),
+                p.container,
+                p.blob_name
+            ),
+            false,
+        );
+    }
+
+    List::new(list_items)
+        .block(normal_block(" Waiting for Upload "))
+        .render(area, buf);
+}
+
+fn draw_records(area: Rect, buf: &mut Buffer, downloader: &AutoDownloader) {
+    // Newest first
+    let list_items: Vec<ListItem> = downloader
+        .records()
+        .iter()
+        .rev()
+        .map(|r| {
+            let (text, color) = match &r.status {
+                DownloadStatus::Downloaded(path) => (format!("-> {}", path), Color::Green),
+                DownloadStatus::Failed(e) => (trf("failed: {0}", &[e]), Color::Red),
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::raw(format!(
+                    "{}  {}/{}  ",
+                    format_datetime(&r.time),
+                    r.container,
+                    r.blob_name
+                )),
+                Span::styled(text, Style::default().fg(color)),
+            ]))
+        })
+        .collect();
+
+    List::new(list_items)
+        .block(normal_block(" Downloads "))
+        .render(area, buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let downloader = app.auto_downloader();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Percentage(30),
+            Constraint::Min(5),
+        ])
+        .split(area);
+
+    let setting = match downloader.download_dir() {
+        Some(dir) => Span::styled(
+            trf("Enabled, downloading to: {0}", &[&dir]),
+            Style::default().fg(Color::Green),
+        ),
+        None => Span::styled(
+            tr("Disabled, start device-monitor with --download-dir <DIR> to enable"),
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    Paragraph::new(Line::from(setting))
+        .block(normal_block(" Auto Download "))
+        .render(chunks[0], buf);
+
+    draw_pending(chunks[1], buf, downloader);
+    draw_records(chunks[2], buf, downloader);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_without_download_dir() {
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
+
+        let area = Rect::new(0, 0, 80, 20);
+        let mut buf = Buffer::empty(area);
+
+        // Drawing does not touch MqttCtrl or AzuriteStorage
+        assert!(draw(area, &mut buf, &app).is_ok());
+    }
+}
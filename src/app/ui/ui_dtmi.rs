@@ -0,0 +1,178 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{focus_block, normal_block},
+    crate::{
+        app::{App, ConfigKey},
+        dtmi::{ContentKind, DtdlModel},
+        error::{DMError, DMErrorExt},
+        i18n::{tr, tr_padded},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{Paragraph, Widget, Wrap},
+    },
+};
+
+fn kind_color(kind: ContentKind) -> Color {
+    match kind {
+        ContentKind::Property => Color::Cyan,
+        ContentKind::Telemetry => Color::Green,
+        ContentKind::Command => Color::Yellow,
+        ContentKind::Component | ContentKind::Relationship => Color::Magenta,
+    }
+}
+
+/// Interface header then its contents, grouped by kind
+fn model_lines(model: &DtdlModel) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(Span::styled(
+        model.id.clone(),
+        Style::default().bold(),
+    ))];
+    if let Some(name) = &model.display_name {
+        lines.push(Line::from(format!("displayName : {}", name)));
+    }
+    if let Some(description) = &model.description {
+        lines.push(Line::from(format!("description : {}", description)));
+    }
+    for extends in &model.extends {
+        lines.push(Line::from(format!("extends     : {}", extends)));
+    }
+
+    let mut kind = None;
+    for content in &model.contents {
+        if kind != Some(content.kind) {
+            kind = Some(content.kind);
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("{}", content.kind),
+                Style::default().fg(kind_color(content.kind)).bold(),
+            )));
+        }
+
+        let mut spans = vec![Span::raw(format!(
+            "  {:<32} {}",
+            content.name, content.schema
+        ))];
+        if content.writable {
+            spans.push(Span::styled(
+                tr_padded(" (writable)"),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        lines.push(Line::from(spans));
+        if let Some(description) = &content.description {
+            lines.push(Line::from(Span::styled(
+                format!("    {}", description),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    if model.contents.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(tr("No contents modeled")));
+    }
+
+    lines
+}
+
+pub fn draw(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &App,
+    model: Option<&Result<DtdlModel, DMError>>,
+    scroll: u16,
+) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    let repository = &app.config_keys[usize::from(ConfigKey::DtmiRepository)];
+    let (block, text) = if app.config_key_editable {
+        (
+            focus_block(" Model Repository "),
+            format!("{}|", repository),
+        )
+    } else {
+        (normal_block(" Model Repository "), repository.clone())
+    };
+    Paragraph::new(text).block(block).render(chunks[0], buf);
+
+    let lines = match model {
+        Some(Ok(model)) => model_lines(model),
+        Some(Err(e)) => vec![Line::from(Span::styled(
+            e.error_str().unwrap_or(tr("Unknown error").to_owned()),
+            Style::default().fg(Color::Red),
+        ))],
+        None => vec![],
+    };
+    Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0))
+        .block(normal_block(" DTDL Model "))
+        .render(chunks[1], buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dtmi::ModelContent;
+
+    #[test]
+    fn test_model_lines() {
+        let model = DtdlModel {
+            id: "dtmi:com:example:thermostat;1".to_owned(),
+            display_name: Some("Thermostat".to_owned()),
+            contents: vec![
+                ModelContent {
+                    kind: ContentKind::Property,
+                    name: "targetTemperature".to_owned(),
+                    schema: "double".to_owned(),
+                    writable: true,
+                    description: None,
+                },
+                ModelContent {
+                    kind: ContentKind::Command,
+                    name: "reboot".to_owned(),
+                    schema: "(integer) -> ".to_owned(),
+                    writable: false,
+                    description: Some("Reboots the device".to_owned()),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let text: Vec<String> = model_lines(&model).iter().map(|l| l.to_string()).collect();
+        assert_eq!(text[1], "displayName : Thermostat");
+        assert!(text.contains(&"Property".to_owned()));
+        assert!(
+            text.iter()
+                .any(|l| l.contains("targetTemperature") && l.ends_with("(writable)"))
+        );
+        assert!(text.contains(&"    Reboots the device".to_owned()));
+    }
+}
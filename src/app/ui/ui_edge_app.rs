@@ -18,11 +18,14 @@ use crate::mqtt_ctrl::with_mqtt_ctrl;
 #[allow(unused)]
 use {
     super::{
+        crop_preview::{self, CROP_PREVIEW_KEYS},
         list_items_push, list_items_push_blank, list_items_push_focus, list_items_push_text_focus,
     },
     crate::{
         app::{App, ConfigKey, DMScreen, DMScreenState, ui::focus_block, ui::normal_block},
-        error::DMError,
+        error::{DMError, DMErrorExt},
+        i18n::{tr, tr_padded, trf},
+        mqtt_ctrl::evp::edge_app::{DiffKind, config_diff, parse_custom_settings},
     },
     json::{JsonValue, object::Object},
     ratatui::{
@@ -34,13 +37,17 @@ use {
         style::Stylize,
         symbols::border,
         text::{Line, Span, Text},
-        widgets::{Block, Borders, List, ListItem, Paragraph, Widget},
+        widgets::{Block, Borders, List, ListItem, Paragraph, Widget, Wrap},
     },
 };
 
-pub fn draw_default_state(area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
+pub fn draw_default_state(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let Some(instance_id) = app.edge_app_instance.as_deref() else {
+        return Ok(());
+    };
+
     with_mqtt_ctrl(|mqtt_ctrl| -> Result<(), DMError> {
-        if let Some(edge_app) = mqtt_ctrl.edge_app() {
+        if let Some(edge_app) = mqtt_ctrl.edge_app(instance_id) {
             // Edge App should be included in the deployment status
             if let Some(deployment_status) = mqtt_ctrl.deployment_status() {
                 if !deployment_status
@@ -52,7 +59,7 @@ pub fn draw_default_state(area: Rect, buf: &mut Buffer, _app: &App) -> Result<()
                 }
             }
 
-            let title = format!("Edge App: {}", edge_app.id());
+            let title = trf("Edge App: {0}", &[&edge_app.id()]);
             let outer_block = normal_block(&title).borders(Borders::NONE);
 
             let inner_area = outer_block.inner(area);
@@ -489,6 +496,10 @@ pub fn draw_default_state(area: Rect, buf: &mut Buffer, _app: &App) -> Result<()
                     }
                 }
             }
+        } else {
+            Paragraph::new(tr("No state has been reported by this instance yet."))
+                .block(normal_block(&trf("Edge App: {0}", &[&instance_id])))
+                .render(area, buf);
         }
         Ok(())
     })
@@ -513,7 +524,7 @@ pub fn draw_configure_state(area: Rect, buf: &mut Buffer, app: &App) -> Result<(
 
     let mut list_items = Vec::<ListItem>::new();
 
-    for key in app.config_key_focus_start..app.config_key_focus_end {
+    for key in app.config_key_focus_start..=app.config_key_focus_end {
         let config_key = ConfigKey::from(key);
 
         list_items_push_focus(
@@ -525,48 +536,231 @@ pub fn draw_configure_state(area: Rect, buf: &mut Buffer, app: &App) -> Result<(
     }
 
     list_items_push_blank(&mut list_items);
-    list_items_push_focus(&mut list_items, "Note", "", false);
+    list_items_push_focus(&mut list_items, tr("Note"), "", false);
     list_items_push_focus(
         &mut list_items,
         "  custom_settings",
-        format!(
-            "Describe in '{}/edge_app_custom_settings.json' if needed",
-            App::config_dir()
-        )
-        .as_str(),
+        &trf(
+            "Input JSON above or describe in '{0}/edge_app_custom_settings.json' if needed",
+            &[&App::config_dir()],
+        ),
         false,
     );
 
     let comment = ConfigKey::from(app.config_key_focus).note();
-    list_items_push_focus(&mut list_items, "  Comment", comment, false);
+    list_items_push_focus(&mut list_items, &tr_padded("  Comment"), comment, false);
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
 
     List::new(list_items)
         .block(normal_block(" EdgeApp Configuration "))
-        .render(area, buf);
+        .render(chunks[0], buf);
+
+    let right_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[1]);
+
+    draw_current_state(right_chunks[0], buf, app);
+    if CROP_PREVIEW_KEYS.contains(&ConfigKey::from(app.config_key_focus)) {
+        draw_crop_preview(right_chunks[1], buf, app);
+    } else {
+        draw_custom_settings_preview(right_chunks[1], buf, app);
+    }
 
     Ok(())
 }
 
+/// Crop and zoom of the entered values, the values left empty are the ones reported by the
+/// instance
+fn draw_crop_preview(area: Rect, buf: &mut Buffer, app: &App) {
+    let reported = |config_key: ConfigKey| {
+        let instance_id = app.edge_app_instance.as_deref()?;
+        with_mqtt_ctrl(|mqtt_ctrl| {
+            mqtt_ctrl
+                .edge_app(instance_id)
+                .and_then(|edge_app| edge_app.module().config_value(config_key))
+        })
+    };
+    let value = |config_key: ConfigKey| {
+        app.config_keys
+            .get(usize::from(config_key))
+            .filter(|v| !v.trim().is_empty())
+            .cloned()
+            .or_else(|| reported(config_key))
+    };
+
+    crop_preview::draw(value, area, buf);
+}
+
+/// Values reported by the instance for the entries being configured, so that they can be
+/// compared with the values entered
+fn draw_current_state(area: Rect, buf: &mut Buffer, app: &App) {
+    let block = normal_block(" Current State ");
+    let Some(instance_id) = app.edge_app_instance.as_deref() else {
+        Paragraph::new(tr("No Edge App instance selected"))
+            .block(block)
+            .render(area, buf);
+        return;
+    };
+
+    with_mqtt_ctrl(|mqtt_ctrl| {
+        let Some(edge_app) = mqtt_ctrl.edge_app(instance_id) else {
+            Paragraph::new(tr("No state has been reported by this instance yet."))
+                .block(block.clone())
+                .render(area, buf);
+            return;
+        };
+
+        let mut list_items = Vec::<ListItem>::new();
+        for key in app.config_key_focus_start..=app.config_key_focus_end {
+            let config_key = ConfigKey::from(key);
+            if config_key == ConfigKey::CustomSettings {
+                continue;
+            }
+
+            let value = edge_app.module().config_value(config_key);
+            list_items_push_focus(
+                &mut list_items,
+                config_key.to_string().as_str(),
+                value.as_deref().unwrap_or("-"),
+                key == app.config_key_focus,
+            );
+        }
+
+        if let Some(res_info) = edge_app.module().res_info() {
+            list_items_push_blank(&mut list_items);
+            list_items_push_text_focus(&mut list_items, "res_info", false);
+            list_items_push(&mut list_items, "  res_id", res_info.res_id());
+            list_items_push(&mut list_items, "  code", res_info.code_str());
+            list_items_push(&mut list_items, "  detail_msg", res_info.detail_msg());
+        }
+
+        List::new(list_items).block(block.clone()).render(area, buf);
+    });
+}
+
+/// Pretty prints the freeform custom settings, or shows why they are invalid
+fn draw_custom_settings_preview(area: Rect, buf: &mut Buffer, app: &App) {
+    let value = app
+        .config_keys
+        .get(usize::from(ConfigKey::CustomSettings))
+        .map(|s| s.trim())
+        .unwrap_or_default();
+
+    if value.is_empty() {
+        Paragraph::new(tr("No custom_settings"))
+            .block(normal_block(" Custom Settings Preview "))
+            .render(area, buf);
+        return;
+    }
+
+    match parse_custom_settings(value) {
+        Ok(custom_settings) => Paragraph::new(json::stringify_pretty(custom_settings, 2))
+            .block(normal_block(" Custom Settings Preview (valid) "))
+            .render(area, buf),
+        Err(e) => Paragraph::new(Span::styled(
+            e.error_str().unwrap_or(tr("Unknown error").to_owned()),
+            Style::default().fg(Color::Red),
+        ))
+        .block(normal_block(" Custom Settings Preview (invalid) "))
+        .wrap(Wrap { trim: false })
+        .render(area, buf),
+    }
+}
+
 pub fn draw_result_state(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
     if let Some(config_result) = app.config_result.as_ref() {
         match config_result {
             Ok(s) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                    .split(area);
+
                 let block = normal_block("Configuration Result");
                 let root = json::parse(s).unwrap();
 
                 Paragraph::new(json::stringify_pretty(root, 4))
                     .block(block)
-                    .render(area, buf);
+                    .render(chunks[0], buf);
+
+                draw_config_diff(chunks[1], buf, app);
             }
             Err(e) => {
+                // The details, e.g. each violated PQ setting on a line
                 let block = normal_block("Configuration Error");
-                Paragraph::new(e.to_string()).block(block).render(area, buf);
+                Paragraph::new(Text::styled(
+                    e.error_str().unwrap_or(e.to_string()),
+                    Style::default().fg(Color::Red),
+                ))
+                .block(block)
+                .wrap(Wrap { trim: false })
+                .render(area, buf);
             }
         }
     }
     Ok(())
 }
 
+/// Entered values compared with the reported state: changed values in yellow, values not
+/// reported by the instance in green and unchanged values in gray
+fn draw_config_diff(area: Rect, buf: &mut Buffer, app: &App) {
+    let range = app.config_key_focus_start..=app.config_key_focus_end;
+    let diff = match app.edge_app_instance.as_deref() {
+        Some(instance_id) => with_mqtt_ctrl(|mqtt_ctrl| {
+            let edge_app = mqtt_ctrl.edge_app(instance_id).map(|e| e.module());
+            config_diff(edge_app, &app.config_keys, range)
+        }),
+        None => config_diff(None, &app.config_keys, range),
+    };
+
+    let changed = diff
+        .iter()
+        .filter(|d| d.kind != DiffKind::Unchanged)
+        .count();
+
+    let lines: Vec<Line> = diff
+        .iter()
+        .map(|d| {
+            let one_line = |v: &str| v.split_whitespace().collect::<Vec<_>>().join(" ");
+            let reported = d.reported.as_deref().map(one_line);
+            let (text, color) = match d.kind {
+                DiffKind::Changed => (
+                    format!(
+                        "~ {}: {} -> {}",
+                        d.config_key,
+                        reported.unwrap_or_default(),
+                        one_line(&d.desired)
+                    ),
+                    Color::Yellow,
+                ),
+                DiffKind::Added => (
+                    format!("+ {}: {}", d.config_key, one_line(&d.desired)),
+                    Color::Green,
+                ),
+                DiffKind::Unchanged => (
+                    format!("= {}: {}", d.config_key, one_line(&d.desired)),
+                    Color::DarkGray,
+                ),
+            };
+            Line::from(Span::styled(text, Style::default().fg(color)))
+        })
+        .collect();
+
+    let title = trf(
+        " Changes ({0} changed, {1} unchanged) ",
+        &[&changed, &(diff.len() - changed)],
+    );
+    Paragraph::new(lines)
+        .block(normal_block(&title))
+        .wrap(Wrap { trim: false })
+        .render(area, buf);
+}
+
 pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
     let current_screen = app.current_screen();
     match current_screen {
@@ -589,7 +783,11 @@ mod tests {
     #[test]
     fn test_draw_configure_and_result_states() {
         // Build an App via the public constructor
-        let mut app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let mut app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Prepare drawing area and buffer
         let area = Rect::new(0, 0, 80, 24);
@@ -609,7 +807,11 @@ mod tests {
 
     #[test]
     fn test_draw_render_paths_no_panics() {
-        let app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
         let area = Rect::new(0, 0, 50, 16);
         let mut buf = Buffer::empty(area);
 
@@ -619,4 +821,26 @@ mod tests {
         // result state when no config_result is set should be Ok (no-op)
         assert!(draw_result_state(area, &mut buf, &app).is_ok());
     }
+
+    #[test]
+    fn test_custom_settings_preview() {
+        let mut app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
+        let area = Rect::new(0, 0, 60, 10);
+        let text = |buf: &Buffer| -> String { buf.content().iter().map(|c| c.symbol()).collect() };
+
+        app.config_keys[usize::from(ConfigKey::CustomSettings)] = r#"{"a": 1}"#.to_owned();
+        let mut buf = Buffer::empty(area);
+        draw_custom_settings_preview(area, &mut buf, &app);
+        assert!(text(&buf).contains("(valid)"));
+        assert!(text(&buf).contains("\"a\": 1"));
+
+        app.config_keys[usize::from(ConfigKey::CustomSettings)] = "[1]".to_owned();
+        let mut buf = Buffer::empty(area);
+        draw_custom_settings_preview(area, &mut buf, &app);
+        assert!(text(&buf).contains("(invalid)"));
+    }
 }
@@ -0,0 +1,79 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{list_items_push_text_focus, normal_block},
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::tr,
+        mqtt_ctrl::{evp::evp_state::is_error_status, with_mqtt_ctrl},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        prelude::{Color, Style},
+        text::Span,
+        widgets::{List, ListItem, Widget},
+    },
+};
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App, focus: usize) -> Result<(), DMError> {
+    with_mqtt_ctrl(|mqtt_ctrl| {
+        let mut list_items = Vec::<ListItem>::new();
+
+        if let Some(deployment_status) = mqtt_ctrl.deployment_status() {
+            for (i, (id, instance)) in deployment_status.sorted_instances().iter().enumerate() {
+                let focused = i == focus;
+                let selected = app.edge_app_instance.as_deref() == Some(id.uuid());
+                let reported = if mqtt_ctrl.edge_app(id.uuid()).is_some() {
+                    tr("reported")
+                } else {
+                    tr("not reported")
+                };
+
+                let text = format!(
+                    "{} {} {}  ModuleID: {}  {} {}  {} {}",
+                    if selected { "*" } else { " " },
+                    tr("Instance:"),
+                    id.uuid(),
+                    instance.module_id(),
+                    tr("Status:"),
+                    instance.status(),
+                    tr("State:"),
+                    reported
+                );
+
+                if is_error_status(instance.status()) && !focused {
+                    list_items.push(ListItem::new(Span::styled(
+                        text,
+                        Style::default().fg(Color::Red),
+                    )));
+                } else {
+                    list_items_push_text_focus(&mut list_items, &text, focused);
+                }
+            }
+        }
+
+        List::new(list_items)
+            .block(normal_block(" Select Edge App Instance "))
+            .render(area, buf);
+    });
+
+    Ok(())
+}
@@ -22,8 +22,9 @@ use {
     crate::{
         app::{App, ConfigKey, DMScreen, MainWindowFocus},
         error::{DMError, DMErrorExt},
-        mqtt_ctrl::MqttCtrl,
+        mqtt_ctrl::{MqttCtrl, elog_stats::ElogStats, evp::clock::TimeZoneMode},
     },
+    chrono::{DateTime, FixedOffset, Local},
     crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
     error_stack::{Report, Result},
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
@@ -54,15 +55,115 @@ use {
     },
 };
 
-pub fn draw(area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
+/// Tab of the Event Log screen
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ElogTab {
+    #[default]
+    Events,
+    /// Elogs aggregated by component
+    Stats,
+}
+
+impl ElogTab {
+    pub fn toggle(self) -> Self {
+        match self {
+            ElogTab::Events => ElogTab::Stats,
+            ElogTab::Stats => ElogTab::Events,
+        }
+    }
+}
+
+fn draw_stats(
+    stats: &ElogStats,
+    now: DateTime<Local>,
+    zone: TimeZoneMode,
+    device_offset: FixedOffset,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let header = format!(
+        "{} {:>6} {:>6} {:>6} {:>6} {:>6} {:>6} {:>7} {:>8}  {}",
+        pad_width(tr("Component"), 32),
+        "CRIT",
+        "ERROR",
+        "WARN",
+        "INFO",
+        "DEBUG",
+        "TRACE",
+        tr("Total"),
+        tr("Per min"),
+        tr("Last")
+    );
+    let mut lines = vec![Line::from(Span::styled(
+        header,
+        Style::default().fg(Color::White).bold(),
+    ))];
+
+    for component in stats.top_offenders() {
+        let color = match component.levels {
+            [critical, ..] if critical > 0 => Color::Red,
+            [_, error, ..] if error > 0 => Color::Magenta,
+            [_, _, warn, ..] if warn > 0 => Color::Yellow,
+            _ => Color::White,
+        };
+        let levels: Vec<String> = component
+            .levels
+            .iter()
+            .map(|count| format!("{count:>6}"))
+            .collect();
+
+        // Errors spiking beyond the baseline of the component
+        let (name, color) = match component.baseline.spike(now) {
+            Some(_) => (format!("▲ {}", component.name()), Color::LightRed),
+            None => (component.name(), color),
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{:<32} {} {:>7} {:>8.1}  {}",
+                name,
+                levels.join(" "),
+                component.total,
+                stats.rate(component, now),
+                zone.convert(&component.last, device_offset)
+                    .format("%H:%M:%S")
+            ),
+            Style::default().fg(color),
+        )));
+    }
+
+    Paragraph::new(lines)
+        .block(normal_block(&trf(
+            " ELOG STATS ({0} received) ",
+            &[&stats.total()],
+        )))
+        .render(area, buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App, tab: ElogTab) -> Result<(), DMError> {
     with_mqtt_ctrl(|mqtt_ctrl| -> Result<(), DMError> {
+        if tab == ElogTab::Stats {
+            draw_stats(
+                mqtt_ctrl.elog_stats(),
+                Local::now(),
+                app.time_zone,
+                mqtt_ctrl.device_offset(),
+                area,
+                buf,
+            );
+            return Ok(());
+        }
+
         let elogs = mqtt_ctrl.elogs();
+        let time = |timestamp: &str| {
+            format_device_time(timestamp, app.time_zone, mqtt_ctrl.device_offset())
+        };
 
         let mut record = vec![];
-        for elog in elogs.iter().rev() {
-            let line = Line::from(vec![
+        for entry in elogs.iter().rev() {
+            let elog = entry.elog();
+            let mut spans = vec![
                 Span::styled(
-                    format!("{} ", elog.timestamp()),
+                    format!("{} ", time(entry.last_timestamp())),
                     Style::default().fg(Color::White),
                 ),
                 match elog.level() {
@@ -87,9 +188,17 @@ pub fn draw(area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
                     format!("{} (0x{:0x})", elog.event_str(), elog.event_id()),
                     Style::default().fg(Color::White),
                 ),
-                Span::styled("\n", Style::default().fg(Color::White)),
-            ]);
-            record.push(line);
+            ];
+            if entry.count() > 1 {
+                spans.push(Span::styled(
+                    trf(
+                        " x{0} since {1}",
+                        &[&entry.count(), &time(entry.first_timestamp())],
+                    ),
+                    Style::default().fg(Color::Cyan),
+                ));
+            }
+            record.push(Line::from(spans));
         }
 
         if !record.is_empty() {
@@ -97,7 +206,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .title(" ELOGS ")
+                        .title(tr_padded(" ELOGS "))
                         .border_style(Style::default().fg(Color::White)),
                 )
                 .render(area, buf);
@@ -113,11 +222,51 @@ mod tests {
     use ratatui::buffer::Buffer;
     use ratatui::layout::Rect;
 
+    #[test]
+    fn test_draw_stats() {
+        use crate::mqtt_ctrl::evp::elog::Elog;
+
+        let now = Local::now();
+        let mut stats = ElogStats::new(now);
+        let elog = Elog::parse(
+            r#"{"serial":"S","level":1,"timestamp":"t","component_id":7,"component_name":"Sensor","event_id":4096}"#,
+        )
+        .unwrap();
+        stats.record(&elog, now);
+        stats.record(&elog, now);
+
+        let area = Rect::new(0, 0, 120, 6);
+        let mut buf = Buffer::empty(area);
+        let device_offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        draw_stats(
+            &stats,
+            now,
+            TimeZoneMode::Device,
+            device_offset,
+            area,
+            &mut buf,
+        );
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("ELOG STATS (2 received)"));
+        assert!(text.contains("Sensor (7)"));
+        assert!(text.contains("0      2      0      0      0      0       2      2.0"));
+        let last = now
+            .with_timezone(&device_offset)
+            .format("%H:%M:%S")
+            .to_string();
+        assert!(text.contains(&last));
+    }
+
     #[test]
     #[should_panic]
     fn test_draw_panics_when_mqtt_uninitialized() {
         // Building App via public constructor
-        let app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Prepare drawing area and buffer
         let area = Rect::new(0, 0, 40, 12);
@@ -0,0 +1,159 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{centered_rect, focus_block, normal_block},
+    crate::{
+        app::{
+            App,
+            error_log::{ErrorLog, Severity},
+        },
+        error::DMError,
+        i18n::{format_datetime, tr, trf},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::{Modifier, Stylize},
+        text::{Line, Span},
+        widgets::{Clear, List, ListItem, Paragraph, Widget, Wrap},
+    },
+};
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Warning => Color::Yellow,
+        Severity::Error => Color::Red,
+    }
+}
+
+pub fn draw_errors(errors: &ErrorLog, focus: usize, expanded: bool, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let list_items: Vec<ListItem> = errors
+        .iter()
+        .enumerate()
+        .map(|(i, event)| {
+            let mut style = Style::default().fg(Color::White);
+            if i == focus {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", event.time.format("%H:%M:%S")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{:<5} ", event.severity),
+                    Style::default().fg(severity_color(event.severity)),
+                ),
+                Span::styled(format!("[{}] {}", event.source, event.message), style),
+            ]))
+        })
+        .collect();
+
+    let title = trf(" Errors ({0}) ", &[&errors.len()]);
+    List::new(list_items)
+        .block(focus_block(&title))
+        .render(chunks[0], buf);
+
+    let mut lines = vec![];
+    if let Some(event) = errors.get(focus) {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", event.severity),
+                Style::default().fg(severity_color(event.severity)).bold(),
+            ),
+            Span::raw(trf(
+                "from {0} at {1}",
+                &[&event.source, &format_datetime(&event.time)],
+            )),
+        ]));
+        lines.push(Line::from(event.message.clone()));
+        if let Some(action) = event.action {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                trf("Suggested action: {0}", &[&action]),
+                Style::default().fg(Color::Green),
+            )));
+        }
+        match &event.report {
+            Some(report) if expanded => {
+                lines.push(Line::from(""));
+                lines.extend(report.lines().map(|l| Line::from(l.to_owned())));
+            }
+            Some(_) => {
+                lines.push(Line::from(""));
+                lines.push(Line::from(tr("(ENTER) show the full report")));
+            }
+            None => {}
+        }
+    } else {
+        lines.push(Line::from(tr("No errors")));
+    }
+
+    Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(normal_block(" Detail "))
+        .render(chunks[1], buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let Some(focus) = app.errors_focus else {
+        return Ok(());
+    };
+
+    let popup_area = centered_rect(90, 80, area);
+    Clear.render(popup_area, buf);
+    draw_errors(&app.errors, focus, app.errors_expanded, popup_area, buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::error_log::{ErrorEvent, ErrorSource};
+    use error_stack::Report;
+
+    #[test]
+    fn test_draw_errors() {
+        let mut errors = ErrorLog::default();
+        let report = Report::new(DMError::Timeout).attach_printable("No response to reboot");
+        errors.push(ErrorEvent::from_report(ErrorSource::Mqtt, &report));
+
+        let area = Rect::new(0, 0, 120, 20);
+        let mut buf = Buffer::empty(area);
+        draw_errors(&errors, 0, false, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("[mqtt] No response to reboot"));
+        assert!(text.contains("Suggested action: Check that the device is online"));
+        assert!(text.contains("(ENTER) show the full report"));
+
+        let mut buf = Buffer::empty(area);
+        draw_errors(&errors, 0, true, area, &mut buf);
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Operation timed out"));
+    }
+}
@@ -20,6 +20,7 @@ use {
     crate::{
         app::{App, DMScreen},
         error::DMError,
+        i18n::{tr, tr_padded},
         mqtt_ctrl::MqttCtrl,
     },
     crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
@@ -63,11 +64,11 @@ pub fn draw(area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
         ])
         .split(pop_area);
 
-    Paragraph::new("Do you want to exit? (y/n)")
+    Paragraph::new(tr("Do you want to exit? (y/n)"))
         .alignment(Alignment::Center)
         .block(
             Block::default()
-                .title(" EXIT ")
+                .title(tr_padded(" EXIT "))
                 .borders(Borders::ALL)
                 .bg(Color::DarkGray),
         )
@@ -85,7 +86,11 @@ mod tests {
     #[test]
     fn test_draw_returns_ok() {
         // Construct an App via the public constructor to pass into the draw function.
-        let app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Prepare drawing area and buffer
         let area = Rect::new(0, 0, 60, 20);
@@ -0,0 +1,421 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::ui_token_provider_blobs::format_file_size,
+    super::{centered_rect, focus_block, list_items_push_text_focus, normal_block},
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{tr, trf},
+    },
+    error_stack::{Report, Result},
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::Span,
+        widgets::{Clear, List, ListItem, Paragraph, Widget},
+    },
+    std::{
+        collections::BTreeSet,
+        path::{Path, PathBuf},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+/// State of the popup used to select a local file to upload
+#[derive(Debug, Clone, Default)]
+pub struct FileBrowserState {
+    pub dir: PathBuf,
+    pub entries: Vec<FileEntry>,
+    pub selected: usize,
+    /// Comma separated list of extensions, e.g. "wasm,aot". All files are shown when empty.
+    pub filter: String,
+    pub filter_editing: bool,
+    pub show_hidden: bool,
+    /// Files marked to be uploaded together, kept while moving between directories
+    pub marked: BTreeSet<PathBuf>,
+}
+
+impl FileBrowserState {
+    pub fn new(dir: &Path) -> Result<Self, DMError> {
+        let mut state = Self {
+            dir: std::fs::canonicalize(dir).unwrap_or(dir.to_path_buf()),
+            ..Default::default()
+        };
+        state.refresh()?;
+        Ok(state)
+    }
+
+    /// Starts from the current working directory
+    pub fn from_current_dir() -> Result<Self, DMError> {
+        let dir = std::env::current_dir().map_err(|e| {
+            Report::new(DMError::IOError)
+                .attach_printable(format!("Failed to get current directory: {}", e))
+        })?;
+        Self::new(&dir)
+    }
+
+    /// Directories are always shown, files only when their extension is in the filter
+    pub fn matches_filter(&self, name: &str) -> bool {
+        let extensions: Vec<String> = self
+            .filter
+            .split(',')
+            .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect();
+
+        if extensions.is_empty() {
+            return true;
+        }
+
+        Path::new(name)
+            .extension()
+            .map(|e| extensions.contains(&e.to_string_lossy().to_lowercase()))
+            .unwrap_or(false)
+    }
+
+    fn read_entries(&self, dir: &Path) -> Result<Vec<FileEntry>, DMError> {
+        let read_dir = std::fs::read_dir(dir).map_err(|e| {
+            Report::new(DMError::IOError).attach_printable(format!(
+                "Failed to read {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+
+        let mut dirs = vec![];
+        let mut files = vec![];
+        for entry in read_dir.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !self.show_hidden && name.starts_with('.') {
+                continue;
+            }
+
+            // Follow symbolic links, broken links are listed as empty files
+            let path = entry.path();
+            let (is_dir, size) = std::fs::metadata(&path)
+                .map(|m| (m.is_dir(), m.len()))
+                .unwrap_or((false, 0));
+
+            if is_dir {
+                dirs.push(FileEntry {
+                    name,
+                    path,
+                    is_dir,
+                    size: 0,
+                });
+            } else if self.matches_filter(&name) {
+                files.push(FileEntry {
+                    name,
+                    path,
+                    is_dir,
+                    size,
+                });
+            }
+        }
+
+        dirs.sort_by(|a, b| a.name.cmp(&b.name));
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut entries = vec![];
+        if let Some(parent) = dir.parent() {
+            entries.push(FileEntry {
+                name: "..".to_owned(),
+                path: parent.to_path_buf(),
+                is_dir: true,
+                size: 0,
+            });
+        }
+        entries.extend(dirs);
+        entries.extend(files);
+
+        Ok(entries)
+    }
+
+    fn change_dir(&mut self, dir: PathBuf, select: Option<&str>) -> Result<(), DMError> {
+        // Stay in the current directory if the new one can't be read
+        let entries = self.read_entries(&dir)?;
+        self.selected = select
+            .and_then(|s| entries.iter().position(|e| e.name == s))
+            .unwrap_or(0);
+        self.entries = entries;
+        self.dir = dir;
+        Ok(())
+    }
+
+    /// Reloads the current directory, keeping the selected entry if it still exists
+    pub fn refresh(&mut self) -> Result<(), DMError> {
+        let selected = self.selected_entry().map(|e| e.name.clone());
+        self.change_dir(self.dir.clone(), selected.as_deref())
+    }
+
+    pub fn selected_entry(&self) -> Option<&FileEntry> {
+        self.entries.get(self.selected)
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Moves to the parent directory and selects the directory we came from
+    pub fn parent(&mut self) -> Result<(), DMError> {
+        let Some(parent) = self.dir.parent().map(|p| p.to_path_buf()) else {
+            return Ok(());
+        };
+
+        let current = self
+            .dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string());
+        self.change_dir(parent, current.as_deref())
+    }
+
+    /// Enters the selected directory, or returns the path of the selected file
+    pub fn enter(&mut self) -> Result<Option<PathBuf>, DMError> {
+        let Some(entry) = self.selected_entry().cloned() else {
+            return Ok(None);
+        };
+
+        if !entry.is_dir {
+            return Ok(Some(entry.path));
+        }
+
+        if entry.name == ".." {
+            self.parent()?;
+        } else {
+            self.change_dir(entry.path, None)?;
+        }
+
+        Ok(None)
+    }
+
+    /// Marks or unmarks the selected file and moves to the next entry
+    pub fn toggle_mark(&mut self) {
+        if let Some(entry) = self.selected_entry().cloned()
+            && !entry.is_dir
+        {
+            if !self.marked.remove(&entry.path) {
+                self.marked.insert(entry.path);
+            }
+            self.move_down();
+        }
+    }
+
+    pub fn toggle_hidden(&mut self) -> Result<(), DMError> {
+        self.show_hidden = !self.show_hidden;
+        self.refresh()
+    }
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let Some(state) = app.file_browser.as_ref() else {
+        return Ok(());
+    };
+
+    let popup_area = centered_rect(80, 70, area);
+    Clear.render(popup_area, buf);
+
+    draw_browser(state, tr("Select Module File"), popup_area, buf);
+    Ok(())
+}
+
+/// Draws the entries of the directory and the filter, also used outside of a popup
+pub fn draw_browser(state: &FileBrowserState, title: &str, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .split(area);
+
+    let height = chunks[0].height.saturating_sub(2) as usize;
+    let width = chunks[0].width.saturating_sub(2) as usize;
+    let offset = (state.selected + 1).saturating_sub(height);
+
+    let mut list_items = Vec::<ListItem>::new();
+    for (i, entry) in state.entries.iter().enumerate().skip(offset).take(height) {
+        let focus = i == state.selected;
+        if entry.is_dir {
+            let text = format!("{}/", entry.name);
+            if focus {
+                list_items_push_text_focus(&mut list_items, &text, focus);
+            } else {
+                list_items.push(ListItem::new(Span::styled(
+                    text,
+                    Style::default().fg(Color::LightBlue),
+                )));
+            }
+        } else {
+            let size = format_file_size(entry.size);
+            let name_width = width.saturating_sub(size.len() + 1);
+            let mark = if state.marked.contains(&entry.path) {
+                "[x] "
+            } else {
+                ""
+            };
+            let name_width = name_width.saturating_sub(mark.len());
+            let text = format!("{}{:<name_width$} {}", mark, entry.name, size);
+            list_items_push_text_focus(&mut list_items, &text, focus);
+        }
+    }
+
+    let title = if state.marked.is_empty() {
+        format!(" {}: {} ", title, state.dir.display())
+    } else {
+        trf(
+            " {0}: {1} ({2} marked) ",
+            &[&title, &state.dir.display(), &state.marked.len()],
+        )
+    };
+    List::new(list_items)
+        .block(focus_block(&title))
+        .render(chunks[0], buf);
+
+    let mut filter = if state.filter.is_empty() && !state.filter_editing {
+        "*".to_owned()
+    } else {
+        state.filter.clone()
+    };
+    if state.filter_editing {
+        filter.push('|');
+    }
+    if state.show_hidden {
+        filter.push_str("  (hidden files shown)");
+    }
+
+    let filter_block = if state.filter_editing {
+        focus_block(" Filter By Extensions ")
+    } else {
+        normal_block(" Filter By Extensions ")
+    };
+    Paragraph::new(filter)
+        .block(filter_block)
+        .render(chunks[1], buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "device-monitor-file-browser-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("b.wasm"), [0u8; 10]).unwrap();
+        std::fs::write(dir.join("a.txt"), [0u8; 3]).unwrap();
+        std::fs::write(dir.join(".hidden"), []).unwrap();
+        std::fs::write(dir.join("sub").join("c.bin"), []).unwrap();
+        dir
+    }
+
+    fn names(state: &FileBrowserState) -> Vec<&str> {
+        state.entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_entries_sorted_with_sizes() {
+        let dir = test_dir("sorted");
+        let mut state = FileBrowserState::new(&dir).unwrap();
+
+        assert_eq!(names(&state), vec!["..", "sub", "a.txt", "b.wasm"]);
+        assert_eq!(state.entries[3].size, 10);
+        assert!(state.entries[1].is_dir);
+
+        state.toggle_hidden().unwrap();
+        assert_eq!(
+            names(&state),
+            vec!["..", "sub", ".hidden", "a.txt", "b.wasm"]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_filter_by_extension() {
+        let dir = test_dir("filter");
+        let mut state = FileBrowserState::new(&dir).unwrap();
+
+        state.filter = ".WASM, bin".to_owned();
+        state.refresh().unwrap();
+        assert_eq!(names(&state), vec!["..", "sub", "b.wasm"]);
+        assert!(state.matches_filter("x.bin"));
+        assert!(!state.matches_filter("bin"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_navigation() {
+        let dir = test_dir("navigation");
+        let mut state = FileBrowserState::new(&dir).unwrap();
+
+        state.move_down();
+        assert_eq!(state.enter().unwrap(), None);
+        assert!(state.dir.ends_with("sub"));
+        assert_eq!(names(&state), vec!["..", "c.bin"]);
+
+        state.move_down();
+        state.move_down();
+        let file = state.enter().unwrap().unwrap();
+        assert!(file.ends_with("sub/c.bin"));
+
+        // Going back selects the directory we came from
+        state.parent().unwrap();
+        assert_eq!(state.selected_entry().unwrap().name, "sub");
+
+        // Only files can be marked, and marks are kept while moving between directories
+        state.toggle_mark();
+        assert!(state.marked.is_empty());
+        state.move_down();
+        state.toggle_mark();
+        assert_eq!(state.selected_entry().unwrap().name, "b.wasm");
+        state.move_up();
+        state.move_up();
+        state.enter().unwrap();
+        state.move_down();
+        state.toggle_mark();
+        state.parent().unwrap();
+        assert_eq!(state.marked.len(), 2);
+        assert!(state.marked.iter().any(|p| p.ends_with("a.txt")));
+        assert!(state.marked.iter().any(|p| p.ends_with("sub/c.bin")));
+
+        // Unreadable directories keep the current state
+        let mut missing = state.clone();
+        missing.entries[1].path = dir.join("missing");
+        assert!(missing.enter().is_err());
+        assert_eq!(missing.dir, state.dir);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
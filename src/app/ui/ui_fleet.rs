@@ -0,0 +1,229 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{focus_block, normal_block, pad_width},
+    crate::{
+        app::{App, ConfigKey, screens::FleetScreen},
+        error::{DMError, DMErrorExt},
+        fleet::{Connection, DeploymentHealth, FleetColumn, FleetDevice},
+        i18n::{tr, trf},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{Paragraph, Widget},
+    },
+};
+
+const WIDTHS: [usize; 6] = [24, 14, 12, 12, 11, 14];
+
+fn connection_color(connection: Connection) -> Color {
+    match connection {
+        Connection::Connected => Color::Green,
+        Connection::Stale => Color::Yellow,
+        Connection::Disconnected | Connection::Unknown => Color::Red,
+    }
+}
+
+fn deployment_color(deployment: DeploymentHealth) -> Color {
+    match deployment {
+        DeploymentHealth::Error => Color::Red,
+        DeploymentHealth::Applying => Color::Yellow,
+        DeploymentHealth::Ok => Color::Green,
+        DeploymentHealth::None => Color::DarkGray,
+    }
+}
+
+fn elog_color(level: Option<u8>) -> Color {
+    match level {
+        Some(0 | 1) => Color::Red,
+        Some(2) => Color::Yellow,
+        _ => Color::White,
+    }
+}
+
+/// One row per device, the sort column marked with an arrow in the header
+fn draw_table(
+    devices: &[FleetDevice],
+    sort: FleetColumn,
+    descending: bool,
+    focus: usize,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let header: String = FleetColumn::ALL
+        .iter()
+        .zip(WIDTHS)
+        .map(|(column, width)| {
+            let title = if *column != sort {
+                tr(column.title()).to_owned()
+            } else if descending {
+                format!("{} ▼", tr(column.title()))
+            } else {
+                format!("{} ▲", tr(column.title()))
+            };
+            format!("{} ", pad_width(&title, width))
+        })
+        .collect();
+    let mut lines = vec![Line::from(Span::styled(header, Style::default().bold()))];
+
+    for (i, device) in devices.iter().enumerate() {
+        let id = match device.snapshot {
+            None => format!("{} *", device.device_id),
+            Some(_) => device.device_id.clone(),
+        };
+        let cell = |text: &str, width: usize, color: Color| {
+            Span::styled(format!("{:<width$} ", text), Style::default().fg(color))
+        };
+
+        let line = Line::from(vec![
+            cell(&id, WIDTHS[0], Color::White),
+            cell(
+                device.connection.name(),
+                WIDTHS[1],
+                connection_color(device.connection),
+            ),
+            cell(
+                device.firmware.as_deref().unwrap_or("-"),
+                WIDTHS[2],
+                Color::White,
+            ),
+            cell(
+                device.deployment.name(),
+                WIDTHS[3],
+                deployment_color(device.deployment),
+            ),
+            cell(
+                device.elog_level_str.unwrap_or("-"),
+                WIDTHS[4],
+                elog_color(device.elog_level),
+            ),
+            cell(&device.uptime_str(), WIDTHS[5], Color::White),
+        ]);
+        if i == focus {
+            lines.push(line.bold().bg(Color::DarkGray));
+        } else {
+            lines.push(line);
+        }
+    }
+
+    let healthy = devices
+        .iter()
+        .filter(|d| {
+            d.connection == Connection::Connected && d.deployment != DeploymentHealth::Error
+        })
+        .count();
+    Paragraph::new(lines)
+        .block(normal_block(&trf(
+            " Fleet: {0}/{1} healthy ",
+            &[&healthy, &devices.len()],
+        )))
+        .render(area, buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App, screen: &FleetScreen) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+    let dir = &app.config_keys[usize::from(ConfigKey::FleetDir)];
+    let (block, text) = if app.config_key_editable {
+        (focus_block(" Fleet Directory "), format!("{}|", dir))
+    } else {
+        (normal_block(" Fleet Directory "), dir.clone())
+    };
+    Paragraph::new(text).block(block).render(chunks[0], buf);
+
+    draw_table(
+        &screen.rows(),
+        screen.sort,
+        screen.descending,
+        screen.focus,
+        chunks[1],
+        buf,
+    );
+
+    let status = match &screen.fleet {
+        Some(Ok(_)) => Span::styled(
+            tr("* device of this session"),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Some(Err(e)) => Span::styled(
+            e.error_str().unwrap_or(tr("Unknown error").to_owned()),
+            Style::default().fg(Color::Red),
+        ),
+        None => Span::styled(
+            tr(
+                "Press (i) to enter the directory of the daemon snapshot directories or start device-monitor with --fleet-dir <DIR>",
+            ),
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    Paragraph::new(status).render(chunks[2], buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_draw_table() {
+        let device = |id: &str, snapshot: Option<&str>, connection, deployment| FleetDevice {
+            device_id: id.to_owned(),
+            snapshot: snapshot.map(PathBuf::from),
+            connection,
+            firmware: Some("D52408".to_owned()),
+            deployment,
+            elog_level: Some(1),
+            elog_level_str: Some("ERROR"),
+            uptime: Some(Duration::seconds(3723)),
+        };
+        let devices = [
+            device("A1", None, Connection::Connected, DeploymentHealth::Ok),
+            device(
+                "B2",
+                Some("fleet/cam2/state_20250101_000000.json"),
+                Connection::Stale,
+                DeploymentHealth::Error,
+            ),
+        ];
+
+        let area = Rect::new(0, 0, 100, 5);
+        let mut buf = Buffer::empty(area);
+        draw_table(&devices, FleetColumn::Uptime, true, 1, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Fleet: 1/2 healthy"));
+        assert!(text.contains("Uptime ▼"));
+        assert!(text.contains("A1 *"));
+        assert!(text.contains("stale          D52408       error        ERROR       01:02:03"));
+    }
+}
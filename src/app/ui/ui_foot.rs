@@ -18,9 +18,17 @@ use crate::mqtt_ctrl::with_mqtt_ctrl;
 #[allow(unused)]
 use {
     crate::{
-        app::{App, DMScreen, DMScreenState, DirectCommand, MainWindowFocus},
+        app::{
+            App, DMScreen, DMScreenState, DirectCommand, MainWindowFocus,
+            error_log::Severity,
+            screens::{
+                EvpModuleScreen, LogSettingsScreen, LogViewerScreen, StaticIpWizardScreen,
+                TokenProviderBlobsScreen, TokenProviderScreen, WifiWizardScreen,
+            },
+        },
         azurite::{AzuriteAction, AzuriteStorage, with_azurite_storage},
         error::DMError,
+        i18n::{format_datetime, format_elapsed, tr, tr_hint, tr_padded, trf},
         mqtt_ctrl::{
             MqttCtrl,
             evp::device_info::{ChipInfo, DeviceInfo},
@@ -32,7 +40,10 @@ use {
                 },
                 evp_state::DeploymentStatus,
             },
+            reboots::BOOT_LOOP_WINDOW,
         },
+        ota::wizard::OtaWizardStep,
+        provisioning::{static_ip::StaticIpWizardStep, wifi::WifiWizardStep},
     },
     chrono::Local,
     crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
@@ -73,30 +84,26 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
             .split(area);
 
         // Draw the current connection status and last connected time
-        let mut connect_info = Span::styled(" Disconnected ", Style::default().fg(Color::Red));
+        let mut connect_info =
+            Span::styled(tr_padded(" Disconnected "), Style::default().fg(Color::Red));
 
         let is_device_connected = mqtt_ctrl.is_device_connected();
         let last_connected = mqtt_ctrl.last_connected_time();
         let now = Local::now();
-        let delta = now - last_connected;
-        let days = delta.num_days();
-        let hours = delta.num_hours() % 24;
-        let minutes = delta.num_minutes() % 60;
-        let seconds = delta.num_seconds() % 60;
-
         let last_connected_str = format!(
-            "{} ({} day {}h {}m {}s ago)",
-            last_connected.format("%Y-%m-%d %H:%M:%S"),
-            days,
-            hours,
-            minutes,
-            seconds
+            "{} ({})",
+            format_datetime(
+                &app.time_zone
+                    .convert(&last_connected, mqtt_ctrl.device_offset())
+            ),
+            format_elapsed(now - last_connected)
         );
         let mut last_connected_info =
             Span::styled(&last_connected_str, Style::default().fg(Color::DarkGray));
 
         if is_device_connected {
-            connect_info = Span::styled(" Connected ", Style::default().fg(Color::Green));
+            connect_info =
+                Span::styled(tr_padded(" Connected "), Style::default().fg(Color::Green));
             last_connected_info =
                 Span::styled(&last_connected_str, Style::default().fg(Color::White));
         }
@@ -110,29 +117,139 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
             .block(Block::default().borders(Borders::NONE))
             .render(foot_chunks[0], buf);
 
-        if let Some(error) = app.app_error.as_ref() {
-            // If there is an error, display it in red
+        if let Some(error) = app.errors.current() {
+            // If there is an error, display it in red, warnings in yellow
+            let color = match error.severity {
+                Severity::Warning => Color::Yellow,
+                Severity::Error => Color::Red,
+            };
             Paragraph::new(Line::from(Span::styled(
-                error,
-                Style::default().fg(Color::Red),
+                format!("[{}] {}", error.source, error.message),
+                Style::default().fg(color),
+            )))
+            .render(foot_chunks[1], buf);
+        } else if let Some(alert) = mqtt_ctrl.deployment_alert()
+            && app.current_screen() == DMScreen::Main
+            && app.deployment_detail.is_none()
+        {
+            // Deployment failures are kept on the main screen until the deployment recovers
+            Paragraph::new(Line::from(vec![
+                Span::styled(
+                    alert,
+                    Style::default().fg(Color::White).bg(Color::Red).bold(),
+                ),
+                Span::styled(
+                    format!(" {}", tr_hint("(ENTER) on DEPLOYMENT STATUS for detail")),
+                    Style::default().fg(Color::Red),
+                ),
+            ]))
+            .render(foot_chunks[1], buf);
+        } else if let Some(reboots) = mqtt_ctrl.reboots().boot_loop(Local::now())
+            && app.current_screen() == DMScreen::Main
+            && app.deployment_detail.is_none()
+        {
+            // Shown until the device stops rebooting
+            Paragraph::new(Line::from(vec![
+                Span::styled(
+                    trf(
+                        "Boot loop suspected: {0} reboots in the last {1} minutes",
+                        &[&reboots, &BOOT_LOOP_WINDOW.num_minutes()],
+                    ),
+                    Style::default().fg(Color::White).bg(Color::Red).bold(),
+                ),
+                Span::styled(
+                    format!(" {}", tr_hint("(b) for the reboot history")),
+                    Style::default().fg(Color::Red),
+                ),
+            ]))
+            .render(foot_chunks[1], buf);
+        } else if let Some(alert) = mqtt_ctrl.req_res_alert()
+            && app.current_screen() == DMScreen::Main
+            && app.deployment_detail.is_none()
+            && app.search.is_none()
+        {
+            // The device keeps reporting the result of an older configuration
+            Paragraph::new(Line::from(Span::styled(
+                alert,
+                Style::default().fg(Color::Yellow).bold(),
             )))
             .render(foot_chunks[1], buf);
         } else if let Some(info) = mqtt_ctrl.info.as_ref() {
-            // If there is info, display it in white
+            // If there is info, display it in white. The App sets it translated, the fixed
+            // messages of MqttCtrl are looked up here.
             Paragraph::new(Line::from(Span::styled(
-                info,
+                tr(info),
                 Style::default().fg(Color::White),
             )))
             .render(foot_chunks[1], buf);
         } else {
             // Shows current keys hint based on the screen and focus
             let current_keys_hint = match app.current_screen() {
+                _ if app
+                    .confirmation
+                    .as_ref()
+                    .is_some_and(|c| c.expected.is_some()) =>
+                {
+                    Span::styled(
+                        "Type the name, (ENTER) confirm, (ESC) cancel",
+                        Style::default().fg(Color::White),
+                    )
+                }
+                _ if app.confirmation.is_some() => Span::styled(
+                    "(y) confirm, (n)/(ESC) cancel",
+                    Style::default().fg(Color::White),
+                ),
+                DMScreen::Main if app.errors_focus.is_some() => Span::styled(
+                    "UP(k)/DOWN(j) select, (ENTER) full report, (ESC)/(x) close, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+                DMScreen::Main
+                    if app
+                        .deployment_detail
+                        .as_ref()
+                        .or(app
+                            .pane_detail
+                            .as_ref()
+                            .filter(|d| d.show_raw)
+                            .map(|d| &d.tree))
+                        .is_some_and(|tree| tree.is_searching()) =>
+                {
+                    Span::styled(
+                        "Type to search, (ENTER)/(ESC) done",
+                        Style::default().fg(Color::White),
+                    )
+                }
+                DMScreen::Main if app.deployment_detail.is_some() => Span::styled(
+                    "UP(k)/DOWN(j) select, (SPACE) fold, LEFT(h)/RIGHT(l) collapse/expand, (/) search, (n) next match, (c) copy path, (ENTER)/(ESC) close, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+                DMScreen::Main if app.pane_detail.as_ref().is_some_and(|d| d.show_raw) => {
+                    Span::styled(
+                        "UP(k)/DOWN(j) select, (SPACE) fold, LEFT(h)/RIGHT(l) collapse/expand, (/) search, (n) next match, (c) copy path, (y) copy JSON, (r) fields, (f) full screen, (ENTER)/(ESC) close, (q) quit",
+                        Style::default().fg(Color::White),
+                    )
+                }
+                DMScreen::Main if app.pane_detail.is_some() => Span::styled(
+                    "UP(k)/DOWN(j) select, (r) raw JSON, (y) copy, (f) full screen, (ENTER)/(ESC) close, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+                DMScreen::Main if app.notes_editor.is_some() => Span::styled(
+                    "Type the labels or the note, (TAB) switch field, (ENTER) save, (ESC) cancel",
+                    Style::default().fg(Color::White),
+                ),
+                DMScreen::Main if app.search.is_some() => Span::styled(
+                    "Type to search, UP/DOWN select, (ENTER) jump to pane, (ESC) close",
+                    Style::default().fg(Color::White),
+                ),
                 DMScreen::Main => match app.main_window_focus() {
-                    MainWindowFocus::AgentState
-                    | MainWindowFocus::SystemSettings
-                    | MainWindowFocus::NetworkSettings
-                    | MainWindowFocus::WirelessSettings => Span::styled(
-                        "UP(k)/DOWN(j)/LEFT(h)/RIGHT(l) move, (ENTER) detail, (e)/(E) edit, (d) DirectCmd, (m) ModuleOp, (t) TokenProvider, (g) elog, (o) OTA, (a) AI Model, (q) quit",
+                    MainWindowFocus::NetworkSettings | MainWindowFocus::WirelessSettings => {
+                        Span::styled(
+                            "UP(k)/DOWN(j)/LEFT(h)/RIGHT(l) move, (ENTER) detail, (e)/(E) edit, (v) reveal passwords, (d) DirectCmd, (m) ModuleOp, (t) TokenProvider, (g) elog, (o) OTA, (a) AI Model, (s) Storage, (D) Downloads, (J) Jobs, (b) Reboots, (A) Audit, (I) Inventory, (C) Compare, (F) Fleet, (L) Log Settings, (G) Debug Log, (S) Scripts, (p) Publish, (R) Retained, (u) Subscriptions, (n) Network, (x) errors, (w) report, (/) search, (N) notes, (z) time zone, (q) quit",
+                            Style::default().fg(Color::White),
+                        )
+                    }
+                    MainWindowFocus::AgentState | MainWindowFocus::SystemSettings => Span::styled(
+                        "UP(k)/DOWN(j)/LEFT(h)/RIGHT(l) move, (ENTER) detail, (e)/(E) edit, (d) DirectCmd, (m) ModuleOp, (t) TokenProvider, (g) elog, (o) OTA, (a) AI Model, (s) Storage, (D) Downloads, (J) Jobs, (b) Reboots, (A) Audit, (I) Inventory, (C) Compare, (F) Fleet, (L) Log Settings, (G) Debug Log, (S) Scripts, (p) Publish, (R) Retained, (u) Subscriptions, (n) Network, (x) errors, (w) report, (/) search, (N) notes, (z) time zone, (q) quit",
                         Style::default().fg(Color::White),
                     ),
                     MainWindowFocus::DeviceState
@@ -143,7 +260,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                     | MainWindowFocus::DeviceReserved
                     | MainWindowFocus::DeploymentStatus
                     | MainWindowFocus::DeviceCapabilities => Span::styled(
-                        "UP(k)/DOWN(j) move, (Enter) detail, (d) DirectCmd, (m) ModuleOp, (t) TokenProvider, (g) elog, (o) OTA, (a) AI Model, (q) quit",
+                        "UP(k)/DOWN(j) move, (Enter) detail, (d) DirectCmd, (m) ModuleOp, (t) TokenProvider, (g) elog, (o) OTA, (a) AI Model, (s) Storage, (D) Downloads, (J) Jobs, (b) Reboots, (A) Audit, (I) Inventory, (C) Compare, (F) Fleet, (L) Log Settings, (G) Debug Log, (S) Scripts, (p) Publish, (R) Retained, (u) Subscriptions, (n) Network, (x) errors, (w) report, (/) search, (N) notes, (z) time zone, (q) quit",
                         Style::default().fg(Color::White),
                     ),
                 },
@@ -153,34 +270,48 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                     | MainWindowFocus::CompanionChip
                     | MainWindowFocus::AgentState
                     | MainWindowFocus::SystemSettings
-                    | MainWindowFocus::NetworkSettings
-                    | MainWindowFocus::DeploymentStatus
-                    | MainWindowFocus::WirelessSettings => Span::styled(
-                        "(e)/(E) edit (d) DirectCmd, (m) ModuleOp, (g) elog, (o) OTA, (ENTER)/(ESC) back, (q) quit",
+                    | MainWindowFocus::DeploymentStatus => Span::styled(
+                        "UP(k)/DOWN(j) select message, (SPACE) fold, (e)/(E) edit, (d) DirectCmd, (m) ModuleOp, (g) elog, (o) OTA, (z) time zone, (ENTER)/(ESC) back, (q) quit",
                         Style::default().fg(Color::White),
                     ),
+                    MainWindowFocus::NetworkSettings | MainWindowFocus::WirelessSettings => {
+                        Span::styled(
+                            "UP(k)/DOWN(j) select message, (SPACE) fold, (e)/(E) edit, (v) reveal passwords, (d) DirectCmd, (m) ModuleOp, (g) elog, (o) OTA, (z) time zone, (ENTER)/(ESC) back, (q) quit",
+                            Style::default().fg(Color::White),
+                        )
+                    }
                     MainWindowFocus::DeviceState
                     | MainWindowFocus::DeviceManifest
                     | MainWindowFocus::DeviceReserved
                     | MainWindowFocus::DeviceCapabilities => Span::styled(
-                        "(d) DirectCmd, (m) ModuleOp, (g) elog, (o) OTA, (ENTER)/(ESC) back, (q) quit",
+                        "UP(k)/DOWN(j) select message, (SPACE) fold, (d) DirectCmd, (m) ModuleOp, (g) elog, (o) OTA, (z) time zone, (ENTER)/(ESC) back, (q) quit",
                         Style::default().fg(Color::White),
                     ),
                 },
 
                 DMScreen::Elog => Span::styled(
-                    "(w) save, (ESC) back, (q) quit",
+                    "(TAB) events/stats, (z) time zone, (w) save, (c) save as CSV, (C) save metrics as CSV, (ESC) back, (q) quit",
                     Style::default().fg(Color::White),
                 ),
 
                 DMScreen::Configuration => {
-                    if app.config_result.is_none() {
+                    if app.config_result.is_none()
+                        && app.main_window_focus() == MainWindowFocus::NetworkSettings
+                    {
+                        Span::styled(
+                            "(ESC):back, UP(k)/DOWN(j) move, (K)/(J) section, (SPACE) fold, (a)/(i) edit, (r) device value, (x) unset, (n) check NTP, (p) check proxy, (w) write",
+                            Style::default().fg(Color::White),
+                        )
+                    } else if app.config_result.is_none() {
                         Span::styled(
-                            "(ESC):back, UP(k)/DOWN(j) move, (a)/(i) edit, (w) write",
+                            "(ESC):back, UP(k)/DOWN(j) move, (K)/(J) section, (SPACE) fold, (a)/(i) edit, (r) device value, (x) unset, (w) write",
                             Style::default().fg(Color::White),
                         )
                     } else {
-                        Span::styled("(ESC) back, (s) send", Style::default().fg(Color::White))
+                        Span::styled(
+                            "(ESC) back, (Q) QoS, (R) retain, (s) send, (v) send and verify",
+                            Style::default().fg(Color::White),
+                        )
                     }
                 }
 
@@ -192,7 +323,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                         )
                     } else {
                         Span::styled(
-                            "(q) quit, (ESC) back, (s) send",
+                            "(q) quit, (ESC) back, (Q) QoS, (R) retain, (s) send, (v) send and verify",
                             Style::default().fg(Color::White),
                         )
                     }
@@ -207,12 +338,24 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                             )
                         } else if let Some(Ok(_)) = mqtt_ctrl.direct_command_result() {
                             Span::styled(
-                                "(ESC) back, (w) save (q) quit",
+                                "(ESC) back, (w) save, (q) quit",
                                 Style::default().fg(Color::White),
                             )
                         } else {
                             Span::styled(
-                                "(ESC) back, (s) send (q) quit",
+                                "(ESC) back, (s) send, (q) quit",
+                                Style::default().fg(Color::White),
+                            )
+                        }
+                    } else if let Some(DirectCommand::Custom) = mqtt_ctrl.get_direct_command() {
+                        if mqtt_ctrl.direct_command_request().is_none() {
+                            Span::styled(
+                                "(ESC) back, UP(k)/DOWN(j) move, (a)/(i) edit, (s) send, (q) quit",
+                                Style::default().fg(Color::White),
+                            )
+                        } else {
+                            Span::styled(
+                                "(ESC) back, (e) edit and send again, (q) quit",
                                 Style::default().fg(Color::White),
                             )
                         }
@@ -221,17 +364,47 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                     }
                 }
 
+                DMScreen::EvpModule
+                    if app
+                        .screen::<EvpModuleScreen>()
+                        .is_some_and(|s| s.batch_summary.is_some()) =>
+                {
+                    Span::styled(
+                        "Press any key to close the summary",
+                        Style::default().fg(Color::White),
+                    )
+                }
+
+                DMScreen::EvpModule if app.config_key_editable => Span::styled(
+                    "(ENTER)/(ESC) done, filter by blob name",
+                    Style::default().fg(Color::White),
+                ),
+
                 DMScreen::EvpModule => {
                     if let Some(action) =
                         with_azurite_storage(|azure_storage| azure_storage.action())
                     {
                         match action {
-                            Some(AzuriteAction::Add) => Span::styled(
-                                "(ESC) back, (ENTER) register",
-                                Style::default().fg(Color::White),
-                            ),
+                            Some(AzuriteAction::Add) => {
+                                if app
+                                    .file_browser
+                                    .as_ref()
+                                    .map(|b| b.filter_editing)
+                                    .unwrap_or(false)
+                                {
+                                    Span::styled(
+                                        "(ENTER/ESC) finish filter, e.g. wasm,bin",
+                                        Style::default().fg(Color::White),
+                                    )
+                                } else {
+                                    Span::styled(
+                                        "UP(k)/DOWN(j) move, (SPACE) mark, (ENTER) open/register, (BACKSPACE/h) parent, (/) filter, (.) hidden, (ESC) back",
+                                        Style::default().fg(Color::White),
+                                    )
+                                }
+                            }
                             Some(AzuriteAction::Select) => Span::styled(
-                                "UP(k)/DOWN(j) move, (a) add, (r) remove, (ESC) back, (q) quit",
+                                "UP(k)/DOWN(j) move, (SPACE) mark, (a) add, (r) remove, (y) copy URL, (/) filter, (o) sort, (ESC) back, (q) quit",
                                 Style::default().fg(Color::White),
                             ),
                             Some(AzuriteAction::Deploy) => {
@@ -242,7 +415,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                                     )
                                 } else {
                                     Span::styled(
-                                        "UP(k)/DOWN(j) move, (a) add, (r) remove, (d) deploy, (u) undeploy, (ESC) back, (q) quit",
+                                        "UP(k)/DOWN(j) move, (SPACE) mark, (a) add, (r) remove, (d) deploy, (u) undeploy, (y) copy URL, (/) filter, (o) sort, (ESC) back, (q) quit",
                                         Style::default().fg(Color::White),
                                     )
                                 }
@@ -253,22 +426,84 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                         Span::styled("", Style::default().fg(Color::White))
                     }
                 }
+                DMScreen::TokenProvider
+                    if app
+                        .screen::<TokenProviderScreen>()
+                        .is_some_and(|s| s.form.is_some()) =>
+                {
+                    Span::styled(
+                        "UP/DOWN(TAB) move, (ENTER) create, (ESC) cancel",
+                        Style::default().fg(Color::White),
+                    )
+                }
+
+                DMScreen::TokenProvider if app.config_key_editable => Span::styled(
+                    "(ENTER)/(ESC) done, filter by name, description, tag or container",
+                    Style::default().fg(Color::White),
+                ),
+
                 DMScreen::TokenProvider => {
-                    if app.token_provider_for_config.is_some() {
+                    if app
+                        .screen::<TokenProviderScreen>()
+                        .is_some_and(|s| s.for_config.is_some())
+                    {
                         Span::styled(
-                            "UP(k)/DOWN(j) move, (ENTER) select, (s) show blobs, (a) add, (d) delete, (ESC) back, (q) quit",
+                            "UP(k)/DOWN(j) move, (ENTER) select, (s) show blobs, (l) log files, (a) add, (d) delete, (f) filter, (1)/(2)/(3) default for logs/input tensor/metadata, (ESC) back, (q) quit",
                             Style::default().fg(Color::White),
                         )
                     } else {
                         Span::styled(
-                            "UP(k)/DOWN(j) move, (s) show blobs, (a) add, (d) delete, (ESC) back, (q) quit",
+                            "UP(k)/DOWN(j) move, (s) show blobs, (l) log files, (a) add, (d) delete, (f) filter, (1)/(2)/(3) default for logs/input tensor/metadata, (ESC) back, (q) quit",
                             Style::default().fg(Color::White),
                         )
                     }
                 }
 
+                DMScreen::TokenProviderBlobs if app.config_key_editable => Span::styled(
+                    "(ENTER)/(ESC) done, filter by blob name",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::TokenProviderBlobs
+                    if app
+                        .screen::<TokenProviderBlobsScreen>()
+                        .is_some_and(|s| s.download_dir_input.is_some()) =>
+                {
+                    Span::styled(
+                        "(ENTER) set download directory, (ESC) cancel",
+                        Style::default().fg(Color::White),
+                    )
+                }
+
                 DMScreen::TokenProviderBlobs => Span::styled(
-                    "UP(k)/DOWN(j) move, (ENTER) download, (ESC) back, (q) quit",
+                    "UP(k)/DOWN(j) move, (SPACE) mark, (ENTER) download marked/selected, (c) download directory, (/) filter, (o) sort, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::LogFiles => Span::styled(
+                    "UP(k)/DOWN(j) move, (ENTER) view, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::LogViewer => {
+                    if app
+                        .screen::<LogViewerScreen>()
+                        .is_some_and(|s| s.state.search_editing)
+                    {
+                        Span::styled(
+                            "(ENTER) search, (ESC) cancel",
+                            Style::default().fg(Color::White),
+                        )
+                    } else {
+                        Span::styled(
+                            "UP(k)/DOWN(j) scroll, PGUP/PGDN(SPACE) page, (g)/(G) top/bottom, (/) search, (n)/(N) next/prev, (ESC) back, (q) quit",
+                            Style::default().fg(Color::White),
+                        )
+                    }
+                }
+
+                DMScreen::EdgeAppInstances => Span::styled(
+                    "UP(k)/DOWN(j) move, (ENTER) select, (ESC) back, (q) quit",
                     Style::default().fg(Color::White),
                 ),
 
@@ -278,23 +513,50 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                         Style::default().fg(Color::White),
                     ),
                     DMScreenState::Configuring => Span::styled(
-                        "UP(k)/DOWN(j) move, (w) write, (ESC) back, (q) quit",
+                        "UP(k)/DOWN(j) move, (w) write, (y) copy, (ESC) back, (q) quit",
                         Style::default().fg(Color::White),
                     ),
                     DMScreenState::Completed => Span::styled(
-                        "(s) send, (ESC) back, (q) quit",
+                        "(s) send, (y) copy, (ESC) back, (q) quit",
                         Style::default().fg(Color::White),
                     ),
                 },
 
                 DMScreen::Ota => Span::styled(
-                    "(ESC) back, (d) deploy, (q) quit",
+                    "(ESC) back, (d) deploy, (w) wizard, (r) rollback, (q) quit",
                     Style::default().fg(Color::White),
                 ),
 
+                DMScreen::OtaWizard => match app.ota_wizard.step {
+                    OtaWizardStep::Target => Span::styled(
+                        "UP(k)/DOWN(j) move, (ENTER) next, (ESC) back, (q) quit",
+                        Style::default().fg(Color::White),
+                    ),
+                    OtaWizardStep::File => Span::styled(
+                        "(ENTER) upload, (ESC) previous step",
+                        Style::default().fg(Color::White),
+                    ),
+                    OtaWizardStep::Review if app.ota_wizard.version_editable => Span::styled(
+                        "(ENTER)/(ESC) finish editing",
+                        Style::default().fg(Color::White),
+                    ),
+                    OtaWizardStep::Review => Span::styled(
+                        "(a)/(i) edit version, (ENTER) generate, (ESC) previous step, (q) quit",
+                        Style::default().fg(Color::White),
+                    ),
+                    OtaWizardStep::Send => Span::styled(
+                        "(s) send, (ESC) previous step, (q) quit",
+                        Style::default().fg(Color::White),
+                    ),
+                    OtaWizardStep::Progress => Span::styled(
+                        "(o) OTA state, (ESC) back, (q) quit",
+                        Style::default().fg(Color::White),
+                    ),
+                },
+
                 DMScreen::OtaConfig(state) => match state {
                     DMScreenState::Initial | DMScreenState::Configuring => Span::styled(
-                        "(ESC) back, UP(k)/DOWN(j) move, (a)/(i) edit, (w) write, (q) quit",
+                        "(ESC) back, UP(k)/DOWN(j) move, (a)/(i) edit, (r) refresh SAS, (w) write, (q) quit",
                         Style::default().fg(Color::White),
                     ),
                     DMScreenState::Completed => Span::styled(
@@ -304,7 +566,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                 },
 
                 DMScreen::AiModel => Span::styled(
-                    "(ESC) back, (d) deploy,  (q) quit",
+                    "(ESC) back, (d) deploy, (q) quit",
                     Style::default().fg(Color::White),
                 ),
 
@@ -320,14 +582,191 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                     ),
                 },
 
+                DMScreen::Subscriptions if app.config_key_editable => Span::styled(
+                    "(ENTER) subscribe, (ESC) cancel, wildcards: + one level, # all levels",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::Subscriptions => Span::styled(
+                    "UP(k)/DOWN(j) move, (a) add, (d) unsubscribe, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::Network => Span::styled(
+                    "(w) Wi-Fi wizard, (i) static IP wizard, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::StaticIpWizard => {
+                    match app.screen::<StaticIpWizardScreen>().map(|s| &s.wizard) {
+                        Some(wizard) => match wizard.step {
+                            StaticIpWizardStep::Settings if wizard.focus == 0 => Span::styled(
+                                "UP/DOWN move, (SPACE) dhcp/static, (ENTER) preview, (ESC) back",
+                                Style::default().fg(Color::White),
+                            ),
+                            StaticIpWizardStep::Settings => Span::styled(
+                                "UP/DOWN move, type to edit, (ENTER) preview, (ESC) back",
+                                Style::default().fg(Color::White),
+                            ),
+                            StaticIpWizardStep::Preview => Span::styled(
+                                "(s) send and verify, (ESC) previous step, (q) quit",
+                                Style::default().fg(Color::White),
+                            ),
+                            StaticIpWizardStep::Verify => Span::styled(
+                                "(ESC) back, (q) quit",
+                                Style::default().fg(Color::White),
+                            ),
+                        },
+                        None => Span::styled("", Style::default().fg(Color::White)),
+                    }
+                }
+
+                DMScreen::WifiWizard => match app.screen::<WifiWizardScreen>().map(|s| &s.wizard) {
+                    Some(wizard) => match wizard.step {
+                        WifiWizardStep::Credentials if wizard.is_encryption_focused() => {
+                            Span::styled(
+                                "UP/DOWN move, (SPACE) change encryption, (ENTER) preview, (ESC) back",
+                                Style::default().fg(Color::White),
+                            )
+                        }
+                        WifiWizardStep::Credentials => Span::styled(
+                            "UP/DOWN move, type to edit, (ENTER) preview, (ESC) back",
+                            Style::default().fg(Color::White),
+                        ),
+                        WifiWizardStep::Preview => Span::styled(
+                            "(s) send, (ESC) previous step, (q) quit",
+                            Style::default().fg(Color::White),
+                        ),
+                        WifiWizardStep::Monitor => {
+                            Span::styled("(ESC) back, (q) quit", Style::default().fg(Color::White))
+                        }
+                    },
+                    None => Span::styled("", Style::default().fg(Color::White)),
+                },
+
+                DMScreen::Dtmi if app.config_key_editable => Span::styled(
+                    "(ENTER) fetch the model, (ESC) cancel",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::Dtmi => Span::styled(
+                    "UP(k)/DOWN(j) scroll, (i)/(e) edit repository, (r) fetch again, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::LogSettings => {
+                    if app.config_result.is_some() {
+                        Span::styled(
+                            "(ESC) back, (Q) QoS, (R) retain, (s) send",
+                            Style::default().fg(Color::White),
+                        )
+                    } else if app
+                        .screen::<LogSettingsScreen>()
+                        .is_some_and(|s| s.choice.is_some())
+                    {
+                        Span::styled(
+                            "UP(k)/DOWN(j) move, (ENTER) select, (ESC) cancel",
+                            Style::default().fg(Color::White),
+                        )
+                    } else {
+                        Span::styled(
+                            "UP(k)/DOWN(j)/LEFT(h)/RIGHT(l) move, (i)/(ENTER) edit, (x) clear, (w) write, (ESC) back, (q) quit",
+                            Style::default().fg(Color::White),
+                        )
+                    }
+                }
+
+                DMScreen::Inventory => Span::styled(
+                    "(i)/(e) edit file, (r) reload, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::Compare => Span::styled(
+                    "UP(k)/DOWN(j) scroll, (d) differences only, (i)/(e) edit file, (r) reload, (s) save this device as reference, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::Fleet => Span::styled(
+                    "UP(k)/DOWN(j) move, LEFT(h)/RIGHT(l) sort column, (o) reverse order, (ENTER) focus device, (i)/(e) edit directory, (r) reload, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::DebugLog => Span::styled(
+                    "UP(k)/DOWN(j) scroll, PageUp/PageDown page, (g) top, (G) follow, (l) level filter, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::Retained => Span::styled(
+                    "UP(k)/DOWN(j) move, (SPACE) mark, (c) clear marked/focused, (r) rescan, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::Publish => {
+                    if app.config_result.is_none() {
+                        Span::styled(
+                            "(ESC) back, UP(k)/DOWN(j) move, (a)/(i) edit, (p)/(n) previous/next, (w) publish, (q) quit",
+                            Style::default().fg(Color::White),
+                        )
+                    } else {
+                        Span::styled(
+                            "(ESC) back, (w) publish again, (W) publish as it is, (p)/(n) previous/next, (q) quit",
+                            Style::default().fg(Color::White),
+                        )
+                    }
+                }
+
+                DMScreen::StorageSettings => {
+                    if app.config_result.is_none() {
+                        Span::styled(
+                            "(ESC) back, UP(k)/DOWN(j) move, (a)/(i) edit, (w) apply, (q) quit",
+                            Style::default().fg(Color::White),
+                        )
+                    } else {
+                        Span::styled(
+                            "(ESC) back, (w) apply again, (q) quit",
+                            Style::default().fg(Color::White),
+                        )
+                    }
+                }
+
+                DMScreen::Downloads => {
+                    Span::styled("(ESC) back, (q) quit", Style::default().fg(Color::White))
+                }
+
+                DMScreen::Kiosk => {
+                    Span::styled("(ESC) full UI, (q) quit", Style::default().fg(Color::White))
+                }
+
+                DMScreen::Reboots => {
+                    Span::styled("(ESC) back, (q) quit", Style::default().fg(Color::White))
+                }
+
+                DMScreen::Audit => Span::styled(
+                    "UP(k)/DOWN(j) select, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::Jobs => Span::styled(
+                    "UP(k)/DOWN(j) select, (c) cancel queued job, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
+                DMScreen::Scripts => Span::styled(
+                    "UP(k)/DOWN(j) move, (ENTER) open/run, (BACKSPACE) parent, (c) cancel script, (ESC) back, (q) quit",
+                    Style::default().fg(Color::White),
+                ),
+
                 DMScreen::Exiting => {
-                    Span::styled("(y) exit / (n) cancel", Style::default().fg(Color::White))
+                    Span::styled("(y) exit, (n) cancel", Style::default().fg(Color::White))
                 }
             };
 
-            Paragraph::new(Line::from(format!(" {}", current_keys_hint)))
-                .block(Block::default().borders(Borders::LEFT))
-                .render(foot_chunks[1], buf);
+            Paragraph::new(Line::from(format!(
+                " {}",
+                tr_hint(&current_keys_hint.content)
+            )))
+            .block(Block::default().borders(Borders::LEFT))
+            .render(foot_chunks[1], buf);
         }
 
         Ok(())
@@ -344,7 +783,11 @@ mod tests {
     #[should_panic]
     fn test_draw_panics_when_mqtt_uninitialized() {
         // Building App via public constructor
-        let app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Prepare drawing area and buffer
         let area = Rect::new(0, 0, 60, 3);
@@ -18,12 +18,16 @@ limitations under the License.
 use {
     crate::{
         app::{App, DMScreen},
+        azurite::health::{AzuriteHealth, HealthMonitor},
         error::DMError,
+        i18n::{tr, trf},
+        jobs::{JobRecord, JobStatus},
         mqtt_ctrl::{
             MqttCtrl,
             evp::device_info::{ChipInfo, DeviceInfo},
             evp::evp_state::{AgentDeviceConfig, AgentSystemInfo, UUID},
             evp::{
+                clock::TimeZoneMode,
                 device_info::{
                     DeviceCapabilities, DeviceReserved, DeviceStates, NetworkSettings,
                     SystemSettings, WirelessSettings,
@@ -31,6 +35,7 @@ use {
                 evp_state::DeploymentStatus,
             },
         },
+        notes::DeviceNote,
     },
     chrono::Local,
     crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
@@ -62,17 +67,181 @@ use {
     },
 };
 
-pub fn draw(area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
+/// Azurite connectivity, with the retry status while it fails
+fn health_badge(monitor: &HealthMonitor, job: Option<&JobRecord>, now: Instant) -> Line<'static> {
+    let health = monitor.health();
+    let color = match health {
+        AzuriteHealth::Unknown => Color::White,
+        AzuriteHealth::Connected => Color::Green,
+        AzuriteHealth::Degraded => Color::Yellow,
+        AzuriteHealth::Unavailable => Color::Red,
+    };
+
+    let mut spans = vec![];
+    if let Some(job) = job {
+        let elapsed = job.duration(now);
+        let progress = job.progress.map(|p| format!(" {p}%")).unwrap_or_default();
+        spans.push(Span::styled(
+            format!(
+                "{} {}{} ({}s)  ",
+                super::spinner(elapsed),
+                job.label,
+                progress,
+                elapsed.as_secs()
+            ),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+    let health_text = health.to_string();
+    spans.push(Span::styled(
+        format!("Azurite: {}", tr(&health_text)),
+        Style::default().fg(color).bold(),
+    ));
+    if let Some(error) = monitor.last_error() {
+        let retry = match monitor.next_check(now) {
+            Some(left) => trf("retry in {0}s", &[&left.as_millis().div_ceil(1000)]),
+            None => tr("retrying").to_owned(),
+        };
+        spans.push(Span::styled(
+            format!(" ({error}, {retry})"),
+            Style::default().fg(color),
+        ));
+    }
+    spans.push(Span::raw(" "));
+
+    Line::from(spans)
+}
+
+/// Time zone the times are shown in, switched with (z)
+fn time_zone_badge(zone: TimeZoneMode) -> Span<'static> {
+    Span::styled(
+        format!("TZ: {}  ", tr(zone.name())),
+        Style::default().fg(Color::Gray),
+    )
+}
+
+/// Reminder that the messages of the user are written to the review log instead of sent
+fn dry_run_badge() -> Span<'static> {
+    Span::styled(
+        format!("{}  ", tr("DRY RUN")),
+        Style::default().fg(Color::Black).bg(Color::Yellow).bold(),
+    )
+}
+
+/// Reminder that the configurations, RPCs, deployments and blob changes are refused
+fn read_only_badge() -> Span<'static> {
+    Span::styled(
+        format!("{}  ", tr("READ ONLY")),
+        Style::default().fg(Color::White).bg(Color::Red).bold(),
+    )
+}
+
+/// Reminder that the latencies and disconnections seen are simulated
+fn slow_network_badge() -> Span<'static> {
+    Span::styled(
+        format!("{}  ", tr("SLOW NETWORK")),
+        Style::default().fg(Color::Black).bg(Color::Magenta).bold(),
+    )
+}
+
+/// Components whose elog errors just spiked beyond their baseline, the latest first
+fn elog_anomaly_badge(components: &[String]) -> Option<Span<'static>> {
+    let (latest, others) = components.split_first()?;
+    let more = if others.is_empty() {
+        String::new()
+    } else {
+        format!(" +{}", others.len())
+    };
+    Some(Span::styled(
+        format!("▲ {}{}  ", trf("Errors up: {0}", &[latest]), more),
+        Style::default().fg(Color::LightRed),
+    ))
+}
+
+/// Labels of the device and the beginning of its note, after the breadcrumbs
+fn note_spans(note: &DeviceNote) -> Vec<Span<'static>> {
+    const NOTE_WIDTH: usize = 40;
+
+    let mut spans = vec![Span::raw("  ")];
+    for label in &note.labels {
+        spans.push(Span::styled(
+            format!(" {label} "),
+            Style::default().fg(Color::Black).bg(Color::Cyan),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if !note.note.is_empty() {
+        let mut text: String = note.note.chars().take(NOTE_WIDTH).collect();
+        if note.note.chars().count() > NOTE_WIDTH {
+            text.push('…');
+        }
+        spans.push(Span::styled(
+            text,
+            Style::default().fg(Color::Gray).italic(),
+        ));
+    }
+    spans
+}
+
+/// Screens from Main to the current one, Alt+<n> goes back to the n-th
+fn breadcrumbs(screens: &[DMScreen]) -> Line<'static> {
+    let mut spans = vec![Span::raw(" ")];
+    for (i, screen) in screens.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" > ", Style::default().fg(Color::DarkGray)));
+        }
+        let style = if i + 1 == screens.len() {
+            Style::default().fg(Color::White).bold()
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        spans.push(Span::styled(screen.name(), style));
+    }
+
+    Line::from(spans)
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
     // Draw title
     const VERSION: &str = env!("CARGO_PKG_VERSION");
     Paragraph::new(Text::styled(
-        format!("Device Monitor v{VERSION}"),
+        trf("Device Monitor v{0}", &[&VERSION]),
         Style::default().fg(Color::White).bold(),
     ))
     .alignment(Alignment::Center)
     .block(Block::default().borders(Borders::empty()))
     .render(area, buf);
 
+    let mut left = breadcrumbs(&app.screens());
+    if let Some(note) = app.device_note() {
+        left.spans.extend(note_spans(note));
+    }
+    Paragraph::new(left)
+        .alignment(Alignment::Left)
+        .render(area, buf);
+
+    let mut badge = health_badge(
+        app.azurite_health(),
+        app.current_job().as_ref(),
+        Instant::now(),
+    );
+    badge.spans.insert(0, time_zone_badge(app.time_zone));
+    if let Some(anomaly) = elog_anomaly_badge(app.elog_anomalies()) {
+        badge.spans.insert(0, anomaly);
+    }
+    if app.dry_run {
+        badge.spans.insert(0, dry_run_badge());
+    }
+    if app.read_only {
+        badge.spans.insert(0, read_only_badge());
+    }
+    if app.slow_network {
+        badge.spans.insert(0, slow_network_badge());
+    }
+    Paragraph::new(badge)
+        .alignment(Alignment::Right)
+        .render(area, buf);
+
     Ok(())
 }
 
@@ -85,7 +254,11 @@ mod tests {
     #[test]
     fn test_draw_renders_title_ok() {
         // Construct a minimal App via public constructor
-        let app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Prepare drawing area and buffer
         let area = Rect::new(0, 0, 60, 3);
@@ -94,4 +267,129 @@ mod tests {
         // draw should succeed and not panic
         assert!(draw(area, &mut buf, &app).is_ok());
     }
+
+    #[test]
+    fn test_draw_mode_badges() {
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            dry_run: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let area = Rect::new(0, 0, 80, 1);
+        let mut buf = Buffer::empty(area);
+        draw(area, &mut buf, &app).unwrap();
+        let line: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_owned())
+            .collect();
+        assert!(line.contains("DRY RUN  TZ: Local"));
+
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            read_only: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let mut buf = Buffer::empty(area);
+        draw(area, &mut buf, &app).unwrap();
+        let line: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_owned())
+            .collect();
+        assert!(line.contains("READ ONLY  TZ: Local"));
+
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            slow_network: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let mut buf = Buffer::empty(area);
+        draw(area, &mut buf, &app).unwrap();
+        let line: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_owned())
+            .collect();
+        assert!(line.contains("SLOW NETWORK  TZ: Local"));
+    }
+
+    #[test]
+    fn test_elog_anomaly_badge() {
+        assert!(elog_anomaly_badge(&[]).is_none());
+        assert_eq!(
+            elog_anomaly_badge(&["Sensor (2)".to_owned()])
+                .unwrap()
+                .content,
+            "▲ Errors up: Sensor (2)  "
+        );
+        assert_eq!(
+            elog_anomaly_badge(&["Sensor (2)".to_owned(), "Main (1)".to_owned()])
+                .unwrap()
+                .content,
+            "▲ Errors up: Sensor (2) +1  "
+        );
+    }
+
+    #[test]
+    fn test_note_spans() {
+        let note = DeviceNote {
+            labels: vec!["lab bench 3".to_owned(), "flaky Wi-Fi".to_owned()],
+            note: "x".repeat(45),
+        };
+        assert_eq!(
+            Line::from(note_spans(&note)).to_string(),
+            format!("   lab bench 3   flaky Wi-Fi  {}…", "x".repeat(40))
+        );
+    }
+
+    #[test]
+    fn test_breadcrumbs() {
+        let screens = [
+            DMScreen::Main,
+            DMScreen::Ota,
+            DMScreen::OtaConfig(crate::app::DMScreenState::Initial),
+        ];
+        assert_eq!(breadcrumbs(&screens).to_string(), " Main > Ota > OtaConfig");
+    }
+
+    #[test]
+    fn test_time_zone_badge() {
+        assert_eq!(
+            time_zone_badge(TimeZoneMode::default()).content,
+            "TZ: Local  "
+        );
+        assert_eq!(time_zone_badge(TimeZoneMode::Utc).content, "TZ: UTC  ");
+    }
+
+    #[test]
+    fn test_health_badge() {
+        let now = Instant::now();
+        let mut monitor = HealthMonitor::default();
+        assert_eq!(
+            health_badge(&monitor, None, now).to_string(),
+            "Azurite: checking "
+        );
+
+        monitor.record(None, now);
+        assert_eq!(
+            health_badge(&monitor, None, now).to_string(),
+            "Azurite: unavailable (Azurite storage not available, retry in 5s) "
+        );
+
+        let job = JobRecord {
+            id: 1,
+            label: "Scanning containers".to_owned(),
+            key: "scan-containers".to_owned(),
+            status: JobStatus::Running,
+            progress: None,
+            submitted: now,
+            started: Some(now),
+            finished: None,
+            background: false,
+        };
+        assert!(
+            health_badge(&monitor, Some(&job), now + Duration::from_secs(2))
+                .to_string()
+                .starts_with("⠋ Scanning containers (2s)  Azurite: unavailable")
+        );
+    }
 }
@@ -0,0 +1,146 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{focus_block, normal_block, pad_width},
+    crate::{
+        app::{App, ConfigKey},
+        error::{DMError, DMErrorExt},
+        i18n::{tr, trf},
+        inventory::{Inventory, InventoryCheck},
+        mqtt_ctrl::with_mqtt_ctrl,
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{Paragraph, Widget},
+    },
+};
+
+/// One line per expected version, mismatches in red and versions not reported in yellow
+fn draw_checks(checks: &[InventoryCheck], area: Rect, buf: &mut Buffer) {
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{} {} {} {}",
+            pad_width(tr("Item"), 30),
+            pad_width(tr("Expected"), 24),
+            pad_width(tr("Reported"), 24),
+            tr("Status")
+        ),
+        Style::default().bold(),
+    ))];
+
+    for check in checks {
+        let (status, color) = match &check.reported {
+            _ if check.compliant() => (tr("OK"), Color::Green),
+            Some(_) => (tr("MISMATCH"), Color::Red),
+            None => (tr("NOT REPORTED"), Color::Yellow),
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{:<30} {:<24} {:<24} {}",
+                check.item,
+                check.expected,
+                check.reported.as_deref().unwrap_or("-"),
+                status
+            ),
+            Style::default().fg(color),
+        )));
+    }
+
+    let compliant = checks.iter().filter(|c| c.compliant()).count();
+    let title = trf(" Compliance: {0}/{1} ", &[&compliant, &checks.len()]);
+    let block = if compliant == checks.len() {
+        normal_block(&title).border_style(Style::default().fg(Color::Green))
+    } else {
+        normal_block(&title).border_style(Style::default().fg(Color::Red))
+    };
+    Paragraph::new(lines).block(block).render(area, buf);
+}
+
+pub fn draw(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &App,
+    inventory: Option<&Result<Inventory, DMError>>,
+) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    let file = &app.config_keys[usize::from(ConfigKey::InventoryFile)];
+    let (block, text) = if app.config_key_editable {
+        (focus_block(" Inventory File "), format!("{}|", file))
+    } else {
+        (normal_block(" Inventory File "), file.clone())
+    };
+    Paragraph::new(text).block(block).render(chunks[0], buf);
+
+    match inventory {
+        Some(Ok(inventory)) => {
+            let checks = with_mqtt_ctrl(|mqtt_ctrl| inventory.compare(mqtt_ctrl.device_info()));
+            draw_checks(&checks, chunks[1], buf);
+        }
+        Some(Err(e)) => Paragraph::new(Span::styled(
+            e.error_str().unwrap_or(tr("Unknown error").to_owned()),
+            Style::default().fg(Color::Red),
+        ))
+        .block(normal_block(" Compliance "))
+        .render(chunks[1], buf),
+        None => Paragraph::new(Span::styled(
+            tr("Press (i) to enter the inventory file or start device-monitor with --inventory <FILE>"),
+            Style::default().fg(Color::DarkGray),
+        ))
+        .block(normal_block(" Compliance "))
+        .render(chunks[1], buf),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_checks() {
+        let check = |item: &str, expected: &str, reported: Option<&str>| InventoryCheck {
+            item: item.to_owned(),
+            expected: expected.to_owned(),
+            reported: reported.map(|r| r.to_owned()),
+        };
+        let checks = [
+            check("main_chip firmware_version", "D52408", Some("D52408")),
+            check("sensor_chip firmware_version", "010707", Some("010706")),
+            check("sensor_chip ai_model", "0308000000000100", None),
+        ];
+
+        let area = Rect::new(0, 0, 100, 6);
+        let mut buf = Buffer::empty(area);
+        draw_checks(&checks, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Compliance: 1/3"));
+        assert!(text.contains("010706                   MISMATCH"));
+        assert!(text.contains("-                        NOT REPORTED"));
+    }
+}
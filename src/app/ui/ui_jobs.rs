@@ -0,0 +1,137 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::normal_block,
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{tr, trf},
+        jobs::{JobRecord, JobStatus},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{List, ListItem, Widget},
+    },
+    std::time::Instant,
+};
+
+fn status_color(status: &JobStatus) -> Color {
+    match status {
+        JobStatus::Queued => Color::White,
+        JobStatus::Running => Color::Cyan,
+        JobStatus::Done => Color::Green,
+        JobStatus::Failed(_) => Color::Red,
+        JobStatus::Cancelled => Color::DarkGray,
+    }
+}
+
+/// Lists the jobs newest first, `focus` indexes that order
+fn draw_jobs(records: &[JobRecord], focus: usize, now: Instant, area: Rect, buf: &mut Buffer) {
+    let list_items: Vec<ListItem> = records
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, r)| {
+            let progress = r.progress.map(|p| format!("{p:3}%")).unwrap_or_default();
+            let mut spans = vec![
+                Span::raw(format!("#{:<4} ", r.id)),
+                Span::styled(
+                    format!("{:<10}", tr(&r.status.to_string())),
+                    Style::default().fg(status_color(&r.status)),
+                ),
+                Span::raw(format!(
+                    "{:>5}s {:>4}  {}",
+                    r.duration(now).as_secs(),
+                    progress,
+                    r.label
+                )),
+            ];
+            if let JobStatus::Failed(e) = &r.status {
+                spans.push(Span::styled(
+                    format!("  {e}"),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+
+            let line = Line::from(spans);
+            if i == focus {
+                ListItem::new(line.bold().bg(Color::DarkGray))
+            } else {
+                ListItem::new(line)
+            }
+        })
+        .collect();
+
+    let active = records.iter().filter(|r| r.is_active()).count();
+    List::new(list_items)
+        .block(normal_block(&trf(" Jobs ({0} active) ", &[&active])))
+        .render(area, buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App, focus: usize) -> Result<(), DMError> {
+    draw_jobs(&app.job_records(), focus, Instant::now(), area, buf);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_jobs() {
+        let now = Instant::now();
+        let record = |id, label: &str, status| JobRecord {
+            id,
+            label: label.to_owned(),
+            key: label.to_owned(),
+            status,
+            progress: None,
+            submitted: now,
+            started: Some(now),
+            finished: None,
+            background: false,
+        };
+        let records = vec![
+            record(1, "Report", JobStatus::Failed("disk full".to_owned())),
+            JobRecord {
+                progress: Some(40),
+                ..record(2, "Uploading fw.bin", JobStatus::Running)
+            },
+            record(3, "Scanning containers", JobStatus::Queued),
+        ];
+
+        let area = Rect::new(0, 0, 80, 10);
+        let mut buf = Buffer::empty(area);
+        draw_jobs(&records, 0, now, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Jobs (2 active)"));
+        assert!(text.contains("40%  Uploading fw.bin"));
+        assert!(text.contains("Report  disk full"));
+
+        // Newest first
+        let queued = text.find("Scanning containers").unwrap();
+        let failed = text.find("disk full").unwrap();
+        assert!(queued < failed);
+    }
+}
@@ -0,0 +1,292 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{format_device_time, normal_block},
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{format_datetime, tr},
+        mqtt_ctrl::{MqttCtrl, evp::clock::TimeZoneMode, with_mqtt_ctrl},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{Paragraph, Widget},
+    },
+};
+
+/// Height of the large characters
+pub const GLYPH_HEIGHT: usize = 5;
+
+/// 3x5 glyph of a character, '#' being a filled cell
+fn glyph(c: char) -> [&'static str; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        '0' => ["###", "# #", "# #", "# #", "###"],
+        '1' => [" # ", "## ", " # ", " # ", "###"],
+        '2' => ["###", "  #", "###", "#  ", "###"],
+        '3' => ["###", "  #", "###", "  #", "###"],
+        '4' => ["# #", "# #", "###", "  #", "  #"],
+        '5' => ["###", "#  ", "###", "  #", "###"],
+        '6' => ["###", "#  ", "###", "# #", "###"],
+        '7' => ["###", "  #", "  #", "  #", "  #"],
+        '8' => ["###", "# #", "###", "# #", "###"],
+        '9' => ["###", "# #", "###", "  #", "###"],
+        'A' => ["###", "# #", "###", "# #", "# #"],
+        'B' => ["## ", "# #", "## ", "# #", "## "],
+        'C' => ["###", "#  ", "#  ", "#  ", "###"],
+        'D' => ["## ", "# #", "# #", "# #", "## "],
+        'E' => ["###", "#  ", "###", "#  ", "###"],
+        'F' => ["###", "#  ", "###", "#  ", "#  "],
+        'G' => ["###", "#  ", "# #", "# #", "###"],
+        'H' => ["# #", "# #", "###", "# #", "# #"],
+        'I' => ["###", " # ", " # ", " # ", "###"],
+        'J' => ["  #", "  #", "  #", "# #", "###"],
+        'K' => ["# #", "# #", "## ", "# #", "# #"],
+        'L' => ["#  ", "#  ", "#  ", "#  ", "###"],
+        'M' => ["# #", "###", "###", "# #", "# #"],
+        'N' => ["## ", "# #", "# #", "# #", "# #"],
+        'O' => ["###", "# #", "# #", "# #", "###"],
+        'P' => ["###", "# #", "###", "#  ", "#  "],
+        'Q' => ["###", "# #", "# #", "###", "  #"],
+        'R' => ["## ", "# #", "## ", "# #", "# #"],
+        'S' => ["###", "#  ", "###", "  #", "###"],
+        'T' => ["###", " # ", " # ", " # ", " # "],
+        'U' => ["# #", "# #", "# #", "# #", "###"],
+        'V' => ["# #", "# #", "# #", "# #", " # "],
+        'W' => ["# #", "# #", "###", "###", "# #"],
+        'X' => ["# #", "# #", " # ", "# #", "# #"],
+        'Y' => ["# #", "# #", " # ", " # ", " # "],
+        'Z' => ["###", "  #", " # ", "#  ", "###"],
+        '.' => ["   ", "   ", "   ", "   ", " # "],
+        ',' => ["   ", "   ", "   ", " # ", "#  "],
+        ':' => ["   ", " # ", "   ", " # ", "   "],
+        '-' => ["   ", "   ", "###", "   ", "   "],
+        '_' => ["   ", "   ", "   ", "   ", "###"],
+        '/' => ["  #", "  #", " # ", "#  ", "#  "],
+        '(' => [" # ", "#  ", "#  ", "#  ", " # "],
+        ')' => [" # ", "  #", "  #", "  #", " # "],
+        ' ' => ["   ", "   ", "   ", "   ", "   "],
+        _ => ["###", "  #", " ##", "   ", " # "],
+    }
+}
+
+/// Text in large characters, one line per row with a blank column between the characters
+pub fn big_text(text: &str) -> [String; GLYPH_HEIGHT] {
+    let mut rows: [String; GLYPH_HEIGHT] = Default::default();
+    for (i, c) in text.chars().enumerate() {
+        for (row, line) in rows.iter_mut().zip(glyph(c)) {
+            if i > 0 {
+                row.push(' ');
+            }
+            row.extend(line.chars().map(|c| if c == '#' { '█' } else { ' ' }));
+        }
+    }
+    rows
+}
+
+/// Width of a text in large characters
+pub fn big_text_width(text: &str) -> usize {
+    (text.chars().count() * 4).saturating_sub(1)
+}
+
+/// Indicator of the kiosk display, the value is drawn in large characters
+#[derive(Debug, Clone, PartialEq)]
+pub struct Indicator {
+    pub label: String,
+    pub value: String,
+    /// Small line under the value, e.g. the time of the last elog
+    pub detail: Option<String>,
+    pub color: Color,
+}
+
+/// Connection, deployment and last elog on the left, firmware versions on the right
+pub fn indicators(mqtt_ctrl: &MqttCtrl, zone: TimeZoneMode) -> [Vec<Indicator>; 2] {
+    let device_offset = mqtt_ctrl.device_offset();
+    let connection = if mqtt_ctrl.is_device_connected() {
+        Indicator {
+            label: tr("Connection").to_owned(),
+            value: tr("ONLINE").to_owned(),
+            detail: None,
+            color: Color::Green,
+        }
+    } else {
+        Indicator {
+            label: tr("Connection").to_owned(),
+            value: tr("OFFLINE").to_owned(),
+            detail: Some(format!(
+                "{} {}",
+                tr("Last connected"),
+                format_datetime(&zone.convert(&mqtt_ctrl.last_connected_time(), device_offset))
+            )),
+            color: Color::Red,
+        }
+    };
+
+    let deployment = match mqtt_ctrl.deployment_status() {
+        Some(status) if !status.failures().is_empty() => Indicator {
+            label: tr("Deployment").to_owned(),
+            value: tr("FAILED").to_owned(),
+            detail: mqtt_ctrl.deployment_alert().map(|a| a.to_owned()),
+            color: Color::Red,
+        },
+        Some(status) => Indicator {
+            label: tr("Deployment").to_owned(),
+            value: status.reconcile_status().unwrap_or("-").to_owned(),
+            detail: status.deployment_id().map(|id| id.uuid().to_owned()),
+            color: Color::Green,
+        },
+        None => Indicator {
+            label: tr("Deployment").to_owned(),
+            value: "-".to_owned(),
+            detail: None,
+            color: Color::Gray,
+        },
+    };
+
+    let elog = match mqtt_ctrl.elogs().last() {
+        Some(entry) => {
+            let elog = entry.elog();
+            Indicator {
+                label: tr("Last elog").to_owned(),
+                value: elog.level_str().to_owned(),
+                detail: Some(format!(
+                    "{} {} {}",
+                    format_device_time(entry.last_timestamp(), zone, device_offset),
+                    elog.component_name().unwrap_or_default(),
+                    elog.event_str()
+                )),
+                color: match elog.level() {
+                    0..=1 => Color::Red,
+                    2 => Color::Yellow,
+                    _ => Color::White,
+                },
+            }
+        }
+        None => Indicator {
+            label: tr("Last elog").to_owned(),
+            value: "-".to_owned(),
+            detail: None,
+            color: Color::Gray,
+        },
+    };
+
+    let device_info = mqtt_ctrl.device_info();
+    let firmware =
+        |label: &str, chip: Option<&crate::mqtt_ctrl::evp::device_info::ChipInfo>| Indicator {
+            label: tr(label).to_owned(),
+            value: chip
+                .and_then(|c| c.firmware_version())
+                .unwrap_or("-")
+                .to_owned(),
+            detail: chip
+                .and_then(|c| c.update_date_firmware())
+                .filter(|d| !d.is_empty() && *d != "-")
+                .map(|d| d.to_owned()),
+            color: Color::Cyan,
+        };
+
+    [
+        vec![connection, deployment, elog],
+        vec![
+            firmware("Main firmware", device_info.and_then(|d| d.main_chip())),
+            firmware(
+                "Companion firmware",
+                device_info.and_then(|d| d.companion_chip()),
+            ),
+            firmware("Sensor firmware", device_info.and_then(|d| d.sensor_chip())),
+        ],
+    ]
+}
+
+fn draw_indicator(indicator: &Indicator, area: Rect, buf: &mut Buffer) {
+    let style = Style::default().fg(indicator.color).bold();
+    let mut lines = vec![Line::from(Span::styled(
+        indicator.label.clone(),
+        Style::default().fg(Color::Yellow),
+    ))];
+
+    // Values too wide for the area are displayed as they are
+    if big_text_width(&indicator.value) <= area.width as usize {
+        lines.extend(
+            big_text(&indicator.value)
+                .into_iter()
+                .map(|row| Line::from(Span::styled(row, style))),
+        );
+    } else {
+        lines.push(Line::from(Span::styled(indicator.value.clone(), style)));
+    }
+
+    if let Some(detail) = indicator.detail.as_ref() {
+        lines.push(Line::from(Span::styled(
+            detail.clone(),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+
+    Paragraph::new(lines).render(area, buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .margin(1)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let [left, right] = with_mqtt_ctrl(|mqtt_ctrl| indicators(mqtt_ctrl, app.time_zone));
+    for (column, indicators) in columns.iter().zip([left, right]) {
+        // Label, value and detail lines followed by a blank one
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                indicators
+                    .iter()
+                    .map(|_| Constraint::Length(GLYPH_HEIGHT as u16 + 3)),
+            )
+            .split(*column);
+        for (indicator, row) in indicators.iter().zip(rows.iter()) {
+            draw_indicator(indicator, *row, buf);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_big_text() {
+        let rows = big_text("1.0");
+        assert_eq!(rows[0], " █      ███");
+        assert_eq!(rows[4], "███  █  ███");
+        assert!(
+            rows.iter()
+                .all(|r| r.chars().count() == big_text_width("1.0"))
+        );
+
+        // Lowercase is drawn in uppercase
+        assert_eq!(big_text("ok"), big_text("OK"));
+        assert_eq!(big_text(""), <[String; GLYPH_HEIGHT]>::default());
+    }
+}
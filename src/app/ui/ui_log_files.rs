@@ -0,0 +1,78 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{
+        focus_block,
+        ui_token_provider_blobs::{BlobListState, blob_items},
+    },
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{tr, trf},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        widgets::{List, Widget},
+    },
+};
+
+/// Returns true when the blob name looks like a log file uploaded by the device,
+/// e.g. "main.log", "sensor_log.txt" or a rotated "main.log.1"
+pub fn is_log_blob(name: &str) -> bool {
+    let name = name.rsplit('/').next().unwrap_or(name).to_lowercase();
+
+    match name.rsplit_once('.') {
+        Some((_, "log" | "txt")) => true,
+        Some((stem, ext)) => {
+            stem.contains("log") && !ext.is_empty() && ext.chars().all(|c| c.is_ascii_digit())
+        }
+        None => name.contains("log"),
+    }
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, state: &BlobListState) -> Result<(), DMError> {
+    let list_items = blob_items(state, area, tr("No log files found in container"));
+    let title = trf(" Log Files in {0} ", &[&state.container_name]);
+
+    List::new(list_items)
+        .block(focus_block(&title))
+        .render(area, buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_log_blob() {
+        assert!(is_log_blob("main.log"));
+        assert!(is_log_blob("MAIN.LOG"));
+        assert!(is_log_blob("sensor_fw.txt"));
+        assert!(is_log_blob("logs/main.log.1"));
+        assert!(is_log_blob("syslog"));
+
+        assert!(!is_log_blob("image.jpg"));
+        assert!(!is_log_blob("main.log.gz"));
+        assert!(!is_log_blob("output_tensor.bin"));
+        assert!(!is_log_blob("dirlog/image.jpg"));
+    }
+}
@@ -0,0 +1,163 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{centered_rect, focus_block, normal_block, pad_width},
+    crate::{
+        app::{App, ConfigKey},
+        error::DMError,
+        i18n::tr,
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{Clear, List, ListItem, Paragraph, Widget},
+    },
+};
+
+pub use crate::mqtt_ctrl::evp::device_info::LOG_FILTERS;
+
+/// Value of a cell, with the name of the choice for the level and the destination
+fn cell_text(config_key: ConfigKey, value: &str) -> String {
+    match value
+        .parse::<usize>()
+        .ok()
+        .and_then(|i| config_key.choices().get(i))
+    {
+        Some(choice) => format!("{value}: {choice}"),
+        None => value.to_owned(),
+    }
+}
+
+/// Table of the log settings, `cells` holds the 4 values of each filter one after the other
+fn draw_table(cells: &[String], focus: usize, editable: bool, area: Rect, buf: &mut Buffer) {
+    let first = usize::from(ConfigKey::AllLogSettingLevel);
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{} {} {} {} {}",
+            pad_width(tr("Filter"), 16),
+            pad_width(tr("Level"), 18),
+            pad_width(tr("Destination"), 18),
+            pad_width(tr("Storage"), 38),
+            tr("Path")
+        ),
+        Style::default().bold(),
+    ))];
+
+    for (row, filter) in LOG_FILTERS.iter().enumerate() {
+        let mut spans = vec![Span::raw(format!("{:<16} ", filter))];
+        for (col, width) in [18, 18, 38, 0].into_iter().enumerate() {
+            let cell = row * 4 + col;
+            let config_key = ConfigKey::from(first + cell);
+            let mut text = cell_text(config_key, &cells[cell]);
+            if editable && cell == focus {
+                text.push('|');
+            }
+            let text = format!("{:<width$} ", text);
+            if cell == focus {
+                spans.push(Span::styled(
+                    text,
+                    Style::default().fg(Color::Black).bg(Color::LightCyan),
+                ));
+            } else {
+                spans.push(Span::raw(text));
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        tr("Empty cells are not sent"),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    Paragraph::new(lines)
+        .block(normal_block(" Log Settings "))
+        .render(area, buf);
+}
+
+/// Dropdown of the values of the focused level or destination
+fn draw_choices(config_key: ConfigKey, choice: usize, area: Rect, buf: &mut Buffer) {
+    let choices = config_key.choices();
+    let popup_area = centered_rect(30, 40, area);
+    Clear.render(popup_area, buf);
+
+    let items: Vec<ListItem> = choices
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let text = format!("{i}: {c}");
+            if i == choice {
+                ListItem::new(Span::styled(
+                    text,
+                    Style::default().fg(Color::Black).bg(Color::LightCyan),
+                ))
+            } else {
+                ListItem::new(text)
+            }
+        })
+        .collect();
+
+    List::new(items)
+        .block(focus_block(&format!(" {} ", config_key)))
+        .render(popup_area, buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App, choice: Option<usize>) -> Result<(), DMError> {
+    if app.config_result.is_some() {
+        return super::ui_config::draw(area, buf, app, None, None);
+    }
+
+    let first = usize::from(ConfigKey::AllLogSettingLevel);
+    let cells = &app.config_keys[first..first + LOG_FILTERS.len() * 4];
+    let focus = app.config_key_focus - first;
+    draw_table(cells, focus, app.config_key_editable, area, buf);
+
+    if let Some(choice) = choice {
+        draw_choices(ConfigKey::from(app.config_key_focus), choice, area, buf);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_table() {
+        let mut cells = vec![String::new(); LOG_FILTERS.len() * 4];
+        cells[4] = "3".to_owned();
+        cells[5] = "1".to_owned();
+        cells[7] = "logs".to_owned();
+
+        let area = Rect::new(0, 0, 140, 10);
+        let mut buf = Buffer::empty(area);
+        draw_table(&cells, 7, true, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("companion_app"));
+        assert!(text.contains("3: info"));
+        assert!(text.contains("1: cloud_storage"));
+        assert!(text.contains("logs|"));
+    }
+}
@@ -0,0 +1,244 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{focus_block, normal_block},
+    crate::{app::App, error::DMError},
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::{Line, Span},
+        widgets::{Paragraph, Widget},
+    },
+};
+
+/// Number of lines scrolled by PageUp/PageDown
+pub const PAGE_LINES: usize = 20;
+
+/// Pager state of a downloaded log file
+#[derive(Debug, Clone, Default)]
+pub struct LogViewerState {
+    pub blob_name: String,
+    pub lines: Vec<String>,
+    /// Index of the first displayed line
+    pub scroll: usize,
+    pub search: String,
+    pub search_editing: bool,
+}
+
+impl LogViewerState {
+    pub fn new(blob_name: &str, content: &[u8]) -> Self {
+        Self {
+            blob_name: blob_name.to_owned(),
+            lines: String::from_utf8_lossy(content)
+                .lines()
+                .map(|l| l.replace('\t', "    "))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn last_line(&self) -> usize {
+        self.lines.len().saturating_sub(1)
+    }
+
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+    }
+
+    pub fn scroll_down(&mut self, n: usize) {
+        self.scroll = (self.scroll + n).min(self.last_line());
+    }
+
+    pub fn scroll_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_bottom(&mut self) {
+        self.scroll = self.last_line();
+    }
+
+    /// Case insensitive match against the current search pattern
+    pub fn is_match(&self, line: &str) -> bool {
+        !self.search.is_empty() && line.to_lowercase().contains(&self.search.to_lowercase())
+    }
+
+    /// Scrolls to the next line matching the search pattern, starting from `from` and wrapping
+    /// around. Returns false when nothing matches.
+    fn search_from(&mut self, from: usize, forward: bool) -> bool {
+        let len = self.lines.len();
+
+        for i in 0..len {
+            let index = if forward {
+                (from + i) % len
+            } else {
+                (from + len - i) % len
+            };
+
+            if self.is_match(&self.lines[index]) {
+                self.scroll = index;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Jumps to the first match at or after the current line
+    pub fn search_first(&mut self) -> bool {
+        self.search_from(self.scroll, true)
+    }
+
+    pub fn search_next(&mut self) -> bool {
+        self.search_from(self.scroll + 1, true)
+    }
+
+    pub fn search_prev(&mut self) -> bool {
+        self.search_from(self.scroll + self.lines.len().saturating_sub(1), false)
+    }
+}
+
+/// Color of a log line based on the log level it contains
+pub fn level_color(line: &str) -> Color {
+    for word in line.split(|c: char| !c.is_ascii_alphabetic()) {
+        match word.to_ascii_uppercase().as_str() {
+            "CRITICAL" | "FATAL" | "ERROR" | "ERR" => return Color::Red,
+            "WARNING" | "WARN" => return Color::Yellow,
+            "INFO" => return Color::Green,
+            "DEBUG" | "DBG" | "TRACE" | "VERBOSE" => return Color::DarkGray,
+            _ => {}
+        }
+    }
+
+    Color::White
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, state: &LogViewerState) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .split(area);
+
+    let height = chunks[0].height.saturating_sub(2) as usize;
+    let width = state.lines.len().to_string().len();
+
+    let lines: Vec<Line> = state
+        .lines
+        .iter()
+        .enumerate()
+        .skip(state.scroll)
+        .take(height)
+        .map(|(i, l)| {
+            let mut style = Style::default().fg(level_color(l));
+            if state.is_match(l) {
+                style = style.bg(Color::Blue);
+            }
+
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>width$} ", i + 1, width = width),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(l.as_str(), style),
+            ])
+        })
+        .collect();
+
+    let title = format!(
+        " {} ({}/{}) ",
+        state.blob_name,
+        (state.scroll + 1).min(state.lines.len()),
+        state.lines.len()
+    );
+    Paragraph::new(lines)
+        .block(focus_block(&title))
+        .render(chunks[0], buf);
+
+    let search = if state.search_editing {
+        format!("/{}|", state.search)
+    } else {
+        format!("/{}", state.search)
+    };
+    Paragraph::new(search)
+        .block(normal_block(" Search "))
+        .render(chunks[1], buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewer() -> LogViewerState {
+        LogViewerState::new(
+            "main.log",
+            b"[INFO] boot\n[WARN] low memory\n[ERROR] failed\n[DEBUG]\tdetail\n[ERROR] again",
+        )
+    }
+
+    #[test]
+    fn test_new_splits_lines() {
+        let state = viewer();
+        assert_eq!(state.lines.len(), 5);
+        assert_eq!(state.lines[3], "[DEBUG]    detail");
+        assert_eq!(state.scroll, 0);
+    }
+
+    #[test]
+    fn test_scroll_is_clamped() {
+        let mut state = viewer();
+        state.scroll_up(3);
+        assert_eq!(state.scroll, 0);
+        state.scroll_down(PAGE_LINES);
+        assert_eq!(state.scroll, 4);
+        state.scroll_top();
+        assert_eq!(state.scroll, 0);
+        state.scroll_bottom();
+        assert_eq!(state.scroll, 4);
+    }
+
+    #[test]
+    fn test_search_wraps_around() {
+        let mut state = viewer();
+        state.search = "error".to_owned();
+
+        assert!(state.search_first());
+        assert_eq!(state.scroll, 2);
+        assert!(state.search_next());
+        assert_eq!(state.scroll, 4);
+        assert!(state.search_next());
+        assert_eq!(state.scroll, 2);
+        assert!(state.search_prev());
+        assert_eq!(state.scroll, 4);
+
+        state.search = "not-found".to_owned();
+        assert!(!state.search_next());
+        assert_eq!(state.scroll, 4);
+    }
+
+    #[test]
+    fn test_level_color() {
+        assert_eq!(level_color("2025-01-01 [ERROR] x"), Color::Red);
+        assert_eq!(level_color("W: warning: y"), Color::Yellow);
+        assert_eq!(level_color("info: z"), Color::Green);
+        assert_eq!(level_color("<debug> z"), Color::DarkGray);
+        assert_eq!(level_color("Information only"), Color::White);
+    }
+}
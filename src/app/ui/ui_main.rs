@@ -120,6 +120,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
         // Device Info
         {
             let device_info = mqtt_ctrl.device_info();
+            let alarms = mqtt_ctrl.temperature_alarms();
 
             // main_chip
             draw_chip_info(
@@ -128,6 +129,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                 device_info,
                 "main_chip",
                 get_block_type(MainWindowFocus::MainChip),
+                alarms.is_raised("main_chip"),
             )?;
 
             // companion and sensor chip shares the same display region.
@@ -140,6 +142,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                     device_info,
                     "sensor_chip",
                     get_block_type(MainWindowFocus::SensorChip),
+                    alarms.is_raised("sensor_chip"),
                 )?;
             } else {
                 // companion_chip
@@ -149,6 +152,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
                     device_info,
                     "companion_chip",
                     get_block_type(MainWindowFocus::CompanionChip),
+                    alarms.is_raised("companion_chip"),
                 )?;
             }
 
@@ -196,6 +200,10 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
             body_sub_chunks_middle[3],
             buf,
             device_states,
+            mqtt_ctrl.battery(),
+            mqtt_ctrl.clock_drift(),
+            app.time_zone,
+            mqtt_ctrl.device_offset(),
             get_block_type(MainWindowFocus::DeviceState),
         )?;
 
@@ -223,6 +231,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
             body_sub_chunks_right[1],
             buf,
             network_settings,
+            app.secrets_revealed(MainWindowFocus::NetworkSettings),
             get_block_type(MainWindowFocus::NetworkSettings),
         )?;
 
@@ -232,6 +241,7 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
             body_sub_chunks_right[2],
             buf,
             wireless_settings,
+            app.secrets_revealed(MainWindowFocus::WirelessSettings),
             get_block_type(MainWindowFocus::WirelessSettings),
         )?;
 
@@ -249,7 +259,11 @@ mod tests {
     #[should_panic]
     fn test_draw_panics_when_mqtt_uninitialized() {
         // Construct an App via the public constructor
-        let app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Prepare drawing area and buffer
         let area = Rect::new(0, 0, 100, 30);
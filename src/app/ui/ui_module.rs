@@ -14,7 +14,13 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use crate::{app::MainWindowFocus, mqtt_ctrl::with_mqtt_ctrl};
+use crate::{
+    app::MainWindowFocus,
+    mqtt_ctrl::{
+        traffic::{TopicFamily, Traffic},
+        with_mqtt_ctrl,
+    },
+};
 #[allow(unused)]
 use {
     super::*,
@@ -64,22 +70,204 @@ use {
     },
 };
 
-pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+/// Row of the message list which can be focused
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrafficRow {
+    Family(TopicFamily),
+    Topic(TopicFamily, String),
+}
+
+impl TrafficRow {
+    pub fn family(&self) -> TopicFamily {
+        match self {
+            TrafficRow::Family(family) | TrafficRow::Topic(family, _) => *family,
+        }
+    }
+}
+
+/// Rows of the message list, the topics of the collapsed families are hidden
+pub fn traffic_rows(traffic: &Traffic, collapsed: &[TopicFamily]) -> Vec<TrafficRow> {
+    let mut rows = vec![];
+    for family in TopicFamily::ALL {
+        // Only there once a hook decoded a value or something was reviewed in the dry-run
+        // mode
+        if matches!(family, TopicFamily::Hook | TopicFamily::DryRun)
+            && traffic.last_update(family).is_none()
+        {
+            continue;
+        }
+        rows.push(TrafficRow::Family(family));
+        if !collapsed.contains(&family) {
+            rows.extend(
+                traffic
+                    .topics(family)
+                    .into_iter()
+                    .map(|(topic, _)| TrafficRow::Topic(family, topic.to_owned())),
+            );
+        }
+    }
+    rows
+}
+
+/// Lines of the message list and the index of the line of the focused row
+fn traffic_lines(
+    traffic: &Traffic,
+    collapsed: &[TopicFamily],
+    focus: usize,
+) -> (Vec<Line<'static>>, usize) {
+    let mut lines = vec![];
+    let mut focus_line = 0;
+
+    for (i, row) in traffic_rows(traffic, collapsed).iter().enumerate() {
+        let style = if i == focus {
+            focus_line = lines.len();
+            Style::default().fg(Color::Black).bg(Color::White)
+        } else {
+            Style::default()
+        };
+
+        match row {
+            TrafficRow::Family(family) => {
+                let fold = if collapsed.contains(family) {
+                    "[+]"
+                } else {
+                    "[-]"
+                };
+                let last_update = traffic
+                    .last_update(*family)
+                    .map(|t| trf(", last {0}", &[&t.format("%H:%M:%S")]))
+                    .unwrap_or_default();
+                lines.push(Line::from(Span::styled(
+                    trf(
+                        "{0} {1} ({2} topics{3})",
+                        &[
+                            &fold,
+                            &family.name(),
+                            &traffic.topics(*family).len(),
+                            &last_update,
+                        ],
+                    ),
+                    style.bold(),
+                )));
+            }
+            TrafficRow::Topic(family, topic) => {
+                let Some((_, message)) = traffic
+                    .topics(*family)
+                    .into_iter()
+                    .find(|(t, _)| t == topic)
+                else {
+                    continue;
+                };
+                lines.push(Line::from(Span::styled(
+                    trf(
+                        "    {0}  ({1} messages, last {2})",
+                        &[
+                            topic,
+                            &message.count,
+                            &message.last_update.format("%H:%M:%S"),
+                        ],
+                    ),
+                    style,
+                )));
+                // Payloads which aren't JSON are displayed as they are
+                let payload: Vec<String> = match serde_json::from_str(&message.payload) {
+                    Ok(value) => JsonTree::expanded(value)
+                        .lines(false)
+                        .into_iter()
+                        .map(|line| line.to_string())
+                        .collect(),
+                    Err(_) => message.payload.lines().map(|l| l.to_owned()).collect(),
+                };
+                for line in payload {
+                    lines.push(Line::from(Span::styled(
+                        format!("        {}", line),
+                        Style::default().fg(Color::Gray),
+                    )));
+                }
+            }
+        }
+    }
+
+    (lines, focus_line)
+}
+
+fn draw_traffic(
+    area: Rect,
+    buf: &mut Buffer,
+    traffic: &Traffic,
+    collapsed: &[TopicFamily],
+    focus: usize,
+) {
+    let (lines, focus_line) = traffic_lines(traffic, collapsed, focus);
+    let height = area.height.saturating_sub(2) as usize;
+    let scroll = focus_line.saturating_sub(height / 2);
+
+    Paragraph::new(lines)
+        .scroll((scroll as u16, 0))
+        .block(normal_block(" Messages "))
+        .render(area, buf);
+}
+
+pub fn draw(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &App,
+    collapsed_families: &[TopicFamily],
+    traffic_focus: usize,
+) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let area = chunks[0];
+
     with_mqtt_ctrl(|mqtt_ctrl| -> Result<(), DMError> {
+        draw_traffic(
+            chunks[1],
+            buf,
+            mqtt_ctrl.traffic(),
+            collapsed_families,
+            traffic_focus,
+        );
+
         match app.main_window_focus {
             MainWindowFocus::MainChip => {
                 let device_info = mqtt_ctrl.device_info();
-                draw_chip_info(area, buf, device_info, "main_chip", BlockType::Normal)
+                let alarm = mqtt_ctrl.temperature_alarms().is_raised("main_chip");
+                draw_chip_info(
+                    area,
+                    buf,
+                    device_info,
+                    "main_chip",
+                    BlockType::Normal,
+                    alarm,
+                )
             }
 
             MainWindowFocus::CompanionChip => {
                 let device_info = mqtt_ctrl.device_info();
-                draw_chip_info(area, buf, device_info, "companion_chip", BlockType::Normal)
+                let alarm = mqtt_ctrl.temperature_alarms().is_raised("companion_chip");
+                draw_chip_info(
+                    area,
+                    buf,
+                    device_info,
+                    "companion_chip",
+                    BlockType::Normal,
+                    alarm,
+                )
             }
 
             MainWindowFocus::SensorChip => {
                 let device_info = mqtt_ctrl.device_info();
-                draw_chip_info(area, buf, device_info, "sensor_chip", BlockType::Normal)
+                let alarm = mqtt_ctrl.temperature_alarms().is_raised("sensor_chip");
+                draw_chip_info(
+                    area,
+                    buf,
+                    device_info,
+                    "sensor_chip",
+                    BlockType::Normal,
+                    alarm,
+                )
             }
 
             MainWindowFocus::DeviceManifest => {
@@ -110,7 +298,16 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
 
             MainWindowFocus::DeviceState => {
                 let device_states = mqtt_ctrl.device_states();
-                draw_device_states(area, buf, device_states, BlockType::Normal)
+                draw_device_states(
+                    area,
+                    buf,
+                    device_states,
+                    mqtt_ctrl.battery(),
+                    mqtt_ctrl.clock_drift(),
+                    app.time_zone,
+                    mqtt_ctrl.device_offset(),
+                    BlockType::Normal,
+                )
             }
 
             MainWindowFocus::DeviceCapabilities => {
@@ -125,12 +322,24 @@ pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
 
             MainWindowFocus::NetworkSettings => {
                 let network_settings = mqtt_ctrl.network_settings();
-                draw_network_settings(area, buf, network_settings, BlockType::Normal)
+                draw_network_settings(
+                    area,
+                    buf,
+                    network_settings,
+                    app.secrets_revealed(MainWindowFocus::NetworkSettings),
+                    BlockType::Normal,
+                )
             }
 
             MainWindowFocus::WirelessSettings => {
                 let wireless_settings = mqtt_ctrl.wireless_settings();
-                draw_wireless_settings(area, buf, wireless_settings, BlockType::Normal)
+                draw_wireless_settings(
+                    area,
+                    buf,
+                    wireless_settings,
+                    app.secrets_revealed(MainWindowFocus::WirelessSettings),
+                    BlockType::Normal,
+                )
             }
         }
     })
@@ -146,7 +355,11 @@ mod tests {
     #[should_panic]
     fn test_draw_panics_when_mqtt_uninitialized() {
         // Construct an App via the public constructor
-        let app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Prepare drawing area and buffer
         let area = Rect::new(0, 0, 80, 24);
@@ -155,4 +368,56 @@ mod tests {
         // draw() uses with_mqtt_ctrl which will panic when the global MqttCtrl is not initialized.
         let _ = draw(area, &mut buf, &app);
     }
+
+    #[test]
+    fn test_traffic_lines() {
+        let mut traffic = Traffic::default();
+        let now = Local::now();
+        traffic.record(TopicFamily::NonEvp, "sensors/1", r#"{"t":21}"#, now);
+        traffic.record(
+            TopicFamily::Server,
+            "v1/devices/me/attributes/response/1",
+            "{}",
+            now,
+        );
+
+        let rows = traffic_rows(&traffic, &[TopicFamily::Server]);
+        assert_eq!(rows.len(), 4);
+        assert!(!rows.contains(&TrafficRow::Family(TopicFamily::DryRun)));
+        assert_eq!(
+            rows[3],
+            TrafficRow::Topic(TopicFamily::NonEvp, "sensors/1".to_owned())
+        );
+
+        let (lines, focus_line) = traffic_lines(&traffic, &[TopicFamily::Server], 3);
+        let text: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(focus_line, 3);
+        assert!(text[1].starts_with("[+] Server messages (1 topics"));
+        assert!(text[3].contains("sensors/1  (1 messages"));
+        assert_eq!(text[4], "          t: 21");
+
+        // Hook values are listed once a hook decoded one
+        traffic.record(TopicFamily::Hook, "temperature", "42", now);
+        let rows = traffic_rows(&traffic, &[TopicFamily::Server]);
+        assert_eq!(rows[4], TrafficRow::Family(TopicFamily::Hook));
+        assert_eq!(
+            rows[5],
+            TrafficRow::Topic(TopicFamily::Hook, "temperature".to_owned())
+        );
+        let (lines, _) = traffic_lines(&traffic, &[TopicFamily::Server], 0);
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.to_string().contains("temperature  (1 messages"))
+        );
+
+        traffic.record(TopicFamily::DryRun, "v1/devices/me/attributes", "{}", now);
+        let rows = traffic_rows(&traffic, &[TopicFamily::Server]);
+        assert_eq!(rows[6], TrafficRow::Family(TopicFamily::DryRun));
+        let (lines, _) = traffic_lines(&traffic, &[TopicFamily::Server], 0);
+        assert!(lines.iter().any(|l| {
+            l.to_string()
+                .starts_with("[-] Dry-run messages (not sent) (1 topics")
+        }));
+    }
 }
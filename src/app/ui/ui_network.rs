@@ -0,0 +1,258 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::normal_block,
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{tr, tr_padded, trf},
+        mqtt_ctrl::{
+            evp::device_info::{NetworkSettings, WirelessSettings},
+            latency::{LatencyTracker, is_degraded, median},
+            with_mqtt_ctrl,
+        },
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{Paragraph, Sparkline, Widget},
+    },
+    std::{collections::VecDeque, time::Duration},
+};
+
+/// Usual Wi-Fi signal quality ranges
+fn signal_quality(rssi: i32) -> (&'static str, Color) {
+    match rssi {
+        r if r >= -50 => ("excellent", Color::Green),
+        r if r >= -60 => ("good", Color::Green),
+        r if r >= -70 => ("fair", Color::Yellow),
+        _ => ("weak", Color::Red),
+    }
+}
+
+pub fn wireless_lines(wireless: Option<&WirelessSettings>) -> Vec<Line<'static>> {
+    let Some(wireless) = wireless else {
+        return vec![Line::from(tr("No wireless_setting reported"))];
+    };
+
+    let mut lines = vec![];
+    if let Some(sta) = wireless.sta_mode_setting() {
+        lines.push(Line::from(format!("ssid             : {}", sta.ssid())));
+        lines.push(Line::from(format!(
+            "encryption       : {}",
+            sta.encryption()
+        )));
+    }
+    lines.push(Line::from(format!(
+        "connection_state : {}",
+        wireless.connection_state().unwrap_or("not reported")
+    )));
+    match wireless.rssi() {
+        Some(rssi) => {
+            let (quality, color) = signal_quality(rssi);
+            lines.push(Line::from(vec![
+                Span::raw(format!("rssi             : {} dBm ", rssi)),
+                Span::styled(quality, Style::default().fg(color).bold()),
+            ]));
+        }
+        None => lines.push(Line::from(format!(
+            "rssi             : {}",
+            tr("not reported")
+        ))),
+    }
+
+    lines
+}
+
+fn network_lines(network: Option<&NetworkSettings>) -> Vec<Line<'static>> {
+    let Some(network) = network else {
+        return vec![Line::from(tr("No network_settings reported"))];
+    };
+
+    let mut lines = vec![Line::from(format!(
+        "ip_method        : {}",
+        network.ip_method()
+    ))];
+    if network.ip_method() == "static" {
+        for (name, setting) in [("ipv4", network.ipv4()), ("ipv6", network.ipv6())] {
+            let Some(setting) = setting.filter(|s| !s.ip_address().is_empty()) else {
+                continue;
+            };
+            lines.push(Line::from(format!(
+                "{}             : {} mask {} gw {} dns {}",
+                name,
+                setting.ip_address(),
+                setting.subnet_mask(),
+                setting.gateway(),
+                setting.dns()
+            )));
+        }
+    }
+    lines.push(Line::from(format!(
+        "ntp_url          : {}",
+        network.ntp_url()
+    )));
+    if let Some(proxy) = network.proxy().filter(|p| !p.url().is_empty()) {
+        lines.push(Line::from(format!(
+            "proxy            : {}:{}",
+            proxy.url(),
+            proxy.port()
+        )));
+    }
+    lines.push(Line::from(format!(
+        "interface_status : {}",
+        network.interface_status().unwrap_or("not reported")
+    )));
+
+    lines
+}
+
+/// Sparkline of the latencies in milliseconds, red when the latest one is far beyond the
+/// usual ones
+fn draw_latency(name: &str, history: &VecDeque<Duration>, area: Rect, buf: &mut Buffer) {
+    let data: Vec<u64> = history.iter().map(|l| l.as_millis() as u64).collect();
+    let title = match (history.back(), median(history.iter().copied())) {
+        (Some(last), Some(usual)) => trf(
+            " {0}: {1} ms (median {2}) ",
+            &[&tr(name), &last.as_millis(), &usual.as_millis()],
+        ),
+        _ => trf(" {0}: no sample ", &[&tr(name)]),
+    };
+    let color = if is_degraded(history) {
+        Color::Red
+    } else {
+        Color::Green
+    };
+
+    let width = area.width.saturating_sub(2) as usize;
+    Sparkline::default()
+        .block(normal_block(&title))
+        .data(&data[data.len().saturating_sub(width)..])
+        .style(Style::default().fg(color))
+        .render(area, buf);
+}
+
+pub fn draw_diagnostics(
+    wireless: Option<&WirelessSettings>,
+    network: Option<&NetworkSettings>,
+    history: &VecDeque<i32>,
+    latency: &LatencyTracker,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Min(3),
+        ])
+        .split(area);
+
+    Paragraph::new(wireless_lines(wireless))
+        .block(normal_block(" Wireless "))
+        .render(chunks[0], buf);
+
+    // dBm values are shifted so that -100 dBm is the bottom of the graph
+    let data: Vec<u64> = history
+        .iter()
+        .map(|rssi| (rssi + 100).max(0) as u64)
+        .collect();
+    let title = match (history.iter().min(), history.iter().max()) {
+        (Some(min), Some(max)) => trf(
+            " Signal Strength ({0} samples, {1} .. {2} dBm) ",
+            &[&history.len(), min, max],
+        ),
+        _ => tr_padded(" Signal Strength (no sample) ").into_owned(),
+    };
+    let width = chunks[1].width.saturating_sub(2) as usize;
+    Sparkline::default()
+        .block(normal_block(&title))
+        .data(&data[data.len().saturating_sub(width)..])
+        .max(100)
+        .style(Style::default().fg(Color::Green))
+        .render(chunks[1], buf);
+
+    // Connect request answered until the first state report, RPC until its response
+    let latency_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[2]);
+    draw_latency("Connect", latency.connect(), latency_chunks[0], buf);
+    draw_latency("RPC", latency.rpc(), latency_chunks[1], buf);
+
+    Paragraph::new(network_lines(network))
+        .block(normal_block(" Network "))
+        .render(chunks[3], buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
+    with_mqtt_ctrl(|mqtt_ctrl| {
+        draw_diagnostics(
+            mqtt_ctrl.wireless_settings(),
+            mqtt_ctrl.network_settings(),
+            mqtt_ctrl.rssi_history(),
+            mqtt_ctrl.latency(),
+            area,
+            buf,
+        )
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_diagnostics() {
+        let wireless: WirelessSettings = serde_json::from_str(
+            r#"{
+                "req_info": { "req_id": "" },
+                "sta_mode_setting": { "ssid": "lab", "password": "secret", "encryption": 0 },
+                "rssi": -72,
+                "res_info": { "res_id": "", "code": 0, "detail_msg": "" }
+            }"#,
+        )
+        .unwrap();
+        let history: VecDeque<i32> = [-60, -65, -72].into_iter().collect();
+        let mut latency = LatencyTracker::default();
+        for ms in [80, 120, 100] {
+            latency.rpc_answered(Duration::from_millis(ms));
+        }
+
+        let area = Rect::new(0, 0, 80, 24);
+        let mut buf = Buffer::empty(area);
+        draw_diagnostics(Some(&wireless), None, &history, &latency, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("ssid             : lab"));
+        assert!(text.contains("rssi             : -72 dBm weak"));
+        assert!(text.contains("Signal Strength (3 samples, -72 .. -60 dBm)"));
+        assert!(text.contains("Connect: no sample"));
+        assert!(text.contains("RPC: 100 ms (median 100)"));
+        assert!(text.contains("No network_settings reported"));
+        assert!(!text.contains("secret"));
+    }
+}
@@ -0,0 +1,99 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{centered_rect, focus_block, normal_block},
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{tr_padded, trf},
+        notes::{NotesEditor, NotesField},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        widgets::{Clear, Paragraph, Widget, Wrap},
+    },
+};
+
+/// The field being typed in has the cursor and the focus border
+fn field<'a>(text: &str, title: &'a str, focused: bool) -> Paragraph<'a> {
+    if focused {
+        Paragraph::new(format!("{text}|")).block(focus_block(title))
+    } else {
+        Paragraph::new(text.to_owned()).block(normal_block(title))
+    }
+}
+
+fn draw_editor(editor: &NotesEditor, area: Rect, buf: &mut Buffer) {
+    let popup_area = centered_rect(60, 40, area);
+    Clear.render(popup_area, buf);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(popup_area);
+
+    field(
+        &editor.labels,
+        &tr_padded(" Labels (comma separated) "),
+        editor.field == NotesField::Labels,
+    )
+    .render(chunks[0], buf);
+    field(
+        &editor.note,
+        &trf(" Note of {0} ", &[&editor.key]),
+        editor.field == NotesField::Note,
+    )
+    .wrap(Wrap { trim: false })
+    .render(chunks[1], buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    if let Some(editor) = app.notes_editor.as_ref() {
+        draw_editor(editor, area, buf);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_editor() {
+        let mut editor = NotesEditor::new("dev1", None);
+        for c in "lab bench 3".chars() {
+            editor.push(c);
+        }
+        editor.toggle_field();
+        for c in "flaky".chars() {
+            editor.push(c);
+        }
+
+        let area = Rect::new(0, 0, 100, 30);
+        let mut buf = Buffer::empty(area);
+        draw_editor(&editor, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Labels (comma separated)"));
+        assert!(text.contains("lab bench 3 "));
+        assert!(text.contains("Note of dev1"));
+        assert!(text.contains("flaky|"));
+    }
+}
@@ -19,6 +19,7 @@ use {
     crate::{
         app::App,
         error::DMError,
+        i18n::{tr, tr_padded, trf},
         mqtt_ctrl::evp::ProcessState,
         mqtt_ctrl::with_mqtt_ctrl,
         ota::{ChipId, Component, FirmwareProperty, Target},
@@ -61,7 +62,11 @@ pub fn draw(area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
             .split(chunks[1]);
 
         let chips = [ChipId::MainChip, ChipId::CompanionChip, ChipId::SensorChip];
-        let titles = ["Main Chip OTA", "Companion Chip OTA", "Sensor Chip OTA"];
+        let titles = [
+            tr("Main Chip OTA"),
+            tr("Companion Chip OTA"),
+            tr("Sensor Chip OTA"),
+        ];
 
         for (i, (&chip_id, &title)) in chips.iter().zip(titles.iter()).enumerate() {
             draw_chip_section(chip_chunks[i], buf, title, chip_id, firmware)?;
@@ -83,7 +88,7 @@ fn draw_info_section(
 
     // Draw req_info section
     let req_block = Block::default()
-        .title(" Request Info ")
+        .title(tr_padded(" Request Info "))
         .borders(Borders::ALL)
         .border_set(border::PLAIN);
 
@@ -92,7 +97,8 @@ fn draw_info_section(
 
     let req_items = [
         format!(
-            "Req ID: {}",
+            "{} {}",
+            tr("Req ID:"),
             if firmware.req_info.is_none() {
                 "N/A"
             } else {
@@ -100,7 +106,8 @@ fn draw_info_section(
             }
         ),
         format!(
-            "Version: {}",
+            "{} {}",
+            tr("Version:"),
             if firmware.version.is_none() {
                 "N/A"
             } else {
@@ -120,7 +127,7 @@ fn draw_info_section(
 
     // Draw res_info section
     let res_block = Block::default()
-        .title(" Response Info ")
+        .title(tr_padded(" Response Info "))
         .borders(Borders::ALL)
         .border_set(border::PLAIN);
 
@@ -129,7 +136,8 @@ fn draw_info_section(
 
     let res_items = [
         format!(
-            "Res ID: {}",
+            "{} {}",
+            tr("Res ID:"),
             if firmware.res_info.is_none() {
                 "N/A"
             } else {
@@ -137,7 +145,8 @@ fn draw_info_section(
             }
         ),
         format!(
-            "Code: {}",
+            "{} {}",
+            tr("Code:"),
             if firmware.res_info.is_none() {
                 "N/A".to_owned()
             } else {
@@ -145,7 +154,8 @@ fn draw_info_section(
             }
         ),
         format!(
-            "Detail: {}",
+            "{} {}",
+            tr("Detail:"),
             if firmware.res_info.is_none() {
                 "N/A"
             } else {
@@ -189,16 +199,21 @@ fn draw_chip_section(
 
     // Draw loader subsection
     if let Some(loader_target) = firmware.get_target(chip_id, Component::Loader) {
-        draw_component_subsection(subsections[0], buf, " Loader ", loader_target)?;
+        draw_component_subsection(subsections[0], buf, &tr_padded(" Loader "), loader_target)?;
     } else {
-        draw_empty_component_subsection(subsections[0], buf, " Loader ")?;
+        draw_empty_component_subsection(subsections[0], buf, &tr_padded(" Loader "))?;
     }
 
     // Draw firmware subsection
     if let Some(firmware_target) = firmware.get_target(chip_id, Component::Firmware) {
-        draw_component_subsection(subsections[1], buf, " Firmware ", firmware_target)?;
+        draw_component_subsection(
+            subsections[1],
+            buf,
+            &tr_padded(" Firmware "),
+            firmware_target,
+        )?;
     } else {
-        draw_empty_component_subsection(subsections[1], buf, " Firmware ")?;
+        draw_empty_component_subsection(subsections[1], buf, &tr_padded(" Firmware "))?;
     }
 
     Ok(())
@@ -220,7 +235,8 @@ fn draw_component_subsection(
 
     let items = [
         format!(
-            "Chip: {}",
+            "{} {}",
+            tr("Chip:"),
             if target.chip.is_empty() {
                 "N/A"
             } else {
@@ -228,7 +244,8 @@ fn draw_component_subsection(
             }
         ),
         format!(
-            "Version: {}",
+            "{} {}",
+            tr("Version:"),
             if target.version.is_none() {
                 "N/A"
             } else {
@@ -236,19 +253,21 @@ fn draw_component_subsection(
             }
         ),
         format!(
-            "Progress: {}%",
+            "{} {}%",
+            tr("Progress:"),
             if target.progress.is_none() {
                 "N/A".to_string()
             } else {
                 target.progress.as_ref().unwrap().to_string()
             }
         ),
-        format!("State: {}", {
+        format!("{} {}", tr("State:"), {
             let state = target.process_state.as_ref().unwrap_or(&ProcessState::Idle);
             format_process_state(state)
         }),
         format!(
-            "URL: {}",
+            "{} {}",
+            tr("URL:"),
             if target.package_url.is_none() {
                 "N/A"
             } else {
@@ -256,18 +275,19 @@ fn draw_component_subsection(
             }
         ),
         format!(
-            "Hash: {}",
+            "{} {}",
+            tr("Hash:"),
             if target.hash.is_none() {
                 "N/A"
             } else {
                 target.hash.as_ref().unwrap()
             }
         ),
-        format!("Size: {} bytes", {
+        format!("{} {}", tr("Size:"), {
             if target.size.is_none() {
                 "N/A".to_string()
             } else {
-                target.size.as_ref().unwrap().to_string()
+                trf("{0} bytes", &[target.size.as_ref().unwrap()])
             }
         }),
     ];
@@ -297,7 +317,7 @@ fn draw_empty_component_subsection(
     let inner_area = block.inner(area);
     block.render(area, buf);
 
-    Paragraph::new("No data available")
+    Paragraph::new(tr("No data available"))
         .style(Style::default().fg(Color::DarkGray))
         .render(inner_area, buf);
 
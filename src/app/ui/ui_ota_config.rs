@@ -17,11 +17,14 @@ limitations under the License.
 #[allow(unused)]
 use {
     super::{
-        list_items_push, list_items_push_blank, list_items_push_focus, list_items_push_text_focus,
+        list_items_push, list_items_push_blank, list_items_push_focus, list_items_push_sas,
+        list_items_push_text_focus,
     },
     crate::{
         app::{App, ConfigKey, DMScreen, DMScreenState, ui::focus_block, ui::normal_block},
+        azurite::sas::SasInfo,
         error::{DMError, DMErrorExt},
+        i18n::{tr, tr_padded},
     },
     json::{JsonValue, object::Object},
     ratatui::{
@@ -70,9 +73,23 @@ pub fn draw_initial(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMErr
     }
 
     list_items_push_blank(&mut list_items);
-    list_items_push_focus(&mut list_items, "Note", "", false);
+    list_items_push_focus(&mut list_items, tr("Note"), "", false);
     let comment = ConfigKey::from(app.config_key_focus).note();
-    list_items_push_focus(&mut list_items, "  Comment", comment, false);
+    list_items_push_focus(&mut list_items, &tr_padded("  Comment"), comment, false);
+
+    let focused = ConfigKey::from(app.config_key_focus);
+    if focused.is_sas_url_entry() {
+        let url = &app.config_keys[usize::from(focused)];
+        list_items_push_sas(&mut list_items, &tr_padded("  SAS"), url);
+        if SasInfo::parse(url).is_some_and(|sas| sas.is_expired(chrono::Utc::now())) {
+            list_items_push_focus(
+                &mut list_items,
+                &tr_padded("  Hint"),
+                tr("(r) issue a new SAS URL for the same package"),
+                false,
+            );
+        }
+    }
 
     List::new(list_items).block(block).render(area, buf);
 
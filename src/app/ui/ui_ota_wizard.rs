@@ -0,0 +1,237 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{
+        focus_block, list_items_push, list_items_push_blank, list_items_push_focus,
+        list_items_push_sas, list_items_push_text_focus, normal_block, pad_width,
+    },
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{tr, trf},
+        mqtt_ctrl::with_mqtt_ctrl,
+        ota::{
+            default_chip_name,
+            wizard::{OTA_TARGETS, OtaProgress, OtaWizard, OtaWizardStep, target_name},
+        },
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::{Line, Span},
+        widgets::{Gauge, List, ListItem, Paragraph, Widget, Wrap},
+    },
+};
+
+fn draw_steps(area: Rect, buf: &mut Buffer, wizard: &OtaWizard) {
+    let mut spans = Vec::new();
+
+    for step in OtaWizardStep::all() {
+        if step.index() > 0 {
+            spans.push(Span::styled(" > ", Style::default().fg(Color::DarkGray)));
+        }
+
+        let style = if step == wizard.step {
+            Style::default().fg(Color::Black).bg(Color::Yellow)
+        } else if step.index() < wizard.step.index() {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        spans.push(Span::styled(
+            format!(" {} {} ", step.index() + 1, tr(step.title())),
+            style,
+        ));
+    }
+
+    Paragraph::new(Line::from(spans))
+        .block(normal_block(" OTA Wizard "))
+        .render(area, buf);
+}
+
+fn draw_target(area: Rect, buf: &mut Buffer, wizard: &OtaWizard) {
+    let mut list_items = Vec::<ListItem>::new();
+
+    for (i, (chip_id, component)) in OTA_TARGETS.iter().enumerate() {
+        list_items_push_text_focus(
+            &mut list_items,
+            &target_name(*chip_id, *component),
+            i == wizard.target_index,
+        );
+    }
+
+    List::new(list_items)
+        .block(focus_block(" Select Target "))
+        .render(area, buf);
+}
+
+fn draw_file(area: Rect, buf: &mut Buffer, wizard: &OtaWizard) {
+    let (chip_id, component) = wizard.target();
+    let mut list_items = Vec::<ListItem>::new();
+
+    list_items_push(
+        &mut list_items,
+        tr("Target"),
+        &target_name(chip_id, component),
+    );
+    list_items_push_focus(
+        &mut list_items,
+        tr("Firmware file"),
+        &format!("{}|", wizard.file_path),
+        true,
+    );
+    list_items_push_blank(&mut list_items);
+    list_items_push(
+        &mut list_items,
+        tr("Note"),
+        tr("the file is uploaded to the default container of Azurite"),
+    );
+
+    List::new(list_items)
+        .block(focus_block(" Upload Firmware "))
+        .render(area, buf);
+}
+
+fn draw_review(area: Rect, buf: &mut Buffer, wizard: &OtaWizard) {
+    let (chip_id, component) = wizard.target();
+    let mut list_items = Vec::<ListItem>::new();
+
+    list_items_push(
+        &mut list_items,
+        tr("Target"),
+        &target_name(chip_id, component),
+    );
+    list_items_push(
+        &mut list_items,
+        tr("Firmware file"),
+        wizard.file_path.trim(),
+    );
+
+    let version = if wizard.version_editable {
+        format!("{}|", wizard.version)
+    } else {
+        wizard.version.clone()
+    };
+    list_items_push_focus(&mut list_items, tr("Version"), &version, true);
+
+    if let Some(module) = wizard.module.as_ref() {
+        list_items_push(&mut list_items, tr("Package URL"), &module.sas_url);
+        list_items_push_sas(&mut list_items, "SAS", &module.sas_url);
+        list_items_push(&mut list_items, tr("Hash"), &module.hash_base64);
+        list_items_push(&mut list_items, tr("Size"), &module.size.to_string());
+    }
+
+    list_items_push(
+        &mut list_items,
+        tr("Detected chip"),
+        wizard.metadata.chip.map(default_chip_name).unwrap_or("N/A"),
+    );
+
+    if let Some(warning) = wizard.chip_mismatch() {
+        list_items_push_blank(&mut list_items);
+        list_items.push(ListItem::new(Span::styled(
+            trf("Warning: {0}", &[&warning]),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    List::new(list_items)
+        .block(focus_block(" Review "))
+        .render(area, buf);
+}
+
+fn draw_send(area: Rect, buf: &mut Buffer, wizard: &OtaWizard) {
+    Paragraph::new(wizard.payload.as_deref().unwrap_or_default())
+        .wrap(Wrap { trim: false })
+        .block(focus_block(" OTA Configuration "))
+        .render(area, buf);
+}
+
+fn draw_progress(area: Rect, buf: &mut Buffer, wizard: &OtaWizard) {
+    let (chip_id, component) = wizard.target();
+    let progress = with_mqtt_ctrl(|mqtt_ctrl| wizard.progress(mqtt_ctrl.firmware()));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(5),
+            Constraint::Length(3),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    let (status, color, percent) = match &progress {
+        OtaProgress::Waiting => (
+            tr("Waiting for the device to accept the request").to_owned(),
+            Color::Yellow,
+            0,
+        ),
+        OtaProgress::InProgress(state, percent) => (format!("{:?}", state), Color::White, *percent),
+        OtaProgress::Done => (tr("Done").to_owned(), Color::Green, 100),
+        OtaProgress::Failed(state) => (format!("{:?}", state), Color::Red, 0),
+    };
+
+    let lines = vec![
+        Line::from(format!(
+            "{}: {}",
+            pad_width(tr("Target"), 8),
+            target_name(chip_id, component)
+        )),
+        Line::from(format!(
+            "{}: {}",
+            pad_width(tr("Req ID"), 8),
+            wizard.req_id.as_deref().unwrap_or("N/A")
+        )),
+        Line::from(vec![
+            Span::raw(format!("{}: ", pad_width(tr("State"), 8))),
+            Span::styled(status, Style::default().fg(color)),
+        ]),
+    ];
+    Paragraph::new(lines)
+        .block(focus_block(" OTA Progress "))
+        .render(chunks[0], buf);
+
+    Gauge::default()
+        .block(normal_block(" Progress "))
+        .gauge_style(Style::default().fg(color))
+        .percent(percent.clamp(0, 100) as u16)
+        .render(chunks[1], buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let wizard = &app.ota_wizard;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(5)])
+        .split(area);
+
+    draw_steps(chunks[0], buf, wizard);
+
+    match wizard.step {
+        OtaWizardStep::Target => draw_target(chunks[1], buf, wizard),
+        OtaWizardStep::File => draw_file(chunks[1], buf, wizard),
+        OtaWizardStep::Review => draw_review(chunks[1], buf, wizard),
+        OtaWizardStep::Send => draw_send(chunks[1], buf, wizard),
+        OtaWizardStep::Progress => draw_progress(chunks[1], buf, wizard),
+    }
+
+    Ok(())
+}
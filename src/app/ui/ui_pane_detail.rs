@@ -0,0 +1,223 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{
+        centered_rect,
+        json_tree::JsonTree,
+        list_items_push_text_focus, normal_block,
+        ui_search::{flatten, pane_name},
+    },
+    crate::{
+        app::{App, MainWindowFocus},
+        error::DMError,
+        i18n::{tr, trf},
+        mqtt_ctrl::evp::device_info::mask_secrets,
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        text::Span,
+        widgets::{Clear, List, ListItem, Paragraph, Widget, Wrap},
+    },
+    serde_json::Value,
+};
+
+/// Longest key names are cut to keep the values aligned
+const KEY_WIDTH_MAX: usize = 40;
+
+/// State of the popup displaying every field of a main screen pane
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaneDetail {
+    pub focus: MainWindowFocus,
+    /// Fields of the snapshot taken when the popup was opened, as "a.b[0].c" keys
+    pub fields: Vec<(String, String)>,
+    /// The same snapshot, pretty-printed
+    pub raw: String,
+    /// The same snapshot, displayed as a tree in raw mode
+    pub tree: JsonTree,
+    pub show_raw: bool,
+    pub selected: usize,
+}
+
+impl PaneDetail {
+    pub fn new(focus: MainWindowFocus, value: Option<Value>, reveal_secrets: bool) -> Self {
+        let mut fields = vec![];
+        let mut raw = String::new();
+        let mut tree = JsonTree::default();
+        if let Some(mut value) = value {
+            if !reveal_secrets {
+                mask_secrets(&mut value);
+            }
+            flatten("", &value, &mut fields);
+            raw = serde_json::to_string_pretty(&value).unwrap_or_default();
+            tree = JsonTree::new(value);
+        }
+
+        Self {
+            focus,
+            fields,
+            raw,
+            tree,
+            show_raw: false,
+            selected: 0,
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        if self.show_raw {
+            self.tree.move_up();
+        } else {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.show_raw {
+            self.tree.move_down();
+        } else if self.selected + 1 < self.fields.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn toggle_raw(&mut self) {
+        self.show_raw = !self.show_raw;
+    }
+
+    /// What the 'y' key copies: the selected field, or the whole JSON when displayed raw
+    pub fn yank_target(&self) -> Option<(String, String)> {
+        if self.show_raw {
+            return (!self.raw.is_empty())
+                .then(|| (pane_name(self.focus).to_owned(), self.raw.clone()));
+        }
+
+        self.fields
+            .get(self.selected)
+            .map(|(key, value)| (key.clone(), value.clone()))
+    }
+}
+
+fn draw_pane_detail(detail: &PaneDetail, area: Rect, buf: &mut Buffer) {
+    let popup_area = centered_rect(90, 85, area);
+    Clear.render(popup_area, buf);
+
+    let name = tr(pane_name(detail.focus));
+    if detail.fields.is_empty() {
+        Paragraph::new(tr("No data available"))
+            .block(normal_block(&format!(" {} ", name)))
+            .render(popup_area, buf);
+        return;
+    }
+
+    if detail.show_raw {
+        let title = trf(" {0} Raw JSON ", &[&name]);
+        detail
+            .tree
+            .render(popup_area, buf, normal_block(&title), true);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(5)])
+        .split(popup_area);
+
+    let key_width = detail
+        .fields
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(KEY_WIDTH_MAX);
+
+    let height = chunks[0].height.saturating_sub(2) as usize;
+    let offset = (detail.selected + 1).saturating_sub(height);
+
+    let mut list_items = Vec::<ListItem>::new();
+    for (i, (key, value)) in detail.fields.iter().enumerate().skip(offset).take(height) {
+        let text = format!("{:<width$} : {}", key, value, width = key_width);
+        list_items_push_text_focus(&mut list_items, &text, i == detail.selected);
+    }
+
+    let title = trf(" {0} ({1} fields) ", &[&name, &detail.fields.len()]);
+    List::new(list_items)
+        .block(normal_block(&title))
+        .render(chunks[0], buf);
+
+    // Long values are cut in the list, so the selected one is also displayed in full
+    if let Some((key, value)) = detail.fields.get(detail.selected) {
+        Paragraph::new(value.as_str())
+            .wrap(Wrap { trim: false })
+            .block(normal_block(&format!(" {} ", key)))
+            .render(chunks[1], buf);
+    }
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    if let Some(detail) = app.pane_detail.as_ref() {
+        draw_pane_detail(detail, area, buf);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pane_detail() {
+        let value = serde_json::json!({
+            "proxy_settings": {"proxy_url": "proxy.local", "proxy_password": "secret"},
+            "ntp_url": "pool.ntp.org",
+        });
+        let mut detail = PaneDetail::new(MainWindowFocus::NetworkSettings, Some(value), false);
+        assert!(!detail.raw.contains("secret"));
+        assert_eq!(
+            detail.yank_target(),
+            Some(("ntp_url".to_owned(), "pool.ntp.org".to_owned()))
+        );
+
+        detail.move_down();
+        detail.move_down();
+        detail.move_down();
+        assert_eq!(detail.selected, 2);
+
+        let area = Rect::new(0, 0, 100, 30);
+        let mut buf = Buffer::empty(area);
+        draw_pane_detail(&detail, area, &mut buf);
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("NETWORK SETTINGS (3 fields)"));
+        assert!(text.contains("proxy_settings.proxy_url"));
+
+        detail.toggle_raw();
+        detail.move_down();
+        assert_eq!(
+            detail.tree.selected_path().as_deref(),
+            Some("proxy_settings")
+        );
+        let mut buf = Buffer::empty(area);
+        draw_pane_detail(&detail, area, &mut buf);
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("▾ proxy_settings: {2 keys}"));
+        assert_eq!(
+            detail.yank_target().map(|(name, _)| name),
+            Some("NETWORK SETTINGS".to_owned())
+        );
+    }
+}
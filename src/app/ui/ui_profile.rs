@@ -0,0 +1,110 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::normal_block,
+    crate::{app::App, error::DMError, i18n::trf, profile::FrameProfile},
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        text::Line,
+        widgets::{Clear, Paragraph, Widget},
+    },
+    std::time::Duration,
+};
+
+const WIDTH: u16 = 30;
+const HEIGHT: u16 = 6;
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Average and maximum time of each step over the latest frames
+fn draw_profile(profile: &FrameProfile, area: Rect, buf: &mut Buffer) {
+    let average = profile.average();
+    let max = profile.max();
+    let lines: Vec<Line> = average
+        .steps()
+        .iter()
+        .zip(max.steps())
+        .map(|((step, average), (_, max))| {
+            Line::from(format!(
+                " {:<7}{:>7.1} /{:>7.1} ms",
+                step,
+                ms(*average),
+                ms(max)
+            ))
+        })
+        .collect();
+
+    Clear.render(area, buf);
+    Paragraph::new(lines)
+        .block(normal_block(&trf(
+            " {0} frames, avg / max ",
+            &[&profile.len()],
+        )))
+        .render(area, buf);
+}
+
+/// Overlay in the top right corner, with --profile-frame
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let Some(profile) = &app.frame_profile else {
+        return Ok(());
+    };
+
+    let width = WIDTH.min(area.width);
+    let overlay = Rect::new(
+        area.x + area.width - width,
+        area.y,
+        width,
+        HEIGHT.min(area.height),
+    );
+    draw_profile(profile, overlay, buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::FrameTimes;
+
+    #[test]
+    fn test_draw_profile() {
+        let mut profile = FrameProfile::default();
+        for draw in [2, 6] {
+            profile.record(FrameTimes {
+                parse: Duration::from_micros(300),
+                update: Duration::from_millis(1),
+                draw: Duration::from_millis(draw),
+                events: Duration::from_millis(100),
+            });
+        }
+
+        let area = Rect::new(0, 0, WIDTH, HEIGHT);
+        let mut buf = Buffer::empty(area);
+        draw_profile(&profile, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("2 frames, avg / max"));
+        assert!(text.contains("parse      0.3 /    0.3 ms"));
+        assert!(text.contains("draw       4.0 /    6.0 ms"));
+        assert!(text.contains("events   100.0 /  100.0 ms"));
+    }
+}
@@ -0,0 +1,209 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{list_items_push_blank, list_items_push_focus},
+    crate::{
+        app::{App, ConfigKey, ui::normal_block},
+        error::{DMError, DMErrorExt},
+        i18n::{tr, trf},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::{Line, Span},
+        widgets::{BorderType, List, ListItem, Paragraph, Widget},
+    },
+    std::collections::VecDeque,
+};
+
+const HISTORY_SIZE: usize = 32;
+
+/// Messages published from the console, most recent first
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PublishHistory {
+    entries: VecDeque<(String, String)>,
+    /// Entry recalled into the fields
+    cursor: Option<usize>,
+}
+
+impl PublishHistory {
+    pub fn push(&mut self, topic: &str, payload: &str) {
+        let entry = (topic.to_owned(), payload.to_owned());
+        self.entries.retain(|e| *e != entry);
+        self.entries.push_front(entry);
+        self.entries.truncate(HISTORY_SIZE);
+        self.cursor = None;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(String, String)> {
+        self.entries.iter()
+    }
+
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// Recalls the message sent before the current one
+    pub fn older(&mut self) -> Option<&(String, String)> {
+        let next = self.cursor.map(|c| c + 1).unwrap_or(0);
+        if next < self.entries.len() {
+            self.cursor = Some(next);
+        }
+        self.cursor.and_then(|c| self.entries.get(c))
+    }
+
+    /// Recalls the message sent after the current one
+    pub fn newer(&mut self) -> Option<&(String, String)> {
+        self.cursor = self.cursor.map(|c| c.saturating_sub(1));
+        self.cursor.and_then(|c| self.entries.get(c))
+    }
+}
+
+/// Payloads are expected to be JSON like the messages of the device
+pub fn validate_payload(payload: &str) -> std::result::Result<(), String> {
+    serde_json::from_str::<serde_json::Value>(payload)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(8),
+            Constraint::Length(3),
+            Constraint::Min(4),
+        ])
+        .split(area);
+
+    let focus = |config_key| ConfigKey::from(app.config_key_focus) == config_key;
+    let value = |config_key| {
+        let value = app
+            .config_keys
+            .get(usize::from(config_key))
+            .map(|s| s.as_str())
+            .unwrap_or_default();
+
+        if app.config_key_editable && focus(config_key) {
+            format!("{}|", value)
+        } else {
+            value.to_string()
+        }
+    };
+
+    let mut list_items = Vec::<ListItem>::new();
+    for key in app.config_key_focus_start..=app.config_key_focus_end {
+        let config_key = ConfigKey::from(key);
+        list_items_push_focus(
+            &mut list_items,
+            config_key.to_string().as_str(),
+            &value(config_key),
+            focus(config_key),
+        );
+    }
+
+    let payload = &app.config_keys[usize::from(ConfigKey::PublishPayload)];
+    let validation = if app.config_key_editable {
+        Span::raw("")
+    } else if let Err(e) = validate_payload(payload) {
+        Span::styled(
+            trf("Not JSON: {0}", &[&e]),
+            Style::default().fg(Color::Yellow),
+        )
+    } else {
+        Span::styled(tr("Valid JSON"), Style::default().fg(Color::Green))
+    };
+
+    list_items_push_blank(&mut list_items);
+    list_items.push(ListItem::new(Line::from(validation)));
+    let comment = ConfigKey::from(app.config_key_focus).note();
+    list_items_push_focus(&mut list_items, tr("Comment"), comment, false);
+
+    let block = normal_block(" Publish Message ").border_type(BorderType::Rounded);
+    List::new(list_items).block(block).render(chunks[0], buf);
+
+    let status = match app.config_result.as_ref() {
+        Some(Ok(s)) => Span::styled(s.to_owned(), Style::default().fg(Color::Green)),
+        Some(Err(e)) => Span::styled(
+            e.error_str().unwrap_or(tr("Unknown error").to_owned()),
+            Style::default().fg(Color::Red),
+        ),
+        None => Span::raw(""),
+    };
+    Paragraph::new(Line::from(status))
+        .block(normal_block(" Result ").border_type(BorderType::Rounded))
+        .render(chunks[1], buf);
+
+    let history: Vec<ListItem> = app
+        .publish_history
+        .iter()
+        .enumerate()
+        .map(|(i, (topic, payload))| {
+            let style = if app.publish_history.cursor() == Some(i) {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(Span::styled(format!("{}  {}", topic, payload), style))
+        })
+        .collect();
+    let title = trf(" History ({0}) ", &[&app.publish_history.len()]);
+    List::new(history)
+        .block(normal_block(&title).border_type(BorderType::Rounded))
+        .render(chunks[2], buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_history() {
+        let mut history = PublishHistory::default();
+        assert!(history.older().is_none());
+
+        history.push("a", "1");
+        history.push("b", "2");
+        history.push("a", "1");
+        assert_eq!(history.len(), 2);
+
+        assert_eq!(history.older().unwrap().0, "a");
+        assert_eq!(history.older().unwrap().0, "b");
+        // Stays on the oldest entry
+        assert_eq!(history.older().unwrap().0, "b");
+        assert_eq!(history.newer().unwrap().0, "a");
+
+        history.push("c", "3");
+        assert_eq!(history.cursor(), None);
+    }
+
+    #[test]
+    fn test_validate_payload() {
+        assert!(validate_payload(r#"{"a": 1}"#).is_ok());
+        assert!(validate_payload("{a: 1}").is_err());
+        assert!(validate_payload("").is_err());
+    }
+}
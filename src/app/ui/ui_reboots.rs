@@ -0,0 +1,114 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::normal_block,
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{format_datetime, tr, trf},
+        mqtt_ctrl::{
+            reboots::{RebootTracker, format_uptime},
+            with_mqtt_ctrl,
+        },
+    },
+    chrono::{DateTime, Local},
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Stylize,
+        text::{Line, Span},
+        widgets::{List, ListItem, Paragraph, Widget},
+    },
+};
+
+fn draw_reboots(tracker: &RebootTracker, now: DateTime<Local>, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    let summary = match (tracker.boot_loop_alert(now), tracker.uptime(now)) {
+        (Some(alert), _) => Span::styled(alert, Style::default().fg(Color::Red).bold()),
+        (None, Some(uptime)) => Span::styled(
+            trf(
+                "{0} reboots this session, up for {1}",
+                &[&tracker.total(), &format_uptime(uptime)],
+            ),
+            Style::default().fg(Color::Green),
+        ),
+        (None, None) => Span::styled(
+            tr("No reboot since device-monitor started"),
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    Paragraph::new(Line::from(summary))
+        .block(normal_block(" Uptime "))
+        .render(chunks[0], buf);
+
+    let list_items: Vec<ListItem> = tracker
+        .history()
+        .iter()
+        .map(|reboot| {
+            let uptime = match reboot.uptime {
+                Some(uptime) => Span::raw(trf("up for {0}", &[&format_uptime(uptime)])),
+                None => Span::styled(
+                    trf("running for {0}", &[&format_uptime(now - reboot.time)]),
+                    Style::default().fg(Color::Green),
+                ),
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{}  ", format_datetime(&reboot.time))),
+                uptime,
+            ]))
+        })
+        .collect();
+
+    List::new(list_items)
+        .block(normal_block(" Reboot History "))
+        .render(chunks[1], buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
+    with_mqtt_ctrl(|mqtt_ctrl| draw_reboots(mqtt_ctrl.reboots(), Local::now(), area, buf));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_draw_reboots() {
+        let now = Local::now();
+        let mut tracker = RebootTracker::default();
+        tracker.record(now - Duration::hours(2));
+        tracker.record(now - Duration::minutes(5));
+
+        let area = Rect::new(0, 0, 80, 10);
+        let mut buf = Buffer::empty(area);
+        draw_reboots(&tracker, now, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("2 reboots this session, up for 00:05:00"));
+        assert!(text.contains("running for 00:05:00"));
+        assert!(text.contains("up for 01:55:00"));
+    }
+}
@@ -0,0 +1,124 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{focus_block, list_items_push_text_focus, normal_block},
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{tr, trf},
+        mqtt_ctrl::{retained::RetainedStore, with_mqtt_ctrl},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        prelude::{Color, Style},
+        text::Span,
+        widgets::{List, ListItem, Widget},
+    },
+    std::{collections::BTreeSet, time::Instant},
+};
+
+/// Longest part of a payload shown in the list
+const PAYLOAD_PREVIEW: usize = 80;
+
+pub fn draw_retained(
+    store: &RetainedStore,
+    focus: usize,
+    marked: &BTreeSet<String>,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let mut list_items = Vec::<ListItem>::new();
+
+    for (i, (topic, message)) in store.iter().enumerate() {
+        let mark = if marked.contains(topic) { "[x] " } else { "" };
+        let text = format!(
+            "{}{}  ({})",
+            mark,
+            topic,
+            trf("{0} bytes", &[&message.payload.len()])
+        );
+        list_items_push_text_focus(&mut list_items, &text, i == focus);
+
+        let preview: String = message.payload.chars().take(PAYLOAD_PREVIEW).collect();
+        list_items.push(ListItem::new(Span::styled(
+            format!("      {}", preview),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    if store.is_empty() {
+        list_items.push(ListItem::new(Span::styled(
+            tr("No retained message"),
+            Style::default().fg(Color::Green),
+        )));
+    }
+
+    let scanned = store
+        .scanned()
+        .map(|t| trf(", scanned {0}s ago", &[&t.elapsed().as_secs()]))
+        .unwrap_or_default();
+    let title = if marked.is_empty() {
+        trf(" Retained Messages ({0}{1}) ", &[&store.len(), &scanned])
+    } else {
+        trf(
+            " Retained Messages ({0}{1}, {2} marked) ",
+            &[&store.len(), &scanned, &marked.len()],
+        )
+    };
+
+    List::new(list_items)
+        .block(normal_block(&title))
+        .render(area, buf);
+}
+
+pub fn draw(
+    area: Rect,
+    buf: &mut Buffer,
+    focus: usize,
+    marked: &BTreeSet<String>,
+) -> Result<(), DMError> {
+    with_mqtt_ctrl(|mqtt_ctrl| draw_retained(mqtt_ctrl.retained(), focus, marked, area, buf));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_retained() {
+        let mut store = RetainedStore::default();
+        store.track(
+            "v1/devices/me/attributes",
+            r#"{"configuration/$system/system_settings":"{}"}"#,
+            true,
+            Instant::now(),
+        );
+        let marked = BTreeSet::from(["v1/devices/me/attributes".to_owned()]);
+
+        let area = Rect::new(0, 0, 100, 10);
+        let mut buf = Buffer::empty(area);
+        draw_retained(&store, 0, &marked, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Retained Messages (1, 1 marked)"));
+        assert!(text.contains("[x] v1/devices/me/attributes"));
+    }
+}
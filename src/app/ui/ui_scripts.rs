@@ -0,0 +1,117 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{normal_block, ui_file_browser},
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{tr, trf},
+        script::ScriptRunner,
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::{Line, Span},
+        widgets::{Block, Borders, List, ListItem, Paragraph, Widget},
+    },
+};
+
+fn draw_log(area: Rect, buf: &mut Buffer, runner: Option<&ScriptRunner>) {
+    let Some(runner) = runner else {
+        Paragraph::new(tr("Select a script to run it."))
+            .block(normal_block(" Script "))
+            .render(area, buf);
+        return;
+    };
+
+    let (status, color) = match (runner.outcome(), runner.current_line()) {
+        (Some(Ok(())), _) => (tr("PASSED").to_owned(), Color::Green),
+        (Some(Err(_)), _) => (tr("FAILED").to_owned(), Color::Red),
+        (None, Some(line)) => (trf("running line {0}", &[&line]), Color::Yellow),
+        (None, None) => (tr("running").to_owned(), Color::Yellow),
+    };
+
+    // Keep the last lines visible
+    let height = area.height.saturating_sub(2) as usize;
+    let skip = runner.log.len().saturating_sub(height);
+    let list_items: Vec<ListItem> = runner
+        .log
+        .iter()
+        .skip(skip)
+        .map(|l| {
+            let style = if l.starts_with("FAILED") {
+                Style::default().fg(Color::Red)
+            } else if l == "PASSED" {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Span::styled(l.as_str(), style))
+        })
+        .collect();
+
+    let title = Line::from(vec![
+        Span::styled(
+            trf(" Script {0}: ", &[&runner.name()]),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::styled(status, Style::default().fg(color)),
+        Span::raw(" "),
+    ]);
+    List::new(list_items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .render(area, buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+
+    if let Some(browser) = app.script_browser.as_ref() {
+        ui_file_browser::draw_browser(browser, tr("Scripts"), chunks[0], buf);
+    }
+    draw_log(chunks[1], buf, app.script_runner.as_ref());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script::Script;
+    use std::path::Path;
+
+    #[test]
+    fn test_draw_log() {
+        let script = Script::parse("bringup.dms", "log hello", Path::new(".")).unwrap();
+        let mut runner = ScriptRunner::new(script);
+        runner.cancel();
+
+        let area = Rect::new(0, 0, 60, 10);
+        let mut buf = Buffer::empty(area);
+        draw_log(area, &mut buf, Some(&runner));
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Script bringup.dms: FAILED"));
+        assert!(text.contains("FAILED at line 1: cancelled"));
+    }
+}
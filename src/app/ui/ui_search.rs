@@ -0,0 +1,510 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{centered_rect, focus_block, list_items_push_text_focus, normal_block, pad_width},
+    crate::{
+        app::{App, MainWindowFocus},
+        error::DMError,
+        i18n::{tr, trf},
+        mqtt_ctrl::{
+            MqttCtrl,
+            evp::device_info::{mask_secret, mask_secrets},
+        },
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::Span,
+        widgets::{Clear, List, ListItem, Paragraph, Widget},
+    },
+    serde::Serialize,
+    serde_json::Value,
+};
+
+/// One key/value pair of the device state and the main screen pane displaying it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchEntry {
+    pub focus: MainWindowFocus,
+    pub key: String,
+    pub value: String,
+}
+
+/// State of the search popup
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SearchState {
+    pub query: String,
+    /// Snapshot of the device state taken when the popup was opened
+    entries: Vec<SearchEntry>,
+    /// Entries matching the query, best match first
+    pub hits: Vec<SearchEntry>,
+    pub selected: usize,
+}
+
+impl SearchState {
+    pub fn new(entries: Vec<SearchEntry>) -> Self {
+        let mut state = Self {
+            entries,
+            ..Default::default()
+        };
+        state.search();
+        state
+    }
+
+    fn search(&mut self) {
+        let mut hits: Vec<(u32, &SearchEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|e| {
+                let score =
+                    fuzzy_score(&self.query, &e.key).max(fuzzy_score(&self.query, &e.value))?;
+                Some((score, e))
+            })
+            .collect();
+
+        // Stable sort keeps the pane order among the hits with the same score
+        hits.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.hits = hits.into_iter().map(|(_, e)| e.clone()).collect();
+        self.selected = 0;
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.search();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.search();
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.hits.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn selected_hit(&self) -> Option<&SearchEntry> {
+        self.hits.get(self.selected)
+    }
+}
+
+/// Scores how well `text` matches `query`, case-insensitively.
+///
+/// A substring scores higher than the characters of the query found in order with gaps,
+/// and an earlier or tighter match scores higher. Returns None if the text does not match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let query = query.to_lowercase();
+    let text = text.to_lowercase();
+
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    if let Some(pos) = text.find(&query) {
+        return Some(2000 - pos.min(999) as u32);
+    }
+
+    let mut gaps = 0u32;
+    let mut query_chars = query.chars().peekable();
+    for c in text.chars() {
+        match query_chars.peek() {
+            Some(&q) if q == c => {
+                query_chars.next();
+            }
+            Some(_) => gaps += 1,
+            None => break,
+        }
+    }
+
+    if query_chars.peek().is_some() {
+        None
+    } else {
+        Some(1000u32.saturating_sub(gaps).max(1))
+    }
+}
+
+/// Appends the leaves of a JSON value as "a.b[0].c" keys
+pub fn flatten(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(o) => {
+            for (k, v) in o {
+                let key = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", prefix, k)
+                };
+                flatten(&key, v, out);
+            }
+        }
+        Value::Array(a) => {
+            for (i, v) in a.iter().enumerate() {
+                flatten(&format!("{}[{}]", prefix, i), v, out);
+            }
+        }
+        Value::String(s) => out.push((prefix.to_owned(), s.clone())),
+        v => out.push((prefix.to_owned(), v.to_string())),
+    }
+}
+
+fn to_value<T: Serialize>(value: Option<&T>) -> Option<Value> {
+    value.and_then(|v| serde_json::to_value(v).ok())
+}
+
+fn push_json(
+    entries: &mut Vec<SearchEntry>,
+    focus: MainWindowFocus,
+    prefix: &str,
+    value: Option<Value>,
+) {
+    let mut pairs = vec![];
+    if let Some(mut value) = value {
+        mask_secrets(&mut value);
+        flatten(prefix, &value, &mut pairs);
+    }
+    entries.extend(
+        pairs
+            .into_iter()
+            .map(|(key, value)| SearchEntry { focus, key, value }),
+    );
+}
+
+fn push_pair(entries: &mut Vec<SearchEntry>, focus: MainWindowFocus, key: &str, value: &str) {
+    entries.push(SearchEntry {
+        focus,
+        key: key.to_owned(),
+        value: value.to_owned(),
+    });
+}
+
+/// Collects all key/value pairs currently reported by the device
+pub fn collect_entries(mqtt_ctrl: &MqttCtrl) -> Vec<SearchEntry> {
+    let mut entries = vec![];
+
+    if let Some(device_info) = mqtt_ctrl.device_info() {
+        for (focus, name, chip) in [
+            (
+                MainWindowFocus::MainChip,
+                "main_chip",
+                device_info.main_chip(),
+            ),
+            (
+                MainWindowFocus::CompanionChip,
+                "companion_chip",
+                device_info.companion_chip(),
+            ),
+            (
+                MainWindowFocus::SensorChip,
+                "sensor_chip",
+                device_info.sensor_chip(),
+            ),
+        ] {
+            push_json(&mut entries, focus, name, to_value(chip));
+        }
+
+        if let Some(manifest) = device_info.device_manifest() {
+            push_pair(
+                &mut entries,
+                MainWindowFocus::DeviceManifest,
+                "device_manifest",
+                manifest,
+            );
+        }
+    }
+
+    if let Some(info) = mqtt_ctrl.agent_system_info() {
+        let focus = MainWindowFocus::AgentState;
+        push_pair(&mut entries, focus, "os", info.os());
+        push_pair(&mut entries, focus, "arch", info.arch());
+        push_pair(&mut entries, focus, "evp_agent", info.evp_agent());
+        if let Some(hash) = info.evp_agent_commit_hash() {
+            push_pair(&mut entries, focus, "evp_agent_commit_hash", hash);
+        }
+        push_pair(
+            &mut entries,
+            focus,
+            "wasmMicroRuntime",
+            info.wasm_micro_runtime(),
+        );
+        push_pair(
+            &mut entries,
+            focus,
+            "protocolVersion",
+            info.protocol_version(),
+        );
+    }
+
+    if let Some(config) = mqtt_ctrl.agent_device_config() {
+        let focus = MainWindowFocus::AgentState;
+        push_pair(
+            &mut entries,
+            focus,
+            "report-status-interval-min",
+            &config.report_status_interval_min.to_string(),
+        );
+        push_pair(
+            &mut entries,
+            focus,
+            "report-status-interval-max",
+            &config.report_status_interval_max.to_string(),
+        );
+    }
+
+    if let Some(deployment_status) = mqtt_ctrl.deployment_status() {
+        push_json(
+            &mut entries,
+            MainWindowFocus::DeploymentStatus,
+            "",
+            serde_json::from_str(deployment_status.raw()).ok(),
+        );
+    }
+
+    push_json(
+        &mut entries,
+        MainWindowFocus::DeviceReserved,
+        "",
+        to_value(mqtt_ctrl.device_reserved()),
+    );
+    push_json(
+        &mut entries,
+        MainWindowFocus::DeviceState,
+        "",
+        to_value(mqtt_ctrl.device_states()),
+    );
+    push_json(
+        &mut entries,
+        MainWindowFocus::DeviceCapabilities,
+        "",
+        to_value(mqtt_ctrl.device_capabilities()),
+    );
+    push_json(
+        &mut entries,
+        MainWindowFocus::SystemSettings,
+        "",
+        to_value(mqtt_ctrl.system_settings()),
+    );
+    push_json(
+        &mut entries,
+        MainWindowFocus::NetworkSettings,
+        "",
+        to_value(mqtt_ctrl.network_settings()),
+    );
+    push_json(
+        &mut entries,
+        MainWindowFocus::WirelessSettings,
+        "",
+        to_value(mqtt_ctrl.wireless_settings()),
+    );
+
+    entries
+}
+
+/// Everything reported for a main screen pane, including the fields the pane has no room for.
+///
+/// Passwords are not masked, except the registry credentials of the agent.
+pub fn pane_json(mqtt_ctrl: &MqttCtrl, focus: MainWindowFocus) -> Option<Value> {
+    let device_info = mqtt_ctrl.device_info();
+    match focus {
+        MainWindowFocus::MainChip => to_value(device_info.and_then(|d| d.main_chip())),
+        MainWindowFocus::CompanionChip => to_value(device_info.and_then(|d| d.companion_chip())),
+        MainWindowFocus::SensorChip => to_value(device_info.and_then(|d| d.sensor_chip())),
+        MainWindowFocus::DeviceManifest => device_info.and_then(|d| d.device_manifest()).map(|m| {
+            super::decode_device_manifest(m).unwrap_or_else(|| Value::String(m.to_owned()))
+        }),
+        MainWindowFocus::AgentState => {
+            let mut map = serde_json::Map::new();
+            if let Some(info) = mqtt_ctrl.agent_system_info() {
+                map.insert("os".to_owned(), info.os().into());
+                map.insert("arch".to_owned(), info.arch().into());
+                map.insert("evp_agent".to_owned(), info.evp_agent().into());
+                if let Some(hash) = info.evp_agent_commit_hash() {
+                    map.insert("evp_agent_commit_hash".to_owned(), hash.into());
+                }
+                map.insert(
+                    "wasmMicroRuntime".to_owned(),
+                    info.wasm_micro_runtime().into(),
+                );
+                map.insert("protocolVersion".to_owned(), info.protocol_version().into());
+            }
+            if let Some(config) = mqtt_ctrl.agent_device_config() {
+                map.insert(
+                    "report-status-interval-min".to_owned(),
+                    config.report_status_interval_min.into(),
+                );
+                map.insert(
+                    "report-status-interval-max".to_owned(),
+                    config.report_status_interval_max.into(),
+                );
+                map.insert(
+                    "configuration_id".to_owned(),
+                    config.configuration_id.as_str().into(),
+                );
+                map.insert(
+                    "registry_auth".to_owned(),
+                    mask_secret(&config.registry_auth).into(),
+                );
+            }
+            (!map.is_empty()).then_some(Value::Object(map))
+        }
+        MainWindowFocus::DeploymentStatus => mqtt_ctrl
+            .deployment_status()
+            .and_then(|d| serde_json::from_str(d.raw()).ok()),
+        MainWindowFocus::DeviceReserved => to_value(mqtt_ctrl.device_reserved()),
+        MainWindowFocus::DeviceState => to_value(mqtt_ctrl.device_states()),
+        MainWindowFocus::DeviceCapabilities => to_value(mqtt_ctrl.device_capabilities()),
+        MainWindowFocus::SystemSettings => to_value(mqtt_ctrl.system_settings()),
+        MainWindowFocus::NetworkSettings => to_value(mqtt_ctrl.network_settings()),
+        MainWindowFocus::WirelessSettings => to_value(mqtt_ctrl.wireless_settings()),
+    }
+}
+
+/// Title of the main screen pane, as displayed on its border
+pub fn pane_name(focus: MainWindowFocus) -> &'static str {
+    match focus {
+        MainWindowFocus::MainChip => "MAIN CHIP",
+        MainWindowFocus::CompanionChip => "COMPANION CHIP",
+        MainWindowFocus::SensorChip => "SENSOR CHIP",
+        MainWindowFocus::DeviceManifest => "DEVICE MANIFEST",
+        MainWindowFocus::AgentState => "AGENT STATE",
+        MainWindowFocus::DeploymentStatus => "DEPLOYMENT STATUS",
+        MainWindowFocus::DeviceReserved => "DEVICE RESERVED",
+        MainWindowFocus::DeviceState => "DEVICE STATE",
+        MainWindowFocus::DeviceCapabilities => "DEVICE CAPABILITIES",
+        MainWindowFocus::SystemSettings => "SYSTEM SETTINGS",
+        MainWindowFocus::NetworkSettings => "NETWORK SETTINGS",
+        MainWindowFocus::WirelessSettings => "WIRELESS SETTINGS",
+    }
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let Some(state) = app.search.as_ref() else {
+        return Ok(());
+    };
+
+    let popup_area = centered_rect(80, 70, area);
+    Clear.render(popup_area, buf);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(popup_area);
+
+    Paragraph::new(format!("{}|", state.query))
+        .block(focus_block(" Search Device State "))
+        .render(chunks[0], buf);
+
+    let height = chunks[1].height.saturating_sub(2) as usize;
+    let offset = (state.selected + 1).saturating_sub(height);
+
+    let mut list_items = Vec::<ListItem>::new();
+    for (i, hit) in state.hits.iter().enumerate().skip(offset).take(height) {
+        let text = format!(
+            "{} {} = {}",
+            pad_width(tr(pane_name(hit.focus)), 20),
+            hit.key,
+            hit.value
+        );
+        list_items_push_text_focus(&mut list_items, &text, i == state.selected);
+    }
+
+    let title = trf(" {0} matches ", &[&state.hits.len()]);
+    List::new(list_items)
+        .block(normal_block(&title))
+        .render(chunks[1], buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert!(fuzzy_score("ntp", "ntp_url").is_some());
+        assert!(fuzzy_score("NTP", "ntp_url").is_some());
+        assert!(fuzzy_score("nurl", "ntp_url").is_some());
+        assert!(fuzzy_score("xyz", "ntp_url").is_none());
+
+        // Substring first, then the tighter match
+        assert!(fuzzy_score("url", "ntp_url") > fuzzy_score("nurl", "ntp_url"));
+        assert!(fuzzy_score("nl", "n_l") > fuzzy_score("nl", "n___l"));
+    }
+
+    #[test]
+    fn test_flatten() {
+        let value = serde_json::json!({
+            "a": {"b": 1, "c": ["x", null]},
+            "d": true,
+        });
+        let mut out = vec![];
+        flatten("", &value, &mut out);
+        assert!(out.contains(&("a.b".to_owned(), "1".to_owned())));
+        assert!(out.contains(&("a.c[0]".to_owned(), "x".to_owned())));
+        assert!(out.contains(&("a.c[1]".to_owned(), "null".to_owned())));
+        assert!(out.contains(&("d".to_owned(), "true".to_owned())));
+    }
+
+    #[test]
+    fn test_search_state() {
+        let entry = |focus, key: &str, value: &str| SearchEntry {
+            focus,
+            key: key.to_owned(),
+            value: value.to_owned(),
+        };
+        let mut state = SearchState::new(vec![
+            entry(MainWindowFocus::NetworkSettings, "ntp_url", "pool.ntp.org"),
+            entry(MainWindowFocus::WirelessSettings, "ssid", "office"),
+            entry(
+                MainWindowFocus::MainChip,
+                "main_chip.id",
+                "100A50500A2010072664012000000000",
+            ),
+        ]);
+        assert_eq!(state.hits.len(), 3);
+
+        for c in "offi".chars() {
+            state.push(c);
+        }
+        assert_eq!(state.hits.len(), 1);
+        assert_eq!(
+            state.selected_hit().map(|h| h.focus),
+            Some(MainWindowFocus::WirelessSettings)
+        );
+
+        state.pop();
+        state.pop();
+        state.pop();
+        state.pop();
+        for c in "ntp".chars() {
+            state.push(c);
+        }
+        assert_eq!(
+            state.selected_hit().map(|h| h.key.as_str()),
+            Some("ntp_url")
+        );
+        state.move_down();
+        assert_eq!(state.selected, 0);
+    }
+}
@@ -0,0 +1,202 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{focus_block, list_items_push_blank, list_items_push_focus, normal_block},
+    crate::{
+        app::{
+            App,
+            verify::{ApplyVerify, VerifyPhase},
+        },
+        error::DMError,
+        i18n::tr,
+        mqtt_ctrl::with_mqtt_ctrl,
+        provisioning::static_ip::{STATIC_IP_FIELDS, StaticIpWizard, StaticIpWizardStep},
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::{Line, Span},
+        widgets::{List, ListItem, Paragraph, Widget, Wrap},
+    },
+};
+
+fn draw_steps(area: Rect, buf: &mut Buffer, wizard: &StaticIpWizard) {
+    let mut spans = Vec::new();
+
+    for step in StaticIpWizardStep::all() {
+        if step.index() > 0 {
+            spans.push(Span::styled(" > ", Style::default().fg(Color::DarkGray)));
+        }
+
+        let style = if step == wizard.step {
+            Style::default().fg(Color::Black).bg(Color::Yellow)
+        } else if step.index() < wizard.step.index() {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        spans.push(Span::styled(
+            format!(" {} {} ", step.index() + 1, tr(step.title())),
+            style,
+        ));
+    }
+
+    Paragraph::new(Line::from(spans))
+        .block(normal_block(" Static IP Wizard "))
+        .render(area, buf);
+}
+
+fn push_messages(list_items: &mut Vec<ListItem>, messages: Vec<String>, color: Color) {
+    for message in messages {
+        list_items.push(ListItem::new(Span::styled(
+            format!("- {}", message),
+            Style::default().fg(color),
+        )));
+    }
+}
+
+fn draw_settings(area: Rect, buf: &mut Buffer, wizard: &StaticIpWizard) {
+    let mut list_items = Vec::<ListItem>::new();
+
+    for (i, field) in STATIC_IP_FIELDS.iter().enumerate() {
+        let focus = i == wizard.focus;
+        let value = match (focus, i) {
+            (true, 0) => format!("< {} >", wizard.value(i)),
+            (true, _) => format!("{}|", wizard.value(i)),
+            _ => wizard.value(i),
+        };
+        // ip_method is the name of the config key, kept as it is
+        let name = if i == 0 { *field } else { tr(field) };
+        list_items_push_focus(&mut list_items, name, &value, focus);
+    }
+
+    list_items_push_blank(&mut list_items);
+    list_items_push_focus(
+        &mut list_items,
+        tr("Note"),
+        tr("addresses may be given as CIDR, e.g. 192.168.0.10/24, IPv6 is optional"),
+        false,
+    );
+    list_items_push_blank(&mut list_items);
+
+    let errors = wizard.validate();
+    if errors.is_empty() {
+        list_items.push(ListItem::new(Span::styled(
+            tr("Ready, press (ENTER) to preview the configuration"),
+            Style::default().fg(Color::Green),
+        )));
+    }
+    push_messages(&mut list_items, errors, Color::Red);
+
+    List::new(list_items)
+        .block(focus_block(" Network Settings "))
+        .render(area, buf);
+}
+
+fn draw_preview(area: Rect, buf: &mut Buffer, wizard: &StaticIpWizard) {
+    let warnings =
+        with_mqtt_ctrl(|mqtt_ctrl| wizard.connectivity_warnings(mqtt_ctrl.network_settings()));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(warnings.len() as u16 + 2),
+            Constraint::Min(3),
+        ])
+        .split(area);
+
+    let mut list_items = Vec::<ListItem>::new();
+    push_messages(&mut list_items, warnings, Color::Yellow);
+    List::new(list_items)
+        .block(normal_block(" Connectivity "))
+        .render(chunks[0], buf);
+
+    Paragraph::new(wizard.payload.as_deref().unwrap_or_default())
+        .wrap(Wrap { trim: false })
+        .block(focus_block(" Network Configuration "))
+        .render(chunks[1], buf);
+}
+
+fn draw_verify(area: Rect, buf: &mut Buffer, verify: Option<&ApplyVerify>) {
+    let Some(verify) = verify else {
+        Paragraph::new(tr("No configuration being verified"))
+            .block(focus_block(" Verify "))
+            .render(area, buf);
+        return;
+    };
+
+    let (text, color) = match verify.phase {
+        VerifyPhase::WaitingReboot => (
+            tr("Waiting for the device to reboot").to_owned(),
+            Color::Yellow,
+        ),
+        VerifyPhase::WaitingReport => (
+            tr("Rebooted, waiting for the new settings to be reported").to_owned(),
+            Color::Yellow,
+        ),
+        VerifyPhase::Done | VerifyPhase::TimedOut => match verify.failure() {
+            Some(failure) => (failure, Color::Red),
+            None => (verify.summary(), Color::Green),
+        },
+    };
+
+    let mut list_items = vec![ListItem::new(Span::styled(
+        text,
+        Style::default().fg(color),
+    ))];
+    list_items_push_blank(&mut list_items);
+    for check in verify.checks.iter() {
+        let color = match (verify.phase, check.is_applied()) {
+            (_, true) => Color::Green,
+            (VerifyPhase::WaitingReboot, false) => Color::White,
+            (_, false) => Color::Red,
+        };
+        list_items.push(ListItem::new(Span::styled(
+            check.text(),
+            Style::default().fg(color),
+        )));
+    }
+
+    List::new(list_items)
+        .block(focus_block(" Verify (sent -> reported) "))
+        .render(area, buf);
+}
+
+pub fn draw(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &App,
+    wizard: &StaticIpWizard,
+) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(5)])
+        .split(area);
+
+    draw_steps(chunks[0], buf, wizard);
+
+    match wizard.step {
+        StaticIpWizardStep::Settings => draw_settings(chunks[1], buf, wizard),
+        StaticIpWizardStep::Preview => draw_preview(chunks[1], buf, wizard),
+        StaticIpWizardStep::Verify => draw_verify(chunks[1], buf, app.apply_verify_state()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{
+        list_items_push, list_items_push_blank, list_items_push_focus, list_items_push_text_focus,
+    },
+    crate::{
+        app::{App, ConfigKey, DMScreen, ui::normal_block},
+        azurite::with_azurite_storage,
+        device_config::ConfigKind,
+        error::{DMError, DMErrorExt},
+        i18n::{tr, tr_padded, trf},
+        mqtt_ctrl::evp::device_info::mask_secret,
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::{Line, Span},
+        widgets::{BorderType, List, ListItem, Paragraph, Widget},
+    },
+};
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(8), Constraint::Length(5)])
+        .split(area);
+
+    let block = normal_block(" Storage Settings ").border_type(BorderType::Rounded);
+
+    let focus = |config_key| ConfigKey::from(app.config_key_focus) == config_key;
+
+    let value = |config_key| {
+        let value = app
+            .config_keys
+            .get(usize::from(config_key))
+            .map(|s| s.as_str())
+            .unwrap_or_default();
+
+        if app.config_key_editable && focus(config_key) {
+            format!("{}|", value)
+        } else if config_key.kind() == ConfigKind::Secret {
+            mask_secret(value).to_owned()
+        } else {
+            value.to_string()
+        }
+    };
+
+    let mut list_items = Vec::<ListItem>::new();
+
+    for key in app.config_key_focus_start..=app.config_key_focus_end {
+        let config_key = ConfigKey::from(key);
+
+        list_items_push_focus(
+            &mut list_items,
+            config_key.to_string().as_str(),
+            &value(config_key),
+            focus(config_key),
+        );
+    }
+
+    list_items_push_blank(&mut list_items);
+    list_items_push_focus(&mut list_items, tr("Note"), "", false);
+    let comment = ConfigKey::from(app.config_key_focus).note();
+    list_items_push_focus(&mut list_items, &tr_padded("  Comment"), comment, false);
+
+    List::new(list_items).block(block).render(chunks[0], buf);
+
+    // Connectivity check result
+    let status_block = normal_block(" Connectivity ").border_type(BorderType::Rounded);
+    let status = match app.config_result.as_ref() {
+        Some(Ok(s)) => Span::styled(s.to_owned(), Style::default().fg(Color::Green)),
+        Some(Err(e)) => Span::styled(
+            trf(
+                "Failed to connect: {0}",
+                &[&e.error_str().unwrap_or_else(|| e.to_string())],
+            ),
+            Style::default().fg(Color::Red),
+        ),
+        None => {
+            if with_azurite_storage(|_| true).is_some() {
+                Span::styled(tr("Storage initialized"), Style::default().fg(Color::White))
+            } else {
+                Span::styled(tr("Storage not available"), Style::default().fg(Color::Red))
+            }
+        }
+    };
+
+    Paragraph::new(Line::from(status))
+        .block(status_block)
+        .render(chunks[1], buf);
+
+    Ok(())
+}
@@ -0,0 +1,233 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{focus_block, normal_block},
+    crate::{
+        app::{App, ConfigKey},
+        error::DMError,
+        i18n::{tr, trf},
+        mqtt_ctrl::{
+            guard::MessageGuard,
+            memory::{MemoryUsage, format_bytes},
+            subscriptions::{SubscriptionState, Subscriptions},
+            with_mqtt_ctrl,
+        },
+    },
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        style::Modifier,
+        text::{Line, Span},
+        widgets::{List, ListItem, Paragraph, Widget},
+    },
+};
+
+pub fn draw_subscriptions(
+    subscriptions: &Subscriptions,
+    focus: usize,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let list_items: Vec<ListItem> = subscriptions
+        .iter()
+        .enumerate()
+        .map(|(i, subscription)| {
+            let color = match subscription.state {
+                SubscriptionState::Active => Color::Green,
+                SubscriptionState::Failed => Color::Red,
+                _ => Color::Yellow,
+            };
+            let mut style = Style::default().fg(Color::White);
+            if i == focus {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {:<48}", subscription.filter), style),
+                Span::styled(
+                    format!(" {}", tr(&subscription.state.to_string())),
+                    Style::default().fg(color),
+                ),
+            ]))
+        })
+        .collect();
+
+    let active = subscriptions
+        .iter()
+        .filter(|s| s.state == SubscriptionState::Active)
+        .count();
+    let title = trf(
+        " Subscriptions ({0}/{1} active) ",
+        &[&active, &subscriptions.len()],
+    );
+    List::new(list_items)
+        .block(normal_block(&title))
+        .render(area, buf);
+}
+
+pub fn draw_stats(guard: &MessageGuard, area: Rect, buf: &mut Buffer) {
+    let config = guard.config();
+    let stats = guard.stats();
+    let rate_limit = if config.rate_limit == 0 {
+        tr("off").to_owned()
+    } else {
+        trf("{0}/s per topic", &[&config.rate_limit])
+    };
+    let dropped = |count: u64| {
+        let color = if count == 0 {
+            Color::White
+        } else {
+            Color::Yellow
+        };
+        Span::styled(format!("{}", count), Style::default().fg(color))
+    };
+
+    let lines = vec![
+        Line::from(trf(
+            " Received: {0}  ({1} bytes)",
+            &[&stats.received, &stats.bytes],
+        )),
+        Line::from(vec![
+            Span::raw(trf(" Too large (> {0} bytes): ", &[&config.max_payload])),
+            dropped(stats.oversized),
+        ]),
+        Line::from(vec![
+            Span::raw(trf(" Rate limited ({0}): ", &[&rate_limit])),
+            dropped(stats.rate_limited),
+        ]),
+    ];
+    Paragraph::new(lines)
+        .block(normal_block(" Message Stats "))
+        .render(area, buf);
+}
+
+/// One line per store, yellow once its oldest data has been pruned
+pub fn draw_memory(usage: &[MemoryUsage], area: Rect, buf: &mut Buffer) {
+    let lines: Vec<Line> = usage
+        .iter()
+        .map(|u| {
+            let color = if u.pruned == 0 {
+                Color::White
+            } else {
+                Color::Yellow
+            };
+            Line::from(vec![
+                Span::raw(format!(
+                    " {:<9} {:>9} / {:<9}",
+                    u.subsystem.name(),
+                    format_bytes(u.used),
+                    format_bytes(u.budget)
+                )),
+                Span::styled(trf(" {0} pruned", &[&u.pruned]), Style::default().fg(color)),
+            ])
+        })
+        .collect();
+    Paragraph::new(lines)
+        .block(normal_block(" Memory "))
+        .render(area, buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, app: &App, focus: usize) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(6),
+            Constraint::Length(3),
+        ])
+        .split(area);
+    let stats_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
+
+    with_mqtt_ctrl(|mqtt_ctrl| {
+        draw_subscriptions(mqtt_ctrl.subscriptions(), focus, chunks[0], buf);
+        draw_stats(mqtt_ctrl.guard(), stats_chunks[0], buf);
+        draw_memory(&mqtt_ctrl.memory_usage(), stats_chunks[1], buf);
+    });
+
+    let filter = &app.config_keys[usize::from(ConfigKey::SubscriptionFilter)];
+    let (block, text) = if app.config_key_editable {
+        (focus_block(" New Topic Filter "), format!("{}|", filter))
+    } else {
+        (
+            normal_block(" New Topic Filter "),
+            tr("(a) to add, e.g. v1/devices/+/telemetry").to_owned(),
+        )
+    };
+    Paragraph::new(text).block(block).render(chunks[2], buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt_ctrl::memory::Subsystem;
+
+    #[test]
+    fn test_draw_subscriptions() {
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.take_pending();
+        subscriptions.on_suback(true);
+
+        let area = Rect::new(0, 0, 80, 6);
+        let mut buf = Buffer::empty(area);
+        draw_subscriptions(&subscriptions, 0, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Subscriptions (1/2 active)"));
+        assert!(text.contains("requested"));
+    }
+
+    #[test]
+    fn test_draw_stats() {
+        let mut guard = MessageGuard::default();
+        guard.check("v1/devices/me/telemetry", 300000, std::time::Instant::now());
+
+        let area = Rect::new(0, 0, 80, 5);
+        let mut buf = Buffer::empty(area);
+        draw_stats(&guard, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Received: 1  (300000 bytes)"));
+        assert!(text.contains("Too large (> 262144 bytes): 1"));
+        assert!(text.contains("Rate limited (100/s per topic): 0"));
+    }
+
+    #[test]
+    fn test_draw_memory() {
+        let usage = [MemoryUsage {
+            subsystem: Subsystem::Traffic,
+            used: 12800,
+            budget: 8 * 1024 * 1024,
+            pruned: 3,
+        }];
+
+        let area = Rect::new(0, 0, 60, 3);
+        let mut buf = Buffer::empty(area);
+        draw_memory(&usage, area, &mut buf);
+
+        let text: String = buf.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Memory"));
+        assert!(text.contains("traffic    12.5 KiB / 8.0 MiB   3 pruned"));
+    }
+}
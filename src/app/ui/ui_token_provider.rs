@@ -19,10 +19,14 @@ use {
     super::*,
     crate::{
         app::{App, DMScreen},
-        azurite::{AzuriteStorage, TokenProvider, with_azurite_storage},
+        azurite::{
+            AzuriteStorage, TokenProvider, TokenProviderLabel, defaults::TokenProviderDefaults,
+            with_azurite_storage,
+        },
         error::DMError,
+        mqtt_ctrl::with_mqtt_ctrl,
     },
-    chrono::Local,
+    chrono::{Local, Utc},
     crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
     error_stack::{Report, Result},
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
@@ -43,7 +47,7 @@ use {
         style::Stylize,
         symbols::border,
         text::{Line, Span, Text},
-        widgets::{Block, Borders, List, ListItem, Paragraph, Widget},
+        widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Widget},
     },
     std::{
         collections::HashMap,
@@ -52,34 +56,201 @@ use {
     },
 };
 
+/// Fields of the popup creating a token provider
+pub const FORM_FIELDS: [&str; 4] = ["name", "description", "tags", "container"];
+
+/// Input of the popup creating a token provider
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TokenProviderForm {
+    pub values: [String; FORM_FIELDS.len()],
+    pub focus: usize,
+}
+
+impl TokenProviderForm {
+    pub fn push(&mut self, c: char) {
+        self.values[self.focus].push(c);
+    }
+
+    pub fn pop(&mut self) {
+        self.values[self.focus].pop();
+    }
+
+    pub fn focus_up(&mut self) {
+        self.focus = self.focus.checked_sub(1).unwrap_or(FORM_FIELDS.len() - 1);
+    }
+
+    pub fn focus_down(&mut self) {
+        self.focus = (self.focus + 1) % FORM_FIELDS.len();
+    }
+
+    /// Label of the token provider, tags are comma separated
+    pub fn label(&self) -> TokenProviderLabel {
+        TokenProviderLabel {
+            name: self.values[0].trim().to_owned(),
+            description: self.values[1].trim().to_owned(),
+            tags: TokenProviderLabel::parse_tags(&self.values[2]),
+        }
+    }
+
+    /// Custom name of the upload container, None for the generated one
+    pub fn container(&self) -> Option<&str> {
+        Some(self.values[3].trim()).filter(|c| !c.is_empty())
+    }
+}
+
 fn do_list_token_providers(
     azure_storage: &AzuriteStorage,
+    defaults: &TokenProviderDefaults,
     area: Rect,
     buf: &mut Buffer,
 ) -> Result<(), DMError> {
     let mut list_items = Vec::<ListItem>::new();
-    let token_providers_db = azure_storage.token_providers();
-    let mut no = 1;
-    for (id, (uuid, token_provider)) in token_providers_db.iter().enumerate() {
+    for (id, (uuid, token_provider)) in azure_storage.filtered_token_providers().enumerate() {
         let focus = id == azure_storage.current_token_provider_id();
-        let text = format!("No{:2}  UUID: {}", no, uuid.uuid(),);
+        let label = &token_provider.label;
+        let text = if label.name.is_empty() {
+            format!("No{:2}  UUID: {}", id + 1, uuid.uuid())
+        } else {
+            format!("No{:2}  UUID: {}  ({})", id + 1, uuid.uuid(), label.name)
+        };
         list_items_push_text_focus(&mut list_items, &text, focus);
 
-        let text = format!("       Container: {}", token_provider.container);
+        let purposes = defaults.purposes_of(uuid);
+        if !purposes.is_empty() {
+            let names: Vec<&str> = purposes.iter().map(|p| tr(p.name())).collect();
+            let text = format!("     * {}", trf("Default for {0}", &[&names.join(", ")]));
+            list_items_push_text_focus(&mut list_items, &text, focus);
+        }
+
+        let text = format!("       {} {}", tr("Container:"), token_provider.container);
         list_items_push_text_focus(&mut list_items, &text, focus);
-        no += 1;
+
+        if !label.description.is_empty() {
+            let text = format!("       {} {}", tr("Description:"), label.description);
+            list_items_push_text_focus(&mut list_items, &text, focus);
+        }
+
+        if !label.tags.is_empty() {
+            let text = format!("       {} {}", tr("Tags:"), label.tags.join(", "));
+            list_items_push_text_focus(&mut list_items, &text, focus);
+        }
     }
 
-    let title = " Token Providers ";
-    let block = normal_block(title);
+    let filter = azure_storage.token_provider_filter();
+    let title = if filter.is_empty() {
+        tr_padded(" Token Providers ").into_owned()
+    } else {
+        trf(
+            " Token Providers ({0}/{1} matching '{2}') ",
+            &[
+                &azure_storage.filtered_token_providers().count(),
+                &azure_storage.token_providers().len(),
+                &filter,
+            ],
+        )
+    };
+    let block = normal_block(&title);
 
     List::new(list_items).block(block).render(area, buf);
     Ok(())
 }
 
-pub fn draw(area: Rect, buf: &mut Buffer, _app: &App) -> Result<(), DMError> {
-    with_azurite_storage(|azure_storage| do_list_token_providers(azure_storage, area, buf))
-        .unwrap_or(Ok(()))?;
+fn draw_form(form: &TokenProviderForm, area: Rect, buf: &mut Buffer) {
+    let popup_area = centered_rect(70, 40, area);
+    Clear.render(popup_area, buf);
+
+    let mut list_items = Vec::<ListItem>::new();
+    for (i, field) in FORM_FIELDS.iter().enumerate() {
+        let focus = i == form.focus;
+        let value = if focus {
+            format!("{}|", form.values[i])
+        } else {
+            form.values[i].clone()
+        };
+        list_items_push_focus(&mut list_items, tr(field), &value, focus);
+    }
+
+    list_items_push_blank(&mut list_items);
+    let note = match form.focus {
+        2 => tr("comma separated, e.g. logs,line-a"),
+        3 => tr("empty: upload-<UUID>, else 3-63 of a-z, 0-9 and '-'"),
+        _ => tr("optional, printable ASCII only"),
+    };
+    list_items_push_focus(&mut list_items, &tr_padded("  Comment"), note, false);
+
+    List::new(list_items)
+        .block(focus_block(" New Token Provider "))
+        .render(popup_area, buf);
+}
+
+fn draw_issued_sas(area: Rect, buf: &mut Buffer) {
+    let now = Utc::now();
+    let list_items: Vec<ListItem> = with_mqtt_ctrl(|mqtt_ctrl| {
+        mqtt_ctrl
+            .issued_sas()
+            .iter()
+            .rev()
+            .map(|issued| {
+                let text = format!(
+                    "{}  {}/{}  {}",
+                    issued.time.format("%H:%M:%S"),
+                    issued.container,
+                    issued.blob,
+                    issued.sas.summary(now)
+                );
+                let style = if issued.sas.is_expired(now) {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Span::styled(text, style))
+            })
+            .collect()
+    });
+
+    List::new(list_items)
+        .block(normal_block(" Storage Tokens "))
+        .render(area, buf);
+}
+
+pub fn draw(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &App,
+    form: Option<&TokenProviderForm>,
+) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(8),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    with_azurite_storage(|azure_storage| {
+        do_list_token_providers(azure_storage, app.token_provider_defaults(), chunks[0], buf)
+    })
+    .unwrap_or(Ok(()))?;
+
+    draw_issued_sas(chunks[1], buf);
+
+    let filter =
+        with_azurite_storage(|azure_storage| azure_storage.token_provider_filter().to_owned())
+            .unwrap_or_default();
+    let (block, text) = if app.config_key_editable {
+        (focus_block(" Filter "), format!("{}|", filter))
+    } else {
+        (
+            normal_block(" Filter "),
+            tr("(f) to filter by name, description, tag or container").to_owned(),
+        )
+    };
+    Paragraph::new(text).block(block).render(chunks[2], buf);
+
+    if let Some(form) = form {
+        draw_form(form, area, buf);
+    }
 
     Ok(())
 }
@@ -94,7 +265,11 @@ mod tests {
     #[should_panic]
     fn test_draw_without_azurite_storage_returns_ok() {
         // Creating App via public constructor
-        let app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
 
         // Prepare drawing area and buffer
         let area = Rect::new(0, 0, 40, 12);
@@ -110,11 +285,37 @@ mod tests {
     fn test_do_list_token_providers_handles_empty_db() {
         // We can't easily initialize a full AzuriteStorage here. draw() uses with_azurite_storage
         // which will panic when the global storage has not been initialized in the test env.
-        let app = crate::app::App::new(crate::app::AppConfig { broker: "b" }).unwrap();
+        let app = crate::app::App::new(crate::app::AppConfig {
+            broker: "b",
+            ..Default::default()
+        })
+        .unwrap();
         let area = Rect::new(0, 0, 40, 12);
         let mut buf = Buffer::empty(area);
 
         // Expect a panic due to missing global AzuriteStorage.
         let _ = draw(area, &mut buf, &app);
     }
+
+    #[test]
+    fn test_token_provider_form() {
+        let mut form = TokenProviderForm::default();
+        "bench".chars().for_each(|c| form.push(c));
+        form.focus_down();
+        form.focus_down();
+        " logs, line-a ,".chars().for_each(|c| form.push(c));
+        form.focus_up();
+        form.focus_up();
+        form.focus_up();
+        assert_eq!(form.focus, 3);
+        assert!(form.container().is_none());
+        "line-a-x".chars().for_each(|c| form.push(c));
+        form.pop();
+        form.pop();
+
+        let label = form.label();
+        assert_eq!(label.name, "bench");
+        assert_eq!(label.tags, vec!["logs", "line-a"]);
+        assert_eq!(form.container(), Some("line-a"));
+    }
 }
@@ -19,7 +19,7 @@ use {
     super::*,
     crate::{
         app::{App, DMScreen},
-        azurite::{UiBlob, with_azurite_storage},
+        azurite::{BlobSort, UiBlob, blob_name_matches, with_azurite_storage},
         error::DMError,
     },
     chrono::Local,
@@ -46,17 +46,60 @@ use {
         widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
     },
     std::{
-        collections::HashMap,
+        collections::{BTreeSet, HashMap},
         io,
+        ops::Range,
         time::{Duration, Instant},
     },
 };
 
+/// Rows left below the selection when the next page of blobs is listed
+pub const PREFETCH_ROWS: usize = 20;
+
+/// Status of a blob download queued from the blobs screen
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlobDownload {
+    Queued,
+    /// Percent done
+    Running(u8),
+    /// Local path of the downloaded file
+    Done(String),
+    Failed(String),
+}
+
+impl BlobDownload {
+    pub fn is_active(&self) -> bool {
+        matches!(self, BlobDownload::Queued | BlobDownload::Running(_))
+    }
+
+    pub fn text(&self) -> String {
+        match self {
+            BlobDownload::Queued => tr("queued").to_owned(),
+            BlobDownload::Running(percent) => format!("{}%", percent),
+            BlobDownload::Done(_) => tr("downloaded").to_owned(),
+            BlobDownload::Failed(_) => tr("failed").to_owned(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BlobListState {
+    /// Listed blobs, sorted by `sort`
     pub blobs: Vec<UiBlob>,
+    /// Index of the selected blob among the ones matching the filter
     pub selected_index: usize,
     pub container_name: String,
+    /// Continuation token of the next page, None once the whole container is listed
+    pub next_marker: Option<String>,
+    /// The next page is being listed
+    pub loading: bool,
+    /// Only the blobs whose name matches it are shown
+    pub filter: String,
+    pub sort: BlobSort,
+    /// Blobs marked to be downloaded together
+    pub marked: BTreeSet<String>,
+    /// Downloads queued from the screen, keyed by blob name
+    pub downloads: HashMap<String, BlobDownload>,
 }
 
 impl BlobListState {
@@ -65,19 +108,86 @@ impl BlobListState {
             blobs: Vec::new(),
             selected_index: 0,
             container_name,
+            next_marker: None,
+            loading: false,
+            filter: String::new(),
+            sort: BlobSort::default(),
+            marked: BTreeSet::new(),
+            downloads: HashMap::new(),
+        }
+    }
+
+    /// Sorts the blobs, the selected blob stays selected
+    fn sort_blobs(&mut self) {
+        let selected = self.current_blob().map(|b| b.name.clone());
+        let sort = self.sort;
+        self.blobs.sort_by(|a, b| {
+            sort.compare(
+                (&a.name, a.size, Some(a.created_on)),
+                (&b.name, b.size, Some(b.created_on)),
+            )
+        });
+        if let Some(name) = selected {
+            self.selected_index = self
+                .shown()
+                .iter()
+                .position(|b| b.name == name)
+                .unwrap_or(0);
         }
     }
 
+    /// Adds a listed page. The selected blob stays selected.
+    pub fn append(&mut self, blobs: Vec<UiBlob>, next_marker: Option<String>) {
+        self.blobs.extend(blobs);
+        self.sort_blobs();
+        self.next_marker = next_marker;
+        self.loading = false;
+    }
+
+    /// Sorts the blobs by the next column
+    pub fn sort_by_next(&mut self) {
+        self.sort = self.sort.next();
+        self.sort_blobs();
+    }
+
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_owned();
+        self.selected_index = 0;
+    }
+
+    /// Blobs matching the filter
+    pub fn shown(&self) -> Vec<&UiBlob> {
+        self.blobs
+            .iter()
+            .filter(|b| blob_name_matches(&b.name, &self.filter))
+            .collect()
+    }
+
+    /// Continuation token of the page to list, once the selection nears the end of the
+    /// blobs matching the filter
+    pub fn next_page(&self) -> Option<&str> {
+        if self.loading || self.selected_index + PREFETCH_ROWS < self.shown().len() {
+            return None;
+        }
+        self.next_marker.as_deref()
+    }
+
+    /// Blobs shown in `height` rows, scrolled so that the selected one is visible
+    pub fn visible(&self, height: usize) -> Range<usize> {
+        let first = (self.selected_index + 1).saturating_sub(height);
+        first..(first + height).min(self.shown().len())
+    }
+
     pub fn move_up(&mut self) {
         if self.selected_index == 0 {
-            self.selected_index = self.blobs.len().saturating_sub(1);
+            self.selected_index = self.shown().len().saturating_sub(1);
         } else {
             self.selected_index = self.selected_index.saturating_sub(1);
         }
     }
 
     pub fn move_down(&mut self) {
-        if self.selected_index >= self.blobs.len().saturating_sub(1) {
+        if self.selected_index >= self.shown().len().saturating_sub(1) {
             self.selected_index = 0;
         } else {
             self.selected_index += 1;
@@ -85,11 +195,56 @@ impl BlobListState {
     }
 
     pub fn current_blob(&self) -> Option<&UiBlob> {
-        self.blobs.get(self.selected_index)
+        self.shown().get(self.selected_index).copied()
+    }
+
+    /// Marks or unmarks the selected blob and moves to the next one
+    pub fn toggle_mark(&mut self) {
+        if let Some(name) = self.current_blob().map(|b| b.name.clone()) {
+            if !self.marked.remove(&name) {
+                self.marked.insert(name);
+            }
+            self.move_down();
+        }
+    }
+
+    /// Blobs to download, the marked ones in the listed order or else the selected one.
+    /// The marks are cleared.
+    pub fn take_downloads(&mut self) -> Vec<String> {
+        let blobs: Vec<String> = if self.marked.is_empty() {
+            self.current_blob()
+                .map(|b| b.name.clone())
+                .into_iter()
+                .collect()
+        } else {
+            self.blobs
+                .iter()
+                .filter(|b| self.marked.contains(&b.name))
+                .map(|b| b.name.clone())
+                .collect()
+        };
+        self.marked.clear();
+        blobs
+    }
+
+    /// e.g. "2 running, 3 queued, 1 failed", empty without downloads
+    pub fn downloads_summary(&self) -> String {
+        let count = |f: fn(&BlobDownload) -> bool| self.downloads.values().filter(|d| f(d)).count();
+        [
+            (count(|d| matches!(d, BlobDownload::Running(_))), "running"),
+            (count(|d| *d == BlobDownload::Queued), "queued"),
+            (count(|d| matches!(d, BlobDownload::Done(_))), "downloaded"),
+            (count(|d| matches!(d, BlobDownload::Failed(_))), "failed"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, what)| format!("{} {}", n, tr(what)))
+        .collect::<Vec<String>>()
+        .join(", ")
     }
 }
 
-fn format_file_size(size: u64) -> String {
+pub fn format_file_size(size: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     let mut size = size as f64;
     let mut unit_index = 0;
@@ -106,77 +261,171 @@ fn format_file_size(size: u64) -> String {
     }
 }
 
-fn do_list_blobs(blob_state: &BlobListState, area: Rect, buf: &mut Buffer) -> Result<(), DMError> {
+/// Rows of the blobs visible in the list block, followed by the listing status of the
+/// next page. Only the visible rows are built, containers can have thousands of blobs.
+pub fn blob_items(
+    blob_state: &BlobListState,
+    area: Rect,
+    empty: &'static str,
+) -> Vec<ListItem<'static>> {
+    let status = if blob_state.loading {
+        Some((tr("Loading more blobs..."), Color::Yellow))
+    } else if blob_state.next_marker.is_some() {
+        Some((
+            tr("More blobs in the container, scroll down to list them"),
+            Color::Gray,
+        ))
+    } else {
+        None
+    };
+
+    // Subtract 2 for border
+    let height = (area.height.saturating_sub(2) as usize).saturating_sub(status.is_some() as usize);
     let mut list_items = Vec::<ListItem>::new();
 
-    for (index, blob) in blob_state.blobs.iter().enumerate() {
+    let shown = blob_state.shown();
+    for index in blob_state.visible(height) {
+        let blob = shown[index];
         let focus = index == blob_state.selected_index;
 
         // Format: "001  blob_name.txt  2025-08-12T14:03:00Z  1.2 KB"
-        let created_str = blob.created_on.to_rfc3339();
-        let size_str = format_file_size(blob.size);
+        let mark = if blob_state.marked.contains(&blob.name) {
+            "[x] "
+        } else {
+            ""
+        };
+        let status = blob_state
+            .downloads
+            .get(&blob.name)
+            .map(|d| format!("  [{}]", d.text()))
+            .unwrap_or_default();
         let text = format!(
-            "{:3}  {}  {}  {}",
+            "{:3}  {}{}  {}  {}{}",
             index + 1,
+            mark,
             blob.name,
-            created_str,
-            size_str
+            blob.created_on.to_rfc3339(),
+            format_file_size(blob.size),
+            status
         );
 
         list_items_push_text_focus(&mut list_items, &text, focus);
     }
 
-    if list_items.is_empty() {
+    if list_items.is_empty() && status.is_none() {
         list_items.push(ListItem::new(Span::styled(
-            "No blobs found in container",
+            empty,
             Style::default().fg(Color::Gray),
         )));
     }
 
-    let title = format!(" Blobs in {} ", blob_state.container_name);
-    let block = focus_block(&title);
-
-    // Create a mutable ListState to handle scrolling
-    let mut list_state = ratatui::widgets::ListState::default();
-    list_state.select(Some(blob_state.selected_index));
-
-    // Calculate the visible area for scrolling
-    let list_height = area.height.saturating_sub(2) as usize; // Subtract 2 for border
-    let total_items = blob_state.blobs.len();
+    if let Some((text, color)) = status {
+        list_items.push(ListItem::new(Span::styled(
+            text,
+            Style::default().fg(color),
+        )));
+    }
 
-    // Only show scroll position if there are items and list is scrollable
-    if !blob_state.blobs.is_empty() && total_items > list_height {
-        let selected = blob_state.selected_index;
+    list_items
+}
 
-        // Calculate scroll offset to keep selected item visible
-        let scroll_offset = if selected >= list_height { 1 } else { 0 };
+fn do_list_blobs(blob_state: &BlobListState, area: Rect, buf: &mut Buffer) -> Result<(), DMError> {
+    let list_items = blob_items(blob_state, area, tr("No blobs found in container"));
+
+    let mut title = blob_title(tr("Blobs"), blob_state);
+    if !blob_state.marked.is_empty() {
+        title = format!(
+            "{}{}",
+            title,
+            trf("({0} marked) ", &[&blob_state.marked.len()])
+        );
+    }
+    List::new(list_items)
+        .block(focus_block(&title))
+        .render(area, buf);
+    Ok(())
+}
 
-        // Ensure we don't scroll past the end
-        let max_scroll = total_items.saturating_sub(list_height);
-        let scroll_offset = scroll_offset.min(max_scroll);
+/// Title of the blob list, with the column it is sorted by
+pub fn blob_title(what: &str, blob_state: &BlobListState) -> String {
+    trf(
+        " {0} in {1} (by {2}) ",
+        &[
+            &what,
+            &blob_state.container_name,
+            &tr(blob_state.sort.name()),
+        ],
+    )
+}
 
-        *list_state.offset_mut() = scroll_offset;
-    }
+/// Directory the blobs are downloaded to, `dir_input` while it is typed, and the status of
+/// the downloads
+fn draw_download_dir(
+    blob_state: &BlobListState,
+    dir: Option<&str>,
+    dir_input: Option<&str>,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let (block, text) = match dir_input {
+        Some(input) => (
+            focus_block(" Download To "),
+            format!("{}|  {}", input, tr("(empty for the current directory)")),
+        ),
+        None => {
+            let summary = blob_state.downloads_summary();
+            let summary = if summary.is_empty() {
+                String::new()
+            } else {
+                format!("  {}", trf("Downloads: {0}", &[&summary]))
+            };
+            (
+                normal_block(" Download To "),
+                format!(
+                    "{}  {}{}",
+                    dir.unwrap_or(tr("current directory")),
+                    tr("((c) to change)"),
+                    summary
+                ),
+            )
+        }
+    };
+    Paragraph::new(text).block(block).render(area, buf);
+}
 
-    ratatui::widgets::StatefulWidget::render(
-        List::new(list_items)
-            .block(block)
-            .highlight_style(Style::default()),
-        area,
+pub fn draw(
+    area: Rect,
+    buf: &mut Buffer,
+    blob_state: &BlobListState,
+    filter_editing: bool,
+    download_dir: Option<&str>,
+    download_dir_input: Option<&str>,
+) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    draw_filter(
+        &blob_state.filter,
+        filter_editing,
+        blob_state.shown().len(),
+        chunks[1],
         buf,
-        &mut list_state,
     );
-    Ok(())
-}
-
-pub fn draw(area: Rect, buf: &mut Buffer, blob_state: &BlobListState) -> Result<(), DMError> {
-    do_list_blobs(blob_state, area, buf)
+    draw_download_dir(blob_state, download_dir, download_dir_input, chunks[2], buf);
+    do_list_blobs(blob_state, chunks[0], buf)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Utc;
+    use ratatui::{buffer::Buffer, layout::Rect};
 
     #[test]
     fn test_blob_list_state_new() {
@@ -215,4 +464,191 @@ mod tests {
         assert!(state.current_blob().is_some());
         assert_eq!(state.current_blob().unwrap().name, "test.txt");
     }
+
+    #[test]
+    fn test_blob_list_state_navigation() {
+        let mut state = BlobListState::new("test-container".to_string());
+
+        // Test with empty list
+        assert_eq!(state.selected_index, 0);
+        state.move_up();
+        assert_eq!(state.selected_index, 0);
+        state.move_down();
+        assert_eq!(state.selected_index, 0);
+
+        // Add some test blobs
+        state.blobs = vec![
+            UiBlob {
+                name: "blob1.txt".to_string(),
+                created_on: chrono::Utc::now(),
+                size: 100,
+            },
+            UiBlob {
+                name: "blob2.txt".to_string(),
+                created_on: chrono::Utc::now(),
+                size: 200,
+            },
+            UiBlob {
+                name: "blob3.txt".to_string(),
+                created_on: chrono::Utc::now(),
+                size: 300,
+            },
+        ];
+
+        // Test navigation
+        assert_eq!(state.selected_index, 0);
+        state.move_down();
+        assert_eq!(state.selected_index, 1);
+        state.move_down();
+        assert_eq!(state.selected_index, 2);
+        state.move_down(); // Should wrap to 0
+        assert_eq!(state.selected_index, 0);
+
+        state.move_up(); // Should wrap to last item
+        assert_eq!(state.selected_index, 2);
+        state.move_up();
+        assert_eq!(state.selected_index, 1);
+        state.move_up();
+        assert_eq!(state.selected_index, 0);
+
+        // Test current_blob
+        assert_eq!(state.current_blob().unwrap().name, "blob1.txt");
+        state.move_down();
+        assert_eq!(state.current_blob().unwrap().name, "blob2.txt");
+    }
+
+    fn blobs(from: usize, to: usize) -> Vec<UiBlob> {
+        let start = Utc::now();
+        (from..to)
+            .map(|i| UiBlob {
+                name: format!("blob{}.txt", i),
+                created_on: start - chrono::TimeDelta::seconds(i as i64),
+                size: 100,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_blob_list_state_pages() {
+        let mut state = BlobListState::new("test".to_string());
+        state.append(blobs(0, 50), Some("m1".to_owned()));
+        assert!(state.next_page().is_none());
+
+        // The next page is listed once the selection nears the end
+        while state.selected_index + PREFETCH_ROWS < 50 {
+            state.move_down();
+        }
+        assert_eq!(state.next_page(), Some("m1"));
+        state.loading = true;
+        assert!(state.next_page().is_none());
+
+        // Newer blobs of the page are sorted first, the selected blob stays selected
+        let selected = state.current_blob().unwrap().name.clone();
+        let mut page = blobs(50, 60);
+        page[0].created_on = Utc::now() + chrono::TimeDelta::hours(1);
+        state.append(page, None);
+        assert!(!state.loading);
+        assert_eq!(state.blobs.len(), 60);
+        assert_eq!(state.blobs[0].name, "blob50.txt");
+        assert_eq!(state.current_blob().unwrap().name, selected);
+        assert!(state.next_page().is_none());
+    }
+
+    #[test]
+    fn test_draw_visible_blobs() {
+        let mut state = BlobListState::new("test".to_string());
+        state.append(blobs(0, 1000), Some("m1".to_owned()));
+        state.selected_index = 500;
+        state.loading = true;
+
+        let area = Rect::new(0, 0, 80, 8);
+        let items = blob_items(&state, area, "No blobs found in container");
+        // 6 rows inside the borders, the last one for the status
+        assert_eq!(items.len(), 6);
+        assert_eq!(state.visible(5), 496..501);
+
+        let mut buf = Buffer::empty(area);
+        do_list_blobs(&state, area, &mut buf).unwrap();
+        let text: Vec<String> = (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect();
+        assert!(text[5].contains("501  blob500.txt"));
+        assert!(text[6].contains("Loading more blobs..."));
+    }
+
+    #[test]
+    fn test_blob_list_state_filter_and_sort() {
+        let mut state = BlobListState::new("test".to_string());
+        let mut page = blobs(0, 4);
+        page[0].name = "ApFw_0700FA.bin".to_owned();
+        page[1].name = "image.jpg".to_owned();
+        page[2].name = "apfw_0700FB.bin".to_owned();
+        page[2].size = 300;
+        state.append(page, None);
+        assert_eq!(state.sort, BlobSort::Modified);
+        assert_eq!(state.current_blob().unwrap().name, "ApFw_0700FA.bin");
+
+        state.set_filter("APFW");
+        let names: Vec<&str> = state.shown().iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["ApFw_0700FA.bin", "apfw_0700FB.bin"]);
+        state.move_down();
+        state.move_down();
+        assert_eq!(state.selected_index, 0);
+
+        // The selected blob stays selected when sorted by another column
+        state.move_down();
+        state.sort_by_next();
+        assert_eq!(state.sort, BlobSort::Name);
+        state.sort_by_next();
+        assert_eq!(state.sort, BlobSort::Size);
+        assert_eq!(state.selected_index, 0);
+        assert_eq!(state.current_blob().unwrap().name, "apfw_0700FB.bin");
+
+        state.set_filter("");
+        assert_eq!(state.shown().len(), 4);
+    }
+
+    #[test]
+    fn test_blob_list_state_downloads() {
+        let mut state = BlobListState::new("test".to_string());
+        state.append(blobs(0, 4), None);
+
+        // The selected blob is downloaded when none is marked
+        assert_eq!(state.take_downloads(), vec!["blob0.txt"]);
+
+        state.toggle_mark();
+        state.move_down();
+        state.toggle_mark();
+        state.toggle_mark();
+        assert_eq!(state.selected_index, 0);
+        assert_eq!(state.marked.len(), 3);
+
+        // Marking again unmarks
+        state.move_down();
+        state.move_down();
+        state.toggle_mark();
+        assert_eq!(state.take_downloads(), vec!["blob0.txt", "blob3.txt"]);
+        assert!(state.marked.is_empty());
+
+        assert_eq!(state.downloads_summary(), "");
+        state
+            .downloads
+            .insert("blob0.txt".to_owned(), BlobDownload::Running(40));
+        state
+            .downloads
+            .insert("blob1.txt".to_owned(), BlobDownload::Queued);
+        state
+            .downloads
+            .insert("blob3.txt".to_owned(), BlobDownload::Queued);
+        state.downloads.insert(
+            "blob2.txt".to_owned(),
+            BlobDownload::Failed("timeout".to_owned()),
+        );
+        assert_eq!(state.downloads_summary(), "1 running, 2 queued, 1 failed");
+        assert_eq!(state.downloads["blob0.txt"].text(), "40%");
+    }
 }
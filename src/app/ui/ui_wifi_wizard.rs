@@ -0,0 +1,196 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{
+        focus_block, list_items_push, list_items_push_blank, list_items_push_focus, normal_block,
+        pad_width, ui_network::wireless_lines,
+    },
+    crate::{
+        app::App,
+        error::DMError,
+        i18n::{tr, trf},
+        mqtt_ctrl::with_mqtt_ctrl,
+        provisioning::{
+            ProvisionStatus, mask_configuration,
+            wifi::{ENCRYPTIONS, WIFI_FIELDS, WifiWizard, WifiWizardStep},
+        },
+    },
+    chrono::Local,
+    error_stack::Result,
+    ratatui::{
+        buffer::Buffer,
+        layout::{Constraint, Direction, Layout, Rect},
+        prelude::{Color, Style},
+        text::{Line, Span},
+        widgets::{List, ListItem, Paragraph, Widget, Wrap},
+    },
+};
+
+fn draw_steps(area: Rect, buf: &mut Buffer, wizard: &WifiWizard) {
+    let mut spans = Vec::new();
+
+    for step in WifiWizardStep::all() {
+        if step.index() > 0 {
+            spans.push(Span::styled(" > ", Style::default().fg(Color::DarkGray)));
+        }
+
+        let style = if step == wizard.step {
+            Style::default().fg(Color::Black).bg(Color::Yellow)
+        } else if step.index() < wizard.step.index() {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        spans.push(Span::styled(
+            format!(" {} {} ", step.index() + 1, tr(step.title())),
+            style,
+        ));
+    }
+
+    Paragraph::new(Line::from(spans))
+        .block(normal_block(" Wi-Fi Wizard "))
+        .render(area, buf);
+}
+
+fn draw_credentials(area: Rect, buf: &mut Buffer, wizard: &WifiWizard) {
+    let mut list_items = Vec::<ListItem>::new();
+
+    for (i, field) in WIFI_FIELDS.iter().enumerate() {
+        let focus = i == wizard.focus;
+        // Passwords are never displayed, only their length
+        let value = match i {
+            0 => wizard.ssid.clone(),
+            1 => "*".repeat(wizard.password.chars().count()),
+            2 => "*".repeat(wizard.confirm.chars().count()),
+            _ => ENCRYPTIONS[wizard.encryption].to_owned(),
+        };
+        let value = match (focus, wizard.is_encryption_focused()) {
+            (true, true) => format!("< {} >", value),
+            (true, false) => format!("{}|", value),
+            _ => value,
+        };
+        list_items_push_focus(&mut list_items, tr(field), &value, focus);
+    }
+
+    list_items_push_blank(&mut list_items);
+    let errors = wizard.validate();
+    if errors.is_empty() {
+        list_items.push(ListItem::new(Span::styled(
+            tr("Ready, press (ENTER) to preview the configuration"),
+            Style::default().fg(Color::Green),
+        )));
+    }
+    for error in errors {
+        list_items.push(ListItem::new(Span::styled(
+            format!("- {}", error),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+
+    List::new(list_items)
+        .block(focus_block(" Credentials "))
+        .render(area, buf);
+}
+
+fn draw_preview(area: Rect, buf: &mut Buffer, wizard: &WifiWizard) {
+    let payload = wizard
+        .payload
+        .as_deref()
+        .map(|p| mask_configuration(p, "password"))
+        .unwrap_or_default();
+
+    Paragraph::new(payload)
+        .wrap(Wrap { trim: false })
+        .block(focus_block(" Wireless Configuration (password masked) "))
+        .render(area, buf);
+}
+
+fn draw_monitor(area: Rect, buf: &mut Buffer, wizard: &WifiWizard) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(7), Constraint::Min(3)])
+        .split(area);
+
+    let (status, reported_ssid, wireless) = with_mqtt_ctrl(|mqtt_ctrl| {
+        let wireless = mqtt_ctrl.wireless_settings();
+        (
+            wizard.status(wireless),
+            wizard.is_ssid_reported(wireless),
+            wireless_lines(wireless),
+        )
+    });
+
+    let (text, color) = match &status {
+        ProvisionStatus::Waiting => (
+            tr("Waiting for the device to report the request").to_owned(),
+            Color::Yellow,
+        ),
+        ProvisionStatus::Applied if reported_ssid => (tr("Applied").to_owned(), Color::Green),
+        ProvisionStatus::Applied => (
+            tr("Accepted, waiting for the new SSID to be reported").to_owned(),
+            Color::Yellow,
+        ),
+        ProvisionStatus::Failed(res_info) => (trf("Failed: {0}", &[res_info]), Color::Red),
+    };
+
+    let elapsed = wizard
+        .sent
+        .map(|sent| (Local::now() - sent).num_seconds().max(0))
+        .unwrap_or_default();
+    let lines = vec![
+        Line::from(format!("{}: {}", pad_width("SSID", 8), wizard.ssid.trim())),
+        Line::from(format!(
+            "{}: {}",
+            pad_width(tr("Req ID"), 8),
+            wizard.req_id.as_deref().unwrap_or("N/A")
+        )),
+        Line::from(format!(
+            "{}: {}",
+            pad_width(tr("Sent"), 8),
+            trf("{0}s ago", &[&elapsed])
+        )),
+        Line::from(vec![
+            Span::raw(format!("{}: ", pad_width(tr("State"), 8))),
+            Span::styled(text, Style::default().fg(color)),
+        ]),
+    ];
+    Paragraph::new(lines)
+        .block(focus_block(" Provisioning "))
+        .render(chunks[0], buf);
+
+    Paragraph::new(wireless)
+        .block(normal_block(" Reported Wireless State "))
+        .render(chunks[1], buf);
+}
+
+pub fn draw(area: Rect, buf: &mut Buffer, wizard: &WifiWizard) -> Result<(), DMError> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(5)])
+        .split(area);
+
+    draw_steps(chunks[0], buf, wizard);
+
+    match wizard.step {
+        WifiWizardStep::Credentials => draw_credentials(chunks[1], buf, wizard),
+        WifiWizardStep::Preview => draw_preview(chunks[1], buf, wizard),
+        WifiWizardStep::Monitor => draw_monitor(chunks[1], buf, wizard),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,283 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::i18n::trf,
+    serde_json::Value,
+    std::time::{Duration, Instant},
+};
+
+/// Settings the device applies by rebooting
+pub const REBOOT_SECTIONS: [&str; 2] = ["network_settings", "wireless_setting"];
+
+/// Time given to the device to reboot and report the settings again
+pub const VERIFY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Time given to the device to report the settings after it reconnected
+pub const REPORT_GRACE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyPhase {
+    WaitingReboot,
+    WaitingReport,
+    Done,
+    TimedOut,
+}
+
+/// One sent value and the value reported by the device
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueCheck {
+    /// Dotted path, e.g. "static_settings_ipv4.ip_address"
+    pub key: String,
+    pub expected: Value,
+    pub reported: Option<Value>,
+}
+
+impl ValueCheck {
+    /// Numbers are compared by value so that 1 and 1.0 match
+    pub fn is_applied(&self) -> bool {
+        match (&self.expected, &self.reported) {
+            (Value::Number(e), Some(Value::Number(r))) => e.as_f64() == r.as_f64(),
+            (e, Some(r)) => e == r,
+            (_, None) => false,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let reported = self
+            .reported
+            .as_ref()
+            .map(|r| r.to_string())
+            .unwrap_or_else(|| "-".to_owned());
+        format!("{}: {} -> {}", self.key, self.expected, reported)
+    }
+}
+
+/// Configuration sent in "apply and verify" mode, waiting for the device to reboot and
+/// report the new settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApplyVerify {
+    /// e.g. "network_settings"
+    pub section: String,
+    pub checks: Vec<ValueCheck>,
+    pub phase: VerifyPhase,
+    sent: Instant,
+    reboots_at_send: usize,
+    rebooted: Option<Instant>,
+}
+
+fn flatten(prefix: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                let key = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{prefix}.{k}")
+                };
+                flatten(&key, v, out);
+            }
+        }
+        _ => out.push((prefix.to_owned(), value.clone())),
+    }
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |v, k| v.get(k))
+}
+
+impl ApplyVerify {
+    /// Starts verifying a configuration, None when it doesn't require a reboot.
+    /// Passwords are skipped since the device doesn't report them back.
+    pub fn new(config: &str, reboots: usize, now: Instant) -> Option<Self> {
+        let root: Value = serde_json::from_str(config).ok()?;
+        let (section, settings) = root.as_object()?.iter().find_map(|(topic, value)| {
+            let section = topic.strip_prefix("configuration/$system/")?;
+            REBOOT_SECTIONS
+                .contains(&section)
+                .then(|| (section.to_owned(), value))
+        })?;
+        let settings: Value = serde_json::from_str(settings.as_str()?).ok()?;
+
+        let mut values = vec![];
+        flatten("", &settings, &mut values);
+        let checks = values
+            .into_iter()
+            .filter(|(k, _)| !k.starts_with("req_info") && !k.contains("password"))
+            .map(|(key, expected)| ValueCheck {
+                key,
+                expected,
+                reported: None,
+            })
+            .collect();
+
+        Some(Self {
+            section,
+            checks,
+            phase: VerifyPhase::WaitingReboot,
+            sent: now,
+            reboots_at_send: reboots,
+            rebooted: None,
+        })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.phase, VerifyPhase::Done | VerifyPhase::TimedOut)
+    }
+
+    pub fn applied(&self) -> usize {
+        self.checks.iter().filter(|c| c.is_applied()).count()
+    }
+
+    /// Follows the reboot count and the settings reported by the device
+    pub fn update(&mut self, reboots: usize, reported: Option<&Value>, now: Instant) {
+        if self.is_finished() {
+            return;
+        }
+
+        if self.phase == VerifyPhase::WaitingReboot && reboots > self.reboots_at_send {
+            self.phase = VerifyPhase::WaitingReport;
+            self.rebooted = Some(now);
+        }
+
+        if self.phase == VerifyPhase::WaitingReport {
+            for check in self.checks.iter_mut() {
+                check.reported = reported.and_then(|r| lookup(r, &check.key)).cloned();
+            }
+
+            let settled = self
+                .rebooted
+                .is_some_and(|rebooted| now.duration_since(rebooted) >= REPORT_GRACE);
+            if self.applied() == self.checks.len() || settled {
+                self.phase = VerifyPhase::Done;
+                return;
+            }
+        }
+
+        if now.duration_since(self.sent) >= VERIFY_TIMEOUT {
+            self.phase = VerifyPhase::TimedOut;
+        }
+    }
+
+    /// Result shown once finished, None when all the values took effect
+    pub fn failure(&self) -> Option<String> {
+        match self.phase {
+            VerifyPhase::TimedOut if self.rebooted.is_none() => Some(trf(
+                "{0}: the device didn't reboot within {1}s",
+                &[&self.section, &VERIFY_TIMEOUT.as_secs()],
+            )),
+            _ if self.applied() == self.checks.len() => None,
+            _ => {
+                let failed: Vec<String> = self
+                    .checks
+                    .iter()
+                    .filter(|c| !c.is_applied())
+                    .map(|c| c.text())
+                    .collect();
+                Some(trf(
+                    "{0}: {1}/{2} values applied, not applied: {3}",
+                    &[
+                        &self.section,
+                        &self.applied(),
+                        &self.checks.len(),
+                        &failed.join(", "),
+                    ],
+                ))
+            }
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        self.failure().unwrap_or_else(|| {
+            trf(
+                "{0}: all {1} values applied after reboot",
+                &[&self.section, &self.checks.len()],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{
+        "configuration/$system/network_settings": "{\"ip_method\":1,\"static_settings_ipv4\":{\"ip_address\":\"192.168.0.10\"},\"proxy_settings\":{\"proxy_password\":\"secret\"},\"req_info\":{\"req_id\":\"abc\"}}"
+    }"#;
+
+    #[test]
+    fn test_new_apply_verify() {
+        let verify = ApplyVerify::new(CONFIG, 0, Instant::now()).unwrap();
+        assert_eq!(verify.section, "network_settings");
+        let keys: Vec<&str> = verify.checks.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, vec!["ip_method", "static_settings_ipv4.ip_address"]);
+
+        let system = r#"{"configuration/$system/system_settings": "{\"led_enabled\":true}"}"#;
+        assert!(ApplyVerify::new(system, 0, Instant::now()).is_none());
+        assert!(ApplyVerify::new("not json", 0, Instant::now()).is_none());
+    }
+
+    #[test]
+    fn test_apply_verify_applied() {
+        let start = Instant::now();
+        let mut verify = ApplyVerify::new(CONFIG, 2, start).unwrap();
+        let old = serde_json::json!({"ip_method": 0});
+        let new = serde_json::json!({
+            "ip_method": 1,
+            "static_settings_ipv4": {"ip_address": "192.168.0.10", "subnet_mask": ""}
+        });
+
+        verify.update(2, Some(&old), start + Duration::from_secs(10));
+        assert_eq!(verify.phase, VerifyPhase::WaitingReboot);
+
+        // The settings reported right after the reboot may still be the old ones
+        verify.update(3, Some(&old), start + Duration::from_secs(20));
+        assert_eq!(verify.phase, VerifyPhase::WaitingReport);
+
+        verify.update(3, Some(&new), start + Duration::from_secs(30));
+        assert_eq!(verify.phase, VerifyPhase::Done);
+        assert!(verify.failure().is_none());
+    }
+
+    #[test]
+    fn test_apply_verify_not_applied() {
+        let start = Instant::now();
+        let mut verify = ApplyVerify::new(CONFIG, 0, start).unwrap();
+        let reported = serde_json::json!({"ip_method": 1});
+
+        verify.update(1, Some(&reported), start + Duration::from_secs(10));
+        assert_eq!(verify.phase, VerifyPhase::WaitingReport);
+        verify.update(
+            1,
+            Some(&reported),
+            start + Duration::from_secs(10) + REPORT_GRACE,
+        );
+        assert_eq!(verify.phase, VerifyPhase::Done);
+        let failure = verify.failure().unwrap();
+        assert!(failure.contains("1/2 values applied"));
+        assert!(failure.contains("static_settings_ipv4.ip_address"));
+    }
+
+    #[test]
+    fn test_apply_verify_timeout() {
+        let start = Instant::now();
+        let mut verify = ApplyVerify::new(CONFIG, 0, start).unwrap();
+        verify.update(0, None, start + VERIFY_TIMEOUT);
+        assert_eq!(verify.phase, VerifyPhase::TimedOut);
+        assert!(verify.failure().unwrap().contains("didn't reboot"));
+    }
+}
@@ -0,0 +1,354 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! Append-only record of the operations changing the device or the storage: the
+//! configurations, RPCs and messages published, and the containers and blobs created or
+//! deleted, with who did them and their result.
+
+#[allow(unused)]
+use {
+    crate::error::{DMError, DMErrorExt},
+    chrono::{DateTime, Local},
+    error_stack::{Report, Result},
+    jlogger_tracing::jerror,
+    sha2::{Digest, Sha256},
+    std::{
+        collections::VecDeque,
+        io::Write,
+        path::{Path, PathBuf},
+        sync::{Mutex, OnceLock},
+    },
+};
+
+/// Audit file, in the current directory unless given
+pub const DEFAULT_AUDIT_FILE: &str = "audit.log";
+
+/// Size from which the audit file is rotated before appending
+pub const DEFAULT_AUDIT_MAX_SIZE: u64 = 1024 * 1024;
+
+/// Rotated audit files kept, `<file>.1` being the newest
+pub const DEFAULT_AUDIT_KEEP: usize = 5;
+
+/// Entries kept for the Audit screen
+const RECENT_SIZE: usize = 200;
+
+static GLOBAL_AUDIT_LOG: OnceLock<Mutex<AuditLog>> = OnceLock::new();
+
+/// Makes the audit log the global one, the operations are not recorded before
+pub fn init_global_audit_log(audit_log: AuditLog) -> Result<(), DMError> {
+    GLOBAL_AUDIT_LOG.set(Mutex::new(audit_log)).map_err(|_| {
+        Report::new(DMError::RuntimeError)
+            .attach_printable("Failed to initialize global AuditLog - already initialized")
+    })
+}
+
+/// Runs `f` on the global audit log, None if it is not initialized
+pub fn with_audit_log<F, R>(f: F) -> Option<R>
+where
+    F: FnOnce(&AuditLog) -> R,
+{
+    GLOBAL_AUDIT_LOG.get().map(|audit_log| {
+        f(&audit_log
+            .lock()
+            .expect("Failed to lock global AuditLog mutex"))
+    })
+}
+
+/// Records the operation in the global audit log, if any. The operation is not undone
+/// when the audit file cannot be written, the failure is only logged.
+pub fn record(action: &str, target: &str, payload: &[u8], result: AuditResult) {
+    record_digest(action, target, &sha256_hex(payload), payload.len(), result);
+}
+
+/// Same as `record()` for a payload already hashed, e.g. a blob uploaded
+pub fn record_digest(action: &str, target: &str, sha256: &str, size: usize, result: AuditResult) {
+    let Some(audit_log) = GLOBAL_AUDIT_LOG.get() else {
+        return;
+    };
+
+    let mut audit_log = audit_log
+        .lock()
+        .expect("Failed to lock global AuditLog mutex");
+    let entry = AuditEntry {
+        time: Local::now(),
+        who: audit_log.who.clone(),
+        action: action.to_owned(),
+        target: target.to_owned(),
+        sha256: sha256.to_owned(),
+        size,
+        result,
+    };
+
+    if let Err(e) = audit_log.append(entry) {
+        jerror!(
+            func = "audit::record_digest()",
+            error = e.error_str().unwrap_or_default()
+        );
+    }
+}
+
+pub fn sha256_hex(payload: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(payload))
+}
+
+/// User running device-monitor, from the environment
+fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_owned())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuditResult {
+    Done,
+    /// Written to the review log of the dry-run mode instead
+    DryRun,
+    /// Refused by the read-only mode
+    Refused,
+    Failed(String),
+}
+
+impl AuditResult {
+    pub fn of<T>(result: &Result<T, DMError>) -> Self {
+        match result {
+            Ok(_) => AuditResult::Done,
+            Err(e) if matches!(e.current_context(), DMError::ReadOnly) => AuditResult::Refused,
+            Err(e) => AuditResult::Failed(
+                e.error_str()
+                    .unwrap_or_else(|| e.current_context().to_string()),
+            ),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AuditResult::Done => "done",
+            AuditResult::DryRun => "dry-run",
+            AuditResult::Refused => "refused",
+            AuditResult::Failed(_) => "failed",
+        }
+    }
+}
+
+/// One operation of the audit file
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub time: DateTime<Local>,
+    pub who: String,
+    /// e.g. "configure", "rpc reboot" or "delete blob"
+    pub action: String,
+    /// Topic, or container and blob
+    pub target: String,
+    /// SHA-256 of the payload, hex encoded
+    pub sha256: String,
+    pub size: usize,
+    pub result: AuditResult,
+}
+
+impl AuditEntry {
+    /// Line of the audit file, one JSON object per operation
+    pub fn to_line(&self) -> String {
+        let mut line = json::object! {
+            "time": self.time.to_rfc3339(),
+            "who": self.who.as_str(),
+            "action": self.action.as_str(),
+            "target": self.target.as_str(),
+            "sha256": self.sha256.as_str(),
+            "size": self.size,
+            "result": self.result.name(),
+        };
+        if let AuditResult::Failed(error) = &self.result {
+            line["error"] = error.as_str().into();
+        }
+        line.dump()
+    }
+}
+
+/// Audit file rotated when it grows beyond `max_size`, with the recent entries
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    max_size: u64,
+    keep: usize,
+    who: String,
+    /// Oldest first
+    recent: VecDeque<AuditEntry>,
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_size: DEFAULT_AUDIT_MAX_SIZE,
+            keep: DEFAULT_AUDIT_KEEP,
+            who: current_user(),
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Rotates the file from `max_size` bytes, keeping `keep` rotated files (at least one)
+    pub fn with_rotation(self, max_size: u64, keep: usize) -> Self {
+        Self {
+            max_size,
+            keep: keep.max(1),
+            ..self
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn recent(&self) -> &VecDeque<AuditEntry> {
+        &self.recent
+    }
+
+    /// Path of the n-th rotated file, 1 being the newest
+    pub fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", n));
+        path.into()
+    }
+
+    /// Shifts the rotated files, the oldest one is overwritten
+    fn rotate(&self) -> std::io::Result<()> {
+        for n in (1..self.keep).rev() {
+            let from = self.rotated_path(n);
+            if from.exists() {
+                std::fs::rename(&from, self.rotated_path(n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated_path(1))
+    }
+
+    pub fn append(&mut self, entry: AuditEntry) -> Result<(), DMError> {
+        let line = format!("{}\n", entry.to_line());
+        let io_error = |e: std::io::Error| {
+            Report::new(DMError::IOError).attach_printable(format!(
+                "Failed to write the audit file {}: {}",
+                self.path.display(),
+                e
+            ))
+        };
+
+        let size = std::fs::metadata(&self.path).map_or(0, |m| m.len());
+        if size > 0 && size + line.len() as u64 > self.max_size {
+            self.rotate().map_err(io_error)?;
+        }
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(io_error)?;
+
+        if self.recent.len() == RECENT_SIZE {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(entry);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(action: &str, result: AuditResult) -> AuditEntry {
+        AuditEntry {
+            time: Local.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap(),
+            who: "alice".to_owned(),
+            action: action.to_owned(),
+            target: "v1/devices/me/attributes".to_owned(),
+            sha256: sha256_hex(b"{}"),
+            size: 2,
+            result,
+        }
+    }
+
+    #[test]
+    fn test_audit_entry_line() {
+        let line = json::parse(&entry("configure", AuditResult::Done).to_line()).unwrap();
+        assert_eq!(line["who"], "alice");
+        assert_eq!(line["action"], "configure");
+        assert_eq!(line["target"], "v1/devices/me/attributes");
+        assert_eq!(
+            line["sha256"],
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+        assert_eq!(line["size"], 2);
+        assert_eq!(line["result"], "done");
+        assert!(line["error"].is_null());
+
+        let line = entry("rpc reboot", AuditResult::Failed("broker down".to_owned())).to_line();
+        let line = json::parse(&line).unwrap();
+        assert_eq!(line["result"], "failed");
+        assert_eq!(line["error"], "broker down");
+    }
+
+    #[test]
+    fn test_audit_result_of() {
+        let refused: Result<(), DMError> = Err(Report::new(DMError::ReadOnly));
+        let failed: Result<(), DMError> =
+            Err(Report::new(DMError::IOError).attach_printable("broker down"));
+        assert_eq!(
+            AuditResult::of(&Ok::<_, Report<DMError>>(1)),
+            AuditResult::Done
+        );
+        assert_eq!(AuditResult::of(&refused), AuditResult::Refused);
+        assert_eq!(
+            AuditResult::of(&failed),
+            AuditResult::Failed("broker down".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_audit_log_rotation() {
+        let dir = std::env::temp_dir().join(format!("audit_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let line_len = entry("a1", AuditResult::Done).to_line().len() as u64 + 1;
+        // Two entries per file, two rotated files
+        let mut audit_log = AuditLog::new(dir.join("audit.log")).with_rotation(line_len * 2, 2);
+        for action in ["a1", "a2", "a3", "a4", "a5", "a6", "a7"] {
+            audit_log.append(entry(action, AuditResult::Done)).unwrap();
+        }
+
+        let actions = |path: PathBuf| -> Vec<String> {
+            std::fs::read_to_string(path)
+                .unwrap()
+                .lines()
+                .map(|l| {
+                    json::parse(l).unwrap()["action"]
+                        .as_str()
+                        .unwrap()
+                        .to_owned()
+                })
+                .collect()
+        };
+        assert_eq!(actions(dir.join("audit.log")), ["a7"]);
+        assert_eq!(actions(audit_log.rotated_path(1)), ["a5", "a6"]);
+        assert_eq!(actions(audit_log.rotated_path(2)), ["a3", "a4"]);
+        assert!(!audit_log.rotated_path(3).exists());
+
+        assert_eq!(audit_log.recent().len(), 7);
+        assert_eq!(audit_log.recent()[0].action, "a1");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
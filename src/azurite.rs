@@ -14,10 +14,19 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+pub mod auto_download;
+pub mod bootstrap;
+pub mod defaults;
+pub mod health;
+pub mod sas;
+pub mod worker;
+
 use azure_core::date::OffsetDateTime;
+use azure_core::request_options::{IfMatchCondition, Metadata};
 use azure_storage::prelude::BlobSasPermissions;
 #[allow(unused)]
 use {
+    super::audit::{self, AuditResult},
     super::error::DMError,
     super::mqtt_ctrl::evp::evp_state::UUID,
     super::mqtt_ctrl::evp::module::ModuleInfo,
@@ -28,14 +37,15 @@ use {
     },
     base64::{Engine as _, engine::general_purpose},
     bytes::Bytes,
-    clap::Parser,
     error_stack::{Context, Report, Result, ResultExt},
-    futures::stream::{self, StreamExt},
+    futures::stream::{self, BoxStream, StreamExt},
     jlogger_tracing::{JloggerBuilder, LevelFilter, jdebug, jerror, jinfo},
+    md5::Md5,
     sha2::{Digest, Sha256},
     std::{
         collections::HashMap,
-        io::Read,
+        io::{Read, Write},
+        num::NonZeroU32,
         sync::{Mutex, OnceLock},
     },
 };
@@ -47,17 +57,46 @@ const ACCOUNT_KEY: &str =
 /// Global AzuriteStorage instance protected by mutex for thread safety
 static GLOBAL_AZURITE_STORAGE: OnceLock<Mutex<Option<AzuriteStorage>>> = OnceLock::new();
 
-/// Global AzuriteStorage configuration URL for retry attempts
-static GLOBAL_AZURITE_URL: OnceLock<String> = OnceLock::new();
+/// Global AzuriteStorage connection settings for retry attempts and runtime reconfiguration
+static GLOBAL_AZURITE_CONFIG: OnceLock<Mutex<AzuriteConfig>> = OnceLock::new();
+
+/// Connection settings of the Azurite/Azure blob storage endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AzuriteConfig {
+    pub url: String,
+    pub account_name: String,
+    pub account_key: String,
+    /// Containers and blobs are only listed and downloaded, never created or deleted
+    pub read_only: bool,
+}
+
+impl AzuriteConfig {
+    /// Creates settings for the given endpoint with the default Azurite account
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            account_name: ACCOUNT_NAME.to_owned(),
+            account_key: ACCOUNT_KEY.to_owned(),
+            read_only: false,
+        }
+    }
+}
 
 /// Initialize the global AzuriteStorage instance
 pub fn init_global_azurite_storage(azurite_url: &str) -> Result<(), DMError> {
-    // Store the URL for retry attempts
-    GLOBAL_AZURITE_URL
-        .set(azurite_url.to_string())
+    init_global_azurite_storage_with_config(AzuriteConfig::new(azurite_url))
+}
+
+/// Initialize the global AzuriteStorage instance with other settings than the default
+/// Azurite account, e.g. the read-only mode
+pub fn init_global_azurite_storage_with_config(config: AzuriteConfig) -> Result<(), DMError> {
+    let storage = AzuriteStorage::with_config(&config).ok();
+
+    // Store the settings for retry attempts
+    GLOBAL_AZURITE_CONFIG
+        .set(Mutex::new(config))
         .map_err(|_| DMError::InvalidData)?;
 
-    let storage = AzuriteStorage::new(azurite_url).ok();
     GLOBAL_AZURITE_STORAGE
         .set(Mutex::new(storage))
         .map_err(|_| DMError::InvalidData)?;
@@ -101,30 +140,70 @@ where
     (*storage_guard).as_mut().map(f)
 }
 
+/// Returns a copy of the current AzuriteStorage connection settings
+pub fn azurite_config() -> AzuriteConfig {
+    GLOBAL_AZURITE_CONFIG
+        .get()
+        .expect("Global AzuriteStorage config not initialized")
+        .lock()
+        .expect("Failed to lock global AzuriteStorage config mutex")
+        .clone()
+}
+
+/// Replace the AzuriteStorage connection settings at runtime.
+/// A storage is built with the new settings and its connectivity checked first, the current
+/// storage and settings are only replaced once it answers, so that a typo does not lose a
+/// working connection. Returns the number of containers found on success.
+pub fn reconfigure_azurite_storage(config: AzuriteConfig) -> Result<usize, DMError> {
+    reconfigure_with(config, AzuriteStorage::check_connection)
+}
+
+/// Replaces the settings once `check` accepts the storage built with them
+fn reconfigure_with(
+    config: AzuriteConfig,
+    check: impl FnOnce(&AzuriteStorage) -> Result<usize, DMError>,
+) -> Result<usize, DMError> {
+    let mut storage = AzuriteStorage::with_config(&config)?;
+    let containers = check(&storage)?;
+    let _ = storage.scan_upload_containers();
+
+    *GLOBAL_AZURITE_CONFIG
+        .get()
+        .expect("Global AzuriteStorage config not initialized")
+        .lock()
+        .expect("Failed to lock global AzuriteStorage config mutex") = config;
+
+    *get_global_azurite_storage_ref()
+        .lock()
+        .expect("Failed to lock global AzuriteStorage mutex") = Some(storage);
+
+    Ok(containers)
+}
+
 /// Try to reinitialize AzuriteStorage if it's currently None
 pub fn try_reinit_azurite_storage() -> bool {
-    let azurite_url = GLOBAL_AZURITE_URL
-        .get()
-        .expect("Global AzuriteStorage URL not initialized");
+    if with_azurite_storage(|_| ()).is_some() {
+        return false;
+    }
+
+    let config = azurite_config();
+    let Ok(mut new_storage) = AzuriteStorage::with_config(&config) else {
+        jdebug!("Failed to reinitialize AzuriteStorage");
+        return false;
+    };
+    // Scan for existing token providers before the storage is shared, so that the global
+    // storage is not locked while waiting for Azurite
+    let _ = new_storage.scan_upload_containers();
 
     let mut storage_guard = get_global_azurite_storage_ref()
         .lock()
         .expect("Failed to lock global AzuriteStorage mutex");
-
-    if storage_guard.is_none() {
-        if let Ok(new_storage) = AzuriteStorage::new(azurite_url) {
-            *storage_guard = Some(new_storage);
-            // Scan for existing token providers after successful initialization
-            if let Some(ref mut storage) = *storage_guard {
-                let _ = storage.scan_upload_containers();
-            }
-            jinfo!("AzuriteStorage reinitialized successfully");
-            return true;
-        } else {
-            jdebug!("Failed to reinitialize AzuriteStorage");
-        }
+    if storage_guard.is_some() {
+        return false;
     }
-    false
+    *storage_guard = Some(new_storage);
+    jinfo!("AzuriteStorage reinitialized successfully");
+    true
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -136,10 +215,136 @@ pub enum AzuriteAction {
     Deploy,
 }
 
+/// Container metadata keys of the token provider label, Azure requires C# identifiers
+const METADATA_UUID: &str = "dm_uuid";
+const METADATA_NAME: &str = "dm_name";
+const METADATA_DESCRIPTION: &str = "dm_description";
+const METADATA_TAGS: &str = "dm_tags";
+
+/// Friendly name, description and tags of a token provider, stored in the metadata of its
+/// upload container
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TokenProviderLabel {
+    pub name: String,
+    pub description: String,
+    pub tags: Vec<String>,
+}
+
+impl TokenProviderLabel {
+    /// Parses comma separated tags, empty ones are dropped
+    pub fn parse_tags(tags: &str) -> Vec<String> {
+        tags.split(',')
+            .map(|t| t.trim().to_owned())
+            .filter(|t| !t.is_empty())
+            .collect()
+    }
+
+    /// Metadata values are sent as HTTP headers, only printable ASCII is accepted
+    pub fn validate(&self) -> Result<(), DMError> {
+        let fields = [
+            ("name", self.name.as_str()),
+            ("description", self.description.as_str()),
+        ];
+        for (field, value) in fields
+            .into_iter()
+            .chain(self.tags.iter().map(|t| ("tag", t.as_str())))
+        {
+            if !value.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+                return Err(Report::new(DMError::InvalidData).attach_printable(format!(
+                    "Invalid {} '{}': only printable ASCII characters are allowed",
+                    field, value
+                )));
+            }
+        }
+
+        if self.tags.iter().any(|t| t.contains(',')) {
+            return Err(
+                Report::new(DMError::InvalidData).attach_printable("Tags cannot contain ','")
+            );
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TokenProvider {
     pub uuid: UUID,
     pub container: String,
+    pub label: TokenProviderLabel,
+}
+
+impl TokenProvider {
+    /// Restores a token provider from an upload container. The UUID is taken from the
+    /// metadata, or from the name for containers named `upload-<UUID>`.
+    fn from_container(container: &str, metadata: &HashMap<String, String>) -> Option<Self> {
+        let uuid = match metadata.get(METADATA_UUID) {
+            Some(uuid) => UUID::from(uuid).ok()?,
+            None => UUID::from(container.strip_prefix("upload")?.trim_start_matches('-')).ok()?,
+        };
+
+        let value = |key: &str| metadata.get(key).cloned().unwrap_or_default();
+        Some(Self {
+            uuid,
+            container: container.to_owned(),
+            label: TokenProviderLabel {
+                name: value(METADATA_NAME),
+                description: value(METADATA_DESCRIPTION),
+                tags: TokenProviderLabel::parse_tags(&value(METADATA_TAGS)),
+            },
+        })
+    }
+
+    fn metadata(&self) -> Vec<(&'static str, String)> {
+        let mut metadata = vec![(METADATA_UUID, self.uuid.uuid().to_owned())];
+        if !self.label.name.is_empty() {
+            metadata.push((METADATA_NAME, self.label.name.clone()));
+        }
+        if !self.label.description.is_empty() {
+            metadata.push((METADATA_DESCRIPTION, self.label.description.clone()));
+        }
+        if !self.label.tags.is_empty() {
+            metadata.push((METADATA_TAGS, self.label.tags.join(",")));
+        }
+        metadata
+    }
+
+    /// Whether the name, description, container or one of the tags contains the filter,
+    /// case insensitive
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+        if filter.is_empty() {
+            return true;
+        }
+
+        [&self.label.name, &self.label.description, &self.container]
+            .into_iter()
+            .chain(self.label.tags.iter())
+            .any(|v| v.to_lowercase().contains(&filter))
+    }
+}
+
+/// Checks the Azure naming rules of containers: 3 to 63 lowercase letters, digits and
+/// hyphens, starting with a letter or a digit, without consecutive hyphens
+pub fn validate_container_name(name: &str) -> Result<(), DMError> {
+    let invalid = |reason: &str| {
+        Err(Report::new(DMError::InvalidData)
+            .attach_printable(format!("Invalid container name '{}': {}", name, reason)))
+    };
+
+    if !(3..=63).contains(&name.len()) {
+        return invalid("3 to 63 characters expected");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return invalid("only lowercase letters, digits and '-' are allowed");
+    }
+    if name.starts_with('-') || name.ends_with('-') || name.contains("--") {
+        return invalid("'-' cannot be at either end or repeated");
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
@@ -149,6 +354,258 @@ pub struct UiBlob {
     pub size: u64,
 }
 
+/// Converts Azure's OffsetDateTime to chrono DateTime
+fn to_chrono(time: OffsetDateTime) -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::from_timestamp(time.unix_timestamp(), time.nanosecond())
+        .unwrap_or_else(chrono::Utc::now)
+}
+
+impl UiBlob {
+    fn new(blob: &Blob) -> Self {
+        Self {
+            name: blob.name.clone(),
+            created_on: to_chrono(blob.properties.creation_time),
+            size: blob.properties.content_length,
+        }
+    }
+}
+
+/// Column the blob and module lists are sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlobSort {
+    /// Newest first
+    #[default]
+    Modified,
+    Name,
+    /// Largest first
+    Size,
+}
+
+impl BlobSort {
+    pub fn next(self) -> Self {
+        match self {
+            BlobSort::Modified => BlobSort::Name,
+            BlobSort::Name => BlobSort::Size,
+            BlobSort::Size => BlobSort::Modified,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlobSort::Modified => "last modified",
+            BlobSort::Name => "name",
+            BlobSort::Size => "size",
+        }
+    }
+
+    /// Orders two entries by the column, the names break the ties
+    pub fn compare<T>(
+        &self,
+        a: (&str, u64, Option<T>),
+        b: (&str, u64, Option<T>),
+    ) -> std::cmp::Ordering
+    where
+        T: Ord,
+    {
+        match self {
+            BlobSort::Modified => b.2.cmp(&a.2),
+            BlobSort::Name => std::cmp::Ordering::Equal,
+            BlobSort::Size => b.1.cmp(&a.1),
+        }
+        .then_with(|| a.0.cmp(b.0))
+    }
+}
+
+/// Case insensitive match of a blob name with a filter, an empty filter matches all
+pub fn blob_name_matches(name: &str, filter: &str) -> bool {
+    name.to_lowercase().contains(&filter.trim().to_lowercase())
+}
+
+/// Blobs requested per listing page, the next pages are listed as the list is scrolled
+pub const BLOB_PAGE_SIZE: u32 = 200;
+
+/// Blobs of one listing page
+#[derive(Debug, Clone, Default)]
+pub struct BlobPage {
+    pub blobs: Vec<UiBlob>,
+    /// Continuation token of the next page, None on the last page
+    pub next_marker: Option<String>,
+}
+
+/// Listing continued by `list_blobs_page()`, the pages are fetched with the continuation
+/// token of the previous one
+struct BlobListing {
+    container: String,
+    next_marker: Option<String>,
+    pages: BoxStream<'static, std::result::Result<BlobPage, String>>,
+}
+
+/// What is done when a blob of the same name as an uploaded file already exists
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum OverwritePolicy {
+    /// The file is not uploaded and the conflict is reported
+    #[default]
+    Refuse,
+    /// Replaces the blob only if its ETag is still the one reported with the conflict, so
+    /// that a blob replaced by someone else meanwhile is kept
+    IfMatch(String),
+    /// Uploads the file under the first free name with a "-N" suffix, e.g. "fw-1.bin"
+    Rename,
+}
+
+/// Existing blob of the same name as an uploaded file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobConflict {
+    /// Local file
+    pub path: String,
+    pub blob: String,
+    pub etag: String,
+}
+
+/// Result of an upload guarded by an `OverwritePolicy`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushOutcome {
+    /// Name of the uploaded blob
+    Uploaded(String),
+    Conflict(BlobConflict),
+}
+
+/// Name with a "-N" suffix before the extension, e.g. "fw.bin" gives "fw-2.bin" for 2
+pub fn suffixed_blob_name(name: &str, n: usize) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}-{}.{}", stem, n, ext),
+        _ => format!("{}-{}", name, n),
+    }
+}
+
+/// Size of the ranges a blob is downloaded by, an interrupted download resumes after the
+/// last range written
+pub const DOWNLOAD_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Time given to the storage to send one range of a download
+const DOWNLOAD_CHUNK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Suffix of the file a blob is written to until it is complete and verified
+pub const PARTIAL_SUFFIX: &str = ".part";
+
+/// Suffix of the file keeping the ETag of the blob a partial file belongs to
+const PARTIAL_ETAG_SUFFIX: &str = ".part.etag";
+
+/// How the content of a downloaded blob has been checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Integrity {
+    /// Matches the Content-MD5 of the blob properties
+    Md5,
+    /// Matches the SHA-256 of the module stored as the blob
+    Sha256,
+    /// Neither the blob properties nor the modules give a hash to check
+    Unverified,
+}
+
+impl Integrity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Integrity::Md5 => "MD5 verified",
+            Integrity::Sha256 => "SHA-256 verified",
+            Integrity::Unverified => "not verified",
+        }
+    }
+}
+
+/// Blob downloaded to a local file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Download {
+    pub path: String,
+    pub size: u64,
+    /// Bytes already written by an interrupted download of the same blob
+    pub resumed: u64,
+    pub integrity: Integrity,
+}
+
+impl Download {
+    /// e.g. "/tmp/fw.bin (5242880 bytes, resumed at 4194304, MD5 verified)"
+    pub fn summary(&self) -> String {
+        let resumed = if self.resumed > 0 {
+            format!(", resumed at {}", self.resumed)
+        } else {
+            String::new()
+        };
+        format!(
+            "{} ({} bytes{}, {})",
+            self.path,
+            self.size,
+            resumed,
+            self.integrity.name()
+        )
+    }
+}
+
+/// Partial file of a download to `file_path`
+pub fn partial_path(file_path: &std::path::Path) -> std::path::PathBuf {
+    let mut path = file_path.as_os_str().to_owned();
+    path.push(PARTIAL_SUFFIX);
+    path.into()
+}
+
+fn partial_etag_path(file_path: &std::path::Path) -> std::path::PathBuf {
+    let mut path = file_path.as_os_str().to_owned();
+    path.push(PARTIAL_ETAG_SUFFIX);
+    path.into()
+}
+
+/// Offset a download resumes from. The partial file is kept only if it was written from
+/// the same version of the blob and is not longer than it.
+pub fn resume_offset(partial_len: u64, partial_etag: Option<&str>, etag: &str, size: u64) -> u64 {
+    match partial_etag {
+        Some(partial_etag) if partial_etag == etag && partial_len <= size => partial_len,
+        _ => 0,
+    }
+}
+
+/// Checks the MD5 and SHA-256 digests of the downloaded content against the expected ones,
+/// the MD5 of the blob properties is preferred
+pub fn check_integrity(
+    md5: &[u8],
+    sha256: &str,
+    expected_md5: Option<&[u8]>,
+    expected_sha256: Option<&str>,
+) -> Result<Integrity, DMError> {
+    match (expected_md5, expected_sha256) {
+        (Some(expected), _) if expected == md5 => Ok(Integrity::Md5),
+        (Some(expected), _) => Err(Report::new(DMError::InvalidData).attach_printable(format!(
+            "Content MD5 mismatch: expected {}, got {}",
+            general_purpose::STANDARD.encode(expected),
+            general_purpose::STANDARD.encode(md5)
+        ))),
+        (None, Some(expected)) if expected.eq_ignore_ascii_case(sha256) => Ok(Integrity::Sha256),
+        (None, Some(expected)) => Err(Report::new(DMError::InvalidData).attach_printable(format!(
+            "SHA-256 mismatch: expected {}, got {}",
+            expected, sha256
+        ))),
+        (None, None) => Ok(Integrity::Unverified),
+    }
+}
+
+/// Runs an operation changing the storage, refused in the read-only mode, and records it in
+/// the audit log
+fn audited<T>(
+    read_only: bool,
+    action: &str,
+    target: &str,
+    payload: &[u8],
+    f: impl FnOnce() -> Result<T, DMError>,
+) -> Result<T, DMError> {
+    let result = if read_only {
+        Err(Report::new(DMError::ReadOnly)
+            .attach_printable(format!("Read-only mode, refused to {} {}", action, target)))
+    } else {
+        f()
+    };
+    audit::record(action, target, payload, AuditResult::of(&result));
+    result
+}
+
+/// Blob storage of the modules, the firmware packages and the files uploaded by the device
 pub struct AzuriteStorage {
     runtime: tokio::runtime::Runtime,
     blob_service_client: BlobServiceClient,
@@ -158,33 +615,51 @@ pub struct AzuriteStorage {
     action: Vec<AzuriteAction>,
     token_providers: HashMap<UUID, TokenProvider>,
     current_token_provider_id: usize,
+    /// Only the token providers matching it are listed and focused
+    token_provider_filter: String,
+    /// Blob listing whose next page can be requested
+    blob_listing: Option<BlobListing>,
+    /// Only the modules whose blob name matches it are listed and focused
+    module_filter: String,
+    module_sort: BlobSort,
+    read_only: bool,
 }
 
 #[allow(unused)]
 impl AzuriteStorage {
     pub fn new(azurite_url: &str) -> Result<Self, DMError> {
-        let runtime = tokio::runtime::Runtime::new().map_err(|e| {
-            Report::new(DMError::RuntimeError)
-                .attach_printable("Failed to create Tokio runtime")
-                .attach(e)
-        })?;
-
-        let credential = StorageCredentials::access_key(ACCOUNT_NAME, ACCOUNT_KEY);
+        Self::with_config(&AzuriteConfig::new(azurite_url))
+    }
 
+    /// Splits an Azurite url like "https://127.0.0.1:10000" into address and port
+    pub fn parse_url(azurite_url: &str) -> Result<(String, u16), DMError> {
         let (address, port) = azurite_url
             .trim_end_matches('/')
             .trim_start_matches("https://")
             .split_once(':')
-            .map(|(address, port)| {
-                let port: u16 = port
-                    .parse()
-                    .unwrap_or_else(|_| panic!("invalid port: {}", port));
-                (address.to_owned(), port)
-            })
             .ok_or_else(|| {
                 Report::new(DMError::InvalidData)
                     .attach_printable(format!("Invalid URL: {azurite_url}"))
             })?;
+
+        let port: u16 = port.parse().map_err(|_| {
+            Report::new(DMError::InvalidData).attach_printable(format!("Invalid port: {port}"))
+        })?;
+
+        Ok((address.to_owned(), port))
+    }
+
+    pub fn with_config(config: &AzuriteConfig) -> Result<Self, DMError> {
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| {
+            Report::new(DMError::RuntimeError)
+                .attach_printable("Failed to create Tokio runtime")
+                .attach(e)
+        })?;
+
+        let credential =
+            StorageCredentials::access_key(config.account_name.clone(), config.account_key.clone());
+
+        let (address, port) = Self::parse_url(&config.url)?;
         let client_builder =
             ClientBuilder::with_location(CloudLocation::Emulator { address, port }, credential);
 
@@ -197,11 +672,45 @@ impl AzuriteStorage {
             new_module: String::new(),
             token_providers: HashMap::new(),
             current_token_provider_id: 0,
+            token_provider_filter: String::new(),
+            blob_listing: None,
+            module_filter: String::new(),
+            module_sort: BlobSort::Name,
+            read_only: config.read_only,
         };
 
         Ok(azure_storage)
     }
 
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Checks that the storage endpoint answers with the configured credentials.
+    /// Returns the number of containers in the first listing page.
+    pub fn check_connection(&self) -> Result<usize, DMError> {
+        self.runtime.block_on(async {
+            let mut stream = self.blob_service_client.list_containers().into_stream();
+
+            tokio::select! {
+                _ = tokio::time::sleep(std::time::Duration::from_millis(500)) => {
+                    Err(Report::new(DMError::Timeout)
+                        .attach_printable("Timeout while connecting to Azurite storage"))
+                }
+
+                next = stream.next() => {
+                    match next {
+                        Some(Ok(response)) => Ok(response.containers.len()),
+                        Some(Err(e)) => Err(Report::new(DMError::IOError).attach_printable(
+                            format!("Failed to connect to Azurite storage: {}", e),
+                        )),
+                        None => Ok(0),
+                    }
+                }
+            }
+        })
+    }
+
     pub fn is_container_exists(&self, container_name: &str) -> bool {
         let container_client = self.blob_service_client.container_client(container_name);
         self.runtime.block_on(async {
@@ -263,39 +772,105 @@ impl AzuriteStorage {
         })
     }
 
-    pub fn create_container(&self, container_name: &str) -> Result<(), DMError> {
+    /// Lists the containers with their metadata
+    pub fn list_containers_with_metadata(&self) -> Vec<(String, HashMap<String, String>)> {
+        let mut result = Vec::new();
         self.runtime.block_on(async {
-            tokio::select! {
-                _ = tokio::time::sleep(std::time::Duration::from_millis(500)) => {
-                    jerror!("Timeout while creating container, returning error");
-                    Err(Report::new(DMError::Timeout))
-                }
+            let mut stream = self
+                .blob_service_client
+                .list_containers()
+                .include_metadata(true)
+                .into_stream();
 
-                response = self.blob_service_client.container_client(container_name).create() => {
-                    response.map_err(|e| {
-                        Report::new(DMError::IOError).attach_printable(format!(
-                            "Failed to create container '{}': {}",
-                            container_name, e
-                        ))
-                    })
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(std::time::Duration::from_millis(500)) => {
+                        jerror!("Timeout while listing containers, returning partial list");
+                        break;
+                    }
+
+                    next = stream.next() => {
+                        if let Some(Ok(response)) = next {
+                            for container in response.containers {
+                                result.push((container.name, container.metadata));
+                            }
+                        } else {
+                            break;
+                        }
+                    }
                 }
             }
+
+            result
         })
     }
 
+    pub fn create_container(&self, container_name: &str) -> Result<(), DMError> {
+        self.create_container_with_metadata(container_name, &[])
+    }
+
+    pub fn create_container_with_metadata(
+        &self,
+        container_name: &str,
+        metadata: &[(&str, String)],
+    ) -> Result<(), DMError> {
+        let mut container_metadata = Metadata::new();
+        for (key, value) in metadata {
+            container_metadata.insert(*key, value.clone());
+        }
+
+        audited(
+            self.read_only,
+            "create container",
+            container_name,
+            &[],
+            || {
+                self.runtime.block_on(async {
+                    tokio::select! {
+                        _ = tokio::time::sleep(std::time::Duration::from_millis(500)) => {
+                            jerror!("Timeout while creating container, returning error");
+                            Err(Report::new(DMError::Timeout))
+                        }
+
+                        response = self
+                            .blob_service_client
+                            .container_client(container_name)
+                            .create()
+                            .metadata(container_metadata) => {
+                            response.map_err(|e| {
+                                Report::new(DMError::IOError).attach_printable(format!(
+                                    "Failed to create container '{}': {}",
+                                    container_name, e
+                                ))
+                            })
+                        }
+                    }
+                })
+            },
+        )
+    }
+
     pub fn delete_container(&self, container_name: &str) -> Result<(), DMError> {
-        self.runtime.block_on(async {
-            self.blob_service_client
-                .container_client(container_name)
-                .delete()
-                .await
-                .map_err(|e| {
-                    Report::new(DMError::IOError).attach_printable(format!(
-                        "Failed to delete container '{}': {}",
-                        container_name, e
-                    ))
+        audited(
+            self.read_only,
+            "delete container",
+            container_name,
+            &[],
+            || {
+                self.runtime.block_on(async {
+                    self.blob_service_client
+                        .container_client(container_name)
+                        .delete()
+                        .await
+                        .map_err(|e| {
+                            Report::new(DMError::IOError).attach_printable(format!(
+                                "Failed to delete container '{}': {}",
+                                container_name, e
+                            ))
+                        })
                 })
-        })
+            },
+        )
     }
 
     pub fn container_url(&self, container_name: &str) -> Result<String, DMError> {
@@ -331,6 +906,108 @@ impl AzuriteStorage {
         &mut self,
         container_name: Option<&str>,
         file_path: &str,
+    ) -> Result<(), DMError> {
+        let file_name = Self::blob_name_of(file_path)?;
+        self.put_blob(
+            container_name.unwrap_or("default"),
+            file_path,
+            &file_name,
+            None,
+        )
+    }
+
+    fn blob_name_of(file_path: &str) -> Result<String, DMError> {
+        std::path::Path::new(file_path)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_owned())
+            .ok_or_else(|| {
+                Report::new(DMError::InvalidData)
+                    .attach_printable("Failed to extract file name from the provided path")
+            })
+    }
+
+    /// ETag of the blob, None when it doesn't exist
+    pub fn blob_etag(&self, container_name: &str, blob: &str) -> Result<Option<String>, DMError> {
+        let blob_client = self
+            .blob_service_client
+            .container_client(container_name)
+            .blob_client(blob);
+        let failed = |e: &dyn std::fmt::Display| {
+            Report::new(DMError::IOError).attach_printable(format!(
+                "Failed to get the properties of blob '{}' of container '{}': {}",
+                blob, container_name, e
+            ))
+        };
+
+        self.runtime.block_on(async {
+            tokio::select! {
+                _ = tokio::time::sleep(std::time::Duration::from_secs(10)) => {
+                    Err(Report::new(DMError::Timeout)
+                        .attach_printable("Timeout while getting blob properties"))
+                }
+
+                response = async {
+                    match blob_client.exists().await {
+                        Ok(true) => blob_client
+                            .get_properties()
+                            .await
+                            .map(|r| Some(r.blob.properties.etag.to_string()))
+                            .map_err(|e| failed(&e)),
+                        Ok(false) => Ok(None),
+                        Err(e) => Err(failed(&e)),
+                    }
+                } => response
+            }
+        })
+    }
+
+    /// Uploads a local file unless a blob of the same name exists, in which case the policy
+    /// decides whether it is replaced, renamed or reported as a conflict
+    pub fn push_blob_guarded(
+        &mut self,
+        container_name: Option<&str>,
+        file_path: &str,
+        policy: &OverwritePolicy,
+    ) -> Result<PushOutcome, DMError> {
+        let container_name = container_name.unwrap_or("default");
+        let file_name = Self::blob_name_of(file_path)?;
+
+        let (blob, condition) = match policy {
+            OverwritePolicy::Refuse => match self.blob_etag(container_name, &file_name)? {
+                Some(etag) => {
+                    return Ok(PushOutcome::Conflict(BlobConflict {
+                        path: file_path.to_owned(),
+                        blob: file_name,
+                        etag,
+                    }));
+                }
+                // Fails if the blob is created before the upload
+                None => (file_name, IfMatchCondition::NotMatch("*".to_owned())),
+            },
+            OverwritePolicy::IfMatch(etag) => (file_name, IfMatchCondition::Match(etag.clone())),
+            OverwritePolicy::Rename => {
+                let mut blob = file_name.clone();
+                let mut n = 0;
+                while self.blob_etag(container_name, &blob)?.is_some() {
+                    n += 1;
+                    blob = suffixed_blob_name(&file_name, n);
+                }
+                (blob, IfMatchCondition::NotMatch("*".to_owned()))
+            }
+        };
+
+        self.put_blob(container_name, file_path, &blob, Some(condition))?;
+        Ok(PushOutcome::Uploaded(blob))
+    }
+
+    /// Uploads a local file as the blob, the condition is checked against its ETag
+    fn put_blob(
+        &mut self,
+        container_name: &str,
+        file_path: &str,
+        blob: &str,
+        condition: Option<IfMatchCondition>,
     ) -> Result<(), DMError> {
         let file = std::fs::File::open(file_path).map_err(|e| {
             Report::new(DMError::IOError).attach_printable(format!("Failed to open file: {}", e))
@@ -347,23 +1024,28 @@ impl AzuriteStorage {
         let hash_result = hasher.finalize();
         let hash = format!("{:x}", hash_result);
         let hash_base64 = general_purpose::STANDARD.encode(hash_result);
-        let container_name = container_name.unwrap_or("default");
 
-        self.create_container_if_not_exists(container_name)
-            .map_err(|e| {
-                Report::new(DMError::IOError)
-                    .attach_printable(format!("Failed to create container '{}'", container_name))
-                    .attach(e)
-            })?;
+        let content = Bytes::from(buf);
+        let target = format!("{}/{}", container_name, blob);
+        audited(self.read_only, "upload blob", &target, &content, || {
+            self.create_container_if_not_exists(container_name)
+                .map_err(|e| {
+                    Report::new(DMError::IOError)
+                        .attach_printable(format!(
+                            "Failed to create container '{}'",
+                            container_name
+                        ))
+                        .attach(e)
+                })?;
 
-        if let Some(file_name) = std::path::Path::new(file_path)
-            .file_name()
-            .and_then(|s| s.to_str())
-        {
             let blob_client = self
                 .blob_service_client
                 .container_client(container_name)
-                .blob_client(file_name);
+                .blob_client(blob);
+            let mut put = blob_client.put_block_blob(content.clone());
+            if let Some(condition) = condition {
+                put = put.if_match(condition);
+            }
 
             self.runtime.block_on(async {
                 tokio::select! {
@@ -372,7 +1054,7 @@ impl AzuriteStorage {
                             Err(Report::new(DMError::Timeout))
                         }
 
-                        response = blob_client.put_block_blob(Bytes::from(buf.clone())) => {
+                        response = put => {
                             response.map_err(|e| {
                                 Report::new(DMError::IOError).attach_printable(format!(
                                     "Failed to upload file to container '{}': {}",
@@ -381,26 +1063,24 @@ impl AzuriteStorage {
                             })
                         }
                 }
-            })?;
+            })
+        })?;
 
-            let module_info = ModuleInfo {
-                id: UUID::new(),
-                blob_name: file_path.to_string(),
-                container_name: container_name.to_string(),
-                hash,
-                hash_base64,
-                sas_url: String::new(), // Will be set later if needed
-                size,
-            };
+        let module_info = ModuleInfo {
+            id: UUID::new(),
+            blob_name: file_path.to_string(),
+            container_name: container_name.to_string(),
+            hash,
+            hash_base64,
+            sas_url: String::new(), // Will be set later if needed
+            size,
+            last_modified: None,
+        };
 
-            self.module_info_db
-                .insert(module_info.id.clone(), module_info);
+        self.module_info_db
+            .insert(module_info.id.clone(), module_info);
 
-            Ok(())
-        } else {
-            Err(Report::new(DMError::InvalidData)
-                .attach_printable("Failed to extract file name from the provided path"))
-        }
+        Ok(())
     }
 
     pub fn get_blob(&self, container_name: Option<&str>, blob: &str) -> Result<Vec<u8>, DMError> {
@@ -429,27 +1109,31 @@ impl AzuriteStorage {
     }
 
     pub fn remove_blob(&self, container_name: Option<&str>, blob: &str) -> Result<(), DMError> {
+        let container_name = container_name.unwrap_or("default");
         let blob_client = self
             .blob_service_client
-            .container_client(container_name.unwrap_or("default"))
+            .container_client(container_name)
             .blob_client(blob);
 
-        self.runtime.block_on(async {
-            tokio::select! {
-                    _ = tokio::time::sleep(std::time::Duration::from_secs(10)) => {
-                        jerror!("Timeout while uploading blob, returning error");
-                        Err(Report::new(DMError::Timeout))
-                    }
+        let target = format!("{}/{}", container_name, blob);
+        audited(self.read_only, "delete blob", &target, &[], || {
+            self.runtime.block_on(async {
+                tokio::select! {
+                        _ = tokio::time::sleep(std::time::Duration::from_secs(10)) => {
+                            jerror!("Timeout while uploading blob, returning error");
+                            Err(Report::new(DMError::Timeout))
+                        }
 
-                    response = blob_client.delete() => {
-                        response.map_err(|e| {
-                            Report::new(DMError::IOError).attach_printable(format!(
-                                "Failed to delete file from container '{}': {}",
-                                container_name.unwrap_or("default"), e
-                            ))
-                        })
-                    }
-            }
+                        response = blob_client.delete() => {
+                            response.map_err(|e| {
+                                Report::new(DMError::IOError).attach_printable(format!(
+                                    "Failed to delete file from container '{}': {}",
+                                    container_name, e
+                                ))
+                            })
+                        }
+                }
+            })
         })?;
 
         Ok(())
@@ -531,18 +1215,7 @@ impl AzuriteStorage {
                             Some(Ok(response)) => {
                                 for blob in response.blobs.items.iter() {
                                     if let BlobItem::Blob(blob_item) = blob {
-                                        let ui_blob = UiBlob {
-                                            name: blob_item.name.clone(),
-                                            created_on: {
-                                                // Convert Azure's OffsetDateTime to chrono DateTime
-                                                let creation_time = blob_item.properties.creation_time;
-                                                chrono::DateTime::parse_from_rfc3339(&creation_time.to_string())
-                                                    .map(|parsed| parsed.with_timezone(&chrono::Utc))
-                                                    .unwrap_or_else(|_| chrono::Utc::now())
-                                            },
-                                            size: blob_item.properties.content_length,
-                                        };
-                                        result.push(ui_blob);
+                                        result.push(UiBlob::new(blob_item));
                                     }
                                 }
                             }
@@ -556,56 +1229,317 @@ impl AzuriteStorage {
                 }
             }
 
-            // Sort by creation time (newest first)
-            result.sort_by(|a, b| b.created_on.cmp(&a.created_on));
+            // Sort by creation time (newest first)
+            result.sort_by(|a, b| b.created_on.cmp(&a.created_on));
+
+            // Limit to 100 items
+            if result.len() > 100 {
+                result.truncate(100);
+            }
+
+            Ok(result)
+        })
+    }
+
+    /// Lists a page of the blobs of a container, the first one when `marker` is None, else
+    /// the one following the page which returned `marker`. Only the listing started last
+    /// can be continued.
+    pub fn list_blobs_page(
+        &mut self,
+        container_name: &str,
+        marker: Option<&str>,
+    ) -> Result<BlobPage, DMError> {
+        let mut listing = match marker {
+            None => {
+                self.create_container_if_not_exists(container_name)
+                    .map_err(|e| {
+                        Report::new(DMError::IOError)
+                            .attach_printable(format!(
+                                "Failed to create container '{}'",
+                                container_name
+                            ))
+                            .attach(e)
+                    })?;
+
+                let pages = self
+                    .blob_service_client
+                    .container_client(container_name)
+                    .list_blobs()
+                    .max_results(NonZeroU32::new(BLOB_PAGE_SIZE).unwrap())
+                    .into_stream()
+                    .map(|response| {
+                        response
+                            .map(|response| BlobPage {
+                                blobs: response
+                                    .blobs
+                                    .items
+                                    .iter()
+                                    .filter_map(|blob| match blob {
+                                        BlobItem::Blob(blob_item) => Some(UiBlob::new(blob_item)),
+                                        _ => None,
+                                    })
+                                    .collect(),
+                                next_marker: response
+                                    .next_marker
+                                    .map(|marker| marker.as_str().to_owned()),
+                            })
+                            .map_err(|e| e.to_string())
+                    })
+                    .boxed();
+
+                BlobListing {
+                    container: container_name.to_owned(),
+                    next_marker: None,
+                    pages,
+                }
+            }
+            Some(marker) => match self.blob_listing.take() {
+                Some(listing)
+                    if listing.container == container_name
+                        && listing.next_marker.as_deref() == Some(marker) =>
+                {
+                    listing
+                }
+                _ => {
+                    return Err(Report::new(DMError::InvalidData).attach_printable(format!(
+                        "The listing of the blobs of '{}' has expired, list them again",
+                        container_name
+                    )));
+                }
+            },
+        };
+
+        let page = self.runtime.block_on(async {
+            tokio::select! {
+                _ = tokio::time::sleep(std::time::Duration::from_secs(5)) => {
+                    Err(Report::new(DMError::Timeout)
+                        .attach_printable("Failed to list blobs: timeout after 5s"))
+                }
 
-            // Limit to 100 items
-            if result.len() > 100 {
-                result.truncate(100);
+                response = listing.pages.next() => {
+                    match response {
+                        Some(Ok(page)) => Ok(page),
+                        Some(Err(e)) => Err(Report::new(DMError::IOError)
+                            .attach_printable(format!("Failed to list blobs: {}", e))),
+                        None => Ok(BlobPage::default()),
+                    }
+                }
             }
+        })?;
 
-            Ok(result)
-        })
+        if page.next_marker.is_some() {
+            listing.next_marker = page.next_marker.clone();
+            self.blob_listing = Some(listing);
+        }
+
+        Ok(page)
     }
 
-    pub fn download_blob_to_current_dir(
+    /// Downloads a blob to `dir`, the current directory if None.
+    ///
+    /// The blob is written by ranges to a ".part" file next to the destination, so that a
+    /// download interrupted by a slow link resumes where it stopped as long as the blob has
+    /// not changed. The content is checked against the Content-MD5 of the blob, or the
+    /// SHA-256 of the module stored as the blob, before the file is renamed.
+    pub fn download_blob(
         &self,
         container_name: &str,
         blob_name: &str,
-    ) -> Result<String, DMError> {
-        let blob_data = self.get_blob(Some(container_name), blob_name)?;
-
-        let current_dir = std::env::current_dir().map_err(|e| {
-            Report::new(DMError::IOError)
-                .attach_printable(format!("Failed to get current directory: {}", e))
+        dir: Option<&str>,
+        progress: &mut dyn FnMut(u64, u64),
+    ) -> Result<Download, DMError> {
+        let dir = match dir {
+            Some(dir) => std::path::PathBuf::from(dir),
+            None => std::env::current_dir().map_err(|e| {
+                Report::new(DMError::IOError)
+                    .attach_printable(format!("Failed to get current directory: {}", e))
+            })?,
+        };
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            Report::new(DMError::IOError).attach_printable(format!(
+                "Failed to create directory '{}': {}",
+                dir.display(),
+                e
+            ))
         })?;
 
         // Extract the file name from the blob name
-        let file_name = blob_name.split('/').last().ok_or_else(|| {
-            Report::new(DMError::InvalidData)
-                .attach_printable("Blob name does not contain a valid file name")
+        let file_name = blob_name
+            .split('/')
+            .last()
+            .filter(|n| !n.is_empty())
+            .ok_or_else(|| {
+                Report::new(DMError::InvalidData)
+                    .attach_printable("Blob name does not contain a valid file name")
+            })?;
+        let file_path = dir.join(file_name);
+        let part_path = partial_path(&file_path);
+        let etag_path = partial_etag_path(&file_path);
+        let io_error = |what: &str, path: &std::path::Path, e: std::io::Error| {
+            Report::new(DMError::IOError).attach_printable(format!(
+                "Failed to {} '{}': {}",
+                what,
+                path.display(),
+                e
+            ))
+        };
+
+        let blob_client = self
+            .blob_service_client
+            .container_client(container_name)
+            .blob_client(blob_name);
+
+        let properties = self.runtime.block_on(async {
+            tokio::select! {
+                _ = tokio::time::sleep(std::time::Duration::from_secs(10)) => {
+                    Err(Report::new(DMError::Timeout)
+                        .attach_printable("Timeout while getting blob properties"))
+                }
+
+                response = blob_client.get_properties() => {
+                    response.map(|r| r.blob.properties).map_err(|e| {
+                        Report::new(DMError::IOError).attach_printable(format!(
+                            "Failed to get the properties of blob '{}' of container '{}': {}",
+                            blob_name, container_name, e
+                        ))
+                    })
+                }
+            }
         })?;
-        let file_path = current_dir.join(file_name);
+        let size = properties.content_length;
+        let etag = properties.etag.to_string();
+
+        let partial_len = std::fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+        let partial_etag = std::fs::read_to_string(&etag_path).ok();
+        let resumed = resume_offset(partial_len, partial_etag.as_deref(), &etag, size);
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(resumed > 0)
+            .write(true)
+            .truncate(resumed == 0)
+            .open(&part_path)
+            .map_err(|e| io_error("open", &part_path, e))?;
+        if resumed == 0 {
+            std::fs::write(&etag_path, &etag).map_err(|e| io_error("write", &etag_path, e))?;
+        }
 
         jdebug!(
-            func = "AzuriteStorage::download_blob_to_current_dir()",
+            func = "AzuriteStorage::download_blob()",
             line = line!(),
             message = format!(
-                "Writing blob '{}' to file: {}",
+                "Downloading blob '{}' ({} bytes) to '{}' from offset {}",
                 blob_name,
-                file_path.display()
+                size,
+                file_path.display(),
+                resumed
             ),
         );
 
-        std::fs::write(&file_path, blob_data).map_err(|e| {
+        let mut offset = resumed;
+        progress(offset, size);
+        if offset < size {
+            // The ranges are requested only while the blob is the version the partial file
+            // was started from
+            let mut chunks = blob_client
+                .get()
+                .range(offset..size)
+                .chunk_size(DOWNLOAD_CHUNK_SIZE)
+                .if_match(IfMatchCondition::Match(etag.clone()))
+                .into_stream();
+
+            self.runtime.block_on(async {
+                while offset < size {
+                    let chunk = tokio::select! {
+                        _ = tokio::time::sleep(DOWNLOAD_CHUNK_TIMEOUT) => {
+                            Err(format!("timeout after {}s", DOWNLOAD_CHUNK_TIMEOUT.as_secs()))
+                        }
+
+                        response = async {
+                            match chunks.next().await {
+                                Some(Ok(response)) => {
+                                    response.data.collect().await.map_err(|e| e.to_string())
+                                }
+                                Some(Err(e)) => Err(e.to_string()),
+                                None => Err("the blob ended before its size".to_owned()),
+                            }
+                        } => response
+                    };
+
+                    let chunk = chunk.map_err(|e| {
+                        Report::new(DMError::IOError).attach_printable(format!(
+                            "Download of blob '{}' interrupted at {}/{} bytes, download it again to resume: {}",
+                            blob_name, offset, size, e
+                        ))
+                    })?;
+                    file.write_all(&chunk)
+                        .map_err(|e| io_error("write to", &part_path, e))?;
+                    offset += chunk.len() as u64;
+                    progress(offset, size);
+                }
+                Ok::<(), Report<DMError>>(())
+            })?;
+        }
+        drop(file);
+
+        let (md5, sha256) = Self::file_digests(&part_path)?;
+        let expected_md5 = properties
+            .content_md5
+            .as_ref()
+            .map(|m| m.as_slice().to_vec());
+        let expected_sha256 = self
+            .module_of_blob(container_name, blob_name)
+            .map(|m| m.hash.clone());
+        let integrity = check_integrity(
+            &md5,
+            &sha256,
+            expected_md5.as_deref(),
+            expected_sha256.as_deref(),
+        )
+        .inspect_err(|_| {
+            // A corrupted partial file can't be resumed
+            let _ = std::fs::remove_file(&part_path);
+            let _ = std::fs::remove_file(&etag_path);
+        })?;
+
+        std::fs::rename(&part_path, &file_path).map_err(|e| io_error("rename", &part_path, e))?;
+        let _ = std::fs::remove_file(&etag_path);
+
+        Ok(Download {
+            path: file_path.to_string_lossy().to_string(),
+            size,
+            resumed,
+            integrity,
+        })
+    }
+
+    /// MD5 and hexadecimal SHA-256 of a local file, read by chunks
+    fn file_digests(path: &std::path::Path) -> Result<(Vec<u8>, String), DMError> {
+        let mut file = std::fs::File::open(path).map_err(|e| {
             Report::new(DMError::IOError).attach_printable(format!(
-                "Failed to write to '{}' : {}",
-                file_path.display(),
+                "Failed to open '{}': {}",
+                path.display(),
                 e
             ))
         })?;
-
-        Ok(file_path.to_string_lossy().to_string())
+        let mut md5 = Md5::new();
+        let mut sha256 = Sha256::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf).map_err(|e| {
+                Report::new(DMError::IOError).attach_printable(format!(
+                    "Failed to read '{}': {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            if n == 0 {
+                break;
+            }
+            md5.update(&buf[..n]);
+            sha256.update(&buf[..n]);
+        }
+        Ok((md5.finalize().to_vec(), format!("{:x}", sha256.finalize())))
     }
 
     pub fn get_sas_url(
@@ -665,20 +1599,7 @@ impl AzuriteStorage {
     }
 
     pub fn is_sas_url_valid(sas_url: &str) -> bool {
-        // Extract the raw 'se' query value from the URL string directly to avoid URL
-        // query-pair decoding rules that turn '+' into space (which breaks RFC3339 offsets).
-        if let Some(pos) = sas_url.find("se=") {
-            let rest = &sas_url[pos + 3..];
-            let end = rest.find('&').unwrap_or(rest.len());
-            let expire_raw = rest[..end].trim();
-
-            if let Ok(expire_time) = chrono::DateTime::parse_from_rfc3339(expire_raw) {
-                let now = chrono::Utc::now();
-                return expire_time > now;
-            }
-        }
-
-        false
+        sas::SasInfo::parse(sas_url).is_some_and(|sas| !sas.is_expired(chrono::Utc::now()))
     }
 
     pub fn update_modules(&mut self, container_name: Option<&str>) -> Result<(), DMError> {
@@ -698,6 +1619,7 @@ impl AzuriteStorage {
                 );
                 let uuid = uuid.clone();
                 let mut info = info.clone();
+                info.last_modified = Some(to_chrono(blob.properties.last_modified));
 
                 if info.sas_url.is_empty() || !AzuriteStorage::is_sas_url_valid(&info.sas_url) {
                     let blob_client = self
@@ -760,6 +1682,7 @@ impl AzuriteStorage {
                     hash_base64,
                     sas_url,
                     size,
+                    last_modified: Some(to_chrono(blob.properties.last_modified)),
                 };
                 new_module_info_db.insert(module_id, module_info);
             } else {
@@ -785,6 +1708,37 @@ impl AzuriteStorage {
         &self.module_info_db
     }
 
+    /// Module stored as the blob, if it is a known one
+    pub fn module_of_blob(&self, container_name: &str, blob: &str) -> Option<&ModuleInfo> {
+        self.module_info_db
+            .values()
+            .find(|m| m.container_name == container_name && m.blob_name == blob)
+    }
+
+    /// Issues a new SAS URL for the module and computes its hash and size again from the
+    /// blob content, in case the blob has been replaced since the module was listed.
+    pub fn refresh_module(&mut self, id: &UUID) -> Result<ModuleInfo, DMError> {
+        let mut info = self.module_info_db.get(id).cloned().ok_or_else(|| {
+            Report::new(DMError::InvalidData).attach_printable(format!("Unknown module {id}"))
+        })?;
+
+        let buf = self.get_blob(Some(&info.container_name), &info.blob_name)?;
+        let hash_result = Sha256::digest(&buf);
+        info.hash = format!("{:x}", hash_result);
+        info.hash_base64 = general_purpose::STANDARD.encode(hash_result);
+        info.size = buf.len();
+        info.sas_url = self.get_sas_url(&info.container_name, &info.blob_name, None, None)?;
+
+        self.module_info_db.insert(id.clone(), info.clone());
+        Ok(info)
+    }
+
+    /// Replaces the modules with the ones updated by the worker
+    pub fn set_module_info_db(&mut self, module_info_db: HashMap<UUID, ModuleInfo>) {
+        self.module_info_db = module_info_db;
+        self.current_module_id = 0;
+    }
+
     pub fn action(&self) -> Option<AzuriteAction> {
         self.action.last().cloned()
     }
@@ -797,12 +1751,53 @@ impl AzuriteStorage {
         self.action.pop();
     }
 
+    /// Modules matching the filter, in the order they are listed
+    pub fn listed_modules(&self) -> Vec<(&UUID, &ModuleInfo)> {
+        let mut modules: Vec<(&UUID, &ModuleInfo)> = self
+            .module_info_db
+            .iter()
+            .filter(|(_, m)| blob_name_matches(&m.blob_name, &self.module_filter))
+            .collect();
+        modules.sort_by(|(_, a), (_, b)| {
+            self.module_sort.compare(
+                (&a.blob_name, a.size as u64, a.last_modified),
+                (&b.blob_name, b.size as u64, b.last_modified),
+            )
+        });
+        modules
+    }
+
+    pub fn module_filter(&self) -> &str {
+        &self.module_filter
+    }
+
+    pub fn set_module_filter(&mut self, filter: &str) {
+        self.module_filter = filter.to_owned();
+        self.current_module_id = 0;
+    }
+
+    pub fn module_sort(&self) -> BlobSort {
+        self.module_sort
+    }
+
+    /// Sorts the modules by the next column, the focused module stays focused
+    pub fn sort_modules_by_next(&mut self) {
+        let focused = self.current_module().map(|m| m.id.clone());
+        self.module_sort = self.module_sort.next();
+        self.current_module_id = self
+            .listed_modules()
+            .iter()
+            .position(|(uuid, _)| Some(*uuid) == focused.as_ref())
+            .unwrap_or(0);
+    }
+
     pub fn current_module_focus_init(&mut self) {
         self.current_module_id = 0;
     }
 
     pub fn current_module_focus_down(&mut self) {
-        if self.current_module_id < self.module_info_db.len() - 1 {
+        let count = self.listed_modules().len();
+        if self.current_module_id < count.saturating_sub(1) {
             self.current_module_id += 1;
         } else {
             self.current_module_id = 0;
@@ -811,14 +1806,16 @@ impl AzuriteStorage {
 
     pub fn current_module_focus_up(&mut self) {
         if self.current_module_id == 0 {
-            self.current_module_id = self.module_info_db.len() - 1;
+            self.current_module_id = self.listed_modules().len().saturating_sub(1);
         } else {
             self.current_module_id -= 1;
         }
     }
 
     pub fn current_module(&self) -> Option<&ModuleInfo> {
-        self.module_info_db.values().nth(self.current_module_id)
+        self.listed_modules()
+            .get(self.current_module_id)
+            .map(|(_, m)| *m)
     }
 
     pub fn current_module_id(&self) -> usize {
@@ -834,28 +1831,13 @@ impl AzuriteStorage {
     }
 
     pub fn scan_upload_containers(&mut self) -> Result<(), DMError> {
-        let containers = self.list_containers();
+        let containers = self.list_containers_with_metadata();
         let mut new_token_providers = HashMap::new();
 
-        for container_name in containers {
-            if container_name.starts_with("upload") {
-                if let Ok(uuid_str) = container_name
-                    .strip_prefix("upload")
-                    .unwrap_or("")
-                    .trim_start_matches('-')
-                    .parse::<uuid::Uuid>()
-                {
-                    let uuid = match UUID::from(&uuid_str.to_string()) {
-                        Ok(uuid) => uuid,
-                        Err(_) => continue,
-                    };
-
-                    let token_provider = TokenProvider {
-                        uuid: uuid.clone(),
-                        container: container_name.clone(),
-                    };
-                    new_token_providers.insert(uuid, token_provider);
-                }
+        for (container_name, metadata) in containers {
+            if let Some(token_provider) = TokenProvider::from_container(&container_name, &metadata)
+            {
+                new_token_providers.insert(token_provider.uuid.clone(), token_provider);
             }
         }
 
@@ -864,11 +1846,31 @@ impl AzuriteStorage {
         Ok(())
     }
 
-    pub fn add_token_provider(&mut self) -> Result<UUID, DMError> {
+    /// Creates a token provider and its upload container, named `upload-<UUID>` unless a
+    /// container name is given. The label is stored in the metadata of the container.
+    pub fn add_token_provider(
+        &mut self,
+        container: Option<&str>,
+        label: TokenProviderLabel,
+    ) -> Result<UUID, DMError> {
+        label.validate()?;
+
         let uuid = UUID::new();
-        let container_name = format!("upload-{}", uuid.uuid());
+        let container_name = match container.map(str::trim).filter(|c| !c.is_empty()) {
+            Some(container) => {
+                validate_container_name(container)?;
+                container.to_owned()
+            }
+            None => format!("upload-{}", uuid.uuid()),
+        };
+
+        let token_provider = TokenProvider {
+            uuid: uuid.clone(),
+            container: container_name.clone(),
+            label,
+        };
 
-        self.create_container(&container_name)?;
+        self.create_container_with_metadata(&container_name, &token_provider.metadata())?;
 
         // Create SAS URL with write permissions for short-lived access (1 hour)
         let token_permissions = BlobSasPermissions {
@@ -885,39 +1887,58 @@ impl AzuriteStorage {
         // generation path and validates the container.
         let _ = self.get_sas_url(&container_name, "", Some(token_permissions), Some(one_hour))?;
 
-        let token_provider = TokenProvider {
-            uuid: uuid.clone(),
-            container: container_name.clone(),
-        };
-
         self.token_providers.insert(uuid.clone(), token_provider);
         Ok(uuid)
     }
 
     pub fn remove_token_provider(&mut self, uuid: &UUID) -> Result<(), DMError> {
+        if let Some(token_provider) = self.token_providers.get(uuid) {
+            self.delete_container(&token_provider.container)?;
+            self.forget_token_provider(uuid);
+        }
+        Ok(())
+    }
+
+    /// Drops a token provider whose container has been deleted by the worker
+    pub fn forget_token_provider(&mut self, uuid: &UUID) {
         if self.token_providers.remove(uuid).is_some() {
-            let container_name = format!("upload-{}", uuid.uuid());
-            self.delete_container(&container_name)?;
-
-            if self.current_token_provider_id >= self.token_providers.len() {
-                self.current_token_provider_id = if self.token_providers.is_empty() {
-                    0
-                } else {
-                    self.token_providers.len() - 1
-                };
+            let count = self.filtered_token_providers().count();
+            if self.current_token_provider_id >= count {
+                self.current_token_provider_id = count.saturating_sub(1);
             }
         }
-        Ok(())
     }
 
     pub fn token_providers(&self) -> &HashMap<UUID, TokenProvider> {
         &self.token_providers
     }
 
-    pub fn current_token_provider(&self) -> Option<&TokenProvider> {
+    /// Replaces the token providers with the ones scanned by the worker
+    pub fn set_token_providers(&mut self, token_providers: HashMap<UUID, TokenProvider>) {
+        self.token_providers = token_providers;
+        self.current_token_provider_id = 0;
+    }
+
+    /// Token providers matching the filter, in the order they are listed
+    pub fn filtered_token_providers(&self) -> impl Iterator<Item = (&UUID, &TokenProvider)> {
         self.token_providers
-            .values()
+            .iter()
+            .filter(|(_, tp)| tp.matches(&self.token_provider_filter))
+    }
+
+    pub fn token_provider_filter(&self) -> &str {
+        &self.token_provider_filter
+    }
+
+    pub fn set_token_provider_filter(&mut self, filter: &str) {
+        self.token_provider_filter = filter.to_owned();
+        self.current_token_provider_id = 0;
+    }
+
+    pub fn current_token_provider(&self) -> Option<&TokenProvider> {
+        self.filtered_token_providers()
             .nth(self.current_token_provider_id)
+            .map(|(_, tp)| tp)
     }
 
     pub fn current_token_provider_id(&self) -> usize {
@@ -929,7 +1950,8 @@ impl AzuriteStorage {
     }
 
     pub fn current_token_provider_focus_down(&mut self) {
-        if self.current_token_provider_id < self.token_providers.len().saturating_sub(1) {
+        let count = self.filtered_token_providers().count();
+        if self.current_token_provider_id < count.saturating_sub(1) {
             self.current_token_provider_id += 1;
         } else {
             self.current_token_provider_id = 0;
@@ -938,16 +1960,17 @@ impl AzuriteStorage {
 
     pub fn current_token_provider_focus_up(&mut self) {
         if self.current_token_provider_id == 0 {
-            self.current_token_provider_id = self.token_providers.len().saturating_sub(1);
+            self.current_token_provider_id =
+                self.filtered_token_providers().count().saturating_sub(1);
         } else {
             self.current_token_provider_id -= 1;
         }
     }
 
     pub fn get_current_token_provider_by_highlight(&self) -> Option<&UUID> {
-        self.token_providers
-            .keys()
+        self.filtered_token_providers()
             .nth(self.current_token_provider_id)
+            .map(|(uuid, _)| uuid)
     }
 }
 #[cfg(test)]
@@ -959,6 +1982,54 @@ mod tests {
         assert_eq!(AzuriteAction::default(), AzuriteAction::Deploy);
     }
 
+    #[test]
+    fn test_azurite_config_defaults() {
+        let config = AzuriteConfig::new("https://127.0.0.1:10000");
+        assert_eq!(config.url, "https://127.0.0.1:10000");
+        assert_eq!(config.account_name, ACCOUNT_NAME);
+        assert_eq!(config.account_key, ACCOUNT_KEY);
+    }
+
+    #[test]
+    fn test_parse_url() {
+        let (address, port) = AzuriteStorage::parse_url("https://192.168.1.2:10000/").unwrap();
+        assert_eq!(address, "192.168.1.2");
+        assert_eq!(port, 10000);
+
+        // Invalid input is reported as an error instead of panicking
+        assert!(AzuriteStorage::parse_url("https://192.168.1.2").is_err());
+        assert!(AzuriteStorage::parse_url("https://192.168.1.2:port").is_err());
+        assert!(AzuriteStorage::new("https://192.168.1.2:99999").is_err());
+    }
+
+    #[test]
+    fn test_reconfigure_failure_keeps_storage() {
+        // The endpoint is not contacted, the check fails as an unreachable one does
+        let mut config = AzuriteConfig::new("https://127.0.0.1:1");
+        let mut checked = false;
+        assert!(
+            reconfigure_with(config.clone(), |_| {
+                checked = true;
+                Err(Report::new(DMError::Timeout))
+            })
+            .is_err()
+        );
+        assert!(checked);
+
+        // Invalid settings are rejected before any check
+        config.url = "https://127.0.0.1:port".to_owned();
+        assert!(reconfigure_with(config, |_| unreachable!()).is_err());
+
+        // The settings are only replaced once the new endpoint answers
+        assert!(
+            GLOBAL_AZURITE_CONFIG.get().is_none_or(|config| !config
+                .lock()
+                .unwrap()
+                .url
+                .starts_with("https://127.0.0.1:"))
+        );
+    }
+
     #[test]
     fn test_ui_blob_creation() {
         let blob = UiBlob {
@@ -972,59 +2043,6 @@ mod tests {
         assert!(blob.created_on <= chrono::Utc::now());
     }
 
-    #[test]
-    fn test_blob_list_state_navigation() {
-        use crate::app::ui::ui_token_provider_blobs::BlobListState;
-        let mut state = BlobListState::new("test-container".to_string());
-
-        // Test with empty list
-        assert_eq!(state.selected_index, 0);
-        state.move_up();
-        assert_eq!(state.selected_index, 0);
-        state.move_down();
-        assert_eq!(state.selected_index, 0);
-
-        // Add some test blobs
-        state.blobs = vec![
-            UiBlob {
-                name: "blob1.txt".to_string(),
-                created_on: chrono::Utc::now(),
-                size: 100,
-            },
-            UiBlob {
-                name: "blob2.txt".to_string(),
-                created_on: chrono::Utc::now(),
-                size: 200,
-            },
-            UiBlob {
-                name: "blob3.txt".to_string(),
-                created_on: chrono::Utc::now(),
-                size: 300,
-            },
-        ];
-
-        // Test navigation
-        assert_eq!(state.selected_index, 0);
-        state.move_down();
-        assert_eq!(state.selected_index, 1);
-        state.move_down();
-        assert_eq!(state.selected_index, 2);
-        state.move_down(); // Should wrap to 0
-        assert_eq!(state.selected_index, 0);
-
-        state.move_up(); // Should wrap to last item
-        assert_eq!(state.selected_index, 2);
-        state.move_up();
-        assert_eq!(state.selected_index, 1);
-        state.move_up();
-        assert_eq!(state.selected_index, 0);
-
-        // Test current_blob
-        assert_eq!(state.current_blob().unwrap().name, "blob1.txt");
-        state.move_down();
-        assert_eq!(state.current_blob().unwrap().name, "blob2.txt");
-    }
-
     #[test]
     fn test_new_module_methods() {
         let mut storage = AzuriteStorage {
@@ -1043,6 +2061,10 @@ mod tests {
             action: vec![],
             token_providers: HashMap::new(),
             current_token_provider_id: 0,
+            token_provider_filter: String::new(),
+            blob_listing: None,
+            module_filter: String::new(),
+            module_sort: BlobSort::Name,
         };
         assert_eq!(storage.new_module(), "test_module");
         storage.new_module_mut().push_str("_mut");
@@ -1067,6 +2089,10 @@ mod tests {
             action: vec![],
             token_providers: HashMap::new(),
             current_token_provider_id: 0,
+            token_provider_filter: String::new(),
+            blob_listing: None,
+            module_filter: String::new(),
+            module_sort: BlobSort::Name,
         };
         assert_eq!(storage.action(), None);
         storage.push_action(AzuriteAction::Add);
@@ -1091,10 +2117,214 @@ mod tests {
             action: vec![],
             token_providers: HashMap::new(),
             current_token_provider_id: 0,
+            token_provider_filter: String::new(),
+            blob_listing: None,
+            module_filter: String::new(),
+            module_sort: BlobSort::Name,
         };
         assert_eq!(storage.current_module_id(), 42);
     }
 
+    #[test]
+    fn test_module_of_blob() {
+        let module = ModuleInfo {
+            id: UUID::new(),
+            blob_name: "ApFw_0700FA.bin".to_string(),
+            container_name: "default".to_string(),
+            hash: String::new(),
+            hash_base64: String::new(),
+            sas_url: String::new(),
+            size: 0,
+            last_modified: None,
+        };
+        let storage = AzuriteStorage {
+            runtime: tokio::runtime::Runtime::new().unwrap(),
+            blob_service_client: ClientBuilder::with_location(
+                CloudLocation::Emulator {
+                    address: "127.0.0.1".to_string(),
+                    port: 10000,
+                },
+                StorageCredentials::access_key(ACCOUNT_NAME, ACCOUNT_KEY),
+            )
+            .blob_service_client(),
+            module_info_db: HashMap::from([(module.id.clone(), module.clone())]),
+            current_module_id: 0,
+            new_module: String::new(),
+            action: vec![],
+            token_providers: HashMap::new(),
+            current_token_provider_id: 0,
+            token_provider_filter: String::new(),
+            blob_listing: None,
+            module_filter: String::new(),
+            module_sort: BlobSort::Name,
+        };
+        assert_eq!(
+            storage.module_of_blob("default", "ApFw_0700FA.bin"),
+            Some(&module)
+        );
+        assert!(
+            storage
+                .module_of_blob("upload", "ApFw_0700FA.bin")
+                .is_none()
+        );
+        assert!(storage.module_of_blob("default", "other.bin").is_none());
+    }
+
+    #[test]
+    fn test_read_only_storage() {
+        use crate::error::DMErrorExt;
+
+        let storage = AzuriteStorage::with_config(&AzuriteConfig {
+            read_only: true,
+            ..AzuriteConfig::new("127.0.0.1:10000")
+        })
+        .unwrap();
+        assert!(storage.is_read_only());
+
+        // Refused before reaching the storage
+        let e = storage.remove_blob(None, "detection.wasm").unwrap_err();
+        assert!(matches!(e.current_context(), DMError::ReadOnly));
+        assert_eq!(
+            e.error_str().unwrap(),
+            "Read-only mode, refused to delete blob default/detection.wasm"
+        );
+        assert!(storage.delete_container("default").is_err());
+        assert!(storage.create_container("default").is_err());
+        assert!(
+            !AzuriteStorage::new("127.0.0.1:10000")
+                .unwrap()
+                .is_read_only()
+        );
+    }
+
+    #[test]
+    fn test_listed_modules_filter_and_sort() {
+        let mut storage = AzuriteStorage::new("127.0.0.1:10000").unwrap();
+        let now = chrono::Utc::now();
+        for (name, size, age) in [
+            ("ApFw_0700FA.bin", 300, 2),
+            ("detection.wasm", 100, 0),
+            ("ApFw_0700FB.bin", 200, 1),
+        ] {
+            let module = ModuleInfo {
+                id: UUID::new(),
+                blob_name: name.to_string(),
+                container_name: "default".to_string(),
+                hash: String::new(),
+                hash_base64: String::new(),
+                sas_url: String::new(),
+                size,
+                last_modified: Some(now - chrono::TimeDelta::hours(age)),
+            };
+            storage.module_info_db.insert(module.id.clone(), module);
+        }
+        let names = |storage: &AzuriteStorage| {
+            storage
+                .listed_modules()
+                .iter()
+                .map(|(_, m)| m.blob_name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(&storage),
+            vec!["ApFw_0700FA.bin", "ApFw_0700FB.bin", "detection.wasm"]
+        );
+
+        // The focused module stays focused when sorted by another column
+        storage.current_module_focus_down();
+        assert_eq!(
+            storage.current_module().unwrap().blob_name,
+            "ApFw_0700FB.bin"
+        );
+        storage.sort_modules_by_next();
+        assert_eq!(storage.module_sort(), BlobSort::Size);
+        assert_eq!(
+            names(&storage),
+            vec!["ApFw_0700FA.bin", "ApFw_0700FB.bin", "detection.wasm"]
+        );
+        storage.sort_modules_by_next();
+        assert_eq!(
+            names(&storage),
+            vec!["detection.wasm", "ApFw_0700FB.bin", "ApFw_0700FA.bin"]
+        );
+        assert_eq!(storage.current_module_id(), 1);
+
+        storage.set_module_filter("apfw");
+        assert_eq!(names(&storage), vec!["ApFw_0700FB.bin", "ApFw_0700FA.bin"]);
+        storage.current_module_focus_up();
+        assert_eq!(
+            storage.current_module().unwrap().blob_name,
+            "ApFw_0700FA.bin"
+        );
+
+        storage.set_module_filter("none");
+        assert!(storage.current_module().is_none());
+        storage.current_module_focus_down();
+        assert_eq!(storage.current_module_id(), 0);
+    }
+
+    #[test]
+    fn test_suffixed_blob_name() {
+        assert_eq!(
+            suffixed_blob_name("ApFw_0700FA.bin", 1),
+            "ApFw_0700FA-1.bin"
+        );
+        assert_eq!(suffixed_blob_name("fw.tar.gz", 2), "fw.tar-2.gz");
+        assert_eq!(suffixed_blob_name("README", 3), "README-3");
+        assert_eq!(suffixed_blob_name(".hidden", 1), ".hidden-1");
+    }
+
+    #[test]
+    fn test_resume_offset() {
+        let path = std::path::Path::new("/tmp/dl/fw.bin");
+        assert_eq!(
+            partial_path(path),
+            std::path::PathBuf::from("/tmp/dl/fw.bin.part")
+        );
+
+        assert_eq!(resume_offset(4096, Some("0x1"), "0x1", 8192), 4096);
+        assert_eq!(resume_offset(8192, Some("0x1"), "0x1", 8192), 8192);
+        // The blob has been replaced, or the partial file can't be from it
+        assert_eq!(resume_offset(4096, Some("0x1"), "0x2", 8192), 0);
+        assert_eq!(resume_offset(4096, None, "0x1", 8192), 0);
+        assert_eq!(resume_offset(9000, Some("0x1"), "0x1", 8192), 0);
+    }
+
+    #[test]
+    fn test_check_integrity() {
+        let md5 = Md5::digest(b"firmware").to_vec();
+        let sha256 = format!("{:x}", Sha256::digest(b"firmware"));
+        let other = Md5::digest(b"other").to_vec();
+
+        assert_eq!(
+            check_integrity(&md5, &sha256, Some(&md5), None).unwrap(),
+            Integrity::Md5
+        );
+        assert_eq!(
+            check_integrity(&md5, &sha256, None, Some(&sha256.to_uppercase())).unwrap(),
+            Integrity::Sha256
+        );
+        assert_eq!(
+            check_integrity(&md5, &sha256, None, None).unwrap(),
+            Integrity::Unverified
+        );
+        // The MD5 of the blob properties is checked first
+        assert!(check_integrity(&md5, &sha256, Some(&other), Some(&sha256)).is_err());
+        assert!(check_integrity(&md5, &sha256, None, Some("00")).is_err());
+
+        let download = Download {
+            path: "/tmp/dl/fw.bin".to_owned(),
+            size: 8192,
+            resumed: 4096,
+            integrity: Integrity::Md5,
+        };
+        assert_eq!(
+            download.summary(),
+            "/tmp/dl/fw.bin (8192 bytes, resumed at 4096, MD5 verified)"
+        );
+    }
+
     #[test]
     fn test_is_sas_url_valid_future() {
         let expire = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
@@ -1127,6 +2357,10 @@ mod tests {
             action: vec![],
             token_providers: HashMap::new(),
             current_token_provider_id: 0,
+            token_provider_filter: String::new(),
+            blob_listing: None,
+            module_filter: String::new(),
+            module_sort: BlobSort::Name,
         };
 
         // Initially there are no token providers
@@ -1140,6 +2374,7 @@ mod tests {
             TokenProvider {
                 uuid: u1.clone(),
                 container: format!("upload-{}", u1.uuid()),
+                label: TokenProviderLabel::default(),
             },
         );
         storage.token_providers.insert(
@@ -1147,6 +2382,7 @@ mod tests {
             TokenProvider {
                 uuid: u2.clone(),
                 container: format!("upload-{}", u2.uuid()),
+                label: TokenProviderLabel::default(),
             },
         );
 
@@ -1160,4 +2396,92 @@ mod tests {
         storage.current_token_provider_id = pos_u2;
         assert_eq!(storage.get_current_token_provider_by_highlight(), Some(&u2));
     }
+
+    #[test]
+    fn test_token_provider_from_container() {
+        let uuid = UUID::new();
+        let tp = TokenProvider::from_container(&format!("upload-{}", uuid.uuid()), &HashMap::new())
+            .unwrap();
+        assert_eq!(tp.uuid, uuid);
+        assert_eq!(tp.label, TokenProviderLabel::default());
+
+        // Containers with a custom name are recognized by the UUID in their metadata
+        let metadata = HashMap::from([
+            (METADATA_UUID.to_owned(), uuid.uuid().to_owned()),
+            (METADATA_NAME.to_owned(), "line-a".to_owned()),
+            (METADATA_TAGS.to_owned(), "logs, factory".to_owned()),
+        ]);
+        let tp = TokenProvider::from_container("line-a-logs", &metadata).unwrap();
+        assert_eq!(tp.uuid, uuid);
+        assert_eq!(tp.container, "line-a-logs");
+        assert_eq!(tp.label.name, "line-a");
+        assert_eq!(tp.label.tags, vec!["logs", "factory"]);
+
+        assert!(TokenProvider::from_container("line-a-logs", &HashMap::new()).is_none());
+        assert!(TokenProvider::from_container("default", &HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_token_provider_metadata_round_trip() {
+        let tp = TokenProvider {
+            uuid: UUID::new(),
+            container: "custom".to_owned(),
+            label: TokenProviderLabel {
+                name: "bench".to_owned(),
+                description: "Input tensors of the bench device".to_owned(),
+                tags: vec!["tensor".to_owned(), "bench".to_owned()],
+            },
+        };
+        let metadata: HashMap<String, String> = tp
+            .metadata()
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v))
+            .collect();
+
+        let restored = TokenProvider::from_container("custom", &metadata).unwrap();
+        assert_eq!(restored.uuid, tp.uuid);
+        assert_eq!(restored.label, tp.label);
+    }
+
+    #[test]
+    fn test_token_provider_matches() {
+        let tp = TokenProvider {
+            uuid: UUID::new(),
+            container: "upload-x".to_owned(),
+            label: TokenProviderLabel {
+                name: "Line A".to_owned(),
+                description: String::new(),
+                tags: vec!["logs".to_owned()],
+            },
+        };
+        assert!(tp.matches(""));
+        assert!(tp.matches("line a"));
+        assert!(tp.matches("LOG"));
+        assert!(tp.matches("upload"));
+        assert!(!tp.matches("tensor"));
+    }
+
+    #[test]
+    fn test_validate_container_name() {
+        assert!(validate_container_name("line-a-logs").is_ok());
+        assert!(validate_container_name("ab").is_err());
+        assert!(validate_container_name("Line-A").is_err());
+        assert!(validate_container_name("-logs").is_err());
+        assert!(validate_container_name("line--a").is_err());
+        assert!(validate_container_name(&"a".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn test_token_provider_label_validate() {
+        let mut label = TokenProviderLabel {
+            name: "bench".to_owned(),
+            description: "desc".to_owned(),
+            tags: TokenProviderLabel::parse_tags(" a, ,b "),
+        };
+        assert_eq!(label.tags, vec!["a", "b"]);
+        assert!(label.validate().is_ok());
+
+        label.name = "ベンチ".to_owned();
+        assert!(label.validate().is_err());
+    }
 }
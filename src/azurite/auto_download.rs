@@ -0,0 +1,305 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::AzuriteStorage,
+    crate::error::{DMError, DMErrorExt},
+    chrono::{DateTime, Local},
+    error_stack::Result,
+    jlogger_tracing::{jdebug, jerror, jinfo},
+    std::{
+        collections::HashSet,
+        time::{Duration, Instant},
+    },
+};
+
+/// Interval between two polls of the watched containers
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Uploads are watched as long as the issued SAS URL is valid
+const WATCH_TIMEOUT: chrono::TimeDelta = chrono::TimeDelta::hours(1);
+
+/// Maximum number of download records kept for the Downloads screen
+const MAX_RECORDS: usize = 100;
+
+/// A blob the device was given a SAS URL for but has not been downloaded yet
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingDownload {
+    pub container: String,
+    pub blob_name: String,
+    pub issued_at: DateTime<Local>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadStatus {
+    /// Downloaded to the given local path
+    Downloaded(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadRecord {
+    pub container: String,
+    pub blob_name: String,
+    pub time: DateTime<Local>,
+    pub status: DownloadStatus,
+}
+
+/// Watches containers for blobs uploaded by the device and downloads them to a local directory
+#[derive(Debug, Default)]
+pub struct AutoDownloader {
+    download_dir: Option<String>,
+    pending: Vec<PendingDownload>,
+    records: Vec<DownloadRecord>,
+    last_poll: Option<Instant>,
+}
+
+impl AutoDownloader {
+    /// Auto download is disabled when no download directory is given
+    pub fn new(download_dir: Option<&str>) -> Self {
+        Self {
+            download_dir: download_dir.map(|d| d.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.download_dir.is_some()
+    }
+
+    pub fn download_dir(&self) -> Option<&str> {
+        self.download_dir.as_deref()
+    }
+
+    pub fn pending(&self) -> &[PendingDownload] {
+        &self.pending
+    }
+
+    pub fn records(&self) -> &[DownloadRecord] {
+        &self.records
+    }
+
+    /// Starts watching for `blob_name` in `container`
+    pub fn watch(&mut self, container: &str, blob_name: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        // The device may request a token for the same file again, restart the watch in this case
+        self.pending
+            .retain(|p| !(p.container == container && p.blob_name == blob_name));
+        self.pending.push(PendingDownload {
+            container: container.to_owned(),
+            blob_name: blob_name.to_owned(),
+            issued_at: Local::now(),
+        });
+    }
+
+    fn push_record(&mut self, pending: &PendingDownload, status: DownloadStatus) {
+        self.records.push(DownloadRecord {
+            container: pending.container.clone(),
+            blob_name: pending.blob_name.clone(),
+            time: Local::now(),
+            status,
+        });
+
+        if self.records.len() > MAX_RECORDS {
+            self.records.remove(0);
+        }
+    }
+
+    /// Returns true when the watched containers should be polled
+    pub fn should_poll(&self) -> bool {
+        !self.pending.is_empty() && self.last_poll.is_none_or(|t| t.elapsed() >= POLL_INTERVAL)
+    }
+
+    /// Drops watches whose SAS URL has expired
+    fn expire(&mut self, now: DateTime<Local>) {
+        let (expired, pending): (Vec<_>, Vec<_>) = self
+            .pending
+            .drain(..)
+            .partition(|p| now - p.issued_at > WATCH_TIMEOUT);
+        self.pending = pending;
+
+        for p in expired.iter() {
+            self.push_record(
+                p,
+                DownloadStatus::Failed("No upload before SAS URL expired".to_owned()),
+            );
+        }
+    }
+
+    /// Starts a poll of the watched containers, returning the watches and the download
+    /// directory for `download_ready()`. The watches whose SAS URL has expired are dropped.
+    pub fn start_poll(&mut self) -> Option<(Vec<PendingDownload>, String)> {
+        self.last_poll = Some(Instant::now());
+        self.expire(Local::now());
+
+        let download_dir = self.download_dir.clone()?;
+        (!self.pending.is_empty()).then(|| (self.pending.clone(), download_dir))
+    }
+
+    /// Records the result of a poll, the downloaded blobs are no longer watched.
+    /// Returns the number of downloaded blobs.
+    pub fn apply(&mut self, downloads: Vec<(PendingDownload, Result<String, DMError>)>) -> usize {
+        let mut downloaded = 0;
+        for (p, result) in downloads {
+            self.pending
+                .retain(|w| !(w.container == p.container && w.blob_name == p.blob_name));
+            match result {
+                Ok(file_path) => {
+                    downloaded += 1;
+                    self.push_record(&p, DownloadStatus::Downloaded(file_path));
+                }
+                Err(e) => {
+                    self.push_record(
+                        &p,
+                        DownloadStatus::Failed(e.error_str().unwrap_or("Unknown error".to_owned())),
+                    );
+                }
+            }
+        }
+        downloaded
+    }
+}
+
+/// Checks the containers of the watches and downloads the blobs which have been uploaded
+/// to `download_dir`, with the path of each download. Run by the job worker.
+pub fn download_ready(
+    storage: &AzuriteStorage,
+    pending: &[PendingDownload],
+    download_dir: &str,
+) -> Vec<(PendingDownload, Result<String, DMError>)> {
+    let containers: HashSet<&str> = pending.iter().map(|p| p.container.as_str()).collect();
+    let mut downloads = vec![];
+
+    for container in containers {
+        let uploaded: HashSet<String> = match storage.list_blobs_for_ui(container) {
+            Ok(blobs) => blobs.into_iter().map(|b| b.name).collect(),
+            Err(e) => {
+                jerror!(
+                    func = "auto_download::download_ready()",
+                    line = line!(),
+                    container = container,
+                    error = format!("{:?}", e)
+                );
+                continue;
+            }
+        };
+
+        for p in pending
+            .iter()
+            .filter(|p| p.container == container && uploaded.contains(&p.blob_name))
+        {
+            let result = storage
+                .download_blob(
+                    &p.container,
+                    &p.blob_name,
+                    Some(download_dir),
+                    &mut |_, _| {},
+                )
+                .map(|download| download.path);
+            if let Ok(file_path) = &result {
+                jinfo!(
+                    event = "Blob auto downloaded",
+                    container = p.container,
+                    blob_name = p.blob_name,
+                    file_path = file_path
+                );
+            }
+            downloads.push((p.clone(), result));
+        }
+    }
+
+    downloads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_without_download_dir() {
+        let mut downloader = AutoDownloader::new(None);
+        assert!(!downloader.is_enabled());
+
+        downloader.watch("upload-container", "log.txt");
+        assert!(downloader.pending().is_empty());
+        assert!(!downloader.should_poll());
+    }
+
+    #[test]
+    fn test_watch_replaces_same_blob() {
+        let mut downloader = AutoDownloader::new(Some("/tmp"));
+        downloader.watch("c", "a.txt");
+        downloader.watch("c", "b.txt");
+        downloader.watch("c", "a.txt");
+
+        assert_eq!(downloader.pending().len(), 2);
+        assert_eq!(downloader.pending()[1].blob_name, "a.txt");
+        assert!(downloader.should_poll());
+    }
+
+    #[test]
+    fn test_expire_records_failure() {
+        let mut downloader = AutoDownloader::new(Some("/tmp"));
+        downloader.watch("c", "a.txt");
+        downloader.watch("c", "b.txt");
+        downloader.pending[0].issued_at = Local::now() - chrono::TimeDelta::hours(2);
+
+        downloader.expire(Local::now());
+
+        assert_eq!(downloader.pending().len(), 1);
+        assert_eq!(downloader.pending()[0].blob_name, "b.txt");
+        assert_eq!(downloader.records().len(), 1);
+        assert_eq!(downloader.records()[0].blob_name, "a.txt");
+        assert!(matches!(
+            downloader.records()[0].status,
+            DownloadStatus::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn test_poll_applied() {
+        let mut downloader = AutoDownloader::new(Some("/tmp"));
+        downloader.watch("c", "a.txt");
+        downloader.watch("c", "b.txt");
+        downloader.watch("c", "c.txt");
+
+        let (pending, dir) = downloader.start_poll().unwrap();
+        assert_eq!((pending.len(), dir.as_str()), (3, "/tmp"));
+        assert!(!downloader.should_poll());
+
+        let failure = error_stack::Report::new(DMError::IOError).attach_printable("Disk full");
+        let downloaded = downloader.apply(vec![
+            (pending[0].clone(), Ok("/tmp/a.txt".to_owned())),
+            (pending[2].clone(), Err(failure)),
+        ]);
+        assert_eq!(downloaded, 1);
+        // Not uploaded yet, still watched
+        assert_eq!(downloader.pending().len(), 1);
+        assert_eq!(downloader.pending()[0].blob_name, "b.txt");
+        assert_eq!(
+            downloader.records()[0].status,
+            DownloadStatus::Downloaded("/tmp/a.txt".to_owned())
+        );
+        assert_eq!(
+            downloader.records()[1].status,
+            DownloadStatus::Failed("Disk full".to_owned())
+        );
+    }
+}
@@ -0,0 +1,178 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{
+        AzuriteConfig, AzuriteStorage, TokenProviderLabel,
+        defaults::{TokenProviderDefaults, TokenProviderPurpose},
+    },
+    crate::error::{DMError, DMErrorExt},
+    error_stack::{Report, Result},
+};
+
+/// Container of the modules and of the firmware and AI model packages
+pub const DEFAULT_CONTAINER: &str = "default";
+
+/// Name of the token provider created for the device logs when there is none
+pub const LOGS_PROVIDER_NAME: &str = "device-logs";
+
+/// What to do about a failed connection, from the error of the storage
+pub fn connection_hint(config: &AzuriteConfig, report: &Report<DMError>) -> String {
+    let message = report.error_str().unwrap_or_default();
+    match report.current_context() {
+        DMError::InvalidData => format!(
+            "Check --azurite-url, e.g. https://127.0.0.1:10000 ({})",
+            message
+        ),
+        _ if ["403", "Authorization", "Signature"]
+            .iter()
+            .any(|m| message.contains(m)) =>
+        {
+            format!(
+                "{} refused the credentials of the account '{}', check --account-name and --account-key",
+                config.url, config.account_name
+            )
+        }
+        _ => format!(
+            "No answer from {}, start Azurite (docker run -p 10000:10000 mcr.microsoft.com/azure-storage/azurite) or check --azurite-url",
+            config.url
+        ),
+    }
+}
+
+/// Connects to the storage and checks that it answers with the credentials, the error
+/// tells what to fix. Returns the storage and its number of containers.
+pub fn connect(config: &AzuriteConfig) -> Result<(AzuriteStorage, usize), DMError> {
+    let checked = AzuriteStorage::with_config(config)
+        .and_then(|storage| storage.check_connection().map(|count| (storage, count)));
+    checked.map_err(|e| {
+        let hint = connection_hint(config, &e);
+        e.attach_printable(hint)
+    })
+}
+
+/// Creates the containers of the module, OTA and token provider screens and uploads the
+/// seed files as modules. The token provider of the device logs is made the default one
+/// when there is no default yet. Returns what was done, one line per step.
+pub fn init_storage(
+    storage: &mut AzuriteStorage,
+    defaults_file: &str,
+    seeds: &[String],
+) -> Result<Vec<String>, DMError> {
+    let mut steps = vec![];
+
+    if storage.is_container_exists(DEFAULT_CONTAINER) {
+        steps.push(format!("Container '{DEFAULT_CONTAINER}' exists"));
+    } else {
+        storage.create_container(DEFAULT_CONTAINER)?;
+        steps.push(format!("Container '{DEFAULT_CONTAINER}' created"));
+    }
+
+    storage.scan_upload_containers()?;
+    if storage.token_providers().is_empty() {
+        let uuid = storage.add_token_provider(
+            None,
+            TokenProviderLabel {
+                name: LOGS_PROVIDER_NAME.to_owned(),
+                description: "Created by storage init".to_owned(),
+                tags: vec!["logs".to_owned()],
+            },
+        )?;
+        steps.push(format!(
+            "Token provider {} created with the container 'upload-{}'",
+            uuid.uuid(),
+            uuid.uuid()
+        ));
+    } else {
+        steps.push(format!(
+            "{} token providers exist",
+            storage.token_providers().len()
+        ));
+    }
+
+    let mut defaults = TokenProviderDefaults::load(defaults_file)?;
+    let purpose = TokenProviderPurpose::DeviceLogs;
+    let has_default = storage
+        .token_providers()
+        .keys()
+        .any(|uuid| defaults.get(purpose) == Some(uuid.uuid()));
+    if !has_default {
+        // The one named for the logs, else the first by container name
+        let mut providers: Vec<_> = storage.token_providers().values().collect();
+        providers.sort_by_key(|p| (p.label.name != LOGS_PROVIDER_NAME, p.container.clone()));
+        if let Some(provider) = providers.first() {
+            defaults.toggle(purpose, &provider.uuid);
+            defaults.save(defaults_file)?;
+            steps.push(format!(
+                "Token provider {} is the default for {}",
+                provider.uuid.uuid(),
+                purpose.name()
+            ));
+        }
+    }
+
+    for seed in seeds {
+        storage
+            .push_blob(Some(DEFAULT_CONTAINER), seed)
+            .map_err(|e| e.attach_printable(format!("Failed to upload {seed}, check the path")))?;
+        steps.push(format!("Uploaded {seed} to '{DEFAULT_CONTAINER}'"));
+    }
+
+    storage.update_modules(Some(DEFAULT_CONTAINER))?;
+    steps.push(format!(
+        "{} modules in '{DEFAULT_CONTAINER}'",
+        storage.module_info_db().len()
+    ));
+
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_hint() {
+        let config = AzuriteConfig::new("https://127.0.0.1:10000");
+
+        let e = Report::new(DMError::InvalidData).attach_printable("Invalid port: x");
+        assert!(connection_hint(&config, &e).contains("--azurite-url"));
+
+        let e = Report::new(DMError::IOError).attach_printable(
+            "Failed to connect to Azurite storage: HTTP status code 403 - AuthorizationFailure",
+        );
+        assert!(connection_hint(&config, &e).contains("devstoreaccount1"));
+
+        let e = Report::new(DMError::Timeout);
+        assert!(connection_hint(&config, &e).contains("start Azurite"));
+    }
+
+    #[test]
+    fn test_connect_fails_with_hint() {
+        let e = connect(&AzuriteConfig::new("127.0.0.1")).unwrap_err();
+        assert!(e.error_str().unwrap().contains("--azurite-url"));
+
+        // Nothing listens on a port that was just released
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let e = connect(&AzuriteConfig::new(&format!("https://127.0.0.1:{port}"))).unwrap_err();
+        assert!(e.error_str().unwrap().contains("start Azurite"));
+    }
+}
@@ -0,0 +1,184 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        error::DMError,
+        mqtt_ctrl::evp::evp_state::UUID,
+        versioned::{self, Versioned},
+    },
+    error_stack::{Report, Result},
+    serde::{Deserialize, Serialize},
+};
+
+/// File of the configuration directory the defaults are saved to
+pub const DEFAULTS_FILE: &str = "token_provider_defaults.json";
+
+/// What the device uploads to the container of a token provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenProviderPurpose {
+    DeviceLogs,
+    InputTensor,
+    Metadata,
+}
+
+impl TokenProviderPurpose {
+    pub const ALL: [TokenProviderPurpose; 3] = [
+        TokenProviderPurpose::DeviceLogs,
+        TokenProviderPurpose::InputTensor,
+        TokenProviderPurpose::Metadata,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenProviderPurpose::DeviceLogs => "device logs",
+            TokenProviderPurpose::InputTensor => "input tensor upload",
+            TokenProviderPurpose::Metadata => "metadata upload",
+        }
+    }
+}
+
+/// Token provider used by default for each purpose, as hyphenated UUIDs
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct TokenProviderDefaults {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_logs: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tensor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<String>,
+}
+
+impl Versioned for TokenProviderDefaults {
+    const KIND: &'static str = "token provider defaults";
+    const VERSION: u32 = 1;
+}
+
+impl TokenProviderDefaults {
+    fn entry(&mut self, purpose: TokenProviderPurpose) -> &mut Option<String> {
+        match purpose {
+            TokenProviderPurpose::DeviceLogs => &mut self.device_logs,
+            TokenProviderPurpose::InputTensor => &mut self.input_tensor,
+            TokenProviderPurpose::Metadata => &mut self.metadata,
+        }
+    }
+
+    pub fn get(&self, purpose: TokenProviderPurpose) -> Option<&str> {
+        match purpose {
+            TokenProviderPurpose::DeviceLogs => self.device_logs.as_deref(),
+            TokenProviderPurpose::InputTensor => self.input_tensor.as_deref(),
+            TokenProviderPurpose::Metadata => self.metadata.as_deref(),
+        }
+    }
+
+    /// Makes the token provider the default for the purpose, or clears the default if it
+    /// already is. Returns whether it is the default afterwards.
+    pub fn toggle(&mut self, purpose: TokenProviderPurpose, uuid: &UUID) -> bool {
+        let entry = self.entry(purpose);
+        if entry.as_deref() == Some(uuid.uuid()) {
+            *entry = None;
+            false
+        } else {
+            *entry = Some(uuid.uuid().to_owned());
+            true
+        }
+    }
+
+    /// Purposes the token provider is the default of
+    pub fn purposes_of(&self, uuid: &UUID) -> Vec<TokenProviderPurpose> {
+        TokenProviderPurpose::ALL
+            .into_iter()
+            .filter(|p| self.get(*p) == Some(uuid.uuid()))
+            .collect()
+    }
+
+    /// Reads the defaults, a missing file means no default
+    pub fn load(path: &str) -> Result<Self, DMError> {
+        Ok(versioned::load(path)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), DMError> {
+        versioned::save(path, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_default() {
+        let mut defaults = TokenProviderDefaults::default();
+        let u1 = UUID::new();
+        let u2 = UUID::new();
+
+        assert!(defaults.toggle(TokenProviderPurpose::DeviceLogs, &u1));
+        assert!(defaults.toggle(TokenProviderPurpose::Metadata, &u1));
+        assert_eq!(
+            defaults.purposes_of(&u1),
+            vec![
+                TokenProviderPurpose::DeviceLogs,
+                TokenProviderPurpose::Metadata
+            ]
+        );
+
+        // Another provider replaces the default, toggling it again clears it
+        assert!(defaults.toggle(TokenProviderPurpose::DeviceLogs, &u2));
+        assert_eq!(
+            defaults.get(TokenProviderPurpose::DeviceLogs),
+            Some(u2.uuid())
+        );
+        assert!(!defaults.toggle(TokenProviderPurpose::DeviceLogs, &u2));
+        assert_eq!(defaults.get(TokenProviderPurpose::DeviceLogs), None);
+        assert_eq!(
+            defaults.purposes_of(&u1),
+            vec![TokenProviderPurpose::Metadata]
+        );
+    }
+
+    #[test]
+    fn test_load_and_save() {
+        let dir = std::env::temp_dir().join(format!("dm_tp_defaults_{}", UUID::new_string()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(DEFAULTS_FILE).to_string_lossy().to_string();
+
+        assert_eq!(
+            TokenProviderDefaults::load(&path).unwrap(),
+            TokenProviderDefaults::default()
+        );
+
+        let mut defaults = TokenProviderDefaults::default();
+        defaults.toggle(TokenProviderPurpose::InputTensor, &UUID::new());
+        defaults.save(&path).unwrap();
+        assert_eq!(TokenProviderDefaults::load(&path).unwrap(), defaults);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(TokenProviderDefaults::load(&path).is_err());
+
+        // Written before the files were versioned
+        let uuid = UUID::new();
+        std::fs::write(&path, format!(r#"{{"device_logs": "{}"}}"#, uuid.uuid())).unwrap();
+        assert_eq!(
+            TokenProviderDefaults::load(&path)
+                .unwrap()
+                .get(TokenProviderPurpose::DeviceLogs),
+            Some(uuid.uuid())
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
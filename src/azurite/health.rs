@@ -0,0 +1,175 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::{DMError, DMErrorExt},
+    error_stack::Result,
+    std::{
+        fmt::Display,
+        time::{Duration, Instant},
+    },
+};
+
+/// Interval between two checks while Azurite answers
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Interval between two retries while it does not
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Consecutive failures after which Azurite is considered unavailable
+const MAX_FAILURES: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AzuriteHealth {
+    /// Not checked yet
+    Unknown,
+    Connected,
+    /// The last checks failed but Azurite answered before
+    Degraded,
+    Unavailable,
+}
+
+impl Display for AzuriteHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AzuriteHealth::Unknown => "checking",
+            AzuriteHealth::Connected => "connected",
+            AzuriteHealth::Degraded => "degraded",
+            AzuriteHealth::Unavailable => "unavailable",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Periodic connectivity checks of the Azurite storage
+#[derive(Debug)]
+pub struct HealthMonitor {
+    health: AzuriteHealth,
+    last_check: Option<Instant>,
+    failures: u32,
+    last_error: Option<String>,
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self {
+            health: AzuriteHealth::Unknown,
+            last_check: None,
+            failures: 0,
+            last_error: None,
+        }
+    }
+}
+
+impl HealthMonitor {
+    pub fn health(&self) -> &AzuriteHealth {
+        &self.health
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    fn interval(&self) -> Duration {
+        if self.failures == 0 {
+            CHECK_INTERVAL
+        } else {
+            RETRY_INTERVAL
+        }
+    }
+
+    /// Time left before the next check, None when it is due
+    pub fn next_check(&self, now: Instant) -> Option<Duration> {
+        let last_check = self.last_check?;
+        self.interval()
+            .checked_sub(now.duration_since(last_check))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Defers the next check while the one started now is running in the background
+    pub fn start_check(&mut self, now: Instant) {
+        self.last_check = Some(now);
+    }
+
+    /// Records the result of a check, None when the storage could not be created
+    pub fn record(&mut self, result: Option<&Result<usize, DMError>>, now: Instant) {
+        self.last_check = Some(now);
+
+        let error = match result {
+            Some(Ok(_)) => {
+                self.health = AzuriteHealth::Connected;
+                self.failures = 0;
+                self.last_error = None;
+                return;
+            }
+            Some(Err(e)) => e.error_str().unwrap_or(e.current_context().to_string()),
+            None => "Azurite storage not available".to_owned(),
+        };
+
+        self.failures += 1;
+        self.last_error = Some(error);
+        self.health = match self.health {
+            AzuriteHealth::Connected | AzuriteHealth::Degraded if self.failures < MAX_FAILURES => {
+                AzuriteHealth::Degraded
+            }
+            _ => AzuriteHealth::Unavailable,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error_stack::Report;
+
+    #[test]
+    fn test_health_transitions() {
+        let now = Instant::now();
+        let mut monitor = HealthMonitor::default();
+        assert_eq!(monitor.next_check(now), None);
+
+        monitor.record(Some(&Ok(2)), now);
+        assert_eq!(monitor.health(), &AzuriteHealth::Connected);
+        assert_eq!(monitor.next_check(now), Some(CHECK_INTERVAL));
+
+        let timeout = || Err(Report::new(DMError::Timeout).attach_printable("Timeout"));
+        monitor.record(Some(&timeout()), now);
+        assert_eq!(monitor.health(), &AzuriteHealth::Degraded);
+        assert_eq!(monitor.last_error(), Some("Timeout"));
+        assert_eq!(monitor.next_check(now + RETRY_INTERVAL), None);
+
+        monitor.record(Some(&timeout()), now);
+        monitor.record(None, now);
+        assert_eq!(monitor.health(), &AzuriteHealth::Unavailable);
+        assert_eq!(monitor.failures(), 3);
+
+        monitor.record(Some(&Ok(0)), now);
+        assert_eq!(monitor.health(), &AzuriteHealth::Connected);
+        assert_eq!(monitor.last_error(), None);
+    }
+
+    #[test]
+    fn test_never_connected_is_unavailable() {
+        let mut monitor = HealthMonitor::default();
+        monitor.record(None, Instant::now());
+        assert_eq!(monitor.health(), &AzuriteHealth::Unavailable);
+    }
+}
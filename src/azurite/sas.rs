@@ -0,0 +1,193 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use chrono::{DateTime, FixedOffset, Local, TimeDelta, Utc};
+
+/// Parameters of a SAS URL, without the signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SasInfo {
+    /// Signed permissions, e.g. "racw"
+    pub permissions: String,
+    pub start: Option<DateTime<FixedOffset>>,
+    pub expiry: DateTime<FixedOffset>,
+}
+
+/// Decodes %XX sequences, '+' is kept as it is since it is the sign of RFC 3339 offsets
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = value.get(i + 1..i + 3)
+            && let Ok(b) = u8::from_str_radix(hex, 16)
+        {
+            decoded.push(b);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+impl SasInfo {
+    /// Parses the query of a SAS URL, None if it has no valid expiry time
+    pub fn parse(url: &str) -> Option<Self> {
+        let (_, query) = url.split_once('?')?;
+        let param = |name: &str| {
+            query
+                .split('&')
+                .filter_map(|p| p.split_once('='))
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| percent_decode(v.trim()))
+        };
+        let time = |name: &str| param(name).and_then(|v| DateTime::parse_from_rfc3339(&v).ok());
+
+        Some(Self {
+            permissions: param("sp").unwrap_or_default(),
+            start: time("st"),
+            expiry: time("se")?,
+        })
+    }
+
+    /// Permissions in words, e.g. "read, create, write"
+    pub fn permissions_text(&self) -> String {
+        let words: Vec<&str> = self
+            .permissions
+            .chars()
+            .map(|c| match c {
+                'r' => "read",
+                'a' => "add",
+                'c' => "create",
+                'w' => "write",
+                'd' => "delete",
+                'x' => "delete version",
+                'l' => "list",
+                't' => "tags",
+                'm' => "move",
+                'e' => "execute",
+                'i' => "set immutability policy",
+                _ => "unknown",
+            })
+            .collect();
+
+        if words.is_empty() {
+            "none".to_owned()
+        } else {
+            words.join(", ")
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expiry <= now
+    }
+
+    /// Time left before the expiry, or since it when expired, e.g. "in 59m 58s"
+    pub fn countdown(&self, now: DateTime<Utc>) -> String {
+        let delta = self.expiry.with_timezone(&Utc) - now;
+        let (prefix, suffix, delta) = if delta > TimeDelta::zero() {
+            ("in ", "", delta)
+        } else {
+            ("", " ago", -delta)
+        };
+
+        let secs = delta.num_seconds();
+        let text = match (secs / 3600, secs / 60 % 60, secs % 60) {
+            (0, 0, s) => format!("{s}s"),
+            (0, m, s) => format!("{m}m {s}s"),
+            (h, m, _) if h < 48 => format!("{h}h {m}m"),
+            (h, _, _) => format!("{}d {}h", h / 24, h % 24),
+        };
+        format!("{prefix}{text}{suffix}")
+    }
+
+    /// One line summary, e.g. "read | expires 2025-01-01 10:00:00 (in 59m 58s)"
+    pub fn summary(&self, now: DateTime<Utc>) -> String {
+        format!(
+            "{} | {} {} ({})",
+            self.permissions_text(),
+            if self.is_expired(now) {
+                "EXPIRED"
+            } else {
+                "expires"
+            },
+            self.expiry
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S"),
+            self.countdown(now)
+        )
+    }
+}
+
+/// SAS URL given to the device in a storage token response, the signature is not kept
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssuedSas {
+    pub time: DateTime<Local>,
+    pub container: String,
+    pub blob: String,
+    pub sas: SasInfo,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "http://127.0.0.1:10000/devstoreaccount1/upload/a.log?sv=2022-11-02&st=2025-01-01T09%3A00%3A00Z&se=2025-01-01T10%3A00%3A00Z&sr=b&sp=racw&sig=abc%2Bdef";
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_sas_url() {
+        let sas = SasInfo::parse(URL).unwrap();
+        assert_eq!(sas.permissions, "racw");
+        assert_eq!(sas.permissions_text(), "read, add, create, write");
+        assert_eq!(
+            sas.start,
+            Some(DateTime::parse_from_rfc3339("2025-01-01T09:00:00Z").unwrap())
+        );
+        assert_eq!(
+            sas.expiry,
+            DateTime::parse_from_rfc3339("2025-01-01T10:00:00Z").unwrap()
+        );
+
+        // Offsets with '+' are not taken as a space
+        let sas = SasInfo::parse("https://a/b?se=2025-01-01T19:00:00+09:00&sp=r").unwrap();
+        assert_eq!(sas.expiry.with_timezone(&Utc), at("2025-01-01T10:00:00Z"));
+
+        assert!(SasInfo::parse("https://a/b").is_none());
+        assert!(SasInfo::parse("https://a/b?sp=r").is_none());
+        assert!(SasInfo::parse("https://a/b?se=tomorrow").is_none());
+    }
+
+    #[test]
+    fn test_countdown() {
+        let sas = SasInfo::parse(URL).unwrap();
+        assert!(!sas.is_expired(at("2025-01-01T09:59:59Z")));
+        assert!(sas.is_expired(at("2025-01-01T10:00:00Z")));
+
+        assert_eq!(sas.countdown(at("2025-01-01T09:59:30Z")), "in 30s");
+        assert_eq!(sas.countdown(at("2025-01-01T09:00:02Z")), "in 59m 58s");
+        assert_eq!(sas.countdown(at("2025-01-01T07:30:00Z")), "in 2h 30m");
+        assert_eq!(sas.countdown(at("2025-01-01T10:05:00Z")), "5m 0s ago");
+        assert_eq!(sas.countdown(at("2025-01-04T12:00:00Z")), "3d 2h ago");
+        assert!(sas.summary(at("2025-01-01T10:05:00Z")).contains("EXPIRED"));
+    }
+}
@@ -0,0 +1,427 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{
+        AzuriteConfig, AzuriteStorage, BlobConflict, BlobPage, Download, OverwritePolicy,
+        PushOutcome, TokenProvider,
+        auto_download::{self, PendingDownload},
+        reconfigure_azurite_storage, try_reinit_azurite_storage,
+    },
+    crate::{
+        error::DMError,
+        mqtt_ctrl::evp::{evp_state::UUID, module::ModuleInfo},
+    },
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jerror, jinfo},
+    std::collections::HashMap,
+};
+
+/// What the uploaded files are used for once the upload completes
+#[derive(Debug, Clone, PartialEq)]
+pub enum UploadPurpose {
+    Module,
+    /// Files marked in the file browser, the result of each of them is displayed
+    Batch,
+    OtaFirmware,
+}
+
+/// Azurite operation run on the job worker thread
+#[derive(Debug, Clone)]
+pub enum AzuriteJob {
+    /// Lists the modules of the default container and issues their SAS URLs,
+    /// the known modules keep their ID
+    UpdateModules(HashMap<UUID, ModuleInfo>),
+    /// Lists the upload containers of the token providers
+    ScanContainers,
+    /// Lists a page of the blobs of a container, for the log files screen or the blobs
+    /// screen. `marker` is the continuation token of the page, None for the first one.
+    ListBlobs {
+        container: String,
+        log_files: bool,
+        marker: Option<String>,
+    },
+    /// Downloads a blob to `dir`, the current directory if None, reporting the bytes done
+    Download {
+        container: String,
+        blob: String,
+        dir: Option<String>,
+    },
+    /// Uploads local files to the default container, then updates the modules
+    Upload {
+        paths: Vec<String>,
+        purpose: UploadPurpose,
+        modules: HashMap<UUID, ModuleInfo>,
+        /// Policy of the files whose blob already exists, the conflicts of the files not
+        /// in it are reported
+        overwrite: HashMap<String, OverwritePolicy>,
+    },
+    /// Removes module blobs from the default container, then updates the modules
+    RemoveModules {
+        names: Vec<String>,
+        modules: HashMap<UUID, ModuleInfo>,
+        /// Modules marked in the module list, the result of each of them is displayed
+        batch: bool,
+    },
+    /// Deletes the container of a token provider
+    RemoveTokenProvider { uuid: UUID, container: String },
+    /// Checks the new storage settings and replaces the global storage once they answer
+    Reconfigure(AzuriteConfig),
+    /// Checks that the storage answers, creating the global storage if it is missing
+    CheckConnection,
+    /// Downloads the watched blobs the device has uploaded to `dir`
+    AutoDownload {
+        pending: Vec<PendingDownload>,
+        dir: String,
+    },
+}
+
+/// Label of the job downloading the blob, also used to follow its progress
+pub fn download_label(container: &str, blob: &str) -> String {
+    format!("Downloading {}/{}", container, blob)
+}
+
+impl AzuriteJob {
+    pub fn label(&self) -> String {
+        match self {
+            AzuriteJob::UpdateModules(_) => "Updating modules".to_owned(),
+            AzuriteJob::ScanContainers => "Scanning containers".to_owned(),
+            AzuriteJob::ListBlobs {
+                container,
+                marker: None,
+                ..
+            } => format!("Listing blobs of {}", container),
+            AzuriteJob::ListBlobs { container, .. } => {
+                format!("Listing more blobs of {}", container)
+            }
+            AzuriteJob::Download {
+                container, blob, ..
+            } => download_label(container, blob),
+            AzuriteJob::Upload { paths, .. } if paths.len() == 1 => {
+                let name = std::path::Path::new(&paths[0])
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or(paths[0].clone());
+                format!("Uploading {}", name)
+            }
+            AzuriteJob::Upload { paths, .. } => format!("Uploading {} files", paths.len()),
+            AzuriteJob::RemoveModules { names, .. } if names.len() == 1 => {
+                format!("Removing {}", names[0])
+            }
+            AzuriteJob::RemoveModules { names, .. } => {
+                format!("Removing {} modules", names.len())
+            }
+            AzuriteJob::RemoveTokenProvider { container, .. } => {
+                format!("Removing container {}", container)
+            }
+            AzuriteJob::Reconfigure(config) => format!("Connecting to {}", config.url),
+            AzuriteJob::CheckConnection => "Checking Azurite connection".to_owned(),
+            AzuriteJob::AutoDownload { .. } => "Polling upload containers".to_owned(),
+        }
+    }
+
+    /// Operation and the container, blob or files it works on. Jobs with the same key are
+    /// not queued twice.
+    pub fn key(&self) -> String {
+        match self {
+            AzuriteJob::UpdateModules(_) => "update-modules".to_owned(),
+            AzuriteJob::ScanContainers => "scan-containers".to_owned(),
+            AzuriteJob::ListBlobs {
+                container, marker, ..
+            } => format!(
+                "list-blobs {}/{}",
+                container,
+                marker.as_deref().unwrap_or_default()
+            ),
+            AzuriteJob::Download {
+                container,
+                blob,
+                dir,
+            } => format!(
+                "download {}/{} {}",
+                container,
+                blob,
+                dir.as_deref().unwrap_or(".")
+            ),
+            AzuriteJob::Upload { paths, .. } => format!("upload {}", paths.join(" ")),
+            AzuriteJob::RemoveModules { names, .. } => format!("remove {}", names.join(" ")),
+            AzuriteJob::RemoveTokenProvider { container, .. } => {
+                format!("remove-container {}", container)
+            }
+            AzuriteJob::Reconfigure(_) => "reconfigure".to_owned(),
+            AzuriteJob::CheckConnection => "check-connection".to_owned(),
+            AzuriteJob::AutoDownload { .. } => "auto-download".to_owned(),
+        }
+    }
+
+    /// Periodic checks started by the App itself rather than by the user
+    pub fn is_background(&self) -> bool {
+        matches!(
+            self,
+            AzuriteJob::CheckConnection | AzuriteJob::AutoDownload { .. }
+        )
+    }
+}
+
+/// Completion event of a job, applied to the global storage and the App in `update()`
+#[derive(Debug)]
+pub enum AzuriteJobResult {
+    Downloaded {
+        container: String,
+        blob: String,
+        result: Result<Download, DMError>,
+    },
+    Modules(Result<HashMap<UUID, ModuleInfo>, DMError>),
+    TokenProviders(Result<HashMap<UUID, TokenProvider>, DMError>),
+    Blobs {
+        container: String,
+        log_files: bool,
+        /// Continuation token the page was requested with
+        marker: Option<String>,
+        result: Result<BlobPage, DMError>,
+    },
+    Uploaded {
+        purpose: UploadPurpose,
+        /// Result of each local file, the name of the uploaded blob
+        uploads: Vec<(String, Result<String, DMError>)>,
+        /// Files not uploaded since their blob already exists
+        conflicts: Vec<BlobConflict>,
+        modules: Result<HashMap<UUID, ModuleInfo>, DMError>,
+    },
+    RemovedModules {
+        batch: bool,
+        /// Result of each module
+        removals: Vec<(String, Result<(), DMError>)>,
+        modules: Result<HashMap<UUID, ModuleInfo>, DMError>,
+    },
+    RemovedTokenProvider {
+        uuid: UUID,
+        result: Result<(), DMError>,
+    },
+    /// URL of the new settings and the containers found there
+    Reconfigured {
+        url: String,
+        result: Result<usize, DMError>,
+    },
+    /// Containers found by the connection check
+    Health(Result<usize, DMError>),
+    /// Watched blobs which were uploaded, with the local path they were downloaded to
+    AutoDownloaded(Vec<(PendingDownload, Result<String, DMError>)>),
+}
+
+impl AzuriteJobResult {
+    /// First error of the job, the failed uploads of a batch included
+    pub fn error(&self) -> Option<&Report<DMError>> {
+        match self {
+            AzuriteJobResult::Downloaded { result, .. } => result.as_ref().err(),
+            AzuriteJobResult::Modules(result) => result.as_ref().err(),
+            AzuriteJobResult::TokenProviders(result) => result.as_ref().err(),
+            AzuriteJobResult::Blobs { result, .. } => result.as_ref().err(),
+            AzuriteJobResult::Uploaded {
+                uploads, modules, ..
+            } => uploads
+                .iter()
+                .find_map(|(_, r)| r.as_ref().err())
+                .or(modules.as_ref().err()),
+            AzuriteJobResult::RemovedModules {
+                removals, modules, ..
+            } => removals
+                .iter()
+                .find_map(|(_, r)| r.as_ref().err())
+                .or(modules.as_ref().err()),
+            AzuriteJobResult::RemovedTokenProvider { result, .. } => result.as_ref().err(),
+            AzuriteJobResult::Reconfigured { result, .. } => result.as_ref().err(),
+            AzuriteJobResult::Health(result) => result.as_ref().err(),
+            AzuriteJobResult::AutoDownloaded(downloads) => {
+                downloads.iter().find_map(|(_, r)| r.as_ref().err())
+            }
+        }
+    }
+}
+
+fn unavailable<T>() -> Result<T, DMError> {
+    Err(Report::new(DMError::RuntimeError).attach_printable("Azurite storage not available"))
+}
+
+/// Checks the new settings with a storage of their own, the current one is not used
+fn reconfigure(config: AzuriteConfig) -> AzuriteJobResult {
+    AzuriteJobResult::Reconfigured {
+        url: config.url.clone(),
+        result: reconfigure_azurite_storage(config),
+    }
+}
+
+/// Runs a job, reporting the files done for uploads and the bytes done for downloads
+pub fn run(
+    storage: Option<&mut AzuriteStorage>,
+    job: AzuriteJob,
+    progress: &mut dyn FnMut(usize, usize),
+) -> AzuriteJobResult {
+    let Some(storage) = storage else {
+        return match job {
+            AzuriteJob::UpdateModules(_) => AzuriteJobResult::Modules(unavailable()),
+            AzuriteJob::ScanContainers => AzuriteJobResult::TokenProviders(unavailable()),
+            AzuriteJob::Download {
+                container, blob, ..
+            } => AzuriteJobResult::Downloaded {
+                container,
+                blob,
+                result: unavailable(),
+            },
+            AzuriteJob::ListBlobs {
+                container,
+                log_files,
+                marker,
+            } => AzuriteJobResult::Blobs {
+                container,
+                log_files,
+                marker,
+                result: unavailable(),
+            },
+            AzuriteJob::Upload { paths, purpose, .. } => AzuriteJobResult::Uploaded {
+                purpose,
+                uploads: paths.into_iter().map(|p| (p, unavailable())).collect(),
+                conflicts: vec![],
+                modules: unavailable(),
+            },
+            AzuriteJob::RemoveModules { names, batch, .. } => AzuriteJobResult::RemovedModules {
+                batch,
+                removals: names.into_iter().map(|n| (n, unavailable())).collect(),
+                modules: unavailable(),
+            },
+            AzuriteJob::RemoveTokenProvider { uuid, .. } => {
+                AzuriteJobResult::RemovedTokenProvider {
+                    uuid,
+                    result: unavailable(),
+                }
+            }
+            AzuriteJob::Reconfigure(config) => reconfigure(config),
+            AzuriteJob::CheckConnection => AzuriteJobResult::Health(unavailable()),
+            AzuriteJob::AutoDownload { .. } => AzuriteJobResult::AutoDownloaded(vec![]),
+        };
+    };
+
+    match job {
+        AzuriteJob::UpdateModules(modules) => {
+            storage.module_info_db = modules;
+            AzuriteJobResult::Modules(
+                storage
+                    .update_modules(None)
+                    .map(|_| storage.module_info_db.clone()),
+            )
+        }
+        AzuriteJob::ScanContainers => AzuriteJobResult::TokenProviders(
+            storage
+                .scan_upload_containers()
+                .map(|_| storage.token_providers.clone()),
+        ),
+        AzuriteJob::Download {
+            container,
+            blob,
+            dir,
+        } => {
+            let result =
+                storage.download_blob(&container, &blob, dir.as_deref(), &mut |done, total| {
+                    progress(done as usize, total as usize)
+                });
+            AzuriteJobResult::Downloaded {
+                container,
+                blob,
+                result,
+            }
+        }
+        AzuriteJob::ListBlobs {
+            container,
+            log_files,
+            marker,
+        } => {
+            let result = storage.list_blobs_page(&container, marker.as_deref());
+            AzuriteJobResult::Blobs {
+                container,
+                log_files,
+                marker,
+                result,
+            }
+        }
+        AzuriteJob::Upload {
+            paths,
+            purpose,
+            modules,
+            overwrite,
+        } => {
+            storage.module_info_db = modules;
+            let total = paths.len();
+            let mut uploads = vec![];
+            let mut conflicts = vec![];
+            for (i, path) in paths.into_iter().enumerate() {
+                let policy = overwrite.get(&path).cloned().unwrap_or_default();
+                match storage.push_blob_guarded(None, &path, &policy) {
+                    Ok(PushOutcome::Uploaded(blob)) => uploads.push((path, Ok(blob))),
+                    Ok(PushOutcome::Conflict(conflict)) => conflicts.push(conflict),
+                    Err(e) => uploads.push((path, Err(e))),
+                }
+                progress(i + 1, total);
+            }
+            AzuriteJobResult::Uploaded {
+                purpose,
+                uploads,
+                conflicts,
+                modules: storage
+                    .update_modules(None)
+                    .map(|_| storage.module_info_db.clone()),
+            }
+        }
+        AzuriteJob::RemoveModules {
+            names,
+            modules,
+            batch,
+        } => {
+            storage.module_info_db = modules;
+            let total = names.len();
+            let mut removals = vec![];
+            for (i, name) in names.into_iter().enumerate() {
+                let result = storage.remove_blob(None, &name);
+                removals.push((name, result));
+                progress(i + 1, total);
+            }
+            AzuriteJobResult::RemovedModules {
+                batch,
+                removals,
+                modules: storage
+                    .update_modules(None)
+                    .map(|_| storage.module_info_db.clone()),
+            }
+        }
+        AzuriteJob::RemoveTokenProvider { uuid, container } => {
+            AzuriteJobResult::RemovedTokenProvider {
+                uuid,
+                result: storage.delete_container(&container),
+            }
+        }
+        AzuriteJob::Reconfigure(config) => reconfigure(config),
+        AzuriteJob::CheckConnection => {
+            let result = storage.check_connection();
+            if result.is_ok() && try_reinit_azurite_storage() {
+                jinfo!("AzuriteStorage successfully reinitialized by the connection check");
+            }
+            AzuriteJobResult::Health(result)
+        }
+        AzuriteJob::AutoDownload { pending, dir } => {
+            AzuriteJobResult::AutoDownloaded(auto_download::download_ready(storage, &pending, &dir))
+        }
+    }
+}
@@ -0,0 +1,283 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::DMError,
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jerror, jinfo},
+    std::{
+        fs::File,
+        net::{SocketAddr, TcpStream},
+        path::{Path, PathBuf},
+        process::{Child, Command, Stdio},
+        time::{Duration, Instant},
+    },
+};
+
+/// Image of the broker started with --docker
+pub const MOSQUITTO_IMAGE: &str = "eclipse-mosquitto:2";
+
+/// Time given to the broker to accept connections
+pub const START_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Files written to the data directory
+pub const CONFIG_FILE: &str = "mosquitto.conf";
+pub const LOG_FILE: &str = "mosquitto.log";
+
+/// Where the data directory is mounted in the container
+const CONTAINER_DATA_DIR: &str = "/mosquitto/data";
+
+/// Broker started by `device-monitor broker up`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokerConfig {
+    pub port: u16,
+    /// Persistence database, configuration and log of the broker
+    pub data_dir: PathBuf,
+    /// Run the broker in a container instead of the local mosquitto
+    pub docker: bool,
+}
+
+impl BrokerConfig {
+    fn container_name(&self) -> String {
+        format!("device-monitor-broker-{}", self.port)
+    }
+
+    /// Anonymous listener on all the interfaces so that the device can connect, with the
+    /// sessions and retained messages kept across restarts
+    pub fn mosquitto_conf(&self) -> String {
+        let (port, data_dir) = if self.docker {
+            // The port is mapped by docker
+            (1883, PathBuf::from(CONTAINER_DATA_DIR))
+        } else {
+            (self.port, self.data_dir.clone())
+        };
+
+        format!(
+            "listener {port} 0.0.0.0\n\
+             allow_anonymous true\n\
+             persistence true\n\
+             persistence_location {}/\n\
+             log_dest stdout\n",
+            data_dir.display()
+        )
+    }
+
+    /// Command starting the broker in the foreground, `data_dir` must be absolute
+    pub fn command(&self, data_dir: &Path) -> Command {
+        let conf = data_dir.join(CONFIG_FILE);
+        if self.docker {
+            let mut command = Command::new("docker");
+            command
+                .args(["run", "--rm", "--name", &self.container_name()])
+                .args(["-p", &format!("{}:1883", self.port)])
+                .args([
+                    "-v",
+                    &format!("{}:{CONTAINER_DATA_DIR}", data_dir.display()),
+                ])
+                .args([
+                    MOSQUITTO_IMAGE,
+                    "mosquitto",
+                    "-c",
+                    &format!("{CONTAINER_DATA_DIR}/{CONFIG_FILE}"),
+                ]);
+            command
+        } else {
+            let mut command = Command::new("mosquitto");
+            command.arg("-c").arg(conf);
+            command
+        }
+    }
+}
+
+fn is_listening(port: u16) -> bool {
+    TcpStream::connect_timeout(
+        &SocketAddr::from(([127, 0, 0, 1], port)),
+        Duration::from_millis(200),
+    )
+    .is_ok()
+}
+
+/// Broker of the session, stopped when dropped
+#[derive(Debug)]
+pub struct LocalBroker {
+    config: BrokerConfig,
+    child: Child,
+}
+
+impl LocalBroker {
+    /// Writes the configuration, starts the broker and waits until it accepts connections
+    pub fn start(config: &BrokerConfig) -> Result<Self, DMError> {
+        if is_listening(config.port) {
+            return Err(Report::new(DMError::RuntimeError).attach_printable(format!(
+                "Port {} is already in use, run `device-monitor -b localhost:{}` to use the broker listening on it",
+                config.port, config.port
+            )));
+        }
+
+        let io_error = |path: &Path, e: std::io::Error| {
+            Report::new(DMError::IOError)
+                .attach_printable(format!("Failed to write {}: {e}", path.display()))
+        };
+        std::fs::create_dir_all(&config.data_dir).map_err(|e| io_error(&config.data_dir, e))?;
+        let data_dir = config
+            .data_dir
+            .canonicalize()
+            .map_err(|e| io_error(&config.data_dir, e))?;
+        let conf = data_dir.join(CONFIG_FILE);
+        std::fs::write(&conf, config.mosquitto_conf()).map_err(|e| io_error(&conf, e))?;
+
+        // The terminal belongs to the UI, the broker writes to its log file
+        let log = data_dir.join(LOG_FILE);
+        let stdout = File::create(&log).map_err(|e| io_error(&log, e))?;
+        let stderr = stdout.try_clone().map_err(|e| io_error(&log, e))?;
+
+        let mut command = config.command(&data_dir);
+        jinfo!(
+            func = "LocalBroker::start()",
+            command = format!("{command:?}")
+        );
+        let child = command
+            .stdin(Stdio::null())
+            .stdout(stdout)
+            .stderr(stderr)
+            .spawn()
+            .map_err(|e| {
+                let program = if config.docker { "docker" } else { "mosquitto" };
+                Report::new(DMError::RuntimeError).attach_printable(format!(
+                    "Failed to run {program}: {e}, install it or use {}",
+                    if config.docker {
+                        "the local mosquitto (without --docker)"
+                    } else {
+                        "--docker"
+                    }
+                ))
+            })?;
+
+        let mut broker = Self {
+            config: config.clone(),
+            child,
+        };
+        broker.wait_ready(START_TIMEOUT, &log)?;
+        Ok(broker)
+    }
+
+    fn wait_ready(&mut self, timeout: Duration, log: &Path) -> Result<(), DMError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Ok(Some(status)) = self.child.try_wait() {
+                return Err(Report::new(DMError::RuntimeError).attach_printable(format!(
+                    "The broker exited with {status}, see {}",
+                    log.display()
+                )));
+            }
+
+            if is_listening(self.config.port) {
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                return Err(Report::new(DMError::Timeout).attach_printable(format!(
+                    "The broker didn't listen on port {} within {}s, see {}",
+                    self.config.port,
+                    timeout.as_secs(),
+                    log.display()
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Address the UI connects to
+    pub fn address(&self) -> String {
+        format!("localhost:{}", self.config.port)
+    }
+}
+
+impl Drop for LocalBroker {
+    fn drop(&mut self) {
+        // Killing the docker client would leave the container running
+        if self.config.docker {
+            let _ = Command::new("docker")
+                .args(["stop", &self.config.container_name()])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(docker: bool) -> BrokerConfig {
+        BrokerConfig {
+            port: 1884,
+            data_dir: PathBuf::from("/tmp/broker"),
+            docker,
+        }
+    }
+
+    #[test]
+    fn test_mosquitto_conf() {
+        let conf = config(false).mosquitto_conf();
+        assert!(conf.contains("listener 1884 0.0.0.0\n"));
+        assert!(conf.contains("allow_anonymous true\n"));
+        assert!(conf.contains("persistence_location /tmp/broker/\n"));
+
+        let conf = config(true).mosquitto_conf();
+        assert!(conf.contains("listener 1883 0.0.0.0\n"));
+        assert!(conf.contains("persistence_location /mosquitto/data/\n"));
+    }
+
+    #[test]
+    fn test_command() {
+        let command = config(false).command(Path::new("/tmp/broker"));
+        assert_eq!(command.get_program(), "mosquitto");
+        let args: Vec<_> = command.get_args().map(|a| a.to_string_lossy()).collect();
+        assert_eq!(args.join(" "), "-c /tmp/broker/mosquitto.conf");
+
+        let command = config(true).command(Path::new("/tmp/broker"));
+        assert_eq!(command.get_program(), "docker");
+        let args: Vec<_> = command.get_args().map(|a| a.to_string_lossy()).collect();
+        assert_eq!(
+            args.join(" "),
+            "run --rm --name device-monitor-broker-1884 -p 1884:1883 \
+             -v /tmp/broker:/mosquitto/data eclipse-mosquitto:2 \
+             mosquitto -c /mosquitto/data/mosquitto.conf"
+        );
+    }
+
+    #[test]
+    fn test_start_port_in_use() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let dir = std::env::temp_dir().join(format!("dm_broker_{port}"));
+
+        let e = LocalBroker::start(&BrokerConfig {
+            port,
+            data_dir: dir.clone(),
+            docker: false,
+        })
+        .unwrap_err();
+        assert!(format!("{e:?}").contains("already in use"));
+        // Nothing is written when the broker can't be started
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,87 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::DMError,
+    base64::{Engine as _, engine::general_purpose},
+    error_stack::{Report, Result},
+    std::{
+        io::Write,
+        sync::{LazyLock, Mutex},
+    },
+};
+
+/// System clipboard, kept open as on X11 the copied text is served by its owner and would
+/// disappear with it. None when no clipboard is reachable, e.g. over SSH or in a container.
+static CLIPBOARD: LazyLock<Mutex<Option<arboard::Clipboard>>> =
+    LazyLock::new(|| Mutex::new(arboard::Clipboard::new().ok()));
+
+/// OSC 52 sequence setting the system clipboard to `text`. Inside tmux the sequence is
+/// wrapped in a DCS passthrough so that it reaches the outer terminal.
+pub fn osc52(text: &str, tmux: bool) -> String {
+    let sequence = format!(
+        "\x1b]52;c;{}\x07",
+        general_purpose::STANDARD.encode(text.as_bytes())
+    );
+
+    if tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// Copies `text` through the terminal with OSC 52
+fn copy_osc52(text: &str) -> Result<(), DMError> {
+    let sequence = osc52(text, std::env::var_os("TMUX").is_some());
+    let mut stdout = std::io::stdout();
+
+    stdout
+        .write_all(sequence.as_bytes())
+        .and_then(|_| stdout.flush())
+        .map_err(|e| {
+            Report::new(DMError::IOError)
+                .attach_printable(format!("Failed to copy to clipboard: {}", e))
+        })
+}
+
+/// Copies `text` to the system clipboard. When it cannot be reached, e.g. when
+/// device-monitor runs over SSH or inside a container, the text is sent to the terminal
+/// with OSC 52 instead.
+pub fn copy(text: &str) -> Result<(), DMError> {
+    let copied = CLIPBOARD
+        .lock()
+        .ok()
+        .and_then(|mut clipboard| clipboard.as_mut().map(|c| c.set_text(text).is_ok()))
+        .unwrap_or(false);
+
+    if copied { Ok(()) } else { copy_osc52(text) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(osc52("hello", false), "\x1b]52;c;aGVsbG8=\x07");
+        assert_eq!(
+            osc52("hello", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGVsbG8=\x07\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        daemon::StateSnapshot,
+        error::DMError,
+        versioned::{self, Versioned},
+    },
+    chrono::Local,
+    error_stack::{Report, Result},
+    serde_json::Value,
+};
+
+/// Sections of the device state compared, in the order they are listed
+pub const SECTIONS: [&str; 7] = [
+    "main_chip",
+    "companion_chip",
+    "sensor_chip",
+    "system_settings",
+    "network_settings",
+    "wireless_settings",
+    "deployment_status",
+];
+
+/// Fields changing from one report to the next, which tell nothing about the device
+const VOLATILE: [&str; 2] = ["temperature", "req_id"];
+
+/// One field of the device state, in this device and in the reference one
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompareRow {
+    /// e.g. "main_chip.firmware_version"
+    pub key: String,
+    /// None when the device has not reported it
+    pub device: Option<String>,
+    pub reference: Option<String>,
+}
+
+impl CompareRow {
+    pub fn differs(&self) -> bool {
+        self.device != self.reference
+    }
+}
+
+/// Leaves of `value` with their dotted paths, e.g. "ai_models[0].version"
+fn flatten(prefix: &str, value: &Value, fields: &mut Vec<(String, String)>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                if !VOLATILE.contains(&key.as_str()) {
+                    flatten(&join(key), value, fields);
+                }
+            }
+        }
+        Value::Array(array) if !array.is_empty() => {
+            for (i, value) in array.iter().enumerate() {
+                flatten(&format!("{prefix}[{i}]"), value, fields);
+            }
+        }
+        Value::Null => {}
+        Value::String(s) => fields.push((prefix.to_owned(), s.clone())),
+        _ => fields.push((prefix.to_owned(), value.to_string())),
+    }
+}
+
+/// Fields of the compared sections of both states, in the order of `SECTIONS` then of the
+/// keys. A field reported by only one of them has None on the other side.
+pub fn compare(device: &Value, reference: &Value) -> Vec<CompareRow> {
+    let mut rows: Vec<CompareRow> = vec![];
+    for section in SECTIONS {
+        let (mut device_fields, mut reference_fields) = (vec![], vec![]);
+        flatten(section, &device[section], &mut device_fields);
+        flatten(section, &reference[section], &mut reference_fields);
+
+        let mut section_rows: Vec<CompareRow> = device_fields
+            .into_iter()
+            .map(|(key, value)| CompareRow {
+                key,
+                device: Some(value),
+                reference: None,
+            })
+            .collect();
+        for (key, value) in reference_fields {
+            match section_rows.iter_mut().find(|row| row.key == key) {
+                Some(row) => row.reference = Some(value),
+                None => section_rows.push(CompareRow {
+                    key,
+                    device: None,
+                    reference: Some(value),
+                }),
+            }
+        }
+        section_rows.sort_by(|a, b| a.key.cmp(&b.key));
+        rows.extend(section_rows);
+    }
+    rows
+}
+
+/// Reads the state of the reference device: a state snapshot written by the daemon mode or
+/// the Compare screen, or the state alone
+pub fn load_reference(path: &str) -> Result<Value, DMError> {
+    let s = std::fs::read_to_string(path).map_err(|e| {
+        Report::new(DMError::IOError).attach_printable(format!("Failed to read {path}: {e}"))
+    })?;
+    let mut value: Value = serde_json::from_str(&s).map_err(|e| {
+        Report::new(DMError::InvalidData).attach_printable(format!("Invalid {path}: {e}"))
+    })?;
+
+    match value.get_mut("state") {
+        Some(state) => Ok(state.take()),
+        None => Ok(value),
+    }
+}
+
+/// Saves the state of this device as a state snapshot, to compare other devices with it
+pub fn save_reference(path: &str, state: &Value) -> Result<(), DMError> {
+    versioned::save(
+        path,
+        &StateSnapshot {
+            time: Local::now().to_rfc3339(),
+            state: state.clone(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compare() {
+        let device = json!({
+            "main_chip": {"id": "1", "temperature": 40, "firmware_version": "D52408",
+                "ai_models": [{"version": "0308000000000100"}]},
+            "system_settings": {"led_enabled": true, "req_info": {"req_id": "5"}},
+            "network_settings": null,
+        });
+        let reference = json!({
+            "main_chip": {"id": "2", "temperature": 45, "firmware_version": "D52408",
+                "ai_models": []},
+            "system_settings": {"led_enabled": false, "req_info": {"req_id": "9"}},
+            "network_settings": {"ntp_url": "pool.ntp.org"},
+        });
+
+        let rows = compare(&device, &reference);
+        let row = |key: &str| rows.iter().find(|r| r.key == key).unwrap();
+        assert_eq!(
+            rows.iter().map(|r| r.key.as_str()).collect::<Vec<_>>(),
+            [
+                "main_chip.ai_models",
+                "main_chip.ai_models[0].version",
+                "main_chip.firmware_version",
+                "main_chip.id",
+                "system_settings.led_enabled",
+                "network_settings.ntp_url",
+            ]
+        );
+        assert!(!row("main_chip.firmware_version").differs());
+        assert!(row("main_chip.id").differs());
+        assert_eq!(row("main_chip.ai_models[0].version").reference, None);
+        assert_eq!(row("main_chip.ai_models").device, None);
+        assert_eq!(row("main_chip.ai_models").reference.as_deref(), Some("[]"));
+        assert_eq!(row("network_settings.ntp_url").device, None);
+    }
+
+    #[test]
+    fn test_reference_file() {
+        let path = std::env::temp_dir().join(format!("reference_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let state = json!({"main_chip": {"firmware_version": "D52408"}});
+
+        save_reference(path, &state).unwrap();
+        assert_eq!(load_reference(path).unwrap(), state);
+
+        std::fs::write(path, state.to_string()).unwrap();
+        assert_eq!(load_reference(path).unwrap(), state);
+        std::fs::remove_file(path).unwrap();
+        assert!(load_reference(path).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        error::DMError,
+        mqtt_ctrl,
+        script::ScriptTarget,
+        versioned::{self, Versioned},
+    },
+    chrono::{DateTime, Local},
+    error_stack::{Report, Result},
+    jlogger_tracing::jerror,
+    ratatui::crossterm::{
+        cursor::Show,
+        event::DisableMouseCapture,
+        execute,
+        terminal::{LeaveAlternateScreen, disable_raw_mode},
+    },
+    serde::{Deserialize, Serialize},
+    serde_json::Value,
+    std::{
+        backtrace::Backtrace,
+        path::{Path, PathBuf},
+        sync::atomic::{AtomicBool, Ordering},
+    },
+};
+
+/// Prefix of the crash report files
+pub const CRASH_PREFIX: &str = "crash";
+
+/// Whether the terminal is in raw mode on the alternate screen
+static TERMINAL_TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Called once the TUI has taken the terminal over
+pub fn terminal_taken() {
+    TERMINAL_TAKEN.store(true, Ordering::SeqCst);
+}
+
+/// Leaves the raw mode and the alternate screen. Only the first call restores the terminal,
+/// whichever of dm_teardown(), the TerminalGuard and the panic hook comes first.
+pub fn restore_terminal() -> std::io::Result<()> {
+    if !TERMINAL_TAKEN.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    // The screen is restored even if the raw mode cannot be left
+    let raw_mode = disable_raw_mode();
+    execute!(
+        std::io::stderr(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        Show
+    )?;
+    raw_mode
+}
+
+/// Restores the terminal when dropped, on the early returns of main() as well
+pub struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore_terminal();
+    }
+}
+
+/// Content of the crash report files
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CrashReport {
+    /// RFC 3339
+    pub time: String,
+    pub thread: String,
+    /// Message and location of the panic
+    pub panic: String,
+    pub backtrace: String,
+    /// Device state when the panic happened, None if the MqttCtrl was locked, e.g. by the
+    /// code which panicked
+    pub state: Option<Value>,
+}
+
+impl Versioned for CrashReport {
+    const KIND: &'static str = "crash report";
+    const VERSION: u32 = 1;
+}
+
+/// Writes the report to `<dir>/crash_<time>.json`, the directory is created if needed
+pub fn write_crash_report(
+    dir: &Path,
+    time: DateTime<Local>,
+    report: &CrashReport,
+) -> Result<PathBuf, DMError> {
+    let path = dir.join(format!(
+        "{CRASH_PREFIX}_{}.json",
+        time.format("%Y%m%d_%H%M%S")
+    ));
+    let content = versioned::to_json(report)?;
+    std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&path, content))
+        .map_err(|e| {
+            Report::new(DMError::IOError)
+                .attach_printable(format!("Failed to write {}: {e}", path.display()))
+        })?;
+    Ok(path)
+}
+
+/// What the panic hook tells besides the panic
+#[derive(Debug, Clone, Default)]
+pub struct CrashConfig {
+    /// --log file, pointed to after the panic
+    pub log_file: Option<String>,
+    /// Directory the crash reports are written to, none are written if None
+    pub report_dir: Option<PathBuf>,
+}
+
+/// Restores the terminal before the panic is printed, so that it is readable and the shell
+/// usable, then points to the log file and writes the crash report.
+///
+/// The panics of the background threads do not end the UI, they are only logged while the
+/// terminal is taken over.
+pub fn install_panic_hook(config: CrashConfig) {
+    let default_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        let current = std::thread::current();
+        let thread = current.name().unwrap_or("<unnamed>");
+        jerror!(func = "panic", thread = thread, error = info.to_string());
+
+        if thread != "main" && TERMINAL_TAKEN.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let _ = restore_terminal();
+        default_hook(info);
+
+        match &config.log_file {
+            Some(log_file) => eprintln!("The log of device-monitor is in {log_file}"),
+            None => eprintln!("Start device-monitor with --log <FILE> to keep a log"),
+        }
+
+        // Last, a panic while the state is read aborts without the rest
+        if let Some(dir) = &config.report_dir {
+            let now = Local::now();
+            let report = CrashReport {
+                time: now.to_rfc3339(),
+                thread: thread.to_owned(),
+                panic: info.to_string(),
+                backtrace: Backtrace::force_capture().to_string(),
+                state: mqtt_ctrl::try_with_global_mqtt_ctrl(|mqtt_ctrl| mqtt_ctrl.state()),
+            };
+            match write_crash_report(dir, now, &report) {
+                Ok(path) => eprintln!("Crash report written to {}", path.display()),
+                Err(e) => eprintln!("Failed to write the crash report: {:?}", e),
+            }
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt_ctrl::evp::evp_state::UUID;
+
+    #[test]
+    fn test_write_crash_report() {
+        let dir = std::env::temp_dir().join(format!("dm_crash_{}", UUID::new_string()));
+        let time = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Local);
+        let report = CrashReport {
+            time: time.to_rfc3339(),
+            thread: "main".to_owned(),
+            panic: "panicked at src/app.rs:1:1:\nindex out of bounds".to_owned(),
+            backtrace: String::new(),
+            state: Some(serde_json::json!({"connected": true})),
+        };
+
+        let path = write_crash_report(&dir, time, &report).unwrap();
+        assert_eq!(
+            path,
+            dir.join(format!("crash_{}.json", time.format("%Y%m%d_%H%M%S")))
+        );
+        let read: CrashReport =
+            versioned::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(read, report);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
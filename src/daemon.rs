@@ -0,0 +1,275 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        error::DMError,
+        mqtt_ctrl::evp::elog::{ElogArchive, ElogEntry},
+        versioned::{self, Versioned},
+    },
+    chrono::{DateTime, Local},
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jinfo},
+    serde::{Deserialize, Serialize},
+    serde_json::Value,
+    std::{
+        path::{Path, PathBuf},
+        time::{Duration, Instant},
+    },
+};
+
+/// Prefix of the state snapshot files
+pub const STATE_PREFIX: &str = "state";
+
+/// Prefix of the elog archive files
+pub const ELOGS_PREFIX: &str = "elogs";
+
+/// Content of the state snapshot files
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StateSnapshot {
+    /// RFC 3339
+    pub time: String,
+    pub state: Value,
+}
+
+impl Versioned for StateSnapshot {
+    const KIND: &'static str = "state snapshot";
+    const VERSION: u32 = 1;
+}
+
+/// Where and how often the daemon writes its files
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaemonConfig {
+    pub dir: PathBuf,
+    pub interval: Duration,
+    /// Files of each kind kept in the directory, the oldest ones are removed
+    pub keep: usize,
+}
+
+/// Removes the oldest `<prefix>_<time>.json` files of the directory so that `keep` remain.
+/// The time in the names sorts them from the oldest to the newest.
+pub fn rotate(dir: &Path, prefix: &str, keep: usize) -> Result<Vec<PathBuf>, DMError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        Report::new(DMError::IOError)
+            .attach_printable(format!("Failed to read {}: {e}", dir.display()))
+    })?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&format!("{prefix}_")) && n.ends_with(".json"))
+        })
+        .collect();
+    files.sort();
+
+    let removed: Vec<PathBuf> = files.drain(..files.len().saturating_sub(keep)).collect();
+    for path in removed.iter() {
+        std::fs::remove_file(path).map_err(|e| {
+            Report::new(DMError::IOError)
+                .attach_printable(format!("Failed to remove {}: {e}", path.display()))
+        })?;
+    }
+    Ok(removed)
+}
+
+/// Writes the state snapshots and the elog archives of an unattended session
+#[derive(Debug)]
+pub struct Daemon {
+    config: DaemonConfig,
+    next_snapshot: Option<Instant>,
+    /// Elogs of the last archive, archived again only when they changed
+    archived_elogs: String,
+}
+
+impl Daemon {
+    pub fn new(config: DaemonConfig) -> Result<Self, DMError> {
+        std::fs::create_dir_all(&config.dir).map_err(|e| {
+            Report::new(DMError::IOError)
+                .attach_printable(format!("Failed to create {}: {e}", config.dir.display()))
+        })?;
+
+        Ok(Self {
+            config,
+            next_snapshot: None,
+            archived_elogs: String::new(),
+        })
+    }
+
+    /// Whether a snapshot is due, the first one is written at once
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.next_snapshot.is_none_or(|next| now >= next)
+    }
+
+    fn write(
+        &self,
+        prefix: &str,
+        time: DateTime<Local>,
+        content: &str,
+    ) -> Result<PathBuf, DMError> {
+        let path = self
+            .config
+            .dir
+            .join(format!("{prefix}_{}.json", time.format("%Y%m%d_%H%M%S")));
+        std::fs::write(&path, content).map_err(|e| {
+            Report::new(DMError::IOError)
+                .attach_printable(format!("Failed to write {}: {e}", path.display()))
+        })?;
+        rotate(&self.config.dir, prefix, self.config.keep)?;
+        Ok(path)
+    }
+
+    /// Writes the snapshot of the state, and the elogs when they changed since the last
+    /// archive. Returns the files written.
+    pub fn snapshot(
+        &mut self,
+        now: Instant,
+        time: DateTime<Local>,
+        state: &Value,
+        elogs: &[ElogEntry],
+    ) -> Result<Vec<PathBuf>, DMError> {
+        self.next_snapshot = Some(now + self.config.interval);
+
+        let snapshot = versioned::to_json(&StateSnapshot {
+            time: time.to_rfc3339(),
+            state: state.clone(),
+        })?;
+        let mut written = vec![self.write(STATE_PREFIX, time, &snapshot)?];
+
+        let archive = versioned::to_json(&ElogArchive {
+            elogs: elogs.to_vec(),
+        })?;
+        if !elogs.is_empty() && archive != self.archived_elogs {
+            written.push(self.write(ELOGS_PREFIX, time, &archive)?);
+            self.archived_elogs = archive;
+        }
+
+        jdebug!(
+            func = "Daemon::snapshot()",
+            line = line!(),
+            written = format!("{:?}", written)
+        );
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt_ctrl::evp::{elog::Elog, evp_state::UUID};
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("dm_daemon_{}", UUID::new_string()))
+    }
+
+    fn elog(event_id: u32) -> ElogEntry {
+        ElogEntry::new(
+            Elog::parse(&format!(
+                r#"{{"serial": "SN001", "level": 2, "timestamp": "2025-01-01T00:00:00Z",
+                    "component_id": 1, "event_id": {event_id}}}"#
+            ))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_rotate() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "state_20250101_000002.json",
+            "state_20250101_000000.json",
+            "state_20250101_000001.json",
+            "elogs_20250101_000000.json",
+            "notes.txt",
+        ] {
+            std::fs::write(dir.join(name), "{}").unwrap();
+        }
+
+        let removed = rotate(&dir, STATE_PREFIX, 2).unwrap();
+        assert_eq!(removed, vec![dir.join("state_20250101_000000.json")]);
+        assert!(dir.join("state_20250101_000002.json").exists());
+        assert!(dir.join("elogs_20250101_000000.json").exists());
+        assert!(dir.join("notes.txt").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let dir = temp_dir();
+        let mut daemon = Daemon::new(DaemonConfig {
+            dir: dir.clone(),
+            interval: Duration::from_secs(60),
+            keep: 2,
+        })
+        .unwrap();
+        let start = Instant::now();
+        let time = |s: &str| {
+            DateTime::parse_from_rfc3339(s)
+                .unwrap()
+                .with_timezone(&Local)
+        };
+        let state = serde_json::json!({"connected": true});
+
+        assert!(daemon.is_due(start));
+        let written = daemon
+            .snapshot(start, time("2025-01-01T00:00:00Z"), &state, &[elog(1)])
+            .unwrap();
+        assert_eq!(written.len(), 2);
+        let snapshot: StateSnapshot =
+            versioned::from_json(&std::fs::read_to_string(&written[0]).unwrap()).unwrap();
+        assert_eq!(snapshot.state["connected"], true);
+        let archive: ElogArchive =
+            versioned::from_json(&std::fs::read_to_string(&written[1]).unwrap()).unwrap();
+        assert_eq!(archive.elogs, vec![elog(1)]);
+        assert!(!daemon.is_due(start + Duration::from_secs(59)));
+        assert!(daemon.is_due(start + Duration::from_secs(60)));
+
+        // Unchanged elogs are not archived again
+        let written = daemon
+            .snapshot(start, time("2025-01-01T00:01:00Z"), &state, &[elog(1)])
+            .unwrap();
+        assert_eq!(written.len(), 1);
+
+        daemon
+            .snapshot(
+                start,
+                time("2025-01-01T00:02:00Z"),
+                &state,
+                &[elog(1), elog(2)],
+            )
+            .unwrap();
+        let count = |prefix: &str| {
+            std::fs::read_dir(&dir)
+                .unwrap()
+                .filter(|e| {
+                    e.as_ref()
+                        .unwrap()
+                        .file_name()
+                        .to_string_lossy()
+                        .starts_with(prefix)
+                })
+                .count()
+        };
+        assert_eq!(count(STATE_PREFIX), 2);
+        assert_eq!(count(ELOGS_PREFIX), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,286 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::{DMError, DMErrorExt},
+    error_stack::{Report, Result},
+    std::{
+        collections::VecDeque,
+        fs::File,
+        io::{Read, Seek, SeekFrom},
+        path::{Path, PathBuf},
+    },
+};
+
+/// Lines kept, the oldest ones are dropped beyond
+const MAX_LINES: usize = 5000;
+
+/// Bytes read from the end of a log file already large when it is opened
+const INITIAL_TAIL: u64 = 1024 * 1024;
+
+/// Levels of the jlogger lines, the most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        }
+    }
+
+    /// Level of a line, from the first level name among its first words, which hold the
+    /// time and the level. None for the continuation lines of multi-line reports.
+    pub fn parse(line: &str) -> Option<Self> {
+        line.split_whitespace().take(4).find_map(|word| {
+            let word = word.trim_matches(|c: char| !c.is_ascii_alphabetic());
+            LogLevel::ALL.into_iter().find(|level| level.name() == word)
+        })
+    }
+
+    /// Next filter, from errors only to every level and back
+    pub fn next(&self) -> Self {
+        let i = LogLevel::ALL.iter().position(|l| l == self).unwrap();
+        LogLevel::ALL[(i + 1) % LogLevel::ALL.len()]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub level: LogLevel,
+    pub text: String,
+}
+
+/// Tail of the log file of device-monitor itself, read as it grows
+#[derive(Debug)]
+pub struct DebugLog {
+    path: PathBuf,
+    /// Bytes of the file read so far
+    offset: u64,
+    /// Bytes after the last newline read, completed by the next poll
+    partial: Vec<u8>,
+    /// Oldest first
+    lines: VecDeque<LogLine>,
+    /// Lines of this level and the more severe ones are displayed
+    pub level: LogLevel,
+    /// Displayed lines between the bottom of the screen and the newest one, 0 follows
+    /// the tail
+    pub scroll: usize,
+    /// Why the file could not be read at the last poll
+    pub error: Option<String>,
+}
+
+impl DebugLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            offset: 0,
+            partial: vec![],
+            lines: VecDeque::new(),
+            level: LogLevel::Trace,
+            scroll: 0,
+            error: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn push(&mut self, text: &str) {
+        let level = LogLevel::parse(text)
+            .or_else(|| self.lines.back().map(|l| l.level))
+            .unwrap_or(LogLevel::Info);
+        if self.lines.len() == MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(LogLine {
+            level,
+            text: text.replace('\t', "    "),
+        });
+    }
+
+    /// Reads the lines appended since the last poll, returns how many. A file shorter than
+    /// what was read, e.g. truncated or replaced, is read again from the start.
+    pub fn poll(&mut self) -> Result<usize, DMError> {
+        let io_error = |e: std::io::Error| {
+            Report::new(DMError::IOError).attach_printable(format!(
+                "Failed to read {}: {}",
+                self.path.display(),
+                e
+            ))
+        };
+
+        let mut file = File::open(&self.path).map_err(io_error)?;
+        let len = file.metadata().map_err(io_error)?.len();
+
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+            self.lines.clear();
+        }
+        // The first line read from the middle of the file is incomplete
+        let skip_first = self.offset == 0 && len > INITIAL_TAIL;
+        if skip_first {
+            self.offset = len - INITIAL_TAIL;
+        }
+
+        file.seek(SeekFrom::Start(self.offset)).map_err(io_error)?;
+        let mut bytes = vec![];
+        file.take(len - self.offset)
+            .read_to_end(&mut bytes)
+            .map_err(io_error)?;
+        self.offset += bytes.len() as u64;
+        self.partial.extend(bytes);
+
+        let Some(end) = self.partial.iter().rposition(|b| *b == b'\n') else {
+            return Ok(0);
+        };
+        let rest = self.partial.split_off(end + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        let text = String::from_utf8_lossy(&complete);
+
+        let mut count = 0;
+        for line in text.lines().skip(usize::from(skip_first)) {
+            self.push(line);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Polls the file, keeping the error to display it instead of the lines
+    pub fn refresh(&mut self) {
+        self.error = match self.poll() {
+            Ok(_) => None,
+            Err(e) => Some(e.error_str().unwrap_or("Unknown error".to_owned())),
+        };
+    }
+
+    /// Lines passing the level filter, oldest first
+    pub fn lines(&self) -> impl Iterator<Item = &LogLine> {
+        self.lines.iter().filter(|l| l.level <= self.level)
+    }
+
+    pub fn cycle_level(&mut self) {
+        self.level = self.level.next();
+        self.scroll = 0;
+    }
+
+    pub fn scroll_up(&mut self, n: usize) {
+        let last = self.lines().count().saturating_sub(1);
+        self.scroll = (self.scroll + n).min(last);
+    }
+
+    pub fn scroll_down(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+    }
+
+    pub fn scroll_top(&mut self) {
+        self.scroll = self.lines().count().saturating_sub(1);
+    }
+
+    pub fn follow(&mut self) {
+        self.scroll = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_log_level_parse() {
+        assert_eq!(
+            LogLevel::parse("2025-06-01 10:00:00.123 ERROR  func=\"update()\""),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(
+            LogLevel::parse("10:00:00.123 [DEBUG] note=\"Starting app\""),
+            Some(LogLevel::Debug)
+        );
+        assert_eq!(LogLevel::parse("    at src/app.rs:12:5"), None);
+        // Only the level field counts, not the words of the message
+        assert_eq!(
+            LogLevel::parse("10:00:00 INFO func=main a b error WARN"),
+            Some(LogLevel::Info)
+        );
+        assert_eq!(LogLevel::Trace.next(), LogLevel::Error);
+        assert_eq!(LogLevel::Error.next(), LogLevel::Warn);
+    }
+
+    #[test]
+    fn test_debug_log_poll() {
+        let path = std::env::temp_dir().join(format!("dm-debug-log-{}.log", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        let mut log = DebugLog::new(&path);
+        assert_eq!(log.poll().unwrap(), 0);
+
+        write!(
+            file,
+            "10:00:00 INFO started\n10:00:01 ERROR failed\n    at a.rs\n10:0"
+        )
+        .unwrap();
+        assert_eq!(log.poll().unwrap(), 3);
+        // The continuation line of the error is filtered with it
+        log.level = LogLevel::Error;
+        assert_eq!(
+            log.lines().map(|l| l.text.as_str()).collect::<Vec<_>>(),
+            ["10:00:01 ERROR failed", "    at a.rs"]
+        );
+
+        writeln!(file, "0:02 WARN slow").unwrap();
+        assert_eq!(log.poll().unwrap(), 1);
+        log.level = LogLevel::Trace;
+        assert_eq!(log.lines().last().unwrap().text, "10:00:02 WARN slow");
+
+        log.scroll_up(100);
+        assert_eq!(log.scroll, 3);
+        log.scroll_down(1);
+        assert_eq!(log.scroll, 2);
+        log.cycle_level();
+        assert_eq!((log.level, log.scroll), (LogLevel::Error, 0));
+
+        // Truncated, e.g. by logrotate
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "10:01:00 DEBUG again").unwrap();
+        assert_eq!(log.poll().unwrap(), 1);
+        assert_eq!(log.lines.len(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+        log.refresh();
+        assert!(log.error.is_some());
+    }
+}
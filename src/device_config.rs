@@ -0,0 +1,575 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use std::fmt::Display;
+
+/// Directory of the user configuration files, $DM_CONFIG_DIR or else $HOME
+pub fn config_dir() -> String {
+    if let Ok(config_dir) = std::env::var("DM_CONFIG_DIR") {
+        config_dir
+    } else if let Ok(config_dir) = std::env::var("HOME") {
+        config_dir
+    } else {
+        std::env::var("PWD").unwrap().to_owned()
+    }
+}
+
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone)]
+#[repr(usize)]
+#[allow(unused)]
+pub enum DirectCommand {
+    Reboot = 0,
+    GetDirectImage,
+    FactoryReset,
+    ReadSensorRegister,
+    WriteSensorRegister,
+    ShutDown,
+    /// RPC entered by the user
+    Custom,
+
+    /// Storage token request command from the device
+    StorageTokenRequest(String, String),
+
+    #[default]
+    Invalid,
+}
+
+impl std::fmt::Display for DirectCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DirectCommand::Reboot => write!(f, "Reboot"),
+            DirectCommand::GetDirectImage => write!(f, "GetDirectImage"),
+            DirectCommand::FactoryReset => write!(f, "FactoryReset"),
+            DirectCommand::ReadSensorRegister => write!(f, "ReadSensorRegister"),
+            DirectCommand::WriteSensorRegister => write!(f, "WriteSensorRegister"),
+            DirectCommand::ShutDown => write!(f, "ShutDown"),
+            DirectCommand::Custom => write!(f, "Custom"),
+            DirectCommand::StorageTokenRequest(key, filename) => {
+                write!(f, "StorageTokenRequest({}, {})", key, filename)
+            }
+            DirectCommand::Invalid => write!(f, "InvalidCommand"),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+#[repr(usize)]
+#[allow(unused)]
+pub enum DirectCommandPara {
+    GetDirectImageSensorName = 0,
+    GetDirectImageNetworkId,
+
+    #[default]
+    Invalid,
+}
+
+/// Values of the log level dropdowns, the index of a value is the one sent
+const LOG_LEVELS: &[&str] = &["critical", "error", "warning", "info", "debug", "trace"];
+
+/// Values of the log destination dropdowns
+const LOG_DESTINATIONS: &[&str] = &["uart", "cloud_storage"];
+
+/// Kind of value entered for a configuration key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigKind {
+    Text,
+    /// Integer or decimal number
+    Number,
+    /// "true" or "false"
+    Bool,
+    /// Index of one of the values, offered in a dropdown
+    Choice(&'static [&'static str]),
+    Json,
+    /// Package URL which can be filled with a SAS URL of the local storage
+    SasUrl,
+    /// Password or key, not meant to be shown
+    Secret,
+}
+
+impl ConfigKind {
+    /// Why the value doesn't fit the kind, None when it does. An empty value means the
+    /// key is not set and always fits.
+    pub fn check(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+
+        match self {
+            ConfigKind::Number if value.parse::<f64>().is_err() => {
+                Some(format!("'{value}' is not a number"))
+            }
+            ConfigKind::Bool if value.parse::<bool>().is_err() => {
+                Some(format!("'{value}' is not true or false"))
+            }
+            ConfigKind::Choice(choices)
+                if !value.parse::<usize>().is_ok_and(|i| i < choices.len()) =>
+            {
+                Some(format!(
+                    "'{value}' is not an index from 0 to {}",
+                    choices.len() - 1
+                ))
+            }
+            ConfigKind::Json => serde_json::from_str::<serde_json::Value>(value)
+                .err()
+                .map(|e| format!("invalid JSON: {e}")),
+            _ => None,
+        }
+    }
+}
+
+/// Row of the configuration key table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigKeyMeta {
+    /// Name shown in the configuration screens
+    pub name: &'static str,
+    /// Section of the configuration screens the key belongs to
+    pub group: &'static str,
+    pub kind: ConfigKind,
+    /// Hint shown under the configuration screens
+    pub note: &'static str,
+    /// Value used by the device monitor when the key is left empty
+    pub default: Option<&'static str>,
+}
+
+const INVALID_META: ConfigKeyMeta = ConfigKeyMeta {
+    name: "Invalid",
+    group: "",
+    kind: ConfigKind::Text,
+    note: "",
+    default: None,
+};
+
+macro_rules! config_keys {
+    (@default) => {
+        None
+    };
+    (@default $default:literal) => {
+        Some($default)
+    };
+    ($(
+        $key:ident => $name:literal, $group:literal, $kind:ident $(($choices:expr))?, $note:literal
+        $(, default $default:literal)?;
+    )*) => {
+        /// Configuration keys for the device
+        /// These keys are used to identify the configuration parameters
+        /// and are used to parse the configuration file
+        #[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+        #[repr(usize)]
+        pub enum ConfigKey {
+            $($key,)*
+            #[default]
+            Invalid,
+        }
+
+        impl ConfigKey {
+            /// Metadata of the keys, indexed by the key
+            pub const TABLE: &'static [ConfigKeyMeta] = &[$(
+                ConfigKeyMeta {
+                    name: $name,
+                    group: $group,
+                    kind: ConfigKind::$kind $(($choices))?,
+                    note: $note,
+                    default: config_keys!(@default $($default)?),
+                },
+            )*];
+        }
+    };
+}
+
+// IMPORTANT: Don't change the order of the keys! They are indexes of the config_keys vector
+// and the focus ranges of the screens.
+config_keys! {
+    ReportStatusIntervalMin => "report-status-interval-min", "Agent state", Number, "";
+    ReportStatusIntervalMax => "report-status-interval-max", "Agent state", Number, "";
+
+    LedEnabled => "led_enabled", "System", Bool, "";
+    TemperatureUpdateInterval => "temperature_update_interval", "System", Number, "";
+
+    AllLogSettingLevel => "log.all.level", "Log settings", Choice(LOG_LEVELS), "0: critical, 1: error, 2: warning, 3: info, 4: debug, 5: trace";
+    AllLogSettingDestination => "log.all.destination", "Log settings", Choice(LOG_DESTINATIONS), "0: uart, 1: cloud_storage";
+    AllLogSettingStorageName => "log.all.storage_name", "Log settings", Text, "EVP Token provider ID.";
+    AllLogSettingPath => "log.all.path", "Log settings", Text, "";
+    MainLogSettingLevel => "log.main.level", "Log settings", Choice(LOG_LEVELS), "0: critical, 1: error, 2: warning, 3: info, 4: debug, 5: trace";
+    MainLogSettingDestination => "log.main.destination", "Log settings", Choice(LOG_DESTINATIONS), "0: uart, 1: cloud_storage";
+    MainLogSettingStorageName => "log.main.storage_name", "Log settings", Text, "EVP Token provider ID.";
+    MainLogSettingPath => "log.main.path", "Log settings", Text, "";
+    SensorLogSettingLevel => "log.sensor.level", "Log settings", Choice(LOG_LEVELS), "0: critical, 1: error, 2: warning, 3: info, 4: debug, 5: trace";
+    SensorLogSettingDestination => "log.sensor.destination", "Log settings", Choice(LOG_DESTINATIONS), "0: uart, 1: cloud_storage";
+    SensorLogSettingStorageName => "log.sensor.storage_name", "Log settings", Text, "EVP Token provider ID.";
+    SensorLogSettingPath => "log.sensor.path", "Log settings", Text, "";
+    CompanionFwLogSettingLevel => "log.companion_fw.level", "Log settings", Choice(LOG_LEVELS), "Log level for companion firmware log";
+    CompanionFwLogSettingDestination => "log.companion_fw.destination", "Log settings", Choice(LOG_DESTINATIONS), "0: uart, 1: cloud_storage";
+    CompanionFwLogSettingStorageName => "log.companion_fw.storage_name", "Log settings", Text, "EVP Token provider ID.";
+    CompanionFwLogSettingPath => "log.companion_fw.path", "Log settings", Text, "";
+    CompanionAppLogSettingLevel => "log.companion_app.level", "Log settings", Choice(LOG_LEVELS), "0: critical, 1: error, 2: warning, 3: info, 4: debug, 5: trace";
+    CompanionAppLogSettingDestination => "log.companion_app.destination", "Log settings", Choice(LOG_DESTINATIONS), "0: uart, 1: cloud_storage";
+    CompanionAppLogSettingStorageName => "log.companion_app.storage_name", "Log settings", Text, "EVP Token provider ID.";
+    CompanionAppLogSettingPath => "log.companion_app.path", "Log settings", Text, "";
+
+    IpMethod => "ip_method", "Network", Number, "0: dhcp, 1: static";
+    NtpUrl => "ntp_url", "Network", Text, "Domain name or IP address";
+
+    StaticIpv4Ip => "static_ipv4_ip", "IPv4", Text, "";
+    StaticIpv4SubnetMask => "static_ipv4_subnet_mask", "IPv4", Text, "";
+    StaticIpv4Gateway => "static_ipv4_gateway", "IPv4", Text, "";
+    StaticIpv4Dns => "static_ipv4_dns", "IPv4", Text, "";
+
+    StaticIpv6Ip => "static_ipv6_ip", "IPv6", Text, "";
+    StaticIpv6SubnetMask => "static_ipv6_subnet_mask", "IPv6", Text, "";
+    StaticIpv6Gateway => "static_ipv6_gateway", "IPv6", Text, "";
+    StaticIpv6Dns => "static_ipv6_dns", "IPv6", Text, "";
+
+    ProxyUrl => "proxy_url", "Proxy", Text, "Domain name or IP address";
+    ProxyPort => "proxy_port", "Proxy", Number, "";
+    ProxyUserName => "proxy_user_name", "Proxy", Text, "";
+    ProxyPassword => "proxy_password", "Proxy", Secret, "";
+
+    StaSsid => "station_mode_ssid", "Wireless", Text, "";
+    StaPassword => "station_mode_password", "Wireless", Secret, "";
+    StaEncryption => "station_mode_encryption", "Wireless", Number, "0: wpa2_psk, 1: wpa3_psk, 2: wpa2_wpa3_psk'";
+
+    DirectGetImageSensorName => "sensor_name", "Direct get image", Text, "";
+    DirectGetImageNetworkId => "network_id", "Direct get image", Text, "";
+
+    CustomRpcMethod => "method", "Custom RPC", Text, "e.g. direct_get_image";
+    CustomRpcInstance => "instance", "Custom RPC", Text, "default: $system", default "$system";
+    CustomRpcParams => "params", "Custom RPC", Json, "JSON object, default: {}", default "{}";
+
+    CommonSettingsProcessState => "process_state", "Edge app", Number, "0: stopped, 1: running";
+    CommonSettingsLogLevel => "log_level", "Edge app", Number, "0: critical, 1: error, 2: warning, 3: info, 4: debug, 5: trace";
+    CommonSettingsISNumberOfIterations => "number_of_iterations", "Edge app", Number, "";
+
+    CommonSettingsPQCameraImageSizeWidth => "PQ.camera_image_size.width", "Camera image", Number, "";
+    CommonSettingsPQCameraImageSizeHeight => "PQ.camera_image_size.height", "Camera image", Number, "";
+    CommonSettingsPQCameraImageSizeScalingPolicy => "PQ.camera_image_size.scaling_policy", "Camera image", Number, "1: sensitivity, 2: resolution";
+    CommonSettingsPQFrameRateNum => "PQ.frame_rate.num", "Camera image", Number, "";
+    CommonSettingsPQFrameRateDenom => "PQ.frame_rate.denom", "Camera image", Number, "";
+    CommonSettingsPQDigitalZoom => "PQ.digital_zoom", "Camera image", Number, "";
+    CommonSettingsPQCameraImageFlipHorizontal => "PQ.camera_image_flip_horizontal", "Camera image", Number, "0: normal, 1: flip";
+    CommonSettingsPQCameraImageFlipVertical => "PQ.camera_image_flip_vertical", "Camera image", Number, "0: normal, 1: flip";
+
+    CommonSettingsPQExposureMode => "PQ.exposure_mode", "Exposure", Number, "0: auto, 1: manual";
+    CommonSettingsPQAeMaxExposureTime => "PQ.auto_exposure.max_exposure_time", "Exposure", Number, "";
+    CommonSettingsPQAeMinExposureTime => "PQ.auto_exposure.min_exposure_time", "Exposure", Number, "";
+    CommonSettingsPQAeMaxGain => "PQ.auto_exposure.max_gain", "Exposure", Number, "";
+    CommonSettingsPQAeConvergenceSpeed => "PQ.auto_exposure.convergence_speed", "Exposure", Number, "";
+    CommonSettingsPQEvCompensation => "PQ.ev_compensation", "Exposure", Number, "";
+    CommonSettingsPQAeAntiFlickerMode => "PQ.ae_anti_flicker_mode", "Exposure", Number, "0: off, 1: auto, 2: 50Hz, 3: 60Hz";
+    CommonSettingsPQMeExposureTime => "PQ.manual_exposure.exposure_time", "Exposure", Number, "";
+    CommonSettingsPQMeGain => "PQ.manual_exposure.gain", "Exposure", Number, "";
+
+    CommonSettingsPQWhiteBalanceMode => "PQ.white_balance_mode", "White balance", Number, "0: auto, 1: preset";
+    CommonSettingsPQAwbConvergenceSpeed => "PQ.auto_wb.convergence_speed", "White balance", Number, "4300K ~ 5600K";
+    CommonSettingsPQMWBPColorTemperature => "PQ.manual_wb.color_temperature", "White balance", Number, "0: 3200K, 1: 4300K, 2: 5600K, 3: 6500K";
+    CommonSettingsPQMWBGRed => "PQ.manual_wb.gain_red", "White balance", Number, "manual white balance";
+    CommonSettingsPQMWBGBlue => "PQ.manual_wb.gain_blue", "White balance", Number, "manual white balance";
+
+    CommonSettingsPQICLeft => "PQ.image_cropping.left", "Cropping and rotation", Number, "";
+    CommonSettingsPQICTop => "PQ.image_cropping.top", "Cropping and rotation", Number, "";
+    CommonSettingsPQICWidth => "PQ.image_cropping.width", "Cropping and rotation", Number, "";
+    CommonSettingsPQICHeight => "PQ.image_cropping.height", "Cropping and rotation", Number, "";
+    CommonSettingsPQImageRotation => "PQ.image_rotation", "Cropping and rotation", Number, "0: none, 1: clockwise 90 degrees, 2: clockwise 180 degrees, 3: clockwise 270 degrees";
+
+    CommonSettingsPSMetadataMethod => "port_settings.OT.method", "Metadata port", Number, "0: evp telemetry, 1: blob storage, 2: http storage";
+    CommonSettingsPSMetadataStorageName => "port_settings.OT.storage_name", "Metadata port", Text, "EVP Token provider ID.";
+    CommonSettingsPSMetadataEndpoint => "port_settings.OT.endpoint", "Metadata port", Text, "output tensor/ metadata";
+    CommonSettingsPSMetadataPath => "port_settings.OT.path", "Metadata port", Text, "output tensor/ metadata";
+    CommonSettingsPSMetadataEnabled => "port_settings.OT.enabled", "Metadata port", Bool, "output tensor/ metadata";
+
+    CommonSettingsPSITMethod => "port_settings.IT.method", "Input tensor port", Number, "0: evp telemetry, 1: blob storage, 2: http storage";
+    CommonSettingsPSITStorageName => "port_settings.IT.storage_name", "Input tensor port", Text, "EVP Token provider ID.";
+    CommonSettingsPSITEndpoint => "port_settings.IT.endpoint", "Input tensor port", Text, " input tensor / raw data";
+    CommonSettingsPSITPath => "port_settings.IT.path", "Input tensor port", Text, " input tensor / raw data";
+    CommonSettingsPSITEnabled => "port_settings.IT.enabled", "Input tensor port", Bool, " input tensor / raw data";
+
+    CommonSettingsCSFormat => "codec_settings.format", "Codec and upload", Number, "1: jpeg";
+    CommonSettingsNumberOfInferencePerMessage => "number_of_inference_per_message", "Codec and upload", Number, "";
+    CommonSettingsUploadInterval => "upload_interval", "Codec and upload", Number, "";
+
+    CustomSettingsDetectionAiModelBundleId => "detection.ai_model_bundle_id", "Detection", Text, "";
+    CustomSettingsDetectionMaxDetections => "detection.max_detections", "Detection", Number, "";
+    CustomSettingsDetectionThreshold => "detection.threshold", "Detection", Number, "0.0 - 1.0";
+    CustomSettingsDetectionInputWidth => "detection.input_width", "Detection", Number, "";
+    CustomSettingsDetectionInputHeight => "detection.input_height", "Detection", Number, "";
+    CustomSettingsDetectionBboxOrder => "detection.bbox_order", "Detection", Text, "yxyx or xyxy";
+    CustomSettingsDetectionBboxNormalization => "detection.bbox_normalization", "Detection", Bool, "true or false";
+    CustomSettingsDetectionClassScoreOrder => "detection.class_score_order", "Detection", Text, "cls_score or score_cls";
+
+    CustomSettings => "custom_settings", "Custom settings", Json, "JSON object, e.g. {\"threshold\": 0.3}";
+
+    OtaVersion => "version", "OTA", Text, "";
+
+    OtaMainChipLoaderChip => "main_chip.loader.chip", "Main chip", Text, "default: ApFw", default "ApFw";
+    OtaMainChipLoaderVersion => "main_chip.loader.version", "Main chip", Text, "";
+    OtaMainChipLoaderPackageUrl => "main_chip.loader.package_url", "Main chip", SasUrl, "";
+    OtaMainChipLoaderHash => "main_chip.loader.hash", "Main chip", Text, "";
+    OtaMainChipLoaderSize => "main_chip.loader.size", "Main chip", Number, "";
+    OtaMainChipFirmwareChip => "main_chip.firmware.chip", "Main chip", Text, "default: ApFw", default "ApFw";
+    OtaMainChipFirmwareVersion => "main_chip.firmware.version", "Main chip", Text, "";
+    OtaMainChipFirmwarePackageUrl => "main_chip.firmware.package_url", "Main chip", SasUrl, "";
+    OtaMainChipFirmwareHash => "main_chip.firmware.hash", "Main chip", Text, "";
+    OtaMainChipFirmwareSize => "main_chip.firmware.size", "Main chip", Number, "";
+
+    OtaCompanionChipLoaderChip => "companion_chip.loader.chip", "Companion chip", Text, "default: AI-ISP", default "AI-ISP";
+    OtaCompanionChipLoaderVersion => "companion_chip.loader.version", "Companion chip", Text, "";
+    OtaCompanionChipLoaderPackageUrl => "companion_chip.loader.package_url", "Companion chip", SasUrl, "";
+    OtaCompanionChipLoaderHash => "companion_chip.loader.hash", "Companion chip", Text, "";
+    OtaCompanionChipLoaderSize => "companion_chip.loader.size", "Companion chip", Number, "";
+    OtaCompanionChipFirmwareChip => "companion_chip.firmware.chip", "Companion chip", Text, "default: AI-ISP", default "AI-ISP";
+    OtaCompanionChipFirmwareVersion => "companion_chip.firmware.version", "Companion chip", Text, "";
+    OtaCompanionChipFirmwarePackageUrl => "companion_chip.firmware.package_url", "Companion chip", SasUrl, "";
+    OtaCompanionChipFirmwareHash => "companion_chip.firmware.hash", "Companion chip", Text, "";
+    OtaCompanionChipFirmwareSize => "companion_chip.firmware.size", "Companion chip", Number, "";
+
+    OtaSensorChipLoaderChip => "sensor_chip.loader.chip", "Sensor chip", Text, "default: IMX500", default "IMX500";
+    OtaSensorChipLoaderVersion => "sensor_chip.loader.version", "Sensor chip", Text, "";
+    OtaSensorChipLoaderPackageUrl => "sensor_chip.loader.package_url", "Sensor chip", SasUrl, "";
+    OtaSensorChipLoaderHash => "sensor_chip.loader.hash", "Sensor chip", Text, "";
+    OtaSensorChipLoaderSize => "sensor_chip.loader.size", "Sensor chip", Number, "";
+    OtaSensorChipFirmwareChip => "sensor_chip.firmware.chip", "Sensor chip", Text, "default: IMX500", default "IMX500";
+    OtaSensorChipFirmwareVersion => "sensor_chip.firmware.version", "Sensor chip", Text, "";
+    OtaSensorChipFirmwarePackageUrl => "sensor_chip.firmware.package_url", "Sensor chip", SasUrl, "";
+    OtaSensorChipFirmwareHash => "sensor_chip.firmware.hash", "Sensor chip", Text, "";
+    OtaSensorChipFirmwareSize => "sensor_chip.firmware.size", "Sensor chip", Number, "";
+
+    AiModel0Chip => "ai_model[0].chip", "AI model 0", Text, "";
+    AiModel0Version => "ai_model[0].version", "AI model 0", Text, "";
+    AiModel0PackageUrl => "ai_model[0].package_url", "AI model 0", SasUrl, "";
+    AiModel0Hash => "ai_model[0].hash", "AI model 0", Text, "";
+    AiModel0Size => "ai_model[0].size", "AI model 0", Number, "";
+
+    AiModel1Chip => "ai_model[1].chip", "AI model 1", Text, "";
+    AiModel1Version => "ai_model[1].version", "AI model 1", Text, "";
+    AiModel1PackageUrl => "ai_model[1].package_url", "AI model 1", SasUrl, "";
+    AiModel1Hash => "ai_model[1].hash", "AI model 1", Text, "";
+    AiModel1Size => "ai_model[1].size", "AI model 1", Number, "";
+
+    AiModel2Chip => "ai_model[2].chip", "AI model 2", Text, "";
+    AiModel2Version => "ai_model[2].version", "AI model 2", Text, "";
+    AiModel2PackageUrl => "ai_model[2].package_url", "AI model 2", SasUrl, "";
+    AiModel2Hash => "ai_model[2].hash", "AI model 2", Text, "";
+    AiModel2Size => "ai_model[2].size", "AI model 2", Number, "";
+
+    AiModel3Chip => "ai_model[3].chip", "AI model 3", Text, "";
+    AiModel3Version => "ai_model[3].version", "AI model 3", Text, "";
+    AiModel3PackageUrl => "ai_model[3].package_url", "AI model 3", SasUrl, "";
+    AiModel3Hash => "ai_model[3].hash", "AI model 3", Text, "";
+    AiModel3Size => "ai_model[3].size", "AI model 3", Number, "";
+
+    StorageSettingsUrl => "azurite.url", "Storage", Text, "e.g. https://127.0.0.1:10000";
+    StorageSettingsAccountName => "azurite.account_name", "Storage", Text, "default: devstoreaccount1", default "devstoreaccount1";
+    StorageSettingsAccountKey => "azurite.account_key", "Storage", Secret, "Base64 encoded account key";
+
+    PublishTopic => "topic", "Publish", Text, "e.g. v1/devices/me/attributes";
+    PublishPayload => "payload", "Publish", Json, "JSON, (p)/(n) recall previous/next message";
+
+    SubscriptionFilter => "topic_filter", "Subscriptions", Text, "";
+
+    DtmiRepository => "repository", "DTDL", Text, "directory, URL or .json file";
+
+    InventoryFile => "inventory", "Inventory", Text, ".json file of the expected versions";
+
+    CompareFile => "reference", "Compare", Text, "state snapshot of the reference device";
+
+    FleetDir => "fleet", "Fleet", Text, "directory of the snapshot directories, one per device";
+}
+
+impl From<ConfigKey> for usize {
+    fn from(value: ConfigKey) -> Self {
+        value as usize
+    }
+}
+
+impl From<usize> for ConfigKey {
+    fn from(value: usize) -> Self {
+        if value >= ConfigKey::size() {
+            return ConfigKey::Invalid;
+        }
+
+        // SAFETY: We've verified that value is within the valid range for ConfigKey variants
+        unsafe { std::mem::transmute(value) }
+    }
+}
+
+impl Display for ConfigKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.meta().name)
+    }
+}
+
+impl ConfigKey {
+    // Returns the number of configuration keys including the invalid key
+    // Note ConfigKey is used as index in the config_keys vector starting from 0
+    pub fn size() -> usize {
+        ConfigKey::Invalid as usize + 1
+    }
+
+    pub fn meta(&self) -> &'static ConfigKeyMeta {
+        Self::TABLE.get(*self as usize).unwrap_or(&INVALID_META)
+    }
+
+    pub fn group(&self) -> &'static str {
+        self.meta().group
+    }
+
+    pub fn kind(&self) -> ConfigKind {
+        self.meta().kind
+    }
+
+    pub fn default_value(&self) -> Option<&'static str> {
+        self.meta().default
+    }
+
+    /// Sections of the keys from first to last, as (group, first key, last key) indexes
+    pub fn sections(first: usize, last: usize) -> Vec<(&'static str, usize, usize)> {
+        let mut sections: Vec<(&'static str, usize, usize)> = vec![];
+        for i in first..=last.min(Self::TABLE.len().saturating_sub(1)) {
+            let group = ConfigKey::from(i).group();
+            match sections.last_mut() {
+                Some((g, _, end)) if *g == group => *end = i,
+                _ => sections.push((group, i, i)),
+            }
+        }
+        sections
+    }
+
+    /// Values offered in a dropdown for the key, the index of a value is the one sent
+    pub fn choices(&self) -> &'static [&'static str] {
+        match self.kind() {
+            ConfigKind::Choice(choices) => choices,
+            _ => &[],
+        }
+    }
+
+    pub fn note(&self) -> &'static str {
+        self.meta().note
+    }
+
+    /// Why the value can't be sent for the key, None when it can
+    pub fn check(&self, value: &str) -> Option<String> {
+        self.kind().check(value)
+    }
+
+    pub fn is_sas_url_entry(&self) -> bool {
+        self.kind() == ConfigKind::SasUrl
+    }
+}
+
+/// Value entered for a config key compared with the value reported by the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldState {
+    /// Empty, the key is not sent
+    Unset,
+    /// Same as the reported value
+    Unchanged,
+    Modified,
+}
+
+impl FieldState {
+    pub fn of(value: &str, reported: Option<&str>) -> Self {
+        let value = value.trim();
+        if value.is_empty() {
+            FieldState::Unset
+        } else if reported.map(str::trim) == Some(value) {
+            FieldState::Unchanged
+        } else {
+            FieldState::Modified
+        }
+    }
+
+    /// Shown before the name of the key
+    pub fn marker(&self) -> char {
+        match self {
+            FieldState::Unset => ' ',
+            FieldState::Unchanged => '=',
+            FieldState::Modified => '*',
+        }
+    }
+}
+
+/// Focus areas within the main window for navigation
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+pub enum MainWindowFocus {
+    #[default]
+    MainChip,
+    CompanionChip,
+    SensorChip,
+    DeviceManifest,
+    AgentState,
+    DeploymentStatus,
+    DeviceReserved,
+    DeviceState,
+    DeviceCapabilities,
+    SystemSettings,
+    NetworkSettings,
+    WirelessSettings,
+}
+
+impl MainWindowFocus {
+    /// Navigation order for main window focus areas
+    const NAVIGATION_ORDER: [MainWindowFocus; 12] = [
+        MainWindowFocus::MainChip,
+        MainWindowFocus::CompanionChip,
+        MainWindowFocus::SensorChip,
+        MainWindowFocus::DeviceManifest,
+        MainWindowFocus::AgentState,
+        MainWindowFocus::DeploymentStatus,
+        MainWindowFocus::DeviceReserved,
+        MainWindowFocus::DeviceState,
+        MainWindowFocus::DeviceCapabilities,
+        MainWindowFocus::SystemSettings,
+        MainWindowFocus::NetworkSettings,
+        MainWindowFocus::WirelessSettings,
+    ];
+
+    /// Get the next focus in navigation order
+    pub fn next(&self) -> Self {
+        let current_index = Self::NAVIGATION_ORDER
+            .iter()
+            .position(|&focus| focus == *self)
+            .unwrap_or(0);
+        let next_index = (current_index + 1) % Self::NAVIGATION_ORDER.len();
+        Self::NAVIGATION_ORDER[next_index]
+    }
+
+    /// Get the previous focus in navigation order
+    pub fn previous(&self) -> Self {
+        let current_index = Self::NAVIGATION_ORDER
+            .iter()
+            .position(|&focus| focus == *self)
+            .unwrap_or(0);
+        let prev_index = if current_index == 0 {
+            Self::NAVIGATION_ORDER.len() - 1
+        } else {
+            current_index - 1
+        };
+        Self::NAVIGATION_ORDER[prev_index]
+    }
+    pub fn user_config_file(&self) -> &'static str {
+        match self {
+            MainWindowFocus::DeploymentStatus => "edge_app_deploy.json",
+            MainWindowFocus::SystemSettings => "system_settings.json",
+            MainWindowFocus::NetworkSettings => "network_settings.json",
+            MainWindowFocus::WirelessSettings => "wireless_settings.json",
+            MainWindowFocus::MainChip
+            | MainWindowFocus::SensorChip
+            | MainWindowFocus::CompanionChip => "{ota_fw, ota_ai_model}.json",
+            _ => "configure.json",
+        }
+    }
+}
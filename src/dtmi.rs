@@ -0,0 +1,301 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::DMError,
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jerror, jinfo},
+    serde_json::Value,
+    std::{fmt::Display, path::Path, time::Duration},
+};
+
+/// Models are looked up in the current directory by default
+pub const DEFAULT_REPOSITORY: &str = ".";
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Relative path of a model in a repository, following the IoT Plug and Play convention:
+/// dtmi:com:example:thermostat;1 -> dtmi/com/example/thermostat-1.json
+pub fn model_path(dtmi: &str) -> Result<String, DMError> {
+    let invalid =
+        || Report::new(DMError::InvalidData).attach_printable(format!("Invalid DTMI: {dtmi}"));
+
+    let (path, version) = dtmi.split_once(';').ok_or_else(invalid)?;
+    if !path.starts_with("dtmi:") || version.parse::<u32>().is_err() {
+        return Err(invalid());
+    }
+
+    Ok(format!(
+        "{}-{}.json",
+        path.to_lowercase().replace(':', "/"),
+        version
+    ))
+}
+
+/// Reads the model from a file, a repository directory or a repository URL
+pub fn fetch(source: &str, dtmi: &str) -> Result<String, DMError> {
+    let source = source.trim();
+    let is_url = source.starts_with("http://") || source.starts_with("https://");
+    let location = if source.ends_with(".json") {
+        source.to_owned()
+    } else {
+        format!("{}/{}", source.trim_end_matches('/'), model_path(dtmi)?)
+    };
+    jdebug!(func = "dtmi::fetch()", location = location);
+
+    if is_url {
+        fetch_url(&location)
+    } else {
+        std::fs::read_to_string(Path::new(&location)).map_err(|e| {
+            Report::new(DMError::IOError).attach_printable(format!("{}: {}", location, e))
+        })
+    }
+}
+
+/// Fetches an http or https URL, the server certificate is checked by rustls against the
+/// bundled web PKI roots
+fn fetch_url(url: &str) -> Result<String, DMError> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| {
+            Report::new(DMError::RuntimeError)
+                .attach_printable(format!("Failed to create Tokio runtime: {e}"))
+        })?;
+
+    runtime.block_on(async {
+        let fetch = async {
+            let response = reqwest::get(url).await?.error_for_status()?;
+            response.text().await
+        };
+
+        match tokio::time::timeout(FETCH_TIMEOUT, fetch).await {
+            Ok(Ok(text)) => Ok(text),
+            Ok(Err(e)) => Err(Report::new(DMError::IOError)
+                .attach_printable(format!("Failed to fetch {}: {}", url, e))),
+            Err(_) => Err(Report::new(DMError::Timeout)
+                .attach_printable(format!("Timeout while fetching {}", url))),
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContentKind {
+    Property,
+    Telemetry,
+    Command,
+    Component,
+    Relationship,
+}
+
+impl Display for ContentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ContentKind::Property => "Property",
+            ContentKind::Telemetry => "Telemetry",
+            ContentKind::Command => "Command",
+            ContentKind::Component => "Component",
+            ContentKind::Relationship => "Relationship",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Element of the contents of an interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelContent {
+    pub kind: ContentKind,
+    pub name: String,
+    /// Schema name, or the type of a complex schema
+    pub schema: String,
+    pub writable: bool,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DtdlModel {
+    pub id: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub extends: Vec<String>,
+    pub contents: Vec<ModelContent>,
+}
+
+/// String values may be localized, the English text or the first one is used
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(map) => map
+            .get("en")
+            .or_else(|| map.values().next())
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_owned()),
+        _ => None,
+    }
+}
+
+/// "@type" is a string or an array of a type and its semantic types
+fn types(value: &Value) -> Vec<&str> {
+    match value.get("@type") {
+        Some(Value::String(s)) => vec![s.as_str()],
+        Some(Value::Array(a)) => a.iter().filter_map(|v| v.as_str()).collect(),
+        _ => vec![],
+    }
+}
+
+fn schema_name(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(complex @ Value::Object(_)) => types(complex)
+            .first()
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| "Object".to_owned()),
+        _ => String::new(),
+    }
+}
+
+impl DtdlModel {
+    /// Parses the interface with the given id, the model may hold several in an array
+    pub fn parse(model: &str, dtmi: &str) -> Result<Self, DMError> {
+        let value: Value = serde_json::from_str(model).map_err(|e| {
+            Report::new(DMError::ParserError).attach_printable(format!("Invalid DTDL model: {e}"))
+        })?;
+
+        let interfaces = match &value {
+            Value::Array(a) => a.iter().collect(),
+            v => vec![v],
+        };
+        let interface = interfaces
+            .iter()
+            .find(|i| i.get("@id").and_then(|id| id.as_str()) == Some(dtmi))
+            .or(interfaces.first())
+            .filter(|i| types(i).contains(&"Interface"))
+            .ok_or_else(|| {
+                Report::new(DMError::ParserError)
+                    .attach_printable("No DTDL Interface found in the model")
+            })?;
+
+        let extends = match interface.get("extends") {
+            Some(Value::String(s)) => vec![s.clone()],
+            Some(Value::Array(a)) => a
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_owned()))
+                .collect(),
+            _ => vec![],
+        };
+
+        let mut contents: Vec<ModelContent> = interface
+            .get("contents")
+            .and_then(|c| c.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|content| {
+                let kind = types(content).iter().find_map(|t| match *t {
+                    "Property" => Some(ContentKind::Property),
+                    "Telemetry" => Some(ContentKind::Telemetry),
+                    "Command" => Some(ContentKind::Command),
+                    "Component" => Some(ContentKind::Component),
+                    "Relationship" => Some(ContentKind::Relationship),
+                    _ => None,
+                })?;
+
+                let schema = match kind {
+                    ContentKind::Command => {
+                        let payload = |key| {
+                            content
+                                .get(key)
+                                .map(|p| schema_name(p.get("schema")))
+                                .unwrap_or_default()
+                        };
+                        format!("({}) -> {}", payload("request"), payload("response"))
+                    }
+                    ContentKind::Relationship => content
+                        .get("target")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or_default()
+                        .to_owned(),
+                    _ => schema_name(content.get("schema")),
+                };
+
+                Some(ModelContent {
+                    kind,
+                    name: content.get("name")?.as_str()?.to_owned(),
+                    schema,
+                    writable: content.get("writable").and_then(|w| w.as_bool()) == Some(true),
+                    description: content.get("description").and_then(text),
+                })
+            })
+            .collect();
+        contents.sort_by_key(|c| c.kind);
+
+        Ok(Self {
+            id: interface
+                .get("@id")
+                .and_then(|id| id.as_str())
+                .unwrap_or(dtmi)
+                .to_owned(),
+            display_name: interface.get("displayName").and_then(text),
+            description: interface.get("description").and_then(text),
+            extends,
+            contents,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_path() {
+        assert_eq!(
+            model_path("dtmi:com:sony_semicon:aitrios:sss:edge:system:t3w;2").unwrap(),
+            "dtmi/com/sony_semicon/aitrios/sss/edge/system/t3w-2.json"
+        );
+        assert!(model_path("dtmi:com:example").is_err());
+        assert!(model_path("com:example;1").is_err());
+    }
+
+    #[test]
+    fn test_parse_model() {
+        let model = r#"{
+            "@context": "dtmi:dtdl:context;2",
+            "@id": "dtmi:com:example:thermostat;1",
+            "@type": "Interface",
+            "displayName": { "en": "Thermostat" },
+            "contents": [
+                { "@type": ["Telemetry", "Temperature"], "name": "temperature", "schema": "double" },
+                { "@type": "Property", "name": "targetTemperature", "schema": "double", "writable": true },
+                {
+                    "@type": "Command",
+                    "name": "reboot",
+                    "request": { "name": "delay", "schema": "integer" },
+                    "response": { "name": "result", "schema": { "@type": "Object", "fields": [] } }
+                }
+            ]
+        }"#;
+
+        let model = DtdlModel::parse(model, "dtmi:com:example:thermostat;1").unwrap();
+        assert_eq!(model.display_name.as_deref(), Some("Thermostat"));
+        let names: Vec<_> = model.contents.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["targetTemperature", "temperature", "reboot"]);
+        assert!(model.contents[0].writable);
+        assert_eq!(model.contents[2].schema, "(integer) -> Object");
+
+        assert!(DtdlModel::parse("[]", "dtmi:com:example:thermostat;1").is_err());
+    }
+}
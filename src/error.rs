@@ -17,6 +17,7 @@ limitations under the License.
 use error_stack::{AttachmentKind, FrameKind, Report};
 use std::fmt::Display;
 
+/// Error of the operations, the details are attached as printable attachments
 #[derive(Debug)]
 pub enum DMError {
     InvalidData,
@@ -25,6 +26,8 @@ pub enum DMError {
     IOError,
     RuntimeError,
     Timeout,
+    /// Changes of the device or the storage refused by the read-only mode
+    ReadOnly,
 }
 
 impl Display for DMError {
@@ -36,6 +39,7 @@ impl Display for DMError {
             DMError::IOError => "IO error",
             DMError::RuntimeError => "Runtime error",
             DMError::Timeout => "Operation timed out",
+            DMError::ReadOnly => "Not allowed in the read-only mode",
         };
 
         write!(f, "{msg}")
@@ -0,0 +1,453 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! Summary of the devices of a fleet: the device of this session, and the devices watched
+//! by daemon mode sessions, read from the newest state snapshot of each one.
+
+#[allow(unused)]
+use {
+    crate::{
+        daemon::{ELOGS_PREFIX, STATE_PREFIX, StateSnapshot},
+        error::{DMError, DMErrorExt},
+        mqtt_ctrl::{
+            evp::{
+                clock::parse_device_time,
+                elog::{ElogArchive, ElogEntry},
+                evp_state::is_error_status,
+            },
+            reboots::format_uptime,
+        },
+        versioned,
+    },
+    chrono::{DateTime, Duration, FixedOffset},
+    error_stack::{Report, Result},
+    serde_json::Value,
+    std::path::{Path, PathBuf},
+};
+
+/// Age from which the snapshot of a device is no longer trusted to tell its connection
+pub const STALE_AFTER: Duration = Duration::minutes(10);
+
+/// Connection of a device, from the worst to the best
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Connection {
+    /// The state snapshot cannot be read
+    Unknown,
+    Disconnected,
+    /// The daemon has not written a snapshot for `STALE_AFTER`
+    Stale,
+    Connected,
+}
+
+impl Connection {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Connection::Unknown => "unknown",
+            Connection::Disconnected => "disconnected",
+            Connection::Stale => "stale",
+            Connection::Connected => "connected",
+        }
+    }
+}
+
+/// Health of the deployment, from the worst to the best
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DeploymentHealth {
+    Error,
+    Applying,
+    Ok,
+    /// Nothing deployed, or not reported yet
+    None,
+}
+
+impl DeploymentHealth {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DeploymentHealth::Error => "error",
+            DeploymentHealth::Applying => "applying",
+            DeploymentHealth::Ok => "ok",
+            DeploymentHealth::None => "-",
+        }
+    }
+
+    /// From the deployment status as reported by the device
+    pub fn of(deployment_status: &Value) -> Self {
+        if deployment_status.is_null() {
+            return DeploymentHealth::None;
+        }
+
+        let mut statuses = ["instances", "modules"]
+            .iter()
+            .filter_map(|key| deployment_status[key].as_object())
+            .flat_map(|map| map.values())
+            .filter_map(|v| v["status"].as_str());
+        let reconcile = deployment_status["reconcileStatus"]
+            .as_str()
+            .unwrap_or_default();
+
+        if is_error_status(reconcile) || statuses.any(is_error_status) {
+            DeploymentHealth::Error
+        } else if reconcile.eq_ignore_ascii_case("applying") {
+            DeploymentHealth::Applying
+        } else {
+            DeploymentHealth::Ok
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FleetColumn {
+    Device,
+    Connection,
+    Firmware,
+    Deployment,
+    Elog,
+    Uptime,
+}
+
+impl FleetColumn {
+    pub const ALL: [FleetColumn; 6] = [
+        FleetColumn::Device,
+        FleetColumn::Connection,
+        FleetColumn::Firmware,
+        FleetColumn::Deployment,
+        FleetColumn::Elog,
+        FleetColumn::Uptime,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            FleetColumn::Device => "Device",
+            FleetColumn::Connection => "Connection",
+            FleetColumn::Firmware => "Firmware",
+            FleetColumn::Deployment => "Deployment",
+            FleetColumn::Elog => "Last elog",
+            FleetColumn::Uptime => "Uptime",
+        }
+    }
+
+    fn index(&self) -> usize {
+        FleetColumn::ALL.iter().position(|c| c == self).unwrap()
+    }
+
+    pub fn next(&self) -> Self {
+        FleetColumn::ALL[(self.index() + 1) % FleetColumn::ALL.len()]
+    }
+
+    pub fn prev(&self) -> Self {
+        let len = FleetColumn::ALL.len();
+        FleetColumn::ALL[(self.index() + len - 1) % len]
+    }
+}
+
+/// One row of the fleet dashboard
+#[derive(Debug, Clone, PartialEq)]
+pub struct FleetDevice {
+    /// Main chip id, or the name of the snapshot directory before it is reported
+    pub device_id: String,
+    /// Newest state snapshot, None for the device of this session
+    pub snapshot: Option<PathBuf>,
+    pub connection: Connection,
+    /// Main chip firmware version
+    pub firmware: Option<String>,
+    pub deployment: DeploymentHealth,
+    /// Level of the last elog, 0 being critical
+    pub elog_level: Option<u8>,
+    pub elog_level_str: Option<&'static str>,
+    pub uptime: Option<Duration>,
+}
+
+impl FleetDevice {
+    /// Summarizes the device state as of `time`, with the same keys as the state of the
+    /// scripts
+    pub fn from_state(
+        name: &str,
+        state: &Value,
+        elogs: &[ElogEntry],
+        time: DateTime<FixedOffset>,
+    ) -> Self {
+        let connection = if state["connected"].as_bool().unwrap_or_default() {
+            Connection::Connected
+        } else {
+            Connection::Disconnected
+        };
+        let last_elog = elogs.last().map(|e| e.elog());
+
+        Self {
+            device_id: state["main_chip"]["id"]
+                .as_str()
+                .filter(|id| !id.is_empty())
+                .unwrap_or(name)
+                .to_owned(),
+            snapshot: None,
+            connection,
+            firmware: state["main_chip"]["firmware_version"]
+                .as_str()
+                .map(|v| v.to_owned()),
+            deployment: DeploymentHealth::of(&state["deployment_status"]),
+            elog_level: last_elog.map(|e| e.level()),
+            elog_level_str: last_elog.map(|e| e.level_str()),
+            uptime: state["device_states"]["last_bootup_time"]
+                .as_str()
+                .and_then(parse_device_time)
+                .map(|bootup| time - bootup),
+        }
+    }
+
+    /// Device without a readable snapshot, listed so that it is not forgotten
+    fn unknown(name: &str) -> Self {
+        Self {
+            device_id: name.to_owned(),
+            snapshot: None,
+            connection: Connection::Unknown,
+            firmware: None,
+            deployment: DeploymentHealth::None,
+            elog_level: None,
+            elog_level_str: None,
+            uptime: None,
+        }
+    }
+
+    pub fn uptime_str(&self) -> String {
+        self.uptime
+            .map(format_uptime)
+            .unwrap_or_else(|| "-".to_owned())
+    }
+}
+
+/// Newest `<prefix>_<time>.json` file of the directory, the time in the names sorts them
+fn newest(dir: &Path, prefix: &str) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&format!("{prefix}_")) && n.ends_with(".json"))
+        })
+        .max()
+}
+
+/// Reads the device of a daemon snapshot directory, as of its newest state snapshot
+fn load_device(dir: &Path, now: DateTime<FixedOffset>) -> Option<FleetDevice> {
+    let name = dir.file_name()?.to_string_lossy().into_owned();
+    let path = newest(dir, STATE_PREFIX)?;
+
+    let snapshot = versioned::load::<StateSnapshot>(&path.to_string_lossy())
+        .ok()
+        .flatten();
+    let Some(snapshot) = snapshot else {
+        return Some(FleetDevice::unknown(&name));
+    };
+    let Ok(time) = DateTime::parse_from_rfc3339(&snapshot.time) else {
+        return Some(FleetDevice::unknown(&name));
+    };
+
+    let elogs = newest(dir, ELOGS_PREFIX)
+        .and_then(|path| versioned::load::<ElogArchive>(&path.to_string_lossy()).ok())
+        .flatten()
+        .map(|archive| archive.elogs)
+        .unwrap_or_default();
+
+    let mut device = FleetDevice::from_state(&name, &snapshot.state, &elogs, time);
+    if device.connection == Connection::Connected && now - time > STALE_AFTER {
+        device.connection = Connection::Stale;
+    }
+    device.snapshot = Some(path);
+    Some(device)
+}
+
+/// Reads the devices of the fleet directory: one subdirectory per device, each being the
+/// `--snapshot-dir` of the daemon watching it. Subdirectories without state snapshot are
+/// ignored.
+pub fn load_fleet(dir: &str, now: DateTime<FixedOffset>) -> Result<Vec<FleetDevice>, DMError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        Report::new(DMError::IOError).attach_printable(format!("Failed to read {dir}: {e}"))
+    })?;
+
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+
+    Ok(dirs
+        .iter()
+        .filter_map(|dir| load_device(dir, now))
+        .collect())
+}
+
+/// Sorts the devices by `column`, the worst connections and deployments, the most severe
+/// elogs and the shortest uptimes first unless `descending`. The devices reporting nothing
+/// for the column are listed last either way.
+pub fn sort(devices: &mut [FleetDevice], column: FleetColumn, descending: bool) {
+    fn order<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> std::cmp::Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) if descending => b.cmp(&a),
+            (Some(a), Some(b)) => a.cmp(&b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        }
+    }
+
+    devices.sort_by(|a, b| match column {
+        FleetColumn::Device => order(Some(&a.device_id), Some(&b.device_id), descending),
+        FleetColumn::Connection => order(Some(a.connection), Some(b.connection), descending),
+        FleetColumn::Firmware => order(a.firmware.as_ref(), b.firmware.as_ref(), descending),
+        FleetColumn::Deployment => order(
+            Some(a.deployment).filter(|d| *d != DeploymentHealth::None),
+            Some(b.deployment).filter(|d| *d != DeploymentHealth::None),
+            descending,
+        ),
+        FleetColumn::Elog => order(a.elog_level, b.elog_level, descending),
+        FleetColumn::Uptime => order(a.uptime, b.uptime, descending),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn time(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_deployment_health() {
+        assert_eq!(DeploymentHealth::of(&Value::Null), DeploymentHealth::None);
+        assert_eq!(
+            DeploymentHealth::of(&json!({"reconcileStatus": "ok",
+                "instances": {"1": {"status": "ok"}}})),
+            DeploymentHealth::Ok
+        );
+        assert_eq!(
+            DeploymentHealth::of(&json!({"reconcileStatus": "applying"})),
+            DeploymentHealth::Applying
+        );
+        assert_eq!(
+            DeploymentHealth::of(&json!({"reconcileStatus": "applying",
+                "modules": {"1": {"status": "download failed"}}})),
+            DeploymentHealth::Error
+        );
+    }
+
+    #[test]
+    fn test_from_state() {
+        let state = json!({
+            "connected": true,
+            "main_chip": {"id": "A1", "firmware_version": "D52408"},
+            "device_states": {"last_bootup_time": "2025-01-01T00:00:00Z"},
+            "deployment_status": null,
+        });
+        let device = FleetDevice::from_state("cam1", &state, &[], time("2025-01-02T01:02:03Z"));
+        assert_eq!(device.device_id, "A1");
+        assert_eq!(device.connection, Connection::Connected);
+        assert_eq!(device.firmware.as_deref(), Some("D52408"));
+        assert_eq!(device.deployment, DeploymentHealth::None);
+        assert_eq!(device.elog_level, None);
+        assert_eq!(device.uptime_str(), "1d 01:02:03");
+
+        let device = FleetDevice::from_state("cam1", &json!({}), &[], time("2025-01-02T00:00:00Z"));
+        assert_eq!(device.device_id, "cam1");
+        assert_eq!(device.connection, Connection::Disconnected);
+        assert_eq!(device.uptime_str(), "-");
+    }
+
+    #[test]
+    fn test_sort() {
+        let device = |id: &str, connection, uptime: Option<i64>| FleetDevice {
+            device_id: id.to_owned(),
+            connection,
+            uptime: uptime.map(Duration::seconds),
+            ..FleetDevice::unknown(id)
+        };
+        let mut devices = [
+            device("b", Connection::Connected, Some(20)),
+            device("c", Connection::Stale, None),
+            device("a", Connection::Disconnected, Some(10)),
+        ];
+        let ids = |devices: &[FleetDevice]| -> Vec<String> {
+            devices.iter().map(|d| d.device_id.clone()).collect()
+        };
+
+        sort(&mut devices, FleetColumn::Device, false);
+        assert_eq!(ids(&devices), ["a", "b", "c"]);
+        sort(&mut devices, FleetColumn::Connection, false);
+        assert_eq!(ids(&devices), ["a", "c", "b"]);
+        sort(&mut devices, FleetColumn::Uptime, false);
+        assert_eq!(ids(&devices), ["a", "b", "c"]);
+        // Not reported is still last
+        sort(&mut devices, FleetColumn::Uptime, true);
+        assert_eq!(ids(&devices), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_load_fleet() {
+        let dir = std::env::temp_dir().join(format!("fleet_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for name in ["cam1", "cam2", "empty"] {
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+        }
+
+        let write = |name: &str, file: &str, time: &str, connected: bool| {
+            let snapshot = StateSnapshot {
+                time: time.to_owned(),
+                state: json!({"connected": connected, "main_chip": {"id": name}}),
+            };
+            versioned::save(&dir.join(name).join(file).to_string_lossy(), &snapshot).unwrap();
+        };
+        write(
+            "cam1",
+            "state_20250101_000000.json",
+            "2025-01-01T00:00:00Z",
+            false,
+        );
+        write(
+            "cam1",
+            "state_20250101_005500.json",
+            "2025-01-01T00:55:00Z",
+            true,
+        );
+        write(
+            "cam2",
+            "state_20250101_000000.json",
+            "2025-01-01T00:00:00Z",
+            true,
+        );
+        std::fs::write(dir.join("cam2").join("state_20250101_000100.json"), "{").unwrap();
+
+        let now = time("2025-01-01T01:00:00Z");
+        let devices = load_fleet(&dir.to_string_lossy(), now).unwrap();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].device_id, "cam1");
+        assert_eq!(devices[0].connection, Connection::Connected);
+        assert!(
+            devices[0]
+                .snapshot
+                .as_ref()
+                .unwrap()
+                .ends_with("state_20250101_005500.json")
+        );
+        // The newest snapshot is broken
+        assert_eq!(devices[1].connection, Connection::Unknown);
+
+        let devices = load_fleet(&dir.to_string_lossy(), time("2025-01-01T02:00:00Z")).unwrap();
+        assert_eq!(devices[0].connection, Connection::Stale);
+
+        assert!(load_fleet(&dir.join("missing").to_string_lossy(), now).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
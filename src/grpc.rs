@@ -0,0 +1,273 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        azurite::with_azurite_storage_mut,
+        error::{DMError, DMErrorExt},
+        mqtt_ctrl::{MqttCtrl, evp::evp_state::UUID, with_mqtt_ctrl, with_mqtt_ctrl_mut},
+        script::ScriptTarget,
+    },
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jerror, jinfo},
+    std::net::{SocketAddr, TcpListener},
+    tonic::{Request, Response, Status, transport::Server, transport::server::TcpIncoming},
+};
+
+pub mod proto {
+    tonic::include_proto!("device_monitor");
+}
+
+use proto::{
+    DeployModuleRequest, DeployModuleResponse, GetStateRequest, GetStateResponse,
+    SendConfigureRequest, SendConfigureResponse, TriggerRpcRequest, TriggerRpcResponse,
+    device_monitor_server::{DeviceMonitor, DeviceMonitorServer},
+};
+
+/// Status of a failed operation, the printable attachment is the message
+fn to_status(report: Report<DMError>) -> Status {
+    let message = report
+        .error_str()
+        .unwrap_or_else(|| report.current_context().to_string());
+
+    match report.current_context() {
+        DMError::InvalidData | DMError::ParserError => Status::invalid_argument(message),
+        DMError::Timeout => Status::deadline_exceeded(message),
+        DMError::ReadOnly => Status::permission_denied(message),
+        DMError::IOError => Status::unavailable(message),
+        DMError::UiError | DMError::RuntimeError => Status::internal(message),
+    }
+}
+
+/// Runs an operation on the global MqttCtrl and AzuriteStorage out of the async workers,
+/// their locks may be held by the main loop for a while
+async fn blocking<F, T>(f: F) -> std::result::Result<T, Status>
+where
+    F: FnOnce() -> Result<T, DMError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?
+        .map_err(to_status)
+}
+
+fn ensure_connected(mqtt_ctrl: &MqttCtrl) -> Result<(), DMError> {
+    if mqtt_ctrl.is_device_connected() {
+        Ok(())
+    } else {
+        Err(Report::new(DMError::IOError).attach_printable("Device is not connected"))
+    }
+}
+
+/// Sends the deployment of the module with a new SAS URL, the modules are listed again
+/// when the module is not known yet
+fn deploy_module(module_id: &str) -> Result<String, DMError> {
+    let id = UUID::from(module_id)?;
+    let module = with_azurite_storage_mut(|azurite_storage| {
+        if !azurite_storage.module_info_db().contains_key(&id) {
+            azurite_storage.update_modules(None)?;
+        }
+        azurite_storage.refresh_module(&id)
+    })
+    .unwrap_or_else(|| {
+        Err(Report::new(DMError::RuntimeError).attach_printable("Azurite storage not available"))
+    })?;
+
+    let deployment = module.deployment_json()?;
+    with_mqtt_ctrl_mut(|mqtt_ctrl| {
+        ensure_connected(mqtt_ctrl)?;
+        mqtt_ctrl.send_configure(&deployment)
+    })?;
+    Ok(deployment)
+}
+
+/// Operations of the global MqttCtrl served over gRPC
+#[derive(Debug, Default)]
+pub struct DeviceMonitorService;
+
+#[tonic::async_trait]
+impl DeviceMonitor for DeviceMonitorService {
+    async fn get_state(
+        &self,
+        _request: Request<GetStateRequest>,
+    ) -> std::result::Result<Response<GetStateResponse>, Status> {
+        let (connected, state) = blocking(|| {
+            Ok(with_mqtt_ctrl(|mqtt_ctrl| {
+                (mqtt_ctrl.is_device_connected(), mqtt_ctrl.state())
+            }))
+        })
+        .await?;
+
+        Ok(Response::new(GetStateResponse {
+            connected,
+            state_json: state.to_string(),
+        }))
+    }
+
+    async fn send_configure(
+        &self,
+        request: Request<SendConfigureRequest>,
+    ) -> std::result::Result<Response<SendConfigureResponse>, Status> {
+        let config = request.into_inner().config_json;
+        serde_json::from_str::<serde_json::Value>(&config)
+            .map_err(|e| Status::invalid_argument(format!("Invalid configuration JSON: {e}")))?;
+
+        blocking(move || {
+            with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                ensure_connected(mqtt_ctrl)?;
+                mqtt_ctrl.send_configure(&config)
+            })
+        })
+        .await?;
+
+        Ok(Response::new(SendConfigureResponse {}))
+    }
+
+    async fn trigger_rpc(
+        &self,
+        request: Request<TriggerRpcRequest>,
+    ) -> std::result::Result<Response<TriggerRpcResponse>, Status> {
+        let TriggerRpcRequest {
+            method,
+            instance,
+            params_json,
+        } = request.into_inner();
+
+        let request_json = blocking(move || {
+            with_mqtt_ctrl_mut(|mqtt_ctrl| {
+                ensure_connected(mqtt_ctrl)?;
+                mqtt_ctrl.send_rpc(method.trim(), instance.trim(), params_json.trim())
+            })
+        })
+        .await?;
+
+        Ok(Response::new(TriggerRpcResponse { request_json }))
+    }
+
+    async fn deploy_module(
+        &self,
+        request: Request<DeployModuleRequest>,
+    ) -> std::result::Result<Response<DeployModuleResponse>, Status> {
+        let module_id = request.into_inner().module_id;
+        let deployment_json = blocking(move || deploy_module(&module_id)).await?;
+
+        Ok(Response::new(DeployModuleResponse { deployment_json }))
+    }
+}
+
+/// gRPC server of the control interface, bound before the terminal is taken over so that
+/// a busy address is reported on the console
+pub struct GrpcServer {
+    listener: TcpListener,
+}
+
+impl GrpcServer {
+    pub fn bind(addr: SocketAddr) -> Result<Self, DMError> {
+        let listener = TcpListener::bind(addr).map_err(|e| {
+            Report::new(DMError::IOError).attach_printable(format!("Failed to bind {addr}: {e}"))
+        })?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
+        Ok(Self { listener })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, DMError> {
+        self.listener
+            .local_addr()
+            .map_err(|e| Report::new(DMError::IOError).attach_printable(e))
+    }
+
+    /// Serves the requests on a thread of its own, the global MqttCtrl must be initialized
+    pub fn spawn(self) -> Result<(), DMError> {
+        let addr = self.local_addr()?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| Report::new(DMError::RuntimeError).attach_printable(e))?;
+
+        let incoming = {
+            let _guard = runtime.enter();
+            let listener = tokio::net::TcpListener::from_std(self.listener)
+                .map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
+            TcpIncoming::from_listener(listener, true, None)
+                .map_err(|e| Report::new(DMError::IOError).attach_printable(e.to_string()))?
+        };
+
+        std::thread::spawn(move || {
+            jinfo!(
+                func = "GrpcServer::spawn()",
+                note = "Serving gRPC",
+                addr = addr.to_string()
+            );
+            let result = runtime.block_on(
+                Server::builder()
+                    .add_service(DeviceMonitorServer::new(DeviceMonitorService))
+                    .serve_with_incoming(incoming),
+            );
+            if let Err(e) = result {
+                jerror!(func = "GrpcServer::spawn()", error = e.to_string());
+            }
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_status() {
+        let status = to_status(
+            Report::new(DMError::InvalidData).attach_printable("Params must be a JSON object"),
+        );
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert_eq!(status.message(), "Params must be a JSON object");
+
+        let status = to_status(Report::new(DMError::IOError));
+        assert_eq!(status.code(), tonic::Code::Unavailable);
+        assert_eq!(status.message(), "IO error");
+
+        assert_eq!(
+            to_status(Report::new(DMError::Timeout)).code(),
+            tonic::Code::DeadlineExceeded
+        );
+        assert_eq!(
+            to_status(Report::new(DMError::RuntimeError)).code(),
+            tonic::Code::Internal
+        );
+    }
+
+    #[test]
+    fn test_deploy_module_invalid_id() {
+        let e = deploy_module("not-a-uuid").unwrap_err();
+        assert!(matches!(e.current_context(), DMError::InvalidData));
+    }
+
+    #[test]
+    fn test_bind() {
+        let server = GrpcServer::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = server.local_addr().unwrap();
+        assert_ne!(addr.port(), 0);
+
+        // The address is still in use
+        assert!(GrpcServer::bind(addr).is_err());
+    }
+}
@@ -0,0 +1,414 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    chrono::{DateTime, TimeDelta, TimeZone},
+    std::{borrow::Cow, collections::HashMap, fmt::Display, sync::OnceLock},
+};
+
+/// Language of the UI, English strings are the keys of the catalogs
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+impl Locale {
+    /// Locale of a POSIX locale name or a language tag, e.g. "ja_JP.UTF-8" or "en-US"
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Locale::En),
+            "ja" => Some(Locale::Ja),
+            _ => None,
+        }
+    }
+
+    /// Locale of the environment, from LC_ALL, LC_MESSAGES or LANG in this order
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Locale::from_tag(&value))
+            .unwrap_or_default()
+    }
+
+    fn catalog(&self) -> Option<&'static HashMap<String, String>> {
+        static JA: OnceLock<HashMap<String, String>> = OnceLock::new();
+        match self {
+            Locale::En => None,
+            Locale::Ja => Some(JA.get_or_init(|| {
+                serde_json::from_str(include_str!("i18n/ja.json"))
+                    .expect("Invalid message catalog i18n/ja.json")
+            })),
+        }
+    }
+
+    /// Translation of an English string, the string itself when it has none
+    pub fn tr<'a>(&self, text: &'a str) -> &'a str {
+        self.catalog()
+            .and_then(|catalog| catalog.get(text))
+            .map_or(text, |s| s.as_str())
+    }
+
+    /// Translation of a string padded with spaces such as a block title, keeping the padding
+    pub fn tr_padded<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let trimmed = text.trim();
+        let translated = self.tr(trimmed);
+        if translated == trimmed {
+            return Cow::Borrowed(text);
+        }
+
+        let start = text.len() - text.trim_start().len();
+        let end = text.trim_end().len();
+        Cow::Owned(format!("{}{}{}", &text[..start], translated, &text[end..]))
+    }
+
+    /// Translation with "{0}", "{1}", ... replaced by the arguments
+    pub fn trf(&self, text: &str, args: &[&dyn Display]) -> String {
+        args.iter()
+            .enumerate()
+            .fold(self.tr(text).to_owned(), |s, (i, arg)| {
+                s.replace(&format!("{{{i}}}"), &arg.to_string())
+            })
+    }
+
+    /// Translation of a key hint such as "(ESC) back, UP(k)/DOWN(j) move", the keys are kept
+    /// and the action of each comma separated entry is translated
+    pub fn tr_hint(&self, hint: &str) -> String {
+        if self.catalog().is_none() {
+            return hint.to_owned();
+        }
+
+        hint.split(", ")
+            .map(|entry| match entry.rfind(')') {
+                Some(i) => {
+                    let action = entry[i + 1..].trim_start_matches(':').trim();
+                    if action.is_empty() {
+                        entry.to_owned()
+                    } else {
+                        format!("{} {}", &entry[..=i], self.tr(action))
+                    }
+                }
+                None => self.tr(entry.trim()).to_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// e.g. "2025-01-01 10:00:00" or "2025年01月01日 10:00:00"
+    pub fn format_datetime<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        match self {
+            Locale::En => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            Locale::Ja => time.format("%Y年%m月%d日 %H:%M:%S").to_string(),
+        }
+    }
+
+    /// Time elapsed since an event, e.g. "0 day 1h 2m 3s ago" or "0日 1時間 2分 3秒前"
+    pub fn format_elapsed(&self, delta: TimeDelta) -> String {
+        let (days, hours, minutes, seconds) = (
+            delta.num_days(),
+            delta.num_hours() % 24,
+            delta.num_minutes() % 60,
+            delta.num_seconds() % 60,
+        );
+        match self {
+            Locale::En => format!("{days} day {hours}h {minutes}m {seconds}s ago"),
+            Locale::Ja => format!("{days}日 {hours}時間 {minutes}分 {seconds}秒前"),
+        }
+    }
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// Selects the locale of the UI, detected from the environment when not given. Done once at
+/// startup, English is used until then.
+pub fn init_locale(locale: Option<Locale>) -> Locale {
+    *LOCALE.get_or_init(|| locale.unwrap_or_else(Locale::detect))
+}
+
+pub fn locale() -> Locale {
+    LOCALE.get().copied().unwrap_or_default()
+}
+
+/// Translation of an English string in the locale of the UI
+pub fn tr(text: &str) -> &str {
+    locale().tr(text)
+}
+
+pub fn tr_padded(text: &str) -> Cow<'_, str> {
+    locale().tr_padded(text)
+}
+
+pub fn trf(text: &str, args: &[&dyn Display]) -> String {
+    locale().trf(text, args)
+}
+
+pub fn tr_hint(hint: &str) -> String {
+    locale().tr_hint(hint)
+}
+
+pub fn format_datetime<Tz: TimeZone>(time: &DateTime<Tz>) -> String
+where
+    Tz::Offset: Display,
+{
+    locale().format_datetime(time)
+}
+
+pub fn format_elapsed(delta: TimeDelta) -> String {
+    locale().format_elapsed(delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::BTreeSet,
+        path::{Path, PathBuf},
+    };
+
+    /// Functions looking up their string literal argument in the catalogs, and whether the
+    /// literal is trimmed for it
+    const TRANSLATED_CALLS: [(&str, bool); 5] = [
+        ("tr", false),
+        ("trf", false),
+        ("tr_padded", true),
+        ("normal_block", true),
+        ("focus_block", true),
+    ];
+
+    /// Functions and fields taking the messages of the footer and the Errors popup, and
+    /// whether the message is their second argument
+    const MESSAGE_SINKS: [(&str, bool); 4] = [
+        ("push_warning", true),
+        ("push_error", true),
+        ("push_failure", true),
+        ("info = Some", false),
+    ];
+
+    fn rust_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                rust_files(&path, files);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                files.push(path);
+            }
+        }
+    }
+
+    /// String literal at the start of `code`, with its escapes resolved
+    fn string_literal(code: &str) -> Option<String> {
+        let mut chars = code.strip_prefix('"')?.chars();
+        let mut text = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Some(text),
+                '\\' => match chars.next()? {
+                    'n' => text.push('\n'),
+                    't' => text.push('\t'),
+                    // Continued on the next line, without its indentation
+                    '\n' => chars = chars.as_str().trim_start().chars(),
+                    c => text.push(c),
+                },
+                c => text.push(c),
+            }
+        }
+        None
+    }
+
+    /// Code following each call of `name`, skipping the calls of other functions ending
+    /// with it
+    fn call_args<'a>(code: &'a str, name: &str) -> Vec<&'a str> {
+        let call = format!("{name}(");
+        code.match_indices(&call)
+            .filter(|(i, _)| {
+                code[..*i]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|c| !c.is_alphanumeric() && c != '_')
+            })
+            .map(|(i, _)| &code[i + call.len()..])
+            .collect()
+    }
+
+    /// Literals passed to `name`
+    fn call_literals(code: &str, name: &str) -> Vec<String> {
+        call_args(code, name)
+            .into_iter()
+            .filter_map(|args| string_literal(args.trim_start()))
+            .collect()
+    }
+
+    /// Messages of the footer and the Errors popup, which are looked up in the catalogs when
+    /// set. A message formatted by the UI must be translated by trf() instead of format!(),
+    /// the ones formatted by the core hold device data and are shown as they are.
+    fn message_literals(code: &str, ui: bool) -> Vec<String> {
+        let mut literals = vec![];
+        for (name, second) in MESSAGE_SINKS {
+            for args in call_args(code, name) {
+                let message = match second {
+                    true => args.split_once(',').map(|(_, message)| message),
+                    false => Some(args),
+                };
+                let Some(message) = message.map(|m| m.trim_start().trim_start_matches('&')) else {
+                    continue;
+                };
+                let message = match message.strip_prefix("format!(") {
+                    Some(_) if !ui => continue,
+                    Some(format) => format.trim_start(),
+                    None => message,
+                };
+                literals.extend(string_literal(message));
+            }
+        }
+        literals
+    }
+
+    /// Strings looked up by tr_hint(), the actions of the entries of the hint
+    fn hint_strings(hint: &str) -> Vec<String> {
+        hint.split(", ")
+            .map(|entry| match entry.rfind(')') {
+                Some(i) => entry[i + 1..].trim_start_matches(':').trim(),
+                None => entry.trim(),
+            })
+            .map(|s| s.to_owned())
+            .collect()
+    }
+
+    /// Strings of the UI in the sources, which are looked up in the catalogs when drawn.
+    /// `ui` tells whether the file is a part of the UI, which translates the messages it
+    /// formats.
+    fn ui_strings(path: &Path, ui: bool) -> Vec<String> {
+        let code = std::fs::read_to_string(path).unwrap();
+        let code = code.split("#[cfg(test)]\nmod tests").next().unwrap();
+
+        let mut strings = vec![];
+        for (name, trim) in TRANSLATED_CALLS {
+            for text in call_literals(code, name) {
+                strings.push(if trim { text.trim().to_owned() } else { text });
+            }
+        }
+        let mut hints = call_literals(code, "tr_hint");
+        // The key hints of the screens are translated after the match choosing them
+        if path.ends_with("ui_foot.rs")
+            && let Some((_, screen_hints)) = code.split_once("let current_keys_hint")
+        {
+            hints.extend(call_literals(screen_hints, "Span::styled"));
+        }
+        strings.extend(hints.iter().flat_map(|hint| hint_strings(hint)));
+        strings.extend(message_literals(code, ui));
+
+        strings.retain(|s| !s.is_empty());
+        strings
+    }
+
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(Locale::from_tag("ja_JP.UTF-8"), Some(Locale::Ja));
+        assert_eq!(Locale::from_tag("ja"), Some(Locale::Ja));
+        assert_eq!(Locale::from_tag("en-US"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("C.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("fr_FR"), None);
+        assert_eq!(Locale::from_tag(""), None);
+    }
+
+    #[test]
+    fn test_catalog() {
+        let catalog = Locale::Ja.catalog().unwrap();
+        for (en, ja) in catalog {
+            assert!(!ja.trim().is_empty(), "no translation of {en}");
+            // Placeholders are kept by the translations
+            for i in 0..8 {
+                let placeholder = format!("{{{i}}}");
+                assert_eq!(
+                    en.contains(&placeholder),
+                    ja.contains(&placeholder),
+                    "{en} -> {ja}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_catalog_has_the_ui_strings() {
+        let catalog = Locale::Ja.catalog().unwrap();
+        let src = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src"));
+        let mut files = vec![];
+        rust_files(src, &mut files);
+
+        let mut missing = BTreeSet::new();
+        for path in files.iter().filter(|path| !path.ends_with("i18n.rs")) {
+            for text in ui_strings(path, path.starts_with(src.join("app"))) {
+                if !catalog.contains_key(&text) {
+                    missing.insert(format!("{}: {:?}", path.display(), text));
+                }
+            }
+        }
+        assert!(
+            missing.is_empty(),
+            "Missing in i18n/ja.json:\n{}",
+            missing.into_iter().collect::<Vec<_>>().join("\n")
+        );
+    }
+
+    #[test]
+    fn test_tr() {
+        assert_eq!(Locale::En.tr("Connected"), "Connected");
+        assert_eq!(Locale::Ja.tr("Connected"), "接続中");
+        assert_eq!(Locale::Ja.tr("no such string"), "no such string");
+        assert_eq!(Locale::Ja.tr_padded(" DEVICE STATE "), " デバイス状態 ");
+        assert_eq!(
+            Locale::Ja.tr_padded(" Token Providers (3) "),
+            " Token Providers (3) "
+        );
+        assert_eq!(
+            Locale::Ja.trf("Device Monitor v{0}", &[&"0.1.6"]),
+            "デバイスモニター v0.1.6"
+        );
+    }
+
+    #[test]
+    fn test_tr_hint() {
+        let hint = "(ESC):back, UP(k)/DOWN(j) move, (s) send, (x) untranslated, Type the name";
+        assert_eq!(Locale::En.tr_hint(hint), hint);
+        assert_eq!(
+            Locale::Ja.tr_hint(hint),
+            "(ESC) 戻る, UP(k)/DOWN(j) 移動, (s) 送信, (x) untranslated, 名前を入力"
+        );
+    }
+
+    #[test]
+    fn test_format_time() {
+        let time = DateTime::parse_from_rfc3339("2025-01-02T03:04:05+09:00").unwrap();
+        assert_eq!(Locale::En.format_datetime(&time), "2025-01-02 03:04:05");
+        assert_eq!(Locale::Ja.format_datetime(&time), "2025年01月02日 03:04:05");
+
+        let delta = TimeDelta::seconds(90_061);
+        assert_eq!(Locale::En.format_elapsed(delta), "1 day 1h 1m 1s ago");
+        assert_eq!(Locale::Ja.format_elapsed(delta), "1日 1時間 1分 1秒前");
+    }
+}
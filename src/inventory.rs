@@ -0,0 +1,214 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        error::DMError,
+        mqtt_ctrl::evp::device_info::{ChipInfo, DeviceInfo},
+    },
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jerror, jinfo},
+    serde::Deserialize,
+};
+
+/// Versions expected on one chip, the ones not given are not checked
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChipInventory {
+    pub loader_version: Option<String>,
+    pub firmware_version: Option<String>,
+    /// Versions of the AI models which must be deployed on the chip
+    #[serde(default)]
+    pub ai_models: Vec<String>,
+}
+
+/// Firmware and AI model versions a device must have, e.g. the fleet standard checked during
+/// provisioning:
+///
+/// ```json
+/// {
+///     "main_chip": { "firmware_version": "D52408", "loader_version": "020301" },
+///     "sensor_chip": { "firmware_version": "010707", "ai_models": ["0308000000000100"] }
+/// }
+/// ```
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Inventory {
+    pub main_chip: Option<ChipInventory>,
+    pub companion_chip: Option<ChipInventory>,
+    pub sensor_chip: Option<ChipInventory>,
+}
+
+/// One expected version compared with the one reported by the device
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryCheck {
+    /// Chip and item, e.g. "main_chip firmware_version"
+    pub item: String,
+    pub expected: String,
+    /// None when the device has not reported it
+    pub reported: Option<String>,
+}
+
+impl InventoryCheck {
+    pub fn compliant(&self) -> bool {
+        self.reported.as_deref() == Some(self.expected.as_str())
+    }
+}
+
+impl Inventory {
+    pub fn parse(s: &str) -> Result<Self, DMError> {
+        serde_json::from_str(s).map_err(|e| {
+            Report::new(DMError::InvalidData).attach_printable(format!("Invalid inventory: {e}"))
+        })
+    }
+
+    pub fn load(path: &str) -> Result<Self, DMError> {
+        let s = std::fs::read_to_string(path).map_err(|e| {
+            Report::new(DMError::IOError).attach_printable(format!("Failed to read {path}: {e}"))
+        })?;
+        Self::parse(&s)
+    }
+
+    /// Compares the expected versions with the ones of `device_info`, in the order of the
+    /// chips of the main screen
+    pub fn compare(&self, device_info: Option<&DeviceInfo>) -> Vec<InventoryCheck> {
+        let chips = [
+            (
+                "main_chip",
+                &self.main_chip,
+                device_info.and_then(|d| d.main_chip()),
+            ),
+            (
+                "companion_chip",
+                &self.companion_chip,
+                device_info.and_then(|d| d.companion_chip()),
+            ),
+            (
+                "sensor_chip",
+                &self.sensor_chip,
+                device_info.and_then(|d| d.sensor_chip()),
+            ),
+        ];
+
+        let mut checks = vec![];
+        for (name, expected, chip) in chips {
+            let Some(expected) = expected else {
+                continue;
+            };
+
+            let mut check = |item: &str, expected: &Option<String>, reported: Option<&str>| {
+                if let Some(expected) = expected {
+                    checks.push(InventoryCheck {
+                        item: format!("{name} {item}"),
+                        expected: expected.clone(),
+                        reported: reported.map(|r| r.to_owned()),
+                    });
+                }
+            };
+            check(
+                "loader_version",
+                &expected.loader_version,
+                chip.and_then(|c| c.loader_version()),
+            );
+            check(
+                "firmware_version",
+                &expected.firmware_version,
+                chip.and_then(|c| c.firmware_version()),
+            );
+
+            for model in &expected.ai_models {
+                let deployed = chip.and_then(|c| {
+                    c.ai_models()
+                        .iter()
+                        .find(|m| m.version() == model)
+                        .map(|m| m.version().to_owned())
+                });
+                checks.push(InventoryCheck {
+                    item: format!("{name} ai_model"),
+                    expected: model.clone(),
+                    reported: deployed,
+                });
+            }
+        }
+
+        checks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rejects_unknown_fields() {
+        let e = Inventory::parse(r#"{"main_chip": {"firmware": "D52408"}}"#).unwrap_err();
+        assert!(format!("{e:?}").contains("unknown field `firmware`"));
+    }
+
+    #[test]
+    fn test_compare() {
+        let inventory = Inventory::parse(
+            r#"{
+                "main_chip": { "firmware_version": "D52408", "loader_version": "020301" },
+                "sensor_chip": { "firmware_version": "010707", "ai_models": ["0308000000000100", "0308000000000200"] }
+            }"#,
+        )
+        .unwrap();
+
+        let device_info: DeviceInfo = serde_json::from_str(
+            r#"{
+                "device_manifest": "",
+                "chips": [
+                    {
+                        "name": "main_chip", "id": "", "hardware_version": "", "temperature": 40,
+                        "loader_version": "020301", "loader_hash": "", "update_date_loader": "",
+                        "firmware_version": "D52407", "firmware_hash": "", "update_date_firmware": "",
+                        "ai_models": []
+                    },
+                    {
+                        "name": "sensor_chip", "id": "", "hardware_version": "", "temperature": 40,
+                        "loader_version": "020301", "loader_hash": "", "update_date_loader": "",
+                        "firmware_version": "010707", "firmware_hash": "", "update_date_firmware": "",
+                        "ai_models": [{ "version": "0308000000000100", "hash": "", "update_date": "" }]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let checks = inventory.compare(Some(&device_info));
+        let summary: Vec<(&str, bool)> = checks
+            .iter()
+            .map(|c| (c.item.as_str(), c.compliant()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("main_chip loader_version", true),
+                ("main_chip firmware_version", false),
+                ("sensor_chip firmware_version", true),
+                ("sensor_chip ai_model", true),
+                ("sensor_chip ai_model", false),
+            ]
+        );
+        assert_eq!(checks[1].reported.as_deref(), Some("D52407"));
+        assert_eq!(checks[4].reported, None);
+
+        // Nothing is reported before the device sends its DeviceInfo
+        assert!(inventory.compare(None).iter().all(|c| c.reported.is_none()));
+    }
+}
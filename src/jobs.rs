@@ -0,0 +1,700 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        azurite::{
+            AzuriteConfig, AzuriteStorage,
+            worker::{self, AzuriteJob, AzuriteJobResult},
+        },
+        error::{DMError, DMErrorExt},
+        provisioning::{
+            ntp::{NTP_TIMEOUT, NtpCheck, NtpReport},
+            proxy::{CHECK_TIMEOUT, ProxyCheck, ProxyReport},
+        },
+        report::{DeviceReport, ReportFormat},
+    },
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jerror, jinfo},
+    std::{
+        fmt::Display,
+        sync::{
+            Arc, Mutex,
+            mpsc::{self, Receiver, Sender},
+        },
+        time::{Duration, Instant},
+    },
+};
+
+/// Finished jobs kept for the Jobs screen
+const MAX_FINISHED: usize = 100;
+
+/// Blob downloads run at the same time, next to the other jobs
+pub const DOWNLOAD_WORKERS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed(String),
+    Cancelled,
+}
+
+impl Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Done => "done",
+            JobStatus::Failed(_) => "failed",
+            JobStatus::Cancelled => "cancelled",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JobRecord {
+    pub id: u64,
+    pub label: String,
+    /// Job::key() of the jobs run by the worker, the label otherwise
+    pub key: String,
+    pub status: JobStatus,
+    /// Percent done, for the jobs reporting it
+    pub progress: Option<u8>,
+    pub submitted: Instant,
+    pub started: Option<Instant>,
+    pub finished: Option<Instant>,
+    /// Periodic check of the App, not listed and forgotten once finished
+    pub background: bool,
+}
+
+impl JobRecord {
+    pub fn is_active(&self) -> bool {
+        matches!(self.status, JobStatus::Queued | JobStatus::Running)
+    }
+
+    /// Time spent running, or waiting for the jobs never started
+    pub fn duration(&self, now: Instant) -> Duration {
+        let start = self.started.unwrap_or(self.submitted);
+        self.finished
+            .unwrap_or(now)
+            .saturating_duration_since(start)
+    }
+}
+
+/// Job records shared by the UI thread and the worker thread, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct JobRegistry(Arc<Mutex<Vec<JobRecord>>>);
+
+impl JobRegistry {
+    fn with<R>(&self, f: impl FnOnce(&mut Vec<JobRecord>) -> R) -> R {
+        f(&mut self.0.lock().expect("Failed to lock job registry"))
+    }
+
+    fn with_record(&self, id: u64, f: impl FnOnce(&mut JobRecord)) {
+        self.with(|records| {
+            if let Some(record) = records.iter_mut().find(|r| r.id == id) {
+                f(record);
+            }
+        })
+    }
+
+    pub fn add(&self, id: u64, label: &str, status: JobStatus) {
+        let now = Instant::now();
+        self.with(|records| {
+            records.push(JobRecord {
+                id,
+                label: label.to_owned(),
+                key: label.to_owned(),
+                started: (status == JobStatus::Running).then_some(now),
+                status,
+                progress: None,
+                submitted: now,
+                finished: None,
+                background: false,
+            });
+        })
+    }
+
+    /// Records a job queued for the worker, returns false when one with the same key is
+    /// already queued or running
+    fn queue(&self, id: u64, job: &Job) -> bool {
+        let key = job.key();
+        self.with(|records| {
+            if records.iter().any(|r| r.is_active() && r.key == key) {
+                return false;
+            }
+
+            records.push(JobRecord {
+                id,
+                label: job.label(),
+                key,
+                status: JobStatus::Queued,
+                progress: None,
+                submitted: Instant::now(),
+                started: None,
+                finished: None,
+                background: job.is_background(),
+            });
+            true
+        })
+    }
+
+    /// Forgets the oldest finished jobs beyond `MAX_FINISHED`
+    fn prune(&self) {
+        self.with(|records| {
+            let finished = records.iter().filter(|r| !r.is_active()).count();
+            let mut excess = finished.saturating_sub(MAX_FINISHED);
+            records.retain(|r| {
+                if excess > 0 && !r.is_active() {
+                    excess -= 1;
+                    return false;
+                }
+                true
+            });
+        })
+    }
+
+    /// Marks a queued job as running, returns false when it has been cancelled
+    pub fn start(&self, id: u64) -> bool {
+        let mut started = false;
+        self.with_record(id, |record| {
+            if record.status == JobStatus::Queued {
+                record.status = JobStatus::Running;
+                record.started = Some(Instant::now());
+                started = true;
+            }
+        });
+        started
+    }
+
+    pub fn set_progress(&self, id: u64, done: usize, total: usize) {
+        let percent = (done * 100 / total.max(1)).min(100) as u8;
+        self.with_record(id, |record| record.progress = Some(percent));
+    }
+
+    pub fn finish(&self, id: u64, error: Option<String>) {
+        self.with_record(id, |record| {
+            record.status = match error {
+                Some(e) => JobStatus::Failed(e),
+                None => JobStatus::Done,
+            };
+            record.finished = Some(Instant::now());
+        });
+        self.with(|records| records.retain(|r| r.is_active() || !r.background));
+        self.prune();
+    }
+
+    /// Cancels a job which has not started yet, returns false otherwise
+    pub fn cancel(&self, id: u64) -> bool {
+        let mut cancelled = false;
+        self.with_record(id, |record| {
+            if record.status == JobStatus::Queued {
+                record.status = JobStatus::Cancelled;
+                record.finished = Some(Instant::now());
+                cancelled = true;
+            }
+        });
+        self.prune();
+        cancelled
+    }
+
+    /// Records of the jobs, the background ones excepted
+    pub fn records(&self) -> Vec<JobRecord> {
+        self.with(|records| records.iter().filter(|r| !r.background).cloned().collect())
+    }
+}
+
+/// Operation run on the worker thread
+#[derive(Debug)]
+pub enum Job {
+    Azurite(AzuriteConfig, AzuriteJob),
+    SaveReport(Box<DeviceReport>, ReportFormat),
+    ProxyCheck(ProxyCheck),
+    NtpCheck(NtpCheck),
+}
+
+impl Job {
+    /// Downloads can take long over slow links, they run on workers of their own so that
+    /// they neither wait for nor delay the other jobs
+    pub fn is_download(&self) -> bool {
+        matches!(self, Job::Azurite(_, AzuriteJob::Download { .. }))
+    }
+
+    pub fn is_background(&self) -> bool {
+        matches!(self, Job::Azurite(_, job) if job.is_background())
+    }
+
+    /// Operation and what it works on, e.g. the blob downloaded or the server checked
+    pub fn key(&self) -> String {
+        match self {
+            Job::Azurite(_, job) => job.key(),
+            Job::SaveReport(_, format) => format!("report {}", format.extension()),
+            Job::ProxyCheck(check) => format!("proxy {}:{}", check.host, check.port),
+            Job::NtpCheck(check) => format!("ntp {}:{}", check.server, check.port),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Job::Azurite(_, job) => job.label(),
+            Job::SaveReport(_, format) => format!("Saving {} report", format.extension()),
+            Job::ProxyCheck(check) => format!("Checking proxy {}:{}", check.host, check.port),
+            Job::NtpCheck(check) => format!("Querying NTP server {}", check.server),
+        }
+    }
+}
+
+/// Completion event of a job, applied to the App in `update()`
+#[derive(Debug)]
+pub enum JobResult {
+    Azurite(AzuriteJobResult),
+    /// Path of the saved report
+    Report(Result<String, DMError>),
+    ProxyCheck(ProxyReport),
+    NtpCheck(NtpReport),
+}
+
+impl JobResult {
+    /// Error shown in the Jobs screen
+    fn error(&self) -> Option<String> {
+        let error_str = |e: &Report<DMError>| e.error_str().unwrap_or(e.to_string());
+        match self {
+            JobResult::Azurite(result) => result.error().map(error_str),
+            JobResult::Report(result) => result.as_ref().err().map(error_str),
+            JobResult::ProxyCheck(report) => report.failure(),
+            JobResult::NtpCheck(report) => report.failure(),
+        }
+    }
+}
+
+type QueuedJob = (u64, Job);
+
+type CompletedJob = (u64, JobResult);
+
+/// Runs the jobs one after the other. Azurite jobs use a storage of their own, so that the
+/// global storage is not locked while waiting for Azurite. Workers sharing the receiver run
+/// its jobs concurrently.
+fn worker_loop(
+    jobs: Arc<Mutex<Receiver<QueuedJob>>>,
+    results: Sender<CompletedJob>,
+    registry: JobRegistry,
+) {
+    let mut storage: Option<(AzuriteConfig, AzuriteStorage)> = None;
+
+    loop {
+        let next = jobs.lock().expect("Failed to lock job receiver").recv();
+        let Ok((id, job)) = next else {
+            break;
+        };
+
+        if !registry.start(id) {
+            jdebug!(func = "worker_loop()", cancelled = job.label());
+            continue;
+        }
+
+        jdebug!(func = "worker_loop()", job = job.label());
+        let mut progress = |done: usize, total: usize| registry.set_progress(id, done, total);
+        let result = match job {
+            Job::Azurite(config, job) => {
+                // The settings may have been changed from the Storage Settings screen
+                if storage.as_ref().is_none_or(|(c, _)| *c != config) {
+                    storage = AzuriteStorage::with_config(&config)
+                        .map_err(|e| jerror!(func = "worker_loop()", error = format!("{:?}", e)))
+                        .ok()
+                        .map(|s| (config, s));
+                }
+                JobResult::Azurite(worker::run(
+                    storage.as_mut().map(|(_, s)| s),
+                    job,
+                    &mut progress,
+                ))
+            }
+            Job::SaveReport(report, format) => JobResult::Report(report.save(format, None)),
+            Job::ProxyCheck(check) => JobResult::ProxyCheck(check.run(CHECK_TIMEOUT)),
+            Job::NtpCheck(check) => JobResult::NtpCheck(check.run(NTP_TIMEOUT)),
+        };
+
+        registry.finish(id, result.error());
+        if results.send((id, result)).is_err() {
+            break;
+        }
+    }
+}
+
+/// Senders of the jobs to the workers and receiver of their results
+#[derive(Debug)]
+struct Channels {
+    jobs: Sender<QueuedJob>,
+    downloads: Sender<QueuedJob>,
+    results: Receiver<CompletedJob>,
+}
+
+impl Channels {
+    /// Starts the worker of the jobs and `DOWNLOAD_WORKERS` workers of the downloads
+    fn spawn(registry: &JobRegistry) -> Self {
+        let (result_sender, results) = mpsc::channel();
+        let mut spawn_workers = |count: usize| {
+            let (sender, receiver) = mpsc::channel();
+            let receiver = Arc::new(Mutex::new(receiver));
+            for _ in 0..count {
+                let receiver = receiver.clone();
+                let result_sender = result_sender.clone();
+                let registry = registry.clone();
+                std::thread::spawn(move || worker_loop(receiver, result_sender, registry));
+            }
+            sender
+        };
+
+        let jobs = spawn_workers(1);
+        let downloads = spawn_workers(DOWNLOAD_WORKERS);
+        Self {
+            jobs,
+            downloads,
+            results,
+        }
+    }
+}
+
+/// Queue of the background jobs and the worker threads running them, started on the first
+/// job. Downloads are queued apart and run `DOWNLOAD_WORKERS` at a time.
+#[derive(Debug, Default)]
+pub struct JobQueue {
+    channel: Option<Channels>,
+    registry: JobRegistry,
+    next_id: u64,
+}
+
+impl JobQueue {
+    /// Queues a job, unless one with the same key is already queued or running. Returns
+    /// false when the job is skipped.
+    pub fn submit(&mut self, job: Job) -> bool {
+        if !self.registry.queue(self.next_id + 1, &job) {
+            jdebug!(func = "JobQueue::submit()", skipped = job.key());
+            return false;
+        }
+        self.next_id += 1;
+
+        let channels = self
+            .channel
+            .get_or_insert_with(|| Channels::spawn(&self.registry));
+        let sender = if job.is_download() {
+            &channels.downloads
+        } else {
+            &channels.jobs
+        };
+
+        if sender.send((self.next_id, job)).is_err() {
+            jerror!(func = "JobQueue::submit()", error = "Worker thread stopped");
+            self.registry
+                .finish(self.next_id, Some("Worker thread stopped".to_owned()));
+            self.channel = None;
+        }
+        true
+    }
+
+    /// Records a job running outside of the worker, e.g. waiting for the device, whose
+    /// progress is reported with `set_progress()` and `finish()`
+    pub fn track(&mut self, label: &str) -> u64 {
+        self.next_id += 1;
+        self.registry.add(self.next_id, label, JobStatus::Running);
+        self.next_id
+    }
+
+    pub fn set_progress(&self, id: u64, done: usize, total: usize) {
+        self.registry.set_progress(id, done, total);
+    }
+
+    pub fn finish(&self, id: u64, error: Option<String>) {
+        self.registry.finish(id, error);
+    }
+
+    pub fn cancel(&self, id: u64) -> bool {
+        self.registry.cancel(id)
+    }
+
+    /// Takes the results of the completed jobs
+    pub fn poll(&mut self) -> Vec<JobResult> {
+        let Some(channels) = &self.channel else {
+            return vec![];
+        };
+
+        let mut results = vec![];
+        while let Ok((_, result)) = channels.results.try_recv() {
+            results.push(result);
+        }
+        results
+    }
+
+    /// All the jobs of the session, oldest first
+    pub fn records(&self) -> Vec<JobRecord> {
+        self.registry.records()
+    }
+
+    /// The job started last among the running ones
+    pub fn current(&self) -> Option<JobRecord> {
+        self.records()
+            .into_iter()
+            .filter(|r| r.status == JobStatus::Running)
+            .max_by_key(|r| r.started)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_transitions() {
+        let registry = JobRegistry::default();
+        registry.add(1, "a", JobStatus::Queued);
+        registry.add(2, "b", JobStatus::Queued);
+
+        assert!(registry.cancel(2));
+        assert!(!registry.start(2));
+        assert!(registry.start(1));
+        assert!(!registry.cancel(1));
+
+        registry.set_progress(1, 1, 4);
+        registry.finish(1, Some("timeout".to_owned()));
+
+        let records = registry.records();
+        assert_eq!(records[0].progress, Some(25));
+        assert_eq!(records[0].status, JobStatus::Failed("timeout".to_owned()));
+        assert_eq!(records[1].status, JobStatus::Cancelled);
+        assert!(records.iter().all(|r| !r.is_active()));
+
+        for id in 3..(MAX_FINISHED as u64 + 4) {
+            registry.add(id, "c", JobStatus::Running);
+            registry.finish(id, None);
+        }
+        assert_eq!(registry.records().len(), MAX_FINISHED);
+    }
+
+    #[test]
+    fn test_queue_reports_unavailable_storage() {
+        let mut queue = JobQueue::default();
+        let job = || {
+            // The URL has no port, so the worker cannot create its storage
+            Job::Azurite(
+                AzuriteConfig::new("localhost"),
+                AzuriteJob::ListBlobs {
+                    container: "upload-1".to_owned(),
+                    log_files: false,
+                    marker: None,
+                },
+            )
+        };
+        assert_eq!(job().label(), "Listing blobs of upload-1");
+        let more = AzuriteJob::ListBlobs {
+            container: "upload-1".to_owned(),
+            log_files: false,
+            marker: Some("2!8!MDAwMDA0".to_owned()),
+        };
+        assert_eq!(more.label(), "Listing more blobs of upload-1");
+
+        assert!(queue.submit(job()));
+        assert!(!queue.submit(job()));
+        assert_eq!(queue.records().len(), 1);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut results = vec![];
+        while results.is_empty() && Instant::now() < deadline {
+            results = queue.poll();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        match results.as_slice() {
+            [
+                JobResult::Azurite(AzuriteJobResult::Blobs {
+                    container, result, ..
+                }),
+            ] => {
+                assert_eq!(container, "upload-1");
+                assert!(result.is_err());
+            }
+            r => panic!("unexpected results: {:?}", r),
+        }
+        assert!(matches!(
+            queue.records()[0].status,
+            JobStatus::Failed(ref e) if e == "Azurite storage not available"
+        ));
+    }
+
+    #[test]
+    fn test_queue_reports_failed_removals() {
+        let mut queue = JobQueue::default();
+        // The workers run the jobs concurrently, so they are waited for one at a time
+        let mut wait = |job: AzuriteJob| {
+            assert!(queue.submit(Job::Azurite(AzuriteConfig::new("localhost"), job)));
+            let deadline = Instant::now() + Duration::from_secs(5);
+            let mut results = vec![];
+            while results.is_empty() && Instant::now() < deadline {
+                results = queue.poll();
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            results
+        };
+
+        let remove = AzuriteJob::RemoveModules {
+            names: vec!["a.wasm".to_owned(), "b.wasm".to_owned()],
+            modules: Default::default(),
+            batch: true,
+        };
+        assert_eq!(remove.label(), "Removing 2 modules");
+        match wait(remove).as_slice() {
+            [
+                JobResult::Azurite(AzuriteJobResult::RemovedModules {
+                    batch: true,
+                    removals,
+                    modules: Err(_),
+                }),
+            ] => {
+                assert_eq!(
+                    removals.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>(),
+                    ["a.wasm", "b.wasm"]
+                );
+                assert!(removals.iter().all(|(_, r)| r.is_err()));
+            }
+            r => panic!("unexpected results: {:?}", r),
+        }
+
+        // The new settings are checked by the job itself, the URL has no port
+        let reconfigure = AzuriteJob::Reconfigure(AzuriteConfig::new("localhost"));
+        match wait(reconfigure).as_slice() {
+            [
+                JobResult::Azurite(AzuriteJobResult::Reconfigured {
+                    url,
+                    result: Err(_),
+                }),
+            ] => assert_eq!(url, "localhost"),
+            r => panic!("unexpected results: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_registry_dedupes_on_key() {
+        let registry = JobRegistry::default();
+        let download = |blob: &str, dir: Option<&str>| {
+            Job::Azurite(
+                AzuriteConfig::new("localhost"),
+                AzuriteJob::Download {
+                    container: "upload-1".to_owned(),
+                    blob: blob.to_owned(),
+                    dir: dir.map(str::to_owned),
+                },
+            )
+        };
+        assert_eq!(download("a.jpg", None).key(), "download upload-1/a.jpg .");
+
+        assert!(registry.queue(1, &download("a.jpg", None)));
+        assert!(!registry.queue(2, &download("a.jpg", None)));
+        // Same blob to another directory, or another blob
+        assert!(registry.queue(3, &download("a.jpg", Some("/tmp"))));
+        assert!(registry.queue(4, &download("b.jpg", None)));
+
+        let page = |marker: Option<&str>| {
+            Job::Azurite(
+                AzuriteConfig::new("localhost"),
+                AzuriteJob::ListBlobs {
+                    container: "upload-1".to_owned(),
+                    log_files: false,
+                    marker: marker.map(str::to_owned),
+                },
+            )
+        };
+        assert!(registry.queue(5, &page(None)));
+        assert!(registry.queue(6, &page(Some("2!8!MDAwMDA0"))));
+        assert!(!registry.queue(7, &page(Some("2!8!MDAwMDA0"))));
+
+        // Queued again once the first one finished
+        registry.finish(1, None);
+        assert!(registry.queue(8, &download("a.jpg", None)));
+        assert_eq!(registry.records().len(), 6);
+    }
+
+    #[test]
+    fn test_background_jobs_not_listed() {
+        let mut queue = JobQueue::default();
+        let job = Job::Azurite(AzuriteConfig::new("localhost"), AzuriteJob::CheckConnection);
+        assert!(job.is_background());
+        queue.submit(job);
+        assert!(queue.records().is_empty());
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut results = vec![];
+        while results.is_empty() && Instant::now() < deadline {
+            results = queue.poll();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(matches!(
+            results.as_slice(),
+            [JobResult::Azurite(AzuriteJobResult::Health(Err(_)))]
+        ));
+        // Forgotten once finished
+        assert!(queue.registry.with(|records| records.is_empty()));
+    }
+
+    #[test]
+    fn test_queue_runs_downloads_apart() {
+        let mut queue = JobQueue::default();
+        let download = |blob: &str| {
+            Job::Azurite(
+                AzuriteConfig::new("localhost"),
+                AzuriteJob::Download {
+                    container: "upload-1".to_owned(),
+                    blob: blob.to_owned(),
+                    dir: None,
+                },
+            )
+        };
+        assert!(download("a.jpg").is_download());
+        assert_eq!(download("a.jpg").label(), "Downloading upload-1/a.jpg");
+        assert!(
+            !Job::Azurite(AzuriteConfig::new("localhost"), AzuriteJob::ScanContainers)
+                .is_download()
+        );
+
+        let blobs = ["a.jpg", "b.jpg", "c.jpg", "d.jpg", "e.jpg"];
+        for blob in blobs {
+            queue.submit(download(blob));
+        }
+        assert_eq!(queue.records().len(), blobs.len());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut downloaded = vec![];
+        while downloaded.len() < blobs.len() && Instant::now() < deadline {
+            for result in queue.poll() {
+                match result {
+                    JobResult::Azurite(AzuriteJobResult::Downloaded { blob, result, .. }) => {
+                        assert!(result.is_err());
+                        downloaded.push(blob);
+                    }
+                    r => panic!("unexpected result: {:?}", r),
+                }
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        // Each download reports its own blob
+        downloaded.sort();
+        assert_eq!(downloaded, blobs);
+        assert!(queue.records().iter().all(|r| !r.is_active()));
+    }
+}
@@ -0,0 +1,113 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! Device management of AITRIOS devices over MQTT, without the terminal UI of
+//! device-monitor.
+//!
+//! [`mqtt_ctrl::MqttCtrl`] keeps the MQTT session with the device: it parses the EVP
+//! messages into the device state and sends the configurations and RPCs. The module,
+//! firmware and AI model packages are served by [`azurite::AzuriteStorage`].
+//!
+//! ```no_run
+//! use device_monitor_core::mqtt_ctrl::MqttCtrl;
+//!
+//! let mut mqtt_ctrl = MqttCtrl::new("localhost", 1883).unwrap();
+//! loop {
+//!     mqtt_ctrl.update().unwrap();
+//!     if mqtt_ctrl.is_device_connected() {
+//!         mqtt_ctrl.send_rpc("reboot", "", "").unwrap();
+//!         break;
+//!     }
+//!     std::thread::sleep(std::time::Duration::from_millis(10));
+//! }
+//! ```
+
+pub mod ai_model;
+pub mod audit;
+pub mod azurite;
+pub mod device_config;
+pub mod error;
+pub mod mqtt_ctrl;
+pub mod ota;
+pub mod versioned;
+
+// Terminal UI and modes of the device-monitor binary, built with the tui feature. Public for
+// the binary and the benches only, not part of the API of the library
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod app;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod broker;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod clipboard;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod compare;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod crash;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod daemon;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod debug_log;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod dtmi;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod fleet;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod grpc;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod i18n;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod inventory;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod jobs;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod notes;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod profile;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod provisioning;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod report;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod script;
+
+#[cfg(test)]
+#[ctor::ctor]
+fn test_init() {
+    // Initialize logger for tests
+    // Set log_console to true to see logs in the console during tests
+    jlogger_tracing::JloggerBuilder::new()
+        .max_level(jlogger_tracing::LevelFilter::DEBUG)
+        .log_console(false)
+        .build();
+}
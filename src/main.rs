@@ -14,23 +14,40 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-mod ai_model;
-mod app;
-mod azurite;
-mod error;
-mod mqtt_ctrl;
-mod ota;
+use device_monitor_core::{
+    app, audit, azurite, broker, crash, daemon, error, grpc, i18n, mqtt_ctrl, profile, report,
+    script,
+};
 
 #[allow(unused)]
 use {
-    app::{AppConfig, draw, handle_events, init_global_app, should_exit, update},
-    azurite::init_global_azurite_storage,
+    app::{AppConfig, draw, handle_events, init_global_app, record_frame, should_exit, update},
+    audit::{AuditLog, DEFAULT_AUDIT_FILE},
+    azurite::{
+        AzuriteConfig, bootstrap, defaults::DEFAULTS_FILE, init_global_azurite_storage_with_config,
+    },
+    broker::{BrokerConfig, LocalBroker},
     clap::Parser,
+    crash::{CrashConfig, TerminalGuard},
     crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-    error::DMError,
+    daemon::{Daemon, DaemonConfig},
+    error::{DMError, DMErrorExt},
     error_stack::{Report, Result},
+    grpc::GrpcServer,
+    i18n::Locale,
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
-    mqtt_ctrl::MqttCtrl,
+    mqtt_ctrl::{
+        MqttCtrl, PublishOptions,
+        dry_run::{DEFAULT_REVIEW_LOG, DryRun},
+        guard::{self, GuardConfig},
+        hooks::CommandHook,
+        memory::MemoryBudgets,
+        slow_network::SlowNetwork,
+        subscriptions::valid_filter,
+        syslog::{DEFAULT_SD_ID, SyslogForwarder, SyslogTarget, parse_sd_id},
+        temperature::{THRESHOLDS_FILE, TemperatureThresholds},
+    },
+    profile::FrameTimes,
     ratatui::{
         DefaultTerminal, Frame, Terminal,
         buffer::Buffer,
@@ -50,17 +67,100 @@ use {
         text::{Line, Span, Text},
         widgets::{Block, Borders, List, ListItem, Paragraph, Widget},
     },
+    report::{DeviceReport, ReportFormat},
+    script::{Script, ScriptRunner, ScriptTarget, expect::Expectations},
     serde_derive::{Deserialize, Serialize},
     std::{
         collections::HashMap,
         io::{self, Stderr},
+        path::PathBuf,
         time::{Duration, Instant},
     },
 };
 
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Wait for the device state to match the expectations, exit non-zero on mismatch
+    Check {
+        /// YAML file of the expected device state
+        #[arg(long)]
+        expect: String,
+
+        /// Seconds to wait for the device state to match
+        #[arg(long, default_value_t = 60)]
+        timeout: u64,
+    },
+
+    /// Collect the device messages for a while and write a report of the device
+    Report {
+        /// Format of the report
+        #[arg(long, value_enum, default_value_t = ReportFormat::Markdown)]
+        format: ReportFormat,
+
+        /// Report file (default: report_<time>.md or .html)
+        #[arg(long)]
+        output: Option<String>,
+
+        /// Seconds the device messages are collected before the report is written
+        #[arg(long, default_value_t = 30)]
+        wait: u64,
+    },
+
+    /// Manage a local MQTT broker for the lab
+    Broker {
+        #[command(subcommand)]
+        action: BrokerCommand,
+    },
+
+    /// Prepare the Azurite storage used by the module, OTA and token provider screens
+    Storage {
+        #[command(subcommand)]
+        action: StorageCommand,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum BrokerCommand {
+    /// Start mosquitto with anonymous access and persistence, then the UI connected to it
+    Up {
+        /// Port the broker listens on
+        #[arg(long, default_value_t = 1883)]
+        port: u16,
+
+        /// Directory of the broker configuration, persistence database and log
+        #[arg(long, default_value_t = String::from("broker"))]
+        data_dir: String,
+
+        /// Run the broker in a container instead of the local mosquitto
+        #[arg(long)]
+        docker: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum StorageCommand {
+    /// Check the connection, create the containers and upload the seed modules
+    Init {
+        /// Account name (default: the Azurite one, devstoreaccount1)
+        #[arg(long)]
+        account_name: Option<String>,
+
+        /// Base64 encoded account key (default: the Azurite one)
+        #[arg(long)]
+        account_key: Option<String>,
+
+        /// File uploaded as a module to the default container, can be repeated
+        #[arg(long)]
+        seed: Vec<String>,
+    },
+}
+
 #[derive(Parser)]
 #[command(author, version, about, long_about=None)]
 pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// MQTT broker address
     #[arg(short, long, default_value_t=String::from("localhost:1883"))]
     broker: String,
@@ -69,10 +169,129 @@ pub struct Cli {
     #[arg(short, long, default_value_t=String::from("https://127.0.1:10000"))]
     azurite_url: String,
 
+    /// Directory to download blobs uploaded by the device automatically
+    #[arg(long)]
+    download_dir: Option<String>,
+
+    /// Directory, URL or .json file the DTDL model of the device is read from
+    #[arg(long)]
+    dtmi_repository: Option<String>,
+
+    /// .json file of the firmware and AI model versions expected on the device
+    #[arg(long)]
+    inventory: Option<String>,
+
+    /// Directory of the daemon snapshot directories of the fleet, one per device
+    #[arg(long)]
+    fleet_dir: Option<String>,
+
+    /// Run a command for the messages on matching topics, as <topic filter>=<command>
+    #[arg(long)]
+    hook: Vec<String>,
+
+    /// Forward the elogs to udp://<host>:<port>, tcp://<host>:<port> or journald
+    #[arg(long)]
+    syslog: Option<String>,
+
+    /// SD-ID of the elog IDs in the syslog messages, as <name>@<private enterprise number>
+    #[arg(long, default_value_t = String::from(DEFAULT_SD_ID))]
+    syslog_sd_id: String,
+
+    /// Keep the passwords in the state snapshots instead of masking them
+    #[arg(long)]
+    include_secrets: bool,
+
     /// Log file
     #[arg(short, long)]
     log: Option<String>,
 
+    /// Write a crash report with the device state to this directory when device-monitor panics
+    #[arg(long)]
+    crash_dir: Option<String>,
+
+    /// QoS of the configurations sent to the device
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(0..=2))]
+    qos: u8,
+
+    /// Send the configurations as retained messages
+    #[arg(long)]
+    retain: bool,
+
+    /// Write the configurations and RPCs to the review log instead of sending them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Review log of the dry-run mode
+    #[arg(long, default_value_t = String::from(DEFAULT_REVIEW_LOG))]
+    review_log: String,
+
+    /// Only observe the device: no configuration, RPC, deployment or blob change
+    #[arg(long)]
+    read_only: bool,
+
+    /// Append-only file the operations changing the device or the storage are recorded to
+    #[arg(long, default_value_t = String::from(DEFAULT_AUDIT_FILE))]
+    audit_log: String,
+
+    /// Largest payload parsed, in bytes
+    #[arg(long, default_value_t = guard::DEFAULT_MAX_PAYLOAD)]
+    max_payload: usize,
+
+    /// Messages per second accepted on a topic, 0 for no limit
+    #[arg(long, default_value_t = guard::DEFAULT_RATE_LIMIT)]
+    rate_limit: u32,
+
+    /// Memory budget of a store as <store>=<MiB>, its oldest data is pruned beyond, can be
+    /// repeated. Stores: elogs, traffic, retained, metrics
+    #[arg(long)]
+    memory_budget: Vec<String>,
+
+    /// Debug: delay the messages of the broker by <delay ms>[:<jitter ms>], to try the UI on
+    /// a slow network
+    #[arg(long)]
+    slow_network: Option<String>,
+
+    /// Debug: overlay the time spent parsing, updating, drawing and waiting for the keys in
+    /// each frame
+    #[arg(long)]
+    profile_frame: bool,
+
+    /// Topic filter to subscribe, can be repeated (default: v1/devices/# and v1/gateway/#)
+    #[arg(long)]
+    subscribe: Vec<String>,
+
+    /// Run without the terminal UI, writing state snapshots and elog archives
+    #[arg(long)]
+    daemon: bool,
+
+    /// Directory the snapshots of the daemon mode are written to
+    #[arg(long, default_value_t = String::from("snapshots"))]
+    snapshot_dir: String,
+
+    /// Seconds between two snapshots of the daemon mode
+    #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+    snapshot_interval: u64,
+
+    /// Snapshots and elog archives of the daemon mode kept, the oldest ones are removed
+    #[arg(long, default_value_t = 1440)]
+    snapshot_keep: usize,
+
+    /// Serve the gRPC control interface on the address, e.g. 127.0.0.1:50051
+    #[arg(long)]
+    grpc: Option<std::net::SocketAddr>,
+
+    /// Run a test script without the terminal UI and exit with its result
+    #[arg(long)]
+    script: Option<String>,
+
+    /// Start on the kiosk display, a few indicators in large characters
+    #[arg(long)]
+    kiosk: bool,
+
+    /// Language of the UI (default: from LC_ALL, LC_MESSAGES or LANG)
+    #[arg(long, value_enum)]
+    locale: Option<Locale>,
+
     /// Verbose
     #[arg(short, long, action=clap::ArgAction::Count)]
     verbose: u8,
@@ -81,6 +300,7 @@ pub struct Cli {
 fn dm_setup() -> Result<Terminal<CrosstermBackend<Stderr>>, DMError> {
     // Initial terminal
     enable_raw_mode().map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
+    crash::terminal_taken();
 
     let mut stderr = io::stderr();
     execute!(stderr, EnterAlternateScreen, EnableMouseCapture)
@@ -93,63 +313,240 @@ fn dm_setup() -> Result<Terminal<CrosstermBackend<Stderr>>, DMError> {
     Ok(terminal)
 }
 
-fn dm_teardown(mut terminal: Terminal<CrosstermBackend<Stderr>>) -> Result<(), DMError> {
+fn dm_teardown(terminal: Terminal<CrosstermBackend<Stderr>>) -> Result<(), DMError> {
     // Restore terminal
-    disable_raw_mode().map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )
-    .map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
-
-    terminal
-        .show_cursor()
-        .map_err(|e| Report::new(DMError::IOError).attach_printable(e))
+    drop(terminal);
+    crash::restore_terminal().map_err(|e| Report::new(DMError::IOError).attach_printable(e))
 }
 
 fn run_app<B: Backend>(terminal: &mut Terminal<B>) -> Result<(), DMError> {
     jdebug!(func = "run_app", line = line!(), note = "Main loop");
-    let mut draw_now = Instant::now();
-    let mut draw_old;
-
     loop {
-        draw_old = draw_now;
-        draw_now = Instant::now();
-
-        jinfo!(
-            event = "TIME_MEASURE",
-            main_loop_time = format!("{}ms", (draw_now - draw_old).as_millis())
-        );
-
         if should_exit() {
             break;
         }
 
-        let draw_time = Instant::now();
+        let start = Instant::now();
         update()?;
-        jinfo!(
-            event = "TIME_MEASURE",
-            app_update_time = format!("{}ms", draw_time.elapsed().as_millis())
-        );
+        let update_time = start.elapsed();
+        let parse_time = mqtt_ctrl::with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.take_parse_time());
 
+        let start = Instant::now();
         terminal
             .draw(draw)
             .map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
+        let draw_time = start.elapsed();
 
-        let events_time = Instant::now();
+        let start = Instant::now();
         handle_events()?;
-        jinfo!(
-            event = "TIME_MEASURE",
-            handle_events_time = format!("{}ms", events_time.elapsed().as_millis())
-        );
+        record_frame(FrameTimes {
+            parse: parse_time,
+            update: update_time.saturating_sub(parse_time),
+            draw: draw_time,
+            events: start.elapsed(),
+        });
     }
 
     Ok(())
 }
 
+/// Initializes the global MqttCtrl with the options of the command line
+fn init_mqtt_ctrl(
+    cli: &Cli,
+    publish_options: PublishOptions,
+    syslog: Option<SyslogForwarder>,
+) -> Result<(), DMError> {
+    // No temperature alarm unless the thresholds are given
+    let thresholds_file = format!("{}/{}", device_config::config_dir(), THRESHOLDS_FILE);
+    let thresholds = if std::path::Path::new(&thresholds_file).exists() {
+        TemperatureThresholds::load(&thresholds_file)?
+    } else {
+        TemperatureThresholds::default()
+    };
+    let slow_network = cli
+        .slow_network
+        .as_deref()
+        .map(SlowNetwork::parse)
+        .transpose()?;
+    let mut memory_budgets = MemoryBudgets::default();
+    for spec in &cli.memory_budget {
+        memory_budgets.set(spec)?;
+    }
+
+    mqtt_ctrl::init_global_mqtt_ctrl(&cli.broker)?;
+    mqtt_ctrl::with_mqtt_ctrl_mut(|mqtt_ctrl| {
+        if let Some(slow_network) = slow_network {
+            jinfo!(
+                func = "init_mqtt_ctrl",
+                note = "Slow network",
+                delay = format!("{:?}", slow_network.delay),
+                jitter = format!("{:?}", slow_network.jitter)
+            );
+            mqtt_ctrl.set_slow_network(slow_network);
+        }
+        mqtt_ctrl.set_temperature_thresholds(thresholds);
+        mqtt_ctrl.set_memory_budgets(memory_budgets);
+        // The UI and the daemon mode read the state, not the values returned by update()
+        mqtt_ctrl.set_return_values(false);
+        if let Some(syslog) = syslog {
+            mqtt_ctrl.set_syslog(syslog);
+        }
+        mqtt_ctrl.set_publish_options(publish_options);
+        mqtt_ctrl.set_guard(GuardConfig {
+            max_payload: cli.max_payload,
+            rate_limit: cli.rate_limit,
+        });
+        mqtt_ctrl.set_include_secrets(cli.include_secrets);
+        mqtt_ctrl.set_read_only(cli.read_only);
+        if cli.dry_run {
+            jinfo!(
+                func = "init_mqtt_ctrl",
+                note = "Dry run",
+                review_log = &cli.review_log
+            );
+            mqtt_ctrl.set_dry_run(Some(DryRun::new(&cli.review_log)));
+        }
+        if cli.subscribe.is_empty() {
+            Ok(())
+        } else {
+            mqtt_ctrl.set_subscriptions(cli.subscribe.clone())
+        }
+    })
+}
+
+/// Initializes the global AzuriteStorage with the options of the command line
+fn init_azurite_storage(cli: &Cli) -> Result<(), DMError> {
+    init_global_azurite_storage_with_config(AzuriteConfig {
+        read_only: cli.read_only,
+        ..AzuriteConfig::new(&cli.azurite_url)
+    })
+}
+
+/// Updates the MQTT session, a malformed device message is logged instead of ending the mode
+fn update_mqtt(mqtt_ctrl: &mut MqttCtrl) -> Result<(), DMError> {
+    match mqtt_ctrl.update() {
+        Err(e) if matches!(e.current_context(), DMError::ParserError) => {
+            jerror!(
+                func = "update_mqtt()",
+                error = e.error_str().unwrap_or_default()
+            );
+            Ok(())
+        }
+        result => result.map(|_| ()),
+    }
+}
+
+/// Runs the script until it ends, printing its log to stdout
+fn run_script(mut runner: ScriptRunner) -> Result<(), DMError> {
+    let mut printed = 0;
+
+    loop {
+        mqtt_ctrl::with_mqtt_ctrl_mut(|mqtt_ctrl| {
+            update_mqtt(mqtt_ctrl)?;
+            runner.step(mqtt_ctrl, Instant::now());
+            Ok::<(), Report<DMError>>(())
+        })?;
+
+        for line in &runner.log[printed..] {
+            println!("{line}");
+        }
+        printed = runner.log.len();
+
+        match runner.outcome() {
+            Some(Ok(())) => return Ok(()),
+            Some(Err(e)) => {
+                return Err(Report::new(DMError::RuntimeError).attach_printable(e.clone()));
+            }
+            None => std::thread::sleep(Duration::from_millis(10)),
+        }
+    }
+}
+
+/// Keeps the MQTT session alive and writes the snapshots until the process is stopped
+fn run_daemon(mut daemon: Daemon) -> Result<(), DMError> {
+    loop {
+        mqtt_ctrl::with_mqtt_ctrl_mut(|mqtt_ctrl| {
+            update_mqtt(mqtt_ctrl)?;
+
+            let now = Instant::now();
+            if daemon.is_due(now) {
+                // A full disk or a removed directory is retried on the next snapshot
+                if let Err(e) = daemon.snapshot(
+                    now,
+                    chrono::Local::now(),
+                    &mqtt_ctrl.state(),
+                    mqtt_ctrl.elogs(),
+                ) {
+                    jerror!(func = "run_daemon()", error = format!("{:?}", e));
+                }
+            }
+            Ok::<(), Report<DMError>>(())
+        })?;
+
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Checks the device state until it matches or the timeout expires
+fn run_check(expectations: Expectations, timeout: Duration) -> Result<(), DMError> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let mismatches = mqtt_ctrl::with_mqtt_ctrl_mut(|mqtt_ctrl| {
+            update_mqtt(mqtt_ctrl)?;
+            Ok::<_, Report<DMError>>(expectations.check(&mqtt_ctrl.state()))
+        })?;
+
+        if mismatches.is_empty() {
+            println!("PASSED: {} expectations met", expectations.conditions.len());
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            for mismatch in &mismatches {
+                println!("FAILED {mismatch}");
+            }
+            return Err(Report::new(DMError::Timeout).attach_printable(format!(
+                "{}/{} expectations not met after {}s",
+                mismatches.len(),
+                expectations.conditions.len(),
+                timeout.as_secs()
+            )));
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Collects the device messages until `wait` expires and writes the report
+fn run_report(format: ReportFormat, output: Option<&str>, wait: Duration) -> Result<(), DMError> {
+    let deadline = Instant::now() + wait;
+
+    while Instant::now() < deadline {
+        mqtt_ctrl::with_mqtt_ctrl_mut(update_mqtt)?;
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let report = mqtt_ctrl::with_mqtt_ctrl(DeviceReport::new);
+    let path = report.save(format, output)?;
+    println!("Report saved to {path}");
+    Ok(())
+}
+
+/// Prepares the storage, printing each step to stdout
+fn run_storage_init(config: AzuriteConfig, seeds: &[String]) -> Result<(), DMError> {
+    let (mut storage, containers) = bootstrap::connect(&config)?;
+    println!("Connected to {}: {} containers", config.url, containers);
+
+    let defaults_file = format!("{}/{}", device_config::config_dir(), DEFAULTS_FILE);
+    for step in bootstrap::init_storage(&mut storage, &defaults_file, seeds)? {
+        println!("{step}");
+    }
+    Ok(())
+}
+
 fn main() -> Result<(), DMError> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
 
     let level = match cli.verbose {
         1 => LevelFilter::DEBUG,
@@ -166,15 +563,166 @@ fn main() -> Result<(), DMError> {
             .build();
     }
 
+    crash::install_panic_hook(CrashConfig {
+        log_file: cli.log.clone(),
+        report_dir: cli.crash_dir.as_ref().map(PathBuf::from),
+    });
+
+    audit::init_global_audit_log(AuditLog::new(&cli.audit_log))?;
+
+    // Checked before the terminal is taken over
+    let publish_options = PublishOptions::new(cli.qos, cli.retain)?;
+    if let Some(filter) = cli.subscribe.iter().find(|f| !valid_filter(f)) {
+        return Err(Report::new(DMError::InvalidData)
+            .attach_printable(format!("Invalid topic filter: {}", filter)));
+    }
+    let hooks = cli
+        .hook
+        .iter()
+        .map(|hook| CommandHook::parse(hook))
+        .collect::<Result<Vec<_>, DMError>>()?;
+    let syslog = cli
+        .syslog
+        .as_deref()
+        .map(|target| {
+            SyslogForwarder::new(
+                SyslogTarget::parse(target)?,
+                parse_sd_id(&cli.syslog_sd_id)?,
+            )
+        })
+        .transpose()?;
+    let grpc = cli.grpc.map(GrpcServer::bind).transpose()?;
+
+    if let Some(Command::Check { expect, timeout }) = &cli.command {
+        let expectations = Expectations::load(std::path::Path::new(expect))?;
+
+        init_mqtt_ctrl(&cli, publish_options, syslog)?;
+        return run_check(expectations, Duration::from_secs(*timeout));
+    }
+
+    if let Some(Command::Report {
+        format,
+        output,
+        wait,
+    }) = &cli.command
+    {
+        init_mqtt_ctrl(&cli, publish_options, syslog)?;
+        return run_report(*format, output.as_deref(), Duration::from_secs(*wait));
+    }
+
+    if let Some(Command::Storage {
+        action:
+            StorageCommand::Init {
+                account_name,
+                account_key,
+                seed,
+            },
+    }) = &cli.command
+    {
+        let mut config = AzuriteConfig::new(&cli.azurite_url);
+        config.read_only = cli.read_only;
+        if let Some(account_name) = account_name {
+            config.account_name = account_name.clone();
+        }
+        if let Some(account_key) = account_key {
+            config.account_key = account_key.clone();
+        }
+        return run_storage_init(config, seed);
+    }
+
+    if let Some(script) = cli.script.as_deref() {
+        let runner = ScriptRunner::new(Script::load(std::path::Path::new(script))?);
+
+        init_mqtt_ctrl(&cli, publish_options, syslog)?;
+        for hook in hooks {
+            mqtt_ctrl::with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.register_hook(Box::new(hook)));
+        }
+        // Answers the storage token requests of the device, optional for scripts
+        if let Err(e) = init_azurite_storage(&cli) {
+            jerror!(func = "main", error = format!("{:?}", e));
+        }
+
+        return run_script(runner);
+    }
+
+    if cli.daemon {
+        let daemon = Daemon::new(DaemonConfig {
+            dir: cli.snapshot_dir.clone().into(),
+            interval: Duration::from_secs(cli.snapshot_interval),
+            keep: cli.snapshot_keep,
+        })?;
+
+        init_mqtt_ctrl(&cli, publish_options, syslog)?;
+        for hook in hooks {
+            mqtt_ctrl::with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.register_hook(Box::new(hook)));
+        }
+        // Answers the storage token requests of the device when Azurite is available
+        if let Err(e) = init_azurite_storage(&cli) {
+            jerror!(func = "main", error = format!("{:?}", e));
+        }
+
+        if let Some(grpc) = grpc {
+            grpc.spawn()?;
+        }
+
+        jinfo!(
+            func = "main",
+            note = "Running as a daemon",
+            dir = &cli.snapshot_dir
+        );
+        return run_daemon(daemon);
+    }
+
+    // Stopped when main returns, after the terminal is restored
+    let _broker = if let Some(Command::Broker {
+        action:
+            BrokerCommand::Up {
+                port,
+                data_dir,
+                docker,
+            },
+    }) = &cli.command
+    {
+        let broker = LocalBroker::start(&BrokerConfig {
+            port: *port,
+            data_dir: data_dir.into(),
+            docker: *docker,
+        })?;
+        cli.broker = broker.address();
+        Some(broker)
+    } else {
+        None
+    };
+
     jdebug!(func = "main", line = line!(), note = "Starting app");
+    i18n::init_locale(cli.locale);
+    // Restores the terminal on the errors returned before dm_teardown() as well, including
+    // the ones of dm_setup() once the raw mode is enabled
+    let _terminal_guard = TerminalGuard;
     let mut terminal = dm_setup()?;
 
     // Initialize global MqttCtrl first, then global AzuriteStorage, then global App
-    mqtt_ctrl::init_global_mqtt_ctrl(&cli.broker)?;
-    init_global_azurite_storage(&cli.azurite_url)?;
+    init_mqtt_ctrl(&cli, publish_options, syslog)?;
+    for hook in hooks {
+        mqtt_ctrl::with_mqtt_ctrl_mut(|mqtt_ctrl| mqtt_ctrl.register_hook(Box::new(hook)));
+    }
+    init_azurite_storage(&cli)?;
     init_global_app(AppConfig {
         broker: &cli.broker,
+        download_dir: cli.download_dir.as_deref(),
+        dtmi_repository: cli.dtmi_repository.as_deref(),
+        inventory: cli.inventory.as_deref(),
+        fleet_dir: cli.fleet_dir.as_deref(),
+        kiosk: cli.kiosk,
+        dry_run: cli.dry_run,
+        read_only: cli.read_only,
+        slow_network: cli.slow_network.is_some(),
+        profile_frame: cli.profile_frame,
+        log_file: cli.log.as_deref(),
     })?;
+    if let Some(grpc) = grpc {
+        grpc.spawn()?;
+    }
 
     let app_result = run_app(&mut terminal);
     dm_teardown(terminal)?;
@@ -14,14 +14,40 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+pub mod battery;
+pub mod csv;
+pub mod dry_run;
+pub mod elog_stats;
 pub mod evp;
+pub mod guard;
+pub mod hooks;
+pub mod latency;
+pub mod memory;
+pub mod metrics;
+pub mod reboots;
+pub mod retained;
+pub mod slow_network;
+pub mod subscriptions;
+pub mod syslog;
+pub mod temperature;
+pub mod traffic;
 
 use crate::ai_model::AiModel;
+use crate::audit::{self, AuditResult};
+use crate::azurite::sas::{IssuedSas, SasInfo};
 use azure_storage::prelude::BlobSasPermissions;
-use std::sync::{Mutex, OnceLock};
+use std::cell::RefCell;
+use std::sync::{Mutex, OnceLock, TryLockError};
 
 static GLOBAL_MQTT_CTRL: OnceLock<Mutex<MqttCtrl>> = OnceLock::new();
 
+thread_local! {
+    /// MqttCtrl used instead of the global one by the current thread
+    static LOCAL_MQTT_CTRL: RefCell<Option<MqttCtrl>> = const { RefCell::new(None) };
+}
+
+/// Connects to the broker, "host" or "host:port" (default port 1883), and makes the
+/// MqttCtrl the global one
 pub fn init_global_mqtt_ctrl(broker: &str) -> Result<(), DMError> {
     let (broker_url, broker_port_str) = broker.split_once(':').unwrap_or((broker, "1883"));
     let broker_port = broker_port_str.parse().map_err(|_| {
@@ -36,10 +62,30 @@ pub fn init_global_mqtt_ctrl(broker: &str) -> Result<(), DMError> {
     })
 }
 
+/// Runs `f` with `mqtt_ctrl` taking the place of the global MqttCtrl on the current thread
+/// only, e.g. to render the UI with fixture device states in tests. The MqttCtrl is given back
+/// with the result of `f`.
+pub fn with_local_mqtt_ctrl<F, R>(mqtt_ctrl: MqttCtrl, f: F) -> (MqttCtrl, R)
+where
+    F: FnOnce() -> R,
+{
+    let previous = LOCAL_MQTT_CTRL.replace(Some(mqtt_ctrl));
+    let result = f();
+    let mqtt_ctrl = LOCAL_MQTT_CTRL
+        .replace(previous)
+        .expect("Local MqttCtrl removed");
+    (mqtt_ctrl, result)
+}
+
+/// Runs `f` on the global MqttCtrl, panics if it is not initialized
 pub fn with_mqtt_ctrl<F, R>(f: F) -> R
 where
     F: FnOnce(&MqttCtrl) -> R,
 {
+    if LOCAL_MQTT_CTRL.with_borrow(Option::is_some) {
+        return LOCAL_MQTT_CTRL.with_borrow(|local| f(local.as_ref().unwrap()));
+    }
+
     let mqtt_ctrl = GLOBAL_MQTT_CTRL
         .get()
         .expect("Global MqttCtrl not initialized")
@@ -48,10 +94,15 @@ where
     f(&mqtt_ctrl)
 }
 
+/// Runs `f` on the global MqttCtrl for changes, panics if it is not initialized
 pub fn with_mqtt_ctrl_mut<F, R>(f: F) -> R
 where
     F: FnOnce(&mut MqttCtrl) -> R,
 {
+    if LOCAL_MQTT_CTRL.with_borrow(Option::is_some) {
+        return LOCAL_MQTT_CTRL.with_borrow_mut(|local| f(local.as_mut().unwrap()));
+    }
+
     let mut mqtt_ctrl = GLOBAL_MQTT_CTRL
         .get()
         .expect("Global MqttCtrl not initialized")
@@ -60,6 +111,20 @@ where
     f(&mut mqtt_ctrl)
 }
 
+/// Runs `f` on the global MqttCtrl unless it is not initialized or locked, e.g. by the code
+/// which panicked. Neither blocks nor panics, for the panic hook.
+pub fn try_with_global_mqtt_ctrl<F, R>(f: F) -> Option<R>
+where
+    F: FnOnce(&MqttCtrl) -> R,
+{
+    match GLOBAL_MQTT_CTRL.get()?.try_lock() {
+        Ok(mqtt_ctrl) => Some(f(&mqtt_ctrl)),
+        // Still readable after another thread panicked with the lock
+        Err(TryLockError::Poisoned(e)) => Some(f(&e.into_inner())),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
 // Temporary function to get a reference to the global MqttCtrl for UI compatibility
 // This is not ideal but allows us to migrate gradually
 #[allow(dead_code)]
@@ -69,20 +134,31 @@ pub fn get_global_mqtt_ctrl_ref() -> &'static std::sync::Mutex<MqttCtrl> {
         .expect("Global MqttCtrl not initialized")
 }
 
+use battery::BatteryTracker;
+use dry_run::DryRun;
+use elog_stats::ElogStats;
+use latency::LatencyTracker;
+use memory::{MemoryBudgets, MemoryMonitor, MemoryUsage, Subsystem};
+use metrics::Metrics;
+use reboots::RebootTracker;
+use slow_network::SlowNetwork;
+use temperature::{TemperatureAlarms, TemperatureNotice, TemperatureThresholds};
+use traffic::{TopicFamily, Traffic};
 #[allow(unused)]
 use {
-    super::app::{App, ConfigKey, DirectCommand, MainWindowFocus},
-    super::error::DMError,
+    super::device_config::{ConfigKey, DirectCommand, MainWindowFocus},
+    super::error::{DMError, DMErrorExt},
     super::ota::FirmwareProperty,
-    crate::{app::with_global_app, azurite::with_azurite_storage},
+    crate::azurite::with_azurite_storage,
+    crate::versioned,
     base64::{
         Engine as _, alphabet,
         engine::{self, general_purpose},
     },
-    chrono::{DateTime, Local},
+    chrono::{DateTime, FixedOffset, Local, Offset, Utc},
     core::result::Result as CoreResult,
     error_stack::{Report, Result},
-    evp::EvpMsg,
+    evp::clock::{self, ClockDrift},
     evp::configure::*,
     evp::device_info::{
         DeviceCapabilities, DeviceInfo, DeviceReserved, DeviceStates, NetworkSettings,
@@ -90,61 +166,143 @@ use {
     },
     evp::edge_app::EdgeApp,
     evp::edge_app::EdgeAppInfo,
-    evp::elog::Elog,
+    evp::elog::{Elog, ElogArchive, ElogEntry},
     evp::evp_state::{AgentDeviceConfig, AgentSystemInfo, DeploymentStatus, UUID},
+    evp::req_res::{ReqResStatus, ReqResTracker, SettingsKind},
     evp::rpc::RpcResInfo,
+    evp::rpc_tracker::{RPC_TIMEOUT, RpcTracker},
+    evp::topic::DeviceRoute,
+    evp::{EvpMsg, RawMsg},
+    guard::{GuardConfig, MessageGuard, Verdict},
+    hooks::{HookOutput, HookRegistry, MessageHook},
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
     json::{JsonValue, object::Object},
     rand::Rng,
     regex::Regex,
+    retained::RetainedStore,
     rumqttc::{Client, Connection, Event, MqttOptions, QoS},
     std::{
-        collections::HashMap,
+        collections::{HashMap, VecDeque},
         sync::Arc,
         sync::atomic::AtomicBool,
         sync::mpsc,
         time::{self, Duration, Instant},
     },
+    subscriptions::{Subscriptions, valid_filter},
+    syslog::SyslogForwarder,
 };
 
+/// Signal strengths kept for the diagnostics sparkline
+const RSSI_HISTORY_SIZE: usize = 120;
+
+/// Configurations kept for the device report
+const SENT_CONFIGS_SIZE: usize = 32;
+
+/// Storage token responses kept for the Token Provider screen
+const ISSUED_SAS_SIZE: usize = 20;
+
+/// Incoming packets up to this size reach the guard
+const MAX_INCOMING_PACKET: usize = 16 * 1024 * 1024;
+
+/// Event of the connection read by mqtt_recv_thread
+type RecvEvent = CoreResult<CoreResult<Event, rumqttc::ConnectionError>, rumqttc::RecvError>;
+
+/// MQTT session with the device: sends the configurations and RPCs, and keeps the state
+/// parsed from the EVP messages of the device
 pub struct MqttCtrl {
     client: Client,
     #[allow(dead_code)]
     thread: Option<std::thread::JoinHandle<()>>,
-    rx: mpsc::Receiver<CoreResult<CoreResult<Event, rumqttc::ConnectionError>, rumqttc::RecvError>>,
+    /// Events of the connection with the time they were received
+    rx: mpsc::Receiver<(Instant, RecvEvent)>,
     #[allow(dead_code)]
     should_exit: Arc<AtomicBool>,
-    subscribed: bool,
+    /// Topic filters subscribed in update()
+    subscriptions: Subscriptions,
+    /// Topics the configurations and RPCs are published on, following the device reports
+    route: DeviceRoute,
     device_connected: bool,
     last_connected: DateTime<Local>,
     device_info: Option<DeviceInfo>,
     device_states: Option<DeviceStates>,
+    /// Last estimate of the device clock offset, from its report timestamps
+    clock_drift: Option<ClockDrift>,
+    /// Offset of the last time reported by the device
+    device_offset: Option<FixedOffset>,
     device_capabilities: Option<DeviceCapabilities>,
     system_settings: Option<SystemSettings>,
     network_settings: Option<Box<NetworkSettings>>,
     wireless_settings: Option<WirelessSettings>,
+    /// Signal strengths (dBm) reported by the device, oldest first
+    rssi_history: VecDeque<i32>,
+    metrics: Metrics,
+    /// Messages which do not update the device state, displayed in the Module screen
+    traffic: Traffic,
     device_reserved: Option<DeviceReserved>,
     agent_system_info: Option<Box<AgentSystemInfo>>,
     deployment_status: Option<DeploymentStatus>,
+    /// Failure shown in the main screen until the deployment recovers
+    deployment_alert: Option<String>,
     agent_device_config: Option<AgentDeviceConfig>,
-    edge_app: Option<EdgeAppInfo>,
+    /// EdgeApp states reported by the device, keyed by instance ID
+    edge_apps: HashMap<String, EdgeAppInfo>,
     direct_command: Option<DirectCommand>,
     direct_command_start: Option<Instant>,
     direct_command_end: Option<Instant>,
     direct_command_request: Option<Result<String, DMError>>,
     direct_command_result: Option<Result<RpcResInfo, DMError>>,
     current_rpc_id: u32,
-    elogs: Vec<Elog>,
+    /// Every RPC sent, the direct command only follows the latest one
+    rpcs: RpcTracker,
+    /// Default options of the configuration publishes
+    publish_options: PublishOptions,
+    retained: RetainedStore,
+    /// Payload size and rate limits of the incoming messages
+    guard: MessageGuard,
+    /// Passwords are kept in the state snapshots
+    include_secrets: bool,
+    /// Configurations and RPCs are reviewed instead of published, if enabled
+    dry_run: Option<DryRun>,
+    /// Nothing is published on behalf of the user
+    read_only: bool,
+    /// Received elogs, identical consecutive ones coalesced
+    elogs: Vec<ElogEntry>,
+    elog_stats: ElogStats,
     firmware: FirmwareProperty,
     ai_model: AiModel,
+    /// (container, blob name) pairs the device received an upload SAS URL for
+    issued_uploads: Vec<(String, String)>,
+    /// Parameters of the upload SAS URLs given to the device, oldest first
+    issued_sas: VecDeque<IssuedSas>,
+    /// req_ids of the settings configurations sent
+    req_res: ReqResTracker,
+    /// Configurations sent to the device with their time, oldest first
+    sent_configs: VecDeque<(DateTime<Local>, String)>,
+    /// Settings still reporting the response of an older request
+    req_res_alert: Option<String>,
+    hooks: HookRegistry,
+    /// Forwarder of the elogs received, if enabled
+    syslog: Option<SyslogForwarder>,
+    connect_requests: u32,
+    reboots: RebootTracker,
+    battery: BatteryTracker,
+    /// Connect request and RPC round-trips
+    latency: LatencyTracker,
+    /// Budgets of the stores kept for the whole session
+    memory: MemoryMonitor,
+    /// update() returns the raw messages and the hook values
+    return_values: bool,
+    /// Spent handling the messages received since the last take_parse_time()
+    parse_time: Duration,
+    temperature_alarms: TemperatureAlarms,
+    /// Temperature alarms raised or cleared since the App last took them
+    temperature_notices: Vec<TemperatureNotice>,
     pub info: Option<String>,
 }
 
 fn mqtt_recv_thread(
     mut conn: Connection,
-    sender: mpsc::Sender<
-        CoreResult<CoreResult<Event, rumqttc::ConnectionError>, rumqttc::RecvError>,
-    >,
+    sender: mpsc::Sender<(Instant, RecvEvent)>,
     should_exit: Arc<AtomicBool>,
 ) -> Result<(), DMError> {
     let mut network_options = rumqttc::NetworkOptions::default();
@@ -152,7 +310,8 @@ fn mqtt_recv_thread(
     conn.eventloop.set_network_options(network_options);
 
     while !should_exit.load(std::sync::atomic::Ordering::SeqCst) {
-        if let Err(e) = sender.send(conn.recv()) {
+        let event = conn.recv();
+        if let Err(e) = sender.send((Instant::now(), event)) {
             jerror!(
                 func = "mqtt_recv_thread",
                 line = line!(),
@@ -164,6 +323,7 @@ fn mqtt_recv_thread(
 }
 
 impl MqttCtrl {
+    /// Starts the session with the broker, the topics are subscribed by `update()`
     pub fn new(url: &str, port: u16) -> Result<Self, DMError> {
         let mut rng = rand::rng();
         let id = format!(
@@ -176,7 +336,8 @@ impl MqttCtrl {
 
         let mut mqtt_options = MqttOptions::new(id, url, port);
         mqtt_options.set_keep_alive(Duration::from_secs(60));
-        mqtt_options.set_max_packet_size(262144, 262144);
+        // Large payloads are rejected by the guard instead of closing the connection
+        mqtt_options.set_max_packet_size(MAX_INCOMING_PACKET, 262144);
 
         jdebug!(
             func = "MqttCtrl::new()",
@@ -199,16 +360,6 @@ impl MqttCtrl {
             });
         });
 
-        let mut subscribed = false;
-        if client.subscribe("#", QoS::AtLeastOnce).is_ok() {
-            subscribed = true;
-            jdebug!(
-                func = "MqttCtrl::new()",
-                line = line!(),
-                note = "All topic subscribed"
-            );
-        }
-
         let current_rpc_id = rng.random_range(10000..99999);
 
         Ok(Self {
@@ -216,28 +367,59 @@ impl MqttCtrl {
             thread: Some(thread),
             rx,
             should_exit,
-            subscribed,
+            subscriptions: Subscriptions::default(),
+            route: DeviceRoute::default(),
             device_connected: false,
             last_connected: Local::now(),
             device_info: None,
             device_states: None,
+            clock_drift: None,
+            device_offset: None,
             device_capabilities: None,
             device_reserved: None,
             system_settings: None,
             network_settings: None,
             wireless_settings: None,
+            rssi_history: VecDeque::new(),
+            metrics: Metrics::default(),
+            traffic: Traffic::default(),
             agent_system_info: None,
             elogs: Vec::new(),
+            elog_stats: ElogStats::default(),
             deployment_status: None,
+            deployment_alert: None,
             agent_device_config: None,
-            edge_app: None,
+            edge_apps: HashMap::new(),
             direct_command: None,
             direct_command_start: None,
             direct_command_end: None,
             direct_command_request: None,
             direct_command_result: None,
             current_rpc_id,
+            rpcs: RpcTracker::default(),
+            publish_options: PublishOptions::default(),
+            retained: RetainedStore::default(),
+            guard: MessageGuard::default(),
+            include_secrets: false,
+            dry_run: None,
+            read_only: false,
             info: None,
+            issued_uploads: Vec::new(),
+            issued_sas: VecDeque::new(),
+            req_res: ReqResTracker::default(),
+            sent_configs: VecDeque::new(),
+            req_res_alert: None,
+            hooks: HookRegistry::default(),
+            syslog: None,
+            connect_requests: 0,
+            reboots: RebootTracker::default(),
+            battery: BatteryTracker::default(),
+            latency: LatencyTracker::default(),
+            memory: MemoryMonitor::default(),
+            return_values: true,
+            parse_time: Duration::ZERO,
+            temperature_alarms: TemperatureAlarms::default(),
+            temperature_notices: vec![],
             firmware: FirmwareProperty::new(),
             ai_model: AiModel::new(),
         })
@@ -254,64 +436,258 @@ impl MqttCtrl {
         self.last_connected = Local::now();
     }
 
+    /// Generates the configuration of the settings of the focus from the config keys the
+    /// user edited, the user configuration file is sent as it is.
     pub fn parse_configure(
         &self,
         config_keys: Option<&Vec<String>>,
         focus: MainWindowFocus,
     ) -> Result<String, DMError> {
-        if let Some(config_keys) = config_keys {
-            // Agent State
-            if focus == MainWindowFocus::AgentState {
-                let json = parse_evp_device_config(self.agent_device_config(), config_keys)?;
-                if !json.is_empty() {
-                    return Ok(json);
-                }
-            }
+        let Some(config_keys) = config_keys else {
+            return parse_user_config(focus);
+        };
 
-            // SystemSettings
-            if focus == MainWindowFocus::SystemSettings {
-                let json = parse_system_setting(config_keys)?;
-                if !json.is_empty() {
-                    return Ok(json);
-                }
-            }
+        let json = self.parse_config_keys(config_keys, focus)?;
+        if json.is_empty() {
+            return Err(Report::new(DMError::InvalidData)
+                .attach_printable("No value has been edited, nothing to send"));
+        }
 
-            // NetworkSettings
-            if focus == MainWindowFocus::NetworkSettings {
-                let json = parse_network_settings(config_keys)?;
-                if !json.is_empty() {
-                    return Ok(json);
-                }
-            }
+        Ok(json)
+    }
 
-            // WirelessSetting
-            if focus == MainWindowFocus::WirelessSettings {
-                let json = parse_wireless_settings(config_keys)?;
-                if !json.is_empty() {
-                    return Ok(json);
-                }
+    fn parse_config_keys(
+        &self,
+        config_keys: &[String],
+        focus: MainWindowFocus,
+    ) -> Result<String, DMError> {
+        match focus {
+            MainWindowFocus::AgentState => {
+                parse_evp_device_config(self.agent_device_config(), config_keys)
             }
+            MainWindowFocus::SystemSettings => parse_system_setting(config_keys),
+            MainWindowFocus::NetworkSettings => parse_network_settings(config_keys),
+            MainWindowFocus::WirelessSettings => parse_wireless_settings(config_keys),
+            _ => Ok(String::new()),
+        }
+    }
 
-            Ok(String::new())
-        } else {
-            parse_user_config(focus)
+    pub fn route(&self) -> &DeviceRoute {
+        &self.route
+    }
+
+    pub fn publish_options(&self) -> PublishOptions {
+        self.publish_options
+    }
+
+    pub fn set_publish_options(&mut self, options: PublishOptions) {
+        self.publish_options = options;
+    }
+
+    pub fn guard(&self) -> &MessageGuard {
+        &self.guard
+    }
+
+    pub fn set_guard(&mut self, config: GuardConfig) {
+        self.guard = MessageGuard::new(config);
+    }
+
+    pub fn include_secrets(&self) -> bool {
+        self.include_secrets
+    }
+
+    pub fn set_include_secrets(&mut self, include_secrets: bool) {
+        self.include_secrets = include_secrets;
+    }
+
+    pub fn dry_run(&self) -> Option<&DryRun> {
+        self.dry_run.as_ref()
+    }
+
+    /// Delays the events of the broker from now on, for the debug of the UI
+    pub fn set_slow_network(&mut self, slow_network: SlowNetwork) {
+        let (tx, rx) = mpsc::channel();
+        let upstream = std::mem::replace(&mut self.rx, rx);
+        slow_network.spawn_relay(upstream, tx);
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.is_some()
+    }
+
+    pub fn set_dry_run(&mut self, dry_run: Option<DryRun>) {
+        self.dry_run = dry_run;
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn set_temperature_thresholds(&mut self, thresholds: TemperatureThresholds) {
+        self.temperature_alarms = TemperatureAlarms::new(thresholds);
+    }
+
+    /// Publishes a message sent on behalf of the user and records it in the audit log. In
+    /// the dry-run mode, it is written to the review log and the Module screen instead, and
+    /// in the read-only mode it is refused.
+    fn publish_or_review(
+        &mut self,
+        action: &str,
+        topic: &str,
+        qos: QoS,
+        retain: bool,
+        payload: &str,
+    ) -> Result<(), DMError> {
+        let result = self.publish_unaudited(topic, qos, retain, payload);
+        let audit_result = match &result {
+            Ok(()) if self.is_dry_run() => AuditResult::DryRun,
+            result => AuditResult::of(result),
+        };
+        audit::record(action, topic, payload.as_bytes(), audit_result);
+        result
+    }
+
+    /// publish_or_review() without the audit log
+    fn publish_unaudited(
+        &mut self,
+        topic: &str,
+        qos: QoS,
+        retain: bool,
+        payload: &str,
+    ) -> Result<(), DMError> {
+        if self.read_only {
+            return Err(Report::new(DMError::ReadOnly)
+                .attach_printable(format!("Read-only mode, nothing is sent to {}", topic)));
         }
+
+        if let Some(dry_run) = &mut self.dry_run {
+            let qos = qos as u8;
+            jinfo!(
+                event = "DRY_RUN",
+                topic = topic,
+                qos = qos,
+                retain = retain,
+                payload = payload
+            );
+            dry_run.review(Local::now(), topic, qos, retain, payload)?;
+            self.traffic
+                .record(TopicFamily::DryRun, topic, payload, Local::now());
+            return Ok(());
+        }
+
+        self.client
+            .publish(topic, qos, retain, payload)
+            .map_err(|e| Report::new(DMError::IOError).attach_printable(e.to_string()))
+    }
+
+    pub fn retained(&self) -> &RetainedStore {
+        &self.retained
+    }
+
+    /// Subscribes again so that the broker sends the retained messages it holds
+    pub fn scan_retained(&mut self) {
+        self.retained.start_scan(Instant::now());
+        self.subscriptions.reset();
+    }
+
+    pub fn subscriptions(&self) -> &Subscriptions {
+        &self.subscriptions
+    }
+
+    /// Replaces the subscriptions before connecting, e.g. from the command line
+    pub fn set_subscriptions(&mut self, filters: Vec<String>) -> Result<(), DMError> {
+        if let Some(filter) = filters.iter().find(|f| !valid_filter(f)) {
+            return Err(Report::new(DMError::InvalidData)
+                .attach_printable(format!("Invalid topic filter: {}", filter)));
+        }
+
+        self.subscriptions = Subscriptions::new(filters);
+        Ok(())
+    }
+
+    /// Adds a subscription, sent to the broker in the next update
+    pub fn subscribe(&mut self, filter: &str) -> Result<(), DMError> {
+        if !valid_filter(filter) {
+            return Err(Report::new(DMError::InvalidData)
+                .attach_printable(format!("Invalid topic filter: {}", filter)));
+        }
+
+        if !self.subscriptions.add(filter) {
+            return Err(Report::new(DMError::InvalidData)
+                .attach_printable(format!("Already subscribed: {}", filter)));
+        }
+        Ok(())
+    }
+
+    pub fn unsubscribe(&mut self, filter: &str) -> Result<(), DMError> {
+        if self.subscriptions.remove(filter) {
+            self.client
+                .unsubscribe(filter)
+                .map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
+        }
+        Ok(())
+    }
+
+    /// Removes the retained message of the topic from the broker
+    pub fn clear_retained(&mut self, topic: &str) -> Result<(), DMError> {
+        self.publish_or_review("clear retained", topic, QoS::AtLeastOnce, true, "")?;
+        if self.is_dry_run() {
+            return Ok(());
+        }
+        self.retained.remove(topic);
+        Ok(())
     }
 
     pub fn send_configure(&mut self, config: &str) -> Result<(), DMError> {
-        let topic = "v1/devices/me/attributes";
+        self.send_configure_with(config, self.publish_options)
+    }
+
+    /// Sends the configuration with other options than the default ones
+    pub fn send_configure_with(
+        &mut self,
+        config: &str,
+        options: PublishOptions,
+    ) -> Result<(), DMError> {
+        let (topic, payload) = self.route.attributes(config)?;
         jdebug!(
             func = "mqtt_ctrl::send_configure",
             line = line!(),
             topic = topic,
-            config = config
+            config = payload,
+            options = options.to_string()
         );
 
-        // If set retain to true
-        // MQTT broker will cache this setting
-        self.client
-            .publish(topic, QoS::AtLeastOnce, false, config)
-            .map_err(|_| Report::new(DMError::IOError))
+        // If retain is set, MQTT broker will cache this setting and send it again
+        // every time the device subscribes
+        self.publish_or_review("configure", &topic, options.qos, options.retain, &payload)?;
+        // Nothing to wait for from the device
+        if self.is_dry_run() {
+            return Ok(());
+        }
+
+        self.req_res.record(config, Instant::now());
+        if self.sent_configs.len() == SENT_CONFIGS_SIZE {
+            self.sent_configs.pop_front();
+        }
+        self.sent_configs
+            .push_back((Local::now(), config.to_owned()));
+        Ok(())
+    }
+
+    /// Publishes a message as it is, e.g. from the publish console
+    pub fn publish(&mut self, topic: &str, payload: &str) -> Result<(), DMError> {
+        jdebug!(
+            func = "mqtt_ctrl::publish",
+            line = line!(),
+            topic = topic,
+            payload = payload
+        );
+
+        self.publish_or_review("publish", topic, QoS::AtLeastOnce, false, payload)
     }
 
     pub fn new_rpc_id(&mut self) -> u32 {
@@ -321,7 +697,6 @@ impl MqttCtrl {
 
     pub fn send_rpc_direct_get_image(&mut self, config_keys: &[String]) -> Result<String, DMError> {
         let id = self.new_rpc_id();
-        let topic = format!("v1/devices/me/rpc/request/{id}");
         let params = json::object! {
             "sensor_name": config_keys
                 .get(ConfigKey::DirectGetImageSensorName as usize)
@@ -343,21 +718,27 @@ impl MqttCtrl {
         let mut root = Object::new();
         root.insert("params", payload);
         let result = root.dump();
-
-        self.direct_command_start = Some(Instant::now());
-        self.client
-            .publish(topic, QoS::AtLeastOnce, false, result.clone())
-            .map_err(|_| {
-                Report::new(DMError::IOError).attach_printable("Failed to send reboot command")
-            })?;
-
+        let (topic, payload) = self.route.rpc_request(id, &result)?;
+
+        self.publish_or_review(
+            "rpc direct_get_image",
+            &topic,
+            QoS::AtLeastOnce,
+            false,
+            &payload,
+        )
+        .map_err(|e| e.attach_printable("Failed to send direct_get_image command"))?;
+
+        if !self.is_dry_run() {
+            self.direct_command_start = Some(Instant::now());
+            self.rpcs.start(id, "direct_get_image", Instant::now());
+        }
         self.direct_command_request = Some(Ok(result.clone()));
         Ok(result)
     }
 
     pub fn send_rpc_reboot(&mut self) -> Result<String, DMError> {
         let id = self.new_rpc_id();
-        let topic = format!("v1/devices/me/rpc/request/{id}");
         let params = Object::new();
         let payload = json::object! {
             "direct-command-request": {
@@ -370,26 +751,26 @@ impl MqttCtrl {
 
         let mut root = Object::new();
         root.insert("params", payload);
+        let (topic, payload) = self.route.rpc_request(id, &root.dump())?;
 
         jdebug!(
             func = "mqtt_ctrl::send_rpc_reboot",
             line = line!(),
             topic = topic,
-            payload = root.dump(),
+            payload = payload,
         );
 
-        self.direct_command_start = Some(Instant::now());
-        self.client
-            .publish(topic, QoS::AtLeastOnce, false, root.dump())
-            .map_err(|_| {
-                Report::new(DMError::IOError).attach_printable("Failed to send reboot command")
-            })?;
+        self.publish_or_review("rpc reboot", &topic, QoS::AtLeastOnce, false, &payload)
+            .map_err(|e| e.attach_printable("Failed to send reboot command"))?;
+        if !self.is_dry_run() {
+            self.direct_command_start = Some(Instant::now());
+            self.rpcs.start(id, "reboot", Instant::now());
+        }
         Ok(root.dump())
     }
 
     pub fn send_rpc_factory_reset(&mut self) -> Result<String, DMError> {
         let id = self.new_rpc_id();
-        let topic = format!("v1/devices/me/rpc/request/{id}");
         let params = Object::new();
         let payload = json::object! {
             "direct-command-request": {
@@ -402,24 +783,81 @@ impl MqttCtrl {
 
         let mut root = Object::new();
         root.insert("params", payload);
+        let (topic, payload) = self.route.rpc_request(id, &root.dump())?;
 
         jdebug!(
             func = "mqtt_ctrl::send_rpc_factory_reset",
             line = line!(),
             topic = topic,
-            payload = root.dump(),
+            payload = payload,
         );
 
-        self.direct_command_start = Some(Instant::now());
-        self.client
-            .publish(topic, QoS::AtLeastOnce, false, root.dump())
-            .map_err(|_| {
-                Report::new(DMError::IOError)
-                    .attach_printable("Failed to send factory_reset command")
-            })?;
+        self.publish_or_review(
+            "rpc factory_reset",
+            &topic,
+            QoS::AtLeastOnce,
+            false,
+            &payload,
+        )
+        .map_err(|e| e.attach_printable("Failed to send factory_reset command"))?;
+        if !self.is_dry_run() {
+            self.direct_command_start = Some(Instant::now());
+            self.rpcs.start(id, "factory_reset", Instant::now());
+        }
         Ok(root.dump())
     }
 
+    /// Sends the RPC of the custom fields, the request or its error is kept as the
+    /// direct command request
+    pub fn send_rpc_custom(&mut self, config_keys: &[String]) {
+        let value = |key: ConfigKey| {
+            config_keys
+                .get(key as usize)
+                .map_or("", |s| s.trim())
+                .to_owned()
+        };
+
+        let result = self.send_rpc(
+            &value(ConfigKey::CustomRpcMethod),
+            &value(ConfigKey::CustomRpcInstance),
+            &value(ConfigKey::CustomRpcParams),
+        );
+        self.direct_command_request = Some(result);
+    }
+
+    /// Sends an RPC of any method and returns its request
+    pub fn send_rpc(
+        &mut self,
+        method: &str,
+        instance: &str,
+        params: &str,
+    ) -> Result<String, DMError> {
+        let id = self.new_rpc_id();
+        let request = custom_rpc_request(id, method, instance, params)?;
+        let (topic, payload) = self.route.rpc_request(id, &request)?;
+
+        jdebug!(
+            func = "mqtt_ctrl::send_rpc",
+            line = line!(),
+            topic = topic,
+            payload = payload,
+        );
+
+        self.publish_or_review(
+            &format!("rpc {method}"),
+            &topic,
+            QoS::AtLeastOnce,
+            false,
+            &payload,
+        )
+        .map_err(|e| e.attach_printable("Failed to send RPC"))?;
+        if !self.is_dry_run() {
+            self.direct_command_start = Some(Instant::now());
+            self.rpcs.start(id, method, Instant::now());
+        }
+        Ok(request)
+    }
+
     pub fn direct_command_exec_time(&self) -> Option<u32> {
         if let (Some(start), Some(end)) = (self.direct_command_start, self.direct_command_end) {
             Some(end.duration_since(start).as_millis() as u32)
@@ -443,7 +881,21 @@ impl MqttCtrl {
             payload = payload
         );
 
+        let output = self.hooks.dispatch(topic, payload);
+        self.apply_hook_output(output, &mut result);
+
+        if let Some(route) = DeviceRoute::of_report(topic, payload)
+            && route != self.route
+        {
+            jinfo!(event = "ROUTE", route = format!("{:?}", route));
+            self.route = route;
+        }
+
         for msg in EvpMsg::parse(topic, payload)? {
+            if msg.is_state_report() {
+                self.latency.state_reported(Instant::now());
+            }
+
             match msg {
                 EvpMsg::ConnectMsg((who, req_id)) => {
                     self.client
@@ -454,11 +906,14 @@ impl MqttCtrl {
                             payload,
                         )
                         .map_err(|_| Report::new(DMError::IOError))?;
+                    self.latency.connect_answered(Instant::now());
 
                     result.insert(
                         "Connection request".to_owned(),
                         format!("who={who} req_id={req_id}"),
                     );
+                    self.connect_requests += 1;
+                    self.reboots.record(Local::now());
                     self.update_timestamp();
                     self.info = Some("Device rebooted".to_owned());
                 }
@@ -469,11 +924,39 @@ impl MqttCtrl {
                     );
                 }
                 EvpMsg::DeviceInfoMsg(device_info) => {
+                    for notice in self.temperature_alarms.update(&device_info) {
+                        jinfo!(
+                            func = "mqtt_ctrl::on_message()",
+                            temperature = notice.message.as_str()
+                        );
+                        self.info = Some(notice.message.clone());
+                        self.temperature_notices.push(notice);
+                    }
                     self.device_info = Some(device_info);
+                    self.metrics.record(
+                        self.device_info.as_ref(),
+                        self.device_states.as_ref(),
+                        Local::now(),
+                    );
                     self.update_timestamp();
                 }
                 EvpMsg::DeviceStatesMsg(device_states) => {
+                    if let Some(drift) =
+                        ClockDrift::from_bootup_time(device_states.last_bootup_time(), Local::now())
+                    {
+                        self.clock_drift = Some(drift);
+                    }
+                    if let Some(time) = clock::parse_device_time(device_states.last_bootup_time()) {
+                        self.device_offset = Some(*time.offset());
+                    }
+                    self.battery
+                        .record(device_states.power_state(), Local::now());
                     self.device_states = Some(device_states);
+                    self.metrics.record(
+                        self.device_info.as_ref(),
+                        self.device_states.as_ref(),
+                        Local::now(),
+                    );
                     self.update_timestamp();
                 }
                 EvpMsg::DeviceCapabilities(device_capabilities) => {
@@ -493,6 +976,12 @@ impl MqttCtrl {
                     self.update_timestamp();
                 }
                 EvpMsg::WirelessSettings(wireless_settings) => {
+                    if let Some(rssi) = wireless_settings.rssi() {
+                        if self.rssi_history.len() == RSSI_HISTORY_SIZE {
+                            self.rssi_history.pop_front();
+                        }
+                        self.rssi_history.push_back(rssi);
+                    }
                     self.wireless_settings = Some(wireless_settings);
                     self.update_timestamp();
                 }
@@ -509,6 +998,7 @@ impl MqttCtrl {
                     self.update_timestamp();
                 }
                 EvpMsg::DeploymentStatus(deployment_status) => {
+                    self.update_deployment_alert(&deployment_status);
                     self.deployment_status = Some(deployment_status);
                     self.update_timestamp();
                 }
@@ -522,22 +1012,41 @@ impl MqttCtrl {
                         line = line!(),
                         log = ? elog
                     );
-                    self.elogs.push(elog);
-                    if self.elogs.len() > 100 {
-                        self.elogs.remove(0);
+                    if let Some(drift) = ClockDrift::from_report(elog.timestamp(), Local::now()) {
+                        self.clock_drift = Some(drift);
+                    }
+                    if let Some(time) = clock::parse_device_time(elog.timestamp()) {
+                        self.device_offset = Some(*time.offset());
+                    }
+                    if let Some(syslog) = &self.syslog {
+                        syslog.forward(&elog);
+                    }
+                    self.elog_stats.record(&elog, Local::now());
+                    if !self
+                        .elogs
+                        .last_mut()
+                        .is_some_and(|last| last.coalesce(&elog))
+                    {
+                        self.elogs.push(ElogEntry::new(elog));
+                        if self.elogs.len() > 100 {
+                            self.elogs.remove(0);
+                        }
                     }
                     self.update_timestamp();
                 }
                 EvpMsg::EdgeApp(edge_app_info) => {
-                    self.edge_app = Some(*edge_app_info);
+                    self.edge_apps
+                        .insert(edge_app_info.id().to_owned(), *edge_app_info);
                     self.update_timestamp();
                 }
                 EvpMsg::ClientMsg(v) => {
                     self.update_timestamp();
-                    result.extend(v);
+                    self.record_traffic(TopicFamily::Client, &v);
+                    self.return_value(&mut result, v);
                 }
                 EvpMsg::ServerMsg(v) => {
-                    result.extend(v);
+                    self.record_traffic(TopicFamily::Server, &v);
+                    self.return_value(&mut result, v);
                 }
                 EvpMsg::RpcRequest(v) => {
                     let (req_id, cmd) = v;
@@ -548,6 +1057,7 @@ impl MqttCtrl {
                     );
 
                     if let DirectCommand::StorageTokenRequest(key, filename) = cmd {
+                        let mut issued_upload = None;
                         match with_azurite_storage(|azurite| -> Result<(), DMError> {
                             let topic = format!("v1/devices/me/rpc/response/{req_id}");
                             let mut payload = json::object! {
@@ -560,11 +1070,12 @@ impl MqttCtrl {
                             // Validate provided key is a UUID
                             let uuid = match UUID::from(&key) {
                                 Ok(u) => u,
-                                Err(_) => {
+                                Err(e) => {
                                     jerror!(
                                         func = "mqtt_ctrl::on_message()",
                                         line = line!(),
-                                        event = "Invalid UUID in StorageTokenRequest"
+                                        event = "Invalid UUID in StorageTokenRequest",
+                                        error = e.error_str().unwrap_or_default()
                                     );
                                     // Publish error response
                                     self.client
@@ -625,6 +1136,11 @@ impl MqttCtrl {
                                             }
                                         }
                                     };
+                                    issued_upload = Some((
+                                        token.container.clone(),
+                                        filename.clone(),
+                                        SasInfo::parse(&sas_url),
+                                    ));
                                 } else {
                                     jerror!(
                                         func = "mqtt_ctrl::on_message()",
@@ -648,6 +1164,21 @@ impl MqttCtrl {
                             Some(result) => result?,
                             _ => {}
                         }
+
+                        if let Some((container, blob, sas)) = issued_upload {
+                            if let Some(sas) = sas {
+                                self.issued_sas.push_back(IssuedSas {
+                                    time: Local::now(),
+                                    container: container.clone(),
+                                    blob: blob.clone(),
+                                    sas,
+                                });
+                                if self.issued_sas.len() > ISSUED_SAS_SIZE {
+                                    self.issued_sas.pop_front();
+                                }
+                            }
+                            self.issued_uploads.push((container, blob));
+                        }
                     };
                 }
                 EvpMsg::RpcResponse(v) => {
@@ -659,25 +1190,28 @@ impl MqttCtrl {
                         current_rpc_id = self.current_rpc_id,
                         response = response.to_string()
                     );
+                    if let Some(round_trip) =
+                        self.rpcs.complete(req_id, response.clone(), Instant::now())
+                    {
+                        self.latency.rpc_answered(round_trip);
+                    } else {
+                        jinfo!(
+                            func = "mqtt_ctrl::on_message()",
+                            note = "Response of an unknown RPC",
+                            req_id = req_id
+                        );
+                    }
+
                     if req_id == self.current_rpc_id {
                         self.direct_command_result = Some(Ok(response));
                         self.direct_command_end = Some(Instant::now());
-
-                        if let (Some(start), Some(end)) =
-                            (self.direct_command_start, self.direct_command_end)
-                        {
-                            jinfo!(
-                                event = "TIME_MEASURE",
-                                direct_command =
-                                    format!("{}ms", end.duration_since(start).as_millis())
-                            );
-                        }
                     }
 
                     self.update_timestamp();
                 }
                 EvpMsg::NonEvp(v) => {
-                    result.extend(v);
+                    self.record_traffic(TopicFamily::NonEvp, &v);
+                    self.return_value(&mut result, v);
                 }
             };
         }
@@ -685,15 +1219,26 @@ impl MqttCtrl {
         Ok(result)
     }
 
+    /// Processes the received messages, to be called periodically by the main loop
     pub fn update(&mut self) -> Result<HashMap<String, String>, DMError> {
         let mut result = HashMap::new();
 
-        if !self.subscribed {
+        for filter in self.subscriptions.take_pending() {
+            jdebug!(
+                func = "MqttCtrl::update()",
+                note = "subscribe",
+                filter = filter
+            );
             self.client
-                .subscribe("#", QoS::AtLeastOnce)
+                .subscribe(filter, QoS::AtLeastOnce)
                 .map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
         }
 
+        self.rpcs.expire(Instant::now(), RPC_TIMEOUT);
+        if self.memory.is_due(Instant::now()) {
+            self.prune_memory();
+        }
+
         // If DirectCommand is set, we are in a DirectCommand screen.
         if let Some(cmd) = self.direct_command.as_ref() {
             match cmd {
@@ -746,6 +1291,20 @@ impl MqttCtrl {
                         }
                     }
                 }
+                DirectCommand::Custom => {
+                    // if no response received for 30 seconds,notify user
+                    if let Some(start) = self.direct_command_start
+                        && self.direct_command_end.is_none()
+                        && self.direct_command_result.is_none()
+                        && start.elapsed().as_secs() > 30
+                    {
+                        self.direct_command_result = Some(Err(Report::new(DMError::Timeout)
+                            .attach_printable(format!(
+                                "No response of custom RPC for {} seconds...",
+                                start.elapsed().as_secs()
+                            ))));
+                    }
+                }
                 DirectCommand::FactoryReset => {
                     if let Some(start) = self.direct_command_start {
                         jdebug!(
@@ -765,17 +1324,53 @@ impl MqttCtrl {
             }
         }
 
-        if let Ok(v) = self.rx.try_recv() {
+        let output = self.hooks.poll();
+        self.apply_hook_output(output, &mut result);
+
+        if let Ok((_, v)) = self.rx.try_recv() {
             match v {
                 Ok(event) => match event {
                     Ok(rumqttc::Event::Incoming(i_event)) => match i_event {
                         rumqttc::Packet::Publish(data) => {
                             jdebug!(func = "MqttCtrl::read()", line = line!(), note = "publish");
                             let topic = data.topic;
-                            let payload = String::from_utf8(data.payload.to_vec())
-                                .map_err(|_e| Report::new(DMError::InvalidData))?;
-
-                            result.extend(self.on_message(&topic, &payload)?);
+                            let now = Instant::now();
+                            match self.guard.check(&topic, data.payload.len(), now) {
+                                Verdict::Accept => {
+                                    // Parsed in place, only the messages kept are copied
+                                    let payload = std::str::from_utf8(&data.payload)
+                                        .map_err(|_e| Report::new(DMError::InvalidData))?;
+
+                                    self.retained.track(&topic, payload, data.retain, now);
+                                    // Empty payloads only clear retained messages
+                                    if !payload.is_empty() {
+                                        let start = Instant::now();
+                                        let values = self.on_message(&topic, payload);
+                                        self.parse_time += start.elapsed();
+                                        result.extend(values?);
+                                    }
+                                }
+                                Verdict::Oversized => {
+                                    let head = guard::truncate(&data.payload, 128);
+                                    jinfo!(
+                                        func = "MqttCtrl::update()",
+                                        line = line!(),
+                                        note = "payload too large, not parsed",
+                                        topic = topic,
+                                        payload = head
+                                    );
+                                    // Kept so that it can still be cleared
+                                    self.retained.track(&topic, &head, data.retain, now);
+                                }
+                                Verdict::RateLimited => {}
+                            }
+                        }
+                        rumqttc::Packet::SubAck(ack) => {
+                            self.subscriptions.on_suback(
+                                ack.return_codes
+                                    .iter()
+                                    .all(|c| matches!(c, rumqttc::SubscribeReasonCode::Success(_))),
+                            );
                         }
                         _ => {
                             jdebug!(func = "MqttCtrl::read()", line = line!(), note = "others");
@@ -821,6 +1416,10 @@ impl MqttCtrl {
             self.device_connected = false;
         }
 
+        // The settings are reported together with the state, so a response still not
+        // matching the latest request after the same threshold has not been applied.
+        self.update_req_res_alert(Duration::from_secs(threshold as u64));
+
         Ok(result)
     }
 
@@ -836,6 +1435,213 @@ impl MqttCtrl {
         self.deployment_status.as_ref()
     }
 
+    pub fn deployment_alert(&self) -> Option<&str> {
+        self.deployment_alert.as_deref()
+    }
+
+    /// Raises an alert when the deployment enters an error state, and clears it on recovery
+    fn update_deployment_alert(&mut self, deployment_status: &DeploymentStatus) {
+        let failures = deployment_status.failures();
+
+        let Some(first) = failures.first() else {
+            self.deployment_alert = None;
+            return;
+        };
+
+        jerror!(
+            func = "MqttCtrl::update_deployment_alert()",
+            line = line!(),
+            failures = format!("{:?}", failures)
+        );
+
+        self.deployment_alert = Some(if failures.len() > 1 {
+            format!(
+                "Deployment failed: {} (+{} more)",
+                first,
+                failures.len() - 1
+            )
+        } else {
+            format!("Deployment failed: {}", first)
+        });
+    }
+
+    pub fn register_hook(&mut self, hook: Box<dyn MessageHook>) {
+        self.hooks.register(hook);
+    }
+
+    pub fn set_syslog(&mut self, syslog: SyslogForwarder) {
+        jinfo!(event = "SYSLOG", target = format!("{:?}", syslog.target()));
+        self.syslog = Some(syslog);
+    }
+
+    fn apply_hook_output(&mut self, output: HookOutput, result: &mut HashMap<String, String>) {
+        if let Some(info) = output.info {
+            self.info = Some(info);
+        }
+        // Kept for the Module screen whether or not they are returned
+        let now = Local::now();
+        for (name, value) in &output.values {
+            self.traffic.record(TopicFamily::Hook, name, value, now);
+        }
+        if self.return_values {
+            result.extend(output.values);
+        }
+    }
+
+    fn return_value(&self, result: &mut HashMap<String, String>, msg: RawMsg) {
+        if self.return_values {
+            result.insert(msg.topic.into_owned(), msg.payload.into_owned());
+        }
+    }
+
+    /// Whether update() returns the messages not updating the device state and the hook
+    /// values, copying them for each message. Enabled by default.
+    pub fn set_return_values(&mut self, return_values: bool) {
+        self.return_values = return_values;
+    }
+
+    pub fn req_res_alert(&self) -> Option<&str> {
+        self.req_res_alert.as_deref()
+    }
+
+    /// Compares the res_id reported in the settings with the req_id of the latest
+    /// configuration sent, None if no configuration of the settings has been sent
+    pub fn req_res_status(&self, kind: SettingsKind, grace: Duration) -> Option<ReqResStatus> {
+        let res_id = match kind {
+            SettingsKind::System => self.system_settings.as_ref().map(|s| s.res_info().res_id()),
+            SettingsKind::Network => self
+                .network_settings
+                .as_ref()
+                .map(|s| s.res_info().res_id()),
+            SettingsKind::Wireless => self
+                .wireless_settings
+                .as_ref()
+                .map(|s| s.res_info().res_id()),
+        };
+
+        self.req_res
+            .check(kind, res_id.unwrap_or(""), Instant::now(), grace)
+    }
+
+    /// Raises an alert for the settings whose configuration has not been applied yet
+    fn update_req_res_alert(&mut self, grace: Duration) {
+        let stale: Vec<String> = SettingsKind::ALL
+            .into_iter()
+            .filter_map(|kind| match self.req_res_status(kind, grace)? {
+                ReqResStatus::Stale { known, elapsed, .. } => Some(format!(
+                    "{} responds to {} request ({}s since sent)",
+                    kind,
+                    if known { "an older" } else { "an unknown" },
+                    elapsed.as_secs()
+                )),
+                _ => None,
+            })
+            .collect();
+
+        if stale.is_empty() {
+            self.req_res_alert = None;
+        } else {
+            if self.req_res_alert.is_none() {
+                jerror!(
+                    func = "MqttCtrl::update_req_res_alert()",
+                    line = line!(),
+                    stale = format!("{:?}", stale)
+                );
+            }
+            self.req_res_alert = Some(format!("Not applied yet: {}", stale.join(", ")));
+        }
+    }
+
+    /// Number of attribute requests the device sent when (re)connecting
+    pub fn connect_requests(&self) -> u32 {
+        self.connect_requests
+    }
+
+    pub fn reboots(&self) -> &RebootTracker {
+        &self.reboots
+    }
+
+    pub fn battery(&self) -> &BatteryTracker {
+        &self.battery
+    }
+
+    pub fn latency(&self) -> &LatencyTracker {
+        &self.latency
+    }
+
+    /// Time spent handling the messages since the last call, for the frame profile
+    pub fn take_parse_time(&mut self) -> Duration {
+        std::mem::take(&mut self.parse_time)
+    }
+
+    pub fn set_memory_budgets(&mut self, budgets: MemoryBudgets) {
+        self.memory.set_budgets(budgets);
+    }
+
+    fn memory_size(&self, subsystem: Subsystem) -> usize {
+        match subsystem {
+            Subsystem::Elogs => self.elogs.iter().map(|e| e.memory_size()).sum(),
+            Subsystem::Traffic => self.traffic.memory_size(),
+            Subsystem::Retained => self.retained.memory_size(),
+            Subsystem::TimeSeries => self.metrics.memory_size(),
+        }
+    }
+
+    /// Size of each store against its budget
+    pub fn memory_usage(&self) -> Vec<MemoryUsage> {
+        Subsystem::ALL
+            .iter()
+            .map(|s| self.memory.usage(*s, self.memory_size(*s)))
+            .collect()
+    }
+
+    /// Drops the oldest data of the stores beyond their budget, so that multi-day sessions
+    /// do not grow unbounded
+    fn prune_memory(&mut self) {
+        let budgets = *self.memory.budgets();
+
+        let mut elogs_size = self.memory_size(Subsystem::Elogs);
+        let mut elogs_pruned = 0;
+        while elogs_size > budgets.get(Subsystem::Elogs) && !self.elogs.is_empty() {
+            elogs_size -= self.elogs.remove(0).memory_size();
+            elogs_pruned += 1;
+        }
+
+        for (subsystem, pruned) in [
+            (Subsystem::Elogs, elogs_pruned),
+            (
+                Subsystem::Traffic,
+                self.traffic.prune(budgets.get(Subsystem::Traffic)),
+            ),
+            (
+                Subsystem::Retained,
+                self.retained.prune(budgets.get(Subsystem::Retained)),
+            ),
+            (
+                Subsystem::TimeSeries,
+                self.metrics.prune(budgets.get(Subsystem::TimeSeries)),
+            ),
+        ] {
+            if pruned > 0 {
+                jinfo!(
+                    func = "MqttCtrl::prune_memory()",
+                    subsystem = subsystem.name(),
+                    pruned = pruned
+                );
+                self.memory.record(subsystem, pruned);
+            }
+        }
+    }
+
+    pub fn temperature_alarms(&self) -> &TemperatureAlarms {
+        &self.temperature_alarms
+    }
+
+    /// Temperature alarms raised or cleared since the last call
+    pub fn take_temperature_notices(&mut self) -> Vec<TemperatureNotice> {
+        std::mem::take(&mut self.temperature_notices)
+    }
+
     pub fn agent_device_config(&self) -> Option<&AgentDeviceConfig> {
         self.agent_device_config.as_ref()
     }
@@ -873,6 +1679,58 @@ impl MqttCtrl {
         self.wireless_settings.as_ref()
     }
 
+    /// Value reported by the device for a config key of the Configuration screen, None when
+    /// the device doesn't report it
+    pub fn reported_config_value(&self, config_key: ConfigKey) -> Option<String> {
+        match config_key {
+            ConfigKey::ReportStatusIntervalMin => self
+                .agent_device_config
+                .as_ref()
+                .map(|c| c.report_status_interval_min.to_string()),
+            ConfigKey::ReportStatusIntervalMax => self
+                .agent_device_config
+                .as_ref()
+                .map(|c| c.report_status_interval_max.to_string()),
+            _ => self
+                .system_settings()
+                .and_then(|s| s.config_value(config_key))
+                .or_else(|| {
+                    self.network_settings()
+                        .and_then(|s| s.config_value(config_key))
+                })
+                .or_else(|| {
+                    self.wireless_settings()
+                        .and_then(|s| s.config_value(config_key))
+                }),
+        }
+    }
+
+    pub fn clock_drift(&self) -> Option<ClockDrift> {
+        self.clock_drift
+    }
+
+    /// Offset of the times reported by the device, UTC until it reported one
+    pub fn device_offset(&self) -> FixedOffset {
+        self.device_offset.unwrap_or(Utc.fix())
+    }
+
+    pub fn rssi_history(&self) -> &VecDeque<i32> {
+        &self.rssi_history
+    }
+
+    pub fn sent_configs(&self) -> &VecDeque<(DateTime<Local>, String)> {
+        &self.sent_configs
+    }
+
+    fn record_traffic(&mut self, family: TopicFamily, msg: &RawMsg) {
+        self.traffic
+            .record(family, &msg.topic, &msg.payload, Local::now());
+    }
+
+    pub fn traffic(&self) -> &Traffic {
+        &self.traffic
+    }
+
     #[allow(dead_code)]
     pub fn exit(&mut self) {
         self.should_exit
@@ -896,6 +1754,10 @@ impl MqttCtrl {
         self.direct_command_result.as_ref()
     }
 
+    pub fn rpcs(&self) -> &RpcTracker {
+        &self.rpcs
+    }
+
     pub fn direct_command_clear(&mut self) {
         self.direct_command = None;
         self.direct_command_request = None;
@@ -907,10 +1769,10 @@ impl MqttCtrl {
     pub fn save_elogs(&mut self) -> Result<String, DMError> {
         if !self.elogs.is_empty() {
             let elog_path = format!("elogs_{}.json", Local::now().format("%Y%m%d_%H%M%S"));
-            let mut file = std::fs::File::create(&elog_path)
-                .map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
-            serde_json::to_writer(&mut file, &self.elogs)
-                .map_err(|e| Report::new(DMError::InvalidData).attach_printable(e))?;
+            let archive = ElogArchive {
+                elogs: self.elogs.clone(),
+            };
+            versioned::save(&elog_path, &archive)?;
             jdebug!(
                 func = "MqttCtrl::save_elogs()",
                 line = line!(),
@@ -923,6 +1785,34 @@ impl MqttCtrl {
         }
     }
 
+    /// Writes a CSV file named after its content and the current time
+    fn save_csv(prefix: &str, content: &str) -> Result<String, DMError> {
+        let path = format!("{}_{}.csv", prefix, Local::now().format("%Y%m%d_%H%M%S"));
+        std::fs::write(&path, content)
+            .map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
+        jdebug!(
+            func = "MqttCtrl::save_csv()",
+            line = line!(),
+            note = "CSV saved",
+            path = &path
+        );
+        Ok(path)
+    }
+
+    pub fn save_elogs_csv(&self) -> Result<String, DMError> {
+        if self.elogs.is_empty() {
+            return Err(Report::new(DMError::InvalidData).attach_printable("No elogs to save"));
+        }
+        Self::save_csv("elogs", &csv::elogs_csv(&self.elogs))
+    }
+
+    pub fn save_metrics_csv(&self) -> Result<String, DMError> {
+        if self.metrics.is_empty() {
+            return Err(Report::new(DMError::InvalidData).attach_printable("No metrics to save"));
+        }
+        Self::save_csv("metrics", &csv::metrics_csv(&self.metrics))
+    }
+
     pub fn save_direct_get_image(&mut self) -> Result<String, DMError> {
         if let Some(Ok(response)) = &self.direct_command_result {
             if let Some(image) = &response.image {
@@ -957,12 +1847,26 @@ impl MqttCtrl {
             .attach_printable("No image found in direct command response"))
     }
 
-    pub fn elogs(&self) -> &[Elog] {
+    pub fn elogs(&self) -> &[ElogEntry] {
         &self.elogs
     }
 
-    pub fn edge_app(&self) -> Option<&EdgeAppInfo> {
-        self.edge_app.as_ref()
+    pub fn elog_stats(&self) -> &ElogStats {
+        &self.elog_stats
+    }
+
+    /// Storage token responses of the session, oldest first
+    pub fn issued_sas(&self) -> &VecDeque<IssuedSas> {
+        &self.issued_sas
+    }
+
+    /// Takes the uploads issued since the last call
+    pub fn take_issued_uploads(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.issued_uploads)
+    }
+
+    pub fn edge_app(&self, instance_id: &str) -> Option<&EdgeAppInfo> {
+        self.edge_apps.get(instance_id)
     }
 
     pub fn firmware(&self) -> &FirmwareProperty {
@@ -984,10 +1888,120 @@ impl MqttCtrl {
     }
 }
 
+/// QoS and retain flag of the configuration publishes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PublishOptions {
+    pub qos: QoS,
+    pub retain: bool,
+}
+
+impl Default for PublishOptions {
+    fn default() -> Self {
+        Self {
+            qos: QoS::AtLeastOnce,
+            retain: false,
+        }
+    }
+}
+
+impl PublishOptions {
+    pub fn new(qos: u8, retain: bool) -> Result<Self, DMError> {
+        let qos = rumqttc::qos(qos).map_err(|_| {
+            Report::new(DMError::InvalidData).attach_printable(format!("Invalid QoS: {}", qos))
+        })?;
+        Ok(Self { qos, retain })
+    }
+
+    pub fn next_qos(&mut self) {
+        self.qos = match self.qos {
+            QoS::AtMostOnce => QoS::AtLeastOnce,
+            QoS::AtLeastOnce => QoS::ExactlyOnce,
+            QoS::ExactlyOnce => QoS::AtMostOnce,
+        };
+    }
+}
+
+impl std::fmt::Display for PublishOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "QoS {}, retain {}",
+            self.qos as u8,
+            if self.retain { "on" } else { "off" }
+        )
+    }
+}
+
+/// Direct command request envelope of an RPC, the instance defaults to $system and the
+/// params to {}
+pub fn custom_rpc_request(
+    id: u32,
+    method: &str,
+    instance: &str,
+    params: &str,
+) -> Result<String, DMError> {
+    if method.is_empty() {
+        return Err(Report::new(DMError::InvalidData).attach_printable("Method is empty"));
+    }
+
+    let params = if params.is_empty() { "{}" } else { params };
+    let params = json::parse(params).map_err(|e| {
+        Report::new(DMError::InvalidData).attach_printable(format!("Invalid params JSON: {}", e))
+    })?;
+    if !params.is_object() {
+        return Err(
+            Report::new(DMError::InvalidData).attach_printable("Params must be a JSON object")
+        );
+    }
+
+    let payload = json::object! {
+        "direct-command-request": {
+            "reqid": id.to_string(),
+            "method": method,
+            "instance": if instance.is_empty() { "$system" } else { instance },
+            "params": params.dump(),
+        }
+    };
+
+    let mut root = Object::new();
+    root.insert("params", payload);
+    Ok(root.dump())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_publish_options() {
+        let mut options = PublishOptions::new(2, true).unwrap();
+        assert_eq!(options.to_string(), "QoS 2, retain on");
+        options.next_qos();
+        assert_eq!(options.qos, QoS::AtMostOnce);
+
+        assert_eq!(PublishOptions::default().to_string(), "QoS 1, retain off");
+        assert!(PublishOptions::new(3, false).is_err());
+    }
+
+    #[test]
+    fn test_custom_rpc_request() {
+        let request = custom_rpc_request(12, "my_method", "", r#"{"a": 1}"#).unwrap();
+        let request = json::parse(&request).unwrap();
+        let inner = &request["params"]["direct-command-request"];
+        assert_eq!(inner["reqid"], "12");
+        assert_eq!(inner["method"], "my_method");
+        assert_eq!(inner["instance"], "$system");
+        assert_eq!(inner["params"], r#"{"a":1}"#);
+
+        let request = custom_rpc_request(1, "m", "node", "").unwrap();
+        assert!(request.contains(r#""instance":"node""#));
+        assert!(request.contains(r#""params":"{}""#));
+
+        assert!(custom_rpc_request(1, "", "", "").is_err());
+        assert!(custom_rpc_request(1, "m", "", "{a}").is_err());
+        assert!(custom_rpc_request(1, "m", "", "[1]").is_err());
+    }
+
     #[test]
     fn test_init_global_mqtt_ctrl_invalid_port() {
         // Passing a non-numeric port should return an error
@@ -1015,4 +2029,168 @@ mod tests {
         // with_mqtt_ctrl_mut expects the global to be initialized and will panic otherwise
         with_mqtt_ctrl_mut(|_c| {});
     }
+
+    #[test]
+    fn test_with_local_mqtt_ctrl() {
+        // Nothing listens on the port, the MqttCtrl is only used on this thread
+        let mqtt_ctrl = MqttCtrl::new("127.0.0.1", 1).unwrap();
+        let (mut mqtt_ctrl, connected) = with_local_mqtt_ctrl(mqtt_ctrl, || {
+            with_mqtt_ctrl_mut(|m| m.update_timestamp());
+            with_mqtt_ctrl(|m| m.is_device_connected())
+        });
+        assert!(connected);
+        assert!(mqtt_ctrl.is_device_connected());
+        mqtt_ctrl.exit();
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let path = std::env::temp_dir().join(format!("review_{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut mqtt_ctrl = MqttCtrl::new("127.0.0.1", 1).unwrap();
+        mqtt_ctrl.set_dry_run(Some(DryRun::new(&path)));
+        assert!(mqtt_ctrl.is_dry_run());
+
+        mqtt_ctrl.send_configure(r#"{"a":1}"#).unwrap();
+        let request = mqtt_ctrl.send_rpc_reboot().unwrap();
+        assert_eq!(mqtt_ctrl.dry_run().unwrap().count(), 2);
+
+        // Nothing is waited for from the device
+        assert!(mqtt_ctrl.sent_configs().is_empty());
+        assert_eq!(mqtt_ctrl.rpcs().pending(), 0);
+        assert!(mqtt_ctrl.direct_command_exec_time().is_none());
+
+        let topics = mqtt_ctrl.traffic().topics(TopicFamily::DryRun);
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[0].0, "v1/devices/me/attributes");
+        assert_eq!(topics[1].1.payload, request);
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        assert!(log.contains("v1/devices/me/attributes qos=1 retain=false\n{\"a\":1}\n"));
+        assert!(log.contains(&request));
+        std::fs::remove_file(&path).unwrap();
+        mqtt_ctrl.exit();
+    }
+
+    #[test]
+    fn test_gateway_route() {
+        let path = std::env::temp_dir().join(format!("review_gw_{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut mqtt_ctrl = MqttCtrl::new("127.0.0.1", 1).unwrap();
+        mqtt_ctrl.set_dry_run(Some(DryRun::new(&path)));
+        mqtt_ctrl
+            .on_message(
+                "v1/gateway/telemetry",
+                r#"{"cam-1":[{"ts":1,"values":{}}]}"#,
+            )
+            .unwrap();
+        assert_eq!(mqtt_ctrl.route(), &DeviceRoute::Gateway("cam-1".to_owned()));
+
+        mqtt_ctrl.send_configure(r#"{"a":1}"#).unwrap();
+        mqtt_ctrl.send_rpc("m", "", "").unwrap();
+        let topics = mqtt_ctrl.traffic().topics(TopicFamily::DryRun);
+        assert_eq!(topics[0].0, "v1/gateway/attributes");
+        assert_eq!(topics[0].1.payload, r#"{"device":"cam-1","data":{"a":1}}"#);
+        assert_eq!(topics[1].0, "v1/gateway/rpc");
+        assert!(
+            topics[1]
+                .1
+                .payload
+                .starts_with(r#"{"device":"cam-1","data":{"params":"#)
+        );
+
+        // Back to a device reporting with its own credentials
+        mqtt_ctrl
+            .on_message("v1/devices/me/telemetry", "{}")
+            .unwrap();
+        mqtt_ctrl.send_rpc_reboot().unwrap();
+        let topics = mqtt_ctrl.traffic().topics(TopicFamily::DryRun);
+        assert!(
+            topics
+                .iter()
+                .any(|(topic, _)| topic.starts_with("v1/devices/me/rpc/request/"))
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        mqtt_ctrl.exit();
+    }
+
+    #[test]
+    fn test_return_values() {
+        let mut mqtt_ctrl = MqttCtrl::new("127.0.0.1", 1).unwrap();
+        let values = mqtt_ctrl.on_message("a/b", "x").unwrap();
+        assert_eq!(values.get("a/b").map(String::as_str), Some("x"));
+
+        // Still recorded for the Module screen
+        mqtt_ctrl.set_return_values(false);
+        assert!(mqtt_ctrl.on_message("a/b", "y").unwrap().is_empty());
+        let topics = mqtt_ctrl.traffic().topics(TopicFamily::NonEvp);
+        assert_eq!(topics[0].1.payload, "y");
+        mqtt_ctrl.exit();
+    }
+
+    #[test]
+    fn test_hook_values_visible() {
+        struct DecodeHook;
+
+        impl MessageHook for DecodeHook {
+            fn name(&self) -> &str {
+                "decode"
+            }
+
+            fn topic_filter(&self) -> &str {
+                "v1/devices/+/telemetry"
+            }
+
+            fn handle(&mut self, _topic: &str, payload: &str) -> Result<HookOutput, DMError> {
+                let mut output = HookOutput::default();
+                output
+                    .values
+                    .insert("decoded".to_owned(), payload.len().to_string());
+                Ok(output)
+            }
+        }
+
+        let mut mqtt_ctrl = MqttCtrl::new("127.0.0.1", 1).unwrap();
+        mqtt_ctrl.register_hook(Box::new(DecodeHook));
+        // As in the TUI, which does not use the returned values
+        mqtt_ctrl.set_return_values(false);
+        assert!(
+            mqtt_ctrl
+                .on_message("v1/devices/me/telemetry", "{}")
+                .unwrap()
+                .is_empty()
+        );
+
+        let topics = mqtt_ctrl.traffic().topics(TopicFamily::Hook);
+        assert_eq!(topics.len(), 1);
+        assert_eq!(topics[0].0, "decoded");
+        assert_eq!(topics[0].1.payload, "2");
+        mqtt_ctrl.exit();
+    }
+
+    #[test]
+    fn test_read_only() {
+        let mut mqtt_ctrl = MqttCtrl::new("127.0.0.1", 1).unwrap();
+        mqtt_ctrl.set_read_only(true);
+        assert!(mqtt_ctrl.is_read_only());
+
+        let e = mqtt_ctrl.send_configure(r#"{"a":1}"#).unwrap_err();
+        assert!(matches!(e.current_context(), DMError::ReadOnly));
+        assert_eq!(
+            e.error_str().unwrap(),
+            "Read-only mode, nothing is sent to v1/devices/me/attributes"
+        );
+        assert!(mqtt_ctrl.send_rpc_reboot().is_err());
+        assert!(mqtt_ctrl.send_rpc("m", "", "").is_err());
+        assert!(mqtt_ctrl.publish("t", "{}").is_err());
+        assert!(mqtt_ctrl.clear_retained("t").is_err());
+
+        // Neither sent nor awaited
+        assert!(mqtt_ctrl.sent_configs().is_empty());
+        assert_eq!(mqtt_ctrl.rpcs().pending(), 0);
+        mqtt_ctrl.exit();
+    }
 }
@@ -0,0 +1,195 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::evp::device_info::PowerStates,
+    chrono::{DateTime, Duration, Local},
+    std::collections::VecDeque,
+};
+
+/// Levels the discharge trend is estimated from
+const TREND_WINDOW: Duration = Duration::minutes(30);
+
+/// Time to empty under which the battery is shown as low
+pub const LOW_BATTERY_LEFT: Duration = Duration::minutes(30);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatteryState {
+    /// The device runs on the battery
+    Discharging,
+    /// Another power source is in use and the battery is not full
+    Charging,
+    Full,
+}
+
+impl BatteryState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BatteryState::Discharging => "discharging",
+            BatteryState::Charging => "charging",
+            BatteryState::Full => "full",
+        }
+    }
+}
+
+/// Battery level reported by the device at one time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatterySample {
+    pub time: DateTime<Local>,
+    pub level: i8,
+}
+
+/// Battery levels of the device over the last `TREND_WINDOW`, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct BatteryTracker {
+    samples: VecDeque<BatterySample>,
+    state: Option<BatteryState>,
+}
+
+impl BatteryTracker {
+    /// Records the battery of a device states report, forgotten when the device has none
+    pub fn record(&mut self, power_states: &PowerStates, now: DateTime<Local>) {
+        let Some(level) = power_states.battery_level() else {
+            self.samples.clear();
+            self.state = None;
+            return;
+        };
+
+        let state = if power_states.on_battery() {
+            BatteryState::Discharging
+        } else if level >= 100 {
+            BatteryState::Full
+        } else {
+            BatteryState::Charging
+        };
+        // The trend of the previous state does not tell anything about the new one
+        if self.state != Some(state) {
+            self.samples.clear();
+        }
+        self.state = Some(state);
+
+        self.samples.push_back(BatterySample { time: now, level });
+        while self
+            .samples
+            .front()
+            .is_some_and(|s| now - s.time > TREND_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn state(&self) -> Option<BatteryState> {
+        self.state
+    }
+
+    /// Latest level, in percent
+    pub fn level(&self) -> Option<i8> {
+        self.samples.back().map(|s| s.level)
+    }
+
+    pub fn samples(&self) -> &VecDeque<BatterySample> {
+        &self.samples
+    }
+
+    /// Time until the battery is empty at the discharge rate of the last `TREND_WINDOW`,
+    /// None while not discharging or before the level drops
+    pub fn time_to_empty(&self) -> Option<Duration> {
+        if self.state != Some(BatteryState::Discharging) {
+            return None;
+        }
+        let (first, last) = (self.samples.front()?, self.samples.back()?);
+        let dropped = f64::from(first.level - last.level);
+        let elapsed = (last.time - first.time).num_seconds() as f64;
+        if dropped <= 0.0 || elapsed <= 0.0 {
+            return None;
+        }
+
+        let left = f64::from(last.level.max(0)) * elapsed / dropped;
+        Some(Duration::seconds(left as i64))
+    }
+}
+
+/// Gauge of the level as "[██████░░░░] 60%"
+pub fn battery_gauge(level: i8) -> String {
+    const WIDTH: usize = 10;
+    let level = level.clamp(0, 100) as usize;
+    let filled = (level * WIDTH).div_ceil(100);
+    format!(
+        "[{}{}] {}%",
+        "█".repeat(filled),
+        "░".repeat(WIDTH - filled),
+        level
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn power_states(in_use: i8, level: i8) -> PowerStates {
+        serde_json::from_str(&format!(
+            r#"{{"source":[{{"type":0,"level":0}},{{"type":3,"level":{level}}}],"in_use":{in_use},"is_battery_low":false}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_battery_tracker() {
+        let start = Local::now();
+        let mut tracker = BatteryTracker::default();
+
+        tracker.record(&power_states(0, 90), start);
+        assert_eq!(tracker.state(), Some(BatteryState::Charging));
+        assert_eq!(tracker.time_to_empty(), None);
+
+        tracker.record(&power_states(3, 90), start);
+        assert_eq!(tracker.state(), Some(BatteryState::Discharging));
+        assert_eq!(tracker.samples().len(), 1);
+        assert_eq!(tracker.time_to_empty(), None);
+
+        // 10% in 10 minutes, 80% left
+        tracker.record(&power_states(3, 85), start + Duration::minutes(5));
+        tracker.record(&power_states(3, 80), start + Duration::minutes(10));
+        assert_eq!(tracker.level(), Some(80));
+        assert_eq!(tracker.time_to_empty(), Some(Duration::minutes(80)));
+
+        // Only the last 30 minutes are kept: 10% in 30 minutes, 70% left
+        tracker.record(&power_states(3, 70), start + Duration::minutes(40));
+        assert_eq!(tracker.samples().len(), 2);
+        assert_eq!(tracker.time_to_empty(), Some(Duration::minutes(210)));
+
+        tracker.record(&power_states(0, 100), start + Duration::minutes(50));
+        assert_eq!(tracker.state(), Some(BatteryState::Full));
+        assert_eq!(tracker.time_to_empty(), None);
+
+        let no_battery: PowerStates = serde_json::from_str(
+            r#"{"source":[{"type":0,"level":0}],"in_use":0,"is_battery_low":false}"#,
+        )
+        .unwrap();
+        tracker.record(&no_battery, start + Duration::minutes(51));
+        assert_eq!(tracker.state(), None);
+        assert_eq!(tracker.level(), None);
+    }
+
+    #[test]
+    fn test_battery_gauge() {
+        assert_eq!(battery_gauge(60), "[██████░░░░] 60%");
+        assert_eq!(battery_gauge(5), "[█░░░░░░░░░] 5%");
+        assert_eq!(battery_gauge(0), "[░░░░░░░░░░] 0%");
+        assert_eq!(battery_gauge(120), "[██████████] 100%");
+    }
+}
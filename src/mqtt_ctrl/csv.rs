@@ -0,0 +1,165 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{
+        evp::elog::{Elog, ElogEntry},
+        metrics::Metrics,
+    },
+    chrono::SecondsFormat,
+    std::borrow::Cow,
+};
+
+/// Quotes a field containing a separator, a quote or a line break
+fn field(s: &str) -> Cow<'_, str> {
+    if s.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", s.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+fn push_line<S: AsRef<str>>(out: &mut String, fields: &[S]) {
+    let line = fields
+        .iter()
+        .map(|f| field(f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    out.push_str(&line);
+    out.push_str("\r\n");
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+pub fn elogs_csv(elogs: &[ElogEntry]) -> String {
+    let mut out = String::new();
+    push_line(
+        &mut out,
+        &[
+            "timestamp",
+            "serial",
+            "level",
+            "component_id",
+            "component_name",
+            "event_id",
+            "event",
+            "description",
+            "count",
+            "last_timestamp",
+        ],
+    );
+
+    for entry in elogs {
+        let elog = entry.elog();
+        push_line(
+            &mut out,
+            &[
+                entry.first_timestamp().to_owned(),
+                elog.serial().to_owned(),
+                elog.level_str().to_owned(),
+                elog.component_id().to_string(),
+                optional(elog.component_name()),
+                format!("0x{:04x}", elog.event_id()),
+                elog.event_str().to_owned(),
+                optional(elog.event_description()),
+                entry.count().to_string(),
+                entry.last_timestamp().to_owned(),
+            ],
+        );
+    }
+
+    out
+}
+
+pub fn metrics_csv(metrics: &Metrics) -> String {
+    let mut out = String::new();
+    push_line(
+        &mut out,
+        &[
+            "timestamp",
+            "main_chip_temperature",
+            "sensor_chip_temperature",
+            "companion_chip_temperature",
+            "hours_meter",
+        ],
+    );
+
+    for sample in metrics.samples() {
+        push_line(
+            &mut out,
+            &[
+                sample.time.to_rfc3339_opts(SecondsFormat::Secs, false),
+                optional(sample.main_chip_temperature),
+                optional(sample.sensor_chip_temperature),
+                optional(sample.companion_chip_temperature),
+                optional(sample.hours_meter),
+            ],
+        );
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    #[test]
+    fn test_elogs_csv() {
+        let elog = Elog::parse(
+            r#"{
+                "serial": "SN001",
+                "level": 1,
+                "timestamp": "2024-06-01T10:00:00Z",
+                "component_id": 100,
+                "event_id": 4096,
+                "event_description": "Critical, \"really\""
+            }"#,
+        )
+        .unwrap();
+
+        let csv = elogs_csv(&[ElogEntry::new(elog)]);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(
+            lines[0],
+            "timestamp,serial,level,component_id,component_name,event_id,event,description,count,last_timestamp"
+        );
+        assert!(lines[1].starts_with("2024-06-01T10:00:00Z,SN001,ERROR,100,,0x1000,"));
+        assert!(lines[1].ends_with(",\"Critical, \"\"really\"\"\",1,2024-06-01T10:00:00Z"));
+    }
+
+    #[test]
+    fn test_metrics_csv() {
+        let mut metrics = Metrics::default();
+        let time = Local.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        metrics.record(None, None, time);
+
+        let csv = metrics_csv(&metrics);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(
+            lines[0],
+            "timestamp,main_chip_temperature,sensor_chip_temperature,companion_chip_temperature,hours_meter"
+        );
+        assert_eq!(
+            lines[1],
+            format!("{},,,,", time.to_rfc3339_opts(SecondsFormat::Secs, false))
+        );
+    }
+}
@@ -0,0 +1,139 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::DMError,
+    chrono::{DateTime, Local},
+    error_stack::{Report, Result},
+    std::{io::Write, path::PathBuf},
+};
+
+/// Review log of the dry-run mode, in the current directory unless given
+pub const DEFAULT_REVIEW_LOG: &str = "dry_run.log";
+
+/// Dry-run mode: the configurations and RPCs are appended to the review log instead of
+/// being published
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRun {
+    path: PathBuf,
+    /// Messages reviewed since the start
+    count: usize,
+}
+
+impl DryRun {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            count: 0,
+        }
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Appends the message which would have been published to the review log
+    pub fn review(
+        &mut self,
+        time: DateTime<Local>,
+        topic: &str,
+        qos: u8,
+        retain: bool,
+        payload: &str,
+    ) -> Result<(), DMError> {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| {
+                file.write_all(review_entry(time, topic, qos, retain, payload).as_bytes())
+            })
+            .map_err(|e| {
+                Report::new(DMError::IOError).attach_printable(format!(
+                    "Failed to write the review log {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })?;
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// Entry of the review log: a header line with the publish parameters, then the payload
+pub fn review_entry(
+    time: DateTime<Local>,
+    topic: &str,
+    qos: u8,
+    retain: bool,
+    payload: &str,
+) -> String {
+    format!(
+        "{} {} qos={} retain={}\n{}\n\n",
+        time.format("%Y-%m-%d %H:%M:%S"),
+        topic,
+        qos,
+        retain,
+        payload.trim_end()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dry_run_review() {
+        let path = std::env::temp_dir().join(format!("dry_run_{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let time = Local::now();
+
+        let mut dry_run = DryRun::new(&path);
+        dry_run
+            .review(time, "v1/devices/me/attributes", 1, false, "{}\n")
+            .unwrap();
+        dry_run
+            .review(
+                time,
+                "v1/devices/me/rpc/request/1",
+                1,
+                true,
+                "{\"params\":{}}",
+            )
+            .unwrap();
+        assert_eq!(dry_run.count(), 2);
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        let stamp = time.format("%Y-%m-%d %H:%M:%S");
+        assert_eq!(
+            log,
+            format!(
+                "{stamp} v1/devices/me/attributes qos=1 retain=false\n{{}}\n\n\
+                 {stamp} v1/devices/me/rpc/request/1 qos=1 retain=true\n{{\"params\":{{}}}}\n\n"
+            )
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        let mut dry_run = DryRun::new(path.join("missing").join("dry_run.log"));
+        assert!(dry_run.review(time, "t", 0, false, "").is_err());
+        assert_eq!(dry_run.count(), 0);
+    }
+}
@@ -0,0 +1,327 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::evp::elog::Elog,
+    chrono::{DateTime, Duration, Local},
+    std::collections::HashMap,
+};
+
+/// Levels counted per component, from CRITICAL (0) to TRACE (5)
+pub const LEVELS: usize = 6;
+
+/// Windows the errors of a component are counted over, in seconds
+const WINDOW_SECS: i64 = 60;
+
+/// Windows learned before a spike can be reported
+const LEARNING_WINDOWS: u32 = 5;
+
+/// Weight of the last window in the baseline, the older ones fading away
+const BASELINE_WEIGHT: f64 = 0.2;
+
+/// A window spikes with this many times the baseline errors, and at least
+/// `SPIKE_MIN_ERRORS` so that a few errors of a quiet component are not reported
+const SPIKE_FACTOR: f64 = 3.0;
+const SPIKE_MIN_ERRORS: usize = 3;
+
+/// How long a spike is reported after its last error
+const SPIKE_HOLD_SECS: i64 = 5 * 60;
+
+/// Errors of a window beyond the baseline of the component
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spike {
+    pub last: DateTime<Local>,
+    pub errors: usize,
+    /// Errors per window expected from the previous ones
+    pub baseline: f64,
+}
+
+/// Errors per window of a component learned during the session, as a moving average
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorBaseline {
+    window_start: DateTime<Local>,
+    window_errors: usize,
+    average: f64,
+    /// Windows closed since the beginning of the session
+    windows: u32,
+    spike: Option<Spike>,
+}
+
+impl ErrorBaseline {
+    pub fn new(started: DateTime<Local>) -> Self {
+        Self {
+            window_start: started,
+            window_errors: 0,
+            average: 0.0,
+            windows: 0,
+            spike: None,
+        }
+    }
+
+    /// Closes the windows elapsed until `now`, the ones without elogs counting no error
+    fn advance(&mut self, now: DateTime<Local>) {
+        let elapsed = (now - self.window_start).num_seconds() / WINDOW_SECS;
+        if elapsed <= 0 {
+            return;
+        }
+
+        self.average = if self.windows == 0 {
+            self.window_errors as f64
+        } else {
+            self.average * (1.0 - BASELINE_WEIGHT) + self.window_errors as f64 * BASELINE_WEIGHT
+        };
+        let empty = (elapsed - 1).min(i32::MAX as i64) as i32;
+        self.average *= (1.0 - BASELINE_WEIGHT).powi(empty);
+        self.windows = self
+            .windows
+            .saturating_add(u32::try_from(elapsed).unwrap_or(u32::MAX));
+        self.window_start += Duration::seconds(elapsed * WINDOW_SECS);
+        self.window_errors = 0;
+    }
+
+    pub fn record(&mut self, error: bool, now: DateTime<Local>) {
+        self.advance(now);
+        if !error {
+            return;
+        }
+
+        self.window_errors += 1;
+        if self.windows >= LEARNING_WINDOWS
+            && self.window_errors >= SPIKE_MIN_ERRORS
+            && self.window_errors as f64 > self.average * SPIKE_FACTOR
+        {
+            self.spike = Some(Spike {
+                last: now,
+                errors: self.window_errors,
+                baseline: self.average,
+            });
+        }
+    }
+
+    /// Last spike, until `SPIKE_HOLD_SECS` after it
+    pub fn spike(&self, now: DateTime<Local>) -> Option<&Spike> {
+        self.spike
+            .as_ref()
+            .filter(|spike| now - spike.last < Duration::seconds(SPIKE_HOLD_SECS))
+    }
+}
+
+/// Elogs of one component received during the session
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentStats {
+    pub component_id: u32,
+    pub component_name: Option<String>,
+    /// Count per level, unknown levels are only counted in `total`
+    pub levels: [usize; LEVELS],
+    pub total: usize,
+    pub last: DateTime<Local>,
+    pub baseline: ErrorBaseline,
+}
+
+impl ComponentStats {
+    pub fn name(&self) -> String {
+        match &self.component_name {
+            Some(name) => format!("{} ({})", name, self.component_id),
+            None => self.component_id.to_string(),
+        }
+    }
+
+    /// CRITICAL and ERROR elogs
+    pub fn errors(&self) -> usize {
+        self.levels[0] + self.levels[1]
+    }
+}
+
+/// Elogs aggregated by component over the session, including the ones no longer listed
+#[derive(Debug, Clone)]
+pub struct ElogStats {
+    started: DateTime<Local>,
+    components: HashMap<u32, ComponentStats>,
+}
+
+impl Default for ElogStats {
+    fn default() -> Self {
+        Self::new(Local::now())
+    }
+}
+
+impl ElogStats {
+    pub fn new(started: DateTime<Local>) -> Self {
+        Self {
+            started,
+            components: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, elog: &Elog, now: DateTime<Local>) {
+        // Learned from the beginning of the session, a component silent until now has no
+        // error in its baseline
+        let started = self.started;
+        let stats = self
+            .components
+            .entry(elog.component_id())
+            .or_insert_with(|| ComponentStats {
+                component_id: elog.component_id(),
+                component_name: None,
+                levels: [0; LEVELS],
+                total: 0,
+                last: now,
+                baseline: ErrorBaseline::new(started),
+            });
+
+        if let Some(name) = elog.component_name() {
+            stats.component_name = Some(name.to_owned());
+        }
+        if let Some(count) = stats.levels.get_mut(elog.level() as usize) {
+            *count += 1;
+        }
+        stats.total += 1;
+        stats.last = now;
+        stats.baseline.record(elog.level() <= 1, now);
+    }
+
+    /// Components whose errors spiked beyond their baseline recently, the latest first
+    pub fn anomalies(&self, now: DateTime<Local>) -> Vec<&ComponentStats> {
+        let mut components: Vec<_> = self
+            .components
+            .values()
+            .filter(|c| c.baseline.spike(now).is_some())
+            .collect();
+        components
+            .sort_by_key(|c| std::cmp::Reverse(c.baseline.spike(now).map(|spike| spike.last)));
+        components
+    }
+
+    /// Components with the most errors first, then the noisiest
+    pub fn top_offenders(&self) -> Vec<&ComponentStats> {
+        let mut components: Vec<_> = self.components.values().collect();
+        components.sort_by(|a, b| {
+            b.errors()
+                .cmp(&a.errors())
+                .then(b.total.cmp(&a.total))
+                .then(a.component_id.cmp(&b.component_id))
+        });
+        components
+    }
+
+    pub fn total(&self) -> usize {
+        self.components.values().map(|c| c.total).sum()
+    }
+
+    /// Elogs per minute of `stats` since the beginning of the session, the first minute
+    /// counting as a whole one so that a single elog does not look like a burst
+    pub fn rate(&self, stats: &ComponentStats, now: DateTime<Local>) -> f64 {
+        let minutes = (now - self.started).num_seconds().max(60) as f64 / 60.0;
+        stats.total as f64 / minutes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn elog(level: u8, component_id: u32, component_name: Option<&str>) -> Elog {
+        let name = component_name
+            .map(|n| format!(r#","component_name":"{n}""#))
+            .unwrap_or_default();
+        Elog::parse(&format!(
+            r#"{{"serial":"S","level":{level},"timestamp":"t","component_id":{component_id}{name},"event_id":4096}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_top_offenders() {
+        let started = Local::now();
+        let mut stats = ElogStats::new(started);
+
+        for _ in 0..10 {
+            stats.record(&elog(3, 1, Some("Main")), started);
+        }
+        stats.record(&elog(1, 2, None), started);
+        stats.record(&elog(0, 2, Some("Sensor")), started);
+        stats.record(&elog(9, 3, None), started);
+
+        assert_eq!(stats.total(), 13);
+        let top = stats.top_offenders();
+        assert_eq!(
+            top.iter().map(|c| c.name()).collect::<Vec<_>>(),
+            ["Sensor (2)", "Main (1)", "3"]
+        );
+        assert_eq!(top[0].levels, [1, 1, 0, 0, 0, 0]);
+        assert_eq!(top[2].levels, [0; LEVELS]);
+        assert_eq!(top[2].total, 1);
+
+        assert_eq!(stats.rate(top[1], started + Duration::seconds(10)), 10.0);
+        assert_eq!(stats.rate(top[1], started + Duration::minutes(5)), 2.0);
+    }
+
+    #[test]
+    fn test_anomalies() {
+        let started = Local::now();
+        let mut stats = ElogStats::new(started);
+        let minute = |m: i64| started + Duration::minutes(m) + Duration::seconds(1);
+
+        // One error a minute for the Main component, none for the Sensor
+        for m in 0..10 {
+            stats.record(&elog(1, 1, Some("Main")), minute(m));
+            stats.record(&elog(3, 2, Some("Sensor")), minute(m));
+        }
+        assert!(stats.anomalies(minute(10)).is_empty());
+
+        // Still learning: no spike reported during the first windows
+        let mut early = ElogStats::new(started);
+        for _ in 0..10 {
+            early.record(&elog(1, 2, Some("Sensor")), minute(1));
+        }
+        assert!(early.anomalies(minute(1)).is_empty());
+
+        // Two errors of Main stay around its baseline, three of the quiet Sensor spike
+        for _ in 0..2 {
+            stats.record(&elog(1, 1, Some("Main")), minute(10));
+        }
+        for _ in 0..3 {
+            stats.record(&elog(0, 2, Some("Sensor")), minute(10));
+        }
+        let anomalies = stats.anomalies(minute(10));
+        assert_eq!(
+            anomalies.iter().map(|c| c.name()).collect::<Vec<_>>(),
+            ["Sensor (2)"]
+        );
+        let spike = anomalies[0].baseline.spike(minute(10)).unwrap();
+        assert_eq!(spike.errors, 3);
+        assert_eq!(spike.baseline, 0.0);
+
+        // Main spikes later, reported first
+        for _ in 0..5 {
+            stats.record(&elog(1, 1, Some("Main")), minute(11));
+        }
+        assert_eq!(
+            stats
+                .anomalies(minute(11))
+                .iter()
+                .map(|c| c.name())
+                .collect::<Vec<_>>(),
+            ["Main (1)", "Sensor (2)"]
+        );
+
+        // Reported until 5 minutes after the last error
+        assert_eq!(stats.anomalies(minute(15)).len(), 1);
+        assert!(stats.anomalies(minute(17)).is_empty());
+    }
+}
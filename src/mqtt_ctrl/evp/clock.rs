@@ -0,0 +1,212 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    chrono::{DateTime, Datelike, FixedOffset, Local, TimeDelta, TimeZone, Utc},
+    std::fmt::Display,
+};
+
+/// Offset above which the device clock is considered wrong
+pub const DRIFT_THRESHOLD: TimeDelta = TimeDelta::seconds(30);
+
+/// Devices report times before this year until their clock is set
+const FIRST_VALID_YEAR: i32 = 2020;
+
+/// Device clock compared to the local clock
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClockDrift {
+    /// Device time minus local time, positive when the device is ahead
+    Offset(TimeDelta),
+    /// The device reports times before its clock could have been set
+    NotSet,
+}
+
+impl ClockDrift {
+    /// Estimates the drift from a time the device reported at `local`
+    pub fn from_report(device_time: &str, local: DateTime<Local>) -> Option<Self> {
+        let device_time = DateTime::parse_from_rfc3339(device_time).ok()?;
+        if device_time.year() < FIRST_VALID_YEAR {
+            return Some(ClockDrift::NotSet);
+        }
+
+        Some(ClockDrift::Offset(
+            device_time.signed_duration_since(local.fixed_offset()),
+        ))
+    }
+
+    /// A boot time can only be in the future when the device clock is ahead, otherwise it
+    /// tells nothing about the drift
+    pub fn from_bootup_time(bootup_time: &str, local: DateTime<Local>) -> Option<Self> {
+        match Self::from_report(bootup_time, local)? {
+            ClockDrift::Offset(offset) if offset <= DRIFT_THRESHOLD => None,
+            drift => Some(drift),
+        }
+    }
+
+    pub fn exceeds_threshold(&self) -> bool {
+        match self {
+            ClockDrift::Offset(offset) => offset.abs() > DRIFT_THRESHOLD,
+            ClockDrift::NotSet => true,
+        }
+    }
+}
+
+impl Display for ClockDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let offset = match self {
+            ClockDrift::Offset(offset) => offset,
+            ClockDrift::NotSet => return write!(f, "clock not set"),
+        };
+
+        let sign = if *offset < TimeDelta::zero() {
+            '-'
+        } else {
+            '+'
+        };
+        let secs = offset.num_seconds().abs();
+        match secs {
+            s if s < 60 => write!(f, "{sign}{s}s"),
+            s if s < 3600 => write!(f, "{sign}{}m{}s", s / 60, s % 60),
+            s if s < 86400 => write!(f, "{sign}{}h{}m", s / 3600, s % 3600 / 60),
+            s => write!(f, "{sign}{}d{}h", s / 86400, s % 86400 / 3600),
+        }
+    }
+}
+
+/// Time zone the times are displayed in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimeZoneMode {
+    /// Time zone of the machine running device-monitor
+    #[default]
+    Local,
+    Utc,
+    /// Offset of the times reported by the device
+    Device,
+}
+
+impl TimeZoneMode {
+    pub fn next(self) -> Self {
+        match self {
+            TimeZoneMode::Local => TimeZoneMode::Utc,
+            TimeZoneMode::Utc => TimeZoneMode::Device,
+            TimeZoneMode::Device => TimeZoneMode::Local,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeZoneMode::Local => "Local",
+            TimeZoneMode::Utc => "UTC",
+            TimeZoneMode::Device => "Device",
+        }
+    }
+
+    /// Converts a time to the time zone, `device` is the offset of the device times
+    pub fn convert<Tz: TimeZone>(
+        &self,
+        time: &DateTime<Tz>,
+        device: FixedOffset,
+    ) -> DateTime<FixedOffset> {
+        match self {
+            TimeZoneMode::Local => time.with_timezone(&Local).fixed_offset(),
+            TimeZoneMode::Utc => time.with_timezone(&Utc).fixed_offset(),
+            TimeZoneMode::Device => time.with_timezone(&device),
+        }
+    }
+}
+
+/// Parses a time reported by the device, None when it isn't in RFC 3339
+pub fn parse_device_time(time: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(time.trim()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_drift() {
+        let local: DateTime<Local> = DateTime::parse_from_rfc3339("2025-05-04T17:41:53Z")
+            .unwrap()
+            .into();
+
+        let drift = ClockDrift::from_report("2025-05-04T17:44:06.500Z", local).unwrap();
+        assert_eq!(drift.to_string(), "+2m13s");
+        assert!(drift.exceeds_threshold());
+
+        let drift = ClockDrift::from_report("2025-05-04T17:41:43Z", local).unwrap();
+        assert_eq!(drift.to_string(), "-10s");
+        assert!(!drift.exceeds_threshold());
+
+        assert_eq!(
+            ClockDrift::from_report("1970-01-01T00:00:10Z", local),
+            Some(ClockDrift::NotSet)
+        );
+        assert_eq!(ClockDrift::from_report("garbage", local), None);
+    }
+
+    #[test]
+    fn test_bootup_time() {
+        let local: DateTime<Local> = DateTime::parse_from_rfc3339("2025-05-04T17:41:53Z")
+            .unwrap()
+            .into();
+
+        // Booted in the past, nothing can be said
+        assert_eq!(
+            ClockDrift::from_bootup_time("2025-05-04T08:00:00Z", local),
+            None
+        );
+        assert_eq!(
+            ClockDrift::from_bootup_time("2025-05-04T19:41:53Z", local)
+                .unwrap()
+                .to_string(),
+            "+2h0m"
+        );
+    }
+
+    #[test]
+    fn test_time_zone_mode() {
+        let time = parse_device_time("2025-05-04T17:41:53+09:00").unwrap();
+        let device = *time.offset();
+
+        assert_eq!(
+            TimeZoneMode::Utc.convert(&time, device).to_rfc3339(),
+            "2025-05-04T08:41:53+00:00"
+        );
+        assert_eq!(TimeZoneMode::Device.convert(&time, device), time);
+        assert_eq!(
+            TimeZoneMode::Device
+                .convert(&time.with_timezone(&Utc), device)
+                .to_rfc3339(),
+            "2025-05-04T17:41:53+09:00"
+        );
+        assert_eq!(
+            TimeZoneMode::Local.convert(&time, device),
+            time.with_timezone(&Local).fixed_offset()
+        );
+        assert!(parse_device_time("").is_none());
+
+        let mut mode = TimeZoneMode::default();
+        let names: Vec<&str> = (0..3)
+            .map(|_| {
+                mode = mode.next();
+                mode.name()
+            })
+            .collect();
+        assert_eq!(names, vec!["UTC", "Device", "Local"]);
+    }
+}
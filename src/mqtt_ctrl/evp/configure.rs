@@ -14,7 +14,7 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use crate::app::MainWindowFocus;
+use crate::device_config::MainWindowFocus;
 #[allow(unused)]
 use {
     super::{
@@ -27,13 +27,12 @@ use {
     },
     crate::mqtt_ctrl::MqttCtrl,
     crate::{
-        app::{App, ConfigKey},
+        device_config::{ConfigKey, config_dir},
         error::{DMError, DMErrorExt},
     },
     error_stack::{Context, Report, Result, ResultExt},
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
     json::{JsonValue, object::Object},
-    pest::{Parser, Token},
     regex::Regex,
     rumqttc::{Client, Connection, MqttOptions, QoS},
     std::{
@@ -53,6 +52,30 @@ fn fix_str(s: &str) -> String {
     }
 }
 
+/// Token provider UUID of a log setting, empty to clear it
+fn fix_storage_name(s: &str) -> Result<String, DMError> {
+    let s = fix_str(s);
+    if s.is_empty() {
+        return Ok(s);
+    }
+
+    UUID::from(&s).map(|uuid| uuid.to_string()).map_err(|e| {
+        let reason = e.error_str().unwrap_or_default();
+        Report::new(DMError::InvalidData)
+            .attach_printable(format!("Invalid storage_name: {reason}"))
+    })
+}
+
+/// Copy of the config keys where the keys not edited by the user are unset, so that the
+/// configuration only carries the settings the user changed
+pub fn dirty_config_keys(config_keys: &[String], dirty: &[bool]) -> Vec<String> {
+    config_keys
+        .iter()
+        .zip(dirty)
+        .map(|(value, dirty)| if *dirty { value.clone() } else { String::new() })
+        .collect()
+}
+
 pub fn parse_evp_device_config(
     agent_device_config: Option<&AgentDeviceConfig>,
     config_key: &[String],
@@ -101,7 +124,7 @@ pub fn parse_evp_device_config(
             JsonValue::Object(registry_auth),
         );
 
-        let configure_id = JsonValue::String(UUID::new().uuid().to_owned());
+        let configure_id = JsonValue::String(UUID::new_string());
         json.insert("configuration/$agent/configuration-id", configure_id);
 
         if !json.is_empty() {
@@ -123,7 +146,7 @@ pub fn parse_evp_device_config(
 
 pub fn parse_user_config(focus: MainWindowFocus) -> Result<String, DMError> {
     // User configuration
-    let config_file = format!("{}/{}", App::config_dir(), focus.user_config_file());
+    let config_file = format!("{}/{}", config_dir(), focus.user_config_file());
     let json_str = std::fs::read_to_string(&config_file).map_err(|_| {
         Report::new(DMError::InvalidData)
             .attach_printable(format!("Failed to read {}", config_file))
@@ -225,7 +248,7 @@ pub fn parse_system_setting(config_key: &[String]) -> Result<String, DMError> {
         }
 
         if !s.trim().is_empty() {
-            log.insert("storage_name", JsonValue::String(fix_str(s)));
+            log.insert("storage_name", JsonValue::String(fix_storage_name(s)?));
         }
 
         if !p.trim().is_empty() {
@@ -270,7 +293,7 @@ pub fn parse_system_setting(config_key: &[String]) -> Result<String, DMError> {
         }
 
         if !s.trim().is_empty() {
-            log.insert("storage_name", JsonValue::String(fix_str(s)));
+            log.insert("storage_name", JsonValue::String(fix_storage_name(s)?));
         }
 
         if !p.trim().is_empty() {
@@ -315,7 +338,7 @@ pub fn parse_system_setting(config_key: &[String]) -> Result<String, DMError> {
         }
 
         if !s.trim().is_empty() {
-            log.insert("storage_name", JsonValue::String(fix_str(s)));
+            log.insert("storage_name", JsonValue::String(fix_storage_name(s)?));
         }
 
         if !p.trim().is_empty() {
@@ -362,7 +385,7 @@ pub fn parse_system_setting(config_key: &[String]) -> Result<String, DMError> {
         }
 
         if !s.trim().is_empty() {
-            log.insert("storage_name", JsonValue::String(fix_str(s)));
+            log.insert("storage_name", JsonValue::String(fix_storage_name(s)?));
         }
 
         if !p.trim().is_empty() {
@@ -409,7 +432,7 @@ pub fn parse_system_setting(config_key: &[String]) -> Result<String, DMError> {
         }
 
         if !s.trim().is_empty() {
-            log.insert("storage_name", JsonValue::String(fix_str(s)));
+            log.insert("storage_name", JsonValue::String(fix_storage_name(s)?));
         }
 
         if !p.trim().is_empty() {
@@ -428,7 +451,7 @@ pub fn parse_system_setting(config_key: &[String]) -> Result<String, DMError> {
 
     if !json.is_empty() {
         let mut req_id = Object::new();
-        let uuid = UUID::new().uuid().to_owned();
+        let uuid = UUID::new_string();
         req_id.insert("req_id", JsonValue::String(uuid));
         json.insert("req_info", JsonValue::Object(req_id));
         let mut root = Object::new();
@@ -574,7 +597,7 @@ pub fn parse_network_settings(config_key: &[String]) -> Result<String, DMError>
 
     if !json.is_empty() {
         let mut req_id = Object::new();
-        let uuid = UUID::new().uuid().to_owned();
+        let uuid = UUID::new_string();
         req_id.insert("req_id", JsonValue::String(uuid));
         json.insert("req_info", JsonValue::Object(req_id));
 
@@ -616,7 +639,7 @@ pub fn parse_wireless_settings(config_key: &[String]) -> Result<String, DMError>
 
     if !sta_mod.is_empty() {
         let mut req_id = Object::new();
-        let uuid = UUID::new().uuid().to_owned();
+        let uuid = UUID::new_string();
         req_id.insert("req_id", JsonValue::String(uuid));
         json.insert("req_info", JsonValue::Object(req_id));
         json.insert("sta_mode_setting", JsonValue::Object(sta_mod));
@@ -806,4 +829,50 @@ mod tests {
         // input with surrounding whitespace and four quotes should be treated as the sentinel
         assert_eq!(fix_str("  \"\"\"\"  "), "".to_owned());
     }
+
+    #[test]
+    fn test_fix_storage_name() {
+        assert_eq!(fix_storage_name(r#""""#).unwrap(), "");
+        assert_eq!(
+            fix_storage_name(" B218F90B9228423F8E02A6D3527BC15D ").unwrap(),
+            "b218f90b-9228-423f-8e02-a6d3527bc15d"
+        );
+
+        let e = fix_storage_name("my-storage").unwrap_err();
+        assert!(
+            e.error_str()
+                .unwrap()
+                .starts_with("Invalid storage_name: Invalid UUID 'my-storage'")
+        );
+    }
+
+    #[test]
+    fn test_dirty_config_keys() {
+        let mut config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        let mut dirty = vec![false; ConfigKey::size()];
+        config_keys[usize::from(ConfigKey::IpMethod)] = "0".to_owned();
+        config_keys[usize::from(ConfigKey::ProxyUrl)] = "proxy.lan".to_owned();
+        config_keys[usize::from(ConfigKey::ProxyPort)] = "8080".to_owned();
+        // Set back to the same value after an edit, it is sent all the same
+        config_keys[usize::from(ConfigKey::ProxyPort)] = "3128".to_owned();
+        dirty[usize::from(ConfigKey::ProxyPort)] = true;
+
+        let changed = dirty_config_keys(&config_keys, &dirty);
+        assert_eq!(changed.len(), config_keys.len());
+        assert_eq!(changed[usize::from(ConfigKey::IpMethod)], "");
+        assert_eq!(changed[usize::from(ConfigKey::ProxyUrl)], "");
+        assert_eq!(changed[usize::from(ConfigKey::ProxyPort)], "3128");
+
+        // Only the port is left in the proxy settings
+        let json = json::parse(&parse_network_settings(&changed).unwrap()).unwrap();
+        let settings = json::parse(
+            json["configuration/$system/network_settings"]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(settings["ip_method"].is_null());
+        assert!(settings["proxy_settings"]["proxy_url"].is_null());
+        assert_eq!(settings["proxy_settings"]["proxy_port"], 3128);
+    }
 }
@@ -18,7 +18,7 @@ limitations under the License.
 use {
     super::ReqInfo,
     super::ResInfo,
-    crate::error::DMError,
+    crate::{device_config::ConfigKey, error::DMError},
     error_stack::{Report, Result},
     json::JsonValue,
     serde::{Deserialize, Serialize},
@@ -26,6 +26,40 @@ use {
     std::fmt::Display,
 };
 
+/// Filters of the log settings, in the order of their ConfigKeys
+pub const LOG_FILTERS: [&str; 5] = ["all", "main", "sensor", "companion_fw", "companion_app"];
+
+/// Reported value as entered in a config key, None when it is empty
+fn entry(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_owned())
+}
+
+/// Displayed instead of a password, whatever its length
+pub const SECRET_MASK: &str = "********";
+
+pub fn mask_secret(secret: &str) -> &str {
+    if secret.is_empty() { "" } else { SECRET_MASK }
+}
+
+/// Masks the passwords of the settings serialized as JSON
+pub fn mask_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if key.ends_with("password")
+                    && let serde_json::Value::String(s) = v
+                {
+                    *s = mask_secret(s).to_owned();
+                } else {
+                    mask_secrets(v);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(mask_secrets),
+        _ => {}
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
 pub struct AiModel {
     pub version: String,
@@ -258,6 +292,22 @@ impl PowerStates {
     pub fn is_battery_low(&self) -> bool {
         self.is_battery_low
     }
+
+    /// The device runs on its primary or secondary battery
+    pub fn on_battery(&self) -> bool {
+        matches!(self.in_use, 3 | 4)
+    }
+
+    /// Level of the battery in use, or else of the first battery, in percent
+    pub fn battery_level(&self) -> Option<i8> {
+        let is_battery = |s: &&PowerSource| matches!(s._type, 3 | 4);
+        self.source
+            .iter()
+            .filter(is_battery)
+            .find(|s| s._type == self.in_use)
+            .or_else(|| self.source.iter().find(is_battery))
+            .map(|s| s.level)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -482,6 +532,32 @@ impl SystemSettings {
     pub fn res_info(&self) -> &ResInfo {
         &self.res_info
     }
+
+    /// Reported value of a config key of the system settings configuration screen
+    pub fn config_value(&self, config_key: ConfigKey) -> Option<String> {
+        match config_key {
+            ConfigKey::LedEnabled => self.led_enabled.map(|v| v.to_string()),
+            ConfigKey::TemperatureUpdateInterval => {
+                self.temperature_update_interval.map(|v| v.to_string())
+            }
+            _ => {
+                let index = usize::from(config_key)
+                    .checked_sub(ConfigKey::AllLogSettingLevel.into())
+                    .filter(|i| *i < LOG_FILTERS.len() * 4)?;
+                let log_setting = self
+                    .log_settings
+                    .as_ref()?
+                    .iter()
+                    .find(|l| l.filter == LOG_FILTERS[index / 4])?;
+                match index % 4 {
+                    0 => Some(log_setting.level.to_string()),
+                    1 => Some(log_setting.destination.to_string()),
+                    2 => entry(&log_setting.storage_name),
+                    _ => entry(&log_setting.path),
+                }
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
@@ -544,6 +620,9 @@ pub struct NetworkSettings {
     static_settings_ipv6: Option<IpSetting>,
     static_settings_ipv4: Option<IpSetting>,
     proxy_settings: Option<ProxySettings>,
+    /// Link status of the interface, only reported by some firmware
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    interface_status: Option<String>,
     res_info: ResInfo,
 }
 
@@ -577,9 +656,38 @@ impl NetworkSettings {
         self.proxy_settings.as_ref()
     }
 
+    pub fn interface_status(&self) -> Option<&str> {
+        self.interface_status.as_deref()
+    }
+
     pub fn res_info(&self) -> &ResInfo {
         &self.res_info
     }
+
+    /// Reported value of a config key of the network settings configuration screen
+    pub fn config_value(&self, config_key: ConfigKey) -> Option<String> {
+        let ipv4 = self.static_settings_ipv4.as_ref();
+        let ipv6 = self.static_settings_ipv6.as_ref();
+        let proxy = self.proxy_settings.as_ref();
+
+        match config_key {
+            ConfigKey::IpMethod => self.ip_method.map(|v| v.to_string()),
+            ConfigKey::NtpUrl => entry(self.ntp_url()),
+            ConfigKey::StaticIpv4Ip => ipv4.and_then(|s| entry(s.ip_address())),
+            ConfigKey::StaticIpv4SubnetMask => ipv4.and_then(|s| entry(s.subnet_mask())),
+            ConfigKey::StaticIpv4Gateway => ipv4.and_then(|s| entry(s.gateway())),
+            ConfigKey::StaticIpv4Dns => ipv4.and_then(|s| entry(s.dns())),
+            ConfigKey::StaticIpv6Ip => ipv6.and_then(|s| entry(s.ip_address())),
+            ConfigKey::StaticIpv6SubnetMask => ipv6.and_then(|s| entry(s.subnet_mask())),
+            ConfigKey::StaticIpv6Gateway => ipv6.and_then(|s| entry(s.gateway())),
+            ConfigKey::StaticIpv6Dns => ipv6.and_then(|s| entry(s.dns())),
+            ConfigKey::ProxyUrl => proxy.and_then(|p| entry(p.url())),
+            ConfigKey::ProxyPort => proxy.map(|p| p.port().to_string()),
+            ConfigKey::ProxyUserName => proxy.and_then(|p| p.user_name()).and_then(entry),
+            ConfigKey::ProxyPassword => proxy.and_then(|p| p.password()).and_then(entry),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -622,6 +730,11 @@ impl StationModeSetting {
 pub struct WirelessSettings {
     req_info: ReqInfo,
     sta_mode_setting: Option<StationModeSetting>,
+    /// Signal strength in dBm and connection state, only reported by some firmware
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rssi: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    connection_state: Option<String>,
     res_info: ResInfo,
 }
 
@@ -634,13 +747,67 @@ impl WirelessSettings {
         self.sta_mode_setting.as_ref()
     }
 
+    pub fn rssi(&self) -> Option<i32> {
+        self.rssi
+    }
+
+    pub fn connection_state(&self) -> Option<&str> {
+        self.connection_state.as_deref()
+    }
+
     pub fn res_info(&self) -> &ResInfo {
         &self.res_info
     }
+
+    /// Reported value of a config key of the wireless settings configuration screen
+    pub fn config_value(&self, config_key: ConfigKey) -> Option<String> {
+        let sta = self.sta_mode_setting.as_ref()?;
+        match config_key {
+            ConfigKey::StaSsid => entry(&sta.ssid),
+            ConfigKey::StaPassword => entry(&sta.password),
+            ConfigKey::StaEncryption if sta.encryption != u8::MAX => {
+                Some(sta.encryption.to_string())
+            }
+            _ => None,
+        }
+    }
 }
 
 mod tests {
 
+    #[test]
+    fn test_mask_secrets() {
+        let mut value = serde_json::json!({
+            "proxy_settings": { "proxy_user_name": "me", "proxy_password": "p4ss" },
+            "sta_mode_setting": { "ssid": "lab", "password": "" },
+        });
+        super::mask_secrets(&mut value);
+        assert_eq!(value["proxy_settings"]["proxy_password"], "********");
+        assert_eq!(value["proxy_settings"]["proxy_user_name"], "me");
+        assert_eq!(value["sta_mode_setting"]["password"], "");
+    }
+
+    #[test]
+    fn test_wireless_settings_optional_status() {
+        use super::WirelessSettings;
+        let reported = r#"{
+            "req_info": { "req_id": "" },
+            "sta_mode_setting": { "ssid": "lab", "password": "", "encryption": 0 },
+            "rssi": -58,
+            "connection_state": "connected",
+            "res_info": { "res_id": "", "code": 0, "detail_msg": "ok" }
+        }"#;
+        let wireless: WirelessSettings = serde_json::from_str(reported).unwrap();
+        assert_eq!(wireless.rssi(), Some(-58));
+        assert_eq!(wireless.connection_state(), Some("connected"));
+
+        let configured: WirelessSettings = serde_json::from_str(
+            r#"{ "req_info": { "req_id": "" }, "res_info": { "res_id": "", "code": 0, "detail_msg": "" } }"#,
+        )
+        .unwrap();
+        assert_eq!(configured.rssi(), None);
+    }
+
     #[test]
     fn test_reserved_parse_01() {
         use super::DeviceReserved;
@@ -676,6 +843,80 @@ mod tests {
         assert_eq!(system_settings.res_info().code, 0);
     }
 
+    #[test]
+    fn test_settings_config_value() {
+        use super::{NetworkSettings, SystemSettings, WirelessSettings};
+        use crate::device_config::ConfigKey;
+
+        let system: SystemSettings = serde_json::from_str(
+            r#"{
+            "req_info": {"req_id": ""},
+            "led_enabled": false,
+            "log_settings": [
+                {"filter": "sensor", "level": 3, "destination": 1, "storage_name": "", "path": "logs"}
+            ],
+            "res_info": {"res_id": "", "code": 0, "detail_msg": "ok"}
+        }"#,
+        )
+        .unwrap();
+        let value = |key| system.config_value(key);
+        assert_eq!(value(ConfigKey::LedEnabled).as_deref(), Some("false"));
+        assert_eq!(value(ConfigKey::TemperatureUpdateInterval), None);
+        assert_eq!(
+            value(ConfigKey::SensorLogSettingLevel).as_deref(),
+            Some("3")
+        );
+        assert_eq!(
+            value(ConfigKey::SensorLogSettingDestination).as_deref(),
+            Some("1")
+        );
+        assert_eq!(value(ConfigKey::SensorLogSettingStorageName), None);
+        assert_eq!(
+            value(ConfigKey::SensorLogSettingPath).as_deref(),
+            Some("logs")
+        );
+        assert_eq!(value(ConfigKey::MainLogSettingLevel), None);
+        assert_eq!(value(ConfigKey::IpMethod), None);
+
+        let network: NetworkSettings = serde_json::from_str(
+            r#"{
+            "req_info": {"req_id": ""},
+            "ip_method": 1,
+            "static_settings_ipv4": {"ip_address": "192.168.0.10", "dns_address": ""},
+            "proxy_settings": {"proxy_url": "proxy.lan", "proxy_port": 3128},
+            "res_info": {"res_id": "", "code": 0, "detail_msg": "ok"}
+        }"#,
+        )
+        .unwrap();
+        let value = |key| network.config_value(key);
+        assert_eq!(value(ConfigKey::IpMethod).as_deref(), Some("1"));
+        assert_eq!(
+            value(ConfigKey::StaticIpv4Ip).as_deref(),
+            Some("192.168.0.10")
+        );
+        assert_eq!(value(ConfigKey::StaticIpv4Dns), None);
+        assert_eq!(value(ConfigKey::ProxyPort).as_deref(), Some("3128"));
+        assert_eq!(value(ConfigKey::ProxyUserName), None);
+
+        let wireless: WirelessSettings = serde_json::from_str(
+            r#"{
+            "req_info": {"req_id": ""},
+            "sta_mode_setting": {"ssid": "lab", "password": "", "encryption": 2},
+            "res_info": {"res_id": "", "code": 0, "detail_msg": "ok"}
+        }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            wireless.config_value(ConfigKey::StaSsid).as_deref(),
+            Some("lab")
+        );
+        assert_eq!(wireless.config_value(ConfigKey::StaPassword), None);
+        assert_eq!(
+            wireless.config_value(ConfigKey::StaEncryption).as_deref(),
+            Some("2")
+        );
+    }
+
     #[test]
     fn test_ai_model_accessors() {
         use crate::ai_model::AiModel;
@@ -738,6 +979,22 @@ mod tests {
         assert_eq!(ps.power_sources(), "50@poe,10@usb,5@dc_plug");
         assert_eq!(ps.power_sources_in_use(), "PoE");
         assert!(ps.is_battery_low());
+        assert!(!ps.on_battery());
+        assert_eq!(ps.battery_level(), None);
+
+        let ps = PowerStates {
+            source: vec![
+                p3,
+                PowerSource {
+                    _type: 4,
+                    level: 40,
+                },
+            ],
+            in_use: 4,
+            is_battery_low: false,
+        };
+        assert!(ps.on_battery());
+        assert_eq!(ps.battery_level(), Some(40));
     }
 
     #[test]
@@ -29,13 +29,12 @@ use {
     crate::mqtt_ctrl::MqttCtrl,
     crate::mqtt_ctrl::evp::evp_state::UUID as EvpUUID,
     crate::{
-        app::{App, ConfigKey},
+        device_config::{ConfigKey, config_dir},
         error::{DMError, DMErrorExt},
     },
     error_stack::{Context, Report, Result, ResultExt},
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
     json::{JsonValue, object::Object},
-    pest::{Parser, Token},
     regex::Regex,
     rumqttc::{Client, Connection, MqttOptions, QoS},
     serde::{Deserialize, Serialize},
@@ -496,6 +495,104 @@ impl CommonSettings {
     pub fn upload_interval(&self) -> Option<i32> {
         self.upload_interval
     }
+
+    /// Reported value of a common settings config key, formatted the way it is entered
+    pub fn config_value(&self, config_key: ConfigKey) -> Option<String> {
+        fn value<T: ToString>(v: Option<T>) -> Option<String> {
+            v.map(|v| v.to_string())
+        }
+
+        let pq = self.pq_settings.as_ref();
+        let size = pq.and_then(|p| p.camera_image_size.as_ref());
+        let frame_rate = pq.and_then(|p| p.frame_rate.as_ref());
+        let flip = pq.and_then(|p| p.camera_image_flip.as_ref());
+        let ae = pq.and_then(|p| p.auto_exposure.as_ref());
+        let me = pq.and_then(|p| p.manual_exposure.as_ref());
+        let mwb_gain = pq.and_then(|p| p.manual_white_balance_gain.as_ref());
+        let cropping = pq.and_then(|p| p.image_cropping.as_ref());
+        let port = self.port_settings.as_ref();
+        let ot = port.and_then(|p| p.metadata.as_ref());
+        let it = port.and_then(|p| p.input_tensor.as_ref());
+
+        match config_key {
+            ConfigKey::CommonSettingsProcessState => value(self.process_state),
+            ConfigKey::CommonSettingsLogLevel => value(self.log_level),
+            ConfigKey::CommonSettingsISNumberOfIterations => value(
+                self.inference_settings
+                    .as_ref()
+                    .and_then(|i| i.number_of_iterations),
+            ),
+            ConfigKey::CommonSettingsPQCameraImageSizeWidth => value(size.and_then(|s| s.width)),
+            ConfigKey::CommonSettingsPQCameraImageSizeHeight => value(size.and_then(|s| s.height)),
+            ConfigKey::CommonSettingsPQCameraImageSizeScalingPolicy => {
+                value(size.and_then(|s| s.scaling_policy))
+            }
+            ConfigKey::CommonSettingsPQFrameRateNum => value(frame_rate.and_then(|f| f.num)),
+            ConfigKey::CommonSettingsPQFrameRateDenom => value(frame_rate.and_then(|f| f.denom)),
+            ConfigKey::CommonSettingsPQDigitalZoom => value(pq.and_then(|p| p.digital_zoom)),
+            ConfigKey::CommonSettingsPQCameraImageFlipHorizontal => {
+                value(flip.and_then(|f| f.flip_horizontal))
+            }
+            ConfigKey::CommonSettingsPQCameraImageFlipVertical => {
+                value(flip.and_then(|f| f.flip_vertical))
+            }
+            ConfigKey::CommonSettingsPQExposureMode => value(pq.and_then(|p| p.exposure_mode)),
+            ConfigKey::CommonSettingsPQAeMaxExposureTime => {
+                value(ae.and_then(|a| a.max_exposure_time))
+            }
+            ConfigKey::CommonSettingsPQAeMinExposureTime => {
+                value(ae.and_then(|a| a.min_exposure_time))
+            }
+            ConfigKey::CommonSettingsPQAeMaxGain => value(ae.and_then(|a| a.max_gain)),
+            ConfigKey::CommonSettingsPQAeConvergenceSpeed => {
+                value(ae.and_then(|a| a.convergence_speed))
+            }
+            ConfigKey::CommonSettingsPQEvCompensation => value(pq.and_then(|p| p.ev_compensation)),
+            ConfigKey::CommonSettingsPQAeAntiFlickerMode => {
+                value(pq.and_then(|p| p.ae_anti_flicker_mode))
+            }
+            ConfigKey::CommonSettingsPQMeExposureTime => value(me.and_then(|m| m.exposure_time)),
+            ConfigKey::CommonSettingsPQMeGain => value(me.and_then(|m| m.gain)),
+            ConfigKey::CommonSettingsPQWhiteBalanceMode => {
+                value(pq.and_then(|p| p.white_balance_mode))
+            }
+            ConfigKey::CommonSettingsPQAwbConvergenceSpeed => value(
+                pq.and_then(|p| p.auto_white_balance.as_ref())
+                    .and_then(|a| a.convergence_speed),
+            ),
+            ConfigKey::CommonSettingsPQMWBPColorTemperature => value(
+                pq.and_then(|p| p.manual_white_balance_preset.as_ref())
+                    .and_then(|m| m.color_temperature),
+            ),
+            ConfigKey::CommonSettingsPQMWBGRed => value(mwb_gain.and_then(|g| g.red)),
+            ConfigKey::CommonSettingsPQMWBGBlue => value(mwb_gain.and_then(|g| g.blue)),
+            ConfigKey::CommonSettingsPQICLeft => value(cropping.and_then(|c| c.left)),
+            ConfigKey::CommonSettingsPQICTop => value(cropping.and_then(|c| c.top)),
+            ConfigKey::CommonSettingsPQICWidth => value(cropping.and_then(|c| c.width)),
+            ConfigKey::CommonSettingsPQICHeight => value(cropping.and_then(|c| c.height)),
+            ConfigKey::CommonSettingsPQImageRotation => value(pq.and_then(|p| p.image_rotation)),
+            ConfigKey::CommonSettingsPSMetadataMethod => value(ot.and_then(|d| d.method)),
+            ConfigKey::CommonSettingsPSMetadataStorageName => {
+                ot.and_then(|d| d.storage_name.clone())
+            }
+            ConfigKey::CommonSettingsPSMetadataEndpoint => ot.and_then(|d| d.endpoint.clone()),
+            ConfigKey::CommonSettingsPSMetadataPath => ot.and_then(|d| d.path.clone()),
+            ConfigKey::CommonSettingsPSMetadataEnabled => value(ot.and_then(|d| d.enabled)),
+            ConfigKey::CommonSettingsPSITMethod => value(it.and_then(|d| d.method)),
+            ConfigKey::CommonSettingsPSITStorageName => it.and_then(|d| d.storage_name.clone()),
+            ConfigKey::CommonSettingsPSITEndpoint => it.and_then(|d| d.endpoint.clone()),
+            ConfigKey::CommonSettingsPSITPath => it.and_then(|d| d.path.clone()),
+            ConfigKey::CommonSettingsPSITEnabled => value(it.and_then(|d| d.enabled)),
+            ConfigKey::CommonSettingsCSFormat => {
+                value(self.codec_settings.as_ref().and_then(|c| c.format))
+            }
+            ConfigKey::CommonSettingsNumberOfInferencePerMessage => {
+                value(self.number_of_inference_per_message)
+            }
+            ConfigKey::CommonSettingsUploadInterval => value(self.upload_interval),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Default, PartialEq)]
@@ -503,23 +600,77 @@ pub struct CustomSettingsPassthrough {
     ai_model_bundle_id: String,
 }
 
+/// Parameters of the detection edge app, fields not reported by the device are None
 #[derive(Debug, Default, PartialEq)]
 pub struct CustomSettingsDetectionParameters {
-    max_detections: u32,
-    threshold: f32,
-    input_width: u32,
-    input_height: u32,
-    bbox_order: String,
-    bbox_normalization: bool,
-    class_score_order: String,
+    max_detections: Option<u32>,
+    threshold: Option<f32>,
+    input_width: Option<u32>,
+    input_height: Option<u32>,
+    bbox_order: Option<String>,
+    bbox_normalization: Option<bool>,
+    class_score_order: Option<String>,
 }
 
 #[derive(Debug, Default, PartialEq)]
 pub struct CustomSettingsDetection {
-    ai_model_bundle_id: String,
+    ai_model_bundle_id: Option<String>,
     parameters: CustomSettingsDetectionParameters,
 }
 
+impl CustomSettingsDetection {
+    /// Reads `ai_models.detection` of the reported custom settings
+    pub fn parse(custom_settings: &JsonValue) -> Option<Self> {
+        let detection = &custom_settings["ai_models"]["detection"];
+        if !detection.is_object() {
+            return None;
+        }
+
+        let parameters = &detection["parameters"];
+        let string = |v: &JsonValue| v.as_str().map(|s| s.to_owned());
+
+        Some(Self {
+            ai_model_bundle_id: string(&detection["ai_model_bundle_id"]),
+            parameters: CustomSettingsDetectionParameters {
+                max_detections: parameters["max_detections"].as_u32(),
+                threshold: parameters["threshold"].as_f32(),
+                input_width: parameters["input_width"].as_u32(),
+                input_height: parameters["input_height"].as_u32(),
+                bbox_order: string(&parameters["bbox_order"]),
+                bbox_normalization: parameters["bbox_normalization"].as_bool(),
+                class_score_order: string(&parameters["class_score_order"]),
+            },
+        })
+    }
+
+    /// Values of the detection config keys, in the same order as [`DETECTION_CONFIG_KEYS`]
+    pub fn config_values(&self) -> [Option<String>; 8] {
+        let p = &self.parameters;
+        [
+            self.ai_model_bundle_id.clone(),
+            p.max_detections.map(|v| v.to_string()),
+            p.threshold.map(|v| v.to_string()),
+            p.input_width.map(|v| v.to_string()),
+            p.input_height.map(|v| v.to_string()),
+            p.bbox_order.clone(),
+            p.bbox_normalization.map(|v| v.to_string()),
+            p.class_score_order.clone(),
+        ]
+    }
+}
+
+/// Config keys of the detection custom settings
+pub const DETECTION_CONFIG_KEYS: [ConfigKey; 8] = [
+    ConfigKey::CustomSettingsDetectionAiModelBundleId,
+    ConfigKey::CustomSettingsDetectionMaxDetections,
+    ConfigKey::CustomSettingsDetectionThreshold,
+    ConfigKey::CustomSettingsDetectionInputWidth,
+    ConfigKey::CustomSettingsDetectionInputHeight,
+    ConfigKey::CustomSettingsDetectionBboxOrder,
+    ConfigKey::CustomSettingsDetectionBboxNormalization,
+    ConfigKey::CustomSettingsDetectionClassScoreOrder,
+];
+
 #[derive(Debug, Default, PartialEq)]
 pub struct CustomSettingsMetaSettings {
     format: i8,
@@ -587,6 +738,22 @@ impl EdgeApp {
         self.custom_settings.as_ref()
     }
 
+    /// Reported value of a config key of the EdgeApp configuration screen
+    pub fn config_value(&self, config_key: ConfigKey) -> Option<String> {
+        let custom_settings = self.custom_settings.as_ref();
+
+        if let Some(index) = DETECTION_CONFIG_KEYS.iter().position(|k| *k == config_key) {
+            return custom_settings
+                .and_then(|c| c.ai_model_detection())
+                .and_then(|d| d.config_values()[index].clone());
+        }
+
+        match config_key {
+            ConfigKey::CustomSettings => custom_settings.and_then(|c| c.custom().cloned()),
+            _ => self.common_settings.config_value(config_key),
+        }
+    }
+
     pub fn parse(payload: &str) -> Result<Self, DMError> {
         jdebug!(func = "EdgeApp::parse()", line = line!(), payload = payload);
 
@@ -660,6 +827,7 @@ impl EdgeApp {
                             value = value.dump()
                         );
                         custom_settings = Some(CustomSettings {
+                            ai_model_detection: CustomSettingsDetection::parse(value),
                             custom: Some(JsonUtility::json_value_to_string(value)),
                             ..Default::default()
                         })
@@ -683,9 +851,242 @@ impl EdgeApp {
     }
 }
 
-#[derive(pest_derive::Parser)]
-#[grammar = "src/mqtt_ctrl/evp/evp.pest"]
-struct EvpParser;
+/// Validates freeform custom settings, which must be a JSON object since their schema
+/// depends on the edge app
+pub fn parse_custom_settings(value: &str) -> Result<JsonValue, DMError> {
+    let custom_settings = json::parse(value).map_err(|e| {
+        Report::new(DMError::InvalidData)
+            .attach_printable(format!("Invalid custom_settings: {}", e))
+    })?;
+
+    if !custom_settings.is_object() {
+        return Err(Report::new(DMError::InvalidData)
+            .attach_printable("Invalid custom_settings: not a JSON object"));
+    }
+
+    Ok(custom_settings)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffKind {
+    /// Same as the reported value
+    Unchanged,
+    /// Different from the reported value
+    Changed,
+    /// Not reported by the instance
+    Added,
+}
+
+/// Difference between an entered value and the value reported by the instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDiff {
+    pub config_key: ConfigKey,
+    pub reported: Option<String>,
+    pub desired: String,
+    pub kind: DiffKind,
+}
+
+/// Compares values ignoring their formatting, e.g. "0.30" and "0.3" or JSON whitespace
+fn same_value(config_key: ConfigKey, reported: &str, desired: &str) -> bool {
+    if config_key == ConfigKey::CustomSettings {
+        return match (json::parse(reported), json::parse(desired)) {
+            (Ok(r), Ok(d)) => r == d,
+            _ => reported.trim() == desired.trim(),
+        };
+    }
+
+    match (reported.parse::<f64>(), desired.trim().parse::<f64>()) {
+        (Ok(r), Ok(d)) => (r - d).abs() < 1e-6,
+        _ => reported == desired.trim(),
+    }
+}
+
+/// Field by field difference between the entered values of `config_keys` and the state
+/// reported by the instance. Only the entered values are compared.
+pub fn config_diff(
+    edge_app: Option<&EdgeApp>,
+    config_keys: &[String],
+    range: std::ops::RangeInclusive<usize>,
+) -> Vec<ConfigDiff> {
+    range
+        .filter_map(|key| {
+            let desired = config_keys.get(key).filter(|v| !v.trim().is_empty())?;
+            let config_key = ConfigKey::from(key);
+            let reported = edge_app.and_then(|e| e.config_value(config_key));
+            let kind = match reported.as_deref() {
+                None => DiffKind::Added,
+                Some(r) if same_value(config_key, r, desired) => DiffKind::Unchanged,
+                Some(_) => DiffKind::Changed,
+            };
+
+            Some(ConfigDiff {
+                config_key,
+                reported,
+                desired: desired.clone(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Cross-field constraints of the exposure and white balance settings which the device would
+/// reject or ignore. The entered values are checked against the entered modes, or the
+/// reported ones when no mode is entered. Returns a message for each violation.
+pub fn pq_violations(config_keys: &[String], reported: &EdgeApp) -> Vec<String> {
+    let entered = |config_key: ConfigKey| {
+        config_keys
+            .get(usize::from(config_key))
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    };
+    let value = |config_key: ConfigKey| {
+        entered(config_key)
+            .map(|v| v.to_owned())
+            .or_else(|| reported.config_value(config_key))
+    };
+    let number = |config_key: ConfigKey| value(config_key).and_then(|v| v.parse::<i64>().ok());
+
+    let mut violations = vec![];
+
+    if let (Some(min), Some(max)) = (
+        number(ConfigKey::CommonSettingsPQAeMinExposureTime),
+        number(ConfigKey::CommonSettingsPQAeMaxExposureTime),
+    ) && min > max
+    {
+        violations.push(format!(
+            "{} ({}us) must be less than or equal to {} ({}us)",
+            ConfigKey::CommonSettingsPQAeMinExposureTime,
+            min,
+            ConfigKey::CommonSettingsPQAeMaxExposureTime,
+            max
+        ));
+    }
+
+    // Manual fields are only interpreted in the manual mode, (mode key, manual value, fields)
+    let manual_fields = [
+        (
+            ConfigKey::CommonSettingsPQExposureMode,
+            (1, "manual"),
+            vec![
+                ConfigKey::CommonSettingsPQMeExposureTime,
+                ConfigKey::CommonSettingsPQMeGain,
+            ],
+        ),
+        (
+            ConfigKey::CommonSettingsPQWhiteBalanceMode,
+            (1, "preset"),
+            vec![ConfigKey::CommonSettingsPQMWBPColorTemperature],
+        ),
+    ];
+    for (mode_key, (manual, manual_name), fields) in manual_fields {
+        let mode = number(mode_key);
+        if let Some(mode) = mode
+            && !(0..=1).contains(&mode)
+        {
+            violations.push(format!("{} must be 0 or 1, not {}", mode_key, mode));
+            continue;
+        }
+
+        let set: Vec<String> = fields
+            .into_iter()
+            .filter(|f| entered(*f).is_some())
+            .map(|f| f.to_string())
+            .collect();
+        if !set.is_empty() && mode != Some(manual) {
+            let mode = mode
+                .map(|m| format!("is {}", m))
+                .unwrap_or("is not set".to_owned());
+            violations.push(format!(
+                "{} only applies when {} is {} ({}), but it {}",
+                set.join(", "),
+                mode_key,
+                manual,
+                manual_name,
+                mode
+            ));
+        }
+    }
+
+    if let Some(temperature) = entered(ConfigKey::CommonSettingsPQMWBPColorTemperature)
+        && !temperature
+            .parse::<i64>()
+            .is_ok_and(|t| (0..=3).contains(&t))
+    {
+        violations.push(format!(
+            "{} must be one of the presets 0: 3200K, 1: 4300K, 2: 5600K, 3: 6500K, not {}",
+            ConfigKey::CommonSettingsPQMWBPColorTemperature,
+            temperature
+        ));
+    }
+
+    violations
+}
+
+/// Detection custom settings entered on the screen, None when nothing is entered
+fn detection_custom_settings(config_keys: &[String]) -> Result<Option<JsonValue>, DMError> {
+    let mut detection = JsonValue::new_object();
+    let mut parameters = JsonValue::new_object();
+
+    for config_key in DETECTION_CONFIG_KEYS {
+        let Some(value) = config_keys
+            .get(config_key as usize)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+        else {
+            continue;
+        };
+
+        let invalid = |e: &dyn std::fmt::Display| {
+            Report::new(DMError::InvalidData)
+                .attach_printable(format!("Invalid {}: {}", config_key, e))
+        };
+
+        let json_value = match config_key {
+            ConfigKey::CustomSettingsDetectionMaxDetections
+            | ConfigKey::CustomSettingsDetectionInputWidth
+            | ConfigKey::CustomSettingsDetectionInputHeight => {
+                JsonValue::from(value.parse::<u32>().map_err(|e| invalid(&e))?)
+            }
+            ConfigKey::CustomSettingsDetectionThreshold => {
+                let threshold = value.parse::<f32>().map_err(|e| invalid(&e))?;
+                if !(0.0..=1.0).contains(&threshold) {
+                    return Err(invalid(&"must be between 0.0 and 1.0"));
+                }
+                JsonValue::from(threshold)
+            }
+            ConfigKey::CustomSettingsDetectionBboxNormalization => {
+                JsonValue::from(value.parse::<bool>().map_err(|e| invalid(&e))?)
+            }
+            _ => JsonValue::from(value),
+        };
+
+        let key = config_key.to_string();
+        let key = key.trim_start_matches("detection.");
+        if config_key == ConfigKey::CustomSettingsDetectionAiModelBundleId {
+            detection[key] = json_value;
+        } else {
+            parameters[key] = json_value;
+        }
+    }
+
+    if !parameters.is_empty() {
+        detection["parameters"] = parameters;
+    }
+
+    Ok((!detection.is_empty()).then_some(detection))
+}
+
+/// Merges `value` into `target`, values of `value` take precedence
+fn merge_json(target: &mut JsonValue, value: JsonValue) {
+    match value {
+        JsonValue::Object(o) if target.is_object() || target.is_null() => {
+            for (k, v) in o.iter() {
+                merge_json(&mut target[k], v.clone());
+            }
+        }
+        value => *target = value,
+    }
+}
 
 #[derive(Debug, Default, PartialEq)]
 pub struct EdgeAppInfo {
@@ -696,28 +1097,17 @@ pub struct EdgeAppInfo {
 #[allow(unused)]
 impl EdgeAppInfo {
     pub fn parse(key: &str, payload: &str) -> Result<Self, DMError> {
-        let pairs = EvpParser::parse(Rule::edge_app, key)
-            .map_err(|e| Report::new(DMError::InvalidData).attach_printable(e))?;
-        let mut id_start = 0;
-        let mut id_end = 0;
-
-        for token in pairs.tokens() {
-            match token {
-                Token::Start { rule, pos } => {
-                    if rule == Rule::uuid {
-                        id_start = pos.pos()
-                    }
-                }
-
-                Token::End { rule, pos } => {
-                    if rule == Rule::uuid {
-                        id_end = pos.pos()
-                    }
-                }
-            }
-        }
-
-        let id = key[id_start..id_end].to_string();
+        // "state/<uuid>/edge_app"
+        let id = key
+            .strip_prefix("state/")
+            .and_then(|rest| rest.split_once('/'))
+            .filter(|(_, rest)| rest.starts_with("edge_app"))
+            .map(|(id, _)| id)
+            .ok_or_else(|| {
+                Report::new(DMError::InvalidData)
+                    .attach_printable(format!("Not an edge app state: {key}"))
+            })?;
+        let id = EvpUUID::from(id)?.to_string();
 
         let module = EdgeApp::parse(payload).map_err(|e| {
             Report::new(DMError::InvalidData)
@@ -727,6 +1117,14 @@ impl EdgeAppInfo {
         Ok(Self { id, module })
     }
 
+    /// Instance which hasn't reported its state yet, only used to generate its configuration
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_owned(),
+            ..Default::default()
+        }
+    }
+
     pub fn id(&self) -> &str {
         &self.id
     }
@@ -736,6 +1134,14 @@ impl EdgeAppInfo {
     }
 
     pub fn parse_configure(&self, config_keys: &[String]) -> Result<String, DMError> {
+        let violations = pq_violations(config_keys, &self.module);
+        if !violations.is_empty() {
+            return Err(Report::new(DMError::InvalidData).attach_printable(format!(
+                "Invalid PQ settings:\n- {}",
+                violations.join("\n- ")
+            )));
+        }
+
         let mut edge_app = Object::new();
         enum EntryType {
             NumericI8,
@@ -744,6 +1150,8 @@ impl EdgeAppInfo {
             NumericU32,
             NumericFloat,
             StringType,
+            /// Token provider UUID, with or without hyphens
+            UuidType,
             BoolType,
         }
 
@@ -793,6 +1201,14 @@ impl EdgeAppInfo {
                     EntryType::StringType => {
                         root.insert(key, JsonValue::String(value.to_string()));
                     }
+                    EntryType::UuidType => {
+                        let uuid = EvpUUID::from(value).map_err(|e| {
+                            let reason = e.error_str().unwrap_or_default();
+                            Report::new(DMError::InvalidData)
+                                .attach_printable(format!("Invalid {}: {}", key, reason))
+                        })?;
+                        root.insert(key, JsonValue::String(uuid.to_string()));
+                    }
                     EntryType::BoolType => {
                         let value_bool: bool = value.parse().map_err(|e| {
                             Report::new(DMError::InvalidData)
@@ -808,9 +1224,7 @@ impl EdgeAppInfo {
         // Req info
         {
             let mut req_info = Object::new();
-            let uuid = EvpUUID::new();
-
-            req_info.insert("req_id", JsonValue::String(uuid.uuid().to_string()));
+            req_info.insert("req_id", JsonValue::String(EvpUUID::new_string()));
             edge_app.insert("req_info", JsonValue::Object(req_info));
         }
 
@@ -1156,7 +1570,7 @@ impl EdgeAppInfo {
                         &mut metadata,
                         ConfigKey::CommonSettingsPSMetadataStorageName as usize,
                         "storage_name",
-                        EntryType::StringType,
+                        EntryType::UuidType,
                     )?;
 
                     json_entry(
@@ -1200,7 +1614,7 @@ impl EdgeAppInfo {
                         &mut input_tensor,
                         ConfigKey::CommonSettingsPSITStorageName as usize,
                         "storage_name",
-                        EntryType::StringType,
+                        EntryType::UuidType,
                     )?;
 
                     json_entry(
@@ -1271,13 +1685,23 @@ impl EdgeAppInfo {
             }
         }
 
-        // Custom settings
-        if let Ok(custom_settings) = std::fs::read_to_string(format!(
-            "{}/edge_app_custom_settings.json",
-            App::config_dir()
-        )) {
-            let custom_settings = json::parse(&custom_settings)
-                .map_err(|e| Report::new(DMError::InvalidData).attach_printable(e))?;
+        // Custom settings, the JSON entered on the screen takes precedence over the file
+        let custom_settings = config_keys
+            .get(ConfigKey::CustomSettings as usize)
+            .filter(|v| !v.trim().is_empty())
+            .cloned()
+            .or_else(|| {
+                std::fs::read_to_string(format!("{}/edge_app_custom_settings.json", config_dir()))
+                    .ok()
+            });
+        let mut custom_settings = match custom_settings {
+            Some(custom_settings) => parse_custom_settings(&custom_settings)?,
+            None => JsonValue::new_object(),
+        };
+        if let Some(detection) = detection_custom_settings(config_keys)? {
+            merge_json(&mut custom_settings["ai_models"]["detection"], detection);
+        }
+        if !custom_settings.is_empty() {
             edge_app.insert("custom_settings", custom_settings);
         }
 
@@ -1397,4 +1821,275 @@ mod tests {
         assert!(custom_str.contains("\"foo\":\"bar\""));
         assert!(custom_str.contains("\"nested\""));
     }
+
+    #[test]
+    fn test_edge_app_info_new_configures_instance() {
+        use crate::{device_config::ConfigKey, mqtt_ctrl::EdgeAppInfo};
+        let edge_app = EdgeAppInfo::new("c8fba53c-ffd9-439b-849d-000000000002");
+        let config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+
+        let config = edge_app.parse_configure(&config_keys).unwrap();
+        let root = json::parse(&config).unwrap();
+        assert!(root.has_key("configure/c8fba53c-ffd9-439b-849d-000000000002/edge_app"));
+    }
+
+    #[test]
+    fn test_edge_app_storage_name_is_a_uuid() {
+        use crate::{device_config::ConfigKey, error::DMErrorExt, mqtt_ctrl::EdgeAppInfo};
+        let edge_app = EdgeAppInfo::new("c8fba53c-ffd9-439b-849d-000000000002");
+        let mut config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+
+        let storage_name = usize::from(ConfigKey::CommonSettingsPSMetadataStorageName);
+        config_keys[storage_name] = "B218F90B9228423F8E02A6D3527BC15D".to_owned();
+        let config = edge_app.parse_configure(&config_keys).unwrap();
+        assert!(config.contains("b218f90b-9228-423f-8e02-a6d3527bc15d"));
+
+        config_keys[storage_name] = "my-storage".to_owned();
+        let e = edge_app.parse_configure(&config_keys).unwrap_err();
+        assert!(
+            e.error_str()
+                .unwrap()
+                .starts_with("Invalid storage_name: Invalid UUID 'my-storage'")
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_settings() {
+        use crate::mqtt_ctrl::evp::edge_app::parse_custom_settings;
+
+        let value = parse_custom_settings(r#"{"threshold": 0.3, "labels": ["a"]}"#).unwrap();
+        assert_eq!(value["labels"][0], "a");
+        assert!(parse_custom_settings("[1, 2]").is_err());
+        assert!(parse_custom_settings("{\"a\": ").is_err());
+    }
+
+    #[test]
+    fn test_parse_configure_custom_settings() {
+        use crate::{device_config::ConfigKey, mqtt_ctrl::EdgeAppInfo};
+
+        let edge_app = EdgeAppInfo::new("c8fba53c-ffd9-439b-849d-000000000002");
+        let mut config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        config_keys[ConfigKey::CustomSettings as usize] = r#"{"foo": {"bar": 1}}"#.to_owned();
+
+        let config = edge_app.parse_configure(&config_keys).unwrap();
+        let root = json::parse(&config).unwrap();
+        assert_eq!(
+            root["configure/c8fba53c-ffd9-439b-849d-000000000002/edge_app"]["custom_settings"]["foo"]
+                ["bar"],
+            1
+        );
+
+        config_keys[ConfigKey::CustomSettings as usize] = "not json".to_owned();
+        assert!(edge_app.parse_configure(&config_keys).is_err());
+    }
+
+    #[test]
+    fn test_detection_custom_settings_read_back() {
+        use crate::mqtt_ctrl::EdgeApp;
+
+        let json_str = r#"
+        {
+            "common_settings": { "process_state": 2 },
+            "custom_settings": {
+                "ai_models": {
+                    "detection": {
+                        "ai_model_bundle_id": "000001",
+                        "parameters": {
+                            "max_detections": 10,
+                            "threshold": 0.3,
+                            "input_width": 320,
+                            "bbox_normalization": true
+                        }
+                    }
+                }
+            }
+        }"#;
+
+        let edge_app = EdgeApp::parse(json_str).unwrap();
+        let detection = edge_app
+            .custom_settings()
+            .and_then(|c| c.ai_model_detection())
+            .expect("detection settings present");
+        assert_eq!(detection.parameters.max_detections, Some(10));
+        assert_eq!(detection.parameters.input_height, None);
+
+        let values = detection.config_values();
+        assert_eq!(values[0].as_deref(), Some("000001"));
+        assert_eq!(values[2].as_deref(), Some("0.3"));
+        assert_eq!(values[6].as_deref(), Some("true"));
+        assert_eq!(values[7], None);
+    }
+
+    #[test]
+    fn test_parse_configure_detection_settings() {
+        use crate::{device_config::ConfigKey, mqtt_ctrl::EdgeAppInfo};
+
+        let edge_app = EdgeAppInfo::new("c8fba53c-ffd9-439b-849d-000000000002");
+        let mut config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        config_keys[ConfigKey::CustomSettings as usize] =
+            r#"{"ai_models": {"detection": {"parameters": {"threshold": 0.1, "foo": 1}}}}"#
+                .to_owned();
+        config_keys[ConfigKey::CustomSettingsDetectionThreshold as usize] = "0.5".to_owned();
+        config_keys[ConfigKey::CustomSettingsDetectionMaxDetections as usize] = "5".to_owned();
+        config_keys[ConfigKey::CustomSettingsDetectionBboxOrder as usize] = "yxyx".to_owned();
+
+        let config = edge_app.parse_configure(&config_keys).unwrap();
+        let root = json::parse(&config).unwrap();
+        let parameters = &root["configure/c8fba53c-ffd9-439b-849d-000000000002/edge_app"]["custom_settings"]
+            ["ai_models"]["detection"]["parameters"];
+        assert_eq!(parameters["threshold"], 0.5);
+        assert_eq!(parameters["max_detections"], 5);
+        assert_eq!(parameters["bbox_order"], "yxyx");
+        // Values of the freeform JSON not entered on the screen are kept
+        assert_eq!(parameters["foo"], 1);
+
+        config_keys[ConfigKey::CustomSettingsDetectionThreshold as usize] = "1.5".to_owned();
+        assert!(edge_app.parse_configure(&config_keys).is_err());
+    }
+
+    #[test]
+    fn test_edge_app_config_value() {
+        use crate::{device_config::ConfigKey, mqtt_ctrl::EdgeApp};
+
+        let json_str = r#"
+        {
+            "res_info": {"res_id": "1", "code": 0, "detail_msg": ""},
+            "common_settings": {
+                "process_state": 2,
+                "pq_settings": {
+                    "camera_image_size": {"width": 2028, "height": 1520},
+                    "image_cropping": {"left": 0, "top": 0, "width": 4056, "height": 3040}
+                },
+                "port_settings": {
+                    "metadata": {"method": 0, "storage_name": "", "path": "", "enabled": true}
+                },
+                "codec_settings": {"format": 1}
+            },
+            "custom_settings": {"ai_models": {"detection": {"parameters": {"threshold": 0.5}}}}
+        }"#;
+
+        let edge_app = EdgeApp::parse(json_str).unwrap();
+        let value = |key| edge_app.config_value(key);
+        assert_eq!(
+            value(ConfigKey::CommonSettingsProcessState).as_deref(),
+            Some("2")
+        );
+        assert_eq!(
+            value(ConfigKey::CommonSettingsPQCameraImageSizeWidth).as_deref(),
+            Some("2028")
+        );
+        assert_eq!(
+            value(ConfigKey::CommonSettingsPQICWidth).as_deref(),
+            Some("4056")
+        );
+        assert_eq!(
+            value(ConfigKey::CommonSettingsPSMetadataEnabled).as_deref(),
+            Some("true")
+        );
+        assert_eq!(value(ConfigKey::CommonSettingsPSITEnabled), None);
+        assert_eq!(
+            value(ConfigKey::CommonSettingsCSFormat).as_deref(),
+            Some("1")
+        );
+        assert_eq!(value(ConfigKey::CommonSettingsUploadInterval), None);
+        assert_eq!(
+            value(ConfigKey::CustomSettingsDetectionThreshold).as_deref(),
+            Some("0.5")
+        );
+        assert!(value(ConfigKey::CustomSettings).is_some());
+    }
+
+    #[test]
+    fn test_pq_violations() {
+        use crate::{
+            device_config::ConfigKey,
+            error::DMErrorExt,
+            mqtt_ctrl::{EdgeApp, EdgeAppInfo, evp::edge_app::pq_violations},
+        };
+
+        let json_str = r#"
+        {
+            "common_settings": {
+                "pq_settings": {
+                    "exposure_mode": 1,
+                    "auto_exposure": {"max_exposure_time": 30000, "min_exposure_time": 100},
+                    "white_balance_mode": 0
+                }
+            }
+        }"#;
+        let reported = EdgeApp::parse(json_str).unwrap();
+        let mut config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        let mut set = |key: ConfigKey, value: &str| config_keys[key as usize] = value.to_owned();
+
+        // The manual exposure is entered while the reported mode is manual
+        set(ConfigKey::CommonSettingsPQMeExposureTime, "10000");
+        set(ConfigKey::CommonSettingsPQAeMaxExposureTime, "50");
+        set(ConfigKey::CommonSettingsPQMWBPColorTemperature, "5");
+        let violations = pq_violations(&config_keys, &reported);
+        assert_eq!(violations.len(), 3, "{:?}", violations);
+        assert!(violations[0].contains("min_exposure_time (100us)"));
+        assert!(violations[1].contains("only applies when PQ.white_balance_mode is 1 (preset)"));
+        assert!(violations[1].ends_with("but it is 0"));
+        assert!(violations[2].contains("presets"));
+
+        let mut set = |key: ConfigKey, value: &str| config_keys[key as usize] = value.to_owned();
+        set(ConfigKey::CommonSettingsPQAeMaxExposureTime, "");
+        set(ConfigKey::CommonSettingsPQMWBPColorTemperature, "2");
+        set(ConfigKey::CommonSettingsPQWhiteBalanceMode, "1");
+        set(ConfigKey::CommonSettingsPQExposureMode, "0");
+        let violations = pq_violations(&config_keys, &reported);
+        assert_eq!(violations.len(), 1, "{:?}", violations);
+        assert!(violations[0].starts_with("PQ.manual_exposure.exposure_time only applies"));
+
+        // The configuration is not generated with violations
+        let edge_app = EdgeAppInfo::new("c8fba53c-ffd9-439b-849d-000000000002");
+        let e = edge_app.parse_configure(&config_keys).unwrap_err();
+        assert!(e.error_str().unwrap().contains("Invalid PQ settings"));
+        config_keys[ConfigKey::CommonSettingsPQExposureMode as usize] = "1".to_owned();
+        assert!(edge_app.parse_configure(&config_keys).is_ok());
+    }
+
+    #[test]
+    fn test_config_diff() {
+        use crate::{
+            device_config::ConfigKey,
+            mqtt_ctrl::{
+                EdgeApp,
+                evp::edge_app::{DiffKind, config_diff},
+            },
+        };
+
+        let json_str = r#"
+        {
+            "common_settings": {"process_state": 1, "log_level": 3, "upload_interval": 30},
+            "custom_settings": {"a": 1, "b": [1, 2]}
+        }"#;
+        let edge_app = EdgeApp::parse(json_str).unwrap();
+
+        let mut config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        config_keys[ConfigKey::CommonSettingsProcessState as usize] = "2".to_owned();
+        config_keys[ConfigKey::CommonSettingsLogLevel as usize] = " 3".to_owned();
+        config_keys[ConfigKey::CommonSettingsPQDigitalZoom as usize] = "1.0".to_owned();
+        config_keys[ConfigKey::CustomSettings as usize] = r#"{ "b": [1,2], "a": 1 }"#.to_owned();
+
+        let range = usize::from(ConfigKey::CommonSettingsProcessState)
+            ..=usize::from(ConfigKey::CustomSettings);
+        let diff = config_diff(Some(&edge_app), &config_keys, range.clone());
+        let kinds: Vec<(ConfigKey, DiffKind)> =
+            diff.iter().map(|d| (d.config_key, d.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ConfigKey::CommonSettingsProcessState, DiffKind::Changed),
+                (ConfigKey::CommonSettingsLogLevel, DiffKind::Unchanged),
+                (ConfigKey::CommonSettingsPQDigitalZoom, DiffKind::Added),
+                (ConfigKey::CustomSettings, DiffKind::Unchanged),
+            ]
+        );
+        assert_eq!(diff[0].reported.as_deref(), Some("1"));
+
+        // Everything is new when nothing has been reported
+        let diff = config_diff(None, &config_keys, range);
+        assert!(diff.iter().all(|d| d.kind == DiffKind::Added));
+    }
 }
@@ -18,7 +18,7 @@ limitations under the License.
 use {
     super::JsonUtility,
     super::evp_state::UUID,
-    crate::error::DMError,
+    crate::{error::DMError, versioned::Versioned},
     error_stack::{Report, Result},
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
     json::JsonValue,
@@ -33,7 +33,7 @@ use {
     uuid::Uuid,
 };
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Elog {
     serial: String,
     level: u8,
@@ -48,7 +48,7 @@ pub struct Elog {
 impl Elog {
     pub fn parse(s: &str) -> Result<Self, DMError> {
         jdebug!(func = "Elog::parse", line = line!(), s = s);
-        serde_json::from_str(s).map_err(|_| Report::new(DMError::InvalidData))
+        serde_json::from_str(s).map_err(|e| Report::new(DMError::InvalidData).attach_printable(e))
     }
 
     pub fn serial(&self) -> &str {
@@ -164,6 +164,94 @@ impl Elog {
     pub fn event_description(&self) -> Option<&str> {
         self.event_description.as_deref()
     }
+
+    /// Same event as `other`, only the timestamp may differ
+    pub fn same_event(&self, other: &Elog) -> bool {
+        Elog {
+            timestamp: other.timestamp.clone(),
+            ..self.clone()
+        } == *other
+    }
+}
+
+/// Identical consecutive elogs coalesced into one, e.g. during a crash loop
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ElogEntry {
+    /// First occurrence
+    #[serde(flatten)]
+    elog: Elog,
+    count: usize,
+    first_timestamp: String,
+    last_timestamp: String,
+}
+
+impl ElogEntry {
+    pub fn new(elog: Elog) -> Self {
+        Self {
+            count: 1,
+            first_timestamp: elog.timestamp.clone(),
+            last_timestamp: elog.timestamp.clone(),
+            elog,
+        }
+    }
+
+    /// Counts `elog` in this entry if it is the same event, returns false otherwise
+    pub fn coalesce(&mut self, elog: &Elog) -> bool {
+        if !self.elog.same_event(elog) {
+            return false;
+        }
+
+        self.count += 1;
+        self.last_timestamp = elog.timestamp.clone();
+        true
+    }
+
+    pub fn elog(&self) -> &Elog {
+        &self.elog
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn first_timestamp(&self) -> &str {
+        &self.first_timestamp
+    }
+
+    pub fn last_timestamp(&self) -> &str {
+        &self.last_timestamp
+    }
+
+    /// Approximate bytes kept for this entry
+    pub fn memory_size(&self) -> usize {
+        let len = |s: &Option<String>| s.as_ref().map_or(0, |s| s.len());
+        size_of::<Self>()
+            + self.elog.serial.len()
+            + self.elog.timestamp.len()
+            + len(&self.elog.component_name)
+            + len(&self.elog.event_description)
+            + self.first_timestamp.len()
+            + self.last_timestamp.len()
+    }
+}
+
+/// Elogs saved to a file by the UI and by the daemon mode
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ElogArchive {
+    pub elogs: Vec<ElogEntry>,
+}
+
+impl Versioned for ElogArchive {
+    const KIND: &'static str = "elog archive";
+    const VERSION: u32 = 1;
+
+    /// Version 0 was the list of the elogs alone
+    fn migrate(version: u32, value: &mut serde_json::Value) -> Result<(), DMError> {
+        if version == 0 && value.is_array() {
+            *value = serde_json::json!({ "elogs": value.take() });
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -315,4 +403,53 @@ mod tests {
         let parsed = Elog::parse(&json).unwrap();
         assert_eq!(elog, parsed);
     }
+
+    #[test]
+    fn test_elog_entry_coalesce() {
+        let mut entry = ElogEntry::new(make_elog("S", 1, "t1", 2, None, 0x3010, None));
+
+        assert!(entry.coalesce(&make_elog("S", 1, "t2", 2, None, 0x3010, None)));
+        assert!(entry.coalesce(&make_elog("S", 1, "t3", 2, None, 0x3010, None)));
+        assert!(!entry.coalesce(&make_elog("S", 1, "t4", 2, None, 0x3020, None)));
+        assert!(!entry.coalesce(&make_elog("S", 0, "t4", 2, None, 0x3010, None)));
+
+        assert_eq!(entry.count(), 3);
+        assert_eq!(entry.first_timestamp(), "t1");
+        assert_eq!(entry.last_timestamp(), "t3");
+        assert_eq!(entry.elog().timestamp(), "t1");
+
+        let json: serde_json::Value = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["event_id"], 0x3010);
+        assert_eq!(json["count"], 3);
+        assert_eq!(json["first_timestamp"], "t1");
+        assert_eq!(json["last_timestamp"], "t3");
+        assert_eq!(serde_json::from_value::<ElogEntry>(json).unwrap(), entry);
+    }
+
+    #[test]
+    fn test_elog_archive_versions() {
+        let entry = ElogEntry::new(
+            Elog::parse(
+                r#"{"serial": "SN001", "level": 2, "timestamp": "2025-01-01T00:00:00Z",
+                    "component_id": 1, "event_id": 1}"#,
+            )
+            .unwrap(),
+        );
+        let archive = ElogArchive {
+            elogs: vec![entry.clone()],
+        };
+        let json = crate::versioned::to_json(&archive).unwrap();
+        assert!(json.contains(r#""schema_version": 1"#));
+        assert_eq!(
+            crate::versioned::from_json::<ElogArchive>(&json).unwrap(),
+            archive
+        );
+
+        // Archives written before the versions were introduced
+        let v0 = serde_json::to_string(&vec![entry]).unwrap();
+        assert_eq!(
+            crate::versioned::from_json::<ElogArchive>(&v0).unwrap(),
+            archive
+        );
+    }
 }
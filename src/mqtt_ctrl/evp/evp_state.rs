@@ -17,7 +17,7 @@ limitations under the License.
 #[allow(unused)]
 use {
     super::JsonUtility,
-    crate::error::DMError,
+    crate::error::{DMError, DMErrorExt},
     error_stack::{Report, Result},
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
     json::JsonValue,
@@ -184,6 +184,8 @@ pub struct AgentDeviceConfig {
     pub configuration_id: String,
 }
 
+/// Identifier of the deployments, instances, modules and token providers, always stored in
+/// the lowercase hyphenated form so that the same ID typed differently compares equal
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Eq, Hash, PartialEq, Clone)]
 pub struct UUID {
@@ -191,18 +193,25 @@ pub struct UUID {
 }
 
 impl UUID {
+    /// Parses a UUID with or without hyphens, surrounding spaces are ignored
     pub fn from(uuid: &str) -> Result<UUID, DMError> {
-        if UUID::is_valid(uuid) {
-            Ok(Self {
-                id: uuid.to_owned(),
-            })
-        } else {
-            Err(Report::new(DMError::InvalidData))
+        let trimmed = uuid.trim();
+        let parsed = match trimmed.len() {
+            32 | 36 => Uuid::try_parse(trimmed).map_err(|e| e.to_string()),
+            len => Err(format!("32 hex digits expected, {len} characters given")),
+        };
+
+        match parsed {
+            Ok(parsed) => Ok(Self {
+                id: parsed.hyphenated().to_string(),
+            }),
+            Err(e) => Err(Report::new(DMError::InvalidData)
+                .attach_printable(format!("Invalid UUID '{}': {}", trimmed, e))),
         }
     }
 
     pub fn is_valid(uuid: &str) -> bool {
-        uuid::Uuid::try_parse(uuid).is_ok()
+        UUID::from(uuid).is_ok()
     }
 
     pub fn new() -> Self {
@@ -211,9 +220,33 @@ impl UUID {
         }
     }
 
+    /// A new random UUID in the hyphenated form, for request and deployment IDs
+    pub fn new_string() -> String {
+        Self::new().id
+    }
+
     pub fn uuid(&self) -> &str {
         &self.id
     }
+
+    /// The UUID without hyphens
+    pub fn simple(&self) -> String {
+        self.id.replace('-', "")
+    }
+}
+
+impl std::fmt::Display for UUID {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+impl std::str::FromStr for UUID {
+    type Err = Report<DMError>;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        UUID::from(s)
+    }
 }
 
 #[allow(non_snake_case)]
@@ -262,6 +295,13 @@ pub struct DeploymentStatus {
     modules: HashMap<UUID, Module>,
     deploymentId: Option<UUID>,
     reconcileStatus: Option<String>,
+    raw: String,
+}
+
+/// Instance, module and reconcile status values meaning a failure
+pub fn is_error_status(status: &str) -> bool {
+    let status = status.to_ascii_lowercase();
+    status.contains("error") || status.contains("fail")
 }
 
 impl DeploymentStatus {
@@ -318,6 +358,7 @@ impl DeploymentStatus {
                 modules,
                 deploymentId,
                 reconcileStatus,
+                raw: json::stringify_pretty(JsonValue::Object(o), 4),
             });
         }
 
@@ -328,6 +369,13 @@ impl DeploymentStatus {
         &self.instances
     }
 
+    /// Instances ordered by their ID so that they can be listed and selected
+    pub fn sorted_instances(&self) -> Vec<(&UUID, &Instance)> {
+        let mut instances: Vec<_> = self.instances.iter().collect();
+        instances.sort_by(|a, b| a.0.uuid().cmp(b.0.uuid()));
+        instances
+    }
+
     pub fn modules(&self) -> &HashMap<UUID, Module> {
         &self.modules
     }
@@ -339,6 +387,52 @@ impl DeploymentStatus {
     pub fn reconcile_status(&self) -> Option<&str> {
         self.reconcileStatus.as_deref()
     }
+
+    /// Deployment status as received, pretty printed
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// Descriptions of the instances, modules and reconcile status in an error state
+    pub fn failures(&self) -> Vec<String> {
+        let describe = |kind: &str, uuid: &UUID, status: &str, failure: Option<&str>| {
+            format!(
+                "{} {}: {}{}",
+                kind,
+                uuid.uuid(),
+                status,
+                failure.map(|f| format!(" ({})", f)).unwrap_or_default()
+            )
+        };
+
+        let mut instances: Vec<_> = self
+            .instances
+            .iter()
+            .filter(|(_, i)| is_error_status(i.status()))
+            .map(|(uuid, i)| describe("instance", uuid, i.status(), i.failure_message()))
+            .collect();
+        let mut modules: Vec<_> = self
+            .modules
+            .iter()
+            .filter(|(_, m)| is_error_status(m.status()))
+            .map(|(uuid, m)| describe("module", uuid, m.status(), m.failure_message()))
+            .collect();
+
+        // HashMap order is random, keep the alert stable between two reports
+        instances.sort();
+        modules.sort();
+
+        let mut failures = vec![];
+        if let Some(reconcile_status) = self.reconcile_status()
+            && is_error_status(reconcile_status)
+        {
+            failures.push(format!("reconcile_status: {}", reconcile_status));
+        }
+        failures.extend(instances);
+        failures.extend(modules);
+
+        failures
+    }
 }
 
 #[cfg(test)]
@@ -361,6 +455,32 @@ mod tests {
         assert!(!UUID::is_valid(id))
     }
 
+    #[test]
+    fn test_uuid_formats() {
+        let hyphenated = UUID::from("B218F90B-9228-423F-8E02-A6D3527BC15D").unwrap();
+        let simple = UUID::from(" b218f90b9228423f8e02a6d3527bc15d ").unwrap();
+        assert_eq!(hyphenated, simple);
+        assert_eq!(simple.uuid(), "b218f90b-9228-423f-8e02-a6d3527bc15d");
+        assert_eq!(simple.simple(), "b218f90b9228423f8e02a6d3527bc15d");
+        assert_eq!(simple.to_string(), simple.uuid());
+        assert_eq!(
+            "b218f90b9228423f8e02a6d3527bc15d".parse::<UUID>().unwrap(),
+            simple
+        );
+
+        let e = UUID::from("{b218f90b-9228-423f-8e02-a6d3527bc15d}").unwrap_err();
+        assert_eq!(
+            e.error_str().unwrap(),
+            "Invalid UUID '{b218f90b-9228-423f-8e02-a6d3527bc15d}': 32 hex digits expected, 38 characters given"
+        );
+        let e = UUID::from("b218f90b-9228-423f-8e02-a6d3527bc15g").unwrap_err();
+        assert!(
+            e.error_str()
+                .unwrap()
+                .starts_with("Invalid UUID 'b218f90b-9228-423f-8e02-a6d3527bc15g': ")
+        );
+    }
+
     #[test]
     fn test_uuid_03() {
         let id = UUID::new();
@@ -481,6 +601,14 @@ mod tests {
             Some("1C169145-8EB1-45AE-8267-35427323515E")
         );
 
+        let sorted: Vec<&str> = deployment_status
+            .sorted_instances()
+            .iter()
+            .map(|(id, _)| id.uuid())
+            .collect();
+        assert_eq!(sorted.len(), 4);
+        assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+
         // Validate one of the instances and modules
         let key = "f3a018c5-1997-489a-8f1d-000000000001";
         let uuid = UUID::from(key).unwrap();
@@ -499,6 +627,38 @@ mod tests {
             .expect("module exists");
         assert_eq!(module.status(), "error");
         assert_eq!(module.failure_message(), Some("Failed to load (error=11)"));
+
+        // Failures are reported instances first
+        let failures = deployment_status.failures();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(
+            failures[0],
+            "instance f3a018c5-1997-489a-8f1d-000000000001: error (Module is not ready)"
+        );
+        assert!(failures[1].starts_with("module f3a018c5-1997-489a-8f1d-a758df12977a: error"));
+        assert!(
+            deployment_status
+                .raw()
+                .contains("Failed to load (error=11)")
+        );
+    }
+
+    #[test]
+    fn test_deployment_status_reconcile_error() {
+        let status = r#"{"reconcileStatus":"error","instances":{},"modules":{}}"#;
+        let deployment_status = DeploymentStatus::parse(status).unwrap();
+        assert_eq!(
+            deployment_status.failures(),
+            vec!["reconcile_status: error".to_owned()]
+        );
+
+        let status = r#"{"reconcileStatus":"applying"}"#;
+        assert!(
+            DeploymentStatus::parse(status)
+                .unwrap()
+                .failures()
+                .is_empty()
+        );
     }
 
     #[test]
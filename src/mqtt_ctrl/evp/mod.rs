@@ -14,19 +14,23 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+pub mod clock;
 pub mod configure;
 pub mod device_info;
 pub mod edge_app;
 pub mod elog;
 pub mod evp_state;
 pub mod module;
+pub mod req_res;
 pub mod rpc;
+pub mod rpc_tracker;
+pub mod topic;
 
 #[allow(unused)]
 use {
     crate::ai_model::AiModel,
-    crate::app::DirectCommand,
-    crate::error::DMError,
+    crate::device_config::DirectCommand,
+    crate::error::{DMError, DMErrorExt},
     crate::ota::FirmwareProperty,
     device_info::{
         DeviceCapabilities, DeviceInfo, DeviceReserved, DeviceStates, NetworkSettings,
@@ -39,23 +43,19 @@ use {
     evp_state::{AgentDeviceConfig, AgentSystemInfo},
     jlogger_tracing::{JloggerBuilder, LevelFilter, LogTimeFormat, jdebug, jerror, jinfo},
     json::JsonValue,
-    pest::Parser,
-    pest::Token,
     regex::Regex,
     rpc::{RpcResInfo, RpcResponse, parse_rpc_response},
     rumqttc::{Client, Connection, MqttOptions, QoS},
     serde::{Deserialize, Serialize},
     std::fmt::Display,
     std::{
+        borrow::Cow,
         collections::HashMap,
         time::{self, Duration, Instant},
     },
+    topic::TopicKind,
 };
 
-#[derive(pest_derive::Parser)]
-#[grammar = "src/mqtt_ctrl/evp/evp.pest"]
-struct EvpParser;
-
 pub struct JsonUtility {}
 
 #[allow(unused)]
@@ -185,8 +185,63 @@ impl ResInfo {
     }
 }
 
+/// Message of the device that could not be parsed, attached to the ParserError reports of
+/// EvpMsg::parse() so that the Errors popup tells which message and which key failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedMsg {
+    pub topic: String,
+    /// Key of the payload, e.g. "state/$system/device_info", empty when the topic failed
+    pub key: String,
+    pub reason: String,
+}
+
+impl Display for MalformedMsg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.key.is_empty() {
+            write!(f, "{}: {}", self.topic, self.reason)
+        } else {
+            write!(f, "{} [{}]: {}", self.topic, self.key, self.reason)
+        }
+    }
+}
+
+fn report_reason(report: &Report<DMError>) -> String {
+    report
+        .error_str()
+        .unwrap_or_else(|| report.current_context().to_string())
+}
+
+fn malformed(topic: &str, key: &str, reason: impl Display) -> Report<DMError> {
+    let msg = MalformedMsg {
+        topic: topic.to_owned(),
+        key: key.to_owned(),
+        reason: reason.to_string(),
+    };
+    let text = format!("Malformed message on {msg}");
+    Report::new(DMError::ParserError)
+        .attach(msg)
+        .attach_printable(text)
+}
+
+/// Message kept as received, borrowing the topic and the payload of the publish so that
+/// the messages nobody keeps are not copied
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawMsg<'a> {
+    pub topic: Cow<'a, str>,
+    pub payload: Cow<'a, str>,
+}
+
+impl<'a> RawMsg<'a> {
+    pub fn borrowed(topic: &'a str, payload: &'a str) -> Self {
+        Self {
+            topic: Cow::Borrowed(topic),
+            payload: Cow::Borrowed(payload),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
-pub enum EvpMsg {
+pub enum EvpMsg<'a> {
     ConnectMsg((String, u32)),
     ConnectRespMsg((String, u32)),
     DeviceInfoMsg(DeviceInfo),
@@ -203,92 +258,32 @@ pub enum EvpMsg {
     EdgeApp(Box<EdgeAppInfo>),
     RpcRequest((u32, DirectCommand)),
     RpcResponse((u32, RpcResInfo)),
-    ClientMsg(HashMap<String, String>),
-    ServerMsg(HashMap<String, String>),
+    ClientMsg(RawMsg<'a>),
+    ServerMsg(RawMsg<'a>),
     PrivateDeployFirmware(FirmwareProperty),
     PrivateDeployAiModel(AiModel),
-    NonEvp(HashMap<String, String>),
+    NonEvp(RawMsg<'a>),
 }
 
-impl EvpMsg {
-    pub fn req_id_from_topic(topic: &str) -> Result<u32, DMError> {
-        let re = Regex::new(r"/(\d+)$").map_err(|_| Report::new(DMError::InvalidData))?;
-        if let Some(caps) = re.captures(topic) {
-            if let Some(req_id) = caps.get(1) {
-                return req_id
-                    .as_str()
-                    .parse()
-                    .map_err(|_| Report::new(DMError::InvalidData));
-            }
-        }
-        Err(Report::new(DMError::InvalidData))
-    }
-
-    fn parse_connect_request(topic: &str, _payload: &str) -> Result<Vec<EvpMsg>, DMError> {
-        let pairs = EvpParser::parse(Rule::client_attr_connection, topic)
-            .map_err(|_| Report::new(DMError::InvalidData))?;
-
-        let mut who_start = 0;
-        let mut who_end = 0;
-        let mut req_id_start = 0;
-        let mut req_id_end = 0;
-
-        for token in pairs.tokens() {
-            match token {
-                Token::Start { rule, pos } => match rule {
-                    Rule::who => who_start = pos.pos(),
-                    Rule::id => req_id_start = pos.pos(),
-                    _ => {}
-                },
-                Token::End { rule, pos } => match rule {
-                    Rule::who => who_end = pos.pos(),
-                    Rule::id => req_id_end = pos.pos(),
-                    _ => {}
-                },
-            }
-        }
-
-        let who = topic[who_start..who_end].to_owned();
-        let req_id = topic[req_id_start..req_id_end]
-            .parse()
-            .map_err(|_| Report::new(DMError::InvalidData))?;
-
-        Ok(vec![EvpMsg::ConnectMsg((who, req_id))])
-    }
-
-    fn parse_connect_response(topic: &str, _payload: &str) -> Result<Vec<EvpMsg>, DMError> {
-        let pairs = EvpParser::parse(Rule::server_attr_connection, topic)
-            .map_err(|_| Report::new(DMError::InvalidData))?;
-
-        let mut who_start = 0;
-        let mut who_end = 0;
-        let mut req_id_start = 0;
-        let mut req_id_end = 0;
-
-        for token in pairs.tokens() {
-            match token {
-                Token::Start { rule, pos } => match rule {
-                    Rule::who => who_start = pos.pos(),
-                    Rule::id => req_id_start = pos.pos(),
-                    _ => {}
-                },
-                Token::End { rule, pos } => match rule {
-                    Rule::who => who_end = pos.pos(),
-                    Rule::id => req_id_end = pos.pos(),
-                    _ => {}
-                },
-            }
-        }
-
-        let who = topic[who_start..who_end].to_owned();
-        let req_id = topic[req_id_start..req_id_end]
-            .parse()
-            .map_err(|_| Report::new(DMError::InvalidData))?;
-
-        Ok(vec![EvpMsg::ConnectRespMsg((who, req_id))])
+impl<'a> EvpMsg<'a> {
+    /// Whether the message reports a part of the device state, as after a connect request
+    pub fn is_state_report(&self) -> bool {
+        matches!(
+            self,
+            EvpMsg::DeviceInfoMsg(_)
+                | EvpMsg::DeviceStatesMsg(_)
+                | EvpMsg::DeviceCapabilities(_)
+                | EvpMsg::DeviceReserved(_)
+                | EvpMsg::SystemSettings(_)
+                | EvpMsg::NetworkSettings(_)
+                | EvpMsg::WirelessSettings(_)
+                | EvpMsg::AgentDeviceConfig(_)
+                | EvpMsg::AgentSystemInfo(_)
+                | EvpMsg::DeploymentStatus(_)
+        )
     }
 
-    fn parse_telemetry(_topic: &str, payload: &str) -> Result<Vec<EvpMsg>, DMError> {
+    fn parse_telemetry(topic: &str, payload: &str) -> Result<Vec<EvpMsg<'static>>, DMError> {
         jdebug!(
             func = "EvpMsg::parse_telemetry()",
             line = line!(),
@@ -303,7 +298,9 @@ impl EvpMsg {
             );
             for (k, v) in obj.iter() {
                 if k == "$system/event_log" {
-                    return Elog::parse(&v.dump()).map(|elog| vec![EvpMsg::Elog(elog)]);
+                    return Elog::parse(&v.dump())
+                        .map(|elog| vec![EvpMsg::Elog(elog)])
+                        .map_err(|e| malformed(topic, k, report_reason(&e)));
                 }
             }
         }
@@ -311,7 +308,10 @@ impl EvpMsg {
         Err(Report::new(DMError::InvalidData))
     }
 
-    fn parse_configure_state_msg(_topic: &str, payload: &str) -> Result<Vec<EvpMsg>, DMError> {
+    fn parse_configure_state_msg(
+        topic: &str,
+        payload: &str,
+    ) -> Result<Vec<EvpMsg<'static>>, DMError> {
         jdebug!(
             func = "EvpMsg::parse_configure_state_msg()",
             line = line!(),
@@ -369,7 +369,9 @@ impl EvpMsg {
                 }
 
                 if k == "state/$agent/report-status-interval-min" {
-                    let value = v.as_u32().ok_or(Report::new(DMError::InvalidData))?;
+                    let value = v
+                        .as_u32()
+                        .ok_or_else(|| malformed(topic, k, "not an unsigned integer"))?;
                     if agent_device_config.is_none() {
                         agent_device_config = Some(AgentDeviceConfig::default());
                     }
@@ -383,7 +385,9 @@ impl EvpMsg {
                 }
 
                 if k == "state/$agent/report-status-interval-max" {
-                    let value = v.as_u32().ok_or(Report::new(DMError::InvalidData))?;
+                    let value = v
+                        .as_u32()
+                        .ok_or_else(|| malformed(topic, k, "not an unsigned integer"))?;
                     if agent_device_config.is_none() {
                         agent_device_config = Some(AgentDeviceConfig::default());
                     }
@@ -398,110 +402,88 @@ impl EvpMsg {
 
                 if k == "systemInfo" {
                     let s = JsonUtility::json_value_to_string(v);
-                    system_info = Some(AgentSystemInfo::parse(&s)?);
+                    system_info = Some(
+                        AgentSystemInfo::parse(&s)
+                            .map_err(|e| malformed(topic, k, report_reason(&e)))?,
+                    );
                     continue;
                 }
 
                 if k == "deploymentStatus" {
                     let s = JsonUtility::json_value_to_string(v);
-                    deployment_status = Some(DeploymentStatus::parse(&s)?);
+                    deployment_status = Some(
+                        DeploymentStatus::parse(&s)
+                            .map_err(|e| malformed(topic, k, report_reason(&e)))?,
+                    );
                     continue;
                 }
 
                 if k == "state/$system/device_info" {
                     let s = JsonUtility::json_value_to_string(v);
-                    device_info = Some(
-                        serde_json::from_str(&s)
-                            .map_err(|_| Report::new(DMError::InvalidData))
-                            .unwrap(),
-                    );
+                    device_info =
+                        Some(serde_json::from_str(&s).map_err(|e| malformed(topic, k, e))?);
 
                     continue;
                 }
 
                 if k == "state/$system/device_states" {
                     let s = JsonUtility::json_value_to_string(v);
-                    device_states = Some(
-                        serde_json::from_str(&s)
-                            .map_err(|_| Report::new(DMError::InvalidData))
-                            .unwrap(),
-                    );
+                    device_states =
+                        Some(serde_json::from_str(&s).map_err(|e| malformed(topic, k, e))?);
 
                     continue;
                 }
 
                 if k == "state/$system/device_capabilities" {
                     let s = JsonUtility::json_value_to_string(v);
-                    device_capabilities = Some(
-                        serde_json::from_str(&s)
-                            .map_err(|_| Report::new(DMError::InvalidData))
-                            .unwrap(),
-                    );
+                    device_capabilities =
+                        Some(serde_json::from_str(&s).map_err(|e| malformed(topic, k, e))?);
 
                     continue;
                 }
 
                 if k == "state/$system/PRIVATE_reserved" {
                     let s = JsonUtility::json_value_to_string(v);
-                    device_reserved = Some(
-                        serde_json::from_str(&s)
-                            .map_err(|_| Report::new(DMError::InvalidData))
-                            .unwrap(),
-                    );
+                    device_reserved =
+                        Some(serde_json::from_str(&s).map_err(|e| malformed(topic, k, e))?);
 
                     continue;
                 }
 
                 if k == "state/$system/system_settings" {
                     let s = JsonUtility::json_value_to_string(v);
-                    system_settings = Some(
-                        serde_json::from_str(&s)
-                            .map_err(|_| Report::new(DMError::InvalidData))
-                            .unwrap(),
-                    );
+                    system_settings =
+                        Some(serde_json::from_str(&s).map_err(|e| malformed(topic, k, e))?);
 
                     continue;
                 }
 
                 if k == "state/$system/network_settings" {
                     let s = JsonUtility::json_value_to_string(v);
-                    network_settings = Some(
-                        serde_json::from_str(&s)
-                            .map_err(|_| Report::new(DMError::InvalidData))
-                            .unwrap(),
-                    );
+                    network_settings =
+                        Some(serde_json::from_str(&s).map_err(|e| malformed(topic, k, e))?);
 
                     continue;
                 }
 
                 if k == "state/$system/wireless_setting" {
                     let s = JsonUtility::json_value_to_string(v);
-                    wireless_settings = Some(
-                        serde_json::from_str(&s)
-                            .map_err(|_| Report::new(DMError::InvalidData))
-                            .unwrap(),
-                    );
+                    wireless_settings =
+                        Some(serde_json::from_str(&s).map_err(|e| malformed(topic, k, e))?);
 
                     continue;
                 }
 
                 if k == "state/$system/PRIVATE_deploy_firmware" {
                     let s = JsonUtility::json_value_to_string(v);
-                    firmware_property = Some(
-                        serde_json::from_str(&s)
-                            .map_err(|e| Report::new(DMError::InvalidData).attach_printable(e))
-                            .unwrap(),
-                    );
+                    firmware_property =
+                        Some(serde_json::from_str(&s).map_err(|e| malformed(topic, k, e))?);
                     continue;
                 }
 
                 if k == "state/$system/PRIVATE_deploy_ai_model" {
                     let s = JsonUtility::json_value_to_string(v);
-                    ai_model = Some(
-                        serde_json::from_str(&s)
-                            .map_err(|e| Report::new(DMError::InvalidData).attach_printable(e))
-                            .unwrap(),
-                    );
+                    ai_model = Some(serde_json::from_str(&s).map_err(|e| malformed(topic, k, e))?);
                     continue;
                 }
             }
@@ -566,160 +548,275 @@ impl EvpMsg {
         }
     }
 
-    pub fn parse(topic: &str, payload: &str) -> Result<Vec<EvpMsg>, DMError> {
+    /// Splits a gateway payload into the data of each device.
+    ///
+    /// Messages from the server carry one device as {"device": name, "data": data}, while
+    /// messages from the gateway are keyed by the device names.
+    fn gateway_devices(payload: &str) -> Vec<(String, JsonValue)> {
+        let Ok(JsonValue::Object(obj)) = json::parse(payload) else {
+            return vec![];
+        };
+
+        if let (Some(device), Some(data)) = (obj.get("device"), obj.get("data")) {
+            return vec![(JsonUtility::json_value_to_string(device), data.clone())];
+        }
+
+        obj.iter().map(|(k, v)| (k.to_owned(), v.clone())).collect()
+    }
+
+    fn parse_gateway(
+        topic: &'a str,
+        payload: &'a str,
+        kind: TopicKind,
+    ) -> Result<Vec<EvpMsg<'a>>, DMError> {
+        let raw = RawMsg::borrowed(topic, payload);
+
+        // "v1/gateway/attributes"
+        if kind == TopicKind::GatewayAttributes {
+            let mut result = vec![];
+            for (device, data) in EvpMsg::gateway_devices(payload) {
+                jinfo!(event = "GATEWAY", device = device, note = "attributes");
+                if let Ok(msg) = EvpMsg::parse_configure_state_msg(topic, &data.dump()) {
+                    result.extend(msg);
+                }
+            }
+
+            if result.is_empty() {
+                result.push(EvpMsg::ClientMsg(raw));
+            }
+            return Ok(result);
+        }
+
+        // "v1/gateway/telemetry"
+        // Each device has a list of {"ts": ts, "values": values} or of values
+        if kind == TopicKind::GatewayTelemetry {
+            let mut result = vec![];
+            for (device, data) in EvpMsg::gateway_devices(payload) {
+                jinfo!(event = "GATEWAY", device = device, note = "telemetry");
+                let entries = match data {
+                    JsonValue::Array(entries) => entries,
+                    data => vec![data],
+                };
+
+                for entry in entries {
+                    let values = if entry["values"].is_object() {
+                        &entry["values"]
+                    } else {
+                        &entry
+                    };
+                    if let Ok(msg) = EvpMsg::parse_telemetry(topic, &values.dump()) {
+                        result.extend(msg);
+                    }
+                }
+            }
+
+            if !result.is_empty() {
+                return Ok(result);
+            }
+            return Ok(vec![EvpMsg::NonEvp(raw)]);
+        }
+
+        // "v1/gateway/rpc"
+        // Requests are {"device": name, "data": {"id": id, "method": .., "params": ..}}
+        // and responses are {"device": name, "id": id, "data": data}
+        if kind == TopicKind::GatewayRpc {
+            if let Ok(JsonValue::Object(obj)) = json::parse(payload)
+                && let Some(req_id) = obj.get("id").and_then(|id| id.as_u32())
+                && let Some(data) = obj.get("data")
+            {
+                jinfo!(event = "GATEWAY", note = "RPC Response", payload = payload);
+                if let Ok(rpc_response) = parse_rpc_response(&data.dump()) {
+                    return Ok(vec![EvpMsg::RpcResponse((req_id, rpc_response))]);
+                }
+            }
+
+            return Ok(vec![EvpMsg::ServerMsg(raw)]);
+        }
+
+        // "v1/gateway/attributes/response"
+        if kind == TopicKind::GatewayAttributesResponse {
+            return Ok(vec![EvpMsg::ServerMsg(raw)]);
+        }
+
+        // "v1/gateway/connect", "v1/gateway/disconnect", "v1/gateway/attributes/request"
+        if kind == TopicKind::GatewayClient {
+            jinfo!(event = "GATEWAY", topic = topic, payload = payload);
+            return Ok(vec![EvpMsg::ClientMsg(raw)]);
+        }
+
+        Ok(vec![EvpMsg::NonEvp(raw)])
+    }
+
+    pub fn parse(topic: &'a str, payload: &'a str) -> Result<Vec<EvpMsg<'a>>, DMError> {
         let mut result = vec![];
-        let mut hash = HashMap::new();
-        hash.insert(topic.to_owned(), payload.to_owned());
+        let raw = RawMsg::borrowed(topic, payload);
+        let kind = topic::classify(topic);
 
         jdebug!(func = "EvpMsg::parse()", line = line!(), topic = topic);
 
-        // "v1/devices/me/attributes"
         // https://thingsboard.io/docs/reference/mqtt-api/#subscribe-to-attribute-updates-from-the-server
-        if EvpParser::parse(Rule::attribute_common, topic).is_ok() {
-            // "v1/devices/me/attributes/request"
-            if let Ok(msg) = EvpMsg::parse_connect_request(topic, payload) {
+        match kind {
+            // "v1/devices/me/attributes/request/<id>"
+            TopicKind::ConnectRequest { who, req_id } => {
                 jinfo!(event = "CONNECTION", note = "request");
-                return Ok(msg);
+                return Ok(vec![EvpMsg::ConnectMsg((who.to_owned(), req_id))]);
             }
 
-            // "v1/devices/me/attributes/response"
-            if let Ok(msg) = EvpMsg::parse_connect_response(topic, payload) {
+            // "v1/devices/me/attributes/response/<id>"
+            TopicKind::ConnectResponse { who, req_id } => {
                 jinfo!(event = "CONNECTION", note = "response");
-                return Ok(msg);
+                return Ok(vec![EvpMsg::ConnectRespMsg((who.to_owned(), req_id))]);
             }
 
             // "v1/devices/me/attributes"
-            if let Ok(msg) = EvpMsg::parse_configure_state_msg(topic, payload) {
-                return Ok(msg);
-            }
-
-            return Ok(vec![EvpMsg::ClientMsg(hash)]);
-        }
+            // Payloads which are not JSON objects are kept as they are, known keys with
+            // invalid values are reported.
+            TopicKind::Attributes => {
+                match EvpMsg::parse_configure_state_msg(topic, payload) {
+                    Ok(msg) => return Ok(msg),
+                    Err(e) if matches!(e.current_context(), DMError::ParserError) => {
+                        return Err(e);
+                    }
+                    Err(_) => {}
+                }
 
-        // https://thingsboard.io/docs/reference/mqtt-api/#request-attribute-values-from-the-server
-        if EvpParser::parse(Rule::server_attr_common, topic).is_ok() {
-            return Ok(vec![EvpMsg::ServerMsg(hash)]);
+                return Ok(vec![EvpMsg::ClientMsg(raw)]);
+            }
+            _ => {}
         }
 
         jdebug!(func = "EvpMsg::parse()", line = line!(), topic = topic);
 
         //"v1/devices/me/rpc/request/
         // https://thingsboard.io/docs/reference/mqtt-api/#server-side-rpc
-        if EvpParser::parse(Rule::server_rpc_common, topic).is_ok() {
+        if let TopicKind::RpcRequest(req_id) = kind {
             jinfo!(event = "RPC request", topic = topic, payload = payload);
-            if let Ok(req_id) = EvpMsg::req_id_from_topic(topic) {
-                if let Ok(JsonValue::Object(json)) = json::parse(payload) {
-                    if let Some(cmd) = json
-                        .get("params")
-                        .and_then(|params| {
-                            if let JsonValue::Object(obj) = params {
-                                Some(obj)
-                            } else {
-                                None
-                            }
-                        })
-                        .and_then(|params| params.get("direct-command-request"))
-                        .and_then(|request| {
-                            if let JsonValue::Object(obj) = request {
-                                Some(obj)
-                            } else {
-                                None
-                            }
-                        })
-                        .and_then(|request| request.get("method"))
-                        .and_then(|method| method.as_str())
-                        .map(|method| match method {
-                            "reboot" => DirectCommand::Reboot,
-                            "direct_get_image" => DirectCommand::GetDirectImage,
-                            "factory_reset" => DirectCommand::FactoryReset,
-                            _ => DirectCommand::Invalid,
-                        })
+            if let Ok(JsonValue::Object(json)) = json::parse(payload) {
+                if let Some(cmd) = json
+                    .get("params")
+                    .and_then(|params| {
+                        if let JsonValue::Object(obj) = params {
+                            Some(obj)
+                        } else {
+                            None
+                        }
+                    })
+                    .and_then(|params| params.get("direct-command-request"))
+                    .and_then(|request| {
+                        if let JsonValue::Object(obj) = request {
+                            Some(obj)
+                        } else {
+                            None
+                        }
+                    })
+                    .and_then(|request| request.get("method"))
+                    .and_then(|method| method.as_str())
+                    .map(|method| match method {
+                        "reboot" => DirectCommand::Reboot,
+                        "direct_get_image" => DirectCommand::GetDirectImage,
+                        "factory_reset" => DirectCommand::FactoryReset,
+                        _ => DirectCommand::Invalid,
+                    })
+                {
+                    jinfo!(
+                        event = "RPC request",
+                        req_id = req_id,
+                        cmd = format!("{:?}", cmd)
+                    );
+                    return Ok(vec![EvpMsg::RpcRequest((req_id, cmd))]);
+                }
+
+                if let Some(request) = json
+                    .get("params")
+                    .and_then(|params| {
+                        if let JsonValue::Object(obj) = params {
+                            Some(obj)
+                        } else {
+                            None
+                        }
+                    })
+                    .and_then(|params| params.get("storagetoken-request"))
+                    .and_then(|request| {
+                        if let JsonValue::Object(obj) = request {
+                            Some(obj)
+                        } else {
+                            None
+                        }
+                    })
+                {
+                    // Defensive parsing: ensure key and filename are strings and validate them
+                    if let (Some(key_v), Some(filename_v)) =
+                        (request.get("key"), request.get("filename"))
                     {
-                        jinfo!(
-                            event = "RPC request",
-                            req_id = req_id,
-                            cmd = format!("{:?}", cmd)
+                        jdebug!(
+                            func = "EvpMsg::parse()",
+                            RPC = "storagetoken-request",
+                            line = line!(),
+                            key = ?key_v,
+                            filename = ?filename_v,
                         );
-                        return Ok(vec![EvpMsg::RpcRequest((req_id, cmd))]);
-                    }
 
-                    if let Some(request) = json
-                        .get("params")
-                        .and_then(|params| {
-                            if let JsonValue::Object(obj) = params {
-                                Some(obj)
-                            } else {
-                                None
-                            }
-                        })
-                        .and_then(|params| params.get("storagetoken-request"))
-                        .and_then(|request| {
-                            if let JsonValue::Object(obj) = request {
-                                Some(obj)
-                            } else {
-                                None
-                            }
-                        })
-                    {
-                        // Defensive parsing: ensure key and filename are strings and validate them
-                        if let (Some(key_v), Some(filename_v)) =
-                            (request.get("key"), request.get("filename"))
+                        // Ensure both key and filename are strings
+                        // Bug?? filename is interpreted as a Short value instead of a String.
+                        // So JasonValue::String() can not be used to detemine the type of
+                        // filename
+                        if let (Some(key_s), Some(filename_s)) =
+                            (key_v.as_str(), filename_v.as_str())
                         {
-                            jdebug!(
-                                func = "EvpMsg::parse()",
-                                RPC = "storagetoken-request",
-                                line = line!(),
-                                key = ?key_v,
-                                filename = ?filename_v,
-                            );
-
-                            // Ensure both key and filename are strings
-                            // Bug?? filename is interpreted as a Short value instead of a String.
-                            // So JasonValue::String() can not be used to detemine the type of
-                            // filename
-                            if let (Some(key_s), Some(filename_s)) =
-                                (key_v.as_str(), filename_v.as_str())
+                            let key = key_s.trim().to_owned();
+                            let filename = filename_s.trim_matches('/').to_owned();
+
+                            // Basic validation: non-empty and no path traversal
+                            if !key.is_empty()
+                                && !filename.is_empty()
+                                && !key.contains("..")
+                                && !filename.contains("..")
                             {
-                                let key = key_s.trim().to_owned();
-                                let filename = filename_s.trim_matches('/').to_owned();
-
-                                // Basic validation: non-empty and no path traversal
-                                if !key.is_empty()
-                                    && !filename.is_empty()
-                                    && !key.contains("..")
-                                    && !filename.contains("..")
-                                {
-                                    jinfo!(
-                                        event = "RPC from device request",
-                                        key = key,
-                                        filename = filename,
-                                    );
-
-                                    let cmd = DirectCommand::StorageTokenRequest(key, filename);
-                                    return Ok(vec![EvpMsg::RpcRequest((req_id, cmd))]);
-                                } else {
-                                    jerror!(
-                                        func = "EvpMsg::parse()",
-                                        line = line!(),
-                                        event = "Invalid storagetoken-request fields",
-                                    );
-                                }
+                                jinfo!(
+                                    event = "RPC from device request",
+                                    key = key,
+                                    filename = filename,
+                                );
+
+                                let cmd = DirectCommand::StorageTokenRequest(key, filename);
+                                return Ok(vec![EvpMsg::RpcRequest((req_id, cmd))]);
                             } else {
                                 jerror!(
                                     func = "EvpMsg::parse()",
                                     line = line!(),
-                                    event = "storagetoken-request has non-string key/filename",
-                                    key = JsonUtility::json_type(key_v),
-                                    filename = JsonUtility::json_type(filename_v),
+                                    event = "Invalid storagetoken-request fields",
                                 );
+                                return Err(malformed(
+                                    topic,
+                                    "storagetoken-request",
+                                    "empty key or filename, or '..' in them",
+                                ));
                             }
                         } else {
                             jerror!(
                                 func = "EvpMsg::parse()",
                                 line = line!(),
-                                event = "storagetoken-request missing key or filename",
+                                event = "storagetoken-request has non-string key/filename",
+                                key = JsonUtility::json_type(key_v),
+                                filename = JsonUtility::json_type(filename_v),
                             );
+                            return Err(malformed(
+                                topic,
+                                "storagetoken-request",
+                                "key and filename must be strings",
+                            ));
                         }
+                    } else {
+                        jerror!(
+                            func = "EvpMsg::parse()",
+                            line = line!(),
+                            event = "storagetoken-request missing key or filename",
+                        );
+                        return Err(malformed(
+                            topic,
+                            "storagetoken-request",
+                            "missing key or filename",
+                        ));
                     }
                 }
             }
@@ -727,10 +824,9 @@ impl EvpMsg {
 
         jdebug!(func = "EvpMsg::parse()", line = line!(), topic = topic);
         // https://thingsboard.io/docs/reference/mqtt-api/#client-side-rpc
-        if EvpParser::parse(Rule::client_rpc_common, topic).is_ok() {
+        if let TopicKind::RpcResponse(req_id) = kind {
             jinfo!(event = "RPC Response", topic = topic, payload = payload);
-            let req_id =
-                EvpMsg::req_id_from_topic(topic).map_err(|_| Report::new(DMError::InvalidData))?;
+            let req_id = req_id.ok_or_else(|| malformed(topic, "", "no valid request id"))?;
 
             if let Ok(rpc_response) = parse_rpc_response(payload) {
                 return Ok(vec![EvpMsg::RpcResponse((req_id, rpc_response))]);
@@ -745,19 +841,29 @@ impl EvpMsg {
 
         jdebug!(func = "EvpMsg::parse()", line = line!(), topic = topic);
         // "v1/devices/me/telemetry"
-        if EvpParser::parse(Rule::telemetry, topic).is_ok() {
+        if kind == TopicKind::Telemetry {
             jinfo!(event = "TELEMETRY", topic = topic, payload = payload);
-            if let Ok(msg) = EvpMsg::parse_telemetry(topic, payload) {
-                jinfo!(
-                    event = "ELOG",
-                    payload = ?msg[0]
-                );
-                return Ok(msg);
+            match EvpMsg::parse_telemetry(topic, payload) {
+                Ok(msg) => {
+                    jinfo!(
+                        event = "ELOG",
+                        payload = ?msg[0]
+                    );
+                    return Ok(msg);
+                }
+                Err(e) if matches!(e.current_context(), DMError::ParserError) => return Err(e),
+                Err(_) => {}
             }
         }
 
+        // "v1/gateway/..."
+        // https://thingsboard.io/docs/reference/gateway-mqtt-api/
+        if kind.is_gateway() {
+            return EvpMsg::parse_gateway(topic, payload, kind);
+        }
+
         jdebug!(func = "EvpMsg::parse()", line = line!(), topic = topic);
-        result.push(EvpMsg::NonEvp(hash));
+        result.push(EvpMsg::NonEvp(raw));
 
         Ok(result)
     }
@@ -773,7 +879,7 @@ mod tests {
         let payload = "";
 
         assert_eq!(
-            EvpMsg::parse_connect_request(topic, payload).unwrap(),
+            EvpMsg::parse(topic, payload).unwrap(),
             vec![EvpMsg::ConnectMsg(("me".to_owned(), 1000))]
         );
     }
@@ -793,8 +899,7 @@ mod tests {
     fn test_parse_02() {
         let topic = "v1/devices/me/attributes";
         let payload = "abc";
-        let mut expected = HashMap::new();
-        expected.insert(topic.to_owned(), payload.to_owned());
+        let expected = RawMsg::borrowed(topic, payload);
 
         assert_eq!(
             EvpMsg::parse(topic, payload).unwrap(),
@@ -808,7 +913,7 @@ mod tests {
         let payload = "";
 
         assert_eq!(
-            EvpMsg::parse_connect_response(topic, payload).unwrap(),
+            EvpMsg::parse(topic, payload).unwrap(),
             vec![EvpMsg::ConnectRespMsg(("me".to_owned(), 1000))]
         );
     }
@@ -843,9 +948,277 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_named_device() {
+        let topic = "v1/devices/sz-device_01.lab/attributes/request/7";
+        assert_eq!(
+            EvpMsg::parse(topic, "").unwrap(),
+            vec![EvpMsg::ConnectMsg(("sz-device_01.lab".to_owned(), 7))]
+        );
+
+        let topic = "v1/devices/sz-device_01.lab/attributes/response/7";
+        assert_eq!(
+            EvpMsg::parse(topic, "").unwrap(),
+            vec![EvpMsg::ConnectRespMsg(("sz-device_01.lab".to_owned(), 7))]
+        );
+    }
+
+    #[test]
+    fn test_parse_gateway_attributes() {
+        let topic = "v1/gateway/attributes";
+        let payload = r#"{"camera-1": {"state/$agent/report-status-interval-min": 3}}"#;
+        let msgs = EvpMsg::parse(topic, payload).unwrap();
+        assert!(!msgs.is_empty());
+        assert!(
+            msgs.iter()
+                .all(|m| !matches!(m, EvpMsg::ClientMsg(_) | EvpMsg::NonEvp(_)))
+        );
+
+        // Attributes updated by the server
+        let payload = r#"{"device": "camera-1", "data": {"configuration/$agent/report-status-interval-min": 3}}"#;
+        assert!(EvpMsg::parse(topic, payload).is_ok());
+
+        // Unknown attributes are kept as they are
+        let payload = r#"{"camera-1": {"foo": 1}}"#;
+        let expected = RawMsg::borrowed(topic, payload);
+        assert_eq!(
+            EvpMsg::parse(topic, payload).unwrap(),
+            vec![EvpMsg::ClientMsg(expected)]
+        );
+    }
+
+    #[test]
+    fn test_parse_gateway_telemetry() {
+        let topic = "v1/gateway/telemetry";
+        let payload = r#"{"camera-1": [{"ts": 1700000000000, "values": {"temperature": 42}}]}"#;
+        let expected = RawMsg::borrowed(topic, payload);
+        assert_eq!(
+            EvpMsg::parse(topic, payload).unwrap(),
+            vec![EvpMsg::NonEvp(expected)]
+        );
+    }
+
+    #[test]
+    fn test_parse_gateway_rpc() {
+        let topic = "v1/gateway/rpc";
+        let payload = r#"{"device": "camera-1", "id": 5, "data": {"direct-command-response":{"status":"ok","reqid":"5","response":"{\"res_info\":{\"code\":0,\"detail_msg\":\"ok\"}}"}}}"#;
+        let msgs = EvpMsg::parse(topic, payload).unwrap();
+        assert!(matches!(msgs[..], [EvpMsg::RpcResponse((5, _))]));
+
+        let payload = r#"{"device": "camera-1", "data": {"id": 5, "method": "ModuleMethodCall", "params": {}}}"#;
+        let expected = RawMsg::borrowed(topic, payload);
+        assert_eq!(
+            EvpMsg::parse(topic, payload).unwrap(),
+            vec![EvpMsg::ServerMsg(expected)]
+        );
+    }
+
+    #[test]
+    fn test_parse_gateway_connect() {
+        for topic in [
+            "v1/gateway/connect",
+            "v1/gateway/disconnect",
+            "v1/gateway/attributes/request",
+        ] {
+            let payload = r#"{"device": "camera-1"}"#;
+            let expected = RawMsg::borrowed(topic, payload);
+            assert_eq!(
+                EvpMsg::parse(topic, payload).unwrap(),
+                vec![EvpMsg::ClientMsg(expected)]
+            );
+        }
+
+        let topic = "v1/gateway/attributes/response";
+        let payload = r#"{"id": 1, "device": "camera-1", "value": 1}"#;
+        let expected = RawMsg::borrowed(topic, payload);
+        assert_eq!(
+            EvpMsg::parse(topic, payload).unwrap(),
+            vec![EvpMsg::ServerMsg(expected)]
+        );
+    }
+
     #[test]
     fn test_device_states_01() {
         let v = "{\"power_states\":{\"source\":[{\"type\":-1,\"level\":100}],\"in_use\":-1,\"is_battery_low\":false},\"process_state\":\"Idle\",\"hours_meter\":12,\"bootup_reason\":0,\"last_bootup_time\":\"2025-05-04T17:41:53.869Z\"}";
         let _device_states: DeviceStates = serde_json::from_str(v).unwrap();
     }
+
+    #[test]
+    fn test_parse_malformed_state() {
+        let topic = "v1/devices/me/attributes";
+        let payload = r#"{"state/$system/device_info": "not json"}"#;
+        let e = EvpMsg::parse(topic, payload).unwrap_err();
+        assert!(matches!(e.current_context(), DMError::ParserError));
+        let msg = e.downcast_ref::<MalformedMsg>().unwrap();
+        assert_eq!(msg.topic, topic);
+        assert_eq!(msg.key, "state/$system/device_info");
+        assert!(e.error_str().unwrap().starts_with(
+            "Malformed message on v1/devices/me/attributes [state/$system/device_info]: "
+        ));
+
+        let payload = r#"{"state/$agent/report-status-interval-min": -1}"#;
+        let e = EvpMsg::parse(topic, payload).unwrap_err();
+        assert_eq!(
+            e.downcast_ref::<MalformedMsg>().unwrap().reason,
+            "not an unsigned integer"
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_rpc() {
+        let topic = "v1/devices/me/rpc/request/42";
+        let payload = r#"{"params": {"storagetoken-request": {"key": "k", "filename": "../a"}}}"#;
+        let e = EvpMsg::parse(topic, payload).unwrap_err();
+        assert_eq!(
+            e.downcast_ref::<MalformedMsg>().unwrap().key,
+            "storagetoken-request"
+        );
+
+        // Unicode digits match the grammar but are not a request id
+        let e = EvpMsg::parse("v1/devices/me/rpc/response/\u{0663}", "{}").unwrap_err();
+        assert_eq!(e.downcast_ref::<MalformedMsg>().unwrap().key, "");
+
+        let topic = "v1/devices/me/telemetry";
+        let payload = r#"{"$system/event_log": {"serial": 1}}"#;
+        let e = EvpMsg::parse(topic, payload).unwrap_err();
+        assert_eq!(
+            e.downcast_ref::<MalformedMsg>().unwrap().key,
+            "$system/event_log"
+        );
+    }
+
+    mod prop {
+        use super::*;
+        use proptest::prelude::*;
+        use serde_json::Value;
+
+        const TOPICS: [&str; 10] = [
+            "v1/devices/me/attributes",
+            "v1/devices/me/attributes/request/",
+            "v1/devices/me/attributes/response/",
+            "v1/devices/me/rpc/request/",
+            "v1/devices/me/rpc/response/",
+            "v1/devices/me/telemetry",
+            "v1/gateway/attributes",
+            "v1/gateway/rpc",
+            "v1/gateway/telemetry",
+            "v1/gateway/connect",
+        ];
+
+        const STATE_KEYS: [&str; 14] = [
+            "state/$agent/report-status-interval-min",
+            "state/$agent/report-status-interval-max",
+            "systemInfo",
+            "deploymentStatus",
+            "state/$system/device_info",
+            "state/$system/device_states",
+            "state/$system/device_capabilities",
+            "state/$system/PRIVATE_reserved",
+            "state/$system/system_settings",
+            "state/$system/network_settings",
+            "state/$system/wireless_setting",
+            "state/$system/PRIVATE_deploy_firmware",
+            "state/$system/PRIVATE_deploy_ai_model",
+            "state/node/edge_app",
+        ];
+
+        fn json_value() -> impl Strategy<Value = Value> {
+            let leaf = prop_oneof![
+                Just(Value::Null),
+                any::<bool>().prop_map(Value::from),
+                any::<i64>().prop_map(Value::from),
+                any::<f64>().prop_map(Value::from),
+                ".{0,16}".prop_map(Value::from),
+            ];
+            leaf.prop_recursive(4, 32, 6, |inner| {
+                prop_oneof![
+                    prop::collection::vec(inner.clone(), 0..6).prop_map(Value::from),
+                    prop::collection::btree_map("[a-z_]{1,12}", inner, 0..6)
+                        .prop_map(|m| Value::Object(m.into_iter().collect())),
+                ]
+            })
+        }
+
+        /// Topics of the device with random request ids, random topics under v1/ and
+        /// arbitrary strings
+        fn topic() -> impl Strategy<Value = String> {
+            prop_oneof![
+                (
+                    prop::sample::select(TOPICS.to_vec()),
+                    "[0-9]{0,12}|\\PC{0,3}"
+                )
+                    .prop_map(|(prefix, id)| format!("{prefix}{id}")),
+                "v1/(devices|gateway)/[a-z0-9/._-]{0,24}",
+                "\\PC{0,32}",
+            ]
+        }
+
+        /// State reports with known keys, their values being JSON as they are or stringified
+        /// as the device sends them
+        fn state_payload() -> impl Strategy<Value = String> {
+            let value = prop_oneof![
+                json_value(),
+                json_value().prop_map(|v| Value::String(v.to_string())),
+            ];
+            prop::collection::btree_map(prop::sample::select(STATE_KEYS.to_vec()), value, 1..4)
+                .prop_map(|m| {
+                    Value::Object(m.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+                        .to_string()
+                })
+        }
+
+        fn payload() -> impl Strategy<Value = String> {
+            prop_oneof![
+                json_value().prop_map(|v| v.to_string()),
+                state_payload(),
+                "\\PC{0,64}",
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn parse_never_panics(topic in topic(), payload in payload()) {
+                if let Err(e) = EvpMsg::parse(&topic, &payload) {
+                    // Only malformed messages are errors, and they tell which one failed
+                    prop_assert!(matches!(e.current_context(), DMError::ParserError));
+                    let msg = e.downcast_ref::<MalformedMsg>();
+                    prop_assert_eq!(msg.map(|m| m.topic.as_str()), Some(topic.as_str()));
+                }
+            }
+
+            #[test]
+            fn parse_state_reports(payload in state_payload()) {
+                let topic = "v1/devices/me/attributes";
+                if let Err(e) = EvpMsg::parse(topic, &payload) {
+                    let msg = e.downcast_ref::<MalformedMsg>().unwrap();
+                    prop_assert!(STATE_KEYS.contains(&msg.key.as_str()));
+                }
+            }
+
+            #[test]
+            fn parse_non_evp_topics(topic in "[a-uw-z0-9][a-z0-9/]{0,32}", payload in payload()) {
+                let expected = RawMsg::borrowed(&topic, &payload);
+                prop_assert_eq!(
+                    EvpMsg::parse(&topic, &payload).unwrap(),
+                    vec![EvpMsg::NonEvp(expected)]
+                );
+            }
+
+            #[test]
+            fn deserialize_state_never_panics(value in json_value()) {
+                let _ = serde_json::from_value::<DeviceInfo>(value.clone());
+                let _ = serde_json::from_value::<DeviceStates>(value.clone());
+                let _ = serde_json::from_value::<DeviceCapabilities>(value.clone());
+                let _ = serde_json::from_value::<DeviceReserved>(value.clone());
+                let _ = serde_json::from_value::<SystemSettings>(value.clone());
+                let _ = serde_json::from_value::<NetworkSettings>(value.clone());
+                let _ = serde_json::from_value::<WirelessSettings>(value.clone());
+                let _ = serde_json::from_value::<FirmwareProperty>(value.clone());
+                let _ = serde_json::from_value::<AiModel>(value.clone());
+                let _ = AgentSystemInfo::parse(&value.to_string());
+                let _ = DeploymentStatus::parse(&value.to_string());
+                let _ = Elog::parse(&value.to_string());
+            }
+        }
+    }
 }
@@ -44,6 +44,8 @@ pub struct ModuleInfo {
     pub hash_base64: String,
     pub sas_url: String,
     pub size: usize,
+    /// Last modification time of the blob, None for a module not listed yet
+    pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl ModuleInfo {
@@ -51,7 +53,7 @@ impl ModuleInfo {
         let mut deployment = JsonValue::new_object();
 
         deployment
-            .insert("deploymentId", UUID::new().uuid().to_string())
+            .insert("deploymentId", UUID::new_string())
             .map_err(|_| {
                 Report::new(DMError::InvalidData).attach_printable("Failed to insert deploymentId")
             })?;
@@ -91,14 +93,14 @@ impl ModuleInfo {
         let mut deployment = JsonValue::new_object();
 
         deployment
-            .insert("deploymentId", UUID::new().uuid().to_string())
+            .insert("deploymentId", UUID::new_string())
             .map_err(|_| {
                 Report::new(DMError::InvalidData).attach_printable("Failed to insert deploymentId")
             })?;
-        let instance_id = UUID::new().uuid().to_string();
+        let instance_id = UUID::new_string();
         let instance = object! {
             "name" : self.blob_name.clone(),
-            "moduleId" : self.id.uuid().to_string(),
+            "moduleId" : self.id.to_string(),
             "publish" : {},
             "subscribe": {},
         };
@@ -147,10 +149,130 @@ impl ModuleInfo {
     }
 }
 
+/// Name of a module without extension and version suffix, used to group the versions of a
+/// module, e.g. "detection_v1.2.wasm" and "detection-2.wasm" both give "detection"
+pub fn module_base_name(blob_name: &str) -> String {
+    let name = blob_name.rsplit('/').next().unwrap_or(blob_name);
+    let mut base = name.split('.').next().unwrap_or(name);
+
+    while let Some(pos) = base.rfind(['_', '-']) {
+        let token = &base[pos + 1..];
+        let digits = token.strip_prefix(['v', 'V']).unwrap_or(token);
+
+        if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+            base = &base[..pos];
+        } else {
+            break;
+        }
+    }
+
+    base.to_lowercase()
+}
+
+/// Other versions of `module`: modules with the same base name but a different hash
+pub fn module_versions<'a>(
+    module_info_db: &'a HashMap<UUID, ModuleInfo>,
+    module: &ModuleInfo,
+) -> Vec<&'a ModuleInfo> {
+    let base_name = module_base_name(&module.blob_name);
+    let mut versions: Vec<_> = module_info_db
+        .values()
+        .filter(|m| m.hash != module.hash && module_base_name(&m.blob_name) == base_name)
+        .collect();
+    versions.sort_by(|a, b| a.blob_name.cmp(&b.blob_name));
+
+    versions
+}
+
+/// Other modules with exactly the same content as `module`
+pub fn module_duplicates<'a>(
+    module_info_db: &'a HashMap<UUID, ModuleInfo>,
+    module: &ModuleInfo,
+) -> Vec<&'a ModuleInfo> {
+    let mut duplicates: Vec<_> = module_info_db
+        .values()
+        .filter(|m| m.id != module.id && m.hash == module.hash)
+        .collect();
+    duplicates.sort_by(|a, b| a.blob_name.cmp(&b.blob_name));
+
+    duplicates
+}
+
+/// Module IDs and hashes of a deployment generated by deployment_json(), modules whose ID is
+/// not a UUID are skipped
+pub fn deployment_module_hashes(deployment_json: &str) -> Vec<(UUID, String)> {
+    let Ok(root) = json::parse(deployment_json) else {
+        return vec![];
+    };
+
+    root["deployment"]["modules"]
+        .entries()
+        .filter_map(|(id, m)| Some((UUID::from(id).ok()?, m["hash"].as_str()?.to_owned())))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn module_info(blob_name: &str, hash: &str) -> ModuleInfo {
+        ModuleInfo {
+            id: UUID::new(),
+            blob_name: blob_name.to_string(),
+            container_name: "default".to_string(),
+            hash: hash.to_string(),
+            hash_base64: String::new(),
+            sas_url: String::new(),
+            size: 0,
+            last_modified: None,
+        }
+    }
+
+    #[test]
+    fn test_module_base_name() {
+        assert_eq!(module_base_name("detection.wasm"), "detection");
+        assert_eq!(module_base_name("detection_v1.2.wasm"), "detection");
+        assert_eq!(module_base_name("Detection-2-1.aot"), "detection");
+        assert_eq!(
+            module_base_name("dir/object_detection_v2.wasm"),
+            "object_detection"
+        );
+        assert_eq!(module_base_name("pose_estimation.wasm"), "pose_estimation");
+    }
+
+    #[test]
+    fn test_module_versions_and_duplicates() {
+        let modules = [
+            module_info("detection_v1.wasm", "aaaa"),
+            module_info("detection_v2.wasm", "bbbb"),
+            module_info("detection_copy.wasm", "aaaa"),
+            module_info("classification.wasm", "cccc"),
+        ];
+        let db: HashMap<UUID, ModuleInfo> =
+            modules.iter().map(|m| (m.id.clone(), m.clone())).collect();
+
+        let versions = module_versions(&db, &modules[0]);
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].blob_name, "detection_v2.wasm");
+
+        let duplicates = module_duplicates(&db, &modules[0]);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].blob_name, "detection_copy.wasm");
+
+        assert!(module_versions(&db, &modules[3]).is_empty());
+        assert!(module_duplicates(&db, &modules[3]).is_empty());
+    }
+
+    #[test]
+    fn test_deployment_module_hashes() {
+        let mi = module_info("detection.wasm", "abcd1234");
+        let hashes = deployment_module_hashes(&mi.deployment_json().unwrap());
+        assert_eq!(hashes, vec![(mi.id.clone(), "abcd1234".to_owned())]);
+
+        let hashes = deployment_module_hashes(&ModuleInfo::undeployment_json().unwrap());
+        assert!(hashes.is_empty());
+    }
+
     #[test]
     fn test_undeployment_json_contains_expected_keys() {
         let s = ModuleInfo::undeployment_json().expect("undeployment json");
@@ -174,6 +296,7 @@ mod tests {
             hash_base64: "YWJjZDEyMz".to_string(),
             sas_url: "https://example.com/blob?sas".to_string(),
             size: 1024,
+            last_modified: None,
         };
         let s = mi.deployment_json().expect("deployment json");
         let v = json::parse(&s).expect("parse");
@@ -0,0 +1,219 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    json::JsonValue,
+    std::{
+        collections::HashMap,
+        fmt::Display,
+        time::{Duration, Instant},
+    },
+};
+
+/// Number of requests remembered for each settings
+const HISTORY_SIZE: usize = 16;
+
+/// Settings configured with a req_info and answered with a res_info
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SettingsKind {
+    System,
+    Network,
+    Wireless,
+}
+
+impl SettingsKind {
+    pub const ALL: [SettingsKind; 3] = [
+        SettingsKind::System,
+        SettingsKind::Network,
+        SettingsKind::Wireless,
+    ];
+
+    /// Key of the settings in the configuration sent to the device
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            SettingsKind::System => "configuration/$system/system_settings",
+            SettingsKind::Network => "configuration/$system/network_settings",
+            SettingsKind::Wireless => "configuration/$system/wireless_settings",
+        }
+    }
+}
+
+impl Display for SettingsKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SettingsKind::System => "system_settings",
+            SettingsKind::Network => "network_settings",
+            SettingsKind::Wireless => "wireless_settings",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentRequest {
+    pub req_id: String,
+    pub sent: Instant,
+}
+
+/// Whether the response reported by the device corresponds to the latest request sent
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReqResStatus {
+    Applied,
+    /// The device may not have reported since the request was sent
+    Pending,
+    /// The device keeps reporting the response of an older request
+    Stale {
+        req_id: String,
+        res_id: String,
+        /// The response corresponds to a request sent by device-monitor before the latest one
+        known: bool,
+        elapsed: Duration,
+    },
+}
+
+/// Requests sent in configurations, compared with the responses reported by the device
+#[derive(Debug, Default)]
+pub struct ReqResTracker {
+    sent: HashMap<SettingsKind, Vec<SentRequest>>,
+}
+
+impl ReqResTracker {
+    /// Remembers the req_id of every settings contained in a configuration sent to the device
+    pub fn record(&mut self, config: &str, now: Instant) {
+        let Ok(root) = json::parse(config) else {
+            return;
+        };
+
+        for kind in SettingsKind::ALL {
+            let settings = &root[kind.config_key()];
+            let settings = match settings.as_str() {
+                Some(s) => json::parse(s).unwrap_or(JsonValue::Null),
+                None => settings.clone(),
+            };
+
+            if let Some(req_id) = settings["req_info"]["req_id"].as_str() {
+                let history = self.sent.entry(kind).or_default();
+                history.push(SentRequest {
+                    req_id: req_id.to_owned(),
+                    sent: now,
+                });
+                if history.len() > HISTORY_SIZE {
+                    history.remove(0);
+                }
+            }
+        }
+    }
+
+    /// Compares the res_id reported for the settings with the latest request sent.
+    ///
+    /// Returns None if no request has been sent for the settings. A mismatch reported
+    /// within `grace` of the request is still pending since the device may not have run
+    /// its next report yet.
+    pub fn check(
+        &self,
+        kind: SettingsKind,
+        res_id: &str,
+        now: Instant,
+        grace: Duration,
+    ) -> Option<ReqResStatus> {
+        let history = self.sent.get(&kind)?;
+        let latest = history.last()?;
+
+        if latest.req_id == res_id {
+            return Some(ReqResStatus::Applied);
+        }
+
+        let elapsed = now.saturating_duration_since(latest.sent);
+        if elapsed < grace {
+            return Some(ReqResStatus::Pending);
+        }
+
+        Some(ReqResStatus::Stale {
+            req_id: latest.req_id.clone(),
+            res_id: res_id.to_owned(),
+            known: history.iter().any(|r| r.req_id == res_id),
+            elapsed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(kind: SettingsKind, req_id: &str) -> String {
+        let settings = json::object! { req_info: { req_id: req_id } };
+        let mut root = json::object! {};
+        root[kind.config_key()] = JsonValue::String(settings.dump());
+        root.dump()
+    }
+
+    #[test]
+    fn test_req_res_tracker() {
+        let now = Instant::now();
+        let grace = Duration::from_secs(30);
+        let mut tracker = ReqResTracker::default();
+
+        assert_eq!(tracker.check(SettingsKind::System, "a", now, grace), None);
+
+        tracker.record(&config(SettingsKind::System, "a"), now);
+        tracker.record(&config(SettingsKind::System, "b"), now);
+        assert_eq!(tracker.check(SettingsKind::Network, "b", now, grace), None);
+
+        assert_eq!(
+            tracker.check(SettingsKind::System, "b", now, grace),
+            Some(ReqResStatus::Applied)
+        );
+        assert_eq!(
+            tracker.check(SettingsKind::System, "a", now, grace),
+            Some(ReqResStatus::Pending)
+        );
+
+        let later = now + Duration::from_secs(60);
+        assert_eq!(
+            tracker.check(SettingsKind::System, "a", later, grace),
+            Some(ReqResStatus::Stale {
+                req_id: "b".to_owned(),
+                res_id: "a".to_owned(),
+                known: true,
+                elapsed: Duration::from_secs(60),
+            })
+        );
+        assert!(matches!(
+            tracker.check(SettingsKind::System, "other", later, grace),
+            Some(ReqResStatus::Stale { known: false, .. })
+        ));
+    }
+
+    #[test]
+    fn test_req_res_history_size() {
+        let now = Instant::now();
+        let mut tracker = ReqResTracker::default();
+        for i in 0..HISTORY_SIZE + 1 {
+            tracker.record(&config(SettingsKind::Wireless, &i.to_string()), now);
+        }
+
+        let later = now + Duration::from_secs(60);
+        let known =
+            |res_id| match tracker.check(SettingsKind::Wireless, res_id, later, Duration::ZERO) {
+                Some(ReqResStatus::Stale { known, .. }) => known,
+                _ => panic!("not stale"),
+            };
+        assert!(!known("0"));
+        assert!(known("1"));
+    }
+}
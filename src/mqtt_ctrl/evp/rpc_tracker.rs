@@ -0,0 +1,183 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::rpc::RpcResInfo,
+    std::{
+        collections::BTreeMap,
+        time::{Duration, Instant},
+    },
+};
+
+/// Time after which an RPC without response is considered lost
+pub const RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Number of finished RPCs kept for display
+const HISTORY_SIZE: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RpcState {
+    Pending,
+    Answered(RpcResInfo),
+    TimedOut,
+}
+
+/// RPC sent to the device with its own timer
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingRpc {
+    pub req_id: u32,
+    pub method: String,
+    pub sent: Instant,
+    pub finished: Option<Instant>,
+    pub state: RpcState,
+}
+
+impl PendingRpc {
+    /// Time taken by the device to answer, or waited so far
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        self.finished.unwrap_or(now).duration_since(self.sent)
+    }
+}
+
+/// Outstanding RPCs keyed by req_id, so that overlapping commands keep their responses
+#[derive(Debug, Default)]
+pub struct RpcTracker {
+    requests: BTreeMap<u32, PendingRpc>,
+}
+
+impl RpcTracker {
+    pub fn start(&mut self, req_id: u32, method: &str, now: Instant) {
+        self.requests.insert(
+            req_id,
+            PendingRpc {
+                req_id,
+                method: method.to_owned(),
+                sent: now,
+                finished: None,
+                state: RpcState::Pending,
+            },
+        );
+        self.prune();
+    }
+
+    /// Records the response, returns the round-trip time or None if the req_id was not
+    /// sent by us
+    pub fn complete(
+        &mut self,
+        req_id: u32,
+        response: RpcResInfo,
+        now: Instant,
+    ) -> Option<Duration> {
+        let rpc = self.requests.get_mut(&req_id)?;
+
+        // A late response still replaces the timeout
+        rpc.state = RpcState::Answered(response);
+        rpc.finished = Some(now);
+        Some(now.duration_since(rpc.sent))
+    }
+
+    pub fn expire(&mut self, now: Instant, timeout: Duration) {
+        for rpc in self.requests.values_mut() {
+            if rpc.state == RpcState::Pending && now.duration_since(rpc.sent) > timeout {
+                rpc.state = RpcState::TimedOut;
+                rpc.finished = Some(now);
+            }
+        }
+    }
+
+    pub fn pending(&self) -> usize {
+        self.requests
+            .values()
+            .filter(|r| r.state == RpcState::Pending)
+            .count()
+    }
+
+    /// Most recent first, req_ids being increasing
+    pub fn iter(&self) -> impl Iterator<Item = &PendingRpc> {
+        self.requests.values().rev()
+    }
+
+    /// Drops the oldest finished RPCs, pending ones are always kept
+    fn prune(&mut self) {
+        while self.requests.len() > HISTORY_SIZE {
+            let Some(req_id) = self
+                .requests
+                .values()
+                .find(|r| r.state != RpcState::Pending)
+                .map(|r| r.req_id)
+            else {
+                break;
+            };
+            self.requests.remove(&req_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl RpcTracker {
+        fn get(&self, req_id: u32) -> Option<&PendingRpc> {
+            self.requests.get(&req_id)
+        }
+    }
+
+    #[test]
+    fn test_overlapping_rpcs() {
+        let now = Instant::now();
+        let mut tracker = RpcTracker::default();
+        tracker.start(10, "direct_get_image", now);
+        tracker.start(11, "reboot", now + Duration::from_secs(1));
+        assert_eq!(tracker.pending(), 2);
+
+        // The response of the first request is not lost
+        assert_eq!(
+            tracker.complete(10, RpcResInfo::default(), now + Duration::from_secs(2)),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(tracker.complete(99, RpcResInfo::default(), now), None);
+
+        let rpc = tracker.get(10).unwrap();
+        assert!(matches!(rpc.state, RpcState::Answered(_)));
+        assert_eq!(
+            rpc.elapsed(now + Duration::from_secs(9)),
+            Duration::from_secs(2)
+        );
+
+        tracker.expire(now + Duration::from_secs(40), RPC_TIMEOUT);
+        assert_eq!(tracker.get(11).unwrap().state, RpcState::TimedOut);
+        assert_eq!(tracker.pending(), 0);
+        assert_eq!(tracker.iter().next().unwrap().req_id, 11);
+    }
+
+    #[test]
+    fn test_prune_keeps_pending() {
+        let now = Instant::now();
+        let mut tracker = RpcTracker::default();
+        tracker.start(0, "reboot", now);
+        for id in 1..=HISTORY_SIZE as u32 {
+            tracker.start(id, "reboot", now);
+            tracker.complete(id, RpcResInfo::default(), now);
+        }
+        tracker.start(100, "reboot", now);
+
+        assert_eq!(tracker.iter().count(), HISTORY_SIZE);
+        assert!(tracker.get(0).is_some());
+        assert!(tracker.get(1).is_none());
+    }
+}
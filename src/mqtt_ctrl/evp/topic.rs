@@ -0,0 +1,373 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+use {
+    crate::error::DMError,
+    error_stack::{Report, Result},
+    json::JsonValue,
+};
+
+/// Kind of a topic, telling EvpMsg::parse how to read the payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicKind<'a> {
+    /// v1/devices/<who>/attributes/request/<id>, the device connecting
+    ConnectRequest { who: &'a str, req_id: u32 },
+    /// v1/devices/<who>/attributes/response/<id>
+    ConnectResponse { who: &'a str, req_id: u32 },
+    /// v1/devices/<who>/attributes and the other topics below it
+    Attributes,
+    /// v1/devices/<who>/rpc/request/<id>
+    RpcRequest(u32),
+    /// v1/devices/<who>/rpc/response/<id>, None if the id does not fit in a u32
+    RpcResponse(Option<u32>),
+    /// v1/devices/<who>/telemetry and the other topics below it
+    Telemetry,
+    /// v1/gateway/attributes
+    GatewayAttributes,
+    /// v1/gateway/telemetry
+    GatewayTelemetry,
+    /// v1/gateway/rpc
+    GatewayRpc,
+    /// v1/gateway/attributes/response
+    GatewayAttributesResponse,
+    /// v1/gateway/connect, v1/gateway/disconnect and v1/gateway/attributes/request
+    GatewayClient,
+    /// Other v1/gateway topics
+    Gateway,
+    /// Neither EVP nor gateway topics
+    Other,
+}
+
+impl TopicKind<'_> {
+    pub fn is_gateway(&self) -> bool {
+        matches!(
+            self,
+            TopicKind::GatewayAttributes
+                | TopicKind::GatewayTelemetry
+                | TopicKind::GatewayRpc
+                | TopicKind::GatewayAttributesResponse
+                | TopicKind::GatewayClient
+                | TopicKind::Gateway
+        )
+    }
+}
+
+/// Gateway topics after "v1/gateway/", matched as a whole
+/// https://thingsboard.io/docs/reference/gateway-mqtt-api/
+const GATEWAY_ROUTES: [(&str, TopicKind); 7] = [
+    ("attributes", TopicKind::GatewayAttributes),
+    ("telemetry", TopicKind::GatewayTelemetry),
+    ("rpc", TopicKind::GatewayRpc),
+    ("attributes/response", TopicKind::GatewayAttributesResponse),
+    ("attributes/request", TopicKind::GatewayClient),
+    ("connect", TopicKind::GatewayClient),
+    ("disconnect", TopicKind::GatewayClient),
+];
+
+/// Access token names other than "me" may contain "-", "_" and "."
+fn is_who(who: &str) -> bool {
+    !who.is_empty()
+        && who
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+fn is_id(id: &str) -> bool {
+    !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Classifies a topic in one pass over its levels, without allocating
+/// https://thingsboard.io/docs/reference/mqtt-api/
+pub fn classify(topic: &str) -> TopicKind<'_> {
+    if let Some(rest) = topic.strip_prefix("v1/gateway/") {
+        return GATEWAY_ROUTES
+            .iter()
+            .find(|(route, _)| *route == rest)
+            .map_or(TopicKind::Gateway, |(_, kind)| *kind);
+    }
+
+    let Some(rest) = topic.strip_prefix("v1/devices/") else {
+        return TopicKind::Other;
+    };
+    let mut levels = rest.splitn(5, '/');
+    let who = levels.next().unwrap_or_default();
+    if !is_who(who) {
+        return TopicKind::Other;
+    }
+
+    let levels = [levels.next(), levels.next(), levels.next(), levels.next()];
+    match levels {
+        [Some("attributes"), Some("request"), Some(id), None] if is_id(id) => {
+            id.parse()
+                .map_or(TopicKind::Attributes, |req_id| TopicKind::ConnectRequest {
+                    who,
+                    req_id,
+                })
+        }
+        [Some("attributes"), Some("response"), Some(id), None] if is_id(id) => {
+            id.parse()
+                .map_or(TopicKind::Attributes, |req_id| TopicKind::ConnectResponse {
+                    who,
+                    req_id,
+                })
+        }
+        [Some("attributes"), ..] => TopicKind::Attributes,
+        [Some("rpc"), Some("request"), Some(id), None] if is_id(id) => {
+            id.parse().map_or(TopicKind::Other, TopicKind::RpcRequest)
+        }
+        [Some("rpc"), Some("response"), Some(id), None] if is_id(id) => {
+            TopicKind::RpcResponse(id.parse().ok())
+        }
+        [Some("telemetry"), ..] => TopicKind::Telemetry,
+        _ => TopicKind::Other,
+    }
+}
+
+/// How the device is reached, learned from the topics it reports on
+/// https://thingsboard.io/docs/reference/gateway-mqtt-api/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceRoute {
+    /// v1/devices/<who>/..., "me" for a device connected with its own credentials
+    Direct(String),
+    /// v1/gateway/..., for a device connected through a gateway under this name
+    Gateway(String),
+}
+
+impl Default for DeviceRoute {
+    fn default() -> Self {
+        DeviceRoute::Direct("me".to_owned())
+    }
+}
+
+impl DeviceRoute {
+    /// Route of the device reporting on `topic`, None if the message does not tell it
+    pub fn of_report(topic: &str, payload: &str) -> Option<Self> {
+        match classify(topic) {
+            TopicKind::Attributes | TopicKind::Telemetry => topic
+                .strip_prefix("v1/devices/")
+                .and_then(|rest| rest.split('/').next())
+                .map(|who| DeviceRoute::Direct(who.to_owned())),
+            TopicKind::GatewayAttributes
+            | TopicKind::GatewayTelemetry
+            | TopicKind::GatewayClient => {
+                let Ok(JsonValue::Object(obj)) = json::parse(payload) else {
+                    return None;
+                };
+                // {"device": name, ...} from the server and for connect, otherwise keyed by
+                // the device names
+                match obj.get("device") {
+                    Some(device) => device.as_str().map(str::to_owned),
+                    None => obj.iter().next().map(|(name, _)| name.to_owned()),
+                }
+                .map(DeviceRoute::Gateway)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_gateway(&self) -> bool {
+        matches!(self, DeviceRoute::Gateway(_))
+    }
+
+    /// Topic and payload of a configuration, wrapped as {"device": name, "data": config}
+    /// for a device behind a gateway
+    pub fn attributes(&self, config: &str) -> Result<(String, String), DMError> {
+        match self {
+            DeviceRoute::Direct(who) => {
+                Ok((format!("v1/devices/{who}/attributes"), config.to_owned()))
+            }
+            DeviceRoute::Gateway(device) => {
+                let data = parse_object(config)?;
+                let payload = json::object! { "device": device.as_str(), "data": data };
+                Ok(("v1/gateway/attributes".to_owned(), payload.dump()))
+            }
+        }
+    }
+
+    /// Topic and payload of an RPC request, sent as
+    /// {"device": name, "data": {"id": id, <request>}} for a device behind a gateway
+    pub fn rpc_request(&self, id: u32, request: &str) -> Result<(String, String), DMError> {
+        match self {
+            DeviceRoute::Direct(who) => Ok((
+                format!("v1/devices/{who}/rpc/request/{id}"),
+                request.to_owned(),
+            )),
+            DeviceRoute::Gateway(device) => {
+                let mut data = parse_object(request)?;
+                data.insert("id", id).map_err(|e| {
+                    Report::new(DMError::InvalidData).attach_printable(e.to_string())
+                })?;
+                let payload = json::object! { "device": device.as_str(), "data": data };
+                Ok(("v1/gateway/rpc".to_owned(), payload.dump()))
+            }
+        }
+    }
+}
+
+fn parse_object(payload: &str) -> Result<JsonValue, DMError> {
+    match json::parse(payload) {
+        Ok(value) if value.is_object() => Ok(value),
+        _ => Err(Report::new(DMError::InvalidData)
+            .attach_printable("Only JSON objects can be sent through a gateway")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_device_topics() {
+        assert_eq!(
+            classify("v1/devices/me/attributes/request/1000"),
+            TopicKind::ConnectRequest {
+                who: "me",
+                req_id: 1000
+            }
+        );
+        assert_eq!(
+            classify("v1/devices/cam-1_a.b/attributes/response/7"),
+            TopicKind::ConnectResponse {
+                who: "cam-1_a.b",
+                req_id: 7
+            }
+        );
+        assert_eq!(classify("v1/devices/me/attributes"), TopicKind::Attributes);
+        assert_eq!(
+            classify("v1/devices/me/attributes/response"),
+            TopicKind::Attributes
+        );
+        // Too large for a req_id
+        assert_eq!(
+            classify("v1/devices/me/attributes/request/99999999999"),
+            TopicKind::Attributes
+        );
+
+        assert_eq!(
+            classify("v1/devices/me/rpc/request/12"),
+            TopicKind::RpcRequest(12)
+        );
+        assert_eq!(
+            classify("v1/devices/me/rpc/response/12"),
+            TopicKind::RpcResponse(Some(12))
+        );
+        assert_eq!(
+            classify("v1/devices/me/rpc/response/99999999999"),
+            TopicKind::RpcResponse(None)
+        );
+        assert_eq!(classify("v1/devices/me/rpc/response/abc"), TopicKind::Other);
+        assert_eq!(classify("v1/devices/me/telemetry"), TopicKind::Telemetry);
+
+        assert_eq!(classify("v1/devices//telemetry"), TopicKind::Other);
+        assert_eq!(classify("v1/devices/m e/telemetry"), TopicKind::Other);
+        assert_eq!(classify("v1/devices/me"), TopicKind::Other);
+        assert_eq!(classify("v2/devices/me/telemetry"), TopicKind::Other);
+    }
+
+    #[test]
+    fn test_classify_gateway_topics() {
+        assert_eq!(
+            classify("v1/gateway/attributes"),
+            TopicKind::GatewayAttributes
+        );
+        assert_eq!(
+            classify("v1/gateway/attributes/response"),
+            TopicKind::GatewayAttributesResponse
+        );
+        for topic in [
+            "v1/gateway/connect",
+            "v1/gateway/disconnect",
+            "v1/gateway/attributes/request",
+        ] {
+            assert_eq!(classify(topic), TopicKind::GatewayClient);
+        }
+        assert_eq!(classify("v1/gateway/rpc"), TopicKind::GatewayRpc);
+        assert_eq!(classify("v1/gateway/rpc/1"), TopicKind::Gateway);
+        assert_eq!(classify("v1/gateway/"), TopicKind::Gateway);
+    }
+
+    #[test]
+    fn test_device_route_of_report() {
+        assert_eq!(
+            DeviceRoute::of_report("v1/devices/me/telemetry", "{}"),
+            Some(DeviceRoute::default())
+        );
+        assert_eq!(
+            DeviceRoute::of_report("v1/devices/cam-1/attributes", "{}"),
+            Some(DeviceRoute::Direct("cam-1".to_owned()))
+        );
+        assert_eq!(
+            DeviceRoute::of_report("v1/devices/me/rpc/response/1", "{}"),
+            None
+        );
+
+        let gateway = Some(DeviceRoute::Gateway("cam-1".to_owned()));
+        assert_eq!(
+            DeviceRoute::of_report("v1/gateway/telemetry", r#"{"cam-1":[{"a":1}]}"#),
+            gateway
+        );
+        assert_eq!(
+            DeviceRoute::of_report("v1/gateway/attributes", r#"{"device":"cam-1","data":{}}"#),
+            gateway
+        );
+        assert_eq!(
+            DeviceRoute::of_report("v1/gateway/connect", r#"{"device":"cam-1"}"#),
+            gateway
+        );
+        assert_eq!(DeviceRoute::of_report("v1/gateway/rpc", "{}"), None);
+        assert_eq!(DeviceRoute::of_report("v1/gateway/telemetry", "[]"), None);
+    }
+
+    #[test]
+    fn test_device_route_direct() {
+        let route = DeviceRoute::Direct("cam-1".to_owned());
+        assert_eq!(
+            route.attributes(r#"{"a":1}"#).unwrap(),
+            (
+                "v1/devices/cam-1/attributes".to_owned(),
+                r#"{"a":1}"#.to_owned()
+            )
+        );
+        assert_eq!(
+            route.rpc_request(12, r#"{"params":{}}"#).unwrap(),
+            (
+                "v1/devices/cam-1/rpc/request/12".to_owned(),
+                r#"{"params":{}}"#.to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn test_device_route_gateway() {
+        let route = DeviceRoute::Gateway("cam-1".to_owned());
+        assert_eq!(
+            route.attributes(r#"{"a":1}"#).unwrap(),
+            (
+                "v1/gateway/attributes".to_owned(),
+                r#"{"device":"cam-1","data":{"a":1}}"#.to_owned()
+            )
+        );
+        assert_eq!(
+            route.rpc_request(12, r#"{"params":{}}"#).unwrap(),
+            (
+                "v1/gateway/rpc".to_owned(),
+                r#"{"device":"cam-1","data":{"params":{},"id":12}}"#.to_owned()
+            )
+        );
+        assert!(route.attributes("[1]").is_err());
+        assert!(route.rpc_request(12, "x").is_err());
+    }
+}
@@ -0,0 +1,189 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Largest payload parsed by default, the previous MQTT packet size limit
+pub const DEFAULT_MAX_PAYLOAD: usize = 262144;
+/// Messages accepted per second on a topic by default
+pub const DEFAULT_RATE_LIMIT: u32 = 100;
+
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GuardConfig {
+    pub max_payload: usize,
+    /// Messages per second per topic, 0 for no limit
+    pub rate_limit: u32,
+}
+
+impl Default for GuardConfig {
+    fn default() -> Self {
+        Self {
+            max_payload: DEFAULT_MAX_PAYLOAD,
+            rate_limit: DEFAULT_RATE_LIMIT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Accept,
+    /// Too large to be parsed
+    Oversized,
+    /// Too many messages on the topic in the current second
+    RateLimited,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GuardStats {
+    pub received: u64,
+    pub bytes: u64,
+    pub oversized: u64,
+    pub rate_limited: u64,
+}
+
+/// Protects the parsing and the UI from a runaway device
+#[derive(Debug, Default)]
+pub struct MessageGuard {
+    config: GuardConfig,
+    /// Start of the current window and messages received in it, per topic. Only the topics
+    /// received in the last second are kept.
+    windows: HashMap<String, (Instant, u32)>,
+    stats: GuardStats,
+}
+
+impl MessageGuard {
+    pub fn new(config: GuardConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn config(&self) -> GuardConfig {
+        self.config
+    }
+
+    pub fn stats(&self) -> &GuardStats {
+        &self.stats
+    }
+
+    pub fn check(&mut self, topic: &str, payload_len: usize, now: Instant) -> Verdict {
+        self.stats.received += 1;
+        self.stats.bytes += payload_len as u64;
+
+        if self.config.rate_limit > 0 {
+            // Expired windows are dropped, so that the topics seen once are not kept forever
+            self.windows
+                .retain(|_, (start, _)| now.duration_since(*start) < RATE_WINDOW);
+            let window = self.windows.entry(topic.to_owned()).or_insert((now, 0));
+            window.1 += 1;
+
+            if window.1 > self.config.rate_limit {
+                self.stats.rate_limited += 1;
+                return Verdict::RateLimited;
+            }
+        }
+
+        if payload_len > self.config.max_payload {
+            self.stats.oversized += 1;
+            return Verdict::Oversized;
+        }
+
+        Verdict::Accept
+    }
+}
+
+/// Beginning of a payload for the log, cut on a character boundary
+pub fn truncate(payload: &[u8], max: usize) -> String {
+    let mut end = payload.len().min(max);
+    // Back to the first byte of the UTF-8 character cut, at most 3 continuation bytes
+    while end > 0 && end < payload.len() && max - end < 3 && payload[end] & 0xC0 == 0x80 {
+        end -= 1;
+    }
+    let head = &payload[..end];
+    let text = String::from_utf8_lossy(head);
+    if payload.len() > max {
+        format!("{}... ({} bytes)", text, payload.len())
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_and_size() {
+        let now = Instant::now();
+        let mut guard = MessageGuard::new(GuardConfig {
+            max_payload: 10,
+            rate_limit: 2,
+        });
+
+        assert_eq!(guard.check("a", 5, now), Verdict::Accept);
+        assert_eq!(guard.check("a", 5, now), Verdict::Accept);
+        assert_eq!(guard.check("a", 5, now), Verdict::RateLimited);
+        // Other topics have their own limit
+        assert_eq!(guard.check("b", 11, now), Verdict::Oversized);
+        // A new window starts after a second
+        assert_eq!(
+            guard.check("a", 5, now + Duration::from_secs(1)),
+            Verdict::Accept
+        );
+
+        let stats = guard.stats();
+        assert_eq!(
+            (
+                stats.received,
+                stats.bytes,
+                stats.oversized,
+                stats.rate_limited
+            ),
+            (5, 31, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_expired_windows_dropped() {
+        let now = Instant::now();
+        let mut guard = MessageGuard::new(GuardConfig::default());
+        for i in 0..100 {
+            guard.check(&format!("topic/{i}"), 1, now);
+        }
+        assert_eq!(guard.windows.len(), 100);
+
+        guard.check("a", 1, now + Duration::from_secs(1));
+        assert_eq!(guard.windows.len(), 1);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate(b"abc", 5), "abc");
+        assert_eq!(truncate(b"abcdef", 3), "abc... (6 bytes)");
+        // "é" is 2 bytes, not cut in the middle
+        assert_eq!(truncate("aé".as_bytes(), 2), "a... (3 bytes)");
+        assert_eq!(truncate("aé".as_bytes(), 3), "aé");
+        // Not further back than a character in invalid UTF-8
+        assert_eq!(truncate(&[0x80; 8], 4), "\u{FFFD}... (8 bytes)");
+    }
+}
@@ -0,0 +1,390 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::{DMError, DMErrorExt},
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jerror, jinfo},
+    json::JsonValue,
+    std::{
+        collections::HashMap,
+        io::Write,
+        process::{Command, Stdio},
+        sync::mpsc,
+    },
+};
+
+/// Reaction of a hook to a message
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HookOutput {
+    /// Message displayed in the footer
+    pub info: Option<String>,
+    /// Decoded values, added to the result of `MqttCtrl::on_message()`
+    pub values: HashMap<String, String>,
+}
+
+impl HookOutput {
+    fn merge(&mut self, other: HookOutput) {
+        if other.info.is_some() {
+            self.info = other.info;
+        }
+        self.values.extend(other.values);
+    }
+}
+
+/// Additional handler of the messages published on the topics matching its filter, run before
+/// the message is parsed by `MqttCtrl::on_message()`, e.g. to decode the telemetry of a
+/// proprietary edge app.
+///
+/// Hooks are registered with `MqttCtrl::register_hook()`, external programs are run by
+/// [`CommandHook`].
+pub trait MessageHook: Send {
+    fn name(&self) -> &str;
+
+    /// Topic filter, "+" matches one level and "#" the remaining levels
+    fn topic_filter(&self) -> &str;
+
+    /// Top-level keys of the JSON payload the hook reacts to, any payload if empty
+    fn keys(&self) -> &[String] {
+        &[]
+    }
+
+    fn handle(&mut self, topic: &str, payload: &str) -> Result<HookOutput, DMError>;
+
+    /// Output of a message handled asynchronously, polled on every update
+    fn poll(&mut self) -> Option<HookOutput> {
+        None
+    }
+}
+
+/// Whether the topic matches an MQTT topic filter
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+    let mut topic_levels = topic.split('/');
+
+    for level in filter.split('/') {
+        match (level, topic_levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(_)) => {}
+            (level, Some(t)) if level == t => {}
+            _ => return false,
+        }
+    }
+
+    topic_levels.next().is_none()
+}
+
+fn payload_has_keys(keys: &[String], payload: &str) -> bool {
+    if keys.is_empty() {
+        return true;
+    }
+
+    match json::parse(payload) {
+        Ok(JsonValue::Object(obj)) => keys.iter().any(|k| obj.get(k).is_some()),
+        _ => false,
+    }
+}
+
+#[derive(Default)]
+pub struct HookRegistry {
+    hooks: Vec<Box<dyn MessageHook>>,
+}
+
+impl HookRegistry {
+    pub fn register(&mut self, hook: Box<dyn MessageHook>) {
+        jinfo!(
+            event = "HOOK",
+            name = hook.name(),
+            topic_filter = hook.topic_filter()
+        );
+        self.hooks.push(hook);
+    }
+
+    /// Runs the hooks matching the message, a failing hook does not prevent the others
+    pub fn dispatch(&mut self, topic: &str, payload: &str) -> HookOutput {
+        let mut output = HookOutput::default();
+
+        for hook in self.hooks.iter_mut() {
+            if !topic_matches(hook.topic_filter(), topic) || !payload_has_keys(hook.keys(), payload)
+            {
+                continue;
+            }
+
+            match hook.handle(topic, payload) {
+                Ok(o) => output.merge(o),
+                Err(e) => {
+                    jerror!(
+                        func = "HookRegistry::dispatch()",
+                        hook = hook.name(),
+                        error = format!("{:?}", e)
+                    );
+                    output.info = Some(format!(
+                        "Hook {} failed: {}",
+                        hook.name(),
+                        e.error_str().unwrap_or("Unknown error".to_owned())
+                    ));
+                }
+            }
+        }
+
+        output
+    }
+
+    pub fn poll(&mut self) -> HookOutput {
+        let mut output = HookOutput::default();
+        for hook in self.hooks.iter_mut() {
+            while let Some(o) = hook.poll() {
+                output.merge(o);
+            }
+        }
+        output
+    }
+}
+
+/// Runs a shell command for each matching message, with the payload on its standard input
+/// and the topic in the DM_TOPIC environment variable.
+///
+/// Lines printed as "key=value" are decoded values and the last other line is displayed.
+/// A message arriving while the previous command is still running is skipped.
+pub struct CommandHook {
+    name: String,
+    topic_filter: String,
+    command: String,
+    busy: bool,
+    sender: mpsc::Sender<HookOutput>,
+    receiver: mpsc::Receiver<HookOutput>,
+}
+
+impl CommandHook {
+    pub fn new(topic_filter: &str, command: &str) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            name: command.split_whitespace().next().unwrap_or("").to_owned(),
+            topic_filter: topic_filter.to_owned(),
+            command: command.to_owned(),
+            busy: false,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Parses "<topic filter>=<command>"
+    pub fn parse(s: &str) -> Result<Self, DMError> {
+        match s.split_once('=') {
+            Some((filter, command)) if !filter.is_empty() && !command.trim().is_empty() => {
+                Ok(Self::new(filter, command))
+            }
+            _ => Err(Report::new(DMError::InvalidData)
+                .attach_printable(format!("Hook must be <topic filter>=<command>: {}", s))),
+        }
+    }
+
+    fn parse_output(stdout: &str) -> HookOutput {
+        let mut output = HookOutput::default();
+        for line in stdout.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            match line.split_once('=') {
+                Some((k, v)) if !k.contains(char::is_whitespace) => {
+                    output.values.insert(k.to_owned(), v.to_owned());
+                }
+                _ => output.info = Some(line.to_owned()),
+            }
+        }
+        output
+    }
+}
+
+impl MessageHook for CommandHook {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn topic_filter(&self) -> &str {
+        &self.topic_filter
+    }
+
+    fn handle(&mut self, topic: &str, payload: &str) -> Result<HookOutput, DMError> {
+        if self.busy {
+            jdebug!(
+                func = "CommandHook::handle()",
+                hook = self.name,
+                note = "busy"
+            );
+            return Ok(HookOutput::default());
+        }
+
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .env("DM_TOPIC", topic)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
+
+        let mut stdin = child.stdin.take();
+        let payload = payload.to_owned();
+        let sender = self.sender.clone();
+        self.busy = true;
+
+        // Waiting for the command would block the UI
+        std::thread::spawn(move || {
+            if let Some(stdin) = stdin.as_mut() {
+                let _ = stdin.write_all(payload.as_bytes());
+            }
+            drop(stdin);
+
+            let output = match child.wait_with_output() {
+                Ok(o) if o.status.success() => {
+                    CommandHook::parse_output(&String::from_utf8_lossy(&o.stdout))
+                }
+                Ok(o) => HookOutput {
+                    info: Some(format!("Hook command exited with {}", o.status)),
+                    ..Default::default()
+                },
+                Err(e) => HookOutput {
+                    info: Some(format!("Hook command failed: {}", e)),
+                    ..Default::default()
+                },
+            };
+            let _ = sender.send(output);
+        });
+
+        Ok(HookOutput::default())
+    }
+
+    fn poll(&mut self) -> Option<HookOutput> {
+        let output = self.receiver.try_recv().ok()?;
+        self.busy = false;
+        Some(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    struct CountHook {
+        keys: Vec<String>,
+        count: usize,
+    }
+
+    impl MessageHook for CountHook {
+        fn name(&self) -> &str {
+            "count"
+        }
+
+        fn topic_filter(&self) -> &str {
+            "v1/devices/+/telemetry"
+        }
+
+        fn keys(&self) -> &[String] {
+            &self.keys
+        }
+
+        fn handle(&mut self, _topic: &str, payload: &str) -> Result<HookOutput, DMError> {
+            if payload.contains("bad") {
+                return Err(Report::new(DMError::InvalidData).attach_printable("bad payload"));
+            }
+            self.count += 1;
+            let mut output = HookOutput::default();
+            output
+                .values
+                .insert("count".to_owned(), self.count.to_string());
+            Ok(output)
+        }
+    }
+
+    #[test]
+    fn test_topic_matches() {
+        assert!(topic_matches(
+            "v1/devices/me/telemetry",
+            "v1/devices/me/telemetry"
+        ));
+        assert!(topic_matches(
+            "v1/devices/+/telemetry",
+            "v1/devices/me/telemetry"
+        ));
+        assert!(topic_matches("v1/#", "v1/devices/me/telemetry"));
+        assert!(topic_matches("#", "v1"));
+        assert!(!topic_matches("v1/devices/+", "v1/devices/me/telemetry"));
+        assert!(!topic_matches("v1/devices/+/telemetry", "v1/devices/me"));
+        assert!(!topic_matches("v1/gateway/#", "v1/devices/me/telemetry"));
+    }
+
+    #[test]
+    fn test_hook_registry() {
+        let mut registry = HookRegistry::default();
+        registry.register(Box::new(CountHook {
+            keys: vec!["my_app/result".to_owned()],
+            count: 0,
+        }));
+
+        let topic = "v1/devices/me/telemetry";
+        let output = registry.dispatch(topic, r#"{"my_app/result": 1}"#);
+        assert_eq!(output.values.get("count").map(|s| s.as_str()), Some("1"));
+
+        // Other keys and topics are not handled
+        assert_eq!(
+            registry.dispatch(topic, r#"{"other": 1}"#),
+            HookOutput::default()
+        );
+        assert_eq!(
+            registry.dispatch("v1/devices/me/attributes", r#"{"my_app/result": 1}"#),
+            HookOutput::default()
+        );
+
+        let output = registry.dispatch(topic, r#"{"my_app/result": "bad"}"#);
+        assert_eq!(
+            output.info.as_deref(),
+            Some("Hook count failed: bad payload")
+        );
+    }
+
+    #[test]
+    fn test_command_hook() {
+        assert!(CommandHook::parse("no command").is_err());
+        assert!(CommandHook::parse("v1/#=").is_err());
+
+        let output = CommandHook::parse_output("temperature=42\n\ndecoded 1 value\n");
+        assert_eq!(output.info.as_deref(), Some("decoded 1 value"));
+        assert_eq!(
+            output.values.get("temperature").map(|s| s.as_str()),
+            Some("42")
+        );
+
+        let mut registry = HookRegistry::default();
+        registry.register(Box::new(
+            CommandHook::parse("v1/#=echo topic=$DM_TOPIC; cat").unwrap(),
+        ));
+        registry.dispatch("v1/devices/me/telemetry", "payload");
+
+        let start = Instant::now();
+        let output = loop {
+            let output = registry.poll();
+            if output != HookOutput::default() || start.elapsed() > Duration::from_secs(5) {
+                break output;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(
+            output.values.get("topic").map(|s| s.as_str()),
+            Some("v1/devices/me/telemetry")
+        );
+        assert_eq!(output.info.as_deref(), Some("payload"));
+    }
+}
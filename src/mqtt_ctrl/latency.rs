@@ -0,0 +1,144 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Latencies kept of each kind for the sparklines
+const HISTORY_SIZE: usize = 120;
+
+/// Latencies the usual one is learnt from before a degradation is reported
+const MIN_SAMPLES: usize = 5;
+
+/// Ratio of the latest latency to the median of the previous ones reported as degraded
+const DEGRADED_FACTOR: u32 = 3;
+
+/// Round-trip times between device-monitor and the device, through the broker and the
+/// network: the connect request answered until the first state report, and the RPCs
+/// until their response
+#[derive(Debug, Default)]
+pub struct LatencyTracker {
+    /// When the last connect request was answered, until the device reports its state
+    connect_answered: Option<Instant>,
+    /// Oldest first
+    connect: VecDeque<Duration>,
+    /// Oldest first
+    rpc: VecDeque<Duration>,
+}
+
+fn push(history: &mut VecDeque<Duration>, latency: Duration) {
+    if history.len() == HISTORY_SIZE {
+        history.pop_front();
+    }
+    history.push_back(latency);
+}
+
+impl LatencyTracker {
+    /// The attributes response to a connect request was published
+    pub fn connect_answered(&mut self, now: Instant) {
+        self.connect_answered = Some(now);
+    }
+
+    /// The device reported a part of its state, the first report after the connect
+    /// response ends the round-trip
+    pub fn state_reported(&mut self, now: Instant) {
+        if let Some(answered) = self.connect_answered.take() {
+            push(&mut self.connect, now.duration_since(answered));
+        }
+    }
+
+    pub fn rpc_answered(&mut self, round_trip: Duration) {
+        push(&mut self.rpc, round_trip);
+    }
+
+    pub fn connect(&self) -> &VecDeque<Duration> {
+        &self.connect
+    }
+
+    pub fn rpc(&self) -> &VecDeque<Duration> {
+        &self.rpc
+    }
+}
+
+pub fn median(latencies: impl Iterator<Item = Duration>) -> Option<Duration> {
+    let mut latencies: Vec<Duration> = latencies.collect();
+    latencies.sort();
+    latencies.get(latencies.len() / 2).copied()
+}
+
+/// Whether the latest latency is `DEGRADED_FACTOR` times the median of the previous ones,
+/// e.g. the broker or the network slowing down
+pub fn is_degraded(history: &VecDeque<Duration>) -> bool {
+    let Some(latest) = history.back() else {
+        return false;
+    };
+    if history.len() <= MIN_SAMPLES {
+        return false;
+    }
+
+    median(history.iter().rev().skip(1).copied())
+        .is_some_and(|usual| *latest > usual * DEGRADED_FACTOR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connect_round_trip() {
+        let now = Instant::now();
+        let mut tracker = LatencyTracker::default();
+
+        // State reports without a connect request are not round-trips
+        tracker.state_reported(now);
+        assert!(tracker.connect().is_empty());
+
+        tracker.connect_answered(now);
+        tracker.state_reported(now + Duration::from_millis(250));
+        tracker.state_reported(now + Duration::from_millis(400));
+        assert_eq!(
+            tracker.connect().iter().collect::<Vec<_>>(),
+            [&Duration::from_millis(250)]
+        );
+
+        for _ in 0..HISTORY_SIZE {
+            tracker.rpc_answered(Duration::from_millis(10));
+        }
+        tracker.rpc_answered(Duration::from_millis(20));
+        assert_eq!(tracker.rpc().len(), HISTORY_SIZE);
+        assert_eq!(tracker.rpc().back(), Some(&Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_is_degraded() {
+        let ms = |values: &[u64]| -> VecDeque<Duration> {
+            values.iter().map(|v| Duration::from_millis(*v)).collect()
+        };
+
+        assert!(!is_degraded(&ms(&[])));
+        // Not learnt yet
+        assert!(!is_degraded(&ms(&[100, 100, 100, 100, 900])));
+        assert!(is_degraded(&ms(&[100, 120, 90, 100, 110, 400])));
+        assert!(!is_degraded(&ms(&[100, 120, 90, 100, 110, 250])));
+        assert_eq!(
+            median(ms(&[30, 10, 20]).into_iter()),
+            Some(Duration::from_millis(20))
+        );
+    }
+}
@@ -0,0 +1,213 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::DMError,
+    error_stack::{Report, Result},
+    std::time::{Duration, Instant},
+};
+
+/// Interval between two prunings, the stores only grow by a few messages in between
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(10);
+
+const MIB: usize = 1024 * 1024;
+
+/// Stores kept for the whole session, whose size depends on the device and the broker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Elogs,
+    /// Latest message of the topics not updating the device state
+    Traffic,
+    Retained,
+    /// Temperatures and hours meter
+    TimeSeries,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 4] = [
+        Subsystem::Elogs,
+        Subsystem::Traffic,
+        Subsystem::Retained,
+        Subsystem::TimeSeries,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Subsystem::Elogs => "elogs",
+            Subsystem::Traffic => "traffic",
+            Subsystem::Retained => "retained",
+            Subsystem::TimeSeries => "metrics",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Subsystem::ALL.iter().position(|s| s == self).unwrap()
+    }
+}
+
+/// Bytes each store may keep, its oldest data is pruned beyond
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryBudgets([usize; 4]);
+
+impl Default for MemoryBudgets {
+    fn default() -> Self {
+        Self([MIB, 8 * MIB, 8 * MIB, MIB])
+    }
+}
+
+impl MemoryBudgets {
+    pub fn get(&self, subsystem: Subsystem) -> usize {
+        self.0[subsystem.index()]
+    }
+
+    /// Changes one budget from "<store>=<MiB>", e.g. "traffic=16"
+    pub fn set(&mut self, spec: &str) -> Result<(), DMError> {
+        let invalid = || {
+            Report::new(DMError::InvalidData).attach_printable(format!(
+                "Invalid memory budget {spec}, expected <store>=<MiB> with the store one of {}",
+                Subsystem::ALL.map(|s| s.name()).join(", ")
+            ))
+        };
+
+        let (name, mib) = spec.split_once('=').ok_or_else(invalid)?;
+        let subsystem = Subsystem::ALL
+            .into_iter()
+            .find(|s| s.name() == name.trim())
+            .ok_or_else(invalid)?;
+        let mib: usize = mib.trim().parse().map_err(|_| invalid())?;
+
+        self.0[subsystem.index()] = mib * MIB;
+        Ok(())
+    }
+}
+
+/// Size of a store against its budget
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryUsage {
+    pub subsystem: Subsystem,
+    /// Approximate bytes kept
+    pub used: usize,
+    pub budget: usize,
+    /// Items pruned since the start
+    pub pruned: usize,
+}
+
+/// Budgets of the stores, and when they were last pruned
+#[derive(Debug, Default)]
+pub struct MemoryMonitor {
+    budgets: MemoryBudgets,
+    pruned: [usize; 4],
+    last_pruned: Option<Instant>,
+}
+
+impl MemoryMonitor {
+    pub fn budgets(&self) -> &MemoryBudgets {
+        &self.budgets
+    }
+
+    pub fn set_budgets(&mut self, budgets: MemoryBudgets) {
+        self.budgets = budgets;
+    }
+
+    /// Whether the stores are due for pruning, the clock restarts if so
+    pub fn is_due(&mut self, now: Instant) -> bool {
+        let due = self
+            .last_pruned
+            .is_none_or(|last| now.duration_since(last) >= PRUNE_INTERVAL);
+        if due {
+            self.last_pruned = Some(now);
+        }
+        due
+    }
+
+    pub fn record(&mut self, subsystem: Subsystem, pruned: usize) {
+        self.pruned[subsystem.index()] += pruned;
+    }
+
+    pub fn usage(&self, subsystem: Subsystem, used: usize) -> MemoryUsage {
+        MemoryUsage {
+            subsystem,
+            used,
+            budget: self.budgets.get(subsystem),
+            pruned: self.pruned[subsystem.index()],
+        }
+    }
+}
+
+/// Approximate size of a string, its header and its content
+pub fn string_size(s: &str) -> usize {
+    size_of::<String>() + s.len()
+}
+
+/// e.g. "512 B", "12.5 KiB" or "8.0 MiB"
+pub fn format_bytes(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < MIB {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / MIB as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_budgets() {
+        let mut budgets = MemoryBudgets::default();
+        assert_eq!(budgets.get(Subsystem::Traffic), 8 * MIB);
+
+        budgets.set("traffic=16").unwrap();
+        budgets.set(" elogs = 2 ").unwrap();
+        assert_eq!(budgets.get(Subsystem::Traffic), 16 * MIB);
+        assert_eq!(budgets.get(Subsystem::Elogs), 2 * MIB);
+
+        assert!(budgets.set("traffic").is_err());
+        assert!(budgets.set("cache=1").is_err());
+        assert!(budgets.set("metrics=-1").is_err());
+    }
+
+    #[test]
+    fn test_memory_monitor() {
+        let now = Instant::now();
+        let mut monitor = MemoryMonitor::default();
+        assert!(monitor.is_due(now));
+        assert!(!monitor.is_due(now + Duration::from_secs(9)));
+        assert!(monitor.is_due(now + PRUNE_INTERVAL));
+
+        monitor.record(Subsystem::Retained, 2);
+        monitor.record(Subsystem::Retained, 3);
+        assert_eq!(
+            monitor.usage(Subsystem::Retained, 100),
+            MemoryUsage {
+                subsystem: Subsystem::Retained,
+                used: 100,
+                budget: 8 * MIB,
+                pruned: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(12800), "12.5 KiB");
+        assert_eq!(format_bytes(8 * MIB), "8.0 MiB");
+    }
+}
@@ -0,0 +1,87 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::evp::device_info::{ChipInfo, DeviceInfo, DeviceStates},
+    chrono::{DateTime, Local},
+    std::collections::VecDeque,
+};
+
+/// Samples kept, one day at the default report interval of one minute
+const HISTORY_SIZE: usize = 1440;
+
+/// Temperatures and hours meter reported by the device at one time
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    pub time: DateTime<Local>,
+    pub main_chip_temperature: Option<i32>,
+    pub sensor_chip_temperature: Option<i32>,
+    pub companion_chip_temperature: Option<i32>,
+    pub hours_meter: Option<i32>,
+}
+
+/// Time series of the device metrics, oldest first
+#[derive(Debug, Default)]
+pub struct Metrics {
+    samples: VecDeque<MetricSample>,
+}
+
+impl Metrics {
+    /// Records the latest values on each device info or device states report
+    pub fn record(
+        &mut self,
+        device_info: Option<&DeviceInfo>,
+        device_states: Option<&DeviceStates>,
+        time: DateTime<Local>,
+    ) {
+        // ChipInfo and DeviceStates use out of range defaults for the values not reported
+        let temperature =
+            |chip: Option<&ChipInfo>| chip.map(|c| c.temperature()).filter(|t| *t > -300);
+
+        if self.samples.len() == HISTORY_SIZE {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(MetricSample {
+            time,
+            main_chip_temperature: temperature(device_info.and_then(|d| d.main_chip())),
+            sensor_chip_temperature: temperature(device_info.and_then(|d| d.sensor_chip())),
+            companion_chip_temperature: temperature(device_info.and_then(|d| d.companion_chip())),
+            hours_meter: device_states.map(|s| s.hours_meter()).filter(|h| *h >= 0),
+        });
+    }
+
+    pub fn samples(&self) -> &VecDeque<MetricSample> {
+        &self.samples
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn memory_size(&self) -> usize {
+        self.samples.len() * size_of::<MetricSample>()
+    }
+
+    /// Drops the oldest samples until the others fit in `budget` bytes, returns the number
+    /// of samples dropped
+    pub fn prune(&mut self, budget: usize) -> usize {
+        let kept = budget / size_of::<MetricSample>();
+        let pruned = self.samples.len().saturating_sub(kept);
+        self.samples.drain(..pruned);
+        pruned
+    }
+}
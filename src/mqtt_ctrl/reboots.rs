@@ -0,0 +1,150 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use chrono::{DateTime, Duration, Local};
+
+/// Reboots kept for the history
+const MAX_REBOOTS: usize = 100;
+
+/// A boot loop is suspected when the device reboots this many times within `BOOT_LOOP_WINDOW`
+pub const BOOT_LOOP_REBOOTS: usize = 3;
+pub const BOOT_LOOP_WINDOW: Duration = Duration::minutes(10);
+
+/// One reboot of the device and how long it kept running afterwards
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reboot {
+    pub time: DateTime<Local>,
+    /// Time until the next reboot, None while the device is still running
+    pub uptime: Option<Duration>,
+}
+
+/// Reboots of the device, detected from the connection requests it sends after booting
+#[derive(Debug, Clone, Default)]
+pub struct RebootTracker {
+    times: Vec<DateTime<Local>>,
+    total: usize,
+}
+
+impl RebootTracker {
+    pub fn record(&mut self, now: DateTime<Local>) {
+        self.times.push(now);
+        if self.times.len() > MAX_REBOOTS {
+            self.times.remove(0);
+        }
+        self.total += 1;
+    }
+
+    /// Reboots of the session, including the ones no longer in the history
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Reboots newest first
+    pub fn history(&self) -> Vec<Reboot> {
+        let mut history: Vec<Reboot> = self
+            .times
+            .iter()
+            .enumerate()
+            .map(|(i, time)| Reboot {
+                time: *time,
+                uptime: self.times.get(i + 1).map(|next| *next - *time),
+            })
+            .collect();
+        history.reverse();
+        history
+    }
+
+    /// Time since the last reboot
+    pub fn uptime(&self, now: DateTime<Local>) -> Option<Duration> {
+        self.times.last().map(|last| now - *last)
+    }
+
+    /// Reboots within `BOOT_LOOP_WINDOW` while the device reboots too often
+    pub fn boot_loop(&self, now: DateTime<Local>) -> Option<usize> {
+        let recent = self
+            .times
+            .iter()
+            .filter(|time| now - **time <= BOOT_LOOP_WINDOW)
+            .count();
+
+        (recent >= BOOT_LOOP_REBOOTS).then_some(recent)
+    }
+}
+
+/// Duration as "1d 02:03:04", or "02:03:04" under a day
+pub fn format_uptime(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+    let hms = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    );
+
+    if days > 0 {
+        format!("{days}d {hms}")
+    } else {
+        hms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reboot_tracker() {
+        let start = Local::now();
+        let mut tracker = RebootTracker::default();
+        assert!(tracker.uptime(start).is_none());
+
+        tracker.record(start);
+        tracker.record(start + Duration::minutes(30));
+        assert!(tracker.boot_loop(start + Duration::minutes(30)).is_none());
+
+        tracker.record(start + Duration::minutes(32));
+        tracker.record(start + Duration::minutes(33));
+
+        let history = tracker.history();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0].uptime, None);
+        assert_eq!(history[1].uptime, Some(Duration::minutes(1)));
+        assert_eq!(history[3].uptime, Some(Duration::minutes(30)));
+        assert_eq!(
+            tracker.uptime(start + Duration::minutes(35)),
+            Some(Duration::minutes(2))
+        );
+
+        assert_eq!(tracker.boot_loop(start + Duration::minutes(35)), Some(3));
+        // Calms down once the reboots leave the window
+        assert!(tracker.boot_loop(start + Duration::minutes(43)).is_none());
+
+        for i in 0..MAX_REBOOTS {
+            tracker.record(start + Duration::hours(1 + i as i64));
+        }
+        assert_eq!(tracker.history().len(), MAX_REBOOTS);
+        assert_eq!(tracker.total(), MAX_REBOOTS + 4);
+    }
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(Duration::seconds(59)), "00:00:59");
+        assert_eq!(format_uptime(Duration::seconds(3723)), "01:02:03");
+        assert_eq!(format_uptime(Duration::seconds(90061)), "1d 01:01:01");
+    }
+}
@@ -0,0 +1,150 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::memory::string_size,
+    std::{collections::BTreeMap, time::Instant},
+};
+
+/// Retained message seen on a topic
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetainedMessage {
+    pub payload: String,
+    pub received: Instant,
+}
+
+/// Retained messages held by the broker, learned from the retain flag of the publishes
+/// received when subscribing
+#[derive(Debug, Default)]
+pub struct RetainedStore {
+    messages: BTreeMap<String, RetainedMessage>,
+    /// Start of the last scan
+    scanned: Option<Instant>,
+}
+
+impl RetainedStore {
+    /// Records a publish, an empty retained payload removes the message from the broker
+    pub fn track(&mut self, topic: &str, payload: &str, retain: bool, now: Instant) {
+        if !retain {
+            return;
+        }
+
+        if payload.is_empty() {
+            self.messages.remove(topic);
+        } else {
+            self.messages.insert(
+                topic.to_owned(),
+                RetainedMessage {
+                    payload: payload.to_owned(),
+                    received: now,
+                },
+            );
+        }
+    }
+
+    /// Forgets the messages seen so far, the broker sends them again on subscribe
+    pub fn start_scan(&mut self, now: Instant) {
+        self.messages.clear();
+        self.scanned = Some(now);
+    }
+
+    pub fn scanned(&self) -> Option<Instant> {
+        self.scanned
+    }
+
+    pub fn remove(&mut self, topic: &str) {
+        self.messages.remove(topic);
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Sorted by topic
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &RetainedMessage)> {
+        self.messages.iter()
+    }
+
+    pub fn memory_size(&self) -> usize {
+        self.messages
+            .iter()
+            .map(|(t, m)| size_of::<RetainedMessage>() + m.payload.len() + string_size(t))
+            .sum()
+    }
+
+    /// Forgets the messages received first until the others fit in `budget` bytes, they
+    /// are still held by the broker. Returns the number of messages forgotten.
+    pub fn prune(&mut self, budget: usize) -> usize {
+        let mut pruned = 0;
+        while self.memory_size() > budget
+            && let Some(oldest) = self
+                .messages
+                .iter()
+                .min_by_key(|(_, m)| m.received)
+                .map(|(t, _)| t.clone())
+        {
+            self.messages.remove(&oldest);
+            pruned += 1;
+        }
+        pruned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_track_retained() {
+        let now = Instant::now();
+        let mut store = RetainedStore::default();
+
+        store.track("v1/devices/me/attributes", "{}", true, now);
+        store.track("v1/devices/me/telemetry", "{}", false, now);
+        store.track("a/b", "x", true, now);
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.iter().next().unwrap().0, "a/b");
+
+        // Cleared by an empty retained payload
+        store.track("a/b", "", true, now);
+        assert_eq!(store.len(), 1);
+
+        store.start_scan(now);
+        assert!(store.is_empty());
+        assert_eq!(store.scanned(), Some(now));
+    }
+
+    #[test]
+    fn test_prune_retained() {
+        let now = Instant::now();
+        let mut store = RetainedStore::default();
+        store.track("b", &"x".repeat(1000), true, now);
+        store.track("a", &"x".repeat(1000), true, now + Duration::from_secs(1));
+
+        let size = store.memory_size();
+        assert_eq!(store.prune(size), 0);
+        assert_eq!(store.prune(size - 1), 1);
+        assert_eq!(store.iter().next().unwrap().0, "a");
+        assert_eq!(store.prune(0), 1);
+        assert!(store.is_empty());
+    }
+}
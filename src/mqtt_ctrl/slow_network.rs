@@ -0,0 +1,176 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::DMError,
+    error_stack::{Report, Result},
+    rand::Rng,
+    std::{
+        sync::mpsc,
+        thread::JoinHandle,
+        time::{Duration, Instant},
+    },
+};
+
+/// Debug mode delaying the events of the broker before MqttCtrl sees them, to try the UI
+/// on a poor network without shaping a real one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlowNetwork {
+    pub delay: Duration,
+    /// Extra delay of each event, random up to this one
+    pub jitter: Duration,
+}
+
+impl SlowNetwork {
+    /// Parses "<delay ms>" or "<delay ms>:<jitter ms>", e.g. "500:300"
+    pub fn parse(s: &str) -> Result<Self, DMError> {
+        let ms = |v: &str| {
+            v.trim()
+                .parse::<u64>()
+                .map(Duration::from_millis)
+                .map_err(|_| {
+                    Report::new(DMError::InvalidData).attach_printable(format!(
+                        "Invalid slow network {s}, expected <delay ms>[:<jitter ms>]"
+                    ))
+                })
+        };
+
+        let (delay, jitter) = s.split_once(':').unwrap_or((s, "0"));
+        Ok(Self {
+            delay: ms(delay)?,
+            jitter: ms(jitter)?,
+        })
+    }
+
+    /// Delay of one event, from `delay` to `delay + jitter`
+    pub fn sample(&self, rng: &mut impl Rng) -> Duration {
+        let jitter = rng.random_range(0..=self.jitter.as_millis() as u64);
+        self.delay + Duration::from_millis(jitter)
+    }
+
+    /// Time the event received at `received` is delivered. The events stay in order as on
+    /// a TCP connection, a short delay waits for the longer one of the previous event.
+    pub fn due(received: Instant, previous: Option<Instant>, delay: Duration) -> Instant {
+        let due = received + delay;
+        previous.map_or(due, |previous| due.max(previous))
+    }
+
+    /// Forwards the events from `upstream` to `downstream`, each one delayed from the time
+    /// it was received, so that a burst is delayed as a whole instead of one event after
+    /// the other. The thread ends when either side is closed.
+    pub fn spawn_relay<T: Send + 'static>(
+        self,
+        upstream: mpsc::Receiver<(Instant, T)>,
+        downstream: mpsc::Sender<(Instant, T)>,
+    ) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            let mut rng = rand::rng();
+            let mut previous = None;
+
+            while let Ok((received, event)) = upstream.recv() {
+                let due = Self::due(received, previous, self.sample(&mut rng));
+                std::thread::sleep(due.saturating_duration_since(Instant::now()));
+                previous = Some(due);
+
+                if downstream.send((received, event)).is_err() {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slow_network_parse() {
+        assert_eq!(
+            SlowNetwork::parse("500:300").unwrap(),
+            SlowNetwork {
+                delay: Duration::from_millis(500),
+                jitter: Duration::from_millis(300),
+            }
+        );
+        assert_eq!(SlowNetwork::parse("200").unwrap().jitter, Duration::ZERO);
+        assert!(SlowNetwork::parse("fast").is_err());
+        assert!(SlowNetwork::parse("100:-1").is_err());
+    }
+
+    #[test]
+    fn test_slow_network_due() {
+        let slow = SlowNetwork::parse("100:50").unwrap();
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let delay = slow.sample(&mut rng);
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(150));
+        }
+
+        // A shorter delay does not overtake the previous event
+        let now = Instant::now();
+        let previous = now + Duration::from_millis(150);
+        assert_eq!(
+            SlowNetwork::due(now, Some(previous), Duration::from_millis(100)),
+            previous
+        );
+        assert_eq!(
+            SlowNetwork::due(now, Some(previous), Duration::from_millis(200)),
+            now + Duration::from_millis(200)
+        );
+    }
+
+    #[test]
+    fn test_slow_network_relay() {
+        let slow = SlowNetwork::parse("30").unwrap();
+        let (upstream_tx, upstream_rx) = mpsc::channel();
+        let (downstream_tx, downstream_rx) = mpsc::channel();
+        let relay = slow.spawn_relay(upstream_rx, downstream_tx);
+
+        let start = Instant::now();
+        for i in 0..3 {
+            upstream_tx.send((Instant::now(), i)).unwrap();
+        }
+        drop(upstream_tx);
+
+        let events: Vec<i32> = downstream_rx.iter().map(|(_, i)| i).collect();
+        assert_eq!(events, [0, 1, 2]);
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        relay.join().unwrap();
+    }
+
+    #[test]
+    fn test_slow_network_relay_burst() {
+        let slow = SlowNetwork::parse("50:10").unwrap();
+        let (upstream_tx, upstream_rx) = mpsc::channel();
+        let (downstream_tx, downstream_rx) = mpsc::channel();
+        let relay = slow.spawn_relay(upstream_rx, downstream_tx);
+
+        let start = Instant::now();
+        for i in 0..10 {
+            upstream_tx.send((Instant::now(), i)).unwrap();
+        }
+        drop(upstream_tx);
+
+        // The burst takes one delay + jitter, not 10 of them
+        assert_eq!(downstream_rx.iter().count(), 10);
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed < Duration::from_millis(200), "{elapsed:?}");
+        relay.join().unwrap();
+    }
+}
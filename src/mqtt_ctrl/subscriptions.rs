@@ -0,0 +1,200 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use std::{collections::VecDeque, fmt::Display};
+
+/// Topic families used by the EVP agent, including devices behind a gateway
+pub const DEFAULT_TOPICS: [&str; 2] = ["v1/devices/#", "v1/gateway/#"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionState {
+    /// Not sent to the broker yet
+    Pending,
+    /// Waiting for the SubAck
+    Requested,
+    Active,
+    /// Refused by the broker
+    Failed,
+}
+
+impl Display for SubscriptionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SubscriptionState::Pending => "pending",
+            SubscriptionState::Requested => "requested",
+            SubscriptionState::Active => "active",
+            SubscriptionState::Failed => "failed",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subscription {
+    pub filter: String,
+    pub state: SubscriptionState,
+}
+
+/// '#' only as the last level and '+' only as a whole level
+pub fn valid_filter(filter: &str) -> bool {
+    if filter.is_empty() {
+        return false;
+    }
+
+    let levels: Vec<&str> = filter.split('/').collect();
+    levels.iter().enumerate().all(|(i, level)| match *level {
+        "#" => i == levels.len() - 1,
+        "+" => true,
+        level => !level.contains(['#', '+']),
+    })
+}
+
+/// Topic filters subscribed on the broker
+#[derive(Debug)]
+pub struct Subscriptions {
+    list: Vec<Subscription>,
+    /// Filters waiting for their SubAck, in the order they were requested
+    awaiting: VecDeque<String>,
+}
+
+impl Default for Subscriptions {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOPICS.iter().map(|t| t.to_string()).collect())
+    }
+}
+
+impl Subscriptions {
+    pub fn new(filters: Vec<String>) -> Self {
+        let mut subscriptions = Self {
+            list: vec![],
+            awaiting: VecDeque::new(),
+        };
+        for filter in filters {
+            subscriptions.add(&filter);
+        }
+        subscriptions
+    }
+
+    /// False if the filter is already subscribed
+    pub fn add(&mut self, filter: &str) -> bool {
+        if self.list.iter().any(|s| s.filter == filter) {
+            return false;
+        }
+
+        self.list.push(Subscription {
+            filter: filter.to_owned(),
+            state: SubscriptionState::Pending,
+        });
+        true
+    }
+
+    pub fn remove(&mut self, filter: &str) -> bool {
+        let len = self.list.len();
+        self.list.retain(|s| s.filter != filter);
+        self.list.len() != len
+    }
+
+    /// Filters to send to the broker, marked as requested
+    pub fn take_pending(&mut self) -> Vec<String> {
+        let mut filters = vec![];
+        for subscription in self.list.iter_mut() {
+            if subscription.state == SubscriptionState::Pending {
+                subscription.state = SubscriptionState::Requested;
+                self.awaiting.push_back(subscription.filter.clone());
+                filters.push(subscription.filter.clone());
+            }
+        }
+        filters
+    }
+
+    /// Result of the oldest subscription request, one filter being sent per request
+    pub fn on_suback(&mut self, success: bool) {
+        let Some(filter) = self.awaiting.pop_front() else {
+            return;
+        };
+
+        if let Some(subscription) = self.list.iter_mut().find(|s| s.filter == filter) {
+            subscription.state = if success {
+                SubscriptionState::Active
+            } else {
+                SubscriptionState::Failed
+            };
+        }
+    }
+
+    /// Subscribes everything again, e.g. after a reconnection
+    pub fn reset(&mut self) {
+        self.awaiting.clear();
+        for subscription in self.list.iter_mut() {
+            subscription.state = SubscriptionState::Pending;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Subscription> {
+        self.list.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_filter() {
+        assert!(valid_filter("v1/devices/#"));
+        assert!(valid_filter("v1/+/attributes"));
+        assert!(valid_filter("#"));
+        assert!(!valid_filter(""));
+        assert!(!valid_filter("v1/#/attributes"));
+        assert!(!valid_filter("v1/dev+/attributes"));
+    }
+
+    #[test]
+    fn test_subscription_states() {
+        let mut subscriptions = Subscriptions::default();
+        assert_eq!(subscriptions.len(), DEFAULT_TOPICS.len());
+        assert!(!subscriptions.add("v1/devices/#"));
+        assert!(subscriptions.add("my/topic"));
+
+        assert_eq!(subscriptions.take_pending().len(), 3);
+        assert!(subscriptions.take_pending().is_empty());
+
+        subscriptions.on_suback(true);
+        subscriptions.on_suback(false);
+        let states: Vec<_> = subscriptions.iter().map(|s| s.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                SubscriptionState::Active,
+                SubscriptionState::Failed,
+                SubscriptionState::Requested
+            ]
+        );
+
+        assert!(subscriptions.remove("my/topic"));
+        subscriptions.reset();
+        assert!(
+            subscriptions
+                .iter()
+                .all(|s| s.state == SubscriptionState::Pending)
+        );
+    }
+}
@@ -0,0 +1,345 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::evp::elog::Elog,
+    crate::error::DMError,
+    chrono::{DateTime, SecondsFormat},
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jerror},
+    std::{
+        io::Write,
+        net::{TcpStream, ToSocketAddrs, UdpSocket},
+        os::unix::net::UnixDatagram,
+        sync::mpsc,
+        thread,
+    },
+};
+
+const APP_NAME: &str = "device-monitor";
+
+/// Facility of the forwarded messages, local0
+const FACILITY: u8 = 16;
+
+/// Native protocol socket of systemd-journald
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// SD-ID of the structured data holding the elog IDs. 32473 is the example enterprise
+/// number of RFC 5612, a placeholder to be replaced by the PEN of the organization.
+pub const DEFAULT_SD_ID: &str = "elog@32473";
+
+/// Checks an SD-ID given as <name>@<private enterprise number>
+pub fn parse_sd_id(s: &str) -> Result<String, DMError> {
+    let valid = s.len() <= 32
+        && s.chars()
+            .all(|c| c.is_ascii_graphic() && !matches!(c, '=' | ']' | '"'))
+        && s.split_once('@').is_some_and(|(name, pen)| {
+            !name.is_empty() && !pen.is_empty() && pen.split('.').all(|n| n.parse::<u32>().is_ok())
+        });
+
+    if valid {
+        Ok(s.to_owned())
+    } else {
+        Err(Report::new(DMError::InvalidData).attach_printable(format!(
+            "SD-ID must be <name>@<private enterprise number>: {}",
+            s
+        )))
+    }
+}
+
+/// Where the elogs are forwarded
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyslogTarget {
+    Udp(String),
+    Tcp(String),
+    Journald,
+}
+
+impl SyslogTarget {
+    /// Parses "udp://<host>:<port>", "tcp://<host>:<port>" or "journald"
+    pub fn parse(s: &str) -> Result<Self, DMError> {
+        let target = if s == "journald" {
+            Some(SyslogTarget::Journald)
+        } else if let Some(addr) = s.strip_prefix("udp://") {
+            Some(SyslogTarget::Udp(addr.to_owned()))
+        } else {
+            s.strip_prefix("tcp://")
+                .map(|addr| SyslogTarget::Tcp(addr.to_owned()))
+        };
+
+        match target {
+            Some(SyslogTarget::Udp(addr) | SyslogTarget::Tcp(addr)) if !addr.contains(':') => {
+                Err(Report::new(DMError::InvalidData)
+                    .attach_printable(format!("Syslog address must be <host>:<port>: {}", s)))
+            }
+            Some(target) => Ok(target),
+            None => Err(Report::new(DMError::InvalidData).attach_printable(format!(
+                "Syslog target must be udp://<host>:<port>, tcp://<host>:<port> or journald: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Syslog severity of an elog level
+pub fn severity(level: u8) -> u8 {
+    match level {
+        0 => 2, // critical
+        1 => 3, // error
+        2 => 4, // warning
+        3 => 6, // informational
+        _ => 7, // debug
+    }
+}
+
+fn message(elog: &Elog) -> String {
+    let mut message = format!(
+        "{}: {} (0x{:04x})",
+        elog.component_name()
+            .map(str::to_owned)
+            .unwrap_or(elog.component_id().to_string()),
+        elog.event_str(),
+        elog.event_id()
+    );
+    if let Some(description) = elog.event_description() {
+        message.push_str(": ");
+        message.push_str(description);
+    }
+    message.replace(['\r', '\n'], " ")
+}
+
+/// Characters allowed in the header fields of RFC 5424, "-" when empty
+fn header_field(s: &str, max: usize) -> String {
+    let s: String = s
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max)
+        .collect();
+    if s.is_empty() { "-".to_owned() } else { s }
+}
+
+/// Formats the elog as an RFC 5424 message, with the device serial as the hostname and the
+/// elog IDs in the structured data `sd_id`
+pub fn format_rfc5424(elog: &Elog, sd_id: &str) -> String {
+    let timestamp = DateTime::parse_from_rfc3339(elog.timestamp())
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+        .unwrap_or("-".to_owned());
+
+    format!(
+        "<{}>1 {} {} {} - 0x{:04x} [{} component_id=\"{}\" event_id=\"{}\"] {}",
+        FACILITY * 8 + severity(elog.level()),
+        timestamp,
+        header_field(elog.serial(), 255),
+        APP_NAME,
+        elog.event_id(),
+        sd_id,
+        elog.component_id(),
+        elog.event_id(),
+        message(elog)
+    )
+}
+
+/// Formats the elog for the native journald protocol
+pub fn format_journald(elog: &Elog) -> String {
+    format!(
+        "PRIORITY={}\nSYSLOG_FACILITY={}\nSYSLOG_IDENTIFIER={}\nDEVICE_SERIAL={}\nELOG_COMPONENT_ID={}\nELOG_EVENT_ID={}\nMESSAGE={}\n",
+        severity(elog.level()),
+        FACILITY,
+        APP_NAME,
+        elog.serial().replace('\n', " "),
+        elog.component_id(),
+        elog.event_id(),
+        message(elog)
+    )
+}
+
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(Option<TcpStream>),
+    Journald(UnixDatagram),
+}
+
+impl Connection {
+    fn open(target: &SyslogTarget) -> Result<Self, DMError> {
+        let io_error = |e: std::io::Error| Report::new(DMError::IOError).attach_printable(e);
+        match target {
+            SyslogTarget::Udp(addr) => {
+                let address = addr
+                    .to_socket_addrs()
+                    .map_err(io_error)?
+                    .next()
+                    .ok_or_else(|| {
+                        Report::new(DMError::IOError)
+                            .attach_printable(format!("No address found for {}", addr))
+                    })?;
+                let bind = if address.is_ipv4() {
+                    "0.0.0.0:0"
+                } else {
+                    "[::]:0"
+                };
+                let socket = UdpSocket::bind(bind).map_err(io_error)?;
+                socket.connect(address).map_err(io_error)?;
+                Ok(Connection::Udp(socket))
+            }
+            // Connected by the first message so that a server down does not delay the start
+            SyslogTarget::Tcp(_) => Ok(Connection::Tcp(None)),
+            SyslogTarget::Journald => {
+                let socket = UnixDatagram::unbound().map_err(io_error)?;
+                socket.connect(JOURNALD_SOCKET).map_err(io_error)?;
+                Ok(Connection::Journald(socket))
+            }
+        }
+    }
+
+    fn send(&mut self, target: &SyslogTarget, sd_id: &str, elog: &Elog) -> std::io::Result<()> {
+        match (self, target) {
+            (Connection::Udp(socket), _) => socket
+                .send(format_rfc5424(elog, sd_id).as_bytes())
+                .map(|_| ()),
+            (Connection::Tcp(stream), SyslogTarget::Tcp(addr)) => {
+                let message = format_rfc5424(elog, sd_id);
+                // Octet counting framing of RFC 6587
+                let frame = format!("{} {}", message.len(), message);
+                if stream.is_none() {
+                    *stream = Some(TcpStream::connect(addr)?);
+                }
+                let result = stream.as_mut().unwrap().write_all(frame.as_bytes());
+                if result.is_err() {
+                    // Reconnected by the next message
+                    *stream = None;
+                }
+                result
+            }
+            (Connection::Journald(socket), _) => {
+                socket.send(format_journald(elog).as_bytes()).map(|_| ())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Forwards the elogs received to a syslog server or journald from a background thread, so
+/// that a slow server never blocks the UI
+pub struct SyslogForwarder {
+    target: SyslogTarget,
+    sender: mpsc::Sender<Elog>,
+}
+
+impl SyslogForwarder {
+    /// `sd_id` is the SD-ID of the elog IDs in the RFC 5424 messages, see [`parse_sd_id`]
+    pub fn new(target: SyslogTarget, sd_id: String) -> Result<Self, DMError> {
+        let mut connection = Connection::open(&target)?;
+        let (sender, receiver) = mpsc::channel::<Elog>();
+
+        let thread_target = target.clone();
+        thread::spawn(move || {
+            for elog in receiver {
+                if let Err(e) = connection.send(&thread_target, &sd_id, &elog) {
+                    jerror!(
+                        func = "SyslogForwarder",
+                        target = format!("{:?}", thread_target),
+                        error = e.to_string()
+                    );
+                }
+            }
+        });
+
+        Ok(Self { target, sender })
+    }
+
+    pub fn target(&self) -> &SyslogTarget {
+        &self.target
+    }
+
+    pub fn forward(&self, elog: &Elog) {
+        let _ = self.sender.send(elog.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elog(level: u8) -> Elog {
+        Elog::parse(&format!(
+            r#"{{
+                "serial": "SN 001",
+                "level": {level},
+                "timestamp": "2024-06-01T10:00:00Z",
+                "component_id": 100,
+                "component_name": "Main",
+                "event_id": 4096,
+                "event_description": "line1\nline2"
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            SyslogTarget::parse("udp://127.0.0.1:514").unwrap(),
+            SyslogTarget::Udp("127.0.0.1:514".to_owned())
+        );
+        assert_eq!(
+            SyslogTarget::parse("journald").unwrap(),
+            SyslogTarget::Journald
+        );
+        assert!(SyslogTarget::parse("tcp://localhost").is_err());
+        assert!(SyslogTarget::parse("http://localhost:514").is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let message = format_rfc5424(&elog(1), DEFAULT_SD_ID);
+        assert!(message.starts_with(
+            "<131>1 2024-06-01T10:00:00Z SN001 device-monitor - 0x1000 [elog@32473 component_id=\"100\" event_id=\"4096\"] Main: "
+        ));
+        assert!(message.ends_with(": line1 line2"));
+
+        assert!(format_journald(&elog(0)).starts_with("PRIORITY=2\n"));
+        assert_eq!(severity(5), 7);
+    }
+
+    #[test]
+    fn test_parse_sd_id() {
+        assert_eq!(parse_sd_id(DEFAULT_SD_ID).unwrap(), DEFAULT_SD_ID);
+        assert_eq!(parse_sd_id("elog@1.3.6").unwrap(), "elog@1.3.6");
+        assert!(parse_sd_id("elog").is_err());
+        assert!(parse_sd_id("elog@").is_err());
+        assert!(parse_sd_id("elog@pen").is_err());
+        assert!(parse_sd_id("el og@32473").is_err());
+        assert!(parse_sd_id("elog=1@32473").is_err());
+    }
+
+    #[test]
+    fn test_forward_udp() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        let addr = server.local_addr().unwrap().to_string();
+
+        let forwarder =
+            SyslogForwarder::new(SyslogTarget::Udp(addr), DEFAULT_SD_ID.to_owned()).unwrap();
+        forwarder.forward(&elog(3));
+
+        let mut buf = [0u8; 1024];
+        let n = server.recv(&mut buf).unwrap();
+        assert!(String::from_utf8_lossy(&buf[..n]).starts_with("<134>1 "));
+    }
+}
@@ -0,0 +1,237 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::evp::device_info::DeviceInfo,
+    crate::error::DMError,
+    error_stack::{Report, Result},
+    serde::Deserialize,
+    std::collections::BTreeSet,
+};
+
+/// File of the thresholds, in the configuration directory
+pub const THRESHOLDS_FILE: &str = "temperature_thresholds.json";
+
+/// Degrees below the threshold the temperature must go back to for the alarm to clear
+pub const DEFAULT_HYSTERESIS: i32 = 3;
+
+/// Temperature reported for a chip which has not been measured
+const NOT_MEASURED: i32 = -300;
+
+const CHIPS: [&str; 3] = ["main_chip", "companion_chip", "sensor_chip"];
+
+fn default_hysteresis() -> i32 {
+    DEFAULT_HYSTERESIS
+}
+
+/// Temperature raising the alarm of one chip, in degrees Celsius
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChipThreshold {
+    pub high: i32,
+    #[serde(default = "default_hysteresis")]
+    pub hysteresis: i32,
+}
+
+/// Temperature thresholds of the chips, the ones not given have no alarm:
+///
+/// ```json
+/// {
+///     "main_chip": { "high": 70 },
+///     "sensor_chip": { "high": 60, "hysteresis": 5 }
+/// }
+/// ```
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TemperatureThresholds {
+    pub main_chip: Option<ChipThreshold>,
+    pub companion_chip: Option<ChipThreshold>,
+    pub sensor_chip: Option<ChipThreshold>,
+}
+
+impl TemperatureThresholds {
+    pub fn parse(s: &str) -> Result<Self, DMError> {
+        serde_json::from_str(s).map_err(|e| {
+            Report::new(DMError::InvalidData)
+                .attach_printable(format!("Invalid temperature thresholds: {e}"))
+        })
+    }
+
+    pub fn load(path: &str) -> Result<Self, DMError> {
+        let s = std::fs::read_to_string(path).map_err(|e| {
+            Report::new(DMError::IOError).attach_printable(format!("Failed to read {path}: {e}"))
+        })?;
+        Self::parse(&s)
+    }
+
+    pub fn get(&self, chip: &str) -> Option<&ChipThreshold> {
+        match chip {
+            "main_chip" => self.main_chip.as_ref(),
+            "companion_chip" => self.companion_chip.as_ref(),
+            "sensor_chip" => self.sensor_chip.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Alarm raised or cleared by a temperature report
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemperatureNotice {
+    pub raised: bool,
+    pub message: String,
+}
+
+/// Chips over their threshold. An alarm is raised once when the temperature reaches the
+/// threshold and cleared once it goes back `hysteresis` degrees below, so that a temperature
+/// flapping around the threshold does not raise it again and again.
+#[derive(Debug, Clone, Default)]
+pub struct TemperatureAlarms {
+    thresholds: TemperatureThresholds,
+    raised: BTreeSet<&'static str>,
+}
+
+impl TemperatureAlarms {
+    pub fn new(thresholds: TemperatureThresholds) -> Self {
+        Self {
+            thresholds,
+            raised: BTreeSet::new(),
+        }
+    }
+
+    pub fn thresholds(&self) -> &TemperatureThresholds {
+        &self.thresholds
+    }
+
+    pub fn is_raised(&self, chip: &str) -> bool {
+        self.raised.contains(chip)
+    }
+
+    /// Checks the temperatures of a device info report, the alarms changed are returned
+    pub fn update(&mut self, device_info: &DeviceInfo) -> Vec<TemperatureNotice> {
+        let mut notices = vec![];
+        for chip_name in CHIPS {
+            let Some(threshold) = self.thresholds.get(chip_name) else {
+                continue;
+            };
+            let chip = match chip_name {
+                "main_chip" => device_info.main_chip(),
+                "companion_chip" => device_info.companion_chip(),
+                _ => device_info.sensor_chip(),
+            };
+            let Some(temperature) = chip.map(|c| c.temperature()).filter(|t| *t != NOT_MEASURED)
+            else {
+                continue;
+            };
+
+            if !self.raised.contains(chip_name) && temperature >= threshold.high {
+                self.raised.insert(chip_name);
+                notices.push(TemperatureNotice {
+                    raised: true,
+                    message: format!(
+                        "{chip_name} temperature {temperature}°C reached the threshold {}°C",
+                        threshold.high
+                    ),
+                });
+            } else if self.raised.contains(chip_name)
+                && temperature <= threshold.high - threshold.hysteresis
+            {
+                self.raised.remove(chip_name);
+                notices.push(TemperatureNotice {
+                    raised: false,
+                    message: format!(
+                        "{chip_name} temperature back to {temperature}°C (threshold {}°C)",
+                        threshold.high
+                    ),
+                });
+            }
+        }
+        notices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device_info(main: i32, sensor: i32) -> DeviceInfo {
+        serde_json::from_str(&format!(
+            r#"{{
+                "device_manifest": "",
+                "chips": [
+                    {{"name": "main_chip", "id": "1", "temperature": {main}, "ai_models": []}},
+                    {{"name": "sensor_chip", "id": "2", "temperature": {sensor}, "ai_models": []}}
+                ]
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_thresholds() {
+        let thresholds = TemperatureThresholds::parse(
+            r#"{"main_chip": {"high": 70}, "sensor_chip": {"high": 60, "hysteresis": 5}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            thresholds.get("main_chip"),
+            Some(&ChipThreshold {
+                high: 70,
+                hysteresis: DEFAULT_HYSTERESIS
+            })
+        );
+        assert_eq!(thresholds.get("sensor_chip").unwrap().hysteresis, 5);
+        assert!(thresholds.get("companion_chip").is_none());
+
+        assert!(TemperatureThresholds::parse(r#"{"main_chip": {"low": 0}}"#).is_err());
+    }
+
+    #[test]
+    fn test_temperature_alarms() {
+        let thresholds = TemperatureThresholds::parse(r#"{"main_chip": {"high": 70}}"#).unwrap();
+        let mut alarms = TemperatureAlarms::new(thresholds);
+
+        assert!(alarms.update(&device_info(65, 90)).is_empty());
+        assert!(!alarms.is_raised("sensor_chip"));
+
+        let notices = alarms.update(&device_info(70, 30));
+        assert_eq!(
+            notices,
+            [TemperatureNotice {
+                raised: true,
+                message: "main_chip temperature 70°C reached the threshold 70°C".to_owned()
+            }]
+        );
+        assert!(alarms.is_raised("main_chip"));
+
+        // Flapping around the threshold raises the alarm only once
+        for temperature in [69, 71, 68, 72] {
+            assert!(alarms.update(&device_info(temperature, 30)).is_empty());
+            assert!(alarms.is_raised("main_chip"));
+        }
+
+        let notices = alarms.update(&device_info(67, 30));
+        assert!(!notices[0].raised);
+        assert_eq!(
+            notices[0].message,
+            "main_chip temperature back to 67°C (threshold 70°C)"
+        );
+        assert!(!alarms.is_raised("main_chip"));
+
+        // Not measured
+        assert!(alarms.update(&device_info(NOT_MEASURED, 30)).is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::memory::string_size,
+    chrono::{DateTime, Local},
+    std::collections::BTreeMap,
+};
+
+/// Topics kept per family, the least recently updated ones are dropped first
+const TOPICS_PER_FAMILY: usize = 64;
+
+/// Messages which do not update the device state, grouped as parsed by EvpMsg
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TopicFamily {
+    /// Sent by the device, e.g. unknown attributes
+    Client,
+    /// Sent by the server, e.g. attribute responses or RPC requests not for the device
+    Server,
+    /// Neither EVP nor gateway topics
+    NonEvp,
+    /// Values decoded by the message hooks, keyed by their names
+    Hook,
+    /// Sent by the user in the dry-run mode, only written to the review log
+    DryRun,
+}
+
+impl TopicFamily {
+    pub const ALL: [TopicFamily; 5] = [
+        TopicFamily::Client,
+        TopicFamily::Server,
+        TopicFamily::NonEvp,
+        TopicFamily::Hook,
+        TopicFamily::DryRun,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TopicFamily::Client => "Client messages",
+            TopicFamily::Server => "Server messages",
+            TopicFamily::NonEvp => "Non-EVP messages",
+            TopicFamily::Hook => "Hook values",
+            TopicFamily::DryRun => "Dry-run messages (not sent)",
+        }
+    }
+}
+
+/// Latest message received on one topic
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicMessage {
+    pub payload: String,
+    pub count: usize,
+    pub last_update: DateTime<Local>,
+}
+
+impl TopicMessage {
+    /// Approximate bytes kept for the message and its topic
+    pub fn memory_size(&self, topic: &str) -> usize {
+        size_of::<Self>() + self.payload.len() + string_size(topic)
+    }
+}
+
+/// Latest message of each topic, per family
+#[derive(Debug, Default)]
+pub struct Traffic {
+    families: BTreeMap<TopicFamily, BTreeMap<String, TopicMessage>>,
+}
+
+impl Traffic {
+    pub fn record(
+        &mut self,
+        family: TopicFamily,
+        topic: &str,
+        payload: &str,
+        time: DateTime<Local>,
+    ) {
+        let topics = self.families.entry(family).or_default();
+
+        if let Some(message) = topics.get_mut(topic) {
+            message.payload = payload.to_owned();
+            message.count += 1;
+            message.last_update = time;
+            return;
+        }
+
+        if topics.len() == TOPICS_PER_FAMILY
+            && let Some(oldest) = topics
+                .iter()
+                .min_by_key(|(_, m)| m.last_update)
+                .map(|(t, _)| t.clone())
+        {
+            topics.remove(&oldest);
+        }
+
+        topics.insert(
+            topic.to_owned(),
+            TopicMessage {
+                payload: payload.to_owned(),
+                count: 1,
+                last_update: time,
+            },
+        );
+    }
+
+    /// Topics of a family sorted by name
+    pub fn topics(&self, family: TopicFamily) -> Vec<(&str, &TopicMessage)> {
+        self.families
+            .get(&family)
+            .map(|topics| topics.iter().map(|(t, m)| (t.as_str(), m)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Time of the latest message of a family
+    pub fn last_update(&self, family: TopicFamily) -> Option<DateTime<Local>> {
+        self.families
+            .get(&family)
+            .and_then(|topics| topics.values().map(|m| m.last_update).max())
+    }
+
+    pub fn memory_size(&self) -> usize {
+        self.families
+            .values()
+            .flat_map(|topics| topics.iter())
+            .map(|(t, m)| m.memory_size(t))
+            .sum()
+    }
+
+    /// Drops the least recently updated topics of all the families until the messages fit
+    /// in `budget` bytes, returns the number of topics dropped
+    pub fn prune(&mut self, budget: usize) -> usize {
+        let mut size = self.memory_size();
+        if size <= budget {
+            return 0;
+        }
+
+        let mut oldest: Vec<(DateTime<Local>, TopicFamily, String)> = self
+            .families
+            .iter()
+            .flat_map(|(family, topics)| {
+                topics
+                    .iter()
+                    .map(|(t, m)| (m.last_update, *family, t.clone()))
+            })
+            .collect();
+        oldest.sort();
+
+        let mut pruned = 0;
+        for (_, family, topic) in oldest {
+            if size <= budget {
+                break;
+            }
+            if let Some(message) = self
+                .families
+                .get_mut(&family)
+                .and_then(|topics| topics.remove(&topic))
+            {
+                size -= message.memory_size(&topic);
+                pruned += 1;
+            }
+        }
+        pruned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traffic_record() {
+        let t0 = Local::now();
+        let t1 = t0 + chrono::Duration::seconds(1);
+        let mut traffic = Traffic::default();
+        traffic.record(TopicFamily::NonEvp, "b", "{}", t0);
+        traffic.record(TopicFamily::NonEvp, "a", "1", t0);
+        traffic.record(TopicFamily::NonEvp, "a", "2", t1);
+
+        let topics = traffic.topics(TopicFamily::NonEvp);
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[0].0, "a");
+        assert_eq!(topics[0].1.payload, "2");
+        assert_eq!(topics[0].1.count, 2);
+        assert_eq!(traffic.last_update(TopicFamily::NonEvp), Some(t1));
+        assert!(traffic.topics(TopicFamily::Client).is_empty());
+
+        for i in 0..TOPICS_PER_FAMILY {
+            traffic.record(TopicFamily::NonEvp, &format!("t{i}"), "", t1);
+        }
+        let topics = traffic.topics(TopicFamily::NonEvp);
+        assert_eq!(topics.len(), TOPICS_PER_FAMILY);
+        assert!(!topics.iter().any(|(t, _)| *t == "b"));
+    }
+
+    #[test]
+    fn test_traffic_prune() {
+        let t0 = Local::now();
+        let mut traffic = Traffic::default();
+        for i in 0..4 {
+            let time = t0 + chrono::Duration::seconds(i);
+            traffic.record(
+                TopicFamily::Client,
+                &format!("c{i}"),
+                &"x".repeat(100),
+                time,
+            );
+            traffic.record(
+                TopicFamily::Server,
+                &format!("s{i}"),
+                &"x".repeat(100),
+                time,
+            );
+        }
+
+        let size = traffic.memory_size();
+        assert_eq!(traffic.prune(size), 0);
+
+        // The oldest topics of both families go first
+        let message = size / 8;
+        assert_eq!(traffic.prune(size - message), 1);
+        assert_eq!(traffic.prune(size - 3 * message), 2);
+        assert_eq!(traffic.topics(TopicFamily::Client).len(), 2);
+        assert_eq!(traffic.topics(TopicFamily::Server)[0].0, "s1");
+        assert_eq!(traffic.memory_size(), size - 3 * message);
+    }
+}
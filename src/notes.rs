@@ -0,0 +1,240 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        error::DMError,
+        mqtt_ctrl::evp::device_info::DeviceInfo,
+        versioned::{self, Versioned},
+    },
+    error_stack::{Report, Result},
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+    std::collections::BTreeMap,
+};
+
+/// File of the configuration directory the notes are saved to
+pub const NOTES_FILE: &str = "device_notes.json";
+
+/// Labels and free text attached to one device, e.g. "lab bench 3" and "flaky Wi-Fi"
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeviceNote {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+impl DeviceNote {
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.note.is_empty()
+    }
+}
+
+/// Notes of the devices, keyed by `device_key()`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeviceNotes {
+    #[serde(default)]
+    pub devices: BTreeMap<String, DeviceNote>,
+}
+
+impl Versioned for DeviceNotes {
+    const KIND: &'static str = "device notes";
+    const VERSION: u32 = 1;
+}
+
+impl DeviceNotes {
+    pub fn get(&self, key: &str) -> Option<&DeviceNote> {
+        self.devices.get(key)
+    }
+
+    /// Replaces the notes of the device, an empty note removes them
+    pub fn set(&mut self, key: &str, note: DeviceNote) {
+        if note.is_empty() {
+            self.devices.remove(key);
+        } else {
+            self.devices.insert(key.to_owned(), note);
+        }
+    }
+
+    /// Reads the notes, a missing file means no note
+    pub fn load(path: &str) -> Result<Self, DMError> {
+        Ok(versioned::load(path)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), DMError> {
+        versioned::save(path, self)
+    }
+}
+
+/// Key the notes of a device are saved under: the id of its main chip, or else the hash of
+/// its device manifest. None until the device reports either.
+pub fn device_key(device_info: Option<&DeviceInfo>) -> Option<String> {
+    let device_info = device_info?;
+    if let Some(id) = device_info
+        .main_chip()
+        .map(|chip| chip.id())
+        .filter(|id| !id.is_empty())
+    {
+        return Some(id.to_owned());
+    }
+
+    device_info
+        .device_manifest()
+        .filter(|manifest| !manifest.is_empty())
+        .map(|manifest| {
+            let hash = format!("{:x}", Sha256::digest(manifest.as_bytes()));
+            format!("manifest:{}", &hash[..16])
+        })
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NotesField {
+    #[default]
+    Labels,
+    Note,
+}
+
+/// Popup editing the notes of the device, the labels being comma separated
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct NotesEditor {
+    pub key: String,
+    pub labels: String,
+    pub note: String,
+    pub field: NotesField,
+}
+
+impl NotesEditor {
+    pub fn new(key: &str, note: Option<&DeviceNote>) -> Self {
+        Self {
+            key: key.to_owned(),
+            labels: note.map(|n| n.labels.join(", ")).unwrap_or_default(),
+            note: note.map(|n| n.note.clone()).unwrap_or_default(),
+            field: NotesField::Labels,
+        }
+    }
+
+    fn text(&mut self) -> &mut String {
+        match self.field {
+            NotesField::Labels => &mut self.labels,
+            NotesField::Note => &mut self.note,
+        }
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.text().push(c);
+    }
+
+    pub fn pop(&mut self) {
+        self.text().pop();
+    }
+
+    pub fn toggle_field(&mut self) {
+        self.field = match self.field {
+            NotesField::Labels => NotesField::Note,
+            NotesField::Note => NotesField::Labels,
+        };
+    }
+
+    pub fn to_note(&self) -> DeviceNote {
+        DeviceNote {
+            labels: self
+                .labels
+                .split(',')
+                .map(str::trim)
+                .filter(|label| !label.is_empty())
+                .map(str::to_owned)
+                .collect(),
+            note: self.note.trim().to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_key() {
+        assert_eq!(device_key(None), None);
+
+        let device_info: DeviceInfo = serde_json::from_str(
+            r#"{"device_manifest": "eyJhbGc", "chips": [{"name": "main_chip", "id": "100A50500A2010064012000000000000", "temperature": 40, "ai_models": []}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            device_key(Some(&device_info)).as_deref(),
+            Some("100A50500A2010064012000000000000")
+        );
+
+        let device_info: DeviceInfo =
+            serde_json::from_str(r#"{"device_manifest": "eyJhbGc", "chips": []}"#).unwrap();
+        let key = device_key(Some(&device_info)).unwrap();
+        assert!(key.starts_with("manifest:"));
+        assert_eq!(key.len(), "manifest:".len() + 16);
+
+        let device_info: DeviceInfo =
+            serde_json::from_str(r#"{"device_manifest": "", "chips": []}"#).unwrap();
+        assert_eq!(device_key(Some(&device_info)), None);
+    }
+
+    #[test]
+    fn test_notes_editor() {
+        let mut notes = DeviceNotes::default();
+        let mut editor = NotesEditor::new("dev1", notes.get("dev1"));
+        for c in "lab bench 3, flaky Wi-Fi,".chars() {
+            editor.push(c);
+        }
+        editor.toggle_field();
+        for c in " swapped the antenna ".chars() {
+            editor.push(c);
+        }
+        editor.pop();
+        notes.set(&editor.key, editor.to_note());
+
+        let note = notes.get("dev1").unwrap();
+        assert_eq!(note.labels, ["lab bench 3", "flaky Wi-Fi"]);
+        assert_eq!(note.note, "swapped the antenna");
+
+        // Edited again from the saved notes
+        let editor = NotesEditor::new("dev1", Some(note));
+        assert_eq!(editor.labels, "lab bench 3, flaky Wi-Fi");
+
+        notes.set("dev1", DeviceNote::default());
+        assert!(notes.devices.is_empty());
+    }
+
+    #[test]
+    fn test_notes_file() {
+        let path = std::env::temp_dir().join(format!("device_notes_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+        assert_eq!(DeviceNotes::load(path).unwrap(), DeviceNotes::default());
+
+        let mut notes = DeviceNotes::default();
+        notes.set(
+            "dev1",
+            DeviceNote {
+                labels: vec!["lab bench 3".to_owned()],
+                note: String::new(),
+            },
+        );
+        notes.save(path).unwrap();
+        assert_eq!(DeviceNotes::load(path).unwrap(), notes);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -14,9 +14,13 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+pub mod package;
+pub mod rollback;
+pub mod wizard;
+
 use {
     crate::mqtt_ctrl::evp::{ProcessState, ReqInfo, ResInfo},
-    crate::{app::ConfigKey, error::DMError, mqtt_ctrl::evp::evp_state::UUID},
+    crate::{device_config::ConfigKey, error::DMError, mqtt_ctrl::evp::evp_state::UUID},
     error_stack::{Report, Result},
     json::{self, JsonValue, object::Object},
     serde::{Deserialize, Serialize},
@@ -88,11 +92,7 @@ impl Default for FirmwareProperty {
 
         for chip in &chips {
             for &component in &components {
-                let chip_name = match chip {
-                    ChipId::MainChip => "ApFw",
-                    ChipId::CompanionChip => "AI-ISP",
-                    ChipId::SensorChip => "IMX500",
-                };
+                let chip_name = default_chip_name(*chip);
                 targets.push(Target {
                     component,
                     chip: chip_name.to_string(),
@@ -115,6 +115,72 @@ impl Default for FirmwareProperty {
     }
 }
 
+/// Default chip name used by the device for each chip
+pub fn default_chip_name(chip_id: ChipId) -> &'static str {
+    match chip_id {
+        ChipId::MainChip => "ApFw",
+        ChipId::CompanionChip => "AI-ISP",
+        ChipId::SensorChip => "IMX500",
+    }
+}
+
+/// Config keys of a target in the order of chip, version, package_url, hash and size
+pub fn target_config_keys(chip_id: ChipId, component: Component) -> [ConfigKey; 5] {
+    match (chip_id, component) {
+        (ChipId::MainChip, Component::Loader) => [
+            ConfigKey::OtaMainChipLoaderChip,
+            ConfigKey::OtaMainChipLoaderVersion,
+            ConfigKey::OtaMainChipLoaderPackageUrl,
+            ConfigKey::OtaMainChipLoaderHash,
+            ConfigKey::OtaMainChipLoaderSize,
+        ],
+        (ChipId::MainChip, _) => [
+            ConfigKey::OtaMainChipFirmwareChip,
+            ConfigKey::OtaMainChipFirmwareVersion,
+            ConfigKey::OtaMainChipFirmwarePackageUrl,
+            ConfigKey::OtaMainChipFirmwareHash,
+            ConfigKey::OtaMainChipFirmwareSize,
+        ],
+        (ChipId::CompanionChip, Component::Loader) => [
+            ConfigKey::OtaCompanionChipLoaderChip,
+            ConfigKey::OtaCompanionChipLoaderVersion,
+            ConfigKey::OtaCompanionChipLoaderPackageUrl,
+            ConfigKey::OtaCompanionChipLoaderHash,
+            ConfigKey::OtaCompanionChipLoaderSize,
+        ],
+        (ChipId::CompanionChip, _) => [
+            ConfigKey::OtaCompanionChipFirmwareChip,
+            ConfigKey::OtaCompanionChipFirmwareVersion,
+            ConfigKey::OtaCompanionChipFirmwarePackageUrl,
+            ConfigKey::OtaCompanionChipFirmwareHash,
+            ConfigKey::OtaCompanionChipFirmwareSize,
+        ],
+        (ChipId::SensorChip, Component::Loader) => [
+            ConfigKey::OtaSensorChipLoaderChip,
+            ConfigKey::OtaSensorChipLoaderVersion,
+            ConfigKey::OtaSensorChipLoaderPackageUrl,
+            ConfigKey::OtaSensorChipLoaderHash,
+            ConfigKey::OtaSensorChipLoaderSize,
+        ],
+        (ChipId::SensorChip, _) => [
+            ConfigKey::OtaSensorChipFirmwareChip,
+            ConfigKey::OtaSensorChipFirmwareVersion,
+            ConfigKey::OtaSensorChipFirmwarePackageUrl,
+            ConfigKey::OtaSensorChipFirmwareHash,
+            ConfigKey::OtaSensorChipFirmwareSize,
+        ],
+    }
+}
+
+/// Target whose group contains `config_key`, e.g. OtaSensorChipLoaderPackageUrl gives the
+/// sensor chip loader
+pub fn target_of_config_key(config_key: ConfigKey) -> Option<(ChipId, Component)> {
+    wizard::OTA_TARGETS
+        .iter()
+        .find(|(chip_id, component)| target_config_keys(*chip_id, *component).contains(&config_key))
+        .copied()
+}
+
 pub fn parse_ota_configuration(config_keys: &[String]) -> Result<String, DMError> {
     let key_value = |key: ConfigKey| -> Option<String> {
         let val = config_keys[key as usize]
@@ -125,7 +191,7 @@ pub fn parse_ota_configuration(config_keys: &[String]) -> Result<String, DMError
     };
 
     let req_id = ReqInfo {
-        req_id: UUID::new().uuid().to_string(),
+        req_id: UUID::new_string(),
     };
 
     let version = key_value(ConfigKey::OtaVersion);
@@ -238,6 +304,14 @@ pub fn parse_ota_configuration(config_keys: &[String]) -> Result<String, DMError
     Ok(json::stringify_pretty(root, 4))
 }
 
+/// Parses back the FirmwareProperty of a configuration generated by parse_ota_configuration()
+pub fn firmware_of_configuration(config: &str) -> Option<FirmwareProperty> {
+    let root = json::parse(config).ok()?;
+    let content = root["configuration/$system/PRIVATE_deploy_firmware"].as_str()?;
+
+    serde_json::from_str(content).ok()
+}
+
 #[allow(dead_code)]
 impl FirmwareProperty {
     pub fn new() -> Self {
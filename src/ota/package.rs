@@ -0,0 +1,183 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{ChipId, default_chip_name},
+    crate::error::DMError,
+    error_stack::{Report, Result},
+    std::io::Read,
+};
+
+/// Only the beginning of a package is scanned for metadata
+const HEADER_SIZE: usize = 64 * 1024;
+
+/// Minimum length of printable strings picked up from the header
+const MIN_STRING_LEN: usize = 4;
+
+/// Metadata found in a local firmware/loader package
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageMetadata {
+    pub version: Option<String>,
+    pub chip: Option<ChipId>,
+}
+
+impl PackageMetadata {
+    /// Returns a warning when the package targets another chip than `chip_id`
+    pub fn mismatch_warning(&self, chip_id: ChipId) -> Option<String> {
+        match self.chip {
+            Some(chip) if chip != chip_id => Some(format!(
+                "Package looks like a {} image but is selected for {}",
+                default_chip_name(chip),
+                default_chip_name(chip_id)
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn chip_of(s: &str) -> Option<ChipId> {
+    let s = s.to_lowercase();
+
+    if s.contains("imx500") {
+        Some(ChipId::SensorChip)
+    } else if s.contains("ai-isp") || s.contains("ai_isp") || s.contains("aiisp") {
+        Some(ChipId::CompanionChip)
+    } else if s.contains("apfw") {
+        Some(ChipId::MainChip)
+    } else {
+        None
+    }
+}
+
+/// Printable ASCII strings in `data`, like strings(1)
+fn printable_strings(data: &[u8]) -> Vec<String> {
+    data.split(|b| !(b.is_ascii_graphic() || *b == b' '))
+        .filter(|s| s.len() >= MIN_STRING_LEN)
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+        .collect()
+}
+
+/// Picks the value of "version=xxx", "version: xxx" or "\"version\": \"xxx\""
+fn version_of(s: &str) -> Option<String> {
+    let lower = s.to_lowercase();
+    let pos = lower.find("version")?;
+    let value = s[pos + "version".len()..]
+        .trim_start_matches(|c: char| c == '"' || c == ':' || c == '=' || c.is_whitespace())
+        .split(|c: char| c == '"' || c == ',' || c == '}' || c.is_whitespace())
+        .next()
+        .unwrap_or_default();
+
+    if value.is_empty() || !value.chars().any(|c| c.is_ascii_digit()) {
+        None
+    } else {
+        Some(value.to_owned())
+    }
+}
+
+/// Falls back to the version at the end of the file name, e.g. "ApFw_0700FA.bin" gives "0700FA"
+fn version_of_file_name(file_name: &str) -> Option<String> {
+    let stem = std::path::Path::new(file_name).file_stem()?.to_str()?;
+    let token = stem.rsplit(['_', '-']).next()?;
+
+    if token != stem && token.chars().any(|c| c.is_ascii_digit()) {
+        Some(token.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Extracts the metadata from the header of a package and its file name
+pub fn parse_package_bytes(file_name: &str, data: &[u8]) -> PackageMetadata {
+    let header = &data[..data.len().min(HEADER_SIZE)];
+    let strings = printable_strings(header);
+
+    let version = strings
+        .iter()
+        .find_map(|s| version_of(s))
+        .or_else(|| version_of_file_name(file_name));
+
+    let chip = strings
+        .iter()
+        .find_map(|s| chip_of(s))
+        .or_else(|| chip_of(file_name));
+
+    PackageMetadata { version, chip }
+}
+
+/// Reads the header of the local package `file_path` and extracts its metadata
+pub fn parse_package(file_path: &str) -> Result<PackageMetadata, DMError> {
+    let file = std::fs::File::open(file_path).map_err(|e| {
+        Report::new(DMError::IOError).attach_printable(format!("Failed to open file: {}", e))
+    })?;
+
+    let mut header = Vec::with_capacity(HEADER_SIZE);
+    file.take(HEADER_SIZE as u64)
+        .read_to_end(&mut header)
+        .map_err(|e| {
+            Report::new(DMError::IOError).attach_printable(format!("Failed to read file: {}", e))
+        })?;
+
+    let file_name = std::path::Path::new(file_path)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+
+    Ok(parse_package_bytes(file_name, &header))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header_manifest() {
+        let mut data = vec![0u8, 1, 2, 0xff];
+        data.extend_from_slice(b"{\"chip\": \"IMX500\", \"version\": \"020000\"}");
+        data.extend_from_slice(&[0u8; 16]);
+
+        let meta = parse_package_bytes("firmware.fpk", &data);
+        assert_eq!(meta.version.as_deref(), Some("020000"));
+        assert_eq!(meta.chip, Some(ChipId::SensorChip));
+    }
+
+    #[test]
+    fn test_parse_file_name_fallback() {
+        let meta = parse_package_bytes("ApFw_0700FA.bin", &[0u8, 1, 2, 3]);
+        assert_eq!(meta.version.as_deref(), Some("0700FA"));
+        assert_eq!(meta.chip, Some(ChipId::MainChip));
+
+        let meta = parse_package_bytes("firmware.bin", b"version=without digits");
+        assert_eq!(meta, PackageMetadata::default());
+    }
+
+    #[test]
+    fn test_mismatch_warning() {
+        let meta = parse_package_bytes("AI-ISP_loader.bin", b"");
+        assert_eq!(meta.chip, Some(ChipId::CompanionChip));
+        assert!(meta.mismatch_warning(ChipId::CompanionChip).is_none());
+        assert!(
+            meta.mismatch_warning(ChipId::MainChip)
+                .unwrap()
+                .contains("AI-ISP")
+        );
+        assert!(
+            PackageMetadata::default()
+                .mismatch_warning(ChipId::MainChip)
+                .is_none()
+        );
+    }
+}
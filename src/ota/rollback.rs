@@ -0,0 +1,219 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{ChipId, Component, FirmwareProperty, Target, default_chip_name, wizard::find_target},
+    crate::mqtt_ctrl::evp::device_info::{ChipInfo, DeviceInfo},
+    chrono::{DateTime, Local},
+};
+
+pub fn chip_id_of_name(chip: &str) -> Option<ChipId> {
+    [ChipId::MainChip, ChipId::CompanionChip, ChipId::SensorChip]
+        .into_iter()
+        .find(|chip_id| default_chip_name(*chip_id) == chip)
+}
+
+fn chip_info(device_info: &DeviceInfo, chip_id: ChipId) -> Option<&ChipInfo> {
+    match chip_id {
+        ChipId::MainChip => device_info.main_chip(),
+        ChipId::CompanionChip => device_info.companion_chip(),
+        ChipId::SensorChip => device_info.sensor_chip(),
+    }
+}
+
+/// State of a target just before an OTA updating it was triggered
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetRecord {
+    pub chip_id: ChipId,
+    pub component: Component,
+    /// Version reported in DeviceInfo
+    pub reported_version: Option<String>,
+    /// Deployment reported in PRIVATE_deploy_firmware, holding the package URL
+    pub previous: Option<Target>,
+}
+
+/// Record of the targets updated by the last OTA
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtaSnapshot {
+    pub time: DateTime<Local>,
+    pub targets: Vec<TargetRecord>,
+}
+
+impl OtaSnapshot {
+    /// Records the targets of `sent`, which is about to be sent to the device
+    pub fn new(
+        sent: &FirmwareProperty,
+        device_info: Option<&DeviceInfo>,
+        firmware: &FirmwareProperty,
+    ) -> Self {
+        let targets = sent
+            .get_all_targets()
+            .map(|targets| {
+                targets
+                    .iter()
+                    .filter_map(|t| {
+                        let chip_id = chip_id_of_name(&t.chip)?;
+                        let reported_version = device_info
+                            .and_then(|d| chip_info(d, chip_id))
+                            .and_then(|c| match t.component {
+                                Component::Loader => c.loader_version(),
+                                _ => c.firmware_version(),
+                            })
+                            .filter(|v| !v.is_empty() && *v != "-")
+                            .map(|v| v.to_owned());
+                        let previous = find_target(firmware, &t.chip, t.component)
+                            .filter(|p| p.package_url.as_ref().is_some_and(|u| !u.is_empty()))
+                            .cloned();
+
+                        Some(TargetRecord {
+                            chip_id,
+                            component: t.component,
+                            reported_version,
+                            previous,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            time: Local::now(),
+            targets,
+        }
+    }
+
+    /// Targets which can be rolled back to their previous package
+    pub fn rollback_targets(&self) -> Vec<Target> {
+        self.targets
+            .iter()
+            .filter_map(|r| {
+                let previous = r.previous.as_ref()?;
+                Some(Target {
+                    component: r.component,
+                    chip: previous.chip.clone(),
+                    version: previous.version.clone().or(r.reported_version.clone()),
+                    progress: None,
+                    process_state: None,
+                    package_url: previous.package_url.clone(),
+                    hash: previous.hash.clone(),
+                    size: previous.size,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Container and blob name of a blob URL. The account name is the first path segment for
+/// Azurite but not for Azure storage, so it is skipped only when it matches `account_name`.
+pub fn blob_of_url(url: &str, account_name: &str) -> Option<(String, String)> {
+    let url = url.split(['?', '#']).next()?;
+    let path = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let mut segments = path.split('/').skip(1).filter(|s| !s.is_empty()).peekable();
+
+    if segments.peek() == Some(&account_name) {
+        segments.next();
+    }
+
+    let container = segments.next()?.to_owned();
+    let blob = segments.collect::<Vec<_>>().join("/");
+
+    if blob.is_empty() {
+        None
+    } else {
+        Some((container, blob))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt_ctrl::evp::ProcessState;
+
+    #[test]
+    fn test_blob_of_url() {
+        assert_eq!(
+            blob_of_url(
+                "http://127.0.0.1:10000/devstoreaccount1/default/ApFw.bin?se=x&sig=y",
+                "devstoreaccount1"
+            ),
+            Some(("default".to_owned(), "ApFw.bin".to_owned()))
+        );
+        assert_eq!(
+            blob_of_url(
+                "https://account.blob.core.windows.net/fw/dir/sensor.fpk?sig",
+                "account"
+            ),
+            Some(("fw".to_owned(), "dir/sensor.fpk".to_owned()))
+        );
+        assert_eq!(
+            blob_of_url("http://127.0.0.1:10000/default", "devstoreaccount1"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_snapshot_rollback_targets() {
+        let mut firmware = FirmwareProperty::new();
+        let previous = firmware
+            .get_target_mut(ChipId::SensorChip, Component::Firmware)
+            .unwrap();
+        previous.version = Some("010300".to_owned());
+        previous.package_url = Some("http://h/devstoreaccount1/default/old.fpk".to_owned());
+        previous.hash = Some("aGFzaA==".to_owned());
+        previous.size = Some(10);
+        previous.process_state = Some(ProcessState::Done);
+
+        let sent = FirmwareProperty {
+            req_info: None,
+            version: Some("new".to_owned()),
+            targets: Some(vec![
+                Target {
+                    component: Component::Firmware,
+                    chip: "IMX500".to_owned(),
+                    version: Some("020000".to_owned()),
+                    progress: None,
+                    process_state: None,
+                    package_url: Some("http://h/devstoreaccount1/default/new.fpk".to_owned()),
+                    hash: None,
+                    size: None,
+                },
+                Target {
+                    component: Component::Loader,
+                    chip: "ApFw".to_owned(),
+                    version: Some("1".to_owned()),
+                    progress: None,
+                    process_state: None,
+                    package_url: None,
+                    hash: None,
+                    size: None,
+                },
+            ]),
+            res_info: None,
+        };
+
+        let snapshot = OtaSnapshot::new(&sent, None, &firmware);
+        assert_eq!(snapshot.targets.len(), 2);
+        assert!(snapshot.targets[1].previous.is_none());
+
+        let targets = snapshot.rollback_targets();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].chip, "IMX500");
+        assert_eq!(targets[0].version.as_deref(), Some("010300"));
+        assert_eq!(targets[0].size, Some(10));
+        assert!(targets[0].process_state.is_none());
+    }
+}
@@ -0,0 +1,363 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{
+        ChipId, Component, FirmwareProperty, Target, default_chip_name, firmware_of_configuration,
+        package::PackageMetadata, target_config_keys,
+    },
+    crate::{
+        device_config::ConfigKey,
+        error::DMError,
+        mqtt_ctrl::evp::{ProcessState, module::ModuleInfo},
+    },
+    error_stack::{Report, Result},
+};
+
+/// All OTA targets in the order shown by the wizard
+pub const OTA_TARGETS: [(ChipId, Component); 6] = [
+    (ChipId::MainChip, Component::Loader),
+    (ChipId::MainChip, Component::Firmware),
+    (ChipId::CompanionChip, Component::Loader),
+    (ChipId::CompanionChip, Component::Firmware),
+    (ChipId::SensorChip, Component::Loader),
+    (ChipId::SensorChip, Component::Firmware),
+];
+
+pub fn target_name(chip_id: ChipId, component: Component) -> String {
+    let chip = match chip_id {
+        ChipId::MainChip => "Main Chip",
+        ChipId::CompanionChip => "Companion Chip",
+        ChipId::SensorChip => "Sensor Chip",
+    };
+
+    let component = match component {
+        Component::Loader => "Loader",
+        _ => "Firmware",
+    };
+
+    format!("{} {} ({})", chip, component, default_chip_name(chip_id))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtaWizardStep {
+    /// Select the chip and component to update
+    Target,
+    /// Input the local firmware file and upload it
+    File,
+    /// Review and edit the auto-filled values
+    Review,
+    /// Show the generated configuration before sending it
+    Send,
+    /// Track the progress reported by the device
+    Progress,
+}
+
+impl OtaWizardStep {
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            OtaWizardStep::Target => "Target",
+            OtaWizardStep::File => "Upload",
+            OtaWizardStep::Review => "Review",
+            OtaWizardStep::Send => "Send",
+            OtaWizardStep::Progress => "Progress",
+        }
+    }
+
+    pub fn all() -> [OtaWizardStep; 5] {
+        [
+            OtaWizardStep::Target,
+            OtaWizardStep::File,
+            OtaWizardStep::Review,
+            OtaWizardStep::Send,
+            OtaWizardStep::Progress,
+        ]
+    }
+}
+
+/// Progress of the sent OTA request as reported by the device
+#[derive(Debug, Clone, PartialEq)]
+pub enum OtaProgress {
+    /// The device hasn't reported the sent request yet
+    Waiting,
+    InProgress(ProcessState, i32),
+    Done,
+    Failed(ProcessState),
+}
+
+/// Guided OTA state: pick a file, upload it, auto-fill the target and send it
+#[derive(Debug, Clone)]
+pub struct OtaWizard {
+    pub step: OtaWizardStep,
+    pub target_index: usize,
+    pub file_path: String,
+    pub version: String,
+    pub version_editable: bool,
+    pub module: Option<ModuleInfo>,
+    pub metadata: PackageMetadata,
+    pub payload: Option<String>,
+    pub req_id: Option<String>,
+}
+
+impl Default for OtaWizard {
+    fn default() -> Self {
+        Self {
+            step: OtaWizardStep::Target,
+            target_index: 1,
+            file_path: String::new(),
+            version: String::new(),
+            version_editable: false,
+            module: None,
+            metadata: PackageMetadata::default(),
+            payload: None,
+            req_id: None,
+        }
+    }
+}
+
+impl OtaWizard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn target(&self) -> (ChipId, Component) {
+        OTA_TARGETS[self.target_index]
+    }
+
+    pub fn target_focus_up(&mut self) {
+        self.target_index = (self.target_index + OTA_TARGETS.len() - 1) % OTA_TARGETS.len();
+    }
+
+    pub fn target_focus_down(&mut self) {
+        self.target_index = (self.target_index + 1) % OTA_TARGETS.len();
+    }
+
+    /// Goes back to the previous step, returns false on the first step
+    pub fn step_back(&mut self) -> bool {
+        self.step = match self.step {
+            OtaWizardStep::Target => return false,
+            OtaWizardStep::File => OtaWizardStep::Target,
+            OtaWizardStep::Review => OtaWizardStep::File,
+            OtaWizardStep::Send => OtaWizardStep::Review,
+            OtaWizardStep::Progress => OtaWizardStep::Send,
+        };
+        true
+    }
+
+    /// File name of the selected local file, which is also the blob name after upload
+    pub fn file_name(&self) -> Option<&str> {
+        std::path::Path::new(self.file_path.trim())
+            .file_name()
+            .and_then(|s| s.to_str())
+    }
+
+    /// Stores the uploaded module and auto-fills the version from the package metadata,
+    /// or from the file name when the package doesn't contain one
+    pub fn set_uploaded_module(&mut self, module: ModuleInfo, metadata: PackageMetadata) {
+        self.version = metadata.version.clone().unwrap_or_else(|| {
+            std::path::Path::new(&module.blob_name)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_owned()
+        });
+        self.module = Some(module);
+        self.metadata = metadata;
+        self.step = OtaWizardStep::Review;
+    }
+
+    /// Warning shown when the uploaded package targets another chip than the selected one
+    pub fn chip_mismatch(&self) -> Option<String> {
+        self.metadata.mismatch_warning(self.target().0)
+    }
+
+    /// Fills the OTA config keys of the selected target with the uploaded module
+    pub fn apply_to_config_keys(&self, config_keys: &mut [String]) -> Result<(), DMError> {
+        let module = self.module.as_ref().ok_or_else(|| {
+            Report::new(DMError::InvalidData).attach_printable("No firmware file uploaded")
+        })?;
+
+        let (chip_id, component) = self.target();
+        let [chip, version, url, hash, size] = target_config_keys(chip_id, component);
+
+        config_keys[usize::from(ConfigKey::OtaVersion)] = self.version.clone();
+        config_keys[usize::from(chip)] = default_chip_name(chip_id).to_owned();
+        config_keys[usize::from(version)] = self.version.clone();
+        config_keys[usize::from(url)] = module.sas_url.clone();
+        config_keys[usize::from(hash)] = module.hash_base64.clone();
+        config_keys[usize::from(size)] = module.size.to_string();
+
+        Ok(())
+    }
+
+    /// Stores the generated configuration and remembers its req_id for progress tracking
+    pub fn set_payload(&mut self, payload: String) {
+        self.req_id = firmware_of_configuration(&payload)
+            .and_then(|f| f.req_info)
+            .map(|r| r.req_id);
+        self.payload = Some(payload);
+        self.step = OtaWizardStep::Send;
+    }
+
+    /// Progress of the selected target for the sent request
+    pub fn progress(&self, firmware: &FirmwareProperty) -> OtaProgress {
+        let reported_req_id = firmware.req_info.as_ref().map(|r| r.req_id.as_str());
+        if self.req_id.is_none() || reported_req_id != self.req_id.as_deref() {
+            return OtaProgress::Waiting;
+        }
+
+        let (chip_id, component) = self.target();
+        let Some(target) = find_target(firmware, default_chip_name(chip_id), component) else {
+            return OtaProgress::Waiting;
+        };
+
+        match target.process_state.clone().unwrap_or_default() {
+            ProcessState::Done => OtaProgress::Done,
+            state @ (ProcessState::Failed
+            | ProcessState::FailedInvalidArgument
+            | ProcessState::FailedTokenExpired
+            | ProcessState::FailedDownloadRetryExceeded) => OtaProgress::Failed(state),
+            state => OtaProgress::InProgress(state, target.progress.unwrap_or(0)),
+        }
+    }
+}
+
+/// The device may report targets in any order, so look them up by chip and component
+pub fn find_target<'a>(
+    firmware: &'a FirmwareProperty,
+    chip: &str,
+    component: Component,
+) -> Option<&'a Target> {
+    firmware
+        .get_all_targets()?
+        .iter()
+        .find(|t| t.chip == chip && t.component == component)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mqtt_ctrl::evp::ReqInfo, ota::parse_ota_configuration};
+
+    fn module() -> ModuleInfo {
+        ModuleInfo {
+            id: crate::mqtt_ctrl::evp::evp_state::UUID::new(),
+            blob_name: "ApFw_0700FA.bin".to_owned(),
+            container_name: "default".to_owned(),
+            hash: "abcd".to_owned(),
+            hash_base64: "q80=".to_owned(),
+            sas_url: "http://127.0.0.1:10000/default/ApFw_0700FA.bin?sig".to_owned(),
+            size: 1024,
+            last_modified: None,
+        }
+    }
+
+    #[test]
+    fn test_target_focus_wraps() {
+        let mut wizard = OtaWizard::new();
+        assert_eq!(wizard.target(), (ChipId::MainChip, Component::Firmware));
+
+        wizard.target_focus_up();
+        wizard.target_focus_up();
+        assert_eq!(wizard.target(), (ChipId::SensorChip, Component::Firmware));
+
+        wizard.target_focus_down();
+        assert_eq!(wizard.target(), (ChipId::MainChip, Component::Loader));
+    }
+
+    #[test]
+    fn test_step_back() {
+        let mut wizard = OtaWizard::new();
+        assert!(!wizard.step_back());
+
+        wizard.step = OtaWizardStep::Send;
+        assert!(wizard.step_back());
+        assert_eq!(wizard.step, OtaWizardStep::Review);
+    }
+
+    #[test]
+    fn test_generate_and_track_progress() {
+        let mut wizard = OtaWizard::new();
+        wizard.file_path = " /tmp/ApFw_0700FA.bin ".to_owned();
+        assert_eq!(wizard.file_name(), Some("ApFw_0700FA.bin"));
+
+        wizard.set_uploaded_module(module(), PackageMetadata::default());
+        assert_eq!(wizard.version, "ApFw_0700FA");
+        assert_eq!(wizard.step, OtaWizardStep::Review);
+        assert!(wizard.chip_mismatch().is_none());
+
+        wizard.set_uploaded_module(
+            module(),
+            crate::ota::package::parse_package_bytes("ApFw_0700FA.bin", b""),
+        );
+        assert_eq!(wizard.version, "0700FA");
+        assert!(wizard.chip_mismatch().is_none());
+
+        wizard.target_focus_down();
+        assert!(wizard.chip_mismatch().is_some());
+        wizard.target_focus_up();
+
+        let mut config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        wizard.apply_to_config_keys(&mut config_keys).unwrap();
+        assert_eq!(
+            config_keys[usize::from(ConfigKey::OtaMainChipFirmwareChip)],
+            "ApFw"
+        );
+        assert_eq!(
+            config_keys[usize::from(ConfigKey::OtaMainChipFirmwareSize)],
+            "1024"
+        );
+
+        wizard.set_payload(parse_ota_configuration(&config_keys).unwrap());
+        assert_eq!(wizard.step, OtaWizardStep::Send);
+        let req_id = wizard.req_id.clone().unwrap();
+
+        let mut firmware = FirmwareProperty::new();
+        assert_eq!(wizard.progress(&firmware), OtaProgress::Waiting);
+
+        firmware.req_info = Some(ReqInfo { req_id });
+        let target = firmware
+            .get_target_mut(ChipId::MainChip, Component::Firmware)
+            .unwrap();
+        target.process_state = Some(ProcessState::Downloading);
+        target.progress = Some(40);
+        assert_eq!(
+            wizard.progress(&firmware),
+            OtaProgress::InProgress(ProcessState::Downloading, 40)
+        );
+
+        firmware
+            .get_target_mut(ChipId::MainChip, Component::Firmware)
+            .unwrap()
+            .process_state = Some(ProcessState::FailedTokenExpired);
+        assert_eq!(
+            wizard.progress(&firmware),
+            OtaProgress::Failed(ProcessState::FailedTokenExpired)
+        );
+    }
+
+    #[test]
+    fn test_apply_without_module() {
+        let wizard = OtaWizard::new();
+        let mut config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        assert!(wizard.apply_to_config_keys(&mut config_keys).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use std::{collections::VecDeque, time::Duration};
+
+/// Frames the average and the maximum of the overlay are computed over
+const FRAMES: usize = 60;
+
+/// Time spent in each step of one iteration of the main loop
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FrameTimes {
+    /// Parsing the messages received
+    pub parse: Duration,
+    /// The rest of the update of the App and the MqttCtrl
+    pub update: Duration,
+    pub draw: Duration,
+    /// Waiting for a key, up to the poll timeout when none is pressed
+    pub events: Duration,
+}
+
+impl FrameTimes {
+    /// Steps in the order of the main loop
+    pub fn steps(&self) -> [(&'static str, Duration); 4] {
+        [
+            ("parse", self.parse),
+            ("update", self.update),
+            ("draw", self.draw),
+            ("events", self.events),
+        ]
+    }
+}
+
+/// Times of the latest frames, for the --profile-frame overlay
+#[derive(Debug, Default)]
+pub struct FrameProfile {
+    /// Oldest first
+    frames: VecDeque<FrameTimes>,
+}
+
+impl FrameProfile {
+    pub fn record(&mut self, times: FrameTimes) {
+        if self.frames.len() == FRAMES {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(times);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn average(&self) -> FrameTimes {
+        let count = self.frames.len().max(1) as u32;
+        let sum = |step: fn(&FrameTimes) -> Duration| {
+            self.frames.iter().map(step).sum::<Duration>() / count
+        };
+        FrameTimes {
+            parse: sum(|f| f.parse),
+            update: sum(|f| f.update),
+            draw: sum(|f| f.draw),
+            events: sum(|f| f.events),
+        }
+    }
+
+    pub fn max(&self) -> FrameTimes {
+        let max = |step: fn(&FrameTimes) -> Duration| {
+            self.frames.iter().map(step).max().unwrap_or_default()
+        };
+        FrameTimes {
+            parse: max(|f| f.parse),
+            update: max(|f| f.update),
+            draw: max(|f| f.draw),
+            events: max(|f| f.events),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_profile() {
+        let ms = Duration::from_millis;
+        let mut profile = FrameProfile::default();
+        assert_eq!(profile.average(), FrameTimes::default());
+
+        for i in 0..FRAMES as u64 + 2 {
+            profile.record(FrameTimes {
+                parse: ms(1),
+                update: ms(i % 3),
+                draw: ms(4),
+                events: ms(0),
+            });
+        }
+        assert_eq!(profile.len(), FRAMES);
+        assert_eq!(profile.average().parse, ms(1));
+        assert_eq!(profile.average().update, ms(1));
+        assert_eq!(profile.max().update, ms(2));
+        assert_eq!(profile.max().steps()[2], ("draw", ms(4)));
+    }
+}
@@ -0,0 +1,123 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+pub mod ntp;
+pub mod proxy;
+pub mod static_ip;
+pub mod wifi;
+
+#[allow(unused)]
+use {crate::mqtt_ctrl::evp::ResInfo, serde_json::Value};
+
+/// Result of a sent settings configuration as reported by the device
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProvisionStatus {
+    /// The device hasn't reported the sent request yet
+    Waiting,
+    Applied,
+    /// res_info of the request with an error code
+    Failed(String),
+}
+
+impl ProvisionStatus {
+    /// Status of the request `req_id` from the req_info and res_info reported with the settings
+    pub fn of(req_id: Option<&str>, reported_req_id: &str, res_info: &ResInfo) -> Self {
+        match req_id {
+            Some(req_id) if req_id == reported_req_id => match res_info.code() {
+                0 => ProvisionStatus::Applied,
+                _ => ProvisionStatus::Failed(format!(
+                    "{} {}",
+                    res_info.code_str(),
+                    res_info.detail_msg()
+                )),
+            },
+            _ => ProvisionStatus::Waiting,
+        }
+    }
+}
+
+/// req_id of a "configuration/$system/<section>" configuration
+pub fn req_id_of_configuration(config: &str) -> Option<String> {
+    let root: Value = serde_json::from_str(config).ok()?;
+    let settings = root
+        .as_object()?
+        .iter()
+        .find(|(topic, _)| topic.starts_with("configuration/$system/"))?
+        .1
+        .as_str()?;
+    let settings: Value = serde_json::from_str(settings).ok()?;
+    settings
+        .get("req_info")?
+        .get("req_id")?
+        .as_str()
+        .map(|s| s.to_owned())
+}
+
+/// Configuration with the value of the `key` entries of the settings masked, for previews
+pub fn mask_configuration(config: &str, key: &str) -> String {
+    fn mask(value: &mut Value, key: &str) {
+        match value {
+            Value::Object(map) => {
+                for (k, v) in map.iter_mut() {
+                    if k == key && v.is_string() {
+                        *v = Value::String("********".to_owned());
+                    } else {
+                        mask(v, key);
+                    }
+                }
+            }
+            Value::String(s) => {
+                // Settings are sent as a JSON string in the configuration
+                if let Ok(mut inner) = serde_json::from_str::<Value>(s)
+                    && inner.is_object()
+                {
+                    mask(&mut inner, key);
+                    *s = inner.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Ok(mut root) = serde_json::from_str::<Value>(config) else {
+        return config.to_owned();
+    };
+    mask(&mut root, key);
+    serde_json::to_string_pretty(&root).unwrap_or_else(|_| config.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{
+        "configuration/$system/wireless_setting": "{\"req_info\":{\"req_id\":\"abc\"},\"sta_mode_setting\":{\"ssid\":\"lab\",\"password\":\"secret\"}}"
+    }"#;
+
+    #[test]
+    fn test_req_id_of_configuration() {
+        assert_eq!(req_id_of_configuration(CONFIG).as_deref(), Some("abc"));
+        assert!(req_id_of_configuration("{}").is_none());
+    }
+
+    #[test]
+    fn test_mask_configuration() {
+        let masked = mask_configuration(CONFIG, "password");
+        assert!(!masked.contains("secret"));
+        assert!(masked.contains("********"));
+        assert!(masked.contains("lab"));
+    }
+}
@@ -0,0 +1,320 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{app::ConfigKey, error::DMError},
+    error_stack::{Report, Result},
+    std::{
+        net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket},
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// Time waited for the answer of the server
+pub const NTP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Seconds from 1900-01-01, the NTP epoch, to 1970-01-01
+const NTP_UNIX_OFFSET: f64 = 2_208_988_800.0;
+
+const NTP_PORT: u16 = 123;
+
+/// Answer of the NTP server to one SNTP query
+#[derive(Debug, Clone, PartialEq)]
+pub struct NtpSample {
+    pub address: SocketAddr,
+    pub stratum: u8,
+    /// Clock offset of the server from this machine, in seconds
+    pub offset: f64,
+    /// Round trip delay, in seconds
+    pub delay: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NtpReport {
+    /// ntp_url as given in the network settings
+    pub server: String,
+    pub result: std::result::Result<NtpSample, String>,
+}
+
+impl NtpReport {
+    pub fn failure(&self) -> Option<String> {
+        self.result
+            .as_ref()
+            .err()
+            .map(|e| format!("NTP server {} unreachable, {}", self.server, e))
+    }
+
+    /// e.g. "NTP server pool.ntp.org (1.2.3.4:123): stratum 2, offset +0.012s, delay 0.034s"
+    pub fn summary(&self) -> String {
+        match &self.result {
+            Ok(sample) => format!(
+                "NTP server {} ({}): stratum {}, offset {:+.3}s, delay {:.3}s",
+                self.server, sample.address, sample.stratum, sample.offset, sample.delay
+            ),
+            Err(_) => self.failure().unwrap_or_default(),
+        }
+    }
+}
+
+fn ntp_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64())
+        + NTP_UNIX_OFFSET
+}
+
+fn read_timestamp(bytes: &[u8]) -> f64 {
+    let secs = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let frac = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    secs as f64 + frac as f64 / 4_294_967_296.0
+}
+
+fn write_timestamp(bytes: &mut [u8], time: f64) {
+    let secs = time.trunc() as u32;
+    let frac = (time.fract() * 4_294_967_296.0) as u32;
+    bytes[..4].copy_from_slice(&secs.to_be_bytes());
+    bytes[4..8].copy_from_slice(&frac.to_be_bytes());
+}
+
+/// Client request, version 4 in client mode with the transmit timestamp set
+fn request(transmit: f64) -> [u8; 48] {
+    let mut packet = [0u8; 48];
+    packet[0] = (4 << 3) | 3;
+    write_timestamp(&mut packet[40..48], transmit);
+    packet
+}
+
+/// Checks the answer of the server and computes the offset and delay from the 4 timestamps
+fn parse_response(
+    packet: &[u8],
+    sent: &[u8; 48],
+    received_at: f64,
+    address: SocketAddr,
+) -> std::result::Result<NtpSample, String> {
+    if packet.len() < 48 {
+        return Err(format!("short answer of {} bytes", packet.len()));
+    }
+    if packet[0] & 0x07 != 4 {
+        return Err(format!("not a server answer (mode {})", packet[0] & 0x07));
+    }
+    // The originate timestamp of the answer is the transmit timestamp of the request
+    if packet[24..32] != sent[40..48] {
+        return Err("answer to another request".to_owned());
+    }
+
+    let stratum = packet[1];
+    if stratum == 0 {
+        let code = String::from_utf8_lossy(&packet[12..16]).to_string();
+        return Err(format!("kiss-o'-death {}", code.trim_end_matches('\0')));
+    }
+    if stratum >= 16 || packet[0] >> 6 == 3 {
+        return Err("server is not synchronized".to_owned());
+    }
+
+    let t1 = read_timestamp(&sent[40..48]);
+    let t2 = read_timestamp(&packet[32..40]);
+    let t3 = read_timestamp(&packet[40..48]);
+    let t4 = received_at;
+    Ok(NtpSample {
+        address,
+        stratum,
+        offset: ((t2 - t1) + (t3 - t4)) / 2.0,
+        delay: ((t4 - t1) - (t3 - t2)).max(0.0),
+    })
+}
+
+/// NTP server of the network settings, queried from the machine running device-monitor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NtpCheck {
+    pub server: String,
+    pub port: u16,
+}
+
+impl NtpCheck {
+    /// ntp_url of the network settings being edited, with an optional port
+    pub fn from_config_keys(config_keys: &[String]) -> Result<Self, DMError> {
+        let url = config_keys[usize::from(ConfigKey::NtpUrl)].trim();
+        if url.is_empty() {
+            return Err(Report::new(DMError::InvalidData).attach_printable("ntp_url is empty"));
+        }
+
+        // Addresses first, "fd00::1" and "[fd00::1]:1123" are not split on their last ':'
+        if let Ok(address) = url.parse::<SocketAddr>() {
+            return Ok(Self {
+                server: address.ip().to_string(),
+                port: address.port(),
+            });
+        }
+        if let Ok(ip) = url
+            .strip_prefix('[')
+            .and_then(|u| u.strip_suffix(']'))
+            .unwrap_or(url)
+            .parse::<IpAddr>()
+        {
+            return Ok(Self {
+                server: ip.to_string(),
+                port: NTP_PORT,
+            });
+        }
+
+        let (server, port) = match url.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse::<u16>().map_err(|_| {
+                    Report::new(DMError::InvalidData)
+                        .attach_printable(format!("Invalid port of ntp_url: {port}"))
+                })?,
+            ),
+            None => (url, NTP_PORT),
+        };
+
+        Ok(Self {
+            server: server.to_owned(),
+            port,
+        })
+    }
+
+    fn query(&self, timeout: Duration) -> std::result::Result<NtpSample, String> {
+        let address = (self.server.as_str(), self.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("failed to resolve: {e}"))?
+            .next()
+            .ok_or_else(|| "no address found".to_owned())?;
+
+        let bind = if address.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind).map_err(|e| e.to_string())?;
+        socket
+            .set_read_timeout(Some(timeout))
+            .map_err(|e| e.to_string())?;
+        socket.connect(address).map_err(|e| e.to_string())?;
+
+        let sent = request(ntp_now());
+        socket.send(&sent).map_err(|e| e.to_string())?;
+
+        let mut packet = [0u8; 64];
+        let len = socket.recv(&mut packet).map_err(|e| match e.kind() {
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => {
+                format!("no answer within {}s", timeout.as_secs())
+            }
+            _ => e.to_string(),
+        })?;
+        parse_response(&packet[..len], &sent, ntp_now(), address)
+    }
+
+    pub fn run(&self, timeout: Duration) -> NtpReport {
+        NtpReport {
+            server: self.server.clone(),
+            result: self.query(timeout),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_keys(ntp_url: &str) -> Vec<String> {
+        let mut keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        keys[usize::from(ConfigKey::NtpUrl)] = ntp_url.to_owned();
+        keys
+    }
+
+    /// Answer of a server `skew` seconds ahead of the client, taking 10ms to reply
+    fn answer(sent: &[u8; 48], stratum: u8, skew: f64) -> [u8; 48] {
+        let t1 = read_timestamp(&sent[40..48]);
+        let mut packet = [0u8; 48];
+        packet[0] = (4 << 3) | 4;
+        packet[1] = stratum;
+        packet[24..32].copy_from_slice(&sent[40..48]);
+        write_timestamp(&mut packet[32..40], t1 + skew + 0.01);
+        write_timestamp(&mut packet[40..48], t1 + skew + 0.02);
+        packet
+    }
+
+    #[test]
+    fn test_from_config_keys() {
+        let check = NtpCheck::from_config_keys(&config_keys(" pool.ntp.org ")).unwrap();
+        assert_eq!(check.server, "pool.ntp.org");
+        assert_eq!(check.port, 123);
+
+        let check = NtpCheck::from_config_keys(&config_keys("10.0.0.1:1123")).unwrap();
+        assert_eq!((check.server.as_str(), check.port), ("10.0.0.1", 1123));
+
+        let check = NtpCheck::from_config_keys(&config_keys("fd00::1")).unwrap();
+        assert_eq!((check.server.as_str(), check.port), ("fd00::1", 123));
+
+        let check = NtpCheck::from_config_keys(&config_keys("[fd00::1]")).unwrap();
+        assert_eq!((check.server.as_str(), check.port), ("fd00::1", 123));
+
+        let check = NtpCheck::from_config_keys(&config_keys("[fd00::1]:1123")).unwrap();
+        assert_eq!((check.server.as_str(), check.port), ("fd00::1", 1123));
+
+        let check = NtpCheck::from_config_keys(&config_keys("ntp.example.com:1123")).unwrap();
+        assert_eq!(
+            (check.server.as_str(), check.port),
+            ("ntp.example.com", 1123)
+        );
+
+        assert!(NtpCheck::from_config_keys(&config_keys("")).is_err());
+        assert!(NtpCheck::from_config_keys(&config_keys("ntp:abc")).is_err());
+        assert!(NtpCheck::from_config_keys(&config_keys("[fd00::1]:abc")).is_err());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let address: SocketAddr = "127.0.0.1:123".parse().unwrap();
+        let sent = request(NTP_UNIX_OFFSET + 1_700_000_000.25);
+        let t1 = read_timestamp(&sent[40..48]);
+
+        // 20ms round trip of which 10ms spent in the server
+        let sample = parse_response(&answer(&sent, 2, 1.5), &sent, t1 + 0.03, address).unwrap();
+        assert_eq!(sample.stratum, 2);
+        assert!((sample.offset - 1.5).abs() < 1e-3, "{}", sample.offset);
+        assert!((sample.delay - 0.02).abs() < 1e-3, "{}", sample.delay);
+
+        let mut kod = answer(&sent, 0, 0.0);
+        kod[12..16].copy_from_slice(b"RATE");
+        assert_eq!(
+            parse_response(&kod, &sent, t1, address).unwrap_err(),
+            "kiss-o'-death RATE"
+        );
+        assert!(parse_response(&answer(&sent, 16, 0.0), &sent, t1, address).is_err());
+        assert!(parse_response(&sent, &sent, t1, address).is_err());
+        assert!(parse_response(&[0u8; 12], &sent, t1, address).is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let port = server.local_addr().unwrap().port();
+        let handle = std::thread::spawn(move || {
+            let mut sent = [0u8; 48];
+            let (_, peer) = server.recv_from(&mut sent).unwrap();
+            server.send_to(&answer(&sent, 1, 0.0), peer).unwrap();
+        });
+
+        let check = NtpCheck::from_config_keys(&config_keys(&format!("127.0.0.1:{port}"))).unwrap();
+        let report = check.run(NTP_TIMEOUT);
+        handle.join().unwrap();
+        assert!(report.failure().is_none(), "{:?}", report);
+        assert!(report.summary().contains("stratum 1"));
+    }
+}
@@ -0,0 +1,349 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{app::ConfigKey, error::DMError},
+    base64::{Engine as _, engine::general_purpose},
+    error_stack::{Report, Result},
+    std::{
+        io::{BufRead, BufReader, Write},
+        net::{SocketAddr, TcpStream, ToSocketAddrs},
+        time::Duration,
+    },
+};
+
+/// Time given to each step of the check
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Proxy of the network settings, checked from the machine running device-monitor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyCheck {
+    pub host: String,
+    pub port: u16,
+    pub credentials: Option<(String, String)>,
+    /// "host:port" requested with CONNECT, None to only check the TCP connection
+    pub target: Option<String>,
+}
+
+/// Outcome of one step of the check, e.g. "Resolve" or "Connect"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyStep {
+    pub name: &'static str,
+    pub result: std::result::Result<String, String>,
+}
+
+impl ProxyStep {
+    pub fn text(&self) -> String {
+        match &self.result {
+            Ok(s) => format!("{}: OK, {}", self.name, s),
+            Err(s) => format!("{}: FAILED, {}", self.name, s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyReport {
+    /// "host:port" of the proxy
+    pub proxy: String,
+    pub steps: Vec<ProxyStep>,
+}
+
+impl ProxyReport {
+    /// First failed step, None when the proxy is reachable
+    pub fn failure(&self) -> Option<String> {
+        self.steps
+            .iter()
+            .find(|s| s.result.is_err())
+            .map(|s| format!("Proxy {} unreachable, {}", self.proxy, s.text()))
+    }
+
+    pub fn summary(&self) -> String {
+        self.failure()
+            .unwrap_or_else(|| format!("Proxy {} reachable", self.proxy))
+    }
+}
+
+/// Host of a proxy URL given with or without a scheme, e.g. "http://proxy:8080/" -> "proxy"
+fn proxy_host(url: &str) -> &str {
+    let url = url.trim();
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.split(['/', '?']).next().unwrap_or_default();
+    let url = url.rsplit_once('@').map_or(url, |(_, host)| host);
+    if let Some(host) = url.strip_prefix('[') {
+        return host.split(']').next().unwrap_or_default();
+    }
+    match url.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') && port.parse::<u16>().is_ok() => host,
+        _ => url,
+    }
+}
+
+impl ProxyCheck {
+    /// Proxy of the network settings being edited, the CONNECT request is sent to `target`
+    pub fn from_config_keys(config_keys: &[String], target: Option<&str>) -> Result<Self, DMError> {
+        let key = |k: ConfigKey| config_keys[usize::from(k)].trim();
+        let host = proxy_host(key(ConfigKey::ProxyUrl));
+        if host.is_empty() {
+            return Err(Report::new(DMError::InvalidData).attach_printable("proxy_url is empty"));
+        }
+
+        let port = key(ConfigKey::ProxyPort).parse::<u16>().map_err(|_| {
+            Report::new(DMError::InvalidData).attach_printable("proxy_port must be 1-65535")
+        })?;
+        if port == 0 {
+            return Err(
+                Report::new(DMError::InvalidData).attach_printable("proxy_port must be 1-65535")
+            );
+        }
+
+        let user = key(ConfigKey::ProxyUserName);
+        let credentials =
+            (!user.is_empty()).then(|| (user.to_owned(), key(ConfigKey::ProxyPassword).to_owned()));
+
+        Ok(Self {
+            host: host.to_owned(),
+            port,
+            credentials,
+            target: target.map(|t| t.to_owned()),
+        })
+    }
+
+    fn address(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    fn resolve(&self) -> std::result::Result<Vec<SocketAddr>, String> {
+        let addrs: Vec<SocketAddr> = (self.host.as_str(), self.port)
+            .to_socket_addrs()
+            .map_err(|e| e.to_string())?
+            .collect();
+        if addrs.is_empty() {
+            Err("no address found".to_owned())
+        } else {
+            Ok(addrs)
+        }
+    }
+
+    fn connect(
+        addrs: &[SocketAddr],
+        timeout: Duration,
+    ) -> std::result::Result<(TcpStream, SocketAddr), String> {
+        let mut error = String::new();
+        for addr in addrs {
+            match TcpStream::connect_timeout(addr, timeout) {
+                Ok(stream) => return Ok((stream, *addr)),
+                Err(e) => error = format!("{addr}: {e}"),
+            }
+        }
+        Err(error)
+    }
+
+    /// Sends "CONNECT target" and returns the status line of the response
+    fn tunnel(
+        &self,
+        stream: &mut TcpStream,
+        target: &str,
+        timeout: Duration,
+    ) -> std::result::Result<String, String> {
+        stream
+            .set_read_timeout(Some(timeout))
+            .and_then(|_| stream.set_write_timeout(Some(timeout)))
+            .map_err(|e| e.to_string())?;
+
+        let mut request = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n");
+        if let Some((user, password)) = &self.credentials {
+            let token = general_purpose::STANDARD.encode(format!("{user}:{password}"));
+            request.push_str(&format!("Proxy-Authorization: Basic {token}\r\n"));
+        }
+        request.push_str("\r\n");
+        stream
+            .write_all(request.as_bytes())
+            .map_err(|e| e.to_string())?;
+
+        let mut status = String::new();
+        BufReader::new(stream)
+            .read_line(&mut status)
+            .map_err(|e| e.to_string())?;
+        let status = status.trim().to_owned();
+
+        match status.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(status),
+            Some("407") => Err(format!(
+                "{status}, check proxy_user_name and proxy_password"
+            )),
+            Some(_) => Err(status),
+            None if status.is_empty() => Err("connection closed by the proxy".to_owned()),
+            None => Err(format!("not an HTTP response: {status}")),
+        }
+    }
+
+    /// Resolves the proxy, connects to it and requests a tunnel to the target, stopping at
+    /// the first failed step
+    pub fn run(&self, timeout: Duration) -> ProxyReport {
+        let mut report = ProxyReport {
+            proxy: self.address(),
+            steps: vec![],
+        };
+
+        let addrs = self.resolve();
+        report.steps.push(ProxyStep {
+            name: "Resolve",
+            result: addrs
+                .as_ref()
+                .map(|a| a[0].ip().to_string())
+                .map_err(|e| e.clone()),
+        });
+        let Ok(addrs) = addrs else {
+            return report;
+        };
+
+        let connected = Self::connect(&addrs, timeout);
+        report.steps.push(ProxyStep {
+            name: "Connect",
+            result: connected
+                .as_ref()
+                .map(|(_, addr)| format!("TCP connected to {addr}"))
+                .map_err(|e| e.clone()),
+        });
+        let Ok((mut stream, _)) = connected else {
+            return report;
+        };
+
+        if let Some(target) = self.target.as_deref() {
+            report.steps.push(ProxyStep {
+                name: "CONNECT",
+                result: self.tunnel(&mut stream, target, timeout),
+            });
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io::Read, net::TcpListener};
+
+    fn config_keys(url: &str, port: &str, user: &str, password: &str) -> Vec<String> {
+        let mut keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        keys[usize::from(ConfigKey::ProxyUrl)] = url.to_owned();
+        keys[usize::from(ConfigKey::ProxyPort)] = port.to_owned();
+        keys[usize::from(ConfigKey::ProxyUserName)] = user.to_owned();
+        keys[usize::from(ConfigKey::ProxyPassword)] = password.to_owned();
+        keys
+    }
+
+    /// Proxy answering one CONNECT request with `response`, returns its port and the request
+    fn fake_proxy(response: &'static str) -> (u16, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut buf = [0u8; 256];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (port, handle)
+    }
+
+    #[test]
+    fn test_proxy_host() {
+        assert_eq!(proxy_host("proxy.example.com"), "proxy.example.com");
+        assert_eq!(
+            proxy_host(" http://proxy.example.com:8080/ "),
+            "proxy.example.com"
+        );
+        assert_eq!(proxy_host("http://user:pw@10.0.0.1"), "10.0.0.1");
+        assert_eq!(proxy_host("[fd00::1]:3128"), "fd00::1");
+        assert_eq!(proxy_host("fd00::1"), "fd00::1");
+    }
+
+    #[test]
+    fn test_from_config_keys() {
+        let check =
+            ProxyCheck::from_config_keys(&config_keys("http://proxy", "3128", "", "x"), None)
+                .unwrap();
+        assert_eq!(check.host, "proxy");
+        assert_eq!(check.port, 3128);
+        assert!(check.credentials.is_none());
+
+        assert!(ProxyCheck::from_config_keys(&config_keys("", "3128", "", ""), None).is_err());
+        assert!(ProxyCheck::from_config_keys(&config_keys("proxy", "", "", ""), None).is_err());
+        assert!(ProxyCheck::from_config_keys(&config_keys("proxy", "0", "", ""), None).is_err());
+        assert!(
+            ProxyCheck::from_config_keys(&config_keys("proxy", "70000", "", ""), None).is_err()
+        );
+    }
+
+    #[test]
+    fn test_run_with_authentication() {
+        let (port, handle) = fake_proxy("HTTP/1.1 200 Connection established\r\n\r\n");
+        let keys = config_keys("127.0.0.1", &port.to_string(), "user", "pass");
+        let check = ProxyCheck::from_config_keys(&keys, Some("broker:1883")).unwrap();
+
+        let report = check.run(CHECK_TIMEOUT);
+        assert_eq!(report.steps.len(), 3);
+        assert!(report.failure().is_none(), "{:?}", report);
+
+        let request = handle.join().unwrap();
+        assert!(request.starts_with("CONNECT broker:1883 HTTP/1.1\r\n"));
+        // base64 of "user:pass"
+        assert!(request.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"));
+    }
+
+    #[test]
+    fn test_run_rejected() {
+        let (port, handle) = fake_proxy("HTTP/1.1 407 Proxy Authentication Required\r\n\r\n");
+        let keys = config_keys("127.0.0.1", &port.to_string(), "", "");
+        let check = ProxyCheck::from_config_keys(&keys, Some("broker:1883")).unwrap();
+
+        let report = check.run(CHECK_TIMEOUT);
+        handle.join().unwrap();
+        let failure = report.failure().unwrap();
+        assert!(failure.contains("CONNECT: FAILED"));
+        assert!(failure.contains("proxy_user_name"));
+    }
+
+    #[test]
+    fn test_run_refused() {
+        // Nothing listens on the port once the listener is dropped
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let keys = config_keys("127.0.0.1", &port.to_string(), "", "");
+        let report = ProxyCheck::from_config_keys(&keys, None)
+            .unwrap()
+            .run(CHECK_TIMEOUT);
+        assert_eq!(report.steps.len(), 2);
+        assert!(report.failure().unwrap().contains("Connect: FAILED"));
+    }
+}
@@ -0,0 +1,508 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        app::ConfigKey,
+        i18n::{tr, trf},
+        mqtt_ctrl::evp::device_info::NetworkSettings,
+    },
+    std::net::{Ipv4Addr, Ipv6Addr},
+};
+
+/// Input fields of the settings step, the first one selects the ip_method
+pub const STATIC_IP_FIELDS: [&str; 9] = [
+    "ip_method",
+    "IPv4 address",
+    "IPv4 subnet mask",
+    "IPv4 gateway",
+    "IPv4 DNS",
+    "IPv6 address",
+    "IPv6 prefix length",
+    "IPv6 gateway",
+    "IPv6 DNS",
+];
+
+/// Config keys of the fields after ip_method
+const ADDRESS_KEYS: [ConfigKey; 8] = [
+    ConfigKey::StaticIpv4Ip,
+    ConfigKey::StaticIpv4SubnetMask,
+    ConfigKey::StaticIpv4Gateway,
+    ConfigKey::StaticIpv4Dns,
+    ConfigKey::StaticIpv6Ip,
+    ConfigKey::StaticIpv6SubnetMask,
+    ConfigKey::StaticIpv6Gateway,
+    ConfigKey::StaticIpv6Dns,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaticIpWizardStep {
+    /// Input the addresses, checked while typing
+    Settings,
+    /// Show the generated network_settings and the connectivity warnings before sending
+    Preview,
+    /// Wait for the device to reboot and compare the reported settings
+    Verify,
+}
+
+impl StaticIpWizardStep {
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            StaticIpWizardStep::Settings => "Settings",
+            StaticIpWizardStep::Preview => "Preview",
+            StaticIpWizardStep::Verify => "Verify",
+        }
+    }
+
+    pub fn all() -> [StaticIpWizardStep; 3] {
+        [
+            StaticIpWizardStep::Settings,
+            StaticIpWizardStep::Preview,
+            StaticIpWizardStep::Verify,
+        ]
+    }
+}
+
+/// Subnet mask given as "255.255.255.0", "/24" or "24"
+pub fn parse_ipv4_mask(mask: &str) -> Option<Ipv4Addr> {
+    let mask = mask.trim();
+    if let Ok(prefix) = mask.trim_start_matches('/').parse::<u32>() {
+        return (prefix <= 32)
+            .then(|| Ipv4Addr::from(u32::MAX.checked_shl(32 - prefix).unwrap_or(0)));
+    }
+
+    // The ones of a mask must be contiguous
+    let mask: Ipv4Addr = mask.parse().ok()?;
+    let bits = u32::from(mask);
+    (bits.leading_ones() + bits.trailing_zeros() == 32).then_some(mask)
+}
+
+/// IPv6 prefix length given as "64" or "/64"
+pub fn parse_ipv6_prefix(prefix: &str) -> Option<u32> {
+    prefix
+        .trim()
+        .trim_start_matches('/')
+        .parse()
+        .ok()
+        .filter(|p| *p <= 128)
+}
+
+/// Splits "192.168.0.10/24" into the address and the prefix
+fn split_cidr(address: &str) -> (&str, Option<&str>) {
+    match address.trim().split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (address.trim(), None),
+    }
+}
+
+/// Guided static IP provisioning with subnet checks, sent in "apply and verify" mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticIpWizard {
+    pub step: StaticIpWizardStep,
+    pub focus: usize,
+    /// ip_method to send, dhcp when false
+    pub is_static: bool,
+    /// Values of the fields after ip_method
+    pub values: [String; ADDRESS_KEYS.len()],
+    pub payload: Option<String>,
+}
+
+impl Default for StaticIpWizard {
+    fn default() -> Self {
+        Self {
+            step: StaticIpWizardStep::Settings,
+            focus: 0,
+            is_static: true,
+            values: Default::default(),
+            payload: None,
+        }
+    }
+}
+
+impl StaticIpWizard {
+    /// Starts from the static settings reported by the device
+    pub fn new(network: Option<&NetworkSettings>) -> Self {
+        let mut wizard = Self::default();
+        let Some(network) = network else {
+            return wizard;
+        };
+
+        for (i, setting) in [network.ipv4(), network.ipv6()].into_iter().enumerate() {
+            if let Some(setting) = setting {
+                wizard.values[i * 4] = setting.ip_address().to_owned();
+                wizard.values[i * 4 + 1] = setting.subnet_mask().to_owned();
+                wizard.values[i * 4 + 2] = setting.gateway().to_owned();
+                wizard.values[i * 4 + 3] = setting.dns().to_owned();
+            }
+        }
+        wizard
+    }
+
+    pub fn value(&self, field: usize) -> String {
+        match field {
+            0 if self.is_static => "static".to_owned(),
+            0 => "dhcp".to_owned(),
+            i => self.values[i - 1].clone(),
+        }
+    }
+
+    /// Types into the focused field, ip_method is toggled with `toggle_ip_method`
+    pub fn push(&mut self, c: char) {
+        if self.focus > 0 {
+            self.values[self.focus - 1].push(c);
+        }
+    }
+
+    pub fn pop(&mut self) {
+        if self.focus > 0 {
+            self.values[self.focus - 1].pop();
+        }
+    }
+
+    pub fn focus_up(&mut self) {
+        self.focus = self
+            .focus
+            .checked_sub(1)
+            .unwrap_or(STATIC_IP_FIELDS.len() - 1);
+    }
+
+    pub fn focus_down(&mut self) {
+        self.focus = (self.focus + 1) % STATIC_IP_FIELDS.len();
+    }
+
+    pub fn toggle_ip_method(&mut self) {
+        self.is_static = !self.is_static;
+    }
+
+    /// Goes back to the previous step, returns false on the first step
+    pub fn step_back(&mut self) -> bool {
+        self.step = match self.step {
+            StaticIpWizardStep::Settings => return false,
+            StaticIpWizardStep::Preview => StaticIpWizardStep::Settings,
+            StaticIpWizardStep::Verify => StaticIpWizardStep::Preview,
+        };
+        true
+    }
+
+    fn ipv4_errors(&self, errors: &mut Vec<String>) {
+        let [ip, mask, gateway, dns] = [0, 1, 2, 3].map(|i| self.values[i].trim());
+        let (ip, prefix) = split_cidr(ip);
+        if ip.is_empty() {
+            errors.push(tr("IPv4 address is required for a static IP").to_owned());
+            return;
+        }
+        let Ok(ip) = ip.parse::<Ipv4Addr>() else {
+            errors.push(trf("Invalid IPv4 address: {0}", &[&ip]));
+            return;
+        };
+
+        let mask = match (prefix, mask.is_empty()) {
+            (Some(prefix), _) => parse_ipv4_mask(prefix),
+            (None, false) => parse_ipv4_mask(mask),
+            (None, true) => {
+                errors.push(tr("IPv4 subnet mask is required").to_owned());
+                return;
+            }
+        };
+        let Some(mask) = mask else {
+            errors.push(tr("Invalid IPv4 subnet mask").to_owned());
+            return;
+        };
+
+        let bits = u32::from(mask);
+        let network = u32::from(ip) & bits;
+        let broadcast = network | !bits;
+        if bits.count_ones() < 31 && (u32::from(ip) == network || u32::from(ip) == broadcast) {
+            errors.push(trf(
+                "{0} is the network or broadcast address of the subnet",
+                &[&ip],
+            ));
+        }
+
+        if !gateway.is_empty() {
+            match gateway.parse::<Ipv4Addr>() {
+                Ok(gw) if u32::from(gw) & bits != network => errors.push(trf(
+                    "IPv4 gateway {0} is outside of {1}/{2}",
+                    &[&gw, &Ipv4Addr::from(network), &bits.count_ones()],
+                )),
+                Ok(gw) if gw == ip => {
+                    errors.push(tr("IPv4 gateway is the address of the device").to_owned())
+                }
+                Ok(_) => {}
+                Err(_) => errors.push(trf("Invalid IPv4 gateway: {0}", &[&gateway])),
+            }
+        }
+
+        if !dns.is_empty() && dns.parse::<Ipv4Addr>().is_err() {
+            errors.push(trf("Invalid IPv4 DNS address: {0}", &[&dns]));
+        }
+    }
+
+    fn ipv6_errors(&self, errors: &mut Vec<String>) {
+        let [ip, prefix, gateway, dns] = [4, 5, 6, 7].map(|i| self.values[i].trim());
+        let (ip, cidr_prefix) = split_cidr(ip);
+        if ip.is_empty() {
+            // IPv6 is optional
+            return;
+        }
+        let Ok(ip) = ip.parse::<Ipv6Addr>() else {
+            errors.push(trf("Invalid IPv6 address: {0}", &[&ip]));
+            return;
+        };
+        let Some(prefix) = parse_ipv6_prefix(cidr_prefix.unwrap_or(prefix)) else {
+            errors.push(tr("IPv6 prefix length must be 0 to 128").to_owned());
+            return;
+        };
+
+        let bits = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+        if !gateway.is_empty() {
+            match gateway.parse::<Ipv6Addr>() {
+                // Link-local gateways are usual with IPv6 and outside of any global prefix
+                Ok(gw) if u128::from(gw) >> 118 == 0x3fa => {}
+                Ok(gw) if u128::from(gw) & bits != u128::from(ip) & bits => errors.push(trf(
+                    "IPv6 gateway {0} is outside of the /{1} prefix",
+                    &[&gw, &prefix],
+                )),
+                Ok(_) => {}
+                Err(_) => errors.push(trf("Invalid IPv6 gateway: {0}", &[&gateway])),
+            }
+        }
+
+        if !dns.is_empty() && dns.parse::<Ipv6Addr>().is_err() {
+            errors.push(trf("Invalid IPv6 DNS address: {0}", &[&dns]));
+        }
+    }
+
+    /// Problems of the entered settings, nothing is checked for DHCP
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = vec![];
+        if self.is_static {
+            self.ipv4_errors(&mut errors);
+            self.ipv6_errors(&mut errors);
+        }
+        errors
+    }
+
+    /// Reasons the device may lose its connection to the broker with the new settings
+    pub fn connectivity_warnings(&self, current: Option<&NetworkSettings>) -> Vec<String> {
+        let mut warnings = vec![];
+        let current_method = current.map(|n| n.ip_method()).unwrap_or("unknown");
+
+        match (current_method, self.is_static) {
+            ("static", false) => warnings.push(
+                tr(
+                    "ip_method changes to dhcp: the device is reachable only if a DHCP server \
+                    answers on its network",
+                )
+                .to_owned(),
+            ),
+            (method, true) if method != "static" => warnings.push(
+                tr(
+                    "ip_method changes to static: the device drops its DHCP lease and can't \
+                    reach the broker if the static settings don't match its network",
+                )
+                .to_owned(),
+            ),
+            _ => {}
+        }
+
+        if self.is_static {
+            let (ip, prefix) = split_cidr(&self.values[0]);
+            let mask = parse_ipv4_mask(prefix.unwrap_or(&self.values[1]));
+            let current_ip = current
+                .and_then(|n| n.ipv4())
+                .and_then(|s| s.ip_address().parse::<Ipv4Addr>().ok());
+
+            if let (Ok(ip), Some(mask), Some(current_ip)) =
+                (ip.parse::<Ipv4Addr>(), mask, current_ip)
+                && u32::from(ip) & u32::from(mask) != u32::from(current_ip) & u32::from(mask)
+            {
+                warnings.push(trf(
+                    "{0} is in another subnet than the current address {1}",
+                    &[&ip, &current_ip],
+                ));
+            }
+
+            if self.values[2].trim().is_empty() {
+                warnings.push(
+                    tr("No IPv4 gateway: only the hosts of the subnet can be reached").to_owned(),
+                );
+            }
+            if self.values[3].trim().is_empty() {
+                warnings.push(
+                    tr("No IPv4 DNS: a broker given by its host name can't be resolved").to_owned(),
+                );
+            }
+        }
+
+        warnings
+    }
+
+    /// Fills the network config keys, the subnet mask of a CIDR address is written as a mask
+    pub fn apply_to_config_keys(&self, config_keys: &mut [String]) {
+        config_keys[usize::from(ConfigKey::IpMethod)] =
+            if self.is_static { "1" } else { "0" }.to_owned();
+        if !self.is_static {
+            return;
+        }
+
+        for (key, value) in ADDRESS_KEYS.iter().zip(self.values.iter()) {
+            config_keys[usize::from(*key)] = value.trim().to_owned();
+        }
+
+        let (ip, prefix) = split_cidr(&self.values[0]);
+        config_keys[usize::from(ConfigKey::StaticIpv4Ip)] = ip.to_owned();
+        if let Some(mask) = parse_ipv4_mask(prefix.unwrap_or(&self.values[1])) {
+            config_keys[usize::from(ConfigKey::StaticIpv4SubnetMask)] = mask.to_string();
+        }
+
+        let (ip, prefix) = split_cidr(&self.values[4]);
+        config_keys[usize::from(ConfigKey::StaticIpv6Ip)] = ip.to_owned();
+        if let Some(prefix) = prefix {
+            config_keys[usize::from(ConfigKey::StaticIpv6SubnetMask)] = prefix.to_owned();
+        }
+    }
+
+    pub fn set_payload(&mut self, payload: String) {
+        self.payload = Some(payload);
+        self.step = StaticIpWizardStep::Preview;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt_ctrl::evp::configure::parse_network_settings;
+
+    fn wizard(values: [&str; 4]) -> StaticIpWizard {
+        let mut wizard = StaticIpWizard::default();
+        for (i, v) in values.iter().enumerate() {
+            wizard.values[i] = v.to_string();
+        }
+        wizard
+    }
+
+    #[test]
+    fn test_parse_masks() {
+        assert_eq!(
+            parse_ipv4_mask("255.255.255.0"),
+            Some(Ipv4Addr::new(255, 255, 255, 0))
+        );
+        assert_eq!(
+            parse_ipv4_mask("/20"),
+            Some(Ipv4Addr::new(255, 255, 240, 0))
+        );
+        assert_eq!(parse_ipv4_mask("0"), Some(Ipv4Addr::new(0, 0, 0, 0)));
+        assert!(parse_ipv4_mask("255.0.255.0").is_none());
+        assert!(parse_ipv4_mask("33").is_none());
+        assert_eq!(parse_ipv6_prefix("/64"), Some(64));
+        assert!(parse_ipv6_prefix("129").is_none());
+    }
+
+    #[test]
+    fn test_validate_ipv4() {
+        let ok = wizard(["192.168.0.10", "255.255.255.0", "192.168.0.1", "8.8.8.8"]);
+        assert!(ok.validate().is_empty());
+        assert!(
+            wizard(["192.168.0.10/24", "", "192.168.0.1", ""])
+                .validate()
+                .is_empty()
+        );
+
+        let errors = wizard(["192.168.0.10", "255.255.255.0", "192.168.1.1", "dns"]).validate();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("outside of 192.168.0.0/24"));
+
+        assert_eq!(wizard(["192.168.0.255", "24", "", ""]).validate().len(), 1);
+        assert_eq!(wizard(["192.168.0.300", "24", "", ""]).validate().len(), 1);
+        assert_eq!(wizard(["", "", "", ""]).validate().len(), 1);
+
+        let mut dhcp = wizard(["", "", "", ""]);
+        dhcp.toggle_ip_method();
+        assert!(dhcp.validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_ipv6() {
+        let mut wizard = wizard(["192.168.0.10/24", "", "", ""]);
+        wizard.values[4] = "2001:db8::10/64".to_owned();
+        wizard.values[6] = "fe80::1".to_owned();
+        assert!(wizard.validate().is_empty());
+
+        wizard.values[6] = "2001:db8:1::1".to_owned();
+        assert_eq!(wizard.validate().len(), 1);
+        wizard.values[6] = "2001:db8::1".to_owned();
+        wizard.values[7] = "8.8.8.8".to_owned();
+        assert_eq!(wizard.validate().len(), 1);
+    }
+
+    #[test]
+    fn test_connectivity_warnings() {
+        let current: NetworkSettings = serde_json::from_str(
+            r#"{
+                "req_info": { "req_id": "" },
+                "ip_method": 1,
+                "static_settings_ipv4": { "ip_address": "10.0.0.5", "subnet_mask": "255.0.0.0" },
+                "res_info": { "res_id": "", "code": 0, "detail_msg": "" }
+            }"#,
+        )
+        .unwrap();
+
+        let same = wizard(["10.1.2.3", "255.0.0.0", "10.0.0.1", "10.0.0.1"]);
+        assert!(same.connectivity_warnings(Some(&current)).is_empty());
+
+        let other = wizard(["192.168.0.10", "24", "192.168.0.1", "192.168.0.1"]);
+        assert_eq!(other.connectivity_warnings(Some(&current)).len(), 1);
+
+        // Unknown current settings are treated as DHCP
+        assert_eq!(same.connectivity_warnings(None).len(), 1);
+
+        let mut dhcp = same.clone();
+        dhcp.toggle_ip_method();
+        assert!(dhcp.connectivity_warnings(Some(&current))[0].contains("dhcp"));
+    }
+
+    #[test]
+    fn test_apply_to_config_keys() {
+        let mut wizard = wizard(["192.168.0.10/24", "", "192.168.0.1", "192.168.0.1"]);
+        wizard.values[4] = "2001:db8::10/64".to_owned();
+        let mut config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        wizard.apply_to_config_keys(&mut config_keys);
+        assert_eq!(config_keys[usize::from(ConfigKey::IpMethod)], "1");
+        assert_eq!(
+            config_keys[usize::from(ConfigKey::StaticIpv4Ip)],
+            "192.168.0.10"
+        );
+        assert_eq!(
+            config_keys[usize::from(ConfigKey::StaticIpv4SubnetMask)],
+            "255.255.255.0"
+        );
+        assert_eq!(
+            config_keys[usize::from(ConfigKey::StaticIpv6SubnetMask)],
+            "64"
+        );
+
+        let payload = parse_network_settings(&config_keys).unwrap();
+        assert!(payload.contains("192.168.0.10"));
+        wizard.set_payload(payload);
+        assert_eq!(wizard.step, StaticIpWizardStep::Preview);
+    }
+}
@@ -0,0 +1,312 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{ProvisionStatus, req_id_of_configuration},
+    crate::{
+        app::ConfigKey,
+        i18n::{tr, trf},
+        mqtt_ctrl::evp::device_info::WirelessSettings,
+    },
+    chrono::{DateTime, Local},
+};
+
+/// Encryption values of sta_mode_setting, in the order of their number
+pub const ENCRYPTIONS: [&str; 3] = ["wpa2_psk", "wpa3_psk", "wpa2_wpa3_psk"];
+
+/// Input fields of the credentials step
+pub const WIFI_FIELDS: [&str; 4] = ["SSID", "Password", "Confirm password", "Encryption"];
+const ENCRYPTION_FIELD: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WifiWizardStep {
+    /// Input the SSID, the password twice and the encryption
+    Credentials,
+    /// Show the generated wireless_setting before sending it
+    Preview,
+    /// Track the wireless state and res_info reported by the device
+    Monitor,
+}
+
+impl WifiWizardStep {
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            WifiWizardStep::Credentials => "Credentials",
+            WifiWizardStep::Preview => "Preview",
+            WifiWizardStep::Monitor => "Monitor",
+        }
+    }
+
+    pub fn all() -> [WifiWizardStep; 3] {
+        [
+            WifiWizardStep::Credentials,
+            WifiWizardStep::Preview,
+            WifiWizardStep::Monitor,
+        ]
+    }
+}
+
+/// Guided Wi-Fi provisioning: input the credentials, send them and watch the device connect
+#[derive(Debug, Clone, PartialEq)]
+pub struct WifiWizard {
+    pub step: WifiWizardStep,
+    pub focus: usize,
+    pub ssid: String,
+    pub password: String,
+    pub confirm: String,
+    /// Index in ENCRYPTIONS
+    pub encryption: usize,
+    pub payload: Option<String>,
+    pub req_id: Option<String>,
+    pub sent: Option<DateTime<Local>>,
+}
+
+impl Default for WifiWizard {
+    fn default() -> Self {
+        Self {
+            step: WifiWizardStep::Credentials,
+            focus: 0,
+            ssid: String::new(),
+            password: String::new(),
+            confirm: String::new(),
+            encryption: 0,
+            payload: None,
+            req_id: None,
+            sent: None,
+        }
+    }
+}
+
+impl WifiWizard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn field_mut(&mut self) -> Option<&mut String> {
+        match self.focus {
+            0 => Some(&mut self.ssid),
+            1 => Some(&mut self.password),
+            2 => Some(&mut self.confirm),
+            _ => None,
+        }
+    }
+
+    /// Types into the focused field, the encryption is selected with `next_encryption`
+    pub fn push(&mut self, c: char) {
+        if let Some(field) = self.field_mut() {
+            field.push(c);
+        }
+    }
+
+    pub fn pop(&mut self) {
+        if let Some(field) = self.field_mut() {
+            field.pop();
+        }
+    }
+
+    pub fn focus_up(&mut self) {
+        self.focus = self.focus.checked_sub(1).unwrap_or(WIFI_FIELDS.len() - 1);
+    }
+
+    pub fn focus_down(&mut self) {
+        self.focus = (self.focus + 1) % WIFI_FIELDS.len();
+    }
+
+    pub fn is_encryption_focused(&self) -> bool {
+        self.focus == ENCRYPTION_FIELD
+    }
+
+    pub fn next_encryption(&mut self) {
+        self.encryption = (self.encryption + 1) % ENCRYPTIONS.len();
+    }
+
+    /// Goes back to the previous step, returns false on the first step
+    pub fn step_back(&mut self) -> bool {
+        self.step = match self.step {
+            WifiWizardStep::Credentials => return false,
+            WifiWizardStep::Preview => WifiWizardStep::Credentials,
+            WifiWizardStep::Monitor => WifiWizardStep::Preview,
+        };
+        true
+    }
+
+    /// Checks the credentials against the 802.11 limits, returns the problems found
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = vec![];
+
+        let ssid = self.ssid.trim();
+        if ssid.is_empty() {
+            errors.push(tr("SSID is empty").to_owned());
+        } else if ssid.len() > 32 {
+            errors.push(trf("SSID is {0} bytes long, 32 at most", &[&ssid.len()]));
+        }
+
+        // A passphrase of 8 to 63 printable ASCII characters, or a raw 64 hex digits PSK
+        let len = self.password.len();
+        let is_psk = len == 64 && self.password.chars().all(|c| c.is_ascii_hexdigit());
+        if !is_psk {
+            if !(8..=63).contains(&len) {
+                errors.push(trf(
+                    "Password is {0} characters long, 8 to 63 needed",
+                    &[&len],
+                ));
+            }
+            if !self.password.chars().all(|c| (' '..='~').contains(&c)) {
+                errors.push(tr("Password must be printable ASCII").to_owned());
+            }
+        }
+
+        if self.password != self.confirm {
+            errors.push(tr("Passwords don't match").to_owned());
+        }
+
+        errors
+    }
+
+    /// Fills the wireless config keys with the credentials
+    pub fn apply_to_config_keys(&self, config_keys: &mut [String]) {
+        config_keys[usize::from(ConfigKey::StaSsid)] = self.ssid.trim().to_owned();
+        config_keys[usize::from(ConfigKey::StaPassword)] = self.password.clone();
+        config_keys[usize::from(ConfigKey::StaEncryption)] = self.encryption.to_string();
+    }
+
+    /// Stores the generated configuration and remembers its req_id for monitoring
+    pub fn set_payload(&mut self, payload: String) {
+        self.req_id = req_id_of_configuration(&payload);
+        self.payload = Some(payload);
+        self.step = WifiWizardStep::Preview;
+    }
+
+    pub fn set_sent(&mut self, now: DateTime<Local>) {
+        self.sent = Some(now);
+        self.step = WifiWizardStep::Monitor;
+    }
+
+    /// Result of the sent request according to the reported wireless_setting
+    pub fn status(&self, wireless: Option<&WirelessSettings>) -> ProvisionStatus {
+        match wireless {
+            Some(wireless) => ProvisionStatus::of(
+                self.req_id.as_deref(),
+                &wireless.req_info().req_id,
+                wireless.res_info(),
+            ),
+            None => ProvisionStatus::Waiting,
+        }
+    }
+
+    /// Whether the device reports the sent SSID
+    pub fn is_ssid_reported(&self, wireless: Option<&WirelessSettings>) -> bool {
+        wireless
+            .and_then(|w| w.sta_mode_setting())
+            .is_some_and(|sta| sta.ssid() == self.ssid.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt_ctrl::evp::configure::parse_wireless_settings;
+
+    fn wizard(ssid: &str, password: &str, confirm: &str) -> WifiWizard {
+        WifiWizard {
+            ssid: ssid.to_owned(),
+            password: password.to_owned(),
+            confirm: confirm.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            wizard("lab", "password1", "password1")
+                .validate()
+                .is_empty()
+        );
+        assert!(
+            wizard("lab", &"a1".repeat(32), &"a1".repeat(32))
+                .validate()
+                .is_empty()
+        );
+
+        assert_eq!(wizard(" ", "password1", "password1").validate().len(), 1);
+        assert_eq!(
+            wizard(&"s".repeat(33), "password1", "password1")
+                .validate()
+                .len(),
+            1
+        );
+        assert_eq!(wizard("lab", "short", "short").validate().len(), 1);
+        assert_eq!(wizard("lab", "password1", "password2").validate().len(), 1);
+        assert_eq!(wizard("lab", "pässword1", "pässword1").validate().len(), 1);
+    }
+
+    #[test]
+    fn test_input_and_encryption() {
+        let mut wizard = WifiWizard::new();
+        "lab".chars().for_each(|c| wizard.push(c));
+        wizard.focus_up();
+        assert!(wizard.is_encryption_focused());
+        wizard.push('x');
+        wizard.next_encryption();
+        wizard.next_encryption();
+        wizard.focus_down();
+        wizard.pop();
+        assert_eq!(wizard.ssid, "la");
+        assert_eq!(ENCRYPTIONS[wizard.encryption], "wpa2_wpa3_psk");
+    }
+
+    #[test]
+    fn test_generate_and_monitor() {
+        let mut wizard = wizard("lab", "password1", "password1");
+        let mut config_keys: Vec<String> = (0..ConfigKey::size()).map(|_| String::new()).collect();
+        wizard.apply_to_config_keys(&mut config_keys);
+        wizard.set_payload(parse_wireless_settings(&config_keys).unwrap());
+        assert_eq!(wizard.step, WifiWizardStep::Preview);
+        let req_id = wizard.req_id.clone().unwrap();
+
+        let reported = |req_id: &str, code: i32| -> WirelessSettings {
+            serde_json::from_str(&format!(
+                r#"{{
+                    "req_info": {{ "req_id": "{req_id}" }},
+                    "sta_mode_setting": {{ "ssid": "lab", "password": "", "encryption": 0 }},
+                    "res_info": {{ "res_id": "{req_id}", "code": {code}, "detail_msg": "" }}
+                }}"#
+            ))
+            .unwrap()
+        };
+
+        assert_eq!(wizard.status(None), ProvisionStatus::Waiting);
+        assert_eq!(
+            wizard.status(Some(&reported("other", 0))),
+            ProvisionStatus::Waiting
+        );
+        assert_eq!(
+            wizard.status(Some(&reported(&req_id, 0))),
+            ProvisionStatus::Applied
+        );
+        assert!(matches!(
+            wizard.status(Some(&reported(&req_id, 3))),
+            ProvisionStatus::Failed(_)
+        ));
+        assert!(wizard.is_ssid_reported(Some(&reported(&req_id, 0))));
+    }
+}
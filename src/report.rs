@@ -0,0 +1,353 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::{
+        error::DMError,
+        mqtt_ctrl::{
+            MqttCtrl,
+            evp::{
+                device_info::mask_secrets,
+                elog::{Elog, ElogEntry},
+            },
+        },
+        script::ScriptTarget,
+    },
+    chrono::{DateTime, Local},
+    error_stack::{Report, Result},
+    jlogger_tracing::jdebug,
+    serde_json::Value,
+    std::fmt::Write,
+};
+
+/// Format of the device report
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+/// Device state, elogs and sent configurations, rendered for issue trackers
+#[derive(Debug)]
+pub struct DeviceReport {
+    pub generated: DateTime<Local>,
+    /// Same content as the state checked by scripts
+    pub state: Value,
+    pub elogs: Vec<ElogEntry>,
+    pub configs: Vec<(DateTime<Local>, Value)>,
+}
+
+/// Parses a sent configuration, including the settings sent as JSON strings
+fn parse_config(config: &str, include_secrets: bool) -> Value {
+    let mut value = serde_json::from_str(config).unwrap_or(Value::String(config.to_owned()));
+
+    if let Value::Object(map) = &mut value {
+        for v in map.values_mut() {
+            if let Value::String(s) = v
+                && let Ok(settings @ Value::Object(_)) = serde_json::from_str::<Value>(s)
+            {
+                *v = settings;
+            }
+        }
+    }
+
+    if !include_secrets {
+        mask_secrets(&mut value);
+    }
+    value
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+fn elog_columns(entry: &ElogEntry) -> [String; 5] {
+    let elog = entry.elog();
+    let (time, repeated) = match entry.count() {
+        1 => (entry.first_timestamp().to_owned(), String::new()),
+        count => (
+            format!("{} - {}", entry.first_timestamp(), entry.last_timestamp()),
+            format!(" x{count}"),
+        ),
+    };
+    [
+        time,
+        elog.level_str().to_owned(),
+        elog.component_name()
+            .map(|name| format!("{} ({})", name, elog.component_id()))
+            .unwrap_or(elog.component_id().to_string()),
+        format!(
+            "{} (0x{:0x}){}",
+            elog.event_str(),
+            elog.event_id(),
+            repeated
+        ),
+        elog.event_description().unwrap_or_default().to_owned(),
+    ]
+}
+
+const ELOG_HEADERS: [&str; 5] = ["Time", "Level", "Component", "Event", "Description"];
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl DeviceReport {
+    pub fn new(mqtt_ctrl: &MqttCtrl) -> Self {
+        Self {
+            generated: Local::now(),
+            state: mqtt_ctrl.state(),
+            elogs: mqtt_ctrl.elogs().to_vec(),
+            configs: mqtt_ctrl
+                .sent_configs()
+                .iter()
+                .map(|(time, config)| (*time, parse_config(config, mqtt_ctrl.include_secrets())))
+                .collect(),
+        }
+    }
+
+    /// State sections other than the deployment status, which has its own section
+    fn state_sections(&self) -> Vec<(&str, &Value)> {
+        match &self.state {
+            Value::Object(map) => map
+                .iter()
+                .filter(|(key, _)| key.as_str() != "deployment_status")
+                .map(|(key, value)| (key.as_str(), value))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "Device report {}",
+            self.generated.format("%Y-%m-%d %H:%M:%S %Z")
+        )
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", self.title());
+
+        let _ = writeln!(out, "## Device state\n");
+        for (key, value) in self.state_sections() {
+            let _ = writeln!(out, "### {}\n\n```json\n{}\n```\n", key, pretty(value));
+        }
+
+        let _ = writeln!(out, "## Deployment status\n");
+        match &self.state["deployment_status"] {
+            Value::Null => out.push_str("Not reported.\n\n"),
+            status => {
+                let _ = writeln!(out, "```json\n{}\n```\n", pretty(status));
+            }
+        }
+
+        let _ = writeln!(out, "## Event logs ({})\n", self.elogs.len());
+        if self.elogs.is_empty() {
+            out.push_str("None received.\n\n");
+        } else {
+            let _ = writeln!(out, "| {} |", ELOG_HEADERS.join(" | "));
+            let _ = writeln!(out, "|{}", "---|".repeat(ELOG_HEADERS.len()));
+            for elog in &self.elogs {
+                let columns = elog_columns(elog).map(|c| c.replace('|', "\\|"));
+                let _ = writeln!(out, "| {} |", columns.join(" | "));
+            }
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "## Configuration history ({})\n", self.configs.len());
+        if self.configs.is_empty() {
+            out.push_str("None sent.\n");
+        }
+        for (time, config) in &self.configs {
+            let _ = writeln!(
+                out,
+                "### {}\n\n```json\n{}\n```\n",
+                time.format("%Y-%m-%d %H:%M:%S"),
+                pretty(config)
+            );
+        }
+
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        let title = escape_html(&self.title());
+        let _ = writeln!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\nbody {{ font-family: sans-serif; margin: 2em; }}\n\
+             pre {{ background: #f4f4f4; padding: 0.5em; overflow-x: auto; }}\n\
+             table {{ border-collapse: collapse; }}\n\
+             th, td {{ border: 1px solid #ccc; padding: 0.2em 0.5em; text-align: left; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>"
+        );
+
+        let pre = |value: &Value| format!("<pre>{}</pre>", escape_html(&pretty(value)));
+
+        out.push_str("<h2>Device state</h2>\n");
+        for (key, value) in self.state_sections() {
+            let _ = writeln!(out, "<h3>{}</h3>\n{}", escape_html(key), pre(value));
+        }
+
+        out.push_str("<h2>Deployment status</h2>\n");
+        match &self.state["deployment_status"] {
+            Value::Null => out.push_str("<p>Not reported.</p>\n"),
+            status => {
+                let _ = writeln!(out, "{}", pre(status));
+            }
+        }
+
+        let _ = writeln!(out, "<h2>Event logs ({})</h2>", self.elogs.len());
+        if self.elogs.is_empty() {
+            out.push_str("<p>None received.</p>\n");
+        } else {
+            out.push_str("<table>\n<tr>");
+            for header in ELOG_HEADERS {
+                let _ = write!(out, "<th>{header}</th>");
+            }
+            out.push_str("</tr>\n");
+            for elog in &self.elogs {
+                out.push_str("<tr>");
+                for column in elog_columns(elog) {
+                    let _ = write!(out, "<td>{}</td>", escape_html(&column));
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str("</table>\n");
+        }
+
+        let _ = writeln!(
+            out,
+            "<h2>Configuration history ({})</h2>",
+            self.configs.len()
+        );
+        if self.configs.is_empty() {
+            out.push_str("<p>None sent.</p>\n");
+        }
+        for (time, config) in &self.configs {
+            let _ = writeln!(
+                out,
+                "<h3>{}</h3>\n{}",
+                time.format("%Y-%m-%d %H:%M:%S"),
+                pre(config)
+            );
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Html => self.to_html(),
+        }
+    }
+
+    /// Writes the report to `path`, or to a file named after the current time
+    pub fn save(&self, format: ReportFormat, path: Option<&str>) -> Result<String, DMError> {
+        let path = path.map(str::to_owned).unwrap_or(format!(
+            "report_{}.{}",
+            self.generated.format("%Y%m%d_%H%M%S"),
+            format.extension()
+        ));
+
+        std::fs::write(&path, self.render(format))
+            .map_err(|e| Report::new(DMError::IOError).attach_printable(e))?;
+        jdebug!(
+            func = "DeviceReport::save()",
+            line = line!(),
+            note = "report saved",
+            path = &path
+        );
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> DeviceReport {
+        let elog = Elog::parse(
+            r#"{
+                "serial": "SN001",
+                "level": 1,
+                "timestamp": "2024-06-01T10:00:00Z",
+                "component_id": 100,
+                "component_name": "Main",
+                "event_id": 4096,
+                "event_description": "a|b <c>"
+            }"#,
+        )
+        .unwrap();
+
+        DeviceReport {
+            generated: Local::now(),
+            state: serde_json::json!({ "connected": true, "deployment_status": null }),
+            elogs: vec![ElogEntry::new(elog)],
+            configs: vec![(
+                Local::now(),
+                parse_config(
+                    r#"{"configuration/$system/network_settings":"{\"proxy_settings\":{\"proxy_password\":\"p4ss\"}}"}"#,
+                    false,
+                ),
+            )],
+        }
+    }
+
+    #[test]
+    fn test_parse_config_masks_nested_settings() {
+        let config = &sample().configs[0].1;
+        assert_eq!(
+            config["configuration/$system/network_settings"]["proxy_settings"]["proxy_password"],
+            "********"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let report = sample();
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("### connected\n\n```json\ntrue\n```"));
+        assert!(markdown.contains("| Time | Level | Component | Event | Description |"));
+        assert!(markdown.contains("| ERROR | Main (100) |"));
+        assert!(markdown.contains("a\\|b <c>"));
+        assert!(markdown.contains("## Deployment status\n\nNot reported."));
+        assert!(!markdown.contains("p4ss"));
+
+        let html = report.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<td>a|b &lt;c&gt;</td>"));
+        assert!(html.contains("<h2>Configuration history (1)</h2>"));
+    }
+}
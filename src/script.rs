@@ -0,0 +1,725 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+pub mod expect;
+
+#[allow(unused)]
+use {
+    crate::{
+        app::{ConfigKey, DirectCommand},
+        error::{DMError, DMErrorExt},
+        mqtt_ctrl::{
+            MqttCtrl,
+            evp::{
+                configure::{
+                    parse_network_settings, parse_system_setting, parse_wireless_settings,
+                },
+                device_info::mask_secrets,
+                req_res::{ReqResStatus, SettingsKind},
+            },
+        },
+    },
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jerror, jinfo},
+    serde::Serialize,
+    serde_json::Value,
+    std::{
+        path::{Path, PathBuf},
+        time::{Duration, Instant},
+    },
+};
+
+/// Extension of the script files listed in the Scripts screen
+pub const SCRIPT_EXTENSION: &str = "dms";
+
+const DEFAULT_REBOOT_TIMEOUT: u64 = 180;
+const DEFAULT_APPLY_TIMEOUT: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+impl Op {
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "==" => Op::Eq,
+            "!=" => Op::Ne,
+            "<" => Op::Lt,
+            "<=" => Op::Le,
+            ">" => Op::Gt,
+            ">=" => Op::Ge,
+            "contains" => Op::Contains,
+            _ => return None,
+        })
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Op::Eq => "==",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Contains => "contains",
+        }
+    }
+
+    /// Numbers are compared as numbers, other values as strings
+    fn eval(&self, actual: &str, expected: &str) -> bool {
+        if let (Ok(a), Ok(e)) = (actual.parse::<f64>(), expected.parse::<f64>()) {
+            return match self {
+                Op::Eq => a == e,
+                Op::Ne => a != e,
+                Op::Lt => a < e,
+                Op::Le => a <= e,
+                Op::Gt => a > e,
+                Op::Ge => a >= e,
+                Op::Contains => actual.contains(expected),
+            };
+        }
+
+        match self {
+            Op::Eq => actual == expected,
+            Op::Ne => actual != expected,
+            Op::Lt => actual < expected,
+            Op::Le => actual <= expected,
+            Op::Gt => actual > expected,
+            Op::Ge => actual >= expected,
+            Op::Contains => actual.contains(expected),
+        }
+    }
+}
+
+impl std::fmt::Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// "<path> <op> <value>", e.g. "system_settings.res_info.code == 0"
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub path: String,
+    pub op: Op,
+    pub value: String,
+}
+
+impl Condition {
+    fn parse(s: &str) -> Option<Self> {
+        let mut it = s.splitn(3, char::is_whitespace);
+        let path = it.next()?.to_owned();
+        let op = Op::parse(it.next()?)?;
+        let value = it.next().unwrap_or("").trim().trim_matches('"').to_owned();
+        Some(Self { path, op, value })
+    }
+
+    /// Value at the path of the device state, "null" if not reported
+    pub fn actual(&self, state: &Value) -> String {
+        match lookup(state, &self.path) {
+            Some(Value::String(s)) => s.clone(),
+            Some(v) => v.to_string(),
+            None => "null".to_owned(),
+        }
+    }
+
+    pub fn eval(&self, state: &Value) -> bool {
+        self.op.eval(&self.actual(state), &self.value)
+    }
+}
+
+/// Looks up "a.b.0.c" in a JSON value, numbers index arrays
+pub fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |v, key| match v {
+        Value::Array(a) => a.get(key.parse::<usize>().ok()?),
+        v => v.get(key),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Log(String),
+    Sleep(Duration),
+    /// Sends the reboot direct command and waits for its response
+    Reboot,
+    /// Waits for the device to connect again, e.g. after a reboot
+    WaitReconnect(Duration),
+    /// Sets a configuration key for the next apply
+    Set(ConfigKey, String),
+    /// Sends the configuration of the settings generated from the keys set
+    Apply(SettingsKind),
+    /// Sends a configuration file as it is
+    Send(PathBuf),
+    /// Waits for the res_id of the settings to match the latest configuration sent
+    WaitApplied(SettingsKind, Duration),
+    Assert(Condition),
+    WaitFor(Duration, Condition),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    pub name: String,
+    /// Steps with their line numbers
+    pub steps: Vec<(usize, Step)>,
+}
+
+fn parse_settings_kind(s: &str) -> Option<SettingsKind> {
+    SettingsKind::ALL.into_iter().find(|k| k.to_string() == s)
+}
+
+fn parse_config_key(s: &str) -> Option<ConfigKey> {
+    (0..ConfigKey::size())
+        .map(ConfigKey::from)
+        .find(|k| *k != ConfigKey::Invalid && k.to_string() == s)
+}
+
+fn parse_seconds(s: Option<&str>, default: u64) -> Option<Duration> {
+    match s {
+        None => Some(Duration::from_secs(default)),
+        Some(s) => s.parse().ok().map(Duration::from_secs),
+    }
+}
+
+fn parse_step(line: &str, dir: &Path) -> Option<Step> {
+    let (cmd, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let args = args.trim();
+    let mut words = args.split_whitespace();
+
+    let step = match cmd {
+        "log" => Step::Log(args.to_owned()),
+        "sleep" => Step::Sleep(parse_seconds(Some(args), 0)?),
+        "reboot" if args.is_empty() => Step::Reboot,
+        "wait_reconnect" => {
+            Step::WaitReconnect(parse_seconds(words.next(), DEFAULT_REBOOT_TIMEOUT)?)
+        }
+        "set" => {
+            let (key, value) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+            Step::Set(
+                parse_config_key(key)?,
+                value.trim().trim_matches('"').to_owned(),
+            )
+        }
+        "apply" => Step::Apply(parse_settings_kind(args)?),
+        "send" if !args.is_empty() => Step::Send(dir.join(args)),
+        "wait_applied" => Step::WaitApplied(
+            parse_settings_kind(words.next()?)?,
+            parse_seconds(words.next(), DEFAULT_APPLY_TIMEOUT)?,
+        ),
+        "assert" => Step::Assert(Condition::parse(args)?),
+        "wait_for" => {
+            let (timeout, condition) = args.split_once(char::is_whitespace)?;
+            Step::WaitFor(
+                parse_seconds(Some(timeout), 0)?,
+                Condition::parse(condition.trim())?,
+            )
+        }
+        _ => return None,
+    };
+
+    Some(step)
+}
+
+impl Script {
+    /// Parses a script, files sent by "send" are relative to `dir`
+    pub fn parse(name: &str, text: &str, dir: &Path) -> Result<Self, DMError> {
+        let mut steps = vec![];
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let step = parse_step(line, dir).ok_or_else(|| {
+                Report::new(DMError::ParserError).attach_printable(format!(
+                    "{}:{}: invalid step: {}",
+                    name,
+                    i + 1,
+                    line
+                ))
+            })?;
+            steps.push((i + 1, step));
+        }
+
+        Ok(Self {
+            name: name.to_owned(),
+            steps,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, DMError> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            Report::new(DMError::IOError).attach_printable(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Self::parse(&name, &text, path.parent().unwrap_or(Path::new(".")))
+    }
+}
+
+/// What a script acts on, implemented by MqttCtrl
+pub trait ScriptTarget {
+    /// Device state with the settings, states and capabilities as top-level keys
+    fn state(&self) -> Value;
+    fn connect_requests(&self) -> u32;
+    fn send_configure(&mut self, config: &str) -> Result<(), DMError>;
+    fn start_direct_command(&mut self, command: DirectCommand);
+    /// Response of the direct command, None while it is running
+    fn direct_command_response(&self) -> Option<std::result::Result<String, String>>;
+    fn req_res_status(&self, kind: SettingsKind) -> Option<ReqResStatus>;
+}
+
+fn to_value<T: Serialize>(value: Option<&T>) -> Value {
+    value
+        .and_then(|v| serde_json::to_value(v).ok())
+        .unwrap_or(Value::Null)
+}
+
+impl ScriptTarget for MqttCtrl {
+    fn state(&self) -> Value {
+        let device_info = self.device_info();
+        let mut state = serde_json::json!({
+            "connected": self.is_device_connected(),
+            "main_chip": to_value(device_info.and_then(|d| d.main_chip())),
+            "companion_chip": to_value(device_info.and_then(|d| d.companion_chip())),
+            "sensor_chip": to_value(device_info.and_then(|d| d.sensor_chip())),
+            "device_states": to_value(self.device_states()),
+            "device_capabilities": to_value(self.device_capabilities()),
+            "device_reserved": to_value(self.device_reserved()),
+            "system_settings": to_value(self.system_settings()),
+            "network_settings": to_value(self.network_settings()),
+            "wireless_settings": to_value(self.wireless_settings()),
+            "deployment_status": self
+                .deployment_status()
+                .and_then(|d| serde_json::from_str::<Value>(d.raw()).ok())
+                .unwrap_or(Value::Null),
+        });
+
+        if let Some(Ok(response)) = self.direct_command_result() {
+            state["direct_command_response"] = to_value(Some(response));
+        }
+
+        if !self.include_secrets() {
+            mask_secrets(&mut state);
+        }
+
+        state
+    }
+
+    fn connect_requests(&self) -> u32 {
+        MqttCtrl::connect_requests(self)
+    }
+
+    fn send_configure(&mut self, config: &str) -> Result<(), DMError> {
+        MqttCtrl::send_configure(self, config)
+    }
+
+    fn start_direct_command(&mut self, command: DirectCommand) {
+        self.direct_command_clear();
+        self.set_direct_command(Some(command));
+    }
+
+    fn direct_command_response(&self) -> Option<std::result::Result<String, String>> {
+        self.direct_command_result().map(|r| match r {
+            Ok(response) => Ok(response.to_string()),
+            Err(e) => Err(e.error_str().unwrap_or("Unknown error".to_owned())),
+        })
+    }
+
+    fn req_res_status(&self, kind: SettingsKind) -> Option<ReqResStatus> {
+        // Stale responses are waited for until the timeout of the step
+        MqttCtrl::req_res_status(self, kind, Duration::MAX)
+    }
+}
+
+/// Runs the steps of a script, one update at a time
+#[derive(Debug)]
+pub struct ScriptRunner {
+    script: Script,
+    /// Index of the running step
+    pc: usize,
+    step_start: Option<Instant>,
+    /// Connection requests counted before the device is expected to reconnect
+    connect_requests: Option<u32>,
+    config_keys: Vec<String>,
+    pub log: Vec<String>,
+    outcome: Option<std::result::Result<(), String>>,
+}
+
+impl ScriptRunner {
+    pub fn new(script: Script) -> Self {
+        let log = vec![format!(
+            "Running {} ({} steps)",
+            script.name,
+            script.steps.len()
+        )];
+        Self {
+            script,
+            pc: 0,
+            step_start: None,
+            connect_requests: None,
+            config_keys: (0..ConfigKey::size()).map(|_| String::new()).collect(),
+            log,
+            outcome: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.script.name
+    }
+
+    /// Ok when all the steps passed, None while running
+    pub fn outcome(&self) -> Option<&std::result::Result<(), String>> {
+        self.outcome.as_ref()
+    }
+
+    /// Line number of the running step
+    pub fn current_line(&self) -> Option<usize> {
+        if self.outcome.is_some() {
+            return None;
+        }
+        self.script.steps.get(self.pc).map(|(line, _)| *line)
+    }
+
+    pub fn cancel(&mut self) {
+        if self.outcome.is_none() {
+            self.fail("cancelled".to_owned());
+        }
+    }
+
+    fn fail(&mut self, error: String) {
+        let line = self.script.steps.get(self.pc).map(|(l, _)| *l).unwrap_or(0);
+        let error = format!("FAILED at line {}: {}", line, error);
+        jerror!(
+            func = "ScriptRunner::fail()",
+            script = self.script.name,
+            error = error
+        );
+        self.log.push(error.clone());
+        self.outcome = Some(Err(error));
+    }
+
+    fn timed_out(&mut self, now: Instant, timeout: Duration) -> bool {
+        let start = *self.step_start.get_or_insert(now);
+        now.saturating_duration_since(start) >= timeout
+    }
+
+    /// Runs the step, Ok(true) when it is done and Ok(false) while waiting
+    fn run_step(
+        &mut self,
+        step: &Step,
+        target: &mut dyn ScriptTarget,
+        now: Instant,
+    ) -> std::result::Result<bool, String> {
+        match step {
+            Step::Log(text) => {
+                self.log.push(text.clone());
+                Ok(true)
+            }
+            Step::Sleep(duration) => Ok(self.timed_out(now, *duration)),
+            Step::Reboot => {
+                if self.step_start.is_none() {
+                    self.step_start = Some(now);
+                    self.connect_requests = Some(target.connect_requests());
+                    target.start_direct_command(DirectCommand::Reboot);
+                    return Ok(false);
+                }
+
+                match target.direct_command_response() {
+                    None => Ok(false),
+                    Some(Ok(response)) => {
+                        self.log.push(format!("reboot response: {}", response));
+                        Ok(true)
+                    }
+                    Some(Err(e)) => Err(e),
+                }
+            }
+            Step::WaitReconnect(timeout) => {
+                let current = target.connect_requests();
+                if current > *self.connect_requests.get_or_insert(current) {
+                    self.connect_requests = Some(current);
+                    return Ok(true);
+                }
+
+                if self.timed_out(now, *timeout) {
+                    return Err(format!(
+                        "device did not reconnect within {}s",
+                        timeout.as_secs()
+                    ));
+                }
+                Ok(false)
+            }
+            Step::Set(key, value) => {
+                self.config_keys[usize::from(*key)] = value.clone();
+                Ok(true)
+            }
+            Step::Apply(kind) => {
+                let config = match kind {
+                    SettingsKind::System => parse_system_setting(&self.config_keys),
+                    SettingsKind::Network => parse_network_settings(&self.config_keys),
+                    SettingsKind::Wireless => parse_wireless_settings(&self.config_keys),
+                }
+                .map_err(|e| e.error_str().unwrap_or("Invalid configuration".to_owned()))?;
+
+                if config.is_empty() {
+                    return Err(format!("no {} key set", kind));
+                }
+
+                target
+                    .send_configure(&config)
+                    .map_err(|_| format!("failed to send {}", kind))?;
+                self.config_keys.iter_mut().for_each(|v| v.clear());
+                Ok(true)
+            }
+            Step::Send(path) => {
+                let config = std::fs::read_to_string(path)
+                    .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+                target
+                    .send_configure(&config)
+                    .map_err(|_| format!("failed to send {}", path.display()))?;
+                Ok(true)
+            }
+            Step::WaitApplied(kind, timeout) => match target.req_res_status(*kind) {
+                None => Err(format!("no {} configuration sent", kind)),
+                Some(ReqResStatus::Applied) => Ok(true),
+                Some(_) if self.timed_out(now, *timeout) => Err(format!(
+                    "{} not applied within {}s",
+                    kind,
+                    timeout.as_secs()
+                )),
+                Some(_) => Ok(false),
+            },
+            Step::Assert(condition) => {
+                let state = target.state();
+                if condition.eval(&state) {
+                    Ok(true)
+                } else {
+                    Err(format!(
+                        "assert {} {:?} {}, but it is {}",
+                        condition.path,
+                        condition.op,
+                        condition.value,
+                        condition.actual(&state)
+                    ))
+                }
+            }
+            Step::WaitFor(timeout, condition) => {
+                let state = target.state();
+                if condition.eval(&state) {
+                    Ok(true)
+                } else if self.timed_out(now, *timeout) {
+                    Err(format!(
+                        "{} is {} after {}s",
+                        condition.path,
+                        condition.actual(&state),
+                        timeout.as_secs()
+                    ))
+                } else {
+                    Ok(false)
+                }
+            }
+        }
+    }
+
+    /// Runs the steps until one has to wait or the script ends
+    pub fn step(&mut self, target: &mut dyn ScriptTarget, now: Instant) {
+        while self.outcome.is_none() {
+            let Some((line, step)) = self.script.steps.get(self.pc).cloned() else {
+                self.log.push("PASSED".to_owned());
+                self.outcome = Some(Ok(()));
+                return;
+            };
+
+            match self.run_step(&step, target, now) {
+                Ok(true) => {
+                    jinfo!(event = "SCRIPT", line = line, step = format!("{:?}", step));
+                    self.log.push(format!("ok   {:3}: {:?}", line, step));
+                    self.pc += 1;
+                    self.step_start = None;
+                }
+                Ok(false) => return,
+                Err(e) => self.fail(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeTarget {
+        state: Value,
+        connect_requests: u32,
+        sent: Vec<String>,
+        direct_command: Option<DirectCommand>,
+        response: Option<std::result::Result<String, String>>,
+        applied: bool,
+    }
+
+    impl ScriptTarget for FakeTarget {
+        fn state(&self) -> Value {
+            self.state.clone()
+        }
+
+        fn connect_requests(&self) -> u32 {
+            self.connect_requests
+        }
+
+        fn send_configure(&mut self, config: &str) -> Result<(), DMError> {
+            self.sent.push(config.to_owned());
+            Ok(())
+        }
+
+        fn start_direct_command(&mut self, command: DirectCommand) {
+            self.direct_command = Some(command);
+        }
+
+        fn direct_command_response(&self) -> Option<std::result::Result<String, String>> {
+            self.response.clone()
+        }
+
+        fn req_res_status(&self, _kind: SettingsKind) -> Option<ReqResStatus> {
+            match (self.sent.is_empty(), self.applied) {
+                (true, _) => None,
+                (false, true) => Some(ReqResStatus::Applied),
+                (false, false) => Some(ReqResStatus::Pending),
+            }
+        }
+    }
+
+    const SCRIPT: &str = r#"
+# Bring-up test
+log start
+reboot
+wait_reconnect 60
+set ip_method 0
+set ntp_url pool.ntp.org
+apply network_settings
+wait_applied network_settings 30
+assert network_settings.res_info.code == 0
+"#;
+
+    #[test]
+    fn test_parse_script() {
+        let script = Script::parse("test.dms", SCRIPT, Path::new(".")).unwrap();
+        assert_eq!(script.steps.len(), 8);
+        assert_eq!(script.steps[0], (3, Step::Log("start".to_owned())));
+        assert_eq!(
+            script.steps[2].1,
+            Step::WaitReconnect(Duration::from_secs(60))
+        );
+        assert_eq!(
+            script.steps[4].1,
+            Step::Set(ConfigKey::NtpUrl, "pool.ntp.org".to_owned())
+        );
+        assert_eq!(
+            script.steps[7].1,
+            Step::Assert(Condition {
+                path: "network_settings.res_info.code".to_owned(),
+                op: Op::Eq,
+                value: "0".to_owned(),
+            })
+        );
+
+        let e = Script::parse("bad.dms", "log a\nset unknown_key 1", Path::new(".")).unwrap_err();
+        assert_eq!(
+            e.error_str().as_deref(),
+            Some("bad.dms:2: invalid step: set unknown_key 1")
+        );
+    }
+
+    #[test]
+    fn test_condition() {
+        let state = serde_json::json!({
+            "network_settings": {"res_info": {"code": 0, "detail_msg": "ok"}},
+            "list": [{"a": "x"}],
+        });
+
+        let condition = |s| Condition::parse(s).unwrap();
+        assert!(condition("network_settings.res_info.code == 0").eval(&state));
+        assert!(condition("network_settings.res_info.code < 1.5").eval(&state));
+        assert!(condition("network_settings.res_info.detail_msg == \"ok\"").eval(&state));
+        assert!(condition("list.0.a contains x").eval(&state));
+        assert!(condition("missing == null").eval(&state));
+        assert!(!condition("network_settings.res_info.code != 0").eval(&state));
+        assert!(Condition::parse("path ~ 1").is_none());
+    }
+
+    #[test]
+    fn test_script_runner() {
+        let script = Script::parse("test.dms", SCRIPT, Path::new(".")).unwrap();
+        let mut runner = ScriptRunner::new(script);
+        let mut target = FakeTarget::default();
+        let now = Instant::now();
+
+        // Waits for the reboot response
+        runner.step(&mut target, now);
+        assert_eq!(target.direct_command, Some(DirectCommand::Reboot));
+        assert_eq!(runner.current_line(), Some(4));
+
+        target.response = Some(Ok("{}".to_owned()));
+        runner.step(&mut target, now);
+        assert_eq!(runner.current_line(), Some(5));
+
+        // Reconnected, the network settings are sent and waited for
+        target.connect_requests += 1;
+        runner.step(&mut target, now);
+        assert_eq!(runner.current_line(), Some(9));
+        assert_eq!(target.sent.len(), 1);
+        assert!(target.sent[0].contains("network_settings"));
+        assert!(target.sent[0].contains("pool.ntp.org"));
+
+        target.applied = true;
+        target.state = serde_json::json!({"network_settings": {"res_info": {"code": 3}}});
+        runner.step(&mut target, now);
+        assert!(matches!(runner.outcome(), Some(Err(e)) if e.contains("line 10")));
+    }
+
+    #[test]
+    fn test_script_runner_timeout() {
+        let script = Script::parse("t.dms", "wait_reconnect 10\nlog done", Path::new(".")).unwrap();
+        let mut runner = ScriptRunner::new(script);
+        let mut target = FakeTarget::default();
+        let now = Instant::now();
+
+        runner.step(&mut target, now);
+        assert!(runner.outcome().is_none());
+        runner.step(&mut target, now + Duration::from_secs(11));
+        assert!(matches!(runner.outcome(), Some(Err(e)) if e.contains("did not reconnect")));
+
+        let script = Script::parse("t.dms", "sleep 1\nlog done", Path::new(".")).unwrap();
+        let mut runner = ScriptRunner::new(script);
+        runner.step(&mut target, now);
+        runner.step(&mut target, now + Duration::from_secs(1));
+        assert_eq!(runner.outcome(), Some(&Ok(())));
+        assert_eq!(runner.log.last().map(|s| s.as_str()), Some("PASSED"));
+    }
+}
@@ -0,0 +1,214 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    super::{Condition, Op},
+    crate::error::{DMError, DMErrorExt},
+    error_stack::{Report, Result},
+    jlogger_tracing::{jdebug, jerror, jinfo},
+    serde::Deserialize,
+    serde_json::Value,
+    std::{collections::BTreeMap, path::Path},
+};
+
+/// Expected device state, read from a YAML file of nested keys, e.g.
+///
+/// ```yaml
+/// main_chip:
+///   firmware_version: "0700FA"
+/// network_settings.ntp_url: pool.ntp.org
+/// device_states.hours_meter: ">= 1"
+/// ```
+///
+/// A value may start with one of the operators of the scripts, equality is
+/// checked otherwise. The items of a list are checked against the elements of
+/// the array at the same index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expectations {
+    pub conditions: Vec<Condition>,
+}
+
+/// Expectation not met, with the value reported by the device
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub condition: Condition,
+    pub actual: String,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: expected {} {}, got {}",
+            self.condition.path, self.condition.op, self.condition.value, self.actual
+        )
+    }
+}
+
+/// Value expected for a key of the YAML file
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+enum Expected {
+    Map(BTreeMap<String, Expected>),
+    List(Vec<Expected>),
+    Bool(bool),
+    Number(serde_yaml::Number),
+    Text(String),
+}
+
+fn condition(path: String, value: &str) -> Condition {
+    let value = value.trim();
+    if let Some((op, rest)) = value.split_once(char::is_whitespace)
+        && let Some(op) = Op::parse(op)
+    {
+        return Condition {
+            path,
+            op,
+            value: rest.trim().to_owned(),
+        };
+    }
+
+    Condition {
+        path,
+        op: Op::Eq,
+        value: value.to_owned(),
+    }
+}
+
+/// Conditions of the scalars below `expected`, with their dotted paths
+fn flatten(path: &str, expected: &Expected, conditions: &mut Vec<Condition>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+
+    match expected {
+        Expected::Map(map) => {
+            for (key, value) in map {
+                flatten(&join(key), value, conditions);
+            }
+        }
+        Expected::List(items) => {
+            for (i, item) in items.iter().enumerate() {
+                flatten(&join(&i.to_string()), item, conditions);
+            }
+        }
+        Expected::Bool(b) => conditions.push(condition(path.to_owned(), &b.to_string())),
+        Expected::Number(n) => conditions.push(condition(path.to_owned(), &n.to_string())),
+        Expected::Text(s) => conditions.push(condition(path.to_owned(), s)),
+    }
+}
+
+impl Expectations {
+    pub fn parse(name: &str, text: &str) -> Result<Self, DMError> {
+        let expected: BTreeMap<String, Expected> = serde_yaml::from_str(text).map_err(|e| {
+            Report::new(DMError::ParserError).attach_printable(format!("{name}: {e}"))
+        })?;
+
+        let mut conditions = vec![];
+        flatten("", &Expected::Map(expected), &mut conditions);
+        if conditions.is_empty() {
+            return Err(Report::new(DMError::ParserError)
+                .attach_printable(format!("{name}: no expectation")));
+        }
+
+        Ok(Self { conditions })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, DMError> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            Report::new(DMError::IOError).attach_printable(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::parse(&path.display().to_string(), &text)
+    }
+
+    /// Expectations not met by the device state
+    pub fn check(&self, state: &Value) -> Vec<Mismatch> {
+        self.conditions
+            .iter()
+            .filter(|c| !c.eval(state))
+            .map(|c| Mismatch {
+                condition: c.clone(),
+                actual: c.actual(state),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expectations() {
+        let text = r#"
+# Expected after the OTA
+main_chip:
+  firmware:
+    version: "0700FA"   # quoted
+  id: abc # comment
+network_settings.ntp_url: 'pool.ntp.org'
+device_states: {hours_meter: ">= 10", wireless: true}
+deployment:
+  instances:
+    - name: app
+    - "!= stopped"
+"#;
+        let expectations = Expectations::parse("test.yaml", text).unwrap();
+        let conditions: Vec<_> = expectations
+            .conditions
+            .iter()
+            .map(|c| (c.path.as_str(), c.op, c.value.as_str()))
+            .collect();
+        assert_eq!(
+            conditions,
+            vec![
+                ("deployment.instances.0.name", Op::Eq, "app"),
+                ("deployment.instances.1", Op::Ne, "stopped"),
+                ("device_states.hours_meter", Op::Ge, "10"),
+                ("device_states.wireless", Op::Eq, "true"),
+                ("main_chip.firmware.version", Op::Eq, "0700FA"),
+                ("main_chip.id", Op::Eq, "abc"),
+                ("network_settings.ntp_url", Op::Eq, "pool.ntp.org"),
+            ]
+        );
+
+        assert!(Expectations::parse("test.yaml", "a: [b\n").is_err());
+        assert!(Expectations::parse("test.yaml", "- a\n").is_err());
+        assert!(Expectations::parse("test.yaml", "a: {}\n").is_err());
+        assert!(Expectations::parse("test.yaml", "# empty\n").is_err());
+    }
+
+    #[test]
+    fn test_check_expectations() {
+        let expectations =
+            Expectations::parse("test.yaml", "a:\n  b: 1\n  c: x\nd: \"> 5\"\n").unwrap();
+        let state = serde_json::json!({"a": {"b": 1, "c": "y"}, "d": 3});
+
+        let mismatches = expectations.check(&state);
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].to_string(), "a.c: expected == x, got y");
+        assert_eq!(mismatches[1].actual, "3");
+    }
+}
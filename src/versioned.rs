@@ -0,0 +1,168 @@
+/*
+Copyright [2025] Seimizu Joukan
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+#[allow(unused)]
+use {
+    crate::error::{DMError, DMErrorExt},
+    error_stack::{Report, Result},
+    serde::{Serialize, de::DeserializeOwned},
+    serde_json::Value,
+};
+
+/// Key of the format version in the JSON files written by device-monitor
+pub const VERSION_KEY: &str = "schema_version";
+
+/// JSON file written by device-monitor. The current version is written with the content, and
+/// older files are upgraded on load one version at a time, files written before the versions
+/// were introduced being version 0.
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// Kind of file in the error messages, e.g. "token provider defaults"
+    const KIND: &'static str;
+    /// Version written by this build
+    const VERSION: u32;
+
+    /// Upgrades `value` from `version` to `version + 1`
+    fn migrate(version: u32, value: &mut Value) -> Result<(), DMError> {
+        let _ = (version, value);
+        Ok(())
+    }
+}
+
+fn invalid<T: Versioned>(reason: impl std::fmt::Display) -> Report<DMError> {
+    Report::new(DMError::InvalidData).attach_printable(format!("Invalid {}: {reason}", T::KIND))
+}
+
+/// Serializes `data` with the current version
+pub fn to_json<T: Versioned>(data: &T) -> Result<String, DMError> {
+    let mut value = serde_json::to_value(data).map_err(invalid::<T>)?;
+    let Value::Object(map) = &mut value else {
+        return Err(invalid::<T>("not a JSON object"));
+    };
+    map.insert(VERSION_KEY.to_owned(), T::VERSION.into());
+    serde_json::to_string_pretty(&value).map_err(invalid::<T>)
+}
+
+/// Version of the content, 0 when it has none
+pub fn version_of(value: &Value) -> Result<u32, DMError> {
+    match value.get(VERSION_KEY) {
+        None => Ok(0),
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| {
+                Report::new(DMError::InvalidData)
+                    .attach_printable(format!("Invalid {VERSION_KEY}: {v}"))
+            }),
+    }
+}
+
+/// Deserializes the content of a file, upgraded from its version to the current one.
+/// Files of a newer version are rejected rather than read partially.
+pub fn from_json<T: Versioned>(s: &str) -> Result<T, DMError> {
+    let mut value: Value = serde_json::from_str(s).map_err(invalid::<T>)?;
+    let version = version_of(&value)?;
+    if version > T::VERSION {
+        return Err(Report::new(DMError::InvalidData).attach_printable(format!(
+            "The {} file is version {version}, newer than version {} supported by this \
+             device-monitor, upgrade it",
+            T::KIND,
+            T::VERSION
+        )));
+    }
+
+    for v in version..T::VERSION {
+        T::migrate(v, &mut value)?;
+    }
+    if let Value::Object(map) = &mut value {
+        map.remove(VERSION_KEY);
+    }
+    serde_json::from_value(value).map_err(invalid::<T>)
+}
+
+/// Reads a file written by `save()`, None when it doesn't exist
+pub fn load<T: Versioned>(path: &str) -> Result<Option<T>, DMError> {
+    match std::fs::read_to_string(path) {
+        Ok(s) => from_json(&s).map(Some).map_err(|e| {
+            let reason = e.error_str().unwrap_or_default();
+            e.attach_printable(format!("{path}: {reason}"))
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => {
+            Err(Report::new(DMError::IOError)
+                .attach_printable(format!("Failed to read {path}: {e}")))
+        }
+    }
+}
+
+/// Writes `data` with the current version
+pub fn save<T: Versioned>(path: &str, data: &T) -> Result<(), DMError> {
+    std::fs::write(path, to_json(data)?).map_err(|e| {
+        Report::new(DMError::IOError).attach_printable(format!("Failed to write {path}: {e}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    /// Version 0 had "name", renamed to "names" holding a list in version 1
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Sample {
+        names: Vec<String>,
+    }
+
+    impl Versioned for Sample {
+        const KIND: &'static str = "sample";
+        const VERSION: u32 = 1;
+
+        fn migrate(version: u32, value: &mut Value) -> Result<(), DMError> {
+            if version == 0
+                && let Some(map) = value.as_object_mut()
+                && let Some(name) = map.remove("name")
+            {
+                map.insert("names".to_owned(), Value::Array(vec![name]));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_versioned_roundtrip() {
+        let sample = Sample {
+            names: vec!["a".to_owned()],
+        };
+        let json = to_json(&sample).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(version_of(&value).unwrap(), 1);
+        assert_eq!(from_json::<Sample>(&json).unwrap(), sample);
+    }
+
+    #[test]
+    fn test_versioned_migration() {
+        assert_eq!(
+            from_json::<Sample>(r#"{"name": "a"}"#).unwrap(),
+            Sample {
+                names: vec!["a".to_owned()]
+            }
+        );
+
+        let e = from_json::<Sample>(r#"{"schema_version": 2, "names": []}"#).unwrap_err();
+        assert!(e.error_str().unwrap().contains("newer than version 1"));
+        assert!(from_json::<Sample>(r#"{"schema_version": "1", "names": []}"#).is_err());
+        assert!(from_json::<Sample>("not json").is_err());
+    }
+}